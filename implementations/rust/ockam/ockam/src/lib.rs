@@ -85,22 +85,24 @@ pub use ockam_node::{
 /// TCP transport
 pub mod tcp {
     pub use ockam_transport_tcp::{
-        TcpConnection, TcpConnectionMode, TcpConnectionOptions, TcpInletOptions, TcpListener,
-        TcpListenerInfo, TcpListenerOptions, TcpOutletOptions, TcpSenderInfo, TcpTransport,
-        TcpTransportExtension, MAX_MESSAGE_SIZE, TCP,
+        DestinationAccessControl, DestinationAllowlist, DestinationPattern, HttpProxy,
+        IdentityAttributesProvider, TcpConnection, TcpConnectionMode, TcpConnectionOptions,
+        TcpInletOptions, TcpListener, TcpListenerInfo, TcpListenerOptions, TcpOutletOptions,
+        TcpPortalMetrics, TcpSenderInfo, TcpTlsClientOptions, TcpTlsServerOptions, TcpTransport,
+        TcpTransportExtension, TlsCertificate, MAX_MESSAGE_SIZE, TCP,
     };
 }
 #[cfg(feature = "ockam_transport_udp")]
 /// UDP transport
 pub mod udp {
     pub use ockam_transport_udp::{
-        RendezvousClient, RendezvousService, UdpBindArguments, UdpBindOptions, UdpPuncture,
-        UdpPunctureNegotiation, UdpPunctureNegotiationListener,
-        UdpPunctureNegotiationListenerOptions, UdpTransport, UdpTransportExtension,
-        MAX_MESSAGE_SIZE, UDP,
+        RendezvousClient, RendezvousService, UdpBind, UdpBindArguments, UdpBindOptions, UdpInlet,
+        UdpInletOptions, UdpOutletOptions, UdpPuncture, UdpPunctureNegotiation,
+        UdpPunctureNegotiationListener, UdpPunctureNegotiationListenerOptions, UdpTransport,
+        UdpTransportExtension, DEFAULT_UDP_PORTAL_IDLE_TIMEOUT, MAX_MESSAGE_SIZE, UDP,
     };
 }
-pub use relay_service::{RelayService, RelayServiceOptions};
+pub use relay_service::{RelayLoadBalancing, RelayService, RelayServiceOptions};
 
 /// Transport
 pub mod transport {
@@ -1,7 +1,9 @@
 mod options;
+mod registrations;
 mod relay;
 #[allow(clippy::module_inception)]
 mod relay_service;
 
 pub use options::*;
+pub use registrations::RelayLoadBalancing;
 pub use relay_service::*;
@@ -1,11 +1,16 @@
 use crate::alloc::string::ToString;
+use crate::relay_service::RelayLoadBalancing;
 use alloc::string::String;
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl};
 use ockam_identity::{Identifier, IdentitiesAttributes};
 
+/// Relays check that their registered routes are still alive with that interval by default
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Trust Options for a Forwarding Service
 pub struct RelayServiceOptions {
     pub(super) service_incoming_access_control: Arc<dyn IncomingAccessControl>,
@@ -15,6 +20,9 @@ pub struct RelayServiceOptions {
     pub(super) prefix: String,
     pub(super) authority_validation: Option<AuthorityValidation>,
     pub(super) aliases: Vec<Address>,
+    pub(super) shared_registrations: bool,
+    pub(super) load_balancing: RelayLoadBalancing,
+    pub(super) health_check_interval: Duration,
 }
 
 pub(super) struct AuthorityValidation {
@@ -33,6 +41,9 @@ impl RelayServiceOptions {
             prefix: "".to_string(),
             authority_validation: None,
             aliases: vec![],
+            shared_registrations: false,
+            load_balancing: RelayLoadBalancing::default(),
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
         }
     }
 
@@ -111,6 +122,30 @@ impl RelayServiceOptions {
         self
     }
 
+    /// Allow several nodes to register under the same relay name.
+    ///
+    /// A node can then register under an existing relay name if it has the same identity as the
+    /// node which created the relay, or if the authority gave it an `ockam-relay-shared`
+    /// attribute with that relay name or `*`. Otherwise, or if this option is not set,
+    /// registering under an existing relay name fails.
+    pub fn shared_registrations(mut self) -> Self {
+        self.shared_registrations = true;
+        self
+    }
+
+    /// Set the strategy used to select a node when several nodes are registered
+    /// under the same relay name
+    pub fn load_balancing(mut self, load_balancing: RelayLoadBalancing) -> Self {
+        self.load_balancing = load_balancing;
+        self
+    }
+
+    /// Set the interval used by relays to check that their registered routes are still alive
+    pub fn health_check_interval(mut self, health_check_interval: Duration) -> Self {
+        self.health_check_interval = health_check_interval;
+        self
+    }
+
     pub(super) fn setup_flow_control_for_relay_service(
         &self,
         flow_controls: &FlowControls,
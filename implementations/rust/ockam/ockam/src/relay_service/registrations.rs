@@ -0,0 +1,135 @@
+use ockam_core::compat::collections::BTreeSet;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Address, Route};
+use ockam_identity::Identifier;
+
+/// Strategy used to select a node when several nodes are registered under the same relay name.
+///
+/// A node is selected for each message received by the relay. Once a secure channel or a portal
+/// is established through the relay, its messages don't go through the relay anymore, so a node
+/// is effectively selected for each new session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RelayLoadBalancing {
+    /// Select the registered nodes one after the other
+    #[default]
+    RoundRobin,
+    /// Select the registered node with the fewest active connections. A connection is counted
+    /// until the worker which sent its first message through the relay is stopped
+    LeastConnections,
+}
+
+/// A node registered under a relay name
+struct Registration {
+    /// Route to the registered node
+    route: Route,
+    /// Previous hops of the messages sent to this node, used to count the active connections
+    connections: BTreeSet<Address>,
+}
+
+/// Nodes registered under the same relay name
+pub(super) struct Registrations {
+    /// Identifier of the node which created the relay, if it was registered via a secure channel
+    owner: Option<Identifier>,
+    load_balancing: RelayLoadBalancing,
+    registrations: Vec<Registration>,
+    next: usize,
+    closed: bool,
+}
+
+impl Registrations {
+    pub(super) fn new(owner: Option<Identifier>, load_balancing: RelayLoadBalancing) -> Self {
+        Self {
+            owner,
+            load_balancing,
+            registrations: vec![],
+            next: 0,
+            closed: false,
+        }
+    }
+
+    /// Add a route to a registered node.
+    /// Return false if the relay was closed since it had no more registrations
+    pub(super) fn add(&mut self, route: Route) -> bool {
+        if self.closed {
+            return false;
+        }
+        if !self.registrations.iter().any(|r| r.route == route) {
+            self.registrations.push(Registration {
+                route,
+                connections: BTreeSet::new(),
+            });
+        }
+        true
+    }
+
+    /// Remove a route, for example when its first hop does not exist anymore.
+    /// The relay is closed when there are no more registrations
+    pub(super) fn remove(&mut self, route: &Route) {
+        self.registrations.retain(|r| &r.route != route);
+        if self.registrations.is_empty() {
+            self.closed = true;
+        }
+    }
+
+    /// Select the route to use for a message received from `previous_hop`
+    pub(super) fn select(&mut self, previous_hop: &Address) -> Option<Route> {
+        if self.registrations.is_empty() {
+            return None;
+        }
+
+        let index = match self.load_balancing {
+            RelayLoadBalancing::RoundRobin => {
+                let index = self.next % self.registrations.len();
+                self.next = index + 1;
+                index
+            }
+            RelayLoadBalancing::LeastConnections => self
+                .registrations
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, r)| r.connections.len())
+                .map(|(index, _)| index)?,
+        };
+
+        let registration = &mut self.registrations[index];
+        registration.connections.insert(previous_hop.clone());
+        Some(registration.route.clone())
+    }
+
+    /// Return true if messages can be sent to this address: it must be the first hop of
+    /// a registered route, or any address if a node is registered from the local node
+    pub(super) fn is_next_hop(&self, address: &Address) -> bool {
+        self.registrations
+            .iter()
+            .any(|r| r.route.next().map(|next| next == address).unwrap_or(true))
+    }
+
+    /// Return the registered routes
+    pub(super) fn routes(&self) -> Vec<Route> {
+        self.registrations.iter().map(|r| r.route.clone()).collect()
+    }
+
+    /// Return the previous hops of the connections counted for all the registrations
+    pub(super) fn connections(&self) -> BTreeSet<Address> {
+        self.registrations
+            .iter()
+            .flat_map(|r| r.connections.iter().cloned())
+            .collect()
+    }
+
+    /// Stop counting the connections which are not active anymore
+    pub(super) fn remove_connections(&mut self, inactive: &BTreeSet<Address>) {
+        for registration in self.registrations.iter_mut() {
+            registration.connections.retain(|c| !inactive.contains(c));
+        }
+    }
+
+    /// Return true if the relay was created by a node with that identifier
+    pub(super) fn is_owned_by(&self, identifier: Option<&Identifier>) -> bool {
+        identifier.is_some() && self.owner.as_ref() == identifier
+    }
+
+    pub(super) fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
@@ -1,54 +1,103 @@
+use crate::relay_service::registrations::Registrations;
 use crate::Context;
-use ockam_core::compat::sync::Arc;
-use ockam_core::compat::{boxed::Box, vec::Vec};
+use core::fmt::{Debug, Formatter};
+use core::time::Duration;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeSet;
+use ockam_core::compat::sync::{Arc, Mutex};
 use ockam_core::{
-    route, Address, AllowAll, AllowOnwardAddress, Any, IncomingAccessControl, LocalMessage,
-    OutgoingAccessControl, Result, Route, Routed, Worker,
+    async_trait, Address, AllowSourceAddress, Any, DenyAll, IncomingAccessControl, Mailbox,
+    Mailboxes, OutgoingAccessControl, RelayMessage, Result, Routed, Worker,
 };
-use ockam_node::WorkerBuilder;
-use tracing::info;
+use ockam_node::{DelayedEvent, WorkerBuilder};
+use tracing::{info, warn};
 
 pub(super) struct Relay {
-    forward_route: Route,
-    // this option will be `None` after this worker is initialized, because
-    // while initializing, the worker will send the payload contained in this
-    // field to the `forward_route`, to indicate a successful connection
-    payload: Option<Vec<u8>>,
+    registrations: Arc<Mutex<Registrations>>,
+    health_check_address: Address,
+    health_check: DelayedEvent<()>,
+    health_check_interval: Duration,
 }
 
 impl Relay {
     pub(super) async fn create(
         ctx: &Context,
         address: Address,
-        forward_route: Route,
-        registration_payload: Vec<u8>,
+        registrations: Arc<Mutex<Registrations>>,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
+        health_check_interval: Duration,
     ) -> Result<()> {
-        info!("Created new alias {} for {}", address, forward_route);
-
-        // Should be able to reach last and second last hops
-        let outgoing_access_control: Arc<dyn OutgoingAccessControl> = if forward_route.len() == 1 {
-            // We are accessed with our node, no transport is involved
-            Arc::new(AllowAll)
-        } else {
-            let next_hop = forward_route.next()?.clone();
-            Arc::new(AllowOnwardAddress(next_hop))
-        };
+        info!("Created new alias {}", address);
+
+        // Should be able to reach the first hop of every registered route
+        let outgoing_access_control: Arc<dyn OutgoingAccessControl> =
+            Arc::new(RegisteredRoutesAccessControl {
+                registrations: registrations.clone(),
+            });
+
+        let health_check_address = Address::random_tagged("Relay.health_check");
+        let health_check = DelayedEvent::create(ctx, health_check_address.clone(), ()).await?;
+        let health_check_mailbox = Mailbox::new(
+            health_check_address.clone(),
+            Arc::new(AllowSourceAddress(health_check.address())),
+            Arc::new(DenyAll),
+        );
 
         let relay = Self {
-            forward_route,
-            payload: Some(registration_payload.clone()),
+            registrations,
+            health_check_address,
+            health_check,
+            health_check_interval,
         };
 
         WorkerBuilder::new(relay)
-            .with_address(address)
-            .with_incoming_access_control_arc(incoming_access_control)
-            .with_outgoing_access_control_arc(outgoing_access_control)
+            .with_mailboxes(Mailboxes::new(
+                Mailbox::new(address, incoming_access_control, outgoing_access_control),
+                vec![health_check_mailbox],
+            ))
             .start(ctx)
             .await?;
 
         Ok(())
     }
+
+    /// Remove the registrations which first hop does not exist anymore, and stop counting the
+    /// connections which previous hop does not exist anymore.
+    /// Return false if there are no more registrations.
+    async fn check_health(&self, ctx: &Context) -> Result<bool> {
+        let (routes, connections) = {
+            let registrations = self.registrations.lock().unwrap();
+            (registrations.routes(), registrations.connections())
+        };
+
+        let mut dead_routes = vec![];
+        for route in routes {
+            if let Ok(next) = route.next() {
+                if !ctx.is_worker_registered_at(next.clone()).await? {
+                    dead_routes.push(route);
+                }
+            }
+        }
+
+        let mut inactive_connections = BTreeSet::new();
+        for connection in connections {
+            if !ctx.is_worker_registered_at(connection.clone()).await? {
+                inactive_connections.insert(connection);
+            }
+        }
+
+        let mut registrations = self.registrations.lock().unwrap();
+        for route in dead_routes {
+            warn!(
+                "Removing the route {} from the relay {}",
+                route,
+                ctx.address()
+            );
+            registrations.remove(&route);
+        }
+        registrations.remove_connections(&inactive_connections);
+        Ok(!registrations.is_closed())
+    }
 }
 
 #[crate::worker]
@@ -56,23 +105,12 @@ impl Worker for Relay {
     type Context = Context;
     type Message = Any;
 
-    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
-        let payload = self
-            .payload
-            .take()
-            .expect("payload must be available on init");
-
-        ctx.forward(
-            LocalMessage::new()
-                .with_onward_route(self.forward_route.clone())
-                .with_return_route(route![ctx.address()])
-                .with_payload(payload),
-        )
-        .await?;
-
-        // Remove the last hop so that just route to the node itself is left
-        self.forward_route = self.forward_route.clone().modify().pop_back().into();
+    async fn initialize(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        self.health_check.schedule(self.health_check_interval).await
+    }
 
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        self.health_check.cancel();
         Ok(())
     }
 
@@ -81,31 +119,90 @@ impl Worker for Relay {
         ctx: &mut Self::Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
-        let mut local_message = msg.into_local_message();
-
-        local_message = local_message
-            .pop_front_onward_route()?
-            .prepend_front_onward_route(&self.forward_route);
-
-        let next_hop = local_message.next_on_onward_route()?;
-        let prev_hop = local_message.return_route().next()?;
+        if msg.msg_addr() == self.health_check_address {
+            if self.check_health(ctx).await? {
+                self.health_check
+                    .schedule(self.health_check_interval)
+                    .await?;
+            } else {
+                info!("No more registrations for the relay {}", ctx.address());
+                ctx.stop_worker(ctx.address()).await?;
+            }
+            return Ok(());
+        }
 
-        if let Some(info) = ctx
-            .flow_controls()
-            .find_flow_control_with_producer_address(&next_hop)
-        {
-            ctx.flow_controls()
-                .add_consumer(prev_hop.clone(), info.flow_control_id());
+        let local_message = msg.into_local_message().pop_front_onward_route()?;
+        let prev_hop = local_message.return_route().next()?.clone();
+
+        loop {
+            let selected = self.registrations.lock().unwrap().select(&prev_hop);
+            let forward_route = match selected {
+                Some(forward_route) => forward_route,
+                None => {
+                    warn!(
+                        "No more registrations for the relay {}, dropping the message",
+                        ctx.address()
+                    );
+                    return ctx.stop_worker(ctx.address()).await;
+                }
+            };
+
+            let local_message = local_message
+                .clone()
+                .prepend_front_onward_route(&forward_route);
+            let next_hop = local_message.next_on_onward_route()?;
+
+            if let Some(info) = ctx
+                .flow_controls()
+                .find_flow_control_with_producer_address(&next_hop)
+            {
+                ctx.flow_controls()
+                    .add_consumer(prev_hop.clone(), info.flow_control_id());
+            }
+
+            if let Some(info) = ctx
+                .flow_controls()
+                .find_flow_control_with_producer_address(&prev_hop)
+            {
+                ctx.flow_controls()
+                    .add_consumer(next_hop.clone(), info.flow_control_id());
+            }
+
+            // If the route is dead, remove it and try another registration
+            match ctx.forward(local_message).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    warn!(
+                        "Removing the route {} from the relay {}: {e}",
+                        forward_route,
+                        ctx.address()
+                    );
+                    self.registrations.lock().unwrap().remove(&forward_route);
+                }
+            }
         }
+    }
+}
+
+/// Allow messages to the first hop of the routes registered for a relay
+struct RegisteredRoutesAccessControl {
+    registrations: Arc<Mutex<Registrations>>,
+}
 
-        if let Some(info) = ctx
-            .flow_controls()
-            .find_flow_control_with_producer_address(prev_hop)
-        {
-            ctx.flow_controls()
-                .add_consumer(next_hop.clone(), info.flow_control_id());
+#[async_trait]
+impl OutgoingAccessControl for RegisteredRoutesAccessControl {
+    async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        let next_hop = relay_msg.onward_route().next()?;
+        if self.registrations.lock().unwrap().is_next_hop(next_hop) {
+            ockam_core::allow()
+        } else {
+            ockam_core::deny()
         }
+    }
+}
 
-        ctx.forward(local_message).await
+impl Debug for RegisteredRoutesAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RegisteredRoutesAccessControl").finish()
     }
 }
@@ -1,21 +1,32 @@
 use crate::alloc::string::ToString;
+use crate::relay_service::registrations::Registrations;
 use crate::relay_service::relay::Relay;
 use crate::{Context, RelayServiceOptions};
 use alloc::string::String;
 use ockam_core::compat::boxed::Box;
-use ockam_core::compat::sync::Arc;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
 use ockam_core::{
-    Address, DenyAll, Encodable, Mailbox, Mailboxes, Result, Routed, SecureChannelLocalInfo, Worker,
+    route, Address, AllowOnwardAddress, DenyAll, Encodable, LocalMessage, Mailbox, Mailboxes,
+    Result, Route, Routed, SecureChannelLocalInfo, Worker,
 };
-use ockam_node::WorkerBuilder;
+use ockam_identity::Identifier;
+use ockam_node::{NodeError, WorkerBuilder};
 
 /// Alias worker to register remote workers under local names.
 ///
 /// To talk with this worker, you can use the
 /// [`RemoteRelay`](crate::remote::RemoteRelay) which is a compatible client for this server.
+///
+/// When [`RelayServiceOptions::shared_registrations`] is set, several nodes can register under the
+/// same relay name. Messages sent to the relay are then forwarded to one of them, selected with the [`RelayLoadBalancing`](crate::RelayLoadBalancing)
+/// strategy of the service options. Registrations are removed when their route is not alive
+/// anymore, and the relay is stopped when it has no more registrations.
 #[non_exhaustive]
 pub struct RelayService {
     options: RelayServiceOptions,
+    relays: BTreeMap<Address, Arc<Mutex<Registrations>>>,
 }
 
 impl RelayService {
@@ -39,7 +50,10 @@ impl RelayService {
         }
 
         let service_incoming_access_control = options.service_incoming_access_control.clone();
-        let s = Self { options };
+        let s = Self {
+            options,
+            relays: BTreeMap::new(),
+        };
 
         WorkerBuilder::new(s)
             .with_mailboxes(Mailboxes::new(
@@ -57,6 +71,86 @@ impl RelayService {
 
         Ok(())
     }
+
+    /// Register a route under a relay name, starting the relay if it doesn't exist yet.
+    /// `registrant` is the identifier of the registering node, if it is known, and `may_share`
+    /// is true if it is authorized to register under a relay created by another node
+    async fn register(
+        &mut self,
+        ctx: &Context,
+        relay_address: &Address,
+        forward_route: Route,
+        registrant: Option<Identifier>,
+        may_share: bool,
+    ) -> Result<()> {
+        // Forget the relays which were stopped
+        self.relays
+            .retain(|_, registrations| !registrations.lock().unwrap().is_closed());
+
+        // Remove the last hop so that just route to the node itself is left
+        let node_route: Route = forward_route.modify().pop_back().into();
+
+        if let Some(registrations) = self.relays.get(relay_address) {
+            let mut registrations = registrations.lock().unwrap();
+            if !registrations.is_closed()
+                && !(self.options.shared_registrations
+                    && (may_share || registrations.is_owned_by(registrant.as_ref())))
+            {
+                warn!(%relay_address, "Relay registration not authorized, the relay already exists");
+                return Err(NodeError::Address(relay_address.clone()).already_exists());
+            }
+            if registrations.add(node_route.clone()) {
+                info!("Added route {} to the relay {}", node_route, relay_address);
+                return Ok(());
+            }
+        }
+
+        let mut registrations = Registrations::new(registrant, self.options.load_balancing);
+        registrations.add(node_route);
+        let registrations = Arc::new(Mutex::new(registrations));
+
+        self.options
+            .setup_flow_control_for_relay(ctx.flow_controls(), relay_address);
+
+        Relay::create(
+            ctx,
+            relay_address.clone(),
+            registrations.clone(),
+            self.options.relays_incoming_access_control.clone(),
+            self.options.health_check_interval,
+        )
+        .await?;
+
+        self.relays.insert(relay_address.clone(), registrations);
+        Ok(())
+    }
+
+    /// Send the relay name to the registered node, to indicate a successful registration
+    async fn confirm_registration(
+        ctx: &Context,
+        relay_address: &Address,
+        forward_route: Route,
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        // Should be able to reach the registered node only
+        let next_hop = forward_route.next()?.clone();
+        let confirmation_ctx = ctx
+            .new_detached_with_mailboxes(Mailboxes::main(
+                Address::random_tagged("RelayService.confirmation"),
+                Arc::new(DenyAll),
+                Arc::new(AllowOnwardAddress(next_hop)),
+            ))
+            .await?;
+
+        confirmation_ctx
+            .forward(
+                LocalMessage::new()
+                    .with_onward_route(forward_route)
+                    .with_return_route(route![relay_address.clone()])
+                    .with_payload(payload),
+            )
+            .await
+    }
 }
 
 #[crate::worker]
@@ -69,8 +163,10 @@ impl Worker for RelayService {
         ctx: &mut Self::Context,
         message: Routed<Self::Message>,
     ) -> Result<()> {
-        let secure_channel_local_info =
-            SecureChannelLocalInfo::find_info(message.local_message()).ok();
+        let registrant: Option<Identifier> =
+            SecureChannelLocalInfo::find_info(message.local_message())
+                .ok()
+                .map(|info| info.their_identifier().into());
 
         let forward_route = message.return_route().clone();
         let requested_relay_address = message.into_body()?;
@@ -86,14 +182,12 @@ impl Worker for RelayService {
         debug!(%requested_relay_name, "Relay creation request");
 
         // Verify the relay usage only when an authority is set, otherwise allow any relay name
+        let mut may_share = false;
         if let Some(authority_validation) = &self.options.authority_validation {
-            if let Some(secure_channel_local_info) = secure_channel_local_info {
+            if let Some(registrant) = &registrant {
                 let attributes = authority_validation
                     .identities_attributes
-                    .get_attributes(
-                        &secure_channel_local_info.their_identifier().into(),
-                        &authority_validation.authority,
-                    )
+                    .get_attributes(registrant, &authority_validation.authority)
                     .await?;
 
                 if let Some(attributes) = attributes {
@@ -118,6 +212,13 @@ impl Worker for RelayService {
                         warn!(%attributes, "Relay creation request not authorized, missing or invalid `ockam-relay` attribute, dropping.");
                         return Ok(());
                     }
+
+                    // A node can register under a relay created by another node with that attribute
+                    may_share = attributes
+                        .attrs()
+                        .get("ockam-relay-shared".as_bytes())
+                        .map(|a| a == b"*" || a == requested_relay_name.as_bytes())
+                        .unwrap_or(false);
                 } else {
                     warn!("Relay creation request not authorized, missing `ockam-relay` attribute, no other attribute was found, dropping.");
                     return Ok(());
@@ -132,18 +233,15 @@ impl Worker for RelayService {
         let payload = final_relay_name.clone().encode()?;
         let final_relay_address = Address::from_string(final_relay_name);
 
-        self.options
-            .setup_flow_control_for_relay(ctx.flow_controls(), &final_relay_address);
-
-        Relay::create(
+        self.register(
             ctx,
-            final_relay_address,
-            forward_route,
-            payload.to_vec(),
-            self.options.relays_incoming_access_control.clone(),
+            &final_relay_address,
+            forward_route.clone(),
+            registrant,
+            may_share,
         )
         .await?;
 
-        Ok(())
+        Self::confirm_registration(ctx, &final_relay_address, forward_route, payload.to_vec()).await
     }
 }
@@ -1,10 +1,13 @@
-use ockam::identity::{secure_channels, SecureChannelListenerOptions, SecureChannelOptions};
+use ockam::identity::{
+    secure_channels, Identifier, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
+};
 use ockam::remote::{RemoteRelay, RemoteRelayOptions};
 use ockam::workers::Echoer;
-use ockam::{RelayService, RelayServiceOptions};
-use ockam_core::{route, AllowAll, Result};
-use ockam_node::{Context, MessageReceiveOptions};
+use ockam::{RelayLoadBalancing, RelayService, RelayServiceOptions};
+use ockam_core::{route, Address, AllowAll, Result};
+use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions};
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
+use std::sync::Arc;
 use std::time::Duration;
 
 // Node creates a Relay service and a Remote Relay, Echoer is reached through the Relay. No flow control
@@ -250,3 +253,208 @@ async fn test4(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+// Cloud: Hosts a Relay service allowing shared registrations, runs a secure channel listener
+//        and listens on a tcp port
+// Server: Connects twice to the Cloud using tcp and secure channels with the same identity
+//         and registers both channels under the same relay name
+// Client: Reaches the Server's Echoer through both channels, then through the remaining one
+//         when a channel is closed
+#[ockam_macros::test]
+async fn test5(ctx: &mut Context) -> Result<()> {
+    let (secure_channels, cloud_address) = start_cloud(
+        ctx,
+        RelayServiceOptions::new()
+            .shared_registrations()
+            .load_balancing(RelayLoadBalancing::RoundRobin),
+    )
+    .await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let server_tcp = TcpTransport::create(ctx).await?;
+    let server = identities_creation.create_identity().await?;
+    let mut server_channels = vec![];
+    for _ in 0..2 {
+        let cloud_connection = server_tcp
+            .connect(&cloud_address, TcpConnectionOptions::new())
+            .await?;
+        let cloud_channel = secure_channels
+            .create_secure_channel(
+                ctx,
+                &server,
+                route![cloud_connection, "cloud_listener"],
+                SecureChannelOptions::new(),
+            )
+            .await?;
+        ctx.flow_controls()
+            .add_consumer("echoer", cloud_channel.flow_control_id());
+        let remote_info = RemoteRelay::create_static(
+            ctx,
+            cloud_channel.clone(),
+            "echo",
+            RemoteRelayOptions::new(),
+        )
+        .await?;
+        assert_eq!(remote_info.remote_address(), "echo");
+        server_channels.push(cloud_channel);
+    }
+
+    let client_tcp = TcpTransport::create(ctx).await?;
+    let cloud_connection = client_tcp
+        .connect(&cloud_address, TcpConnectionOptions::new())
+        .await?;
+    let client = identities_creation.create_identity().await?;
+    let cloud_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route![cloud_connection, "cloud_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    // The cloud address used to send the reply back identifies the server channel
+    let mut reply_routes = vec![];
+    for _ in 0..4 {
+        let reply = ctx
+            .send_and_receive_extended::<String>(
+                route![cloud_channel.clone(), "echo", "echoer"],
+                "Hello".to_string(),
+                MessageSendReceiveOptions::new(),
+            )
+            .await?;
+        reply_routes.push(reply.return_route().clone());
+        assert_eq!(reply.into_body()?, "Hello");
+    }
+    assert_ne!(reply_routes[0], reply_routes[1]);
+    assert_eq!(reply_routes[0], reply_routes[2]);
+    assert_eq!(reply_routes[1], reply_routes[3]);
+
+    // Close one of the server channels, the relay keeps using the other one
+    secure_channels
+        .stop_secure_channel(ctx, server_channels[0].encryptor_address())
+        .await?;
+    ctx.sleep(Duration::from_millis(250)).await;
+
+    for _ in 0..2 {
+        let resp = ctx
+            .send_and_receive::<String>(
+                route![cloud_channel.clone(), "echo", "echoer"],
+                "Hello".to_string(),
+            )
+            .await?;
+        assert_eq!(resp, "Hello");
+    }
+
+    Ok(())
+}
+
+// A node can't register under an existing relay name by default, even with the same identity
+#[ockam_macros::test]
+async fn test6(ctx: &mut Context) -> Result<()> {
+    let (secure_channels, cloud_address) = start_cloud(ctx, RelayServiceOptions::new()).await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let server = identities_creation.create_identity().await?;
+
+    let tcp = TcpTransport::create(ctx).await?;
+    assert!(register(ctx, &tcp, &secure_channels, &server, &cloud_address).await?);
+    assert!(!register(ctx, &tcp, &secure_channels, &server, &cloud_address).await?);
+
+    Ok(())
+}
+
+// With shared registrations, a node can register under an existing relay name only if it has
+// the same identity as the node which created the relay
+#[ockam_macros::test]
+async fn test7(ctx: &mut Context) -> Result<()> {
+    let (secure_channels, cloud_address) =
+        start_cloud(ctx, RelayServiceOptions::new().shared_registrations()).await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let server = identities_creation.create_identity().await?;
+    let other = identities_creation.create_identity().await?;
+
+    let tcp = TcpTransport::create(ctx).await?;
+    assert!(register(ctx, &tcp, &secure_channels, &server, &cloud_address).await?);
+    assert!(register(ctx, &tcp, &secure_channels, &server, &cloud_address).await?);
+    assert!(!register(ctx, &tcp, &secure_channels, &other, &cloud_address).await?);
+
+    Ok(())
+}
+
+/// Start a Relay service behind a secure channel listener and a tcp listener.
+/// Return the address of the tcp listener
+async fn start_cloud(
+    ctx: &Context,
+    options: RelayServiceOptions,
+) -> Result<(Arc<SecureChannels>, String)> {
+    let tcp_listener_options = TcpListenerOptions::new();
+    let secure_channel_listener_options = SecureChannelListenerOptions::new()
+        .as_consumer(&tcp_listener_options.spawner_flow_control_id());
+
+    let options = options
+        .service_as_consumer(&secure_channel_listener_options.spawner_flow_control_id())
+        .relay_as_consumer(&secure_channel_listener_options.spawner_flow_control_id());
+    RelayService::create(ctx, "static_forwarding_service", options).await?;
+
+    let secure_channels = secure_channels().await?;
+    let cloud = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &cloud,
+            "cloud_listener",
+            secure_channel_listener_options,
+        )
+        .await?;
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = tcp.listen("127.0.0.1:0", tcp_listener_options).await?;
+    Ok((secure_channels, listener.socket_string()))
+}
+
+/// Register under the `echo` relay name through a new secure channel to the cloud.
+/// Return true if the registration is confirmed
+async fn register(
+    ctx: &Context,
+    tcp: &TcpTransport,
+    secure_channels: &SecureChannels,
+    identifier: &Identifier,
+    cloud_address: &str,
+) -> Result<bool> {
+    let connection = tcp
+        .connect(cloud_address, TcpConnectionOptions::new())
+        .await?;
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            identifier,
+            route![connection, "cloud_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut registrant = ctx
+        .new_detached(Address::random_tagged("registrant"), AllowAll, AllowAll)
+        .await?;
+    ctx.flow_controls()
+        .add_consumer(registrant.address(), channel.flow_control_id());
+    registrant
+        .send(
+            route![channel, "static_forwarding_service"],
+            "echo".to_string(),
+        )
+        .await?;
+
+    let confirmation = registrant
+        .receive_extended::<String>(
+            MessageReceiveOptions::new().with_timeout(Duration::from_millis(500)),
+        )
+        .await;
+    Ok(confirmation.is_ok())
+}
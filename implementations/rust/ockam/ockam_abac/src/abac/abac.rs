@@ -11,7 +11,8 @@ use ockam_core::{Result, SecureChannelLocalInfo};
 use crate::expr::str;
 use crate::{eval, Env, Expr};
 use ockam_core::compat::format;
-use ockam_core::compat::string::ToString;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::time::now;
 use ockam_identity::{Identifier, IdentitiesAttributes};
 use ockam_node::Context;
 use tracing::{debug, warn};
@@ -25,6 +26,45 @@ pub const ABAC_HAS_CREDENTIAL_KEY: &str = "has_credential";
 /// Key we use to check Identifier
 pub const ABAC_IDENTIFIER_KEY: &str = "identifier";
 
+/// Key we use to check the expiration time of the subject credential
+pub const ABAC_EXPIRES_AT_KEY: &str = "expires_at";
+
+/// Key we use to check the current time, in seconds since the Unix epoch
+pub const CURRENT_TIME_KEY: &str = "now";
+
+/// Prefix we use to check for resource attributes
+pub const RESOURCE_KEY: &str = "resource";
+
+/// Prefix we use to check for action attributes
+pub const ACTION_KEY: &str = "action";
+
+/// Key we use to check the resource and action identifiers
+pub const ABAC_ID_KEY: &str = "id";
+
+/// Key we use to check the resource name
+pub const ABAC_RESOURCE_NAME_KEY: &str = "name";
+
+/// Key we use to check the resource type
+pub const ABAC_RESOURCE_TYPE_KEY: &str = "type";
+
+/// Key we use to check the name of the node hosting a resource
+pub const ABAC_RESOURCE_NODE_KEY: &str = "node";
+
+/// Key we use to check the host of an outlet target
+pub const ABAC_RESOURCE_TARGET_HOST_KEY: &str = "target_host";
+
+/// Key we use to check the port of an outlet target
+pub const ABAC_RESOURCE_TARGET_PORT_KEY: &str = "target_port";
+
+/// Key we use to check the `host:port` destination requested from a dynamic outlet
+pub const ABAC_RESOURCE_DESTINATION_KEY: &str = "destination";
+
+/// Key we use to check the host of the destination requested from a dynamic outlet
+pub const ABAC_RESOURCE_DESTINATION_HOST_KEY: &str = "destination_host";
+
+/// Key we use to check the port of the destination requested from a dynamic outlet
+pub const ABAC_RESOURCE_DESTINATION_PORT_KEY: &str = "destination_port";
+
 /// This AccessControl uses a storage for authenticated attributes in order
 /// to verify if a policy expression is valid
 /// A similar access control policy is available as [`crate::policy::PolicyAccessControl`] where
@@ -56,6 +96,14 @@ impl Abac {
             environment,
         }
     }
+
+    /// Return a copy of this access control where the environment is extended
+    /// with some attributes only known when a message is handled
+    pub fn with_attributes(&self, attributes: Env) -> Self {
+        let mut abac = self.clone();
+        abac.environment.merge_right(attributes);
+        abac
+    }
 }
 
 impl Abac {
@@ -123,6 +171,9 @@ impl Abac {
             str(identifier.to_string()),
         );
 
+        // add the current time to be able to write time-based policies
+        environment.put(CURRENT_TIME_KEY, Expr::Int(now()? as i64));
+
         // Get identity attributes and populate the environment:
         if let Some(authority) = authority {
             match identities_attributes
@@ -180,6 +231,14 @@ impl Abac {
                             }
                         }
                     }
+
+                    // the expiration time of the credential takes precedence over an attribute with the same name
+                    if let Some(expires_at) = attrs.expires_at() {
+                        environment.put(
+                            subject_expires_at_attribute().to_string(),
+                            Expr::Int(*expires_at as i64),
+                        );
+                    }
                 }
                 None => {
                     environment.put(
@@ -242,3 +301,18 @@ pub fn subject_has_credential_attribute() -> Expr {
 pub fn subject_identifier_attribute() -> Expr {
     Expr::Ident(format!("{}.{}", SUBJECT_KEY, ABAC_IDENTIFIER_KEY))
 }
+
+/// Identifier for the subject 'expires_at' attribute
+pub fn subject_expires_at_attribute() -> Expr {
+    Expr::Ident(format!("{}.{}", SUBJECT_KEY, ABAC_EXPIRES_AT_KEY))
+}
+
+/// Name of a resource attribute in the evaluation environment, for example `resource.type`
+pub fn resource_attribute_name(key: &str) -> String {
+    format!("{}.{}", RESOURCE_KEY, key)
+}
+
+/// Name of an action attribute in the evaluation environment, for example `action.id`
+pub fn action_attribute_name(key: &str) -> String {
+    format!("{}.{}", ACTION_KEY, key)
+}
@@ -1,6 +1,8 @@
-use ockam_abac::{eval, parse, Env, Expr};
+use ockam_abac::{eval, parse, Env, Expr, CURRENT_TIME_KEY};
+use ockam_core::compat::time::now;
 use rustyline::error::ReadlineError;
 use rustyline::highlight::MatchingBracketHighlighter;
+use rustyline::history::DefaultHistory;
 use rustyline::validate::MatchingBracketValidator;
 use rustyline::{Config, EditMode, Editor, Result};
 use rustyline_derive::{Completer, Helper, Highlighter, Hinter, Validator};
@@ -9,7 +11,16 @@ const HELP: &str = r#"Available commands:
   :def <id> <expression>  -- Add an expression to the environment.
   :env                    -- Show all current environment entries.
   :clear                  -- Remove all bindings from the environment.
-  :help | :h | :?         -- Show this help message."#;
+  :help | :h | :?         -- Show this help message.
+
+Available operators:
+  and, or, not, if, <, >, =, !=, member?, exists?
+  starts-with?, ends-with?, matches?  -- (starts-with? subject.team "infra-")
+  intersects?                         -- (intersects? subject.groups "admin,ops")
+  before?, after?                     -- (before? now subject.expires_at)
+  hour-of-day, day-of-week            -- (< 8 (hour-of-day now) 18)
+
+The identifier `now` is bound to the current time, in seconds since the Unix epoch."#;
 
 #[derive(Completer, Helper, Highlighter, Hinter, Validator)]
 struct ReplHelper {
@@ -27,7 +38,7 @@ fn main() -> Result<()> {
         .build();
 
     let mut env = Env::new();
-    let mut repl = Editor::<ReplHelper>::with_config(c)?;
+    let mut repl = Editor::<ReplHelper, DefaultHistory>::with_config(c)?;
     repl.set_helper(Some(ReplHelper {
         highlighter: MatchingBracketHighlighter::new(),
         validator: MatchingBracketValidator::new(),
@@ -37,6 +48,9 @@ fn main() -> Result<()> {
         let readline = repl.readline("❱ ");
         match readline {
             Ok(line) => {
+                if let Ok(now) = now() {
+                    env.put(CURRENT_TIME_KEY, Expr::Int(now as i64));
+                }
                 if line.starts_with(':') {
                     on_command(&line, &mut env)
                 } else {
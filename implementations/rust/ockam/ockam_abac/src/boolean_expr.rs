@@ -24,6 +24,8 @@ const NAME_FORMAT: &str =
 /// A BooleanExpr models a boolean expression made of:
 ///
 ///  - Names.
+///  - Name-value comparisons: `name=value` (equality), `name^=value` (prefix), `name$=value` (suffix),
+///    `name~=value` (regular expression) and `name&=a,b` (one of the comma-separated values is shared).
 ///  - Binary operators: and, or.
 ///  - Unary operator: not.
 ///  - Optional parentheses: 'and' takes precedence over 'or', and 'not' over 'and'.
@@ -50,6 +52,14 @@ pub enum BooleanExpr {
     Not(#[n(0)] Box<BooleanExpr>),
     #[n(6)]
     Empty,
+    #[n(7)]
+    StartsWith(#[n(0)] String, #[n(1)] String),
+    #[n(8)]
+    EndsWith(#[n(0)] String, #[n(1)] String),
+    #[n(9)]
+    Matches(#[n(0)] String, #[n(1)] String),
+    #[n(10)]
+    Intersects(#[n(0)] String, #[n(1)] String),
 }
 
 impl PartialEq for BooleanExpr {
@@ -63,6 +73,12 @@ impl PartialEq for BooleanExpr {
             (BooleanExpr::Or(e1, e2), BooleanExpr::Or(e3, e4)) => e1 == e3 && e2 == e4,
             (BooleanExpr::And(e1, e2), BooleanExpr::And(e3, e4)) => e1 == e3 && e2 == e4,
             (BooleanExpr::Not(e1), BooleanExpr::Not(e2)) => e1 == e2,
+            (BooleanExpr::StartsWith(n1, v1), BooleanExpr::StartsWith(n2, v2))
+            | (BooleanExpr::EndsWith(n1, v1), BooleanExpr::EndsWith(n2, v2))
+            | (BooleanExpr::Matches(n1, v1), BooleanExpr::Matches(n2, v2))
+            | (BooleanExpr::Intersects(n1, v1), BooleanExpr::Intersects(n2, v2)) => {
+                n1 == n2 && v1 == v2
+            }
             _ => false,
         }
     }
@@ -88,6 +104,7 @@ impl Display for BooleanExpr {
                 BooleanExpr::And(e1, e2) => format!("({e1} and {e2})"),
                 BooleanExpr::Not(e) => format!("(not {e})"),
                 BooleanExpr::Empty => "".to_string(),
+                other => other.to_string(),
             }
         }
 
@@ -113,10 +130,36 @@ impl Display for BooleanExpr {
             )),
             BooleanExpr::Not(e) => f.write_str(&format!("not {}", to_nested_string(e))),
             BooleanExpr::Empty => f.write_str(""),
+            BooleanExpr::StartsWith(s, v) => write_comparison(f, s, "^=", v),
+            BooleanExpr::EndsWith(s, v) => write_comparison(f, s, "$=", v),
+            BooleanExpr::Matches(s, v) => write_comparison(f, s, "~=", v),
+            BooleanExpr::Intersects(s, v) => write_comparison(f, s, "&=", v),
         }
     }
 }
 
+/// Write a name-value comparison, quoting the value when it can't be parsed without quotes
+#[cfg(feature = "std")]
+fn write_comparison(
+    f: &mut Formatter<'_>,
+    name: &str,
+    operator: &str,
+    value: &str,
+) -> core::fmt::Result {
+    let is_unquoted_value_char = |c: char| {
+        c.is_ascii_alphanumeric()
+            || c == '.'
+            || c == '_'
+            || c == '-'
+            || (operator == "&=" && c == ',')
+    };
+    if value.chars().all(is_unquoted_value_char) {
+        write!(f, "{name}{operator}{value}")
+    } else {
+        write!(f, "{name}{operator}\"{value}\"")
+    }
+}
+
 #[cfg(feature = "std")]
 impl TryFrom<&str> for BooleanExpr {
     type Error = crate::ParseError;
@@ -163,6 +206,27 @@ impl BooleanExpr {
         BooleanExpr::NameValue(s.to_string(), v.to_string())
     }
 
+    /// Create a check that the value of a name starts with a prefix.
+    pub fn starts_with(s: &str, prefix: &str) -> BooleanExpr {
+        BooleanExpr::StartsWith(s.to_string(), prefix.to_string())
+    }
+
+    /// Create a check that the value of a name ends with a suffix.
+    pub fn ends_with(s: &str, suffix: &str) -> BooleanExpr {
+        BooleanExpr::EndsWith(s.to_string(), suffix.to_string())
+    }
+
+    /// Create a check that the value of a name matches a regular expression.
+    pub fn matches(s: &str, regex: &str) -> BooleanExpr {
+        BooleanExpr::Matches(s.to_string(), regex.to_string())
+    }
+
+    /// Create a check that the comma-separated values of a name share at least one value
+    /// with the given comma-separated values.
+    pub fn intersects(s: &str, values: &str) -> BooleanExpr {
+        BooleanExpr::Intersects(s.to_string(), values.to_string())
+    }
+
     /// Create an identity identifier to be used in a boolean expression.
     pub fn identifier(s: &str) -> BooleanExpr {
         BooleanExpr::Identifier(s.to_string())
@@ -223,6 +287,10 @@ impl BooleanExpr {
             ]),
             BooleanExpr::Not(e) => List(vec![Ident("not".to_string()), e.to_expression()]),
             BooleanExpr::Empty => List(vec![]),
+            BooleanExpr::StartsWith(n, v) => comparison("starts-with?", n, v),
+            BooleanExpr::EndsWith(n, v) => comparison("ends-with?", n, v),
+            BooleanExpr::Matches(n, v) => comparison("matches?", n, v),
+            BooleanExpr::Intersects(n, v) => comparison("intersects?", n, v),
         }
     }
 
@@ -259,6 +327,15 @@ impl BooleanExpr {
     }
 }
 
+/// Create the policy expression comparing the value of a subject attribute with a string
+fn comparison(operator: &str, name: &str, value: &str) -> Expr {
+    List(vec![
+        Ident(operator.to_string()),
+        Ident(format!("{}.{}", SUBJECT_KEY, name)),
+        Str(value.to_string()),
+    ])
+}
+
 #[cfg(feature = "std")]
 impl Not for BooleanExpr {
     type Output = BooleanExpr;
@@ -276,18 +353,20 @@ impl Not for BooleanExpr {
 ///    and_expr : not_expr (or not_expr)*
 ///    not_expr : not not_expr | parenthesized | name
 ///    parenthesized : '(' expr ')'
-///    name : (alphanum | '.' | '_' | '-')+
+///    name : (alphanum | '.' | '_' | '-')+ (comparison value)?
+///    comparison : '=' | '^=' | '$=' | '~=' | '&='
+///    value : '"' [^"]+ '"' | (alphanum | '.' | '_' | '-')+ | (alphanum | '.' | '_' | '-' | ',')+ after '&='
 #[cfg(feature = "std")]
 mod parsers {
     use crate::boolean_expr::{BooleanExpr, NAME_FORMAT};
     use ockam_core::env::FromString;
     use ockam_identity::Identifier;
     use winnow::ascii::multispace0;
-    use winnow::combinator::{alt, delimited, separated};
+    use winnow::combinator::{alt, delimited, opt, separated};
     use winnow::error::StrContext;
     use winnow::stream::AsChar;
     use winnow::token::{literal, take_until, take_while};
-    use winnow::{IResult, PResult, Parser};
+    use winnow::{PResult, Parser};
 
     /// Top-level parser for boolean expressions as a series of 'or-ed' and-expressions
     pub fn expr(i: &mut &str) -> PResult<BooleanExpr> {
@@ -364,19 +443,18 @@ mod parsers {
         }
 
         // otherwise, keep processing the input to figure out if it's a name-value pair
-        // peek the next char; continue only if it's an equal sign
-        let peeked: IResult<&str, &str> = take_while(1, |c| c == '=').parse_peek(input.as_ref());
-        let next_char_is_not_equal_sign = peeked.map(|(_, s)| s.is_empty()).unwrap_or(true);
-        if next_char_is_not_equal_sign {
+        // continue only if the next characters are a comparison operator
+        let comparison: PResult<Option<&str>> = opt(alt((
+            literal("^="),
+            literal("$="),
+            literal("~="),
+            literal("&="),
+            literal("="),
+        )))
+        .parse_next(input);
+        let Some(comparison) = comparison? else {
             return Ok(name);
-        }
-
-        // skip '=' character
-        take_while(1, |c| c == '=')
-            .context(StrContext::Expected(
-                "not a name-value pair, missing '='".into(),
-            ))
-            .parse_next(input)?;
+        };
         // skip the opening '"' if any
         let is_quoted = {
             let res: PResult<&str> = take_while(1, |c| c == '"').parse_next(input);
@@ -393,14 +471,23 @@ mod parsers {
             take_while(1, |c| c == '"').parse_next(input)?;
             value
         } else {
-            take_while(1.., |c| {
-                AsChar::is_alphanum(c) || c == '.' || c == '_' || c == '-'
+            // a list of values can be separated with commas
+            let is_list = comparison == "&=";
+            take_while(1.., move |c| {
+                AsChar::is_alphanum(c) || c == '.' || c == '_' || c == '-' || (is_list && c == ',')
             })
             .context(StrContext::Expected(empty_value_error.into()))
             .parse_next(input)?
             .to_string()
         };
-        Ok(BooleanExpr::NameValue(name.to_string(), value))
+        let name = name.to_string();
+        Ok(match comparison {
+            "^=" => BooleanExpr::StartsWith(name, value),
+            "$=" => BooleanExpr::EndsWith(name, value),
+            "~=" => BooleanExpr::Matches(name, value),
+            "&=" => BooleanExpr::Intersects(name, value),
+            _ => BooleanExpr::NameValue(name, value),
+        })
     }
 
     /// Parse the 'and' operator
@@ -467,6 +554,17 @@ mod tests {
         .unwrap()
         .unwrap();
         assert_eq!(boolean_expr.to_expression(), expr);
+
+        let boolean_expr = BooleanExpr::and(
+            BooleanExpr::starts_with("team", "infra-"),
+            BooleanExpr::intersects("groups", "admin,ops"),
+        );
+        let expr = parse(
+            "and (starts-with? subject.team \"infra-\") (intersects? subject.groups \"admin,ops\")",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(boolean_expr.to_expression(), expr);
     }
 
     #[test]
@@ -498,6 +596,21 @@ mod tests {
         );
         let expr = "(a=\"the value\" or b) and (not c)".to_string();
         assert_eq!(boolean_expr.to_string(), expr);
+
+        let boolean_expr = BooleanExpr::or(
+            BooleanExpr::and(
+                BooleanExpr::starts_with("team", "infra-"),
+                BooleanExpr::ends_with("region", "-eu"),
+            ),
+            BooleanExpr::and(
+                BooleanExpr::matches("email", ".*@example[.]com$"),
+                BooleanExpr::intersects("groups", "admin,ops"),
+            ),
+        );
+        let expr = "(team^=infra- and region$=-eu) or (email~=\".*@example[.]com$\" and groups&=admin,ops)"
+            .to_string();
+        assert_eq!(boolean_expr.to_string(), expr);
+        assert_eq!(BooleanExpr::try_from(expr).unwrap(), boolean_expr);
     }
 
     #[test]
@@ -523,7 +636,29 @@ mod tests {
         test_parse_name_value("a.b.c=a_b-c");
         test_parse_name_value("a.b.c=\"the value\"");
 
+        test_parse(
+            &mut name,
+            &mut "team^=infra-",
+            BooleanExpr::starts_with("team", "infra-"),
+        );
+        test_parse(
+            &mut name,
+            &mut "region$=-eu",
+            BooleanExpr::ends_with("region", "-eu"),
+        );
+        test_parse(
+            &mut name,
+            &mut "email~=\"^[a-z]+@example[.]com$\"",
+            BooleanExpr::matches("email", "^[a-z]+@example[.]com$"),
+        );
+        test_parse(
+            &mut name,
+            &mut "groups&=admin,ops",
+            BooleanExpr::intersects("groups", "admin,ops"),
+        );
+
         test_fail_parse_name_value("a=", "the value can't be empty");
+        test_fail_parse_name_value("a^=", "the value can't be empty");
         test_fail_parse_name_value("=b", "The first character cannot be");
     }
 
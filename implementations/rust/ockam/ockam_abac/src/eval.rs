@@ -3,9 +3,15 @@ use core::cmp::Ordering;
 use crate::env::Env;
 use crate::error::EvalError;
 use crate::expr::{unit, Expr};
+use ockam_core::compat::collections::BTreeSet;
+#[cfg(feature = "std")]
+use ockam_core::compat::format;
 use ockam_core::compat::string::ToString;
 use ockam_core::compat::vec::Vec;
 
+/// Number of seconds in a day.
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
 #[rustfmt::skip]
 pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
     /// A stack operation.
@@ -23,6 +29,15 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
         Gt(usize),
         Lt(usize),
         Member,
+        StartsWith,
+        EndsWith,
+        #[cfg(feature = "std")]
+        Matches,
+        Intersects,
+        Before(usize),
+        After(usize),
+        HourOfDay,
+        DayOfWeek,
         Seq(usize),
     }
 
@@ -132,6 +147,63 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             }
                             ctrl.push(Op::Member)
                         }
+                        "starts-with?" => {
+                            if nargs != 2 {
+                                let msg = "'starts-with?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::StartsWith)
+                        }
+                        "ends-with?" => {
+                            if nargs != 2 {
+                                let msg = "'ends-with?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::EndsWith)
+                        }
+                        #[cfg(feature = "std")]
+                        "matches?" => {
+                            if nargs != 2 {
+                                let msg = "'matches?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Matches)
+                        }
+                        "intersects?" => {
+                            if nargs != 2 {
+                                let msg = "'intersects?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Intersects)
+                        }
+                        "before?" => {
+                            if nargs < 2 {
+                                let msg = "'before?' requires at least two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Before(nargs))
+                        }
+                        "after?" => {
+                            if nargs < 2 {
+                                let msg = "'after?' requires at least two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::After(nargs))
+                        }
+                        "hour-of-day" => {
+                            if nargs != 1 {
+                                let msg = "'hour-of-day' requires one argument";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::HourOfDay)
+                        }
+                        "day-of-week" => {
+                            if nargs != 1 {
+                                let msg = "'day-of-week' requires one argument";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::DayOfWeek)
+                        }
                         "exists?" => {
                             let mut b = true;
                             for x in &xs[1 ..] {
@@ -255,6 +327,60 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                     }
                 }
             }
+            Op::StartsWith => {
+                let p = pop(&mut args);
+                let x = pop(&mut args);
+                match (x, p) {
+                    (Expr::Str(x), Expr::Str(p)) => args.push(Expr::Bool(x.starts_with(p.as_str()))),
+                    (x, p) => return Err(EvalError::TypeMismatch(x, p))
+                }
+            }
+            Op::EndsWith => {
+                let p = pop(&mut args);
+                let x = pop(&mut args);
+                match (x, p) {
+                    (Expr::Str(x), Expr::Str(p)) => args.push(Expr::Bool(x.ends_with(p.as_str()))),
+                    (x, p) => return Err(EvalError::TypeMismatch(x, p))
+                }
+            }
+            #[cfg(feature = "std")]
+            Op::Matches => {
+                let p = pop(&mut args);
+                let x = pop(&mut args);
+                match (x, p) {
+                    (Expr::Str(x), Expr::Str(p)) => {
+                        let re = regex::Regex::new(&p)
+                            .map_err(|e| EvalError::malformed(format!("invalid regular expression {p:?}: {e}")))?;
+                        args.push(Expr::Bool(re.is_match(&x)))
+                    }
+                    (x, p) => return Err(EvalError::TypeMismatch(x, p))
+                }
+            }
+            Op::Intersects => {
+                let b = pop(&mut args);
+                let a = pop(&mut args);
+                let msg = "'intersects?' expects strings of comma-separated values or sequences of strings";
+                let a = set_elements(&a, msg)?;
+                let b = set_elements(&b, msg)?;
+                args.push(Expr::Bool(!a.is_disjoint(&b)))
+            }
+            Op::Before(n) => eval_predicate(n, &mut args, |x, y| {
+                let msg = "'before?' expects timestamps as arguments";
+                Ok(timestamp(x, msg)? < timestamp(y, msg)?)
+            })?,
+            Op::After(n) => eval_predicate(n, &mut args, |x, y| {
+                let msg = "'after?' expects timestamps as arguments";
+                Ok(timestamp(x, msg)? > timestamp(y, msg)?)
+            })?,
+            Op::HourOfDay => {
+                let t = timestamp(&pop(&mut args), "'hour-of-day' expects a timestamp")?;
+                args.push(Expr::Int(t.rem_euclid(SECONDS_PER_DAY) / 3600))
+            }
+            Op::DayOfWeek => {
+                let t = timestamp(&pop(&mut args), "'day-of-week' expects a timestamp")?;
+                // The Unix epoch was a Thursday, days are numbered from 1 (Monday) to 7 (Sunday)
+                args.push(Expr::Int((t.div_euclid(SECONDS_PER_DAY) + 3).rem_euclid(7) + 1))
+            }
             Op::Seq(n) => {
                 let s = args.split_off(args.len() - n);
                 args.push(Expr::Seq(s))
@@ -293,10 +419,52 @@ where
     Ok(())
 }
 
+/// Interpret an expression as a timestamp, in seconds since the Unix epoch.
+///
+/// Attribute values are strings, hence strings of digits are accepted as well.
+fn timestamp(x: &Expr, msg: &'static str) -> Result<i64, EvalError> {
+    match x {
+        Expr::Int(t) => Ok(*t),
+        Expr::Str(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| EvalError::InvalidType(x.clone(), msg)),
+        other => Err(EvalError::InvalidType(other.clone(), msg)),
+    }
+}
+
+/// Interpret an expression as a set of strings.
+///
+/// Attribute values are strings, hence a set can be given as comma-separated values.
+fn set_elements<'a>(x: &'a Expr, msg: &'static str) -> Result<BTreeSet<&'a str>, EvalError> {
+    match x {
+        Expr::Str(s) => Ok(s
+            .split(',')
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect()),
+        Expr::Seq(xs) => {
+            let mut elements = BTreeSet::new();
+            for x in xs {
+                match x {
+                    Expr::Str(s) => {
+                        elements.insert(s.as_str());
+                    }
+                    other => return Err(EvalError::InvalidType(other.clone(), msg)),
+                }
+            }
+            Ok(elements)
+        }
+        other => Err(EvalError::InvalidType(other.clone(), msg)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::expr::str;
     use crate::{
-        eval, subject_has_credential_attribute, subject_has_credential_policy_expression, Env, Expr,
+        eval, parse, subject_has_credential_attribute, subject_has_credential_policy_expression,
+        Env, Expr,
     };
 
     #[test]
@@ -324,4 +492,44 @@ mod tests {
         let res = eval(&check_credential_expression, &environment).unwrap();
         matches!(res, Expr::Bool(true));
     }
+
+    #[test]
+    fn string_set_and_time_operators() {
+        let mut environment = Env::new();
+        environment.put("subject.team", str("infra-eu"));
+        environment.put("subject.groups", str("dev, ops"));
+        environment.put("subject.expires_at", str("1700003600"));
+        // Tuesday, 14 November 2023 22:13:20 UTC
+        environment.put("now", Expr::Int(1700000000));
+
+        let check = |policy: &str, expected: bool| {
+            let expression = parse(policy).unwrap().unwrap();
+            assert_eq!(
+                eval(&expression, &environment).unwrap(),
+                Expr::Bool(expected),
+                "{policy}"
+            );
+        };
+
+        check(r#"(starts-with? subject.team "infra-")"#, true);
+        check(r#"(starts-with? subject.team "eu")"#, false);
+        check(r#"(ends-with? subject.team "-eu")"#, true);
+        check(r#"(matches? subject.team "^infra-(eu|us)$")"#, true);
+        check(r#"(matches? subject.team "^infra-us")"#, false);
+        check(r#"(intersects? subject.groups "admin,ops")"#, true);
+        check(r#"(intersects? subject.groups ["admin" "qa"])"#, false);
+        check("(before? now subject.expires_at)", true);
+        check("(after? now subject.expires_at)", false);
+        check("(= (hour-of-day now) 22)", true);
+        check("(= (day-of-week now) 2)", true);
+        check(
+            r#"(and (starts-with? subject.team "infra-") (< 8 (hour-of-day now) 18))"#,
+            false,
+        );
+
+        let expression = parse(r#"(matches? subject.team "(")"#).unwrap().unwrap();
+        assert!(eval(&expression, &environment).is_err());
+        let expression = parse(r#"(before? now "tomorrow")"#).unwrap().unwrap();
+        assert!(eval(&expression, &environment).is_err());
+    }
 }
@@ -31,7 +31,8 @@ pub use error::{EvalError, ParseError};
 pub use eval::eval;
 pub use expr::Expr;
 pub use policy::{
-    storage::*, Policies, PolicyAccessControl, ResourcePolicy, ResourceTypePolicy, Resources,
+    storage::*, Policies, PolicyAccessControl, PolicyAccessControlMetrics, ResourcePolicy,
+    ResourceTypePolicy, Resources,
 };
 pub use policy_expr::*;
 pub use resource::{Resource, ResourceType};
@@ -19,8 +19,25 @@ fn ident_pattern() -> &'static Regex {
     })
 }
 
-pub const OPERATORS: [&str; 10] = [
-    "and", "or", "not", "if", "<", ">", "=", "!=", "member?", "exists?",
+pub const OPERATORS: [&str; 18] = [
+    "and",
+    "or",
+    "not",
+    "if",
+    "<",
+    ">",
+    "=",
+    "!=",
+    "member?",
+    "exists?",
+    "starts-with?",
+    "ends-with?",
+    "matches?",
+    "intersects?",
+    "before?",
+    "after?",
+    "hour-of-day",
+    "day-of-week",
 ];
 
 #[rustfmt::skip]
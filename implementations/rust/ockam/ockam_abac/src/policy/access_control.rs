@@ -1,5 +1,7 @@
 use crate::abac::Abac;
-use crate::policy::{IncomingPolicyAccessControl, OutgoingPolicyAccessControl};
+use crate::policy::{
+    IncomingPolicyAccessControl, OutgoingPolicyAccessControl, PolicyAccessControlMetrics,
+};
 use crate::{Action, Env, Policies, Resource};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
@@ -19,6 +21,7 @@ pub struct PolicyAccessControl {
     pub(super) policies: Policies,
     pub(super) resource: Resource,
     pub(super) action: Action,
+    pub(super) metrics: Option<Arc<PolicyAccessControlMetrics>>,
 }
 
 /// Debug implementation writing out the resource, action and initial environment
@@ -53,6 +56,19 @@ impl PolicyAccessControl {
             policies,
             resource,
             action,
+            metrics: None,
+        }
+    }
+
+    /// Count the denied messages with the given metrics
+    pub fn with_metrics(mut self, metrics: Arc<PolicyAccessControlMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub(super) fn record_denial(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_denial(&self.resource.resource_type)
         }
     }
 
@@ -78,6 +94,21 @@ impl PolicyAccessControl {
     }
 
     pub async fn is_identity_authorized(&self, identifier: &Identifier) -> Result<bool> {
+        self.is_authorized_with_abac(identifier, &self.abac).await
+    }
+
+    /// Returns true if the identity is authorized when the environment is extended with
+    /// some additional attributes, for example the destination requested from an outlet
+    pub async fn is_identity_authorized_with_attributes(
+        &self,
+        identifier: &Identifier,
+        attributes: Env,
+    ) -> Result<bool> {
+        self.is_authorized_with_abac(identifier, &self.abac.with_attributes(attributes))
+            .await
+    }
+
+    async fn is_authorized_with_abac(&self, identifier: &Identifier, abac: &Abac) -> Result<bool> {
         // Load the policy expression for resource and action:
         let expression = if let Some(expr) = self
             .policies
@@ -95,8 +126,6 @@ impl PolicyAccessControl {
             return Ok(false);
         };
 
-        self.abac
-            .is_identity_authorized(identifier, &expression)
-            .await
+        abac.is_identity_authorized(identifier, &expression).await
     }
 }
@@ -14,6 +14,16 @@ pub struct IncomingPolicyAccessControl {
 #[async_trait]
 impl IncomingAccessControl for IncomingPolicyAccessControl {
     async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        let is_authorized = self.evaluate(relay_msg).await?;
+        if !is_authorized {
+            self.policy_access_control.record_denial();
+        }
+        Ok(is_authorized)
+    }
+}
+
+impl IncomingPolicyAccessControl {
+    async fn evaluate(&self, relay_msg: &RelayMessage) -> Result<bool> {
         // Load the policy expression for resource and action:
         let expression = if let Some(expr) = self
             .policy_access_control
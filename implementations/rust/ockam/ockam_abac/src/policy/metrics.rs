@@ -0,0 +1,54 @@
+use crate::ResourceType;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::Mutex;
+use ockam_core::compat::vec::Vec;
+
+/// Counters for the messages denied by policy access controls, per resource type.
+///
+/// The same instance can be shared by all the access controls created on a node,
+/// see [`crate::PolicyAccessControl::with_metrics`].
+#[derive(Debug, Default)]
+pub struct PolicyAccessControlMetrics {
+    denials: Mutex<BTreeMap<String, u64>>,
+}
+
+impl PolicyAccessControlMetrics {
+    /// Return the number of denied messages for each resource type, sorted by resource type
+    pub fn denials(&self) -> Vec<(String, u64)> {
+        let denials = self.denials.lock().unwrap();
+        denials.iter().map(|(k, v)| (k.clone(), *v)).collect()
+    }
+
+    /// Return the total number of denied messages
+    pub fn total_denials(&self) -> u64 {
+        self.denials.lock().unwrap().values().sum()
+    }
+
+    pub(crate) fn record_denial(&self, resource_type: &ResourceType) {
+        let mut denials = self.denials.lock().unwrap();
+        *denials.entry(resource_type.to_string()).or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn denials_are_counted_per_resource_type() {
+        let metrics = PolicyAccessControlMetrics::default();
+        metrics.record_denial(&ResourceType::TcpOutlet);
+        metrics.record_denial(&ResourceType::TcpInlet);
+        metrics.record_denial(&ResourceType::TcpOutlet);
+
+        assert_eq!(metrics.total_denials(), 3);
+        assert_eq!(
+            metrics.denials(),
+            vec![
+                (ResourceType::TcpInlet.to_string(), 1),
+                (ResourceType::TcpOutlet.to_string(), 2)
+            ]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 mod access_control;
 mod incoming;
+mod metrics;
 mod outgoing;
 mod policies;
 mod resource_policy;
@@ -9,6 +10,7 @@ pub(crate) mod storage;
 
 pub use access_control::*;
 pub use incoming::*;
+pub use metrics::PolicyAccessControlMetrics;
 pub use outgoing::*;
 
 pub use policies::Policies;
@@ -23,6 +23,16 @@ impl Debug for OutgoingPolicyAccessControl {
 #[async_trait]
 impl OutgoingAccessControl for OutgoingPolicyAccessControl {
     async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        let is_authorized = self.evaluate(relay_msg).await?;
+        if !is_authorized {
+            self.policy_access_control.record_denial();
+        }
+        Ok(is_authorized)
+    }
+}
+
+impl OutgoingPolicyAccessControl {
+    async fn evaluate(&self, relay_msg: &RelayMessage) -> Result<bool> {
         // Load the policy expression for resource and action:
         let expression = if let Some(expr) = self
             .policy_access_control
@@ -5,7 +5,7 @@ use crate::{
     ResourceTypePoliciesRepository,
 };
 use ockam_core::compat::sync::Arc;
-use ockam_core::compat::vec::Vec;
+use ockam_core::compat::vec::{vec, Vec};
 use ockam_core::Result;
 use ockam_identity::{Identifier, IdentitiesAttributes};
 use strum::IntoEnumIterator;
@@ -63,12 +63,12 @@ impl Policies {
 
 // Methods for resource policies
 impl Policies {
+    /// Store a default policy for the `handle_message` action of each resource type.
+    /// The other actions fall back to that policy, see [`Policies::get_expression_for_resource`].
     pub async fn store_default_resource_type_policies(&self) -> Result<()> {
         for resource_type in ResourceType::iter() {
-            for action in Action::iter() {
-                self.store_default_policy_for_resource_type(&resource_type, &action)
-                    .await?;
-            }
+            self.store_default_policy_for_resource_type(&resource_type, &Action::HandleMessage)
+                .await?;
         }
         Ok(())
     }
@@ -113,26 +113,40 @@ impl Policies {
             .await
     }
 
+    /// Return the policy expression for a resource and an action.
+    ///
+    /// A policy for the resource name takes precedence over a policy for the resource type.
+    /// If there is no policy for a specific action, the `handle_message` policy is used.
     pub async fn get_expression_for_resource(
         &self,
         resource: &Resource,
         action: &Action,
     ) -> Result<Option<Expr>> {
+        let actions = if action == &Action::HandleMessage {
+            vec![action]
+        } else {
+            vec![action, &Action::HandleMessage]
+        };
+
         // Try to get a policy for the resource name.
-        if let Some(policy) = self
-            .get_policy_for_resource_name(&resource.resource_name, action)
-            .await?
-        {
-            return Ok(Some(policy.expression));
+        for action in actions.iter() {
+            if let Some(policy) = self
+                .get_policy_for_resource_name(&resource.resource_name, action)
+                .await?
+            {
+                return Ok(Some(policy.expression));
+            }
         }
 
         // If there is no policy for the resource name, try to get
         // the policy for the resource type associated to the resource name.
-        if let Some(policy) = self
-            .get_policy_for_resource_type(&resource.resource_type, action)
-            .await?
-        {
-            return Ok(Some(policy.expression));
+        for action in actions.iter() {
+            if let Some(policy) = self
+                .get_policy_for_resource_type(&resource.resource_type, action)
+                .await?
+            {
+                return Ok(Some(policy.expression));
+            }
         }
 
         Ok(None)
@@ -192,3 +206,67 @@ impl Policies {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{eq, ident, str};
+    use crate::{ResourcePolicySqlxDatabase, ResourceTypePolicySqlxDatabase};
+
+    #[tokio::test]
+    async fn test_fallback_to_handle_message_policy() -> Result<()> {
+        let policies = Policies::new(
+            Arc::new(ResourcePolicySqlxDatabase::create().await?),
+            Arc::new(ResourceTypePolicySqlxDatabase::create().await?),
+        );
+        let outlet = Resource::new("outlet", ResourceType::TcpOutlet);
+        let type_expression = eq([ident("subject.role"), str("db-admin")]);
+        let name_expression = eq([ident("subject.role"), str("admin")]);
+
+        // the handle_message policy of the resource type is used for all actions
+        policies
+            .store_policy_for_resource_type(
+                &ResourceType::TcpOutlet,
+                &Action::HandleMessage,
+                &type_expression,
+            )
+            .await?;
+        let expression = policies
+            .get_expression_for_resource(&outlet, &Action::Connect)
+            .await?;
+        assert_eq!(expression, Some(type_expression.clone()));
+
+        // a policy for the resource name takes precedence
+        policies
+            .store_policy_for_resource_name(
+                &outlet.resource_name,
+                &Action::HandleMessage,
+                &name_expression,
+            )
+            .await?;
+        let expression = policies
+            .get_expression_for_resource(&outlet, &Action::Connect)
+            .await?;
+        assert_eq!(expression, Some(name_expression.clone()));
+
+        // a policy for a specific action takes precedence
+        let connect_expression = eq([ident("resource.target_port"), 5432.into()]);
+        policies
+            .store_policy_for_resource_name(
+                &outlet.resource_name,
+                &Action::Connect,
+                &connect_expression,
+            )
+            .await?;
+        let expression = policies
+            .get_expression_for_resource(&outlet, &Action::Connect)
+            .await?;
+        assert_eq!(expression, Some(connect_expression));
+        let expression = policies
+            .get_expression_for_resource(&outlet, &Action::HandleMessage)
+            .await?;
+        assert_eq!(expression, Some(name_expression));
+
+        Ok(())
+    }
+}
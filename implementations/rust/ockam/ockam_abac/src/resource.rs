@@ -59,6 +59,12 @@ pub enum ResourceType {
     #[n(7)]
     #[strum(serialize = "lessor")]
     InfluxDBLessor,
+    #[n(8)]
+    #[strum(serialize = "udp-inlet")]
+    UdpInlet,
+    #[n(9)]
+    #[strum(serialize = "udp-outlet")]
+    UdpOutlet,
 }
 
 impl ResourceType {
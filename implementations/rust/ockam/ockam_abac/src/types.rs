@@ -3,9 +3,10 @@ use minicbor::decode::{self, Decoder};
 use minicbor::encode::{self, Encoder, Write};
 use minicbor::{CborLen, Decode, Encode};
 use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
 use serde::{Serialize, Serializer};
 use str_buf::StrBuf;
-use strum::{AsRefStr, Display, EnumIter, EnumString};
+use strum::{AsRefStr, Display, EnumIter, EnumString, IntoEnumIterator};
 
 macro_rules! define {
     ($t:ident) => {
@@ -109,9 +110,36 @@ define!(ResourceName);
 )]
 #[cbor(index_only)]
 pub enum Action {
+    /// Any message sent to a resource
     #[n(1)]
     #[strum(serialize = "handle_message")]
     HandleMessage,
+    /// Connection to the target of an outlet
+    #[n(2)]
+    #[strum(serialize = "connect")]
+    Connect,
+    /// Records published to a Kafka topic
+    #[n(3)]
+    #[strum(serialize = "produce")]
+    Produce,
+    /// Records fetched from a Kafka topic
+    #[n(4)]
+    #[strum(serialize = "consume")]
+    Consume,
+    /// Creation of a relay
+    #[n(5)]
+    #[strum(serialize = "relay_create")]
+    RelayCreate,
+}
+
+impl Action {
+    /// Return a string with all valid values joined by a commas
+    pub fn join_enum_values_as_string() -> String {
+        Self::iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
 }
 
 impl Serialize for Action {
@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use serde::{Deserialize, Serialize};
+
+use ockam_core::Result;
+
+use crate::error::ApiError;
+
+/// Keys are fetched again after that period, even if no unknown key id was received
+const JWKS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Keys are not fetched more often than that when tokens with unknown key ids are received
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Location of the JSON Web Key Set used to verify tokens
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum JwksSource {
+    /// The key set is found with the OpenID Connect discovery document of an issuer:
+    /// `<issuer>/.well-known/openid-configuration`
+    Discovery(String),
+    /// The key set is served at a URL
+    Url(String),
+    /// The key set is stored in a local file
+    File(PathBuf),
+}
+
+impl JwksSource {
+    /// Retrieve the key set
+    async fn fetch(&self) -> Result<JwkSet> {
+        match self {
+            JwksSource::Discovery(issuer) => {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    issuer.trim_end_matches('/')
+                );
+                let document: OpenIdConfiguration = Self::get_json(&url).await?;
+                Self::get_json(&document.jwks_uri).await
+            }
+            JwksSource::Url(url) => Self::get_json(url).await,
+            JwksSource::File(path) => {
+                let contents = tokio::fs::read_to_string(path).await.map_err(|e| {
+                    ApiError::core(format!(
+                        "Failed to read the JWKS file {}: {e}",
+                        path.display()
+                    ))
+                })?;
+                serde_json::from_str(&contents).map_err(|e| {
+                    ApiError::core(format!(
+                        "Failed to parse the JWKS file {}: {e}",
+                        path.display()
+                    ))
+                })
+            }
+        }
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(url: &str) -> Result<T> {
+        reqwest::get(url)
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ApiError::core(format!("Failed to get {url}: {e}")))?
+            .json()
+            .await
+            .map_err(|e| ApiError::core(format!("Failed to parse the response of {url}: {e}")))
+    }
+}
+
+/// Subset of an OpenID Connect discovery document
+#[derive(Deserialize)]
+struct OpenIdConfiguration {
+    jwks_uri: String,
+}
+
+/// Cache for the keys of an issuer.
+///
+/// The keys are fetched again when they are too old, or when a token is signed with a key
+/// which is not known yet, since this is what happens when the issuer rotates its keys.
+pub struct JwksCache {
+    source: JwksSource,
+    keys: Option<JwkSet>,
+    fetched_at: Option<Instant>,
+    min_refresh_interval: Duration,
+}
+
+impl JwksCache {
+    pub fn new(source: JwksSource) -> Self {
+        Self {
+            source,
+            keys: None,
+            fetched_at: None,
+            min_refresh_interval: JWKS_MIN_REFRESH_INTERVAL,
+        }
+    }
+
+    /// Set the minimum duration between two fetches triggered by unknown key ids
+    pub fn with_min_refresh_interval(mut self, min_refresh_interval: Duration) -> Self {
+        self.min_refresh_interval = min_refresh_interval;
+        self
+    }
+
+    /// Return the key with the given key id. If no key id is given, the key set must contain
+    /// a single key.
+    pub async fn get_key(&mut self, kid: Option<&str>) -> Result<Option<Jwk>> {
+        let expired = self
+            .fetched_at
+            .map(|t| t.elapsed() >= JWKS_MAX_AGE)
+            .unwrap_or(true);
+        if expired {
+            self.refresh().await?;
+        }
+
+        if let Some(key) = self.find(kid) {
+            return Ok(Some(key));
+        }
+
+        // The issuer might have rotated its keys
+        let can_refresh = self
+            .fetched_at
+            .map(|t| t.elapsed() >= self.min_refresh_interval)
+            .unwrap_or(true);
+        if !expired && can_refresh {
+            debug!("no JWK found for the key id {kid:?}, fetching the keys again");
+            self.refresh().await?;
+            return Ok(self.find(kid));
+        }
+        Ok(None)
+    }
+
+    async fn refresh(&mut self) -> Result<()> {
+        let keys = self.source.fetch().await?;
+        debug!("fetched {} JWK(s) from {:?}", keys.keys.len(), self.source);
+        self.keys = Some(keys);
+        self.fetched_at = Some(Instant::now());
+        Ok(())
+    }
+
+    fn find(&self, kid: Option<&str>) -> Option<Jwk> {
+        let keys = self.keys.as_ref()?;
+        match kid {
+            Some(kid) => keys.find(kid).cloned(),
+            None if keys.keys.len() == 1 => keys.keys.first().cloned(),
+            None => None,
+        }
+    }
+}
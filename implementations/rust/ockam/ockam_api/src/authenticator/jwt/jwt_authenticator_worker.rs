@@ -0,0 +1,90 @@
+use either::Either;
+use minicbor::Decoder;
+use tracing::trace;
+
+use ockam::identity::Identifier;
+use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Result, Routed, SecureChannelLocalInfo, Worker};
+use ockam_node::Context;
+
+use crate::authenticator::jwt::{JwksCache, JwtAuthenticator};
+use crate::authenticator::AuthorityMembersRepository;
+use crate::cloud::enroll::auth0::AuthenticateOidcToken;
+
+pub struct JwtAuthenticatorWorker {
+    authenticator: JwtAuthenticator,
+}
+
+impl JwtAuthenticatorWorker {
+    pub fn new(
+        members: Arc<dyn AuthorityMembersRepository>,
+        authority: &Identifier,
+        keys: JwksCache,
+        issuer: &str,
+        audience: &str,
+        identifier_claim: &str,
+        attributes: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            authenticator: JwtAuthenticator::new(
+                members,
+                authority,
+                keys,
+                issuer,
+                audience,
+                identifier_claim,
+                attributes,
+            ),
+        }
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for JwtAuthenticatorWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let secure_channel_info = match SecureChannelLocalInfo::find_info(m.local_message()) {
+            Ok(secure_channel_info) => secure_channel_info,
+            Err(_e) => {
+                let resp = Response::bad_request_no_request("secure channel required").to_vec()?;
+                c.send(m.return_route().clone(), resp).await?;
+                return Ok(());
+            }
+        };
+
+        let from = Identifier::from(secure_channel_info.their_identifier());
+        let return_route = m.return_route().clone();
+        let body = m.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = dec.decode()?;
+        trace! {
+            target: "jwt_authenticator",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let path_segments = req.path_segments::<2>();
+        let res = match (req.method(), path_segments.as_slice()) {
+            (Some(Method::Post), ["v0", "enroll"]) => {
+                let token: AuthenticateOidcToken = dec.decode()?;
+                let res = self
+                    .authenticator
+                    .authenticate(&token.access_token.0, &from)
+                    .await?;
+                match res {
+                    Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+        c.send(return_route, res).await
+    }
+}
@@ -0,0 +1,7 @@
+mod jwks;
+mod jwt_authenticator;
+mod jwt_authenticator_worker;
+
+pub use jwks::*;
+pub use jwt_authenticator::*;
+pub use jwt_authenticator_worker::*;
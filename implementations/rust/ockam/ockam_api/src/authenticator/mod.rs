@@ -1,7 +1,10 @@
 pub mod credential_issuer;
 pub mod direct;
 pub mod enrollment_tokens;
+pub mod jwt;
 pub mod one_time_code;
+pub mod revocation_list_issuer;
+pub mod svid;
 
 pub(crate) mod common;
 
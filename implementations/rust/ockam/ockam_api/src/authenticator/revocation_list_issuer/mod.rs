@@ -0,0 +1,6 @@
+#[allow(clippy::module_inception)]
+mod revocation_list_issuer;
+mod revocation_list_issuer_worker;
+
+pub use revocation_list_issuer::*;
+pub use revocation_list_issuer_worker::*;
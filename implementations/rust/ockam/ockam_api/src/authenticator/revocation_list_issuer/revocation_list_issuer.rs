@@ -0,0 +1,61 @@
+use crate::authenticator::AuthorityRevocationsRepository;
+use ockam::identity::models::RevocationListAndPurposeKey;
+use ockam::identity::{Credentials, Identifier};
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use ockam_node::compat::asynchronous::Mutex;
+
+/// This struct issues the signed list of the credentials revoked by an authority
+pub struct RevocationListIssuer {
+    revocations: Arc<dyn AuthorityRevocationsRepository>,
+    credentials: Arc<Credentials>,
+    issuer: Identifier,
+    /// The last issued revocation list. It is issued again only when new revocations are made
+    last_revocation_list: Mutex<Option<(u64, RevocationListAndPurposeKey)>>,
+}
+
+impl RevocationListIssuer {
+    /// Create a new revocation list issuer
+    pub fn new(
+        revocations: Arc<dyn AuthorityRevocationsRepository>,
+        credentials: Arc<Credentials>,
+        issuer: &Identifier,
+    ) -> Self {
+        Self {
+            revocations,
+            credentials,
+            issuer: issuer.clone(),
+            last_revocation_list: Mutex::new(None),
+        }
+    }
+
+    #[instrument(skip_all, fields(issuer = %self.issuer))]
+    pub async fn issue_revocation_list(&self) -> Result<RevocationListAndPurposeKey> {
+        let mut last_revocation_list = self.last_revocation_list.lock().await;
+        let version = self.revocations.get_version().await?;
+
+        if let Some((last_version, revocation_list)) = last_revocation_list.as_ref() {
+            if *last_version == version {
+                return Ok(revocation_list.clone());
+            }
+        }
+
+        let revoked_subjects = self
+            .revocations
+            .get_revocations()
+            .await?
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+
+        let revocation_list = self
+            .credentials
+            .credentials_creation()
+            .issue_revocation_list(&self.issuer, version, revoked_subjects)
+            .await?;
+        info!("Successfully issued the revocation list version {version}");
+
+        *last_revocation_list = Some((version, revocation_list.clone()));
+        Ok(revocation_list)
+    }
+}
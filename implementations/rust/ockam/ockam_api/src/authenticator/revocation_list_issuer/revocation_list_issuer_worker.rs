@@ -0,0 +1,76 @@
+use minicbor::Decoder;
+use tracing::trace;
+
+use crate::authenticator::revocation_list_issuer::RevocationListIssuer;
+use crate::authenticator::AuthorityRevocationsRepository;
+use ockam::identity::{Credentials, Identifier};
+use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Result, Routed, SecureChannelLocalInfo, Worker};
+use ockam_node::Context;
+
+/// This struct runs as a Worker to return the current revocation list of the authority
+pub struct RevocationListIssuerWorker {
+    revocation_list_issuer: RevocationListIssuer,
+}
+
+impl RevocationListIssuerWorker {
+    /// Create a new revocation list issuer
+    pub fn new(
+        revocations: Arc<dyn AuthorityRevocationsRepository>,
+        credentials: Arc<Credentials>,
+        issuer: &Identifier,
+    ) -> Self {
+        Self {
+            revocation_list_issuer: RevocationListIssuer::new(revocations, credentials, issuer),
+        }
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for RevocationListIssuerWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let secure_channel_info = match SecureChannelLocalInfo::find_info(m.local_message()) {
+            Ok(secure_channel_info) => secure_channel_info,
+            Err(_e) => {
+                let resp = Response::bad_request_no_request("secure channel required").to_vec()?;
+                c.send(m.return_route().clone(), resp).await?;
+                return Ok(());
+            }
+        };
+
+        let from = Identifier::from(secure_channel_info.their_identifier());
+        let return_route = m.return_route().clone();
+        let body = m.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = dec.decode()?;
+        trace! {
+            target: "revocation_list_issuer",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let res = match (req.method(), req.path()) {
+            (Some(Method::Get), "/") => {
+                match self.revocation_list_issuer.issue_revocation_list().await {
+                    Ok(revocation_list) => Response::ok()
+                        .with_headers(&req)
+                        .body(revocation_list)
+                        .to_vec()?,
+                    Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
+                }
+            }
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+
+        c.send(return_route, res).await
+    }
+}
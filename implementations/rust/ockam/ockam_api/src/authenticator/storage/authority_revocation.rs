@@ -0,0 +1,73 @@
+use ockam::identity::models::RevokedSubject;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::str::FromStr;
+use ockam_core::{Error, Result};
+
+/// Revocation of a project member's credentials, stored on the Authority node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorityRevocation {
+    identifier: Identifier,
+    revoked_by: Identifier,
+    revoked_at: TimestampInSeconds,
+    // Version of the revocation list that first included that revocation
+    version: u64,
+}
+
+impl AuthorityRevocation {
+    pub fn new(
+        identifier: Identifier,
+        revoked_by: Identifier,
+        revoked_at: TimestampInSeconds,
+        version: u64,
+    ) -> Self {
+        Self {
+            identifier,
+            revoked_by,
+            revoked_at,
+            version,
+        }
+    }
+    pub fn identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+    pub fn revoked_by(&self) -> &Identifier {
+        &self.revoked_by
+    }
+    pub fn revoked_at(&self) -> TimestampInSeconds {
+        self.revoked_at
+    }
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+impl From<AuthorityRevocation> for RevokedSubject {
+    fn from(value: AuthorityRevocation) -> Self {
+        RevokedSubject {
+            subject: value.identifier,
+            revoked_at: value.revoked_at,
+        }
+    }
+}
+
+// Low-level representation of a table row
+#[derive(sqlx::FromRow)]
+pub(crate) struct AuthorityRevocationRow {
+    identifier: String,
+    revoked_by: String,
+    revoked_at: i64,
+    version: i64,
+}
+
+impl TryFrom<AuthorityRevocationRow> for AuthorityRevocation {
+    type Error = Error;
+
+    fn try_from(value: AuthorityRevocationRow) -> Result<Self, Self::Error> {
+        Ok(AuthorityRevocation::new(
+            Identifier::from_str(&value.identifier)?,
+            Identifier::from_str(&value.revoked_by)?,
+            TimestampInSeconds(value.revoked_at as u64),
+            value.version as u64,
+        ))
+    }
+}
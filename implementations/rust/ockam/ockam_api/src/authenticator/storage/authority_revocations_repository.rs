@@ -0,0 +1,25 @@
+use crate::authenticator::AuthorityRevocation;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+/// This repository stores the revoked credentials of project members on the Authority node
+#[async_trait]
+pub trait AuthorityRevocationsRepository: Send + Sync + 'static {
+    /// Revoke all the credentials issued to a member at or before `revoked_at`.
+    /// This increments the version of the revocation list
+    async fn revoke(
+        &self,
+        identifier: &Identifier,
+        revoked_by: &Identifier,
+        revoked_at: TimestampInSeconds,
+    ) -> Result<()>;
+
+    /// Return all the revocations
+    async fn get_revocations(&self) -> Result<Vec<AuthorityRevocation>>;
+
+    /// Return the current version of the revocation list, 0 if nothing was revoked yet
+    async fn get_version(&self) -> Result<u64>;
+}
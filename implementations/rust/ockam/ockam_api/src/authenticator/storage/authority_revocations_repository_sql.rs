@@ -0,0 +1,138 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+use crate::authenticator::{
+    AuthorityRevocation, AuthorityRevocationRow, AuthorityRevocationsRepository,
+};
+
+#[derive(Clone)]
+pub struct AuthorityRevocationsSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl AuthorityRevocationsSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for authority revocations");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority revocations").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityRevocationsRepository for AuthorityRevocationsSqlxDatabase {
+    async fn revoke(
+        &self,
+        identifier: &Identifier,
+        revoked_by: &Identifier,
+        revoked_at: TimestampInSeconds,
+    ) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let version: Option<i64> = query_scalar("SELECT MAX(version) FROM authority_revocation")
+            .fetch_one(&mut *transaction)
+            .await
+            .into_core()?;
+        let version = version.unwrap_or(0) + 1;
+
+        let query = query(
+            r#"
+             INSERT INTO authority_revocation (identifier, revoked_by, revoked_at, version)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (identifier)
+             DO UPDATE SET revoked_by = $2, revoked_at = $3, version = $4"#,
+        )
+        .bind(identifier)
+        .bind(revoked_by)
+        .bind(revoked_at)
+        .bind(version);
+        query.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_revocations(&self) -> Result<Vec<AuthorityRevocation>> {
+        let query = query_as(
+            "SELECT identifier, revoked_by, revoked_at, version FROM authority_revocation ORDER BY version",
+        );
+        let rows: Vec<AuthorityRevocationRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+
+    async fn get_version(&self) -> Result<u64> {
+        let version: Option<i64> = query_scalar("SELECT MAX(version) FROM authority_revocation")
+            .fetch_one(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(version.unwrap_or(0) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::models::IDENTIFIER_LEN;
+    use ockam::identity::utils::now;
+    use ockam_core::compat::rand::RngCore;
+    use ockam_core::compat::sync::Arc;
+    use ockam_node::database::with_dbs;
+    use rand::thread_rng;
+
+    fn random_identifier() -> Identifier {
+        let mut data = [0u8; IDENTIFIER_LEN];
+
+        let mut rng = thread_rng();
+        rng.fill_bytes(&mut data);
+
+        Identifier(data)
+    }
+
+    #[tokio::test]
+    async fn test_authority_revocations_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn AuthorityRevocationsRepository> =
+                Arc::new(AuthorityRevocationsSqlxDatabase::new(db));
+
+            assert_eq!(repository.get_version().await?, 0);
+            assert!(repository.get_revocations().await?.is_empty());
+
+            let admin = random_identifier();
+            let identifier1 = random_identifier();
+            let identifier2 = random_identifier();
+            let timestamp1 = now()?;
+            let timestamp2 = timestamp1 + 10;
+
+            repository.revoke(&identifier1, &admin, timestamp1).await?;
+            repository.revoke(&identifier2, &admin, timestamp1).await?;
+            assert_eq!(repository.get_version().await?, 2);
+
+            // revoking a member again updates the revocation time and the version
+            repository.revoke(&identifier1, &admin, timestamp2).await?;
+            assert_eq!(repository.get_version().await?, 3);
+
+            let revocations = repository.get_revocations().await?;
+            assert_eq!(
+                revocations,
+                vec![
+                    AuthorityRevocation::new(identifier2, admin.clone(), timestamp1, 2),
+                    AuthorityRevocation::new(identifier1, admin, timestamp2, 3),
+                ]
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
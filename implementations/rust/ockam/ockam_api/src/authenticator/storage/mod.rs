@@ -3,6 +3,9 @@ mod authority_enrollment_token_repository_sql;
 mod authority_member;
 mod authority_members_repository;
 mod authority_members_repository_sql;
+mod authority_revocation;
+mod authority_revocations_repository;
+mod authority_revocations_repository_sql;
 mod enrollment_token;
 
 pub use authority_enrollment_token_repository::*;
@@ -10,4 +13,7 @@ pub use authority_enrollment_token_repository_sql::*;
 pub use authority_member::*;
 pub use authority_members_repository::*;
 pub use authority_members_repository_sql::*;
+pub use authority_revocation::*;
+pub use authority_revocations_repository::*;
+pub use authority_revocations_repository_sql::*;
 pub use enrollment_token::*;
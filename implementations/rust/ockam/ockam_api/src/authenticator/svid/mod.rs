@@ -0,0 +1,8 @@
+#[allow(clippy::module_inception)]
+mod svid;
+mod svid_authenticator;
+mod svid_authenticator_worker;
+
+pub use svid::*;
+pub use svid_authenticator::*;
+pub use svid_authenticator_worker::*;
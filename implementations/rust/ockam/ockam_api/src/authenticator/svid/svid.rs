@@ -0,0 +1,265 @@
+use minicbor::bytes::ByteVec;
+use minicbor::{CborLen, Decode, Encode};
+use ring::rand::SystemRandom;
+use ring::signature::{
+    EcdsaKeyPair, Ed25519KeyPair, RsaKeyPair, ECDSA_P256_SHA256_ASN1_SIGNING,
+    ECDSA_P384_SHA384_ASN1_SIGNING, RSA_PKCS1_SHA256,
+};
+use webpki::types::{CertificateDer, PrivateKeyDer};
+
+use ockam::identity::Identifier;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::fmt::{Display, Formatter};
+use ockam_core::compat::str::FromStr;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Prefix of the message signed with the private key of an SVID to enroll an identity
+const PROOF_OF_POSSESSION_PREFIX: &[u8] = b"ockam-svid-enrollment";
+
+/// Prefix of the attributes created from a SPIFFE ID
+pub const SPIFFE_ATTRIBUTE_PREFIX: &str = "spiffe_";
+
+/// Attribute containing the full SPIFFE ID of an enrolled workload
+pub const SPIFFE_ID_ATTRIBUTE: &str = "spiffe_id";
+
+/// Request sent to the SVID authenticator to enroll an identity
+#[derive(Encode, Decode, CborLen, Debug, Clone)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SvidEnrollment {
+    /// DER-encoded X.509 SVID certificate chain, starting with the leaf certificate
+    #[n(1)] pub certificate_chain: Vec<ByteVec>,
+    /// Signature of the enrolling identifier, made with the private key of the SVID
+    #[n(2)] pub signature: ByteVec,
+}
+
+impl SvidEnrollment {
+    /// Return the message which must be signed by the SVID private key to prove that its holder
+    /// controls the enrolling identity
+    pub fn proof_of_possession_message(authority: &Identifier, identifier: &Identifier) -> Vec<u8> {
+        let mut message = PROOF_OF_POSSESSION_PREFIX.to_vec();
+        message.extend_from_slice(&authority.0);
+        message.extend_from_slice(&identifier.0);
+        message
+    }
+}
+
+/// An X.509 SVID held by a workload, used to create an enrollment request
+pub struct X509Svid {
+    certificate_chain: Vec<CertificateDer<'static>>,
+    private_key: PrivateKeyDer<'static>,
+}
+
+impl X509Svid {
+    /// Create an SVID from a PEM certificate chain, starting with the leaf certificate,
+    /// and the PEM private key of the leaf certificate
+    pub fn from_pem(certificate_chain: &str, private_key: &str) -> Result<Self> {
+        let certificate_chain = rustls_pemfile::certs(&mut certificate_chain.as_bytes())
+            .collect::<core::result::Result<Vec<_>, _>>()
+            .map_err(|e| svid_error(format!("Invalid SVID certificates: {e}")))?;
+        if certificate_chain.is_empty() {
+            return Err(svid_error("No SVID certificate found"));
+        }
+        let private_key = rustls_pemfile::private_key(&mut private_key.as_bytes())
+            .map_err(|e| svid_error(format!("Invalid SVID private key: {e}")))?
+            .ok_or_else(|| svid_error("No SVID private key found"))?;
+        Ok(Self {
+            certificate_chain,
+            private_key,
+        })
+    }
+
+    /// Create a request to enroll `identifier` with `authority`
+    pub fn enrollment(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<SvidEnrollment> {
+        let message = SvidEnrollment::proof_of_possession_message(authority, identifier);
+        Ok(SvidEnrollment {
+            certificate_chain: self
+                .certificate_chain
+                .iter()
+                .map(|c| c.as_ref().to_vec().into())
+                .collect(),
+            signature: self.sign(&message)?.into(),
+        })
+    }
+
+    /// Sign a message with the private key. ECDSA P-256 and P-384, Ed25519 and RSA keys
+    /// are supported.
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let rng = SystemRandom::new();
+        match &self.private_key {
+            PrivateKeyDer::Pkcs8(key) => {
+                let key = key.secret_pkcs8_der();
+                for algorithm in [
+                    &ECDSA_P256_SHA256_ASN1_SIGNING,
+                    &ECDSA_P384_SHA384_ASN1_SIGNING,
+                ] {
+                    if let Ok(key_pair) = EcdsaKeyPair::from_pkcs8(algorithm, key, &rng) {
+                        return key_pair
+                            .sign(&rng, message)
+                            .map(|s| s.as_ref().to_vec())
+                            .map_err(|_| svid_error("Cannot sign with the SVID private key"));
+                    }
+                }
+                if let Ok(key_pair) = Ed25519KeyPair::from_pkcs8_maybe_unchecked(key) {
+                    return Ok(key_pair.sign(message).as_ref().to_vec());
+                }
+                let key_pair = RsaKeyPair::from_pkcs8(key)
+                    .map_err(|_| svid_error("Unsupported SVID private key"))?;
+                Self::sign_rsa(&key_pair, message)
+            }
+            PrivateKeyDer::Pkcs1(key) => {
+                let key_pair = RsaKeyPair::from_der(key.secret_pkcs1_der())
+                    .map_err(|_| svid_error("Unsupported SVID private key"))?;
+                Self::sign_rsa(&key_pair, message)
+            }
+            _ => Err(svid_error(
+                "Unsupported SVID private key, the key must be in the PKCS#8 format",
+            )),
+        }
+    }
+
+    fn sign_rsa(key_pair: &RsaKeyPair, message: &[u8]) -> Result<Vec<u8>> {
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                message,
+                &mut signature,
+            )
+            .map_err(|_| svid_error("Cannot sign with the SVID private key"))?;
+        Ok(signature)
+    }
+}
+
+/// SPIFFE ID, for example `spiffe://example.org/ns/production/sa/web`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpiffeId {
+    trust_domain: String,
+    path: Vec<String>,
+}
+
+impl SpiffeId {
+    pub fn trust_domain(&self) -> &str {
+        &self.trust_domain
+    }
+
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// Return the attributes of a workload with this SPIFFE ID.
+    ///
+    /// The path segments are read as name/value pairs, like in `/ns/production/sa/web`,
+    /// and added with the `spiffe_` prefix: `spiffe_ns=production`, `spiffe_sa=web`.
+    /// The full SPIFFE ID is stored as the `spiffe_id` attribute.
+    pub fn attributes(&self) -> BTreeMap<String, String> {
+        let mut attributes = BTreeMap::new();
+        for pair in self.path.chunks_exact(2) {
+            attributes.insert(
+                format!("{SPIFFE_ATTRIBUTE_PREFIX}{}", pair[0]),
+                pair[1].clone(),
+            );
+        }
+        attributes.insert(SPIFFE_ID_ATTRIBUTE.to_string(), self.to_string());
+        attributes
+    }
+}
+
+impl FromStr for SpiffeId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let rest = s.strip_prefix("spiffe://").ok_or_else(|| {
+            svid_error(format!("Invalid SPIFFE ID {s}: missing spiffe:// scheme"))
+        })?;
+        let mut segments = rest.split('/');
+        let trust_domain = segments.next().unwrap_or_default();
+        if trust_domain.is_empty()
+            || !trust_domain
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-._".contains(c))
+        {
+            return Err(svid_error(format!(
+                "Invalid SPIFFE ID {s}: invalid trust domain"
+            )));
+        }
+
+        let path: Vec<String> = segments.map(|s| s.to_string()).collect();
+        let valid_segment = |segment: &String| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-._".contains(c))
+        };
+        if !path.iter().all(valid_segment) {
+            return Err(svid_error(format!("Invalid SPIFFE ID {s}: invalid path")));
+        }
+
+        Ok(Self {
+            trust_domain: trust_domain.to_string(),
+            path,
+        })
+    }
+}
+
+impl Display for SpiffeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "spiffe://{}", self.trust_domain)?;
+        for segment in self.path.iter() {
+            write!(f, "/{segment}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn svid_error(message: impl Into<String>) -> Error {
+    Error::new(Origin::Api, Kind::Invalid, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spiffe_id() -> Result<()> {
+        let id = SpiffeId::from_str("spiffe://example.org/ns/production/sa/web")?;
+        assert_eq!(id.trust_domain(), "example.org");
+        assert_eq!(id.path(), &["ns", "production", "sa", "web"]);
+        assert_eq!(id.to_string(), "spiffe://example.org/ns/production/sa/web");
+
+        let attributes = id.attributes();
+        assert_eq!(attributes.len(), 3);
+        assert_eq!(attributes["spiffe_ns"], "production");
+        assert_eq!(attributes["spiffe_sa"], "web");
+        assert_eq!(
+            attributes["spiffe_id"],
+            "spiffe://example.org/ns/production/sa/web"
+        );
+
+        // a trailing segment without a value is only part of the SPIFFE ID
+        let id = SpiffeId::from_str("spiffe://example.org/workload/api/v1")?;
+        assert_eq!(id.attributes().len(), 2);
+        assert_eq!(id.attributes()["spiffe_workload"], "api");
+
+        for invalid in [
+            "https://example.org/ns/production",
+            "spiffe://",
+            "spiffe://Example.org/ns",
+            "spiffe://example.org/ns//sa",
+            "spiffe://example.org/ns/../sa",
+            "spiffe://example.org/ns/",
+            "spiffe://example.org/ns/prod?x=1",
+        ] {
+            assert!(SpiffeId::from_str(invalid).is_err(), "{invalid}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use either::Either;
+use minicbor::Decoder;
+use tracing::trace;
+use webpki::types::CertificateDer;
+
+use ockam::identity::Identifier;
+use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Result, Routed, SecureChannelLocalInfo, Worker};
+use ockam_node::Context;
+
+use crate::authenticator::svid::{SvidAuthenticator, SvidEnrollment};
+use crate::authenticator::AuthorityMembersRepository;
+
+pub struct SvidAuthenticatorWorker {
+    authenticator: SvidAuthenticator,
+}
+
+impl SvidAuthenticatorWorker {
+    pub fn new(
+        members: Arc<dyn AuthorityMembersRepository>,
+        authority: &Identifier,
+        trust_domain: &str,
+        trust_bundle: &[CertificateDer<'_>],
+    ) -> Result<Self> {
+        Ok(Self {
+            authenticator: SvidAuthenticator::new(members, authority, trust_domain, trust_bundle)?,
+        })
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for SvidAuthenticatorWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let secure_channel_info = match SecureChannelLocalInfo::find_info(m.local_message()) {
+            Ok(secure_channel_info) => secure_channel_info,
+            Err(_e) => {
+                let resp = Response::bad_request_no_request("secure channel required").to_vec()?;
+                c.send(m.return_route().clone(), resp).await?;
+                return Ok(());
+            }
+        };
+
+        let from = Identifier::from(secure_channel_info.their_identifier());
+        let return_route = m.return_route().clone();
+        let body = m.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = dec.decode()?;
+        trace! {
+            target: "svid_authenticator",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let res = match (req.method(), req.path()) {
+            (Some(Method::Post), "/") => {
+                let enrollment: SvidEnrollment = dec.decode()?;
+                let res = self.authenticator.authenticate(enrollment, &from).await?;
+                match res {
+                    Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+        c.send(return_route, res).await
+    }
+}
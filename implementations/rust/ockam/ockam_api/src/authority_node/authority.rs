@@ -6,9 +6,13 @@ use crate::authenticator::direct::{AccountAuthorityInfo, DirectAuthenticatorWork
 use crate::authenticator::enrollment_tokens::{
     EnrollmentTokenAcceptorWorker, EnrollmentTokenIssuerWorker,
 };
+use crate::authenticator::jwt::{JwksCache, JwtAuthenticatorWorker};
+use crate::authenticator::revocation_list_issuer::RevocationListIssuerWorker;
+use crate::authenticator::svid::{SvidAuthenticator, SvidAuthenticatorWorker};
 use crate::authenticator::{
     AuthorityEnrollmentTokenRepository, AuthorityEnrollmentTokenSqlxDatabase, AuthorityMember,
-    AuthorityMembersRepository, AuthorityMembersSqlxDatabase,
+    AuthorityMembersRepository, AuthorityMembersSqlxDatabase, AuthorityRevocationsRepository,
+    AuthorityRevocationsSqlxDatabase,
 };
 use ockam::identity::utils::now;
 use ockam::identity::{
@@ -25,6 +29,7 @@ use ockam_node::Context;
 
 use crate::authority_node::Configuration;
 use crate::echoer::Echoer;
+use crate::error::ApiError;
 use crate::nodes::service::default_address::DefaultAddress;
 
 /// This struct represents an Authority, which is an
@@ -34,10 +39,12 @@ use crate::nodes::service::default_address::DefaultAddress;
 //   - a credential issuer
 //   - an enrollment token issuer
 //   - an enrollment token acceptor
+//   - a revocation list issuer
 pub struct Authority {
     identifier: Identifier,
     secure_channels: Arc<SecureChannels>,
     members: Arc<dyn AuthorityMembersRepository>,
+    revocations: Arc<dyn AuthorityRevocationsRepository>,
     tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
     account_authority: Option<AccountAuthorityInfo>,
 }
@@ -74,6 +81,7 @@ impl Authority {
         };
 
         let members = Arc::new(AuthorityMembersSqlxDatabase::new(database.clone()));
+        let revocations = Arc::new(AuthorityRevocationsSqlxDatabase::new(database.clone()));
         let tokens = Arc::new(AuthorityEnrollmentTokenSqlxDatabase::new(database.clone()));
         let secure_channel_repository = Arc::new(SecureChannelSqlxDatabase::new(database.clone()));
 
@@ -105,6 +113,7 @@ impl Authority {
             identifier,
             secure_channels,
             members,
+            revocations,
             tokens,
             account_authority,
         })
@@ -166,6 +175,7 @@ impl Authority {
 
         let direct = DirectAuthenticatorWorker::new(
             self.members.clone(),
+            self.revocations.clone(),
             self.secure_channels.identities().identities_attributes(),
             self.account_authority.clone(),
         );
@@ -255,6 +265,29 @@ impl Authority {
         Ok(())
     }
 
+    /// Start the revocation list issuer service to publish the list of
+    /// credentials revoked by the authority
+    pub async fn start_revocation_list_issuer(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+    ) -> Result<()> {
+        let issuer = RevocationListIssuerWorker::new(
+            self.revocations.clone(),
+            self.secure_channels.identities().credentials(),
+            &self.identifier,
+        );
+
+        let address = DefaultAddress::REVOCATION_LIST_ISSUER.to_string();
+        ctx.flow_controls()
+            .add_consumer(address.clone(), secure_channel_flow_control_id);
+
+        ctx.start_worker(address.clone(), issuer).await?;
+
+        info!("started a revocation list issuer at '{address}'");
+        Ok(())
+    }
+
     /// Start the Okta service to retrieve attributes authenticated by Okta
     pub async fn start_okta(
         &self,
@@ -278,6 +311,66 @@ impl Authority {
         Ok(())
     }
 
+    /// Start the JWT authenticator to enroll identities presenting a token
+    /// issued by an OpenID Connect provider
+    pub async fn start_jwt_authenticator(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        if let Some(jwt) = &configuration.jwt {
+            let jwt_worker = JwtAuthenticatorWorker::new(
+                self.members.clone(),
+                &self.identifier,
+                JwksCache::new(jwt.jwks.clone()),
+                &jwt.issuer,
+                &jwt.audience,
+                &jwt.identifier_claim,
+                jwt.attributes.clone(),
+            );
+
+            ctx.flow_controls()
+                .add_consumer(jwt.address.clone(), secure_channel_flow_control_id);
+
+            ctx.start_worker(jwt.address.clone(), jwt_worker).await?;
+
+            info!("started a JWT authenticator at '{}'", jwt.address);
+        }
+        Ok(())
+    }
+
+    /// Start the SVID authenticator to enroll workloads presenting a SPIFFE X.509 SVID
+    pub async fn start_svid_authenticator(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        if let Some(svid) = &configuration.svid {
+            let trust_bundle = std::fs::read_to_string(&svid.trust_bundle).map_err(|e| {
+                ApiError::core(format!(
+                    "Failed to read the SVID trust bundle {}: {e}",
+                    svid.trust_bundle.display()
+                ))
+            })?;
+            let svid_worker = SvidAuthenticatorWorker::new(
+                self.members.clone(),
+                &self.identifier,
+                &svid.trust_domain,
+                &SvidAuthenticator::parse_trust_bundle(&trust_bundle)?,
+            )?;
+
+            ctx.flow_controls()
+                .add_consumer(svid.address.clone(), secure_channel_flow_control_id);
+
+            ctx.start_worker(svid.address.clone(), svid_worker).await?;
+
+            info!("started an SVID authenticator at '{}'", svid.address);
+        }
+        Ok(())
+    }
+
     /// Start an echo service
     pub async fn start_echo_service(
         &self,
@@ -1,12 +1,14 @@
 use ockam::identity::models::ChangeHistory;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 use ockam::identity::Identifier;
-use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::collections::{BTreeMap, HashMap};
 use ockam_core::compat::fmt;
 use ockam_core::compat::fmt::{Display, Formatter};
 use ockam_node::database::DatabaseConfiguration;
 
+use crate::authenticator::jwt::JwksSource;
 use crate::authenticator::PreTrustedIdentities;
 use crate::config::lookup::InternetAddress;
 use crate::nodes::service::default_address::DefaultAddress;
@@ -46,6 +48,12 @@ pub struct Configuration {
     /// optional configuration for the okta service
     pub okta: Option<OktaConfiguration>,
 
+    /// optional configuration for the JWT authenticator
+    pub jwt: Option<JwtConfiguration>,
+
+    /// optional configuration for the SPIFFE X.509 SVID authenticator
+    pub svid: Option<SvidConfiguration>,
+
     /// Account Authority identity
     pub account_authority: Option<ChangeHistory>,
 
@@ -117,6 +125,41 @@ impl OktaConfiguration {
     }
 }
 
+/// Configuration for the JWT authenticator, which enrolls the identities presenting
+/// a token issued by an OpenID Connect provider
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct JwtConfiguration {
+    pub address: String,
+
+    /// Expected `iss` claim of the tokens
+    pub issuer: String,
+
+    /// Expected `aud` claim of the tokens
+    pub audience: String,
+
+    /// Claim which must contain the identifier of the enrolling identity, for example `nonce`
+    pub identifier_claim: String,
+
+    /// Location of the keys used to verify the tokens
+    pub jwks: JwksSource,
+
+    /// Name of the member attribute to set for each claim name
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Configuration for the SVID authenticator, which enrolls the workloads presenting
+/// a SPIFFE X.509 SVID
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct SvidConfiguration {
+    pub address: String,
+
+    /// SPIFFE trust domain of the workloads, for example "example.org"
+    pub trust_domain: String,
+
+    /// Path of a PEM file containing the CA certificates of the trust domain
+    pub trust_bundle: PathBuf,
+}
+
 /// This struct represents an identity that the Authority accepts
 /// as having all its attributes fully authenticated
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
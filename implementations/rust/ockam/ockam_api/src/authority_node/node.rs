@@ -34,12 +34,29 @@ pub async fn start_node(
         .await?;
     debug!("credential issuer started");
 
+    authority
+        .start_revocation_list_issuer(ctx, &secure_channel_flow_control_id)
+        .await?;
+    debug!("revocation list issuer started");
+
     // start the Okta service (if the optional configuration has been provided)
     authority
         .start_okta(ctx, &secure_channel_flow_control_id, configuration)
         .await?;
     debug!("okta service started");
 
+    // start the JWT authenticator (if the optional configuration has been provided)
+    authority
+        .start_jwt_authenticator(ctx, &secure_channel_flow_control_id, configuration)
+        .await?;
+    debug!("jwt authenticator started");
+
+    // start the SVID authenticator (if the optional configuration has been provided)
+    authority
+        .start_svid_authenticator(ctx, &secure_channel_flow_control_id, configuration)
+        .await?;
+    debug!("svid authenticator started");
+
     // start an echo service so that the node can be queried as healthy
     authority
         .start_echo_service(ctx, &secure_channel_flow_control_id)
@@ -1,6 +1,7 @@
 use crate::CliState;
 use ockam::identity::{
     IdentitiesAttributes, IdentityAttributesRepository, IdentityAttributesSqlxDatabase,
+    RevocationListRepository, RevocationListSqlxDatabase,
 };
 use std::sync::Arc;
 
@@ -9,6 +10,7 @@ impl CliState {
     pub fn identities_attributes(&self, node_name: &str) -> Arc<IdentitiesAttributes> {
         Arc::new(IdentitiesAttributes::new(
             self.identity_attributes_repository(node_name),
+            self.revocation_list_repository(node_name),
         ))
     }
 
@@ -23,4 +25,10 @@ impl CliState {
             node_name,
         ))
     }
+
+    /// The revocation lists repository is used to ignore the attributes
+    /// of identities which have been revoked by an authority
+    fn revocation_list_repository(&self, node_name: &str) -> Arc<dyn RevocationListRepository> {
+        Arc::new(RevocationListSqlxDatabase::new(self.database(), node_name))
+    }
 }
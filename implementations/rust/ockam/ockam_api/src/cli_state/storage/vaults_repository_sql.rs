@@ -7,7 +7,9 @@ use ockam_core::async_trait;
 use ockam_core::Result;
 use ockam_node::database::{Boolean, Nullable};
 
-use crate::cli_state::{NamedVault, UseAwsKms, VaultType, VaultsRepository};
+use crate::cli_state::{
+    NamedVault, UseAwsKms, UseEncryption, UsePkcs11, VaultType, VaultsRepository,
+};
 
 #[derive(Clone)]
 pub struct VaultsSqlxDatabase {
@@ -39,15 +41,17 @@ impl VaultsRepository for VaultsSqlxDatabase {
         let query = query(
             r#"
         INSERT INTO
-            vault (name, path, is_default, is_kms)
-            VALUES ($1, $2, $3, $4)
+            vault (name, path, is_default, is_kms, is_pkcs11, is_encrypted)
+            VALUES ($1, $2, $3, $4, $5, $6)
             ON CONFLICT (name)
-            DO UPDATE SET path = $2, is_default = $3, is_kms = $4"#,
+            DO UPDATE SET path = $2, is_default = $3, is_kms = $4, is_pkcs11 = $5, is_encrypted = $6"#,
         )
         .bind(name)
         .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
         .bind(!default_exists)
-        .bind(vault_type.use_aws_kms());
+        .bind(vault_type.use_aws_kms())
+        .bind(vault_type.use_pkcs11())
+        .bind(vault_type.use_encryption());
         query.execute(&mut *transaction).await.void()?;
 
         transaction.commit().await.void()?;
@@ -55,10 +59,14 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn update_vault(&self, name: &str, vault_type: VaultType) -> Result<()> {
-        let query = query("UPDATE vault SET path = $1, is_kms = $2 WHERE name = $3")
-            .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
-            .bind(vault_type.use_aws_kms())
-            .bind(name);
+        let query = query(
+            "UPDATE vault SET path = $1, is_kms = $2, is_pkcs11 = $3, is_encrypted = $4 WHERE name = $5",
+        )
+        .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
+        .bind(vault_type.use_aws_kms())
+        .bind(vault_type.use_pkcs11())
+        .bind(vault_type.use_encryption())
+        .bind(name);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -68,7 +76,9 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_database_vault(&self) -> Result<Option<NamedVault>> {
-        let query = query_as("SELECT name, path, is_default, is_kms FROM vault WHERE path is NULL");
+        let query = query_as(
+            "SELECT name, path, is_default, is_kms, is_pkcs11, is_encrypted FROM vault WHERE path is NULL",
+        );
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -77,8 +87,10 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vault(&self, name: &str) -> Result<Option<NamedVault>> {
-        let query =
-            query_as("SELECT name, path, is_default, is_kms FROM vault WHERE name = $1").bind(name);
+        let query = query_as(
+            "SELECT name, path, is_default, is_kms, is_pkcs11, is_encrypted FROM vault WHERE name = $1",
+        )
+        .bind(name);
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -87,7 +99,8 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vaults(&self) -> Result<Vec<NamedVault>> {
-        let query = query_as("SELECT name, path, is_default, is_kms FROM vault");
+        let query =
+            query_as("SELECT name, path, is_default, is_kms, is_pkcs11, is_encrypted FROM vault");
         let rows: Vec<VaultRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         rows.iter().map(|r| r.named_vault()).collect()
     }
@@ -101,6 +114,8 @@ pub(crate) struct VaultRow {
     path: Nullable<String>,
     is_default: Boolean,
     is_kms: Boolean,
+    is_pkcs11: Boolean,
+    is_encrypted: Boolean,
 }
 
 impl VaultRow {
@@ -113,13 +128,16 @@ impl VaultRow {
     }
 
     pub(crate) fn vault_type(&self) -> VaultType {
-        match self.path.to_option() {
+        let vault_type = match self.path.to_option() {
             None => VaultType::database(UseAwsKms::from(self.is_kms.to_bool())),
             Some(p) => VaultType::local_file(
                 PathBuf::from(p).as_path(),
                 UseAwsKms::from(self.is_kms.to_bool()),
             ),
-        }
+        };
+        vault_type
+            .with_pkcs11(UsePkcs11::from(self.is_pkcs11.to_bool()))
+            .with_encryption(UseEncryption::from(self.is_encrypted.to_bool()))
     }
 
     pub(crate) fn is_default(&self) -> bool {
@@ -183,6 +201,35 @@ mod test {
             let kms = repository.store_vault("kms", vault_type.clone()).await?;
             let expected = NamedVault::new("kms", vault_type, true);
             assert_eq!(kms, expected);
+
+            // It is also possible to create a vault storing its signing keys on a PKCS#11 token
+            let vault_type =
+                VaultType::local_file("path", UseAwsKms::No).with_pkcs11(UsePkcs11::Yes);
+            let pkcs11 = repository.store_vault("pkcs11", vault_type.clone()).await?;
+            let expected = NamedVault::new("pkcs11", vault_type, false);
+            assert_eq!(pkcs11, expected);
+            assert_eq!(repository.get_named_vault("pkcs11").await?, Some(expected));
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_store_encrypted_vault() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn VaultsRepository> = Arc::new(VaultsSqlxDatabase::new(db));
+
+            // A vault is not encrypted by default
+            let vault_type = VaultType::local_file("path", UseAwsKms::No);
+            repository.store_vault("vault", vault_type.clone()).await?;
+            let result = repository.get_named_vault("vault").await?.unwrap();
+            assert!(!result.use_encryption());
+
+            // The vault can then be marked as encrypted
+            let vault_type = vault_type.with_encryption(UseEncryption::Yes);
+            repository.update_vault("vault", vault_type.clone()).await?;
+            let result = repository.get_named_vault("vault").await?;
+            assert_eq!(result, Some(NamedVault::new("vault", vault_type, true)));
             Ok(())
         })
         .await
@@ -1,8 +1,12 @@
 use colorful::Colorful;
 use ockam::identity::{Identities, Vault};
+use ockam_core::env::get_env;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_node::database::SqlxDatabase;
+use ockam_vault::storage::{SecretsEncryption, SecretsSqlxDatabase};
+use ockam_vault::{AeadSecret, AEAD_SECRET_LENGTH};
 use ockam_vault_aws::AwsSigningVault;
+use ockam_vault_pkcs11::Pkcs11SigningVault;
 use std::fmt::Write;
 use std::fmt::{Debug, Display, Formatter};
 use std::fs::OpenOptions;
@@ -16,9 +20,18 @@ use crate::{fmt_log, fmt_ok, fmt_warn};
 
 static DEFAULT_VAULT_NAME: &str = "default";
 
+/// Passphrase used to derive the key encrypting the vault secrets at rest
+pub const OCKAM_VAULT_PASSPHRASE: &str = "OCKAM_VAULT_PASSPHRASE";
+
+/// Hex-encoded key encrypting the vault secrets at rest
+pub const OCKAM_VAULT_KEY: &str = "OCKAM_VAULT_KEY";
+
+/// Path to a file containing the hex-encoded key encrypting the vault secrets at rest
+pub const OCKAM_VAULT_KEY_FILE: &str = "OCKAM_VAULT_KEY_FILE";
+
 /// The methods below support the creation and update of local vaults
 ///
-///  - by default private keys are stored locally but they can also be stored in a KMS
+///  - by default private keys are stored locally but they can also be stored in a KMS or an HSM
 ///  - keys stored locally are stored with other application data in the local database if the default vault is used
 ///  - any additional vault stores its keys in a separate file
 ///
@@ -33,6 +46,30 @@ impl CliState {
         vault_name: Option<String>,
         path: Option<PathBuf>,
         use_aws_kms: UseAwsKms,
+    ) -> Result<NamedVault> {
+        self.create_vault(vault_name, path, use_aws_kms, UsePkcs11::No)
+            .await
+    }
+
+    /// Create a vault with a given name, storing its signing keys on a PKCS#11 token.
+    /// The token is configured with the OCKAM_PKCS11_MODULE, OCKAM_PKCS11_TOKEN_LABEL and
+    /// OCKAM_PKCS11_PIN environment variables when the vault is used.
+    #[instrument(skip_all, fields(vault_name = vault_name.clone()))]
+    pub async fn create_named_pkcs11_vault(
+        &self,
+        vault_name: Option<String>,
+        path: Option<PathBuf>,
+    ) -> Result<NamedVault> {
+        self.create_vault(vault_name, path, UseAwsKms::No, UsePkcs11::Yes)
+            .await
+    }
+
+    async fn create_vault(
+        &self,
+        vault_name: Option<String>,
+        path: Option<PathBuf>,
+        use_aws_kms: UseAwsKms,
+        use_pkcs11: UsePkcs11,
     ) -> Result<NamedVault> {
         let vaults_repository = self.vaults_repository();
 
@@ -59,17 +96,20 @@ impl CliState {
         match path {
             None => match self.vaults_repository().get_database_vault().await? {
                 None => Ok(vaults_repository
-                    .store_vault(&vault_name, VaultType::database(use_aws_kms))
+                    .store_vault(
+                        &vault_name,
+                        VaultType::database(use_aws_kms).with_pkcs11(use_pkcs11),
+                    )
                     .await?),
                 Some(_) => {
                     let path = self.make_vault_path(&vault_name);
                     Ok(self
-                        .create_local_vault(vault_name, &path, use_aws_kms)
+                        .create_local_vault(vault_name, &path, use_aws_kms, use_pkcs11)
                         .await?)
                 }
             },
             Some(path) => Ok(self
-                .create_local_vault(vault_name, &path, use_aws_kms)
+                .create_local_vault(vault_name, &path, use_aws_kms, use_pkcs11)
                 .await?),
         }
     }
@@ -209,6 +249,7 @@ impl CliState {
                     vault_name.to_string(),
                     &self.make_vault_path(vault_name),
                     UseAwsKms::No,
+                    UsePkcs11::No,
                 )
                 .await?;
             self.notify_message(fmt_ok!(
@@ -278,12 +319,19 @@ impl CliState {
             VaultType::LocalFileVault {
                 path: old_path,
                 use_aws_kms,
+                use_pkcs11,
+                use_encryption,
             } => {
                 // copy the file to the new location
                 std::fs::copy(&old_path, path)?;
                 // update the path in the database
                 repository
-                    .update_vault(vault_name, VaultType::local_file(path, use_aws_kms))
+                    .update_vault(
+                        vault_name,
+                        VaultType::local_file(path, use_aws_kms)
+                            .with_pkcs11(use_pkcs11)
+                            .with_encryption(use_encryption),
+                    )
                     .await?;
                 // remove the old file
                 std::fs::remove_file(old_path)?;
@@ -292,28 +340,115 @@ impl CliState {
         Ok(())
     }
 
+    /// Encrypt the secrets of a vault at rest, with a key provided directly or derived from
+    /// a passphrase. The secrets already stored in the vault are encrypted and the vault is
+    /// marked as encrypted, so that the same passphrase or key is required to use it afterwards.
+    #[instrument(skip_all, fields(vault_name = vault_name))]
+    pub async fn encrypt_named_vault(
+        &self,
+        vault_name: &str,
+        encryption: SecretsEncryption,
+    ) -> Result<NamedVault> {
+        let named_vault = self.get_named_vault(vault_name).await?;
+        if named_vault.use_encryption() {
+            return Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("the secrets of the vault {vault_name} are already encrypted"),
+            ))?;
+        }
+
+        let db = self.vault_database(&named_vault).await?;
+        SecretsSqlxDatabase::encrypt_secrets(db, encryption).await?;
+
+        let vault_type = named_vault.vault_type().with_encryption(UseEncryption::Yes);
+        self.vaults_repository()
+            .update_vault(vault_name, vault_type)
+            .await?;
+        self.get_named_vault(vault_name).await
+    }
+
     /// Make a concrete vault based on the NamedVault metadata
     #[instrument(skip_all, fields(vault_name = named_vault.name))]
     pub async fn make_vault(&self, named_vault: NamedVault) -> Result<Vault> {
-        let db = match named_vault.vault_type {
-            VaultType::DatabaseVault { .. } => self.database(),
-            VaultType::LocalFileVault { ref path, .. } =>
-            // TODO: Avoid creating multiple dbs with the same file
-            {
-                SqlxDatabase::create_sqlite(path.as_path()).await?
+        let db = self.vault_database(&named_vault).await?;
+
+        // The secrets of an encrypted vault can only be accessed with the passphrase or the key
+        // which was used to encrypt them
+        let secrets_repository = if named_vault.use_encryption() {
+            let encryption = secrets_encryption_from_env()?.ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Misuse,
+                    format!(
+                        "the secrets of the vault {} are encrypted. Set {OCKAM_VAULT_PASSPHRASE}, {OCKAM_VAULT_KEY} or {OCKAM_VAULT_KEY_FILE} to access them",
+                        named_vault.name()
+                    ),
+                )
+            })?;
+            SecretsSqlxDatabase::new_encrypted(db, encryption).await?
+        } else {
+            let secrets_repository = SecretsSqlxDatabase::new(db);
+            if secrets_repository.is_encrypted().await? {
+                return Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!(
+                        "the secrets of the vault {} are encrypted but the vault is not marked as encrypted",
+                        named_vault.name()
+                    ),
+                ))?;
             }
+            secrets_repository
         };
+        let mut vault = Vault::create_with_secrets_repository(Arc::new(secrets_repository));
 
         if named_vault.vault_type.use_aws_kms() {
-            let mut vault = Vault::create_with_database(db);
             let aws_vault = Arc::new(AwsSigningVault::create().await?);
             vault.identity_vault = aws_vault.clone();
             vault.credential_vault = aws_vault;
-            Ok(vault)
-        } else {
-            Ok(Vault::create_with_database(db))
+        } else if named_vault.vault_type.use_pkcs11() {
+            let pkcs11_vault = Arc::new(Pkcs11SigningVault::create().await?);
+            vault.identity_vault = pkcs11_vault.clone();
+            vault.credential_vault = pkcs11_vault;
         }
+        Ok(vault)
+    }
+}
+
+/// Return the encryption of the vault secrets at rest configured with environment variables:
+///
+///  - [`OCKAM_VAULT_PASSPHRASE`]: the key is derived from a passphrase
+///  - [`OCKAM_VAULT_KEY`]: a hex-encoded key
+///  - [`OCKAM_VAULT_KEY_FILE`]: a file containing a hex-encoded key, for example written from an OS keyring
+pub fn secrets_encryption_from_env() -> Result<Option<SecretsEncryption>> {
+    if let Some(passphrase) = get_env::<String>(OCKAM_VAULT_PASSPHRASE)? {
+        return Ok(Some(SecretsEncryption::Passphrase(passphrase)));
     }
+
+    let key = match get_env::<String>(OCKAM_VAULT_KEY)? {
+        Some(key) => key,
+        None => match get_env::<String>(OCKAM_VAULT_KEY_FILE)? {
+            Some(path) => std::fs::read_to_string(&path).map_err(|e| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Io,
+                    format!("cannot read the vault key file {path}: {e}"),
+                )
+            })?,
+            None => return Ok(None),
+        },
+    };
+
+    let mut secret = AeadSecret([0u8; AEAD_SECRET_LENGTH]);
+    hex::decode_to_slice(key.trim(), &mut secret.0).map_err(|_| {
+        ockam_core::Error::new(
+            Origin::Api,
+            Kind::Invalid,
+            format!("the vault key must be {AEAD_SECRET_LENGTH} hex-encoded bytes"),
+        )
+    })?;
+    Ok(Some(SecretsEncryption::Key(secret)))
 }
 
 /// Builder functions
@@ -328,6 +463,18 @@ impl CliState {
 
 /// Private functions
 impl CliState {
+    /// Return the database storing the secrets of a vault
+    async fn vault_database(&self, named_vault: &NamedVault) -> Result<SqlxDatabase> {
+        Ok(match named_vault.vault_type {
+            VaultType::DatabaseVault { .. } => self.database(),
+            VaultType::LocalFileVault { ref path, .. } =>
+            // TODO: Avoid creating multiple dbs with the same file
+            {
+                SqlxDatabase::create_sqlite(path.as_path()).await?
+            }
+        })
+    }
+
     /// Create the database vault if it doesn't exist already
     async fn create_database_vault(
         &self,
@@ -352,6 +499,7 @@ impl CliState {
         vault_name: String,
         path: &PathBuf,
         use_aws_kms: UseAwsKms,
+        use_pkcs11: UsePkcs11,
     ) -> Result<NamedVault> {
         // check if the new file can be created
         let path_taken = self
@@ -375,7 +523,10 @@ impl CliState {
         };
         Ok(self
             .vaults_repository()
-            .store_vault(&vault_name, VaultType::local_file(path, use_aws_kms))
+            .store_vault(
+                &vault_name,
+                VaultType::local_file(path, use_aws_kms).with_pkcs11(use_pkcs11),
+            )
             .await?)
     }
 
@@ -412,10 +563,14 @@ pub struct NamedVault {
 pub enum VaultType {
     DatabaseVault {
         use_aws_kms: UseAwsKms,
+        use_pkcs11: UsePkcs11,
+        use_encryption: UseEncryption,
     },
     LocalFileVault {
         path: PathBuf,
         use_aws_kms: UseAwsKms,
+        use_pkcs11: UsePkcs11,
+        use_encryption: UseEncryption,
     },
 }
 
@@ -432,6 +587,12 @@ impl Display for VaultType {
         if self.use_aws_kms() {
             writeln!(f, "Uses AWS KMS: true",)?;
         }
+        if self.use_pkcs11() {
+            writeln!(f, "Uses PKCS#11: true",)?;
+        }
+        if self.use_encryption() {
+            writeln!(f, "Encrypted: true",)?;
+        }
         Ok(())
     }
 }
@@ -452,16 +613,82 @@ impl UseAwsKms {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
+pub enum UsePkcs11 {
+    Yes,
+    No,
+}
+
+impl UsePkcs11 {
+    pub fn from(b: bool) -> Self {
+        if b {
+            UsePkcs11::Yes
+        } else {
+            UsePkcs11::No
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
+pub enum UseEncryption {
+    Yes,
+    No,
+}
+
+impl UseEncryption {
+    pub fn from(b: bool) -> Self {
+        if b {
+            UseEncryption::Yes
+        } else {
+            UseEncryption::No
+        }
+    }
+}
+
 impl VaultType {
     pub fn database(use_aws_kms: UseAwsKms) -> Self {
-        VaultType::DatabaseVault { use_aws_kms }
+        VaultType::DatabaseVault {
+            use_aws_kms,
+            use_pkcs11: UsePkcs11::No,
+            use_encryption: UseEncryption::No,
+        }
     }
 
     pub fn local_file(path: impl Into<PathBuf>, use_aws_kms: UseAwsKms) -> Self {
         VaultType::LocalFileVault {
             path: path.into(),
             use_aws_kms,
+            use_pkcs11: UsePkcs11::No,
+            use_encryption: UseEncryption::No,
+        }
+    }
+
+    /// Store the signing keys on a PKCS#11 token
+    pub fn with_pkcs11(mut self, use_pkcs11: UsePkcs11) -> Self {
+        match &mut self {
+            VaultType::DatabaseVault {
+                use_pkcs11: value, ..
+            }
+            | VaultType::LocalFileVault {
+                use_pkcs11: value, ..
+            } => *value = use_pkcs11,
+        }
+        self
+    }
+
+    /// Encrypt the secrets stored in the vault database
+    pub fn with_encryption(mut self, use_encryption: UseEncryption) -> Self {
+        match &mut self {
+            VaultType::DatabaseVault {
+                use_encryption: value,
+                ..
+            }
+            | VaultType::LocalFileVault {
+                use_encryption: value,
+                ..
+            } => *value = use_encryption,
         }
+        self
     }
 
     pub fn path(&self) -> Option<&Path> {
@@ -473,11 +700,26 @@ impl VaultType {
 
     pub fn use_aws_kms(&self) -> bool {
         match self {
-            VaultType::DatabaseVault { use_aws_kms } => use_aws_kms == &UseAwsKms::Yes,
-            VaultType::LocalFileVault {
-                path: _,
-                use_aws_kms,
-            } => use_aws_kms == &UseAwsKms::Yes,
+            VaultType::DatabaseVault { use_aws_kms, .. } => use_aws_kms == &UseAwsKms::Yes,
+            VaultType::LocalFileVault { use_aws_kms, .. } => use_aws_kms == &UseAwsKms::Yes,
+        }
+    }
+
+    pub fn use_pkcs11(&self) -> bool {
+        match self {
+            VaultType::DatabaseVault { use_pkcs11, .. } => use_pkcs11 == &UsePkcs11::Yes,
+            VaultType::LocalFileVault { use_pkcs11, .. } => use_pkcs11 == &UsePkcs11::Yes,
+        }
+    }
+
+    pub fn use_encryption(&self) -> bool {
+        match self {
+            VaultType::DatabaseVault { use_encryption, .. } => {
+                use_encryption == &UseEncryption::Yes
+            }
+            VaultType::LocalFileVault { use_encryption, .. } => {
+                use_encryption == &UseEncryption::Yes
+            }
         }
     }
 }
@@ -512,6 +754,16 @@ impl NamedVault {
         self.vault_type.use_aws_kms()
     }
 
+    /// Return true if a PKCS#11 token is used to store signing keys
+    pub fn use_pkcs11(&self) -> bool {
+        self.vault_type.use_pkcs11()
+    }
+
+    /// Return true if the secrets stored in the vault database are encrypted
+    pub fn use_encryption(&self) -> bool {
+        self.vault_type.use_encryption()
+    }
+
     /// Return the vault path if the vault data is stored in a local file
     pub fn path(&self) -> Option<&Path> {
         self.vault_type.path()
@@ -548,6 +800,9 @@ impl Output for NamedVault {
         if self.vault_type.use_aws_kms() {
             writeln!(output, "Uses AWS KMS: true",)?;
         }
+        if self.vault_type.use_encryption() {
+            writeln!(output, "Encrypted: true",)?;
+        }
         Ok(output)
     }
 }
@@ -557,6 +812,7 @@ mod tests {
     use super::*;
     use ockam::identity::models::{PurposeKeyAttestation, PurposeKeyAttestationSignature};
     use ockam::identity::Purpose;
+    use ockam_vault::storage::SecretsRepository;
     use ockam_vault::{
         ECDSASHA256CurveP256SecretKey, ECDSASHA256CurveP256Signature, HandleToSecret,
         SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
@@ -824,4 +1080,40 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_encrypt_vault() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        // create a vault and store a secret in clear
+        let vault = cli.create_named_vault(None, None, UseAwsKms::No).await?;
+        assert!(!vault.use_encryption());
+
+        let handle =
+            SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(vec![1, 2, 3]));
+        let secret =
+            SigningSecret::ECDSASHA256CurveP256(ECDSASHA256CurveP256SecretKey::new([1; 32]));
+        cli.secrets_repository()
+            .store_signing_secret(&handle, secret.clone())
+            .await?;
+
+        // the vault is encrypted explicitly, and only once
+        let key = SecretsEncryption::Key(AeadSecret([2; 32]));
+        let vault = cli.encrypt_named_vault(&vault.name(), key.clone()).await?;
+        assert!(vault.use_encryption());
+        assert!(cli.get_named_vault(&vault.name()).await?.use_encryption());
+        assert!(cli
+            .encrypt_named_vault(&vault.name(), key.clone())
+            .await
+            .is_err());
+
+        // the existing secret can be read with the same key
+        let secrets_repository = SecretsSqlxDatabase::new_encrypted(cli.database(), key).await?;
+        assert!(secrets_repository.get_signing_secret(&handle).await? == Some(secret));
+
+        // the vault can't be used without the key
+        assert!(cli.make_vault(vault).await.is_err());
+
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
 use crate::authenticator::one_time_code::OneTimeCode;
+use crate::authenticator::svid::X509Svid;
 use crate::cloud::enroll::auth0::{AuthenticateOidcToken, OidcToken};
 use crate::cloud::HasSecureClient;
 use crate::nodes::service::default_address::DefaultAddress;
@@ -32,6 +33,18 @@ pub trait Enrollment {
         token: OidcToken,
     ) -> miette::Result<()>;
 
+    async fn enroll_with_jwt(
+        &self,
+        ctx: &Context,
+        token: OidcToken,
+    ) -> miette::Result<EnrollStatus>;
+
+    async fn enroll_with_svid(
+        &self,
+        ctx: &Context,
+        svid: &X509Svid,
+    ) -> miette::Result<EnrollStatus>;
+
     async fn present_token(
         &self,
         ctx: &Context,
@@ -63,6 +76,22 @@ impl<T: HasSecureClient + Send + Sync> Enrollment for T {
             .await
     }
 
+    async fn enroll_with_jwt(
+        &self,
+        ctx: &Context,
+        token: OidcToken,
+    ) -> miette::Result<EnrollStatus> {
+        self.get_secure_client().enroll_with_jwt(ctx, token).await
+    }
+
+    async fn enroll_with_svid(
+        &self,
+        ctx: &Context,
+        svid: &X509Svid,
+    ) -> miette::Result<EnrollStatus> {
+        self.get_secure_client().enroll_with_svid(ctx, svid).await
+    }
+
     async fn present_token(
         &self,
         ctx: &Context,
@@ -119,6 +148,39 @@ impl Enrollment for SecureClient {
             .into_diagnostic()
     }
 
+    #[instrument(skip_all)]
+    async fn enroll_with_jwt(
+        &self,
+        ctx: &Context,
+        token: OidcToken,
+    ) -> miette::Result<EnrollStatus> {
+        let req = Request::post("v0/enroll").body(AuthenticateOidcToken::new(token));
+        trace!(target: TARGET, "present a jwt");
+        let reply = self
+            .tell(ctx, DefaultAddress::JWT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?;
+        enroll_status(reply)
+    }
+
+    #[instrument(skip_all)]
+    async fn enroll_with_svid(
+        &self,
+        ctx: &Context,
+        svid: &X509Svid,
+    ) -> miette::Result<EnrollStatus> {
+        let enrollment = svid
+            .enrollment(self.server_identifier(), self.client_identifier())
+            .into_diagnostic()?;
+        let req = Request::post("/").body(enrollment);
+        trace!(target: TARGET, "present an svid");
+        let reply = self
+            .tell(ctx, DefaultAddress::SVID_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?;
+        enroll_status(reply)
+    }
+
     #[instrument(skip_all)]
     async fn present_token(
         &self,
@@ -127,24 +189,11 @@ impl Enrollment for SecureClient {
     ) -> miette::Result<EnrollStatus> {
         let req = Request::post("/").body(token);
         trace!(target: TARGET, "present a token");
-        match self
+        let reply = self
             .tell(ctx, DefaultAddress::ENROLLMENT_TOKEN_ACCEPTOR, req)
             .await
-            .into_diagnostic()?
-        {
-            Reply::Successful(_) => Ok(EnrollStatus::EnrolledSuccessfully),
-            Reply::Failed(e, s) => match (e.message(), s) {
-                // TODO: the `authenticator` should return proper error codes
-                (Some(error), Some(Status::Forbidden)) => {
-                    if error.to_lowercase().contains("already a member") {
-                        Ok(EnrollStatus::AlreadyEnrolled)
-                    } else {
-                        Err(miette::miette!(e))
-                    }
-                }
-                _ => Err(miette::miette!(e)),
-            },
-        }
+            .into_diagnostic()?;
+        enroll_status(reply)
     }
 
     #[instrument(skip_all)]
@@ -158,3 +207,21 @@ impl Enrollment for SecureClient {
             .into_diagnostic()
     }
 }
+
+/// Return the status of an enrollment from the reply of an authenticator
+fn enroll_status(reply: Reply<()>) -> miette::Result<EnrollStatus> {
+    match reply {
+        Reply::Successful(_) => Ok(EnrollStatus::EnrolledSuccessfully),
+        Reply::Failed(e, s) => match (e.message(), s) {
+            // TODO: the `authenticator` should return proper error codes
+            (Some(error), Some(Status::Forbidden)) => {
+                if error.to_lowercase().contains("already a member") {
+                    Ok(EnrollStatus::AlreadyEnrolled)
+                } else {
+                    Err(miette::miette!(e))
+                }
+            }
+            _ => Err(miette::miette!(e)),
+        },
+    }
+}
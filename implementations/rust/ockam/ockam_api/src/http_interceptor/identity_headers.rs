@@ -0,0 +1,498 @@
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use ockam::identity::utils::now;
+use ockam::tcp::IdentityAttributesProvider;
+use ockam_core::{async_trait, LocalInfoIdentifier};
+use ockam_node::Context;
+use ockam_transport_tcp::{Direction, PortalInterceptor, PortalInterceptorFactory};
+use sha2::Sha256;
+use tokio::sync::{Mutex, OnceCell};
+use tracing::warn;
+
+use crate::http_interceptor::{
+    write_header, write_request_line, RequestState, BAD_REQUEST_RESPONSE,
+};
+
+/// Prefix of all the headers set by the interceptor.
+/// The headers with this prefix sent by the client are removed
+pub const OCKAM_HEADER_PREFIX: &str = "X-Ockam-";
+
+/// Header containing the identifier of the identity on the other side of the secure channel
+pub const OCKAM_IDENTIFIER_HEADER: &str = "X-Ockam-Identifier";
+
+/// Prefix of the headers containing the credential attributes of the identity.
+/// There is one header per attribute, named `X-Ockam-Attribute-<attribute name>`
+pub const OCKAM_ATTRIBUTE_HEADER_PREFIX: &str = "X-Ockam-Attribute-";
+
+/// Header containing the time, in seconds since the Unix epoch, when the headers were signed
+pub const OCKAM_TIMESTAMP_HEADER: &str = "X-Ockam-Timestamp";
+
+/// Header containing the hex-encoded HMAC-SHA256 signature of the identity headers,
+/// see [`sign_identity_headers`]
+pub const OCKAM_SIGNATURE_HEADER: &str = "X-Ockam-Signature";
+
+/// Create interceptors for the HTTP/1.1 requests sent to an outlet, which pass the verified
+/// identity of the inlet to the HTTP server:
+///
+///  - the `X-Ockam-*` headers sent by the client are removed,
+///  - the identifier on the other side of the secure channel is set in [`OCKAM_IDENTIFIER_HEADER`],
+///  - the attributes returned by the attributes provider are set in
+///    [`OCKAM_ATTRIBUTE_HEADER_PREFIX`] headers,
+///  - if an HMAC key is configured, the headers are signed with [`OCKAM_TIMESTAMP_HEADER`]
+///    and [`OCKAM_SIGNATURE_HEADER`], so that the server can check that they were set by the outlet.
+///
+/// The responses are not modified.
+#[derive(Clone, Default)]
+pub struct HttpIdentityInterceptorFactory {
+    attributes_provider: Option<Arc<dyn IdentityAttributesProvider>>,
+    hmac_key: Option<Arc<Vec<u8>>>,
+}
+
+impl HttpIdentityInterceptorFactory {
+    /// Create a factory for interceptors only setting the identifier header
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the provider of the credential attributes sent in the attributes headers
+    pub fn with_attributes_provider(
+        mut self,
+        attributes_provider: Arc<dyn IdentityAttributesProvider>,
+    ) -> Self {
+        self.attributes_provider = Some(attributes_provider);
+        self
+    }
+
+    /// Sign the identity headers with this key
+    pub fn with_hmac_key(mut self, hmac_key: Vec<u8>) -> Self {
+        self.hmac_key = Some(Arc::new(hmac_key));
+        self
+    }
+}
+
+impl PortalInterceptorFactory for HttpIdentityInterceptorFactory {
+    fn create(&self) -> Arc<dyn PortalInterceptor> {
+        self.create_for_identifier(None)
+    }
+
+    fn create_for_identifier(
+        &self,
+        their_identifier: Option<LocalInfoIdentifier>,
+    ) -> Arc<dyn PortalInterceptor> {
+        Arc::new(HttpIdentityInterceptor {
+            their_identifier,
+            attributes_provider: self.attributes_provider.clone(),
+            hmac_key: self.hmac_key.clone(),
+            attributes: OnceCell::new(),
+            state: Mutex::new(RequestState::default()),
+        })
+    }
+}
+
+/// Interceptor for the requests of one portal connection
+struct HttpIdentityInterceptor {
+    their_identifier: Option<LocalInfoIdentifier>,
+    attributes_provider: Option<Arc<dyn IdentityAttributesProvider>>,
+    hmac_key: Option<Arc<Vec<u8>>>,
+    // The attributes are retrieved once per connection
+    attributes: OnceCell<Vec<(String, String)>>,
+    state: Mutex<RequestState>,
+}
+
+impl HttpIdentityInterceptor {
+    /// Return the attributes which can be sent as headers, sorted by name
+    async fn attributes(&self) -> ockam_core::Result<&Vec<(String, String)>> {
+        self.attributes
+            .get_or_try_init(|| async {
+                let (Some(provider), Some(their_identifier)) =
+                    (&self.attributes_provider, &self.their_identifier)
+                else {
+                    return Ok(vec![]);
+                };
+                let mut attributes: Vec<(String, String)> = provider
+                    .get_attributes(their_identifier)
+                    .await?
+                    .into_iter()
+                    .filter(|(name, value)| {
+                        let is_valid = is_header_name(name) && is_header_value(value);
+                        if !is_valid {
+                            warn!("The attribute {name} can't be sent in an HTTP header");
+                        }
+                        is_valid
+                    })
+                    .collect();
+                attributes.sort();
+                Ok(attributes)
+            })
+            .await
+    }
+
+    /// Write the request header without the client `X-Ockam-*` headers,
+    /// followed by the identity headers
+    fn write_request_header(
+        &self,
+        req: &httparse::Request,
+        attributes: &[(String, String)],
+        timestamp: u64,
+        buffer: &mut Vec<u8>,
+    ) {
+        write_request_line(req, buffer);
+        for h in &*req.headers {
+            if !is_ockam_header(h.name) {
+                write_header(h.name, h.value, buffer);
+            }
+        }
+
+        let identifier = self.their_identifier.as_ref().map(|i| i.to_string());
+        if let Some(identifier) = &identifier {
+            write_header(OCKAM_IDENTIFIER_HEADER, identifier.as_bytes(), buffer);
+        }
+        for (name, value) in attributes {
+            write_header(
+                &format!("{OCKAM_ATTRIBUTE_HEADER_PREFIX}{name}"),
+                value.as_bytes(),
+                buffer,
+            );
+        }
+        if let Some(hmac_key) = &self.hmac_key {
+            let signature = sign_identity_headers(
+                hmac_key,
+                req.method.unwrap(),
+                req.path.unwrap(),
+                timestamp,
+                identifier.as_deref(),
+                attributes,
+            );
+            write_header(
+                OCKAM_TIMESTAMP_HEADER,
+                timestamp.to_string().as_bytes(),
+                buffer,
+            );
+            write_header(OCKAM_SIGNATURE_HEADER, signature.as_bytes(), buffer);
+        }
+        buffer.extend_from_slice(b"\r\n");
+    }
+
+    /// Rewrite the header of the requests received in this buffer.
+    /// The `X-Ockam-*` trailer fields of chunked requests are removed as well
+    async fn process_requests(
+        &self,
+        buffer: &[u8],
+        attributes: &[(String, String)],
+        timestamp: u64,
+    ) -> ockam_core::Result<Vec<u8>> {
+        self.state.lock().await.process_http_buffer(
+            buffer,
+            |req, buffer| self.write_request_header(req, attributes, timestamp, buffer),
+            |name| !is_ockam_header(name),
+        )
+    }
+}
+
+#[async_trait]
+impl PortalInterceptor for HttpIdentityInterceptor {
+    async fn intercept(
+        &self,
+        _context: &mut Context,
+        direction: Direction,
+        buffer: &[u8],
+    ) -> ockam_core::Result<Option<Vec<u8>>> {
+        match direction {
+            Direction::FromOutletToInlet => Ok(Some(buffer.to_vec())),
+            Direction::FromInletToOutlet => {
+                let attributes = self.attributes().await?;
+                let out = self.process_requests(buffer, attributes, now()?.0).await?;
+                Ok(Some(out))
+            }
+        }
+    }
+
+    fn error_response(&self, direction: Direction) -> Option<Vec<u8>> {
+        match direction {
+            Direction::FromInletToOutlet => Some(BAD_REQUEST_RESPONSE.to_vec()),
+            Direction::FromOutletToInlet => None,
+        }
+    }
+}
+
+/// Return the hex-encoded HMAC-SHA256 signature of the identity headers of a request.
+///
+/// The signed message is made of the following lines, separated by `\n`:
+///
+///  - the request method,
+///  - the request path,
+///  - the value of the [`OCKAM_TIMESTAMP_HEADER`] header,
+///  - the value of the [`OCKAM_IDENTIFIER_HEADER`] header, or an empty line if there is none,
+///  - one `<name>=<value>` line per attribute header, sorted by attribute name.
+pub fn sign_identity_headers(
+    hmac_key: &[u8],
+    method: &str,
+    path: &str,
+    timestamp: u64,
+    identifier: Option<&str>,
+    attributes: &[(String, String)],
+) -> String {
+    let mut message = format!(
+        "{method}\n{path}\n{timestamp}\n{}",
+        identifier.unwrap_or_default()
+    );
+    for (name, value) in attributes {
+        message.push_str(&format!("\n{name}={value}"));
+    }
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(hmac_key).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Return true if the header starts with `X-Ockam-`.
+/// Underscores are considered as dashes, since some servers don't make the difference
+fn is_ockam_header(name: &str) -> bool {
+    let prefix = OCKAM_HEADER_PREFIX.as_bytes();
+    name.len() >= prefix.len()
+        && name.as_bytes()[..prefix.len()]
+            .iter()
+            .zip(prefix)
+            .all(|(c, p)| c.eq_ignore_ascii_case(p) || (*c == b'_' && *p == b'-'))
+}
+
+/// Return true if the name only contains the characters allowed in a header name
+fn is_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&c))
+}
+
+/// Return true if the value can't be interpreted as another header
+fn is_header_value(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|c| c == b'\t' || (b' '..=b'~').contains(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::Identifier;
+    use std::fmt::{Debug, Formatter};
+    use std::str::FromStr;
+
+    const IDENTIFIER: &str = "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    struct TestAttributesProvider;
+
+    impl Debug for TestAttributesProvider {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            f.write_str("TestAttributesProvider")
+        }
+    }
+
+    #[async_trait]
+    impl IdentityAttributesProvider for TestAttributesProvider {
+        async fn get_attributes(
+            &self,
+            _their_identifier: &LocalInfoIdentifier,
+        ) -> ockam_core::Result<Vec<(String, String)>> {
+            Ok(vec![
+                ("team".into(), "payroll".into()),
+                ("role".into(), "admin".into()),
+                ("bad\r\nname".into(), "value".into()),
+                ("note".into(), "x\r\nX-Injected: true".into()),
+            ])
+        }
+    }
+
+    fn interceptor(hmac_key: Option<&str>) -> HttpIdentityInterceptor {
+        let identifier = Identifier::from_str(IDENTIFIER).unwrap();
+        HttpIdentityInterceptor {
+            their_identifier: Some(identifier.into()),
+            attributes_provider: Some(Arc::new(TestAttributesProvider)),
+            hmac_key: hmac_key.map(|k| Arc::new(k.as_bytes().to_vec())),
+            attributes: OnceCell::new(),
+            state: Mutex::new(RequestState::default()),
+        }
+    }
+
+    async fn rewrite(interceptor: &HttpIdentityInterceptor, request: &str) -> String {
+        let out = rewrite_chunks(interceptor, request, request.len()).await;
+        String::from_utf8(out.unwrap()).unwrap()
+    }
+
+    async fn rewrite_chunks(
+        interceptor: &HttpIdentityInterceptor,
+        data: &str,
+        size: usize,
+    ) -> ockam_core::Result<Vec<u8>> {
+        let attributes = interceptor.attributes().await?;
+        let mut result = vec![];
+        for chunk in data.as_bytes().chunks(size) {
+            result.extend(
+                interceptor
+                    .process_requests(chunk, attributes, 1700000000)
+                    .await?,
+            );
+        }
+        Ok(result)
+    }
+
+    #[tokio::test]
+    async fn client_headers_are_replaced_by_the_identity_headers() {
+        let request = "PATCH /users/1 HTTP/1.1\r\n\
+Host: app.internal\r\n\
+X-Ockam-Identifier: Ispoofed\r\n\
+x-ockam-attribute-role: admin\r\n\
+X_Ockam_Attribute_Team: payroll\r\n\
+Content-Length: 4\r\n\r\n\
+body";
+        let expected = format!(
+            "PATCH /users/1 HTTP/1.1\r\n\
+Host: app.internal\r\n\
+Content-Length: 4\r\n\
+X-Ockam-Identifier: {IDENTIFIER}\r\n\
+X-Ockam-Attribute-role: admin\r\n\
+X-Ockam-Attribute-team: payroll\r\n\r\n\
+body"
+        );
+
+        let interceptor = interceptor(None);
+        assert_eq!(rewrite(&interceptor, request).await, expected);
+        // The state is ready for the next request
+        assert_eq!(
+            rewrite(&interceptor, "GET / HTTP/1.1\r\n\r\n").await,
+            format!(
+                "GET / HTTP/1.1\r\n\
+X-Ockam-Identifier: {IDENTIFIER}\r\n\
+X-Ockam-Attribute-role: admin\r\n\
+X-Ockam-Attribute-team: payroll\r\n\r\n"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn identity_headers_can_be_signed() {
+        let interceptor = interceptor(Some("secret"));
+        let out = rewrite(&interceptor, "GET /reports?q=1 HTTP/1.1\r\n\r\n").await;
+
+        let attributes = vec![
+            ("role".to_string(), "admin".to_string()),
+            ("team".to_string(), "payroll".to_string()),
+        ];
+        let signature = sign_identity_headers(
+            b"secret",
+            "GET",
+            "/reports?q=1",
+            1700000000,
+            Some(IDENTIFIER),
+            &attributes,
+        );
+        assert_eq!(
+            out,
+            format!(
+                "GET /reports?q=1 HTTP/1.1\r\n\
+X-Ockam-Identifier: {IDENTIFIER}\r\n\
+X-Ockam-Attribute-role: admin\r\n\
+X-Ockam-Attribute-team: payroll\r\n\
+X-Ockam-Timestamp: 1700000000\r\n\
+X-Ockam-Signature: {signature}\r\n\r\n"
+            )
+        );
+
+        // The signature depends on the key and on the signed values
+        assert_ne!(
+            signature,
+            sign_identity_headers(
+                b"other secret",
+                "GET",
+                "/reports?q=1",
+                1700000000,
+                Some(IDENTIFIER),
+                &attributes,
+            )
+        );
+        assert_ne!(
+            signature,
+            sign_identity_headers(
+                b"secret",
+                "GET",
+                "/reports?q=2",
+                1700000000,
+                Some(IDENTIFIER),
+                &attributes,
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn forged_headers_are_removed_from_all_the_pipelined_requests() {
+        let request = "POST /a HTTP/1.1\r\n\
+Content-Length: 5\r\n\
+X-Ockam-Identifier: Ispoofed\r\n\r\n\
+hello\
+POST /b HTTP/1.1\r\n\
+Transfer-Encoding: chunked\r\n\
+x-ockam-attribute-role: admin\r\n\r\n\
+5;ext=1\r\nhello\r\n0\r\n\
+X-Ockam-Identifier: Ispoofed\r\n\
+Expires: never\r\n\r\n\
+GET /c HTTP/1.1\r\n\
+X_Ockam_Identifier: Ispoofed\r\n\r\n";
+        let identity_headers = format!(
+            "X-Ockam-Identifier: {IDENTIFIER}\r\n\
+X-Ockam-Attribute-role: admin\r\n\
+X-Ockam-Attribute-team: payroll\r\n\r\n"
+        );
+        let expected = format!(
+            "POST /a HTTP/1.1\r\n\
+Content-Length: 5\r\n\
+{identity_headers}\
+hello\
+POST /b HTTP/1.1\r\n\
+Transfer-Encoding: chunked\r\n\
+{identity_headers}\
+5;ext=1\r\nhello\r\n0\r\n\
+Expires: never\r\n\r\n\
+GET /c HTTP/1.1\r\n\
+{identity_headers}"
+        );
+
+        for size in [1, 5, 32, 1024] {
+            let interceptor = interceptor(None);
+            let out = rewrite_chunks(&interceptor, request, size).await.unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn smuggled_requests_close_the_connection() {
+        let interceptor = interceptor(None);
+        let request = "POST / HTTP/1.1\r\n\
+Content-Length: 4\r\n\
+Transfer-Encoding: chunked\r\n\r\n\
+0\r\n\r\n\
+GET /admin HTTP/1.1\r\n\
+X-Ockam-Identifier: Ispoofed\r\n\r\n";
+        assert!(rewrite_chunks(&interceptor, request, request.len())
+            .await
+            .is_err());
+        // All the following data is rejected
+        assert!(rewrite_chunks(&interceptor, "GET / HTTP/1.1\r\n\r\n", 32)
+            .await
+            .is_err());
+        assert_eq!(
+            interceptor.error_response(Direction::FromInletToOutlet),
+            Some(BAD_REQUEST_RESPONSE.to_vec())
+        );
+    }
+
+    #[test]
+    fn ockam_headers_are_detected() {
+        assert!(is_ockam_header("X-Ockam-Identifier"));
+        assert!(is_ockam_header("x-ockam-signature"));
+        assert!(is_ockam_header("X_OCKAM_ATTRIBUTE_ROLE"));
+        assert!(!is_ockam_header("X-Ockam"));
+        assert!(!is_ockam_header("X-Forwarded-For"));
+    }
+}
@@ -0,0 +1,7 @@
+//! Portal interceptors for HTTP/1.1 traffic
+
+mod identity_headers;
+mod request_parser;
+
+pub use identity_headers::*;
+pub(crate) use request_parser::*;
@@ -0,0 +1,466 @@
+use std::io::Write;
+
+use httparse::Header;
+use ockam::errcode::{Kind, Origin};
+use tracing::{debug, error};
+
+/// Maximum size of a request header, of a chunk size line, or of a chunked body trailer
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+
+/// Response sent to the client when a request can't be parsed. The connection is then closed
+pub(crate) const BAD_REQUEST_RESPONSE: &[u8] =
+    b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+/// State of the parsing of a stream of HTTP/1.1 requests
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RequestState {
+    ParsingHeader(Option<Vec<u8>>),
+    ParsingChunkSize(Option<Vec<u8>>),
+    RemainingInChunk(u64),
+    ParsingChunkEnd(Option<Vec<u8>>),
+    /// Trailer of a chunked body, with the size of the fields already received
+    ParsingTrailer(Option<Vec<u8>>, usize),
+    RemainingBody(u64),
+    /// A request was invalid, no more data can be parsed
+    Rejected,
+}
+
+impl Default for RequestState {
+    fn default() -> Self {
+        RequestState::ParsingHeader(None)
+    }
+}
+
+/// Write the request line of a request
+pub(crate) fn write_request_line(req: &httparse::Request, buffer: &mut Vec<u8>) {
+    debug!("Serializing http req header");
+    write!(
+        buffer,
+        "{} {} HTTP/1.{}\r\n",
+        req.method.unwrap(),
+        req.path.unwrap(),
+        req.version.unwrap()
+    )
+    .unwrap();
+}
+
+/// Write a header line
+pub(crate) fn write_header(name: &str, value: &[u8], buffer: &mut Vec<u8>) {
+    write!(buffer, "{}: ", name).unwrap();
+    buffer.extend_from_slice(value);
+    buffer.extend_from_slice(b"\r\n");
+}
+
+fn invalid(message: impl Into<String>) -> ockam_core::Error {
+    let message = message.into();
+    error!("Invalid HTTP request: {message}");
+    ockam_core::Error::new(Origin::Transport, Kind::Invalid, message)
+}
+
+fn trim_whitespaces(value: &[u8]) -> &[u8] {
+    let is_whitespace = |c: &u8| *c == b' ' || *c == b'\t';
+    let start = value.iter().position(|c| !is_whitespace(c));
+    let end = value.iter().rposition(|c| !is_whitespace(c));
+    match (start, end) {
+        (Some(start), Some(end)) => &value[start..=end],
+        _ => &[],
+    }
+}
+
+/// Return the state for the body of a request.
+///
+/// To prevent request smuggling, the framing of the body must be unambiguous: requests with
+/// both a Content-Length and a Transfer-Encoding, with several Content-Length or
+/// Transfer-Encoding headers, or with a Transfer-Encoding other than `chunked` are rejected
+fn body_state(method: &str, headers: &[Header]) -> ockam_core::Result<RequestState> {
+    let mut content_length = None;
+    let mut chunked = false;
+    for h in headers {
+        if h.name.eq_ignore_ascii_case("Content-Length") {
+            if content_length.is_some() {
+                return Err(invalid("Duplicate Content-Length"));
+            }
+            let value = trim_whitespaces(h.value);
+            if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+                return Err(invalid("Invalid Content-Length"));
+            }
+            let length = std::str::from_utf8(value)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .ok_or_else(|| invalid("Invalid Content-Length"))?;
+            content_length = Some(length);
+        } else if h.name.eq_ignore_ascii_case("Transfer-Encoding") {
+            if chunked {
+                return Err(invalid("Duplicate Transfer-Encoding"));
+            }
+            if !trim_whitespaces(h.value).eq_ignore_ascii_case(b"chunked") {
+                return Err(invalid("Unsupported Transfer-Encoding"));
+            }
+            chunked = true;
+        }
+    }
+    match (content_length, chunked) {
+        (Some(_), true) => Err(invalid("Both Content-Length and Transfer-Encoding")),
+        (None, true) => Ok(RequestState::ParsingChunkSize(None)),
+        (Some(0), false) => Ok(RequestState::ParsingHeader(None)),
+        (Some(length), false) => Ok(RequestState::RemainingBody(length)),
+        (None, false) => match method.to_uppercase().as_str() {
+            // Not content-length, no chunked encoding, fail.
+            "POST" | "PUT" => Err(invalid("No Content-Length nor chunked Transfer-Encoding")),
+            _ => Ok(RequestState::ParsingHeader(None)),
+        },
+    }
+}
+
+/// Read a line ending with `\r\n`, which might be split across several buffers.
+/// Return `None` if the line is not complete yet, the received data is then kept in `prev`
+fn read_line(
+    prev: &mut Option<Vec<u8>>,
+    cursor: &mut &[u8],
+) -> ockam_core::Result<Option<Vec<u8>>> {
+    let (data, complete) = match cursor.iter().position(|c| *c == b'\n') {
+        Some(position) => (&cursor[..=position], true),
+        None => (*cursor, false),
+    };
+    let mut line = prev.take().unwrap_or_default();
+    if line.len() + data.len() > MAX_HEADER_SIZE {
+        return Err(invalid("Line too long"));
+    }
+    line.extend_from_slice(data);
+    *cursor = &cursor[data.len()..];
+
+    if !complete {
+        *prev = Some(line);
+        return Ok(None);
+    }
+    if !line.ends_with(b"\r\n") {
+        return Err(invalid("Line not ending with CRLF"));
+    }
+    Ok(Some(line))
+}
+
+/// Parse a chunk size line: `chunk-size *( BWS ";" BWS chunk-ext-name [ BWS "=" BWS chunk-ext-val ] ) CRLF`
+fn parse_chunk_size(line: &[u8]) -> ockam_core::Result<u64> {
+    let line = &line[..line.len() - 2];
+    let (size, extensions) = match line.iter().position(|c| *c == b';') {
+        Some(position) => (trim_whitespaces(&line[..position]), &line[position..]),
+        None => (line, &line[line.len()..]),
+    };
+    // The extensions are forwarded as they are, they must not contain any control character
+    if !extensions
+        .iter()
+        .all(|c| *c == b'\t' || (b' '..=b'~').contains(c) || *c >= 0x80)
+    {
+        return Err(invalid("Invalid chunk extension"));
+    }
+    if size.is_empty() || size.len() > 16 || !size.iter().all(u8::is_ascii_hexdigit) {
+        return Err(invalid("Invalid chunk size"));
+    }
+    std::str::from_utf8(size)
+        .ok()
+        .and_then(|s| u64::from_str_radix(s, 16).ok())
+        .ok_or_else(|| invalid("Invalid chunk size"))
+}
+
+/// Check a trailer field line: `field-name ":" OWS field-value OWS CRLF`
+fn check_trailer_field(line: &[u8]) -> ockam_core::Result<&str> {
+    let line = &line[..line.len() - 2];
+    let colon = line
+        .iter()
+        .position(|c| *c == b':')
+        .ok_or_else(|| invalid("Invalid trailer field"))?;
+    let (name, value) = (&line[..colon], &line[colon + 1..]);
+    let is_token_char = |c: &u8| c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(c);
+    if name.is_empty()
+        || !name.iter().all(is_token_char)
+        || !value
+            .iter()
+            .all(|c| *c == b'\t' || (b' '..=b'~').contains(c) || *c >= 0x80)
+    {
+        return Err(invalid("Invalid trailer field"));
+    }
+    std::str::from_utf8(name).map_err(|_| invalid("Invalid trailer field"))
+}
+
+impl RequestState {
+    /* Parse the incoming data, rewriting the header of each request with `write_header`.
+     * data is received in chunks, and there is no warranty on what we get on each:
+     * incomplete requests,  multiple requests, etc.
+     * The fields of the trailer of chunked requests are only kept if `keep_trailer_field`
+     * returns true for their name.
+     * Once an invalid request is found, an error is returned for all the subsequent data.
+     */
+    pub(crate) fn process_http_buffer(
+        &mut self,
+        buf: &[u8],
+        write_header: impl FnMut(&httparse::Request, &mut Vec<u8>),
+        keep_trailer_field: impl Fn(&str) -> bool,
+    ) -> ockam_core::Result<Vec<u8>> {
+        let result = self.process(buf, write_header, keep_trailer_field);
+        if result.is_err() {
+            *self = RequestState::Rejected;
+        }
+        result
+    }
+
+    fn process(
+        &mut self,
+        buf: &[u8],
+        mut write_header: impl FnMut(&httparse::Request, &mut Vec<u8>),
+        keep_trailer_field: impl Fn(&str) -> bool,
+    ) -> ockam_core::Result<Vec<u8>> {
+        let mut acc = Vec::with_capacity(buf.len());
+        let mut cursor = buf;
+        loop {
+            if cursor.is_empty() {
+                return Ok(acc);
+            }
+            match self {
+                RequestState::ParsingHeader(prev) => {
+                    let (to_parse, prev_size): (&[u8], usize) = if let Some(b) = prev {
+                        let prev_size = b.len();
+                        b.extend_from_slice(cursor);
+                        (b, prev_size)
+                    } else {
+                        (cursor, 0usize)
+                    };
+                    let mut headers = [httparse::EMPTY_HEADER; 64];
+                    let mut req = httparse::Request::new(&mut headers);
+                    match req.parse(to_parse) {
+                        Ok(httparse::Status::Partial) => {
+                            if to_parse.len() > MAX_HEADER_SIZE {
+                                return Err(invalid("Request header too large"));
+                            }
+                            if prev_size == 0 {
+                                // No previous buffered, need to copy and own the unparsed data
+                                *self = RequestState::ParsingHeader(Some(cursor.to_vec()));
+                            }
+                            // Otherwise, we already added the newly data to the previous buffer
+                            return Ok(acc);
+                        }
+                        Ok(httparse::Status::Complete(body_offset)) => {
+                            if body_offset > MAX_HEADER_SIZE {
+                                return Err(invalid("Request header too large"));
+                            }
+                            cursor = &cursor[body_offset - prev_size..];
+                            let state = body_state(req.method.unwrap(), req.headers)?;
+                            write_header(&req, &mut acc);
+                            *self = state;
+                        }
+                        Err(e) => return Err(invalid(format!("Can't parse header: {e}"))),
+                    }
+                }
+                RequestState::RemainingBody(remaining) => {
+                    if *remaining <= cursor.len() as u64 {
+                        let (body, rest) = cursor.split_at(*remaining as usize);
+                        acc.extend_from_slice(body);
+                        cursor = rest;
+                        *self = RequestState::ParsingHeader(None);
+                    } else {
+                        acc.extend_from_slice(cursor);
+                        *remaining -= cursor.len() as u64;
+                        return Ok(acc);
+                    }
+                }
+                RequestState::ParsingChunkSize(prev) => {
+                    let Some(line) = read_line(prev, &mut cursor)? else {
+                        return Ok(acc);
+                    };
+                    let chunk_size = parse_chunk_size(&line)?;
+                    acc.extend_from_slice(&line);
+                    *self = if chunk_size == 0 {
+                        RequestState::ParsingTrailer(None, 0)
+                    } else {
+                        RequestState::RemainingInChunk(chunk_size)
+                    };
+                }
+                RequestState::RemainingInChunk(size) => {
+                    if *size <= cursor.len() as u64 {
+                        let (data, rest) = cursor.split_at(*size as usize);
+                        acc.extend_from_slice(data);
+                        cursor = rest;
+                        *self = RequestState::ParsingChunkEnd(None);
+                    } else {
+                        acc.extend_from_slice(cursor);
+                        *size -= cursor.len() as u64;
+                        return Ok(acc);
+                    }
+                }
+                RequestState::ParsingChunkEnd(prev) => {
+                    let Some(line) = read_line(prev, &mut cursor)? else {
+                        return Ok(acc);
+                    };
+                    // chunks end in \r\n
+                    if line != b"\r\n" {
+                        return Err(invalid("Chunk data not ending with CRLF"));
+                    }
+                    acc.extend_from_slice(&line);
+                    *self = RequestState::ParsingChunkSize(None);
+                }
+                RequestState::ParsingTrailer(prev, size) => {
+                    let Some(line) = read_line(prev, &mut cursor)? else {
+                        return Ok(acc);
+                    };
+                    if line == b"\r\n" {
+                        // end of the chunked body
+                        acc.extend_from_slice(&line);
+                        *self = RequestState::ParsingHeader(None);
+                    } else {
+                        *size += line.len();
+                        if *size > MAX_HEADER_SIZE {
+                            return Err(invalid("Trailer too large"));
+                        }
+                        let name = check_trailer_field(&line)?;
+                        if keep_trailer_field(name) {
+                            acc.extend_from_slice(&line);
+                        }
+                    }
+                }
+                RequestState::Rejected => return Err(invalid("The connection was rejected")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &[u8], size: usize) -> ockam_core::Result<Vec<u8>> {
+        let mut state = RequestState::default();
+        let mut result = vec![];
+        for chunk in data.chunks(size) {
+            result.extend(state.process_http_buffer(
+                chunk,
+                |req, buffer| {
+                    write_request_line(req, buffer);
+                    for h in &*req.headers {
+                        write_header(h.name, h.value, buffer);
+                    }
+                    buffer.extend_from_slice(b"\r\n");
+                },
+                |name| !name.eq_ignore_ascii_case("X-Removed"),
+            )?);
+        }
+        Ok(result)
+    }
+
+    fn assert_rejected(request: &str) {
+        for size in [1, 7, 1024] {
+            assert!(
+                parse(request.as_bytes(), size).is_err(),
+                "the request should be rejected: {request:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn chunk_extensions_and_trailers_are_parsed() {
+        let request = "POST / HTTP/1.1\r\n\
+Transfer-Encoding: chunked\r\n\r\n\
+4 ;name=value;other=\"quoted; value\"\r\nWiki\r\n\
+00000007\r\npedia i\r\n\
+0;last\r\n\
+X-Removed: yes\r\n\
+Expires: never\r\n\r\n\
+GET / HTTP/1.1\r\n\r\n";
+        let expected = request.replace("X-Removed: yes\r\n", "");
+
+        for size in [1, 5, 32, 1024] {
+            let result = parse(request.as_bytes(), size).unwrap();
+            assert_eq!(String::from_utf8(result).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn requests_with_both_content_length_and_transfer_encoding_are_rejected() {
+        // CL.TE
+        assert_rejected(
+            "POST / HTTP/1.1\r\n\
+Content-Length: 13\r\n\
+Transfer-Encoding: chunked\r\n\r\n\
+0\r\n\r\n\
+SMUGGLED",
+        );
+        // TE.CL
+        assert_rejected(
+            "POST / HTTP/1.1\r\n\
+Transfer-Encoding: chunked\r\n\
+Content-Length: 3\r\n\r\n\
+8\r\n\
+SMUGGLED\r\n\
+0\r\n\r\n",
+        );
+    }
+
+    #[test]
+    fn requests_with_duplicate_content_length_are_rejected() {
+        assert_rejected(
+            "POST / HTTP/1.1\r\n\
+Content-Length: 5\r\n\
+Content-Length: 5\r\n\r\n\
+hello",
+        );
+        assert_rejected(
+            "POST / HTTP/1.1\r\n\
+Content-Length: 5\r\n\
+content-length: 30\r\n\r\n\
+hello",
+        );
+        assert_rejected("POST / HTTP/1.1\r\nContent-Length: 5, 5\r\n\r\nhello");
+        assert_rejected("POST / HTTP/1.1\r\nContent-Length: +5\r\n\r\nhello");
+        assert_rejected("POST / HTTP/1.1\r\nContent-Length: 99999999999999999999999\r\n\r\n");
+    }
+
+    #[test]
+    fn obfuscated_transfer_encodings_are_rejected() {
+        for transfer_encoding in [
+            "Transfer-Encoding: gzip, chunked",
+            "Transfer-Encoding: chunked, identity",
+            "Transfer-Encoding: xchunked",
+            "Transfer-Encoding: chunked\r\nTransfer-Encoding: chunked",
+            "Transfer-Encoding: identity\r\nTransfer-Encoding: chunked",
+            "Transfer-Encoding : chunked",
+            "Transfer-Encoding:\r\n chunked",
+        ] {
+            assert_rejected(&format!(
+                "POST / HTTP/1.1\r\n{transfer_encoding}\r\n\r\n0\r\n\r\n"
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_chunks_are_rejected() {
+        let request =
+            |body: &str| format!("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n{body}");
+        // chunk sizes overflowing
+        assert_rejected(&request("FFFFFFFFFFFFFFFFF\r\n"));
+        // invalid chunk sizes
+        assert_rejected(&request("0x5\r\nhello\r\n0\r\n\r\n"));
+        assert_rejected(&request("-5\r\nhello\r\n0\r\n\r\n"));
+        assert_rejected(&request("\r\n"));
+        // bare LF line endings
+        assert_rejected(&request("5\nhello\r\n0\r\n\r\n"));
+        assert_rejected(&request("0\r\n\n"));
+        // chunk data longer than the chunk size
+        assert_rejected(&request("3\r\nhello\r\n0\r\n\r\n"));
+        // control characters in extensions or trailers
+        assert_rejected(&request("5;a=\x01\r\nhello\r\n0\r\n\r\n"));
+        assert_rejected(&request("0\r\nInvalid\r\n\r\n"));
+        assert_rejected(&request("0\r\nBad Name: value\r\n\r\n"));
+    }
+
+    #[test]
+    fn oversized_headers_are_rejected() {
+        let request = format!(
+            "GET / HTTP/1.1\r\nX-Large: {}\r\n\r\n",
+            "a".repeat(MAX_HEADER_SIZE)
+        );
+        assert_rejected(&request);
+        let request = format!(
+            "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5;{}\r\n",
+            "a".repeat(MAX_HEADER_SIZE)
+        );
+        assert_rejected(&request);
+    }
+}
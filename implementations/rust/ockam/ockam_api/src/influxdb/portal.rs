@@ -37,6 +37,9 @@ impl NodeManagerWorker {
             policy_expression,
             privileged,
             tls,
+            allowed_destinations: _,
+            proxy_protocol_attributes: _,
+            http_identity_headers: _,
         } = body.tcp_outlet;
         let address = self
             .node_manager
@@ -120,6 +123,7 @@ impl NodeManagerWorker {
             disable_tcp_fallback,
             privileged,
             tls_certificate_provider,
+            socks5: _,
         } = body.tcp_inlet.clone();
 
         //TODO: should be an easier way to tweak the multiaddr
@@ -217,7 +221,7 @@ impl NodeManagerWorker {
             .policy_access_control(
                 self.node_manager.project_authority().clone(),
                 Resource::new(outlet_address.to_string(), ResourceType::TcpOutlet),
-                Action::HandleMessage,
+                Action::Connect,
                 outlet_policy_expression.clone(),
             )
             .await?;
@@ -376,6 +380,7 @@ impl InfluxDBPortals for BackgroundNodeClient {
                 disable_tcp_fallback,
                 false,
                 tls_certificate_provider,
+                false,
             );
             let payload = CreateInfluxDBInlet::new(inlet_payload, lease_usage, lease_issuer_route);
             Request::post("/node/influxdb_inlet").body(payload)
@@ -5,8 +5,11 @@ use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 
 pub(crate) mod controller;
+mod record_key;
 mod secure_channels;
 
+pub(crate) use record_key::RecordKeyCipher;
+
 /// Describe how to reach the consumer node: either directly or through a relay
 #[derive(Debug, Clone, Encode, Decode, CborLen)]
 #[rustfmt::skip]
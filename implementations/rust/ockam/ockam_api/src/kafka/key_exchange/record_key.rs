@@ -0,0 +1,130 @@
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf::{Salt, HKDF_SHA256};
+use ring::hmac;
+
+const RECORD_KEY_SALT: &[u8] = b"ockam kafka record keys";
+
+/// Deterministic encryption of Kafka record keys.
+///
+/// Record keys are used by the brokers to select a partition and to compact a topic, so the same
+/// key must always be encrypted to the same content, by any producer and across restarts.
+/// Both an AES-256-GCM key and an HMAC-SHA256 key are derived from a secret shared by all the
+/// producers and consumers of a topic. The nonce is the HMAC of the record key, so that it is
+/// the same for the same record key, and different for different record keys.
+///
+/// The encrypted content is the nonce followed by the ciphertext and its tag. It only reveals
+/// which records have the same key.
+pub(crate) struct RecordKeyCipher {
+    encryption_key: LessSafeKey,
+    nonce_key: hmac::Key,
+}
+
+impl RecordKeyCipher {
+    /// Derive the encryption keys from a shared secret
+    pub(crate) fn new(secret: &[u8]) -> Result<Self> {
+        let invalid = || {
+            Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                "cannot derive the record key encryption keys",
+            )
+        };
+        if secret.is_empty() {
+            return Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                "the record key secret can't be empty",
+            ));
+        }
+
+        let prk = Salt::new(HKDF_SHA256, RECORD_KEY_SALT).extract(secret);
+        let encryption_key: UnboundKey = prk
+            .expand(&[b"encryption"], &AES_256_GCM)
+            .map_err(|_| invalid())?
+            .into();
+        let nonce_key: hmac::Key = prk
+            .expand(&[b"nonce"], hmac::HMAC_SHA256)
+            .map_err(|_| invalid())?
+            .into();
+
+        Ok(Self {
+            encryption_key: LessSafeKey::new(encryption_key),
+            nonce_key,
+        })
+    }
+
+    /// Encrypt a record key, the same record key is always encrypted to the same content
+    pub(crate) fn encrypt(&self, record_key: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&hmac::sign(&self.nonce_key, record_key).as_ref()[..NONCE_LEN]);
+
+        let mut ciphertext = record_key.to_vec();
+        self.encryption_key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut ciphertext,
+            )
+            .map_err(|_| Error::new(Origin::Api, Kind::Internal, "cannot encrypt a record key"))?;
+
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend_from_slice(&ciphertext);
+        Ok(encrypted)
+    }
+
+    /// Decrypt a record key encrypted with the same secret
+    pub(crate) fn decrypt(&self, encrypted: &[u8]) -> Result<Vec<u8>> {
+        let invalid = || Error::new(Origin::Api, Kind::Invalid, "cannot decrypt a record key");
+        if encrypted.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+
+        let (nonce, ciphertext) = encrypted.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid())?;
+        let mut ciphertext = ciphertext.to_vec();
+        let record_key = self
+            .encryption_key
+            .open_in_place(nonce, Aad::empty(), &mut ciphertext)
+            .map_err(|_| invalid())?;
+        Ok(record_key.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_same_key_is_encrypted_to_the_same_content() -> Result<()> {
+        let cipher = RecordKeyCipher::new(b"shared secret")?;
+        let encrypted = cipher.encrypt(b"user-42")?;
+
+        // another producer, or the same one after a restart
+        let other_cipher = RecordKeyCipher::new(b"shared secret")?;
+        assert_eq!(other_cipher.encrypt(b"user-42")?, encrypted);
+        assert_ne!(cipher.encrypt(b"user-43")?, encrypted);
+        assert_ne!(encrypted[NONCE_LEN..], b"user-42"[..]);
+
+        assert_eq!(other_cipher.decrypt(&encrypted)?, b"user-42");
+        Ok(())
+    }
+
+    #[test]
+    fn a_record_key_can_only_be_decrypted_with_the_same_secret() -> Result<()> {
+        let cipher = RecordKeyCipher::new(b"shared secret")?;
+        let mut encrypted = cipher.encrypt(b"user-42")?;
+
+        let other_cipher = RecordKeyCipher::new(b"another secret")?;
+        assert!(other_cipher.decrypt(&encrypted).is_err());
+        assert_ne!(other_cipher.encrypt(b"user-42")?, encrypted);
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+        assert!(cipher.decrypt(&encrypted).is_err());
+        assert!(cipher.decrypt(&encrypted[..NONCE_LEN - 1]).is_err());
+        assert!(RecordKeyCipher::new(b"").is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::kafka::key_exchange::controller::KafkaKeyExchangeControllerImpl;
-use crate::kafka::key_exchange::KafkaKeyExchangeController;
+use crate::kafka::key_exchange::{KafkaKeyExchangeController, RecordKeyCipher};
 use crate::kafka::protocol_aware::{
     CorrelationId, KafkaMessageInterceptor, KafkaMessageInterceptorWrapper, RequestInfo,
     TopicUuidMap, MAX_KAFKA_MESSAGE_SIZE,
@@ -24,6 +24,8 @@ pub(crate) struct InletInterceptorImpl {
     inlet_map: KafkaInletController,
     encrypt_content: bool,
     encrypted_fields: Vec<String>,
+    encrypt_headers: bool,
+    record_key_cipher: Option<Arc<RecordKeyCipher>>,
 }
 
 #[async_trait]
@@ -36,6 +38,8 @@ impl InletInterceptorImpl {
         inlet_map: KafkaInletController,
         encrypt_content: bool,
         encrypted_fields: Vec<String>,
+        encrypt_headers: bool,
+        record_key_cipher: Option<Arc<RecordKeyCipher>>,
     ) -> InletInterceptorImpl {
         Self {
             request_map: Arc::new(Mutex::new(Default::default())),
@@ -44,6 +48,8 @@ impl InletInterceptorImpl {
             inlet_map,
             encrypt_content,
             encrypted_fields,
+            encrypt_headers,
+            record_key_cipher,
         }
     }
 
@@ -70,6 +76,8 @@ pub(crate) struct KafkaInletInterceptorFactory {
     inlet_map: KafkaInletController,
     encrypt_content: bool,
     encrypted_fields: Vec<String>,
+    encrypt_headers: bool,
+    record_key_cipher: Option<Arc<RecordKeyCipher>>,
 }
 
 impl KafkaInletInterceptorFactory {
@@ -78,6 +86,8 @@ impl KafkaInletInterceptorFactory {
         inlet_map: KafkaInletController,
         encrypt_content: bool,
         encrypted_fields: Vec<String>,
+        encrypt_headers: bool,
+        record_key_cipher: Option<Arc<RecordKeyCipher>>,
     ) -> Self {
         Self {
             secure_channel_controller,
@@ -85,6 +95,8 @@ impl KafkaInletInterceptorFactory {
             inlet_map,
             encrypt_content,
             encrypted_fields,
+            encrypt_headers,
+            record_key_cipher,
         }
     }
 }
@@ -98,6 +110,8 @@ impl PortalInterceptorFactory for KafkaInletInterceptorFactory {
                 self.inlet_map.clone(),
                 self.encrypt_content,
                 self.encrypted_fields.clone(),
+                self.encrypt_headers,
+                self.record_key_cipher.clone(),
             )),
             MAX_KAFKA_MESSAGE_SIZE,
         ))
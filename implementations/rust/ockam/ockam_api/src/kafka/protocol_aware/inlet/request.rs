@@ -1,5 +1,7 @@
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
-use crate::kafka::protocol_aware::utils::{decode_body, encode_request};
+use crate::kafka::protocol_aware::utils::{
+    decode_body, decode_record_batches, encode_record_batches, encode_request,
+};
 use crate::kafka::protocol_aware::RequestInfo;
 use crate::kafka::protocol_aware::{InterceptError, KafkaMessageRequestInterceptor};
 use bytes::{Bytes, BytesMut};
@@ -9,9 +11,6 @@ use kafka_protocol::messages::request_header::RequestHeader;
 use kafka_protocol::messages::{ApiKey, ApiVersionsRequest, TopicName};
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::{Decodable, Message};
-use kafka_protocol::records::{
-    Compression, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
-};
 use minicbor::encode::Encoder;
 use ockam_core::async_trait;
 use ockam_node::Context;
@@ -209,14 +208,12 @@ impl InletInterceptorImpl {
         for topic in request.topic_data.iter_mut() {
             for data in &mut topic.partition_data {
                 if let Some(content) = data.records.take() {
-                    let mut content = BytesMut::from(content.as_ref());
-                    let mut records = RecordBatchDecoder::decode(
-                        &mut content,
-                        None::<fn(&mut Bytes, Compression) -> Result<BytesMut, _>>,
-                    )
-                    .map_err(|_| InterceptError::InvalidData)?;
-
-                    for record in records.iter_mut() {
+                    let mut batches = decode_record_batches(content)?;
+
+                    for record in batches
+                        .iter_mut()
+                        .flat_map(|batch| batch.records.iter_mut())
+                    {
                         if let Some(record_value) = record.value.take() {
                             let buffer = if !self.encrypted_fields.is_empty() {
                                 // if we encrypt only specific fields, we assume the record must be
@@ -234,21 +231,33 @@ impl InletInterceptorImpl {
                             };
                             record.value = Some(buffer.into());
                         }
+
+                        // record keys are encrypted deterministically, so that the brokers
+                        // can still partition and compact the records by key
+                        if let Some(record_key_cipher) = &self.record_key_cipher {
+                            if let Some(record_key) = record.key.take() {
+                                let buffer = record_key_cipher
+                                    .encrypt(&record_key)
+                                    .map_err(InterceptError::Ockam)?;
+                                record.key = Some(buffer.into());
+                            }
+                        }
+
+                        // header names are kept in clear text, only their values are encrypted
+                        if self.encrypt_headers {
+                            for header_value in record.headers.values_mut() {
+                                if let Some(value) = header_value.take() {
+                                    let buffer = self
+                                        .encrypt_whole_record(context, &topic.name, data, value)
+                                        .await?;
+                                    *header_value = Some(buffer.into());
+                                }
+                            }
+                        }
                     }
 
-                    let mut encoded = BytesMut::new();
-                    RecordBatchEncoder::encode(
-                        &mut encoded,
-                        records.iter(),
-                        &RecordEncodeOptions {
-                            version: 2,
-                            compression: Compression::None,
-                        },
-                        None::<fn(&mut BytesMut, &mut BytesMut, Compression) -> Result<(), _>>,
-                    )
-                    .map_err(|_| InterceptError::InvalidData)?;
-
-                    data.records = Some(encoded.freeze());
+                    // the records are compressed again with the codec chosen by the producer
+                    data.records = Some(encode_record_batches(&batches)?);
                 }
             }
         }
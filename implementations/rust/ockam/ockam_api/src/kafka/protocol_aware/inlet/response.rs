@@ -1,5 +1,7 @@
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
-use crate::kafka::protocol_aware::utils::{decode_body, encode_response};
+use crate::kafka::protocol_aware::utils::{
+    decode_body, decode_record_batches, encode_record_batches, encode_response,
+};
 use crate::kafka::protocol_aware::{
     InterceptError, KafkaEncryptedContent, KafkaMessageResponseInterceptor, RequestInfo,
 };
@@ -11,9 +13,6 @@ use kafka_protocol::messages::{
 };
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::{Decodable, Message, StrBytes};
-use kafka_protocol::records::{
-    Compression, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
-};
 use minicbor::Decoder;
 use ockam_core::async_trait;
 use ockam_node::Context;
@@ -291,14 +290,12 @@ impl InletInterceptorImpl {
         for response in response.responses.iter_mut() {
             for partition in response.partitions.iter_mut() {
                 if let Some(content) = partition.records.take() {
-                    let mut content = BytesMut::from(content.as_ref());
-                    let mut records = RecordBatchDecoder::decode(
-                        &mut content,
-                        None::<fn(&mut Bytes, Compression) -> Result<BytesMut, _>>,
-                    )
-                    .map_err(|_| InterceptError::InvalidData)?;
-
-                    for record in records.iter_mut() {
+                    let mut batches = decode_record_batches(content)?;
+
+                    for record in batches
+                        .iter_mut()
+                        .flat_map(|batch| batch.records.iter_mut())
+                    {
                         if let Some(record_value) = record.value.take() {
                             let decrypted_content = if self.encrypted_fields.is_empty() {
                                 self.decrypt_whole_record(context, record_value).await?
@@ -307,20 +304,29 @@ impl InletInterceptorImpl {
                             };
                             record.value = Some(decrypted_content.into());
                         }
+
+                        if let Some(record_key_cipher) = &self.record_key_cipher {
+                            if let Some(record_key) = record.key.take() {
+                                let decrypted_key = record_key_cipher
+                                    .decrypt(&record_key)
+                                    .map_err(InterceptError::Ockam)?;
+                                record.key = Some(decrypted_key.into());
+                            }
+                        }
+
+                        if self.encrypt_headers {
+                            for header_value in record.headers.values_mut() {
+                                if let Some(value) = header_value.take() {
+                                    let decrypted_value =
+                                        self.decrypt_whole_record(context, value).await?;
+                                    *header_value = Some(decrypted_value.into());
+                                }
+                            }
+                        }
                     }
 
-                    let mut encoded = BytesMut::new();
-                    RecordBatchEncoder::encode(
-                        &mut encoded,
-                        records.iter(),
-                        &RecordEncodeOptions {
-                            version: 2,
-                            compression: Compression::None,
-                        },
-                        None::<fn(&mut BytesMut, &mut BytesMut, Compression) -> Result<(), _>>,
-                    )
-                    .map_err(|_| InterceptError::InvalidData)?;
-                    partition.records = Some(encoded.freeze());
+                    // the records are compressed again with the codec chosen by the producer
+                    partition.records = Some(encode_record_batches(&batches)?);
                 }
             }
         }
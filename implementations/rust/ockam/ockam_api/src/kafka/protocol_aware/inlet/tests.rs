@@ -1,10 +1,11 @@
-use crate::kafka::key_exchange::KafkaKeyExchangeController;
+use crate::kafka::key_exchange::{KafkaKeyExchangeController, RecordKeyCipher};
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
 use crate::kafka::protocol_aware::{
     utils, KafkaEncryptedContent, KafkaMessageRequestInterceptor, KafkaMessageResponseInterceptor,
 };
 use crate::kafka::KafkaInletController;
 use bytes::{Bytes, BytesMut};
+use indexmap::IndexMap;
 use kafka_protocol::messages::fetch_response::{FetchableTopicResponse, PartitionData};
 use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
 use kafka_protocol::messages::{
@@ -63,7 +64,7 @@ impl KafkaKeyExchangeController for MockKafkaKeyExchangeController {
 
 const TEST_KAFKA_API_VERSION: i16 = 13;
 
-pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
+pub fn create_kafka_produce_request(content: &[u8], compression: Compression) -> BytesMut {
     let header = RequestHeader::default()
         .with_request_api_key(ApiKey::ProduceKey as i16)
         .with_request_api_version(TEST_KAFKA_API_VERSION)
@@ -90,7 +91,7 @@ pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
         .iter(),
         &RecordEncodeOptions {
             version: 2,
-            compression: Compression::None,
+            compression,
         },
         None::<fn(&mut BytesMut, &mut BytesMut, Compression) -> Result<(), _>>,
     )
@@ -112,7 +113,7 @@ pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
     .unwrap()
 }
 
-pub fn create_kafka_fetch_response(content: &[u8]) -> BytesMut {
+pub fn create_kafka_fetch_response(content: &[u8], compression: Compression) -> BytesMut {
     let header = ResponseHeader::default().with_correlation_id(1);
 
     let mut encoded = BytesMut::new();
@@ -135,7 +136,7 @@ pub fn create_kafka_fetch_response(content: &[u8]) -> BytesMut {
         .iter(),
         &RecordEncodeOptions {
             version: 2,
-            compression: Compression::None,
+            compression,
         },
         None::<fn(&mut BytesMut, &mut BytesMut, Compression) -> Result<(), _>>,
     )
@@ -216,6 +217,8 @@ pub async fn json_encrypt_specific_fields(context: &mut Context) -> ockam::Resul
             "field2".to_string(),
             "field3".to_string(),
         ],
+        false,
+        None,
     );
 
     let encrypted_response = interceptor
@@ -238,6 +241,7 @@ pub async fn json_encrypt_specific_fields(context: &mut Context) -> ockam::Resul
                 )
                 .to_string()
                 .as_bytes(),
+                Compression::None,
             ),
         )
         .await
@@ -297,6 +301,8 @@ pub async fn json_decrypt_specific_fields(context: &mut Context) -> ockam::Resul
             "field2".to_string(),
             "field3".to_string(),
         ],
+        false,
+        None,
     );
 
     interceptor.add_request(1, ApiKey::FetchKey, TEST_KAFKA_API_VERSION);
@@ -322,6 +328,7 @@ pub async fn json_decrypt_specific_fields(context: &mut Context) -> ockam::Resul
                 )
                 .to_string()
                 .as_bytes(),
+                Compression::None,
             ),
         )
         .await
@@ -364,3 +371,200 @@ pub async fn json_decrypt_specific_fields(context: &mut Context) -> ockam::Resul
 
     Ok(())
 }
+
+#[ockam::test]
+pub async fn compressed_records_round_trip(context: &mut Context) -> ockam::Result<()> {
+    let interceptor = InletInterceptorImpl::new(
+        Arc::new(MockKafkaKeyExchangeController {}),
+        Default::default(),
+        KafkaInletController::stub(),
+        true,
+        vec![],
+        false,
+        None,
+    );
+
+    for compression in [
+        Compression::Gzip,
+        Compression::Snappy,
+        Compression::Lz4,
+        Compression::Zstd,
+    ] {
+        let encrypted_request = interceptor
+            .intercept_request(context, create_kafka_produce_request(b"hello", compression))
+            .await
+            .unwrap();
+
+        let request = parse_produce_request(&encrypted_request);
+        let records = request.topic_data[0].partition_data[0]
+            .records
+            .clone()
+            .unwrap();
+
+        // the producer compression is kept
+        let batches = utils::decode_record_batches(records).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].compression, compression);
+
+        let encrypted_value = batches[0].records[0].value.clone().unwrap();
+        let encrypted_content: KafkaEncryptedContent =
+            Decoder::new(encrypted_value.as_ref()).decode().unwrap();
+        assert_eq!(encrypted_content.content, b"encrypted:hello");
+
+        interceptor.add_request(1, ApiKey::FetchKey, TEST_KAFKA_API_VERSION);
+        let cleartext_response = interceptor
+            .intercept_response(
+                context,
+                create_kafka_fetch_response(&encrypted_value, compression),
+            )
+            .await
+            .unwrap();
+
+        let response = parse_fetch_response(&cleartext_response);
+        let records = response.responses[0].partitions[0].records.clone().unwrap();
+
+        let batches = utils::decode_record_batches(records).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].compression, compression);
+        assert_eq!(
+            batches[0].records[0].value.as_deref(),
+            Some(b"hello".as_slice())
+        );
+    }
+
+    Ok(())
+}
+
+#[ockam::test]
+pub async fn encrypt_and_decrypt_keys_and_headers(context: &mut Context) -> ockam::Result<()> {
+    let interceptor = InletInterceptorImpl::new(
+        Arc::new(MockKafkaKeyExchangeController {}),
+        Default::default(),
+        KafkaInletController::stub(),
+        true,
+        vec![],
+        true,
+        Some(Arc::new(RecordKeyCipher::new(b"record key secret")?)),
+    );
+
+    let mut headers = IndexMap::new();
+    headers.insert(
+        StrBytes::from_static_str("traceparent"),
+        Some(Bytes::from_static(b"00-trace-id")),
+    );
+
+    let mut encoded = BytesMut::new();
+    RecordBatchEncoder::encode(
+        &mut encoded,
+        [Record {
+            transactional: false,
+            control: false,
+            partition_leader_epoch: 0,
+            producer_id: 0,
+            producer_epoch: 0,
+            timestamp_type: TimestampType::Creation,
+            offset: 0,
+            sequence: 0,
+            timestamp: 0,
+            key: Some(Bytes::from_static(b"user-42")),
+            value: Some(Bytes::from_static(b"hello")),
+            headers,
+        }]
+        .iter(),
+        &RecordEncodeOptions {
+            version: 2,
+            compression: Compression::None,
+        },
+        None::<fn(&mut BytesMut, &mut BytesMut, Compression) -> Result<(), _>>,
+    )
+    .unwrap();
+
+    let header = RequestHeader::default()
+        .with_request_api_key(ApiKey::ProduceKey as i16)
+        .with_request_api_version(TEST_KAFKA_API_VERSION)
+        .with_correlation_id(1)
+        .with_client_id(Some(StrBytes::from_static_str("my-client-id")));
+    let request = ProduceRequest::default().with_topic_data(vec![TopicProduceData::default()
+        .with_name(TopicName::from(StrBytes::from_static_str("topic-name")))
+        .with_partition_data(vec![PartitionProduceData::default()
+            .with_index(1)
+            .with_records(Some(encoded.freeze()))])]);
+    let request = utils::encode_request(
+        &header,
+        &request,
+        TEST_KAFKA_API_VERSION,
+        ApiKey::ProduceKey,
+    )
+    .unwrap();
+
+    let encrypted_request = interceptor
+        .intercept_request(context, request)
+        .await
+        .unwrap();
+
+    let request = parse_produce_request(&encrypted_request);
+    let encrypted_records = request.topic_data[0].partition_data[0]
+        .records
+        .clone()
+        .unwrap();
+
+    let batches = utils::decode_record_batches(encrypted_records.clone()).unwrap();
+    let record = &batches[0].records[0];
+
+    // the record key is encrypted deterministically, as any other inlet using the same secret would
+    let encrypted_key = record.key.as_deref().unwrap();
+    assert_ne!(encrypted_key, b"user-42");
+    assert_eq!(
+        RecordKeyCipher::new(b"record key secret")?.encrypt(b"user-42")?,
+        encrypted_key
+    );
+
+    // header names are kept, their values are encrypted
+    let traceparent = record
+        .headers
+        .get(&StrBytes::from_static_str("traceparent"))
+        .unwrap()
+        .as_ref()
+        .unwrap();
+    let encrypted_header: KafkaEncryptedContent = Decoder::new(traceparent).decode().unwrap();
+    assert_eq!(encrypted_header.content, b"encrypted:00-trace-id");
+
+    interceptor.add_request(1, ApiKey::FetchKey, TEST_KAFKA_API_VERSION);
+    let response = FetchResponse::default().with_responses(vec![FetchableTopicResponse::default()
+        .with_topic(TopicName::from(StrBytes::from_static_str("topic-name")))
+        .with_topic_id(Default::default())
+        .with_partitions(vec![PartitionData::default()
+            .with_partition_index(1)
+            .with_records(Some(encrypted_records))])]);
+    let response = utils::encode_response(
+        &ResponseHeader::default().with_correlation_id(1),
+        &response,
+        TEST_KAFKA_API_VERSION,
+        ApiKey::FetchKey,
+    )
+    .unwrap();
+
+    let cleartext_response = interceptor
+        .intercept_response(context, response)
+        .await
+        .unwrap();
+
+    let response = parse_fetch_response(&cleartext_response);
+    let records = response.responses[0].partitions[0].records.clone().unwrap();
+    let batches = utils::decode_record_batches(records).unwrap();
+    let record = &batches[0].records[0];
+
+    assert_eq!(record.key.as_deref(), Some(b"user-42".as_slice()));
+    assert_eq!(record.value.as_deref(), Some(b"hello".as_slice()));
+    assert_eq!(
+        record
+            .headers
+            .get(&StrBytes::from_static_str("traceparent"))
+            .cloned()
+            .flatten()
+            .as_deref(),
+        Some(b"00-trace-id".as_slice())
+    );
+
+    Ok(())
+}
@@ -73,6 +73,8 @@ mod test {
             inlet_map,
             true,
             vec![],
+            false,
+            None,
         );
 
         let mut correlation_id = 0;
@@ -1,8 +1,11 @@
 use crate::kafka::protocol_aware::InterceptError;
-use bytes::BytesMut;
+use bytes::{Buf, Bytes, BytesMut};
 use kafka_protocol::messages::ApiKey;
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::{Decodable, Encodable};
+use kafka_protocol::records::{
+    Compression, Record, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
+};
 
 pub(crate) fn decode_body<T, B>(buffer: &mut B, api_version: i16) -> Result<T, InterceptError>
 where
@@ -53,3 +56,101 @@ pub(crate) fn encode_response<H: Encodable, T: Encodable>(
 
     Ok(buffer)
 }
+
+/// Records of a single record batch, along with the compression chosen by the producer
+pub(crate) struct RecordBatch {
+    pub(crate) compression: Compression,
+    pub(crate) records: Vec<Record>,
+}
+
+/// Decode and decompress the record batches of a produce request or a fetch response.
+/// Each batch keeps its own compression, so it can be compressed again the same way once
+/// its records have been encrypted or decrypted.
+pub(crate) fn decode_record_batches(
+    mut content: Bytes,
+) -> Result<Vec<RecordBatch>, InterceptError> {
+    let mut batches = vec![];
+
+    while content.has_remaining() {
+        // every batch starts with the base offset (8 bytes) and the batch length (4 bytes)
+        let length = content.try_peek_bytes(8..12)?.get_i32();
+        if length < 0 {
+            warn!("invalid record batch length: {length}");
+            return Err(InterceptError::InvalidData);
+        }
+        let mut batch = content.try_get_bytes(12 + length as usize)?;
+
+        let compression = record_batch_compression(&mut batch)?;
+        let records = RecordBatchDecoder::decode(
+            &mut batch,
+            None::<fn(&mut Bytes, Compression) -> Result<Bytes, _>>,
+        )
+        .map_err(|error| {
+            warn!("cannot decode record batch, closing connection");
+            debug!("error: {:?}", error);
+            InterceptError::InvalidData
+        })?;
+
+        batches.push(RecordBatch {
+            compression,
+            records,
+        });
+    }
+
+    Ok(batches)
+}
+
+/// Encode record batches, each compressed with its original compression
+pub(crate) fn encode_record_batches(batches: &[RecordBatch]) -> Result<Bytes, InterceptError> {
+    let mut encoded = BytesMut::new();
+
+    for batch in batches {
+        RecordBatchEncoder::encode(
+            &mut encoded,
+            batch.records.iter(),
+            &RecordEncodeOptions {
+                version: 2,
+                compression: batch.compression,
+            },
+            None::<fn(&mut BytesMut, &mut BytesMut, Compression) -> Result<(), _>>,
+        )
+        .map_err(|error| {
+            warn!("cannot encode record batch, closing connection");
+            debug!("error: {:?}", error);
+            InterceptError::InvalidData
+        })?;
+    }
+
+    Ok(encoded.freeze())
+}
+
+/// Read the compression from the attributes of a record batch, see:
+/// https://kafka.apache.org/documentation/#recordbatch
+fn record_batch_compression(batch: &mut Bytes) -> Result<Compression, InterceptError> {
+    // the magic byte, which is the version of the batch, is at the same offset for all versions
+    let magic = batch.try_peek_bytes(16..17)?.get_i8();
+    let attributes = match magic {
+        // baseOffset, batchLength, partitionLeaderEpoch, magic, crc
+        2 => batch.try_peek_bytes(21..23)?.get_i16(),
+        // legacy message sets: offset, messageSize, crc, magic
+        0 | 1 => batch.try_peek_bytes(17..18)?.get_i8() as i16,
+        _ => {
+            warn!("unknown record batch version: {magic}");
+            return Err(InterceptError::InvalidData);
+        }
+    };
+
+    let compression = match attributes & 0x7 {
+        0 => Compression::None,
+        1 => Compression::Gzip,
+        2 => Compression::Snappy,
+        3 => Compression::Lz4,
+        4 => Compression::Zstd,
+        other => {
+            warn!("unknown record batch compression: {other}");
+            return Err(InterceptError::InvalidData);
+        }
+    };
+
+    Ok(compression)
+}
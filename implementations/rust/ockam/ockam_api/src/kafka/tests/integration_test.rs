@@ -116,6 +116,8 @@ async fn create_kafka_service(
             inlet_controller,
             true,
             vec![],
+            false,
+            None,
         )),
         Arc::new(AllowAll),
         Arc::new(AllowAll),
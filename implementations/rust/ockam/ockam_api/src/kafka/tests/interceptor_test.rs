@@ -324,6 +324,8 @@ async fn setup_only_worker(context: &mut Context, handle: &NodeManagerHandle) ->
                 inlet_map,
                 true,
                 vec![],
+                false,
+                None,
             )),
             TEST_MAX_KAFKA_MESSAGE_SIZE,
         )),
@@ -422,6 +424,8 @@ async fn kafka_portal_worker__metadata_exchange__response_changed(
                 inlet_map.clone(),
                 true,
                 vec![],
+                false,
+                None,
             )),
             MAX_KAFKA_MESSAGE_SIZE,
         )),
@@ -1,6 +1,10 @@
 mod plain_tcp;
 mod project;
 mod secure;
+mod udp;
+#[cfg(unix)]
+mod uds;
+mod websocket;
 
 use ockam::tcp::TcpConnection;
 use ockam_core::errcode::{Kind, Origin};
@@ -19,6 +23,10 @@ pub(crate) use plain_tcp::PlainTcpInstantiator;
 pub(crate) use project::ProjectInstantiator;
 pub(crate) use secure::SecureChannelInstantiator;
 use std::fmt::{Debug, Formatter};
+pub(crate) use udp::UdpInstantiator;
+#[cfg(unix)]
+pub(crate) use uds::UdsInstantiator;
+pub(crate) use websocket::WebSocketInstantiator;
 
 #[derive(Clone)]
 pub struct Connection {
@@ -36,10 +44,24 @@ pub struct Connection {
     pub(crate) secure_channel_encryptors: Vec<Address>,
     /// A TCP worker address if used when instantiating the connection
     pub(crate) tcp_connection: Option<TcpConnection>,
+    /// A connection of another transport if used when instantiating the connection
+    pub(crate) transport_connection: Option<TransportConnection>,
     /// If a flow control was created
     flow_control_id: Option<FlowControlId>,
 }
 
+/// Resources of a transport other than TCP which must be released when the connection is closed.
+///
+/// WebSocket connections are shared by all the connections to the same peer and are kept open.
+#[derive(Clone, Debug)]
+pub enum TransportConnection {
+    /// The sender address of a UDP socket bound to a single peer
+    Udp(Address),
+    /// A Unix domain socket connected to the given path
+    #[cfg(unix)]
+    Uds(String),
+}
+
 impl Connection {
     /// Shorthand to add the address as consumer to the flow control
     pub fn add_consumer(&self, context: &Context, address: &Address) {
@@ -105,6 +127,25 @@ impl Connection {
             }
         }
 
+        match self.transport_connection.as_ref() {
+            Some(TransportConnection::Udp(address)) => {
+                if let Some(udp_transport) = node_manager.udp_transport.as_ref() {
+                    if let Err(error) = udp_transport.unbind(address.clone()).await {
+                        debug!("cannot unbind udp socket `{address}`: {error}");
+                    }
+                }
+            }
+            #[cfg(unix)]
+            Some(TransportConnection::Uds(path)) => {
+                if let Some(uds_transport) = node_manager.uds_transport.get() {
+                    if let Err(error) = uds_transport.disconnect(path).await {
+                        debug!("cannot disconnect unix domain socket `{path}`: {error}");
+                    }
+                }
+            }
+            None => {}
+        }
+
         Ok(())
     }
 }
@@ -134,6 +175,7 @@ pub(crate) struct ConnectionBuilder {
     pub(crate) flow_control_id: Option<FlowControlId>,
     pub(crate) secure_channel_encryptors: Vec<Address>,
     pub(crate) tcp_connection: Option<TcpConnection>,
+    pub(crate) transport_connection: Option<TransportConnection>,
 }
 
 impl Debug for ConnectionBuilder {
@@ -163,6 +205,8 @@ pub struct Changes {
     pub secure_channel_encryptors: Vec<Address>,
     /// Optional, to keep track of tcp worker when created for the connection
     pub tcp_connection: Option<TcpConnection>,
+    /// Optional, to keep track of the resources of another transport used for the connection
+    pub transport_connection: Option<TransportConnection>,
 }
 
 /// Takes in a [`MultiAddr`] and instantiate it, can be implemented for any protocol.
@@ -197,6 +241,7 @@ impl ConnectionBuilder {
             secure_channel_encryptors: vec![],
             flow_control_id: None,
             tcp_connection: None,
+            transport_connection: None,
         }
     }
 
@@ -207,6 +252,7 @@ impl ConnectionBuilder {
             original_addr: self.original_multiaddr,
             secure_channel_encryptors: self.secure_channel_encryptors,
             tcp_connection: self.tcp_connection,
+            transport_connection: self.transport_connection,
             flow_control_id: self.flow_control_id,
         }
     }
@@ -261,6 +307,17 @@ impl ConnectionBuilder {
                         self.tcp_connection = changes.tcp_connection;
                     }
 
+                    if changes.transport_connection.is_some() {
+                        if self.transport_connection.is_some() {
+                            return Err(ockam_core::Error::new(
+                                Origin::Transport,
+                                Kind::Unsupported,
+                                "multiple transport connections created in a `MultiAddr`",
+                            ));
+                        }
+                        self.transport_connection = changes.transport_connection;
+                    }
+
                     if changes.flow_control_id.is_some() {
                         self.flow_control_id = changes.flow_control_id;
                     }
@@ -280,6 +337,7 @@ impl ConnectionBuilder {
             current_multiaddr: self.current_multiaddr,
             flow_control_id: self.flow_control_id,
             tcp_connection: self.tcp_connection,
+            transport_connection: self.transport_connection,
         })
     }
 
@@ -65,6 +65,7 @@ impl Instantiator for PlainTcpInstantiator {
             flow_control_id: tcp.flow_control_id,
             secure_channel_encryptors: vec![],
             tcp_connection: Some(tcp_connection),
+            transport_connection: None,
         })
     }
 }
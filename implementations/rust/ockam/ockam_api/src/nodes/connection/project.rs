@@ -72,6 +72,7 @@ impl Instantiator for ProjectInstantiator {
                 None,
                 self.timeout,
                 SecureChannelType::KeyExchangeAndMessages,
+                Some(project_piece.to_string()),
             )
             .await?;
 
@@ -85,6 +86,7 @@ impl Instantiator for ProjectInstantiator {
             current_multiaddr,
             secure_channel_encryptors: vec![sc.encryptor_address().clone()],
             tcp_connection: tcp.tcp_connection,
+            transport_connection: None,
         })
     }
 }
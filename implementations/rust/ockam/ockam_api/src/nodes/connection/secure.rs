@@ -45,7 +45,7 @@ impl Instantiator for SecureChannelInstantiator {
         transport_route: Route,
         extracted: (MultiAddr, MultiAddr, MultiAddr),
     ) -> Result<Changes, Error> {
-        let (_before, secure_piece, after) = extracted;
+        let (before, secure_piece, after) = extracted;
         debug!(%secure_piece, %transport_route, "creating secure channel");
         let route = local_multiaddr_to_route(&secure_piece)?;
 
@@ -61,6 +61,8 @@ impl Instantiator for SecureChannelInstantiator {
                 None,
                 self.timeout,
                 SecureChannelType::KeyExchangeAndMessages,
+                // the listener is identified by the full address used to reach it
+                Some(format!("{before}{secure_piece}")),
             )
             .await?;
 
@@ -74,6 +76,7 @@ impl Instantiator for SecureChannelInstantiator {
             flow_control_id: Some(sc.flow_control_id().clone()),
             secure_channel_encryptors: vec![sc.encryptor_address().clone()],
             tcp_connection: None,
+            transport_connection: None,
         })
     }
 }
@@ -0,0 +1,71 @@
+use crate::error::ApiError;
+use crate::nodes::connection::{Changes, ConnectionBuilder, Instantiator, TransportConnection};
+use crate::try_address_to_multiaddr;
+
+use crate::nodes::NodeManager;
+use ockam::udp::{UdpBindArguments, UdpBindOptions};
+use ockam_core::{async_trait, Error, Route};
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Udp};
+use ockam_multiaddr::{Match, MultiAddr, Protocol};
+use ockam_node::Context;
+
+/// Binds a UDP socket to the peer.
+///
+/// Reliable delivery is enabled so that TCP portals can use the connection.
+pub(crate) struct UdpInstantiator {}
+
+impl UdpInstantiator {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl Instantiator for UdpInstantiator {
+    fn matches(&self) -> Vec<Match> {
+        vec![
+            // matches any ip address or hostname followed by a udp protocol
+            Match::any([DnsAddr::CODE, Ip4::CODE, Ip6::CODE]),
+            Udp::CODE.into(),
+        ]
+    }
+
+    async fn instantiate(
+        &self,
+        _ctx: &Context,
+        node_manager: &NodeManager,
+        _transport_route: Route,
+        extracted: (MultiAddr, MultiAddr, MultiAddr),
+    ) -> Result<Changes, Error> {
+        let (before, udp_piece, after) = extracted;
+
+        let udp_transport = node_manager.udp_transport.as_ref().ok_or_else(|| {
+            ApiError::core(format!(
+                "The node must be created with the UDP transport to connect to {udp_piece}"
+            ))
+        })?;
+        let peer = udp_piece.to_socket_addr()?;
+
+        let bind = udp_transport
+            .bind(
+                UdpBindArguments::new()
+                    .with_bind_address("0.0.0.0:0")?
+                    .with_peer_address(peer)
+                    .await?
+                    .with_reliable_delivery(true),
+                UdpBindOptions::new(),
+            )
+            .await?;
+
+        let multiaddr = try_address_to_multiaddr(bind.sender_address())?;
+        let current_multiaddr = ConnectionBuilder::combine(before, multiaddr, after)?;
+
+        Ok(Changes {
+            current_multiaddr,
+            flow_control_id: Some(bind.flow_control_id().clone()),
+            secure_channel_encryptors: vec![],
+            tcp_connection: None,
+            transport_connection: Some(TransportConnection::Udp(bind.sender_address().clone())),
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use crate::error::ApiError;
+use crate::nodes::connection::{Changes, ConnectionBuilder, Instantiator, TransportConnection};
+use crate::try_address_to_multiaddr;
+
+use crate::nodes::NodeManager;
+use ockam_core::{async_trait, Error, Route};
+use ockam_multiaddr::proto::Unix;
+use ockam_multiaddr::{Match, MultiAddr, Protocol};
+use ockam_node::Context;
+
+/// Creates a Unix domain socket connection.
+pub(crate) struct UdsInstantiator {}
+
+impl UdsInstantiator {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl Instantiator for UdsInstantiator {
+    fn matches(&self) -> Vec<Match> {
+        vec![Unix::CODE.into()]
+    }
+
+    async fn instantiate(
+        &self,
+        ctx: &Context,
+        node_manager: &NodeManager,
+        _transport_route: Route,
+        extracted: (MultiAddr, MultiAddr, MultiAddr),
+    ) -> Result<Changes, Error> {
+        let (before, uds_piece, after) = extracted;
+
+        let path = uds_piece
+            .first()
+            .and_then(|p| p.cast::<Unix>().map(|path| path.to_string()))
+            .ok_or_else(|| {
+                ApiError::core(format!("Couldn't read the socket path of {uds_piece}"))
+            })?;
+        let sender_address = node_manager
+            .uds_transport(ctx)
+            .await?
+            .connect(&path)
+            .await?;
+
+        let multiaddr = try_address_to_multiaddr(&sender_address)?;
+        let current_multiaddr = ConnectionBuilder::combine(before, multiaddr, after)?;
+
+        Ok(Changes {
+            current_multiaddr,
+            flow_control_id: None,
+            secure_channel_encryptors: vec![],
+            tcp_connection: None,
+            transport_connection: Some(TransportConnection::Uds(path)),
+        })
+    }
+}
@@ -0,0 +1,57 @@
+use crate::nodes::connection::{Changes, ConnectionBuilder, Instantiator};
+use crate::try_address_to_multiaddr;
+
+use crate::nodes::NodeManager;
+use ockam_core::{async_trait, Error, Route};
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Tcp, Ws};
+use ockam_multiaddr::{Match, MultiAddr, Protocol};
+use ockam_node::Context;
+
+/// Creates a WebSocket connection, or reuses the existing connection to the same peer.
+pub(crate) struct WebSocketInstantiator {}
+
+impl WebSocketInstantiator {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl Instantiator for WebSocketInstantiator {
+    fn matches(&self) -> Vec<Match> {
+        vec![
+            // matches any tcp address followed by a ws protocol
+            Match::any([DnsAddr::CODE, Ip4::CODE, Ip6::CODE]),
+            Tcp::CODE.into(),
+            Ws::CODE.into(),
+        ]
+    }
+
+    async fn instantiate(
+        &self,
+        ctx: &Context,
+        node_manager: &NodeManager,
+        _transport_route: Route,
+        extracted: (MultiAddr, MultiAddr, MultiAddr),
+    ) -> Result<Changes, Error> {
+        let (before, ws_piece, after) = extracted;
+
+        let peer = ws_piece.to_socket_addr()?;
+        let sender_address = node_manager
+            .websocket_transport(ctx)
+            .await?
+            .connect(peer)
+            .await?;
+
+        let multiaddr = try_address_to_multiaddr(&sender_address)?;
+        let current_multiaddr = ConnectionBuilder::combine(before, multiaddr, after)?;
+
+        Ok(Changes {
+            current_multiaddr,
+            flow_control_id: None,
+            secure_channel_encryptors: vec![],
+            tcp_connection: None,
+            transport_connection: None,
+        })
+    }
+}
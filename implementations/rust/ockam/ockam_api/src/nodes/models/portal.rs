@@ -6,6 +6,7 @@ use std::time::Duration;
 
 use minicbor::{CborLen, Decode, Encode};
 use ockam::identity::Identifier;
+use ockam::tcp::DestinationPattern;
 use ockam::transport::HostnamePort;
 use ockam_abac::PolicyExpression;
 use ockam_core::{Address, IncomingAccessControl, OutgoingAccessControl, Route};
@@ -57,6 +58,9 @@ pub struct CreateInlet {
     #[n(12)] pub(crate) privileged: bool,
     /// TLS certificate provider route.
     #[n(13)] pub(crate) tls_certificate_provider: Option<MultiAddr>,
+    /// Read the destination of each connection from a SOCKS5 request,
+    /// and ask a dynamic outlet to connect to it.
+    #[n(14)] pub(crate) socks5: bool,
 }
 
 impl CreateInlet {
@@ -83,6 +87,7 @@ impl CreateInlet {
             disable_tcp_fallback,
             privileged,
             tls_certificate_provider: None,
+            socks5: false,
         }
     }
 
@@ -110,6 +115,7 @@ impl CreateInlet {
             disable_tcp_fallback,
             privileged,
             tls_certificate_provider: None,
+            socks5: false,
         }
     }
 
@@ -117,6 +123,10 @@ impl CreateInlet {
         self.tls_certificate_provider = Some(provider);
     }
 
+    pub fn set_socks5(&mut self) {
+        self.socks5 = true;
+    }
+
     pub fn set_wait_ms(&mut self, ms: u64) {
         self.wait_for_outlet_duration = Some(Duration::from_millis(ms))
     }
@@ -169,7 +179,17 @@ pub struct CreateOutlet {
     /// will be used.
     #[n(5)] pub policy_expression: Option<PolicyExpression>,
     /// Use eBPF and RawSocket to access TCP packets instead of TCP data stream.
-    #[n(6)] pub privileged: bool
+    #[n(6)] pub privileged: bool,
+    /// If set, the outlet is dynamic and SOCKS5 inlets can ask it to connect to any destination
+    /// matching one of these `host:ports` patterns, and authorized by the policy expression.
+    /// An empty list only uses the policy expression.
+    #[n(7)] pub allowed_destinations: Option<Vec<String>>,
+    /// If set, the outlet sends a PROXY protocol v2 header to its destination, with the identifier
+    /// of the inlet and the values of these credential attributes.
+    #[n(8)] pub proxy_protocol_attributes: Option<Vec<String>>,
+    /// If set, the outlet intercepts the HTTP/1.1 requests sent to its destination, and adds
+    /// headers with the identifier and the credential attributes of the inlet.
+    #[n(9)] pub http_identity_headers: Option<HttpIdentityHeaders>,
 }
 
 impl CreateOutlet {
@@ -187,12 +207,132 @@ impl CreateOutlet {
             reachable_from_default_secure_channel,
             policy_expression: None,
             privileged,
+            allowed_destinations: None,
+            proxy_protocol_attributes: None,
+            http_identity_headers: None,
         }
     }
 
     pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
         self.policy_expression = Some(expression);
     }
+
+    pub fn set_allowed_destinations(&mut self, destinations: Vec<DestinationPattern>) {
+        self.allowed_destinations = Some(destinations.iter().map(|d| d.to_string()).collect());
+    }
+
+    pub fn set_proxy_protocol_attributes(&mut self, attributes: Vec<String>) {
+        self.proxy_protocol_attributes = Some(attributes);
+    }
+
+    pub fn set_http_identity_headers(&mut self, http_identity_headers: HttpIdentityHeaders) {
+        self.http_identity_headers = Some(http_identity_headers);
+    }
+}
+
+/// Configuration of the identity headers added by an outlet to HTTP requests
+#[derive(Clone, Debug, Default, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HttpIdentityHeaders {
+    /// The credential attributes sent as headers
+    #[n(1)] pub attributes: Vec<String>,
+    /// If set, the headers are signed with an HMAC-SHA256 using this key
+    #[n(2)] pub hmac_key: Option<String>,
+}
+
+impl HttpIdentityHeaders {
+    pub fn new(attributes: Vec<String>, hmac_key: Option<String>) -> Self {
+        Self {
+            attributes,
+            hmac_key,
+        }
+    }
+}
+
+/// Request body to create a UDP inlet
+#[derive(Clone, Debug, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateUdpInlet {
+    /// The address the inlet should listen at.
+    #[n(1)] pub(crate) listen_addr: HostnamePort,
+    /// The address of the UDP outlet, directly or via a relay
+    #[n(2)] pub(crate) outlet_addr: MultiAddr,
+    /// A human-friendly alias for this inlet
+    #[b(3)] pub(crate) alias: String,
+    /// An authorised identity for secure channels.
+    /// Only set for non-project addresses as for projects the project's
+    /// authorised identity will be used.
+    #[n(4)] pub(crate) authorized: Option<Identifier>,
+    /// The expression for the access control policy for this inlet.
+    /// If not set, the policy set for the [UDP inlet resource type](ockam_abac::ResourceType::UdpInlet)
+    /// will be used.
+    #[n(5)] pub(crate) policy_expression: Option<PolicyExpression>,
+    /// The duration after which the flow of a client is closed if no datagram was exchanged
+    #[n(6)] pub(crate) idle_timeout: Option<Duration>,
+}
+
+impl CreateUdpInlet {
+    pub fn new(
+        listen: HostnamePort,
+        to: MultiAddr,
+        alias: String,
+        authorized: Option<Identifier>,
+    ) -> Self {
+        Self {
+            listen_addr: listen,
+            outlet_addr: to,
+            alias,
+            authorized,
+            policy_expression: None,
+            idle_timeout: None,
+        }
+    }
+
+    pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
+        self.policy_expression = Some(expression);
+    }
+
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.idle_timeout = Some(idle_timeout);
+    }
+}
+
+/// Request body to create a UDP outlet
+#[derive(Clone, Debug, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateUdpOutlet {
+    /// The address the datagrams are sent to
+    #[n(1)] pub hostname_port: HostnamePort,
+    /// The address of the outlet worker
+    #[n(2)] pub worker_addr: Option<Address>,
+    /// The expression for the access control policy for this outlet.
+    /// If not set, the policy set for the [UDP outlet resource type](ockam_abac::ResourceType::UdpOutlet)
+    /// will be used.
+    #[n(3)] pub policy_expression: Option<PolicyExpression>,
+    /// The duration after which the flow of an inlet client is closed if no datagram was exchanged
+    #[n(4)] pub idle_timeout: Option<Duration>,
+}
+
+impl CreateUdpOutlet {
+    pub fn new(hostname_port: HostnamePort, worker_addr: Option<Address>) -> Self {
+        Self {
+            hostname_port,
+            worker_addr,
+            policy_expression: None,
+            idle_timeout: None,
+        }
+    }
+
+    pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
+        self.policy_expression = Some(expression);
+    }
+
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.idle_timeout = Some(idle_timeout);
+    }
 }
 
 /// Response body when interacting with a portal endpoint
@@ -100,6 +100,8 @@ pub struct StartKafkaInletRequest {
     #[n(8)] consumer_policy_expression: Option<PolicyExpression>,
     #[n(9)] producer_policy_expression: Option<PolicyExpression>,
     #[n(10)] encrypted_fields: Vec<String>,
+    #[n(11)] encrypt_headers: bool,
+    #[n(12)] record_key_secret: Option<String>,
 }
 
 impl StartKafkaInletRequest {
@@ -110,6 +112,8 @@ impl StartKafkaInletRequest {
         kafka_outlet_route: MultiAddr,
         encrypt_content: bool,
         encrypted_fields: Vec<String>,
+        encrypt_headers: bool,
+        record_key_secret: Option<String>,
         consumer_resolution: ConsumerResolution,
         consumer_publishing: ConsumerPublishing,
         inlet_policy_expression: Option<PolicyExpression>,
@@ -127,6 +131,8 @@ impl StartKafkaInletRequest {
             consumer_policy_expression,
             producer_policy_expression,
             encrypted_fields,
+            encrypt_headers,
+            record_key_secret,
         }
     }
 
@@ -148,6 +154,15 @@ impl StartKafkaInletRequest {
         self.encrypted_fields.clone()
     }
 
+    pub fn encrypt_headers(&self) -> bool {
+        self.encrypt_headers
+    }
+
+    /// Secret used to encrypt the record keys, if they must be encrypted
+    pub fn record_key_secret(&self) -> Option<String> {
+        self.record_key_secret.clone()
+    }
+
     pub fn consumer_resolution(&self) -> ConsumerResolution {
         self.consumer_resolution.clone()
     }
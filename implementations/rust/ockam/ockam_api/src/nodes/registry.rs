@@ -3,12 +3,14 @@ use crate::DefaultAddress;
 
 use ockam::identity::Identifier;
 use ockam::identity::{SecureChannel, SecureChannelListener};
+use ockam::tcp::TcpPortalMetrics;
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::{Address, Route};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::compat::asynchronous::{Mutex, RwLock};
 use ockam_transport_core::HostnamePort;
 
+use crate::nodes::connection::Connection;
 use crate::session::session::Session;
 use std::borrow::Borrow;
 use std::fmt::Display;
@@ -128,6 +130,7 @@ pub(crate) struct InletInfo {
     pub(crate) outlet_addr: MultiAddr,
     pub(crate) session: Arc<Mutex<Session>>,
     pub(crate) privileged: bool,
+    pub(crate) metrics: Arc<TcpPortalMetrics>,
 }
 
 impl InletInfo {
@@ -136,12 +139,14 @@ impl InletInfo {
         outlet_addr: MultiAddr,
         session: Session,
         privileged: bool,
+        metrics: Arc<TcpPortalMetrics>,
     ) -> Self {
         Self {
             bind_addr: bind_addr.to_owned(),
             outlet_addr,
             session: Arc::new(Mutex::new(session)),
             privileged,
+            metrics,
         }
     }
 }
@@ -151,10 +156,18 @@ pub struct OutletInfo {
     pub(crate) to: HostnamePort,
     pub(crate) worker_addr: Address,
     pub(crate) privileged: bool,
+    pub(crate) metrics: Arc<TcpPortalMetrics>,
+    /// The address of the TCP outlet when the worker address is an HTTP interceptor
+    pub(crate) intercepted_outlet_addr: Option<Address>,
 }
 
 impl OutletInfo {
-    pub(crate) fn new(to: HostnamePort, worker_addr: Option<&Address>, privileged: bool) -> Self {
+    pub(crate) fn new(
+        to: HostnamePort,
+        worker_addr: Option<&Address>,
+        privileged: bool,
+        metrics: Arc<TcpPortalMetrics>,
+    ) -> Self {
         let worker_addr = match worker_addr {
             Some(addr) => addr.clone(),
             None => Address::from_string(""),
@@ -163,8 +176,51 @@ impl OutletInfo {
             to,
             worker_addr,
             privileged,
+            metrics,
+            intercepted_outlet_addr: None,
         }
     }
+
+    pub(crate) fn with_intercepted_outlet_addr(mut self, address: Address) -> Self {
+        self.intercepted_outlet_addr = Some(address);
+        self
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct UdpInletInfo {
+    pub(crate) bind_addr: String,
+    pub(crate) outlet_addr: MultiAddr,
+    pub(crate) processor_address: Address,
+    pub(crate) connection: Connection,
+}
+
+impl UdpInletInfo {
+    pub(crate) fn new(
+        bind_addr: &str,
+        outlet_addr: MultiAddr,
+        processor_address: Address,
+        connection: Connection,
+    ) -> Self {
+        Self {
+            bind_addr: bind_addr.to_owned(),
+            outlet_addr,
+            processor_address,
+            connection,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct UdpOutletInfo {
+    pub(crate) to: HostnamePort,
+    pub(crate) worker_addr: Address,
+}
+
+impl UdpOutletInfo {
+    pub(crate) fn new(to: HostnamePort, worker_addr: Address) -> Self {
+        Self { to, worker_addr }
+    }
 }
 
 #[derive(Clone)]
@@ -185,6 +241,8 @@ pub(crate) struct Registry {
     pub(crate) relays: RegistryOf<String, RegistryRelayInfo>,
     pub(crate) inlets: RegistryOf<String, InletInfo>,
     pub(crate) outlets: RegistryOf<Address, OutletInfo>,
+    pub(crate) udp_inlets: RegistryOf<String, UdpInletInfo>,
+    pub(crate) udp_outlets: RegistryOf<Address, UdpOutletInfo>,
     pub(crate) influxdb_services: RegistryOf<Address, ()>, // TODO: what should we persist here?
 }
 
@@ -346,6 +404,11 @@ mod tests {
     }
 
     fn outlet_info(worker_addr: Address) -> OutletInfo {
-        OutletInfo::new(HostnamePort::new("127.0.0.1", 0), Some(&worker_addr), true)
+        OutletInfo::new(
+            HostnamePort::new("127.0.0.1", 0),
+            Some(&worker_addr),
+            true,
+            Default::default(),
+        )
     }
 }
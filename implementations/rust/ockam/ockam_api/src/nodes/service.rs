@@ -19,11 +19,13 @@ mod secure_channel;
 pub mod tcp_inlets;
 pub mod tcp_outlets;
 mod transport;
+pub mod udp_portals;
 pub mod workers;
 
 mod certificate_provider;
 mod http;
 mod manager;
+mod metrics;
 mod trust;
 mod worker;
 
@@ -16,9 +16,12 @@ impl DefaultAddress {
     pub const RENDEZVOUS_SERVICE: &'static str = "rendezvous";
     pub const DIRECT_AUTHENTICATOR: &'static str = "direct_authenticator";
     pub const CREDENTIAL_ISSUER: &'static str = "credential_issuer";
+    pub const REVOCATION_LIST_ISSUER: &'static str = "revocation_list_issuer";
     pub const ENROLLMENT_TOKEN_ISSUER: &'static str = "enrollment_token_issuer";
     pub const ENROLLMENT_TOKEN_ACCEPTOR: &'static str = "enrollment_token_acceptor";
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
+    pub const JWT_AUTHENTICATOR: &'static str = "jwt_authenticator";
+    pub const SVID_AUTHENTICATOR: &'static str = "svid_authenticator";
     pub const KAFKA_OUTLET: &'static str = "kafka_outlet";
     pub const KAFKA_INLET: &'static str = "kafka_inlet";
     pub const LEASE_MANAGER: &'static str = "lease_manager";
@@ -39,9 +42,12 @@ impl DefaultAddress {
             | Self::KEY_EXCHANGER_LISTENER
             | Self::DIRECT_AUTHENTICATOR
             | Self::CREDENTIAL_ISSUER
+            | Self::REVOCATION_LIST_ISSUER
             | Self::ENROLLMENT_TOKEN_ISSUER
             | Self::ENROLLMENT_TOKEN_ACCEPTOR
             | Self::OKTA_IDENTITY_PROVIDER
+            | Self::JWT_AUTHENTICATOR
+            | Self::SVID_AUTHENTICATOR
             | Self::KAFKA_INLET
             | Self::KAFKA_OUTLET
             | Self::LEASE_MANAGER)
@@ -59,9 +65,12 @@ impl DefaultAddress {
             Self::KEY_EXCHANGER_LISTENER,
             Self::DIRECT_AUTHENTICATOR,
             Self::CREDENTIAL_ISSUER,
+            Self::REVOCATION_LIST_ISSUER,
             Self::ENROLLMENT_TOKEN_ISSUER,
             Self::ENROLLMENT_TOKEN_ACCEPTOR,
             Self::OKTA_IDENTITY_PROVIDER,
+            Self::JWT_AUTHENTICATOR,
+            Self::SVID_AUTHENTICATOR,
             Self::KAFKA_INLET,
             Self::KAFKA_OUTLET,
             Self::LEASE_MANAGER,
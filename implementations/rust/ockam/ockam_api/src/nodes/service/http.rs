@@ -5,6 +5,7 @@ use std::sync::{Arc, Weak};
 use http_body_util::combinators::BoxBody;
 use http_body_util::{BodyExt, Empty, Full};
 use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper::{Method, Request, Response};
@@ -22,6 +23,7 @@ use tokio::net::TcpListener;
 ///
 /// This server is complementary to the node's API and is intended to be used
 /// for health checks and monitoring of the node's status.
+/// The `/metrics` endpoint can be scraped by Prometheus.
 ///
 /// It is not intended to be a full-fledged HTTP version of the node's API.
 pub struct HttpServer;
@@ -63,6 +65,7 @@ struct HttpServerProcessor {
 impl HttpServerProcessor {
     async fn handle_request(
         node_manager: Weak<NodeManager>,
+        router_mailbox_depth: usize,
         req: Request<hyper::body::Incoming>,
     ) -> Result<Response<BoxBody<Bytes, Infallible>>> {
         debug!("Processing request: {req:?}");
@@ -83,6 +86,18 @@ impl HttpServerProcessor {
                 };
                 Self::json_response(node_resources)
             }
+            (&Method::GET, ["metrics"]) => {
+                let metrics = {
+                    let node_manager = node_manager
+                        .upgrade()
+                        .ok_or_else(|| ApiError::core("node manager was shut down"))?;
+                    node_manager.get_metrics(router_mailbox_depth).await
+                };
+                Ok(Response::builder()
+                    .header(CONTENT_TYPE, "text/plain; version=0.0.4")
+                    .body(Full::new(Bytes::from(metrics)).boxed())
+                    .map_err(HttpError::from)?)
+            }
             _ => {
                 warn!("Request received for a non supported endpoint: {req:?}");
                 Ok(Response::builder()
@@ -117,12 +132,13 @@ impl Processor for HttpServerProcessor {
         Ok(())
     }
 
-    async fn process(&mut self, _context: &mut Self::Context) -> ockam_core::Result<bool> {
+    async fn process(&mut self, context: &mut Self::Context) -> ockam_core::Result<bool> {
         if let Ok((stream, _)) = self.tcp_listener.accept().await {
             let io = TokioIo::new(stream);
+            let context: &Context = context;
             let service = service_fn(|req| {
                 let node_manager = self.node_manager.clone();
-                Self::handle_request(node_manager, req)
+                Self::handle_request(node_manager, context.router_mailbox_depth(), req)
             });
             if let Err(err) = http1::Builder::new().serve_connection(io, service).await {
                 error!("Error serving connection: {err:?}");
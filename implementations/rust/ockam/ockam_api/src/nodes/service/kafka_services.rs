@@ -1,6 +1,7 @@
 use super::NodeManagerWorker;
 use crate::error::ApiError;
 use crate::kafka::key_exchange::controller::KafkaKeyExchangeControllerImpl;
+use crate::kafka::key_exchange::RecordKeyCipher;
 use crate::kafka::protocol_aware::inlet::KafkaInletInterceptorFactory;
 use crate::kafka::protocol_aware::outlet::KafkaOutletInterceptorFactory;
 use crate::kafka::KafkaOutletController;
@@ -14,6 +15,7 @@ use crate::nodes::models::services::{
 };
 use crate::nodes::registry::{KafkaServiceInfo, KafkaServiceKind};
 use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::tcp_outlets::outlet_resource_attributes;
 use crate::nodes::InMemoryNode;
 use crate::port_range::PortRange;
 use ockam::transport::HostnamePort;
@@ -46,6 +48,8 @@ impl NodeManagerWorker {
                 request.project_route(),
                 request.encrypt_content(),
                 request.encrypted_fields(),
+                request.encrypt_headers(),
+                request.record_key_secret(),
                 request.consumer_resolution(),
                 request.consumer_publishing(),
                 request.inlet_policy_expression(),
@@ -119,12 +123,18 @@ impl InMemoryNode {
         outlet_node_multiaddr: MultiAddr,
         encrypt_content: bool,
         encrypted_fields: Vec<String>,
+        encrypt_headers: bool,
+        record_key_secret: Option<String>,
         consumer_resolution: ConsumerResolution,
         consumer_publishing: ConsumerPublishing,
         inlet_policy_expression: Option<PolicyExpression>,
         consumer_policy_expression: Option<PolicyExpression>,
         producer_policy_expression: Option<PolicyExpression>,
     ) -> Result<()> {
+        let record_key_cipher = record_key_secret
+            .map(|secret| RecordKeyCipher::new(secret.as_bytes()).map(Arc::new))
+            .transpose()?;
+
         let consumer_policy_access_control = self
             .policy_access_control(
                 self.project_authority().clone(),
@@ -132,7 +142,7 @@ impl InMemoryNode {
                     format!("kafka-consumer-{}", interceptor_address.address()),
                     ResourceType::KafkaConsumer,
                 ),
-                Action::HandleMessage,
+                Action::Consume,
                 consumer_policy_expression,
             )
             .await?;
@@ -144,7 +154,7 @@ impl InMemoryNode {
                     format!("kafka-producer-{}", interceptor_address.address()),
                     ResourceType::KafkaProducer,
                 ),
-                Action::HandleMessage,
+                Action::Produce,
                 producer_policy_expression,
             )
             .await?;
@@ -238,6 +248,8 @@ impl InMemoryNode {
                 inlet_controller,
                 encrypt_content,
                 encrypted_fields,
+                encrypt_headers,
+                record_key_cipher,
             )),
             Arc::new(policy_access_control.create_incoming()),
             Arc::new(policy_access_control.create_outgoing(context).await?),
@@ -271,11 +283,12 @@ impl InMemoryNode {
             })?;
 
         let policy_access_control = self
-            .policy_access_control(
+            .policy_access_control_with_attributes(
                 self.project_authority().clone(),
                 Resource::new(service_address.to_string(), ResourceType::TcpOutlet),
-                Action::HandleMessage,
+                Action::Connect,
                 outlet_policy_expression.clone(),
+                outlet_resource_attributes(&bootstrap_server_addr),
             )
             .await?;
 
@@ -1,8 +1,10 @@
 use crate::cloud::project::Project;
 use crate::cloud::{AuthorityNodeClient, ControllerClient, CredentialsEnabled, ProjectNodeClient};
+#[cfg(unix)]
+use crate::nodes::connection::UdsInstantiator;
 use crate::nodes::connection::{
     Connection, ConnectionBuilder, PlainTcpInstantiator, ProjectInstantiator,
-    SecureChannelInstantiator,
+    SecureChannelInstantiator, UdpInstantiator, WebSocketInstantiator,
 };
 use crate::nodes::models::portal::OutletStatus;
 use crate::nodes::models::transport::{Port, TransportMode, TransportType};
@@ -19,8 +21,8 @@ use crate::{ApiError, CliState, DefaultAddress};
 use miette::IntoDiagnostic;
 use ockam::identity::{
     CachedCredentialRetrieverCreator, CredentialRetrieverCreator, Identifier,
-    MemoryCredentialRetrieverCreator, RemoteCredentialRetrieverCreator, SecureChannelListener,
-    SecureChannels,
+    MemoryCredentialRetrieverCreator, RemoteCredentialRetrieverCreator,
+    RemoteRevocationListRetriever, SecureChannelListener, SecureChannels,
 };
 use ockam::tcp::TcpTransport;
 use ockam::udp::{
@@ -29,7 +31,9 @@ use ockam::udp::{
 use ockam::{RelayService, RelayServiceOptions};
 use ockam_abac::expr::str;
 use ockam_abac::{
-    Action, Env, Policies, PolicyAccessControl, PolicyExpression, Resource, ResourceType, Resources,
+    action_attribute_name, resource_attribute_name, Action, Env, Policies, PolicyAccessControl,
+    PolicyAccessControlMetrics, PolicyExpression, Resource, ResourceType, Resources, ABAC_ID_KEY,
+    ABAC_RESOURCE_NAME_KEY, ABAC_RESOURCE_NODE_KEY, ABAC_RESOURCE_TYPE_KEY,
 };
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
@@ -38,9 +42,13 @@ use ockam_core::{
 };
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
+#[cfg(unix)]
+use ockam_transport_uds::UdsTransport;
+use ockam_transport_websocket::WebSocketTransport;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::{watch, OnceCell};
 
 /// Node manager provides high-level operations to
 ///  - send messages
@@ -54,11 +62,16 @@ pub struct NodeManager {
     pub(crate) api_transport_flow_control_id: FlowControlId,
     pub(crate) tcp_transport: TcpTransport,
     pub(crate) udp_transport: Option<UdpTransport>,
+    pub(crate) websocket_transport: OnceCell<WebSocketTransport>,
+    #[cfg(unix)]
+    pub(crate) uds_transport: OnceCell<UdsTransport>,
     pub(crate) secure_channels: Arc<SecureChannels>,
     pub(crate) api_sc_listener: Option<SecureChannelListener>,
     pub(crate) credential_retriever_creators: CredentialRetrieverCreators,
     pub(super) project_authority: Option<Identifier>,
     pub(crate) registry: Arc<Registry>,
+    pub(super) configuration_reloads: watch::Sender<()>,
+    pub(super) policy_access_control_metrics: Arc<PolicyAccessControlMetrics>,
 }
 
 impl NodeManager {
@@ -86,6 +99,22 @@ impl NodeManager {
 
         let secure_channels = cli_state.secure_channels(&node_name).await?;
 
+        // Regularly retrieve the credentials revoked by the project authority
+        if let NodeManagerCredentialRetrieverOptions::Remote { info, .. } =
+            &trust_options.project_member_credential_retriever_options
+        {
+            RemoteRevocationListRetriever::new(
+                ctx.async_try_clone().await?,
+                Arc::new(transport_options.tcp_transport.clone()),
+                secure_channels.clone(),
+                info.issuer.clone(),
+                info.route.clone(),
+                node_identifier.clone(),
+            )
+            .with_service_address(DefaultAddress::REVOCATION_LIST_ISSUER)
+            .start();
+        }
+
         let project_member_credential_retriever_creator: Option<
             Arc<dyn CredentialRetrieverCreator>,
         > = match trust_options.project_member_credential_retriever_options {
@@ -149,11 +178,16 @@ impl NodeManager {
             api_transport_flow_control_id: transport_options.api_transport_flow_control_id,
             tcp_transport: transport_options.tcp_transport,
             udp_transport: transport_options.udp_transport.clone(),
+            websocket_transport: OnceCell::new(),
+            #[cfg(unix)]
+            uds_transport: OnceCell::new(),
             secure_channels,
             api_sc_listener: None,
             credential_retriever_creators,
             project_authority: trust_options.project_authority,
             registry,
+            configuration_reloads: watch::channel(()).0,
+            policy_access_control_metrics: Default::default(),
         };
 
         debug!("initializing services");
@@ -234,7 +268,7 @@ impl NodeManager {
                 .policy_access_control(
                     self.project_authority.clone(),
                     Resource::new(DefaultAddress::RELAY_SERVICE, ResourceType::Relay),
-                    Action::HandleMessage,
+                    Action::RelayCreate,
                     None,
                 )
                 .await?;
@@ -307,15 +341,24 @@ impl NodeManager {
         timeout: Option<Duration>,
     ) -> ockam_core::Result<Connection> {
         debug!(?timeout, "connecting to {}", &addr);
-        let connection = ConnectionBuilder::new(addr.clone())
+        let builder = ConnectionBuilder::new(addr.clone())
             .instantiate(
                 ctx,
                 self,
                 ProjectInstantiator::new(identifier.clone(), timeout),
             )
             .await?
+            .instantiate(ctx, self, WebSocketInstantiator::new())
+            .await?
             .instantiate(ctx, self, PlainTcpInstantiator::new())
             .await?
+            .instantiate(ctx, self, UdpInstantiator::new())
+            .await?;
+        #[cfg(unix)]
+        let builder = builder
+            .instantiate(ctx, self, UdsInstantiator::new())
+            .await?;
+        let connection = builder
             .instantiate(
                 ctx,
                 self,
@@ -373,6 +416,24 @@ impl NodeManager {
         &self.tcp_transport
     }
 
+    /// Return the WebSocket transport of this node, which is created on first use
+    pub async fn websocket_transport(
+        &self,
+        ctx: &Context,
+    ) -> ockam_core::Result<&WebSocketTransport> {
+        self.websocket_transport
+            .get_or_try_init(|| WebSocketTransport::create(ctx))
+            .await
+    }
+
+    /// Return the Unix domain socket transport of this node, which is created on first use
+    #[cfg(unix)]
+    pub async fn uds_transport(&self, ctx: &Context) -> ockam_core::Result<&UdsTransport> {
+        self.uds_transport
+            .get_or_try_init(|| UdsTransport::create(ctx))
+            .await
+    }
+
     pub async fn list_outlets(&self) -> Vec<OutletStatus> {
         self.registry
             .outlets
@@ -529,13 +590,44 @@ impl NodeManager {
     ) -> ockam_core::Result<(
         Arc<dyn IncomingAccessControl>,
         Arc<dyn OutgoingAccessControl>,
+    )> {
+        self.access_control_with_attributes(
+            ctx,
+            authority,
+            resource,
+            action,
+            expression,
+            Env::new(),
+        )
+        .await
+    }
+
+    /// Create access controls for a resource and an action.
+    /// The resource attributes, like `resource.target_port`, can be used by the policy expression.
+    pub(crate) async fn access_control_with_attributes(
+        &self,
+        ctx: &Context,
+        authority: Option<Identifier>,
+        resource: Resource,
+        action: Action,
+        expression: Option<PolicyExpression>,
+        resource_attributes: Env,
+    ) -> ockam_core::Result<(
+        Arc<dyn IncomingAccessControl>,
+        Arc<dyn OutgoingAccessControl>,
     )> {
         let resource_name_str = resource.resource_name.as_str();
         let resource_type_str = resource.resource_type.to_string();
         let action_str = action.as_ref();
         if authority.is_some() || expression.is_some() {
             let policy_access_control = self
-                .policy_access_control(authority, resource, action, expression)
+                .policy_access_control_with_attributes(
+                    authority,
+                    resource,
+                    action,
+                    expression,
+                    resource_attributes,
+                )
                 .await?;
 
             let incoming_ac = policy_access_control.create_incoming();
@@ -580,22 +672,58 @@ impl NodeManager {
         resource: Resource,
         action: Action,
         expression: Option<PolicyExpression>,
+    ) -> ockam_core::Result<PolicyAccessControl> {
+        self.policy_access_control_with_attributes(
+            authority,
+            resource,
+            action,
+            expression,
+            Env::new(),
+        )
+        .await
+    }
+
+    /// Create a policy access control for a resource and an action.
+    /// The resource attributes, like `resource.target_port`, can be used by the policy expression.
+    ///
+    /// The expression, if any, is stored as the `handle_message` policy of the resource, so that
+    /// it can be managed with `ockam policy` like the policies of the other resources. The other
+    /// actions fall back to that policy.
+    pub async fn policy_access_control_with_attributes(
+        &self,
+        authority: Option<Identifier>,
+        resource: Resource,
+        action: Action,
+        expression: Option<PolicyExpression>,
+        resource_attributes: Env,
     ) -> ockam_core::Result<PolicyAccessControl> {
         let resource_name_str = resource.resource_name.as_str();
         let action_str = action.as_ref();
 
         // Populate environment with known attributes:
-        let mut env = Env::new();
-        env.put("resource.id", str(resource_name_str));
-        env.put("action.id", str(action_str));
-
-        // Store policy for the given resource and action
+        let mut env = resource_attributes;
+        env.put(resource_attribute_name(ABAC_ID_KEY), str(resource_name_str));
+        env.put(
+            resource_attribute_name(ABAC_RESOURCE_NAME_KEY),
+            str(resource_name_str),
+        );
+        env.put(
+            resource_attribute_name(ABAC_RESOURCE_TYPE_KEY),
+            str(resource.resource_type.to_string()),
+        );
+        env.put(
+            resource_attribute_name(ABAC_RESOURCE_NODE_KEY),
+            str(self.node_name.clone()),
+        );
+        env.put(action_attribute_name(ABAC_ID_KEY), str(action_str));
+
+        // Store the policy of the resource
         let policies = self.policies();
         if let Some(expression) = expression {
             policies
                 .store_policy_for_resource_name(
                     &resource.resource_name,
-                    &action,
+                    &Action::HandleMessage,
                     &expression.into(),
                 )
                 .await?;
@@ -603,13 +731,15 @@ impl NodeManager {
         self.resources().store_resource(&resource).await?;
 
         // Create the policy access control
-        Ok(policies.make_policy_access_control(
-            self.cli_state.identities_attributes(&self.node_name),
-            resource,
-            action,
-            env,
-            authority,
-        ))
+        Ok(policies
+            .make_policy_access_control(
+                self.cli_state.identities_attributes(&self.node_name),
+                resource,
+                action,
+                env,
+                authority,
+            )
+            .with_metrics(self.policy_access_control_metrics.clone()))
     }
 }
 
@@ -0,0 +1,314 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::nodes::NodeManager;
+use crate::session::connection_status::ConnectionStatus;
+use ockam::tcp::TcpPortalMetrics;
+
+impl NodeManager {
+    /// Return the node metrics in the Prometheus text exposition format.
+    ///
+    /// The router mailbox depth is passed by the caller since it must be read from a `Context`.
+    pub async fn get_metrics(&self, router_mailbox_depth: usize) -> String {
+        let mut metrics = PrometheusText::default();
+
+        let inlets: Vec<(String, Arc<TcpPortalMetrics>)> = self
+            .registry
+            .inlets
+            .entries()
+            .await
+            .into_iter()
+            .map(|(alias, info)| (alias, info.metrics))
+            .collect();
+        metrics.portals("tcp_inlet", "alias", &inlets);
+
+        let outlets: Vec<(String, Arc<TcpPortalMetrics>)> = self
+            .registry
+            .outlets
+            .values()
+            .await
+            .into_iter()
+            .map(|info| (info.worker_addr.address().to_string(), info.metrics))
+            .collect();
+        metrics.portals("tcp_outlet", "worker_address", &outlets);
+
+        let secure_channel_registry = self.secure_channels.secure_channel_registry();
+        metrics.header(
+            "ockam_secure_channels",
+            "gauge",
+            "Number of secure channels currently established",
+        );
+        metrics.sample(
+            "ockam_secure_channels",
+            &[],
+            secure_channel_registry.get_channel_list().len() as u64,
+        );
+        metrics.header(
+            "ockam_secure_channel_listeners",
+            "gauge",
+            "Number of secure channel listeners",
+        );
+        metrics.sample(
+            "ockam_secure_channel_listeners",
+            &[],
+            self.registry.secure_channel_listeners.keys().await.len() as u64,
+        );
+        metrics.header(
+            "ockam_secure_channel_handshake_failures_total",
+            "counter",
+            "Number of secure channel handshakes which failed",
+        );
+        metrics.sample(
+            "ockam_secure_channel_handshake_failures_total",
+            &[],
+            secure_channel_registry.handshake_failures() as u64,
+        );
+        metrics.header(
+            "ockam_secure_channel_handshakes_total",
+            "counter",
+            "Number of secure channel handshakes started by secure channel listeners",
+        );
+        metrics.sample(
+            "ockam_secure_channel_handshakes_total",
+            &[],
+            secure_channel_registry.handshakes_started() as u64,
+        );
+        metrics.header(
+            "ockam_secure_channel_handshakes_rejected_total",
+            "counter",
+            "Number of secure channel handshakes rejected by the limits of secure channel listeners",
+        );
+        metrics.sample(
+            "ockam_secure_channel_handshakes_rejected_total",
+            &[],
+            secure_channel_registry.handshakes_rejected() as u64,
+        );
+        metrics.header(
+            "ockam_secure_channel_handshake_retries_total",
+            "counter",
+            "Number of handshake cookies sent by secure channel listeners",
+        );
+        metrics.sample(
+            "ockam_secure_channel_handshake_retries_total",
+            &[],
+            secure_channel_registry.handshake_retries() as u64,
+        );
+        metrics.header(
+            "ockam_secure_channel_handshakes_resumed_total",
+            "counter",
+            "Number of secure channels resumed with a resumption ticket",
+        );
+        metrics.sample(
+            "ockam_secure_channel_handshakes_resumed_total",
+            &[],
+            secure_channel_registry.handshakes_resumed() as u64,
+        );
+
+        let mut relays = vec![];
+        let mut sessions = vec![];
+        for (alias, info) in self.registry.relays.entries().await {
+            let session = info.session.lock().await;
+            let is_up = session.connection_status() == ConnectionStatus::Up;
+            relays.push((alias.clone(), is_up as u64));
+            sessions.push((
+                "relay",
+                alias,
+                session.replacement_count(),
+                session.replacement_failure_count(),
+            ));
+        }
+        for (alias, info) in self.registry.inlets.entries().await {
+            let session = info.session.lock().await;
+            sessions.push((
+                "tcp_inlet",
+                alias,
+                session.replacement_count(),
+                session.replacement_failure_count(),
+            ));
+        }
+
+        metrics.header(
+            "ockam_relay_up",
+            "gauge",
+            "Whether the connection to the relay is up (1) or down (0)",
+        );
+        for (alias, is_up) in relays.iter() {
+            metrics.sample("ockam_relay_up", &[("alias", alias)], *is_up);
+        }
+
+        metrics.header(
+            "ockam_session_replacements_total",
+            "counter",
+            "Number of times a session was successfully re-established",
+        );
+        for (kind, name, replacements, _) in sessions.iter() {
+            metrics.sample(
+                "ockam_session_replacements_total",
+                &[("kind", kind), ("name", name)],
+                *replacements,
+            );
+        }
+        metrics.header(
+            "ockam_session_replacement_failures_total",
+            "counter",
+            "Number of failed attempts to re-establish a session",
+        );
+        for (kind, name, _, failures) in sessions.iter() {
+            metrics.sample(
+                "ockam_session_replacement_failures_total",
+                &[("kind", kind), ("name", name)],
+                *failures,
+            );
+        }
+
+        metrics.header(
+            "ockam_router_mailbox_depth",
+            "gauge",
+            "Number of messages waiting to be processed by the node router",
+        );
+        metrics.sample(
+            "ockam_router_mailbox_depth",
+            &[],
+            router_mailbox_depth as u64,
+        );
+
+        metrics.header(
+            "ockam_abac_denials_total",
+            "counter",
+            "Number of messages denied by an access control policy",
+        );
+        for (resource_type, denials) in self.policy_access_control_metrics.denials() {
+            metrics.sample(
+                "ockam_abac_denials_total",
+                &[("resource_type", resource_type.as_str())],
+                denials,
+            );
+        }
+
+        metrics.text
+    }
+}
+
+/// Name suffix, type, help text and value of a metric collected for each TCP portal
+type PortalMetric = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&TcpPortalMetrics) -> u64,
+);
+
+const PORTAL_METRICS: [PortalMetric; 4] = [
+    (
+        "connections_total",
+        "counter",
+        "Number of TCP connections handled by the portal",
+        TcpPortalMetrics::connections,
+    ),
+    (
+        "active_connections",
+        "gauge",
+        "Number of TCP connections currently open",
+        TcpPortalMetrics::active_connections,
+    ),
+    (
+        "received_bytes_total",
+        "counter",
+        "Number of bytes read from the TCP connections",
+        TcpPortalMetrics::bytes_received,
+    ),
+    (
+        "sent_bytes_total",
+        "counter",
+        "Number of bytes written to the TCP connections",
+        TcpPortalMetrics::bytes_sent,
+    ),
+];
+
+/// Builder for a text document following the Prometheus exposition format
+#[derive(Default)]
+struct PrometheusText {
+    text: String,
+}
+
+impl PrometheusText {
+    /// Write the metrics of a list of TCP portals, identified by the given label
+    fn portals(&mut self, kind: &str, label: &str, portals: &[(String, Arc<TcpPortalMetrics>)]) {
+        for (suffix, metric_type, help, value) in PORTAL_METRICS {
+            let name = format!("ockam_{kind}_{suffix}");
+            self.header(&name, metric_type, help);
+            for (id, metrics) in portals {
+                self.sample(&name, &[(label, id)], value(metrics));
+            }
+        }
+    }
+
+    fn header(&mut self, name: &str, metric_type: &str, help: &str) {
+        let _ = writeln!(self.text, "# HELP {name} {help}");
+        let _ = writeln!(self.text, "# TYPE {name} {metric_type}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: u64) {
+        self.text.push_str(name);
+        if !labels.is_empty() {
+            let labels = labels
+                .iter()
+                .map(|(k, v)| format!("{k}=\"{}\"", escape_label_value(v)))
+                .collect::<Vec<_>>()
+                .join(",");
+            let _ = write!(self.text, "{{{labels}}}");
+        }
+        let _ = writeln!(self.text, " {value}");
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_samples_with_and_without_labels() {
+        let mut metrics = PrometheusText::default();
+        metrics.header("ockam_relay_up", "gauge", "Relay status");
+        metrics.sample("ockam_relay_up", &[("alias", "default")], 1);
+        metrics.sample("ockam_router_mailbox_depth", &[], 3);
+
+        assert_eq!(
+            metrics.text,
+            "# HELP ockam_relay_up Relay status\n\
+             # TYPE ockam_relay_up gauge\n\
+             ockam_relay_up{alias=\"default\"} 1\n\
+             ockam_router_mailbox_depth 3\n"
+        );
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        let mut metrics = PrometheusText::default();
+        metrics.sample("m", &[("a", "x\"y\\z\n"), ("b", "c")], 0);
+        assert_eq!(metrics.text, "m{a=\"x\\\"y\\\\z\\n\",b=\"c\"} 0\n");
+    }
+
+    #[test]
+    fn write_portal_metrics() {
+        let mut metrics = PrometheusText::default();
+        let portal = Arc::new(TcpPortalMetrics::default());
+        metrics.portals("tcp_inlet", "alias", &[("db".to_string(), portal)]);
+
+        assert!(metrics
+            .text
+            .contains("# TYPE ockam_tcp_inlet_connections_total counter\n"));
+        assert!(metrics
+            .text
+            .contains("ockam_tcp_inlet_active_connections{alias=\"db\"} 0\n"));
+        assert!(metrics
+            .text
+            .contains("ockam_tcp_inlet_sent_bytes_total{alias=\"db\"} 0\n"));
+    }
+}
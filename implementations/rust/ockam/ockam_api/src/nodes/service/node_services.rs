@@ -90,6 +90,14 @@ impl NodeManagerWorker {
         }
     }
 
+    #[instrument(skip_all)]
+    pub(super) async fn reload_configuration(&self) -> Result<Response, Response<Error>> {
+        match self.node_manager.reload_configuration() {
+            Ok(_) => Ok(Response::ok()),
+            Err(e) => Err(Response::bad_request_no_request(&e.to_string())),
+        }
+    }
+
     #[instrument(skip_all)]
     pub(super) async fn get_node_resources(
         &self,
@@ -260,6 +268,19 @@ impl NodeManager {
         Ok(NodeStatus::from(&node))
     }
 
+    /// Return a receiver notified every time a reload of the node configuration is requested
+    pub fn subscribe_to_configuration_reloads(&self) -> tokio::sync::watch::Receiver<()> {
+        self.configuration_reloads.subscribe()
+    }
+
+    /// Request a reload of the configuration file the node was created with.
+    /// Fail if the node does not watch a configuration file
+    pub fn reload_configuration(&self) -> Result<()> {
+        self.configuration_reloads
+            .send(())
+            .map_err(|_| ApiError::core("The node is not watching a configuration file"))
+    }
+
     pub async fn get_node_resources(&self) -> Result<NodeResources> {
         let node = self.cli_state.get_node(&self.node_name).await?;
         let identity = self
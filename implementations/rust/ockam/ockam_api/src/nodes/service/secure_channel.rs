@@ -29,6 +29,9 @@ use crate::nodes::registry::SecureChannelInfo;
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::{NodeManager, NodeManagerWorker};
 
+/// Lifetime of the resumption tickets issued by the secure channel listeners of a node
+pub const DEFAULT_RESUMPTION_TICKET_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
 #[derive(PartialOrd, PartialEq, Debug)]
 pub enum SecureChannelType {
     KeyExchangeAndMessages,
@@ -193,6 +196,7 @@ impl NodeManager {
                 credential,
                 timeout,
                 secure_channel_type,
+                None,
             )
             .await?;
 
@@ -210,6 +214,7 @@ impl NodeManager {
         credential: Option<CredentialAndPurposeKey>,
         timeout: Option<Duration>,
         secure_channel_type: SecureChannelType,
+        resumption_name: Option<String>,
     ) -> Result<SecureChannel> {
         debug!(%sc_route, "Creating secure channel");
         let options = SecureChannelOptions::new();
@@ -247,6 +252,12 @@ impl NodeManager {
             options
         };
 
+        // Resume the secure channel when the connection is replaced
+        let options = match resumption_name {
+            Some(name) => options.with_session_resumption(name),
+            None => options,
+        };
+
         let sc = self
             .secure_channels
             .create_secure_channel(ctx, identifier, sc_route.clone(), options)
@@ -352,8 +363,9 @@ impl NodeManager {
         let vault = self.cli_state.make_vault(named_vault).await?;
         let secure_channels = self.build_secure_channels(vault).await?;
 
-        let options =
-            SecureChannelListenerOptions::new().as_consumer(&self.api_transport_flow_control_id);
+        let options = SecureChannelListenerOptions::new()
+            .as_consumer(&self.api_transport_flow_control_id)
+            .with_resumption_tickets(DEFAULT_RESUMPTION_TICKET_LIFETIME);
 
         let options = match authorized_identifiers {
             Some(ids) => options.with_trust_policy(TrustMultiIdentifiersPolicy::new(ids)),
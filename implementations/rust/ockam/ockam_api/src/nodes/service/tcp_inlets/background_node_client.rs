@@ -26,6 +26,7 @@ pub fn create_inlet_payload(
     disable_tcp_fallback: bool,
     privileged: bool,
     tls_certificate_provider: &Option<MultiAddr>,
+    socks5: bool,
 ) -> CreateInlet {
     let via_project = outlet_addr.matches(0, &[ProjectProto::CODE.into()]);
     let mut payload = if via_project {
@@ -59,6 +60,9 @@ pub fn create_inlet_payload(
     if let Some(tls_provider) = tls_certificate_provider {
         payload.set_tls_certificate_provider(tls_provider.clone())
     }
+    if socks5 {
+        payload.set_socks5();
+    }
     payload.set_wait_ms(wait_for_outlet_timeout.as_millis() as u64);
     payload
 }
@@ -80,6 +84,7 @@ impl Inlets for BackgroundNodeClient {
         disable_tcp_fallback: bool,
         privileged: bool,
         tls_certificate_provider: &Option<MultiAddr>,
+        socks5: bool,
     ) -> miette::Result<Reply<InletStatus>> {
         let request = {
             let payload = create_inlet_payload(
@@ -95,6 +100,7 @@ impl Inlets for BackgroundNodeClient {
                 disable_tcp_fallback,
                 privileged,
                 tls_certificate_provider,
+                socks5,
             );
             Request::post("/node/inlet").body(payload)
         };
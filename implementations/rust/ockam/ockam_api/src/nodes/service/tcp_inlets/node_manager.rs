@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use crate::address::get_free_address_for;
 use ockam::identity::Identifier;
+use ockam::tcp::TcpPortalMetrics;
 use ockam::Result;
 use ockam_abac::{PolicyExpression, Resource, ResourceType};
 use ockam_core::errcode::{Kind, Origin};
@@ -41,6 +42,51 @@ impl NodeManager {
         disable_tcp_fallback: bool,
         privileged: bool,
         tls_certificate_provider: Option<MultiAddr>,
+    ) -> Result<InletStatus> {
+        self.create_inlet_internal(
+            ctx,
+            listen_addr,
+            prefix_route,
+            suffix_route,
+            outlet_addr,
+            alias,
+            policy_expression,
+            wait_for_outlet_duration,
+            authorized,
+            wait_connection,
+            secure_channel_identifier,
+            enable_udp_puncture,
+            disable_tcp_fallback,
+            privileged,
+            tls_certificate_provider,
+            false,
+        )
+        .await
+    }
+
+    /// Create an inlet which can also read the destination of each connection
+    /// from a SOCKS5 request, to be sent to a dynamic outlet
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub(crate) async fn create_inlet_internal(
+        self: &Arc<Self>,
+        ctx: &Context,
+        listen_addr: HostnamePort,
+        prefix_route: Route,
+        suffix_route: Route,
+        outlet_addr: MultiAddr,
+        alias: String,
+        policy_expression: Option<PolicyExpression>,
+        wait_for_outlet_duration: Option<Duration>,
+        authorized: Option<Identifier>,
+        wait_connection: bool,
+        secure_channel_identifier: Option<Identifier>,
+        enable_udp_puncture: bool,
+        // TODO: Introduce mode enum
+        disable_tcp_fallback: bool,
+        privileged: bool,
+        tls_certificate_provider: Option<MultiAddr>,
+        socks5: bool,
     ) -> Result<InletStatus> {
         info!("Handling request to create inlet portal");
         debug! {
@@ -51,9 +97,18 @@ impl NodeManager {
             %alias,
             %enable_udp_puncture,
             %disable_tcp_fallback,
+            %socks5,
             "Creating inlet portal"
         }
 
+        if socks5 && (privileged || tls_certificate_provider.is_some()) {
+            return Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "A SOCKS5 inlet can't be privileged or use TLS",
+            ));
+        }
+
         let udp_transport = if enable_udp_puncture {
             Some(self.udp_transport.clone().ok_or_else(|| {
                 ockam_core::Error::new(
@@ -108,6 +163,7 @@ impl NodeManager {
             }
         }
 
+        let metrics = Arc::new(TcpPortalMetrics::default());
         let replacer = InletSessionReplacer {
             node_manager: Arc::downgrade(self),
             udp_transport,
@@ -123,6 +179,8 @@ impl NodeManager {
             secure_channel_identifier,
             disable_tcp_fallback,
             tls_certificate_provider,
+            socks5,
+            metrics: metrics.clone(),
             inlet: None,
             connection: None,
             main_route: None,
@@ -193,6 +251,7 @@ impl NodeManager {
                     outlet_addr.clone(),
                     session,
                     privileged,
+                    metrics,
                 ),
             )
             .await;
@@ -30,10 +30,11 @@ impl NodeManagerWorker {
             disable_tcp_fallback,
             privileged,
             tls_certificate_provider,
+            socks5,
         } = create_inlet;
         match self
             .node_manager
-            .create_inlet(
+            .create_inlet_internal(
                 ctx,
                 listen_addr,
                 route![],
@@ -49,6 +50,7 @@ impl NodeManagerWorker {
                 disable_tcp_fallback,
                 privileged,
                 tls_certificate_provider,
+                socks5,
             )
             .await
         {
@@ -7,7 +7,7 @@ use tokio::time::timeout;
 
 use crate::DefaultAddress;
 use ockam::identity::{Identifier, SecureChannel};
-use ockam::tcp::TcpInletOptions;
+use ockam::tcp::{TcpInletOptions, TcpPortalMetrics};
 use ockam::udp::{UdpPuncture, UdpPunctureNegotiation, UdpTransport};
 use ockam::Result;
 use ockam_abac::{Action, PolicyExpression, Resource};
@@ -42,6 +42,8 @@ pub(super) struct InletSessionReplacer {
     pub(super) secure_channel_identifier: Option<Identifier>,
     pub(super) disable_tcp_fallback: bool,
     pub(super) tls_certificate_provider: Option<MultiAddr>,
+    pub(super) socks5: bool,
+    pub(super) metrics: Arc<TcpPortalMetrics>,
 
     // current status
     pub(super) inlet: Option<Arc<TcpInlet>>,
@@ -106,6 +108,7 @@ impl InletSessionReplacer {
     async fn inlet_options(&self, node_manager: &NodeManager) -> Result<TcpInletOptions> {
         let (incoming_ac, outgoing_ac) = self.access_control(node_manager).await?;
         let options = TcpInletOptions::new()
+            .with_metrics(self.metrics.clone())
             .with_incoming_access_control(incoming_ac)
             .with_outgoing_access_control(outgoing_ac);
 
@@ -123,6 +126,12 @@ impl InletSessionReplacer {
             options
         };
 
+        let options = if self.socks5 {
+            options.socks5()
+        } else {
+            options
+        };
+
         Ok(options)
     }
 
@@ -357,6 +366,7 @@ impl AdditionalSessionReplacer for InletSessionReplacer {
                 // TODO: Have a dedicated timeout
                 Some(Duration::from_secs(10)),
                 SecureChannelType::KeyExchangeAndMessages,
+                None,
             )
             .await?;
         let additional_sc = self.additional_secure_channel.insert(additional_sc);
@@ -1,13 +1,31 @@
-use ockam::tcp::TcpOutletOptions;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use ockam::identity::{Identifier, IdentitiesAttributes};
+use ockam::tcp::{
+    DestinationAccessControl, DestinationAllowlist, DestinationPattern, IdentityAttributesProvider,
+    TcpOutletOptions, TcpPortalMetrics,
+};
 use ockam::transport::HostnamePort;
 use ockam::{Address, Result};
-use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
+use ockam_abac::expr::{int, str};
+use ockam_abac::{
+    resource_attribute_name, Action, Env, Expr, PolicyAccessControl, PolicyExpression, Resource,
+    ResourceType, ABAC_RESOURCE_DESTINATION_HOST_KEY, ABAC_RESOURCE_DESTINATION_KEY,
+    ABAC_RESOURCE_DESTINATION_PORT_KEY, ABAC_RESOURCE_TARGET_HOST_KEY,
+    ABAC_RESOURCE_TARGET_PORT_KEY,
+};
 use ockam_core::api::{Error, Request, RequestHeader, Response};
-use ockam_core::async_trait;
 use ockam_core::errcode::{Kind, Origin};
+use ockam_core::flow_control::{FlowControlId, FlowControls};
+use ockam_core::{async_trait, IncomingAccessControl, LocalInfoIdentifier, OutgoingAccessControl};
 use ockam_node::Context;
+use ockam_transport_tcp::PortalOutletInterceptor;
 
-use crate::nodes::models::portal::{CreateOutlet, OutletAccessControl, OutletStatus};
+use crate::http_interceptor::HttpIdentityInterceptorFactory;
+use crate::nodes::models::portal::{
+    CreateOutlet, HttpIdentityHeaders, OutletAccessControl, OutletStatus,
+};
 use crate::nodes::registry::OutletInfo;
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::BackgroundNodeClient;
@@ -28,11 +46,27 @@ impl NodeManagerWorker {
             policy_expression,
             tls,
             privileged,
+            allowed_destinations,
+            proxy_protocol_attributes,
+            http_identity_headers,
         } = create_outlet;
 
+        let allowed_destinations = match allowed_destinations
+            .map(|destinations| {
+                destinations
+                    .iter()
+                    .map(|d| d.parse())
+                    .collect::<Result<Vec<DestinationPattern>>>()
+            })
+            .transpose()
+        {
+            Ok(destinations) => destinations.map(DestinationAllowlist::new),
+            Err(e) => return Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        };
+
         match self
             .node_manager
-            .create_outlet(
+            .create_outlet_internal(
                 ctx,
                 hostname_port,
                 tls,
@@ -40,6 +74,9 @@ impl NodeManagerWorker {
                 reachable_from_default_secure_channel,
                 OutletAccessControl::WithPolicyExpression(policy_expression),
                 privileged,
+                allowed_destinations,
+                proxy_protocol_attributes,
+                http_identity_headers,
             )
             .await
         {
@@ -100,6 +137,47 @@ impl NodeManager {
         reachable_from_default_secure_channel: bool,
         access_control: OutletAccessControl,
         privileged: bool,
+    ) -> Result<OutletStatus> {
+        self.create_outlet_internal(
+            ctx,
+            to,
+            tls,
+            worker_addr,
+            reachable_from_default_secure_channel,
+            access_control,
+            privileged,
+            None,
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Create an outlet which is dynamic if some allowed destinations are given:
+    /// SOCKS5 inlets can then ask it to connect to any destination in the allowlist,
+    /// if that destination is also authorized by the outlet policy for the `connect` action.
+    ///
+    /// If some PROXY protocol attributes are given, the outlet sends a PROXY protocol header
+    /// to its destination, with the identifier of the inlet and the values of these attributes
+    /// in its credential.
+    ///
+    /// If some HTTP identity headers are configured, the HTTP requests sent to the destination
+    /// go through an interceptor, started at the outlet address, which sets the identifier and
+    /// the credential attributes of the inlet as headers
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub(crate) async fn create_outlet_internal(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        tls: bool,
+        worker_addr: Option<Address>,
+        reachable_from_default_secure_channel: bool,
+        access_control: OutletAccessControl,
+        privileged: bool,
+        allowed_destinations: Option<DestinationAllowlist>,
+        proxy_protocol_attributes: Option<Vec<String>>,
+        http_identity_headers: Option<HttpIdentityHeaders>,
     ) -> Result<OutletStatus> {
         let worker_addr = self
             .registry
@@ -122,52 +200,162 @@ impl NodeManager {
             ));
         }
 
-        let (incoming_ac, outgoing_ac) = match access_control {
-            OutletAccessControl::AccessControl((incoming_ac, outgoing_ac)) => {
-                (incoming_ac, outgoing_ac)
-            }
-            OutletAccessControl::WithPolicyExpression(expression) => {
-                self.access_control(
-                    ctx,
-                    self.project_authority(),
-                    Resource::new(worker_addr.address(), ResourceType::TcpOutlet),
-                    Action::HandleMessage,
-                    expression,
-                )
-                .await?
+        if allowed_destinations.is_some() && privileged {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "A privileged TCP outlet can't be dynamic",
+            ));
+        }
+
+        if proxy_protocol_attributes.is_some() && privileged {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "A privileged TCP outlet can't send a PROXY protocol header",
+            ));
+        }
+
+        if http_identity_headers.is_some() && privileged {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "A privileged TCP outlet can't intercept HTTP requests",
+            ));
+        }
+
+        let resource = Resource::new(worker_addr.address(), ResourceType::TcpOutlet);
+        let (incoming_ac, outgoing_ac, destination_access_control) =
+            match (access_control, allowed_destinations) {
+                (OutletAccessControl::AccessControl((incoming_ac, outgoing_ac)), None) => {
+                    (incoming_ac, outgoing_ac, None)
+                }
+                (
+                    OutletAccessControl::AccessControl((incoming_ac, outgoing_ac)),
+                    Some(allowlist),
+                ) => {
+                    let destination_access_control =
+                        PolicyDestinationAccessControl::new(allowlist, None);
+                    (incoming_ac, outgoing_ac, Some(destination_access_control))
+                }
+                (OutletAccessControl::WithPolicyExpression(expression), None) => {
+                    let (incoming_ac, outgoing_ac) = self
+                        .access_control_with_attributes(
+                            ctx,
+                            self.project_authority(),
+                            resource,
+                            Action::Connect,
+                            expression,
+                            outlet_resource_attributes(&to),
+                        )
+                        .await?;
+                    (incoming_ac, outgoing_ac, None)
+                }
+                (OutletAccessControl::WithPolicyExpression(expression), Some(allowlist)) => {
+                    // The destination is only known when a connection is requested, so the policy
+                    // for the `connect` action is evaluated for each destination. Messages are
+                    // checked with the policy for the `handle_message` action, hence the
+                    // expression, which can use the destination attributes, is only stored as
+                    // the `connect` policy of the outlet
+                    let has_expression = expression.is_some();
+                    if let Some(expression) = expression {
+                        self.policies()
+                            .store_policy_for_resource_name(
+                                &resource.resource_name,
+                                &Action::Connect,
+                                &expression.into(),
+                            )
+                            .await?;
+                    }
+                    let (incoming_ac, outgoing_ac) = self
+                        .access_control_with_attributes(
+                            ctx,
+                            self.project_authority(),
+                            resource.clone(),
+                            Action::HandleMessage,
+                            None,
+                            outlet_resource_attributes(&to),
+                        )
+                        .await?;
+                    let policy_access_control =
+                        if self.project_authority().is_some() || has_expression {
+                            Some(
+                                self.policy_access_control_with_attributes(
+                                    self.project_authority(),
+                                    resource,
+                                    Action::Connect,
+                                    None,
+                                    outlet_resource_attributes(&to),
+                                )
+                                .await?,
+                            )
+                        } else {
+                            None
+                        };
+                    let destination_access_control =
+                        PolicyDestinationAccessControl::new(allowlist, policy_access_control);
+                    (incoming_ac, outgoing_ac, Some(destination_access_control))
+                }
+            };
+
+        // Flow controls of the secure channels which can reach the outlet
+        let mut consumer_flow_control_ids = vec![];
+        if self.project_authority().is_none() {
+            consumer_flow_control_ids.push(self.api_transport_flow_control_id.clone());
+        }
+        if reachable_from_default_secure_channel {
+            // Accept messages from the default secure channel listener
+            if let Some(flow_control_id) = ctx
+                .flow_controls()
+                .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+            {
+                consumer_flow_control_ids.push(flow_control_id);
             }
-        };
+        }
 
+        let metrics = Arc::new(TcpPortalMetrics::default());
         let options = {
             let options = TcpOutletOptions::new()
-                .with_incoming_access_control(incoming_ac)
-                .with_outgoing_access_control(outgoing_ac)
+                .with_metrics(metrics.clone())
                 .with_tls(tls);
-            let options = if self.project_authority().is_none() {
-                options.as_consumer(&self.api_transport_flow_control_id)
+            let options = if let Some(destination_access_control) = destination_access_control {
+                options.with_destination_access_control(Arc::new(destination_access_control))
             } else {
                 options
             };
-            if reachable_from_default_secure_channel {
-                // Accept messages from the default secure channel listener
-                if let Some(flow_control_id) = ctx
-                    .flow_controls()
-                    .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
-                {
-                    options.as_consumer(&flow_control_id)
-                } else {
-                    options
-                }
+            if let Some(attribute_names) = proxy_protocol_attributes {
+                options.with_proxy_protocol_attributes(Arc::new(CredentialAttributesProvider::new(
+                    self.secure_channels.identities().identities_attributes(),
+                    self.project_authority(),
+                    attribute_names,
+                )))
             } else {
                 options
             }
         };
 
+        // With an HTTP interceptor, the access controls and the flow controls of the outlet
+        // are applied to the interceptor, and the TCP outlet only accepts messages from it
+        let http_interceptor = http_identity_headers
+            .map(|headers| (headers, FlowControls::generate_flow_control_id()));
+        let (options, outlet_addr) = if let Some((_, spawner_flow_control_id)) = &http_interceptor {
+            let outlet_addr: Address = format!("{}_outlet", worker_addr.address()).into();
+            (options.as_consumer(spawner_flow_control_id), outlet_addr)
+        } else {
+            let options = options
+                .with_incoming_access_control(incoming_ac.clone())
+                .with_outgoing_access_control(outgoing_ac.clone());
+            let options = consumer_flow_control_ids
+                .iter()
+                .fold(options, |options, id| options.as_consumer(id));
+            (options, worker_addr.clone())
+        };
+
         let res = if privileged {
             #[cfg(privileged_portals_support)]
             {
                 self.tcp_transport
-                    .create_privileged_outlet(worker_addr.clone(), to.clone(), options)
+                    .create_privileged_outlet(outlet_addr.clone(), to.clone(), options)
                     .await
             }
             #[cfg(not(privileged_portals_support))]
@@ -180,19 +368,45 @@ impl NodeManager {
             }
         } else {
             self.tcp_transport
-                .create_outlet(worker_addr.clone(), to.clone(), options)
+                .create_outlet(outlet_addr.clone(), to.clone(), options)
                 .await
         };
 
+        let res = match (res, http_interceptor) {
+            (Ok(_), Some((http_identity_headers, spawner_flow_control_id))) => {
+                let res = self
+                    .create_http_identity_interceptor(
+                        ctx,
+                        worker_addr.clone(),
+                        outlet_addr.clone(),
+                        http_identity_headers,
+                        spawner_flow_control_id,
+                        &consumer_flow_control_ids,
+                        incoming_ac,
+                        outgoing_ac,
+                    )
+                    .await;
+                if res.is_err() {
+                    let _ = self.tcp_transport.stop_outlet(outlet_addr.clone()).await;
+                }
+                res
+            }
+            (res, _) => res.map(|_| ()),
+        };
+
         Ok(match res {
             Ok(_) => {
                 // TODO: Use better way to store outlets?
+                let outlet_info =
+                    OutletInfo::new(to.clone(), Some(&worker_addr), privileged, metrics);
+                let outlet_info = if outlet_addr != worker_addr {
+                    outlet_info.with_intercepted_outlet_addr(outlet_addr)
+                } else {
+                    outlet_info
+                };
                 self.registry
                     .outlets
-                    .insert(
-                        worker_addr.clone(),
-                        OutletInfo::new(to.clone(), Some(&worker_addr), privileged),
-                    )
+                    .insert(worker_addr.clone(), outlet_info)
                     .await;
 
                 self.cli_state
@@ -230,6 +444,11 @@ impl NodeManager {
             {
                 warn!(%worker_addr, %e, "Failed to stop outlet worker");
             }
+            if let Some(outlet_addr) = &deleted_outlet.intercepted_outlet_addr {
+                if let Err(e) = self.tcp_transport.stop_outlet(outlet_addr.clone()).await {
+                    warn!(%outlet_addr, %e, "Failed to stop intercepted outlet worker");
+                }
+            }
             trace!(%worker_addr, "Successfully stopped outlet");
             Ok(Some(deleted_outlet))
         } else {
@@ -238,6 +457,60 @@ impl NodeManager {
         }
     }
 
+    /// Start an HTTP interceptor setting the identity headers in front of the TCP outlet
+    #[allow(clippy::too_many_arguments)]
+    async fn create_http_identity_interceptor(
+        &self,
+        ctx: &Context,
+        interceptor_addr: Address,
+        outlet_addr: Address,
+        http_identity_headers: HttpIdentityHeaders,
+        spawner_flow_control_id: FlowControlId,
+        consumer_flow_control_ids: &[FlowControlId],
+        incoming_ac: Arc<dyn IncomingAccessControl>,
+        outgoing_ac: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<()> {
+        let HttpIdentityHeaders {
+            attributes,
+            hmac_key,
+        } = http_identity_headers;
+
+        let factory = HttpIdentityInterceptorFactory::new();
+        let factory = if attributes.is_empty() {
+            factory
+        } else {
+            factory.with_attributes_provider(Arc::new(CredentialAttributesProvider::new(
+                self.secure_channels.identities().identities_attributes(),
+                self.project_authority(),
+                attributes,
+            )))
+        };
+        let factory = if let Some(hmac_key) = hmac_key {
+            factory.with_hmac_key(hmac_key.into_bytes())
+        } else {
+            factory
+        };
+
+        PortalOutletInterceptor::create_for_outlet(
+            ctx,
+            interceptor_addr.clone(),
+            outlet_addr,
+            Some(spawner_flow_control_id.clone()),
+            Arc::new(factory),
+            outgoing_ac,
+            incoming_ac,
+        )
+        .await?;
+
+        let flow_controls = ctx.flow_controls();
+        for flow_control_id in consumer_flow_control_ids {
+            flow_controls.add_consumer(interceptor_addr.clone(), flow_control_id);
+        }
+        // The interceptor spawns the workers sending messages to the TCP outlet
+        flow_controls.add_spawner(interceptor_addr, &spawner_flow_control_id);
+        Ok(())
+    }
+
     pub(super) async fn show_outlet(&self, worker_addr: &Address) -> Option<OutletStatus> {
         info!(%worker_addr, "Handling request to show outlet portal");
         if let Some(outlet_to_show) = self.registry.outlets.get(worker_addr).await {
@@ -255,8 +528,181 @@ impl NodeManager {
     }
 }
 
+/// Attributes of an outlet which can be used in a policy expression
+pub(crate) fn outlet_resource_attributes(to: &HostnamePort) -> Env {
+    let mut env = Env::new();
+    env.put(
+        resource_attribute_name(ABAC_RESOURCE_TARGET_HOST_KEY),
+        str(to.hostname()),
+    );
+    env.put(
+        resource_attribute_name(ABAC_RESOURCE_TARGET_PORT_KEY),
+        int(to.port()),
+    );
+    env
+}
+
+/// Access control for the destinations requested from a dynamic outlet.
+///
+/// A destination must match the allowlist, so that an empty allowlist denies every destination,
+/// and be authorized by the policy for the `connect` action, if any, where the destination
+/// attributes can be used.
+#[derive(Debug)]
+pub(crate) struct PolicyDestinationAccessControl {
+    allowlist: DestinationAllowlist,
+    policy_access_control: Option<PolicyAccessControl>,
+}
+
+impl PolicyDestinationAccessControl {
+    fn new(
+        allowlist: DestinationAllowlist,
+        policy_access_control: Option<PolicyAccessControl>,
+    ) -> Self {
+        Self {
+            allowlist,
+            policy_access_control,
+        }
+    }
+}
+
+#[async_trait]
+impl DestinationAccessControl for PolicyDestinationAccessControl {
+    async fn is_authorized(
+        &self,
+        their_identifier: Option<&LocalInfoIdentifier>,
+        destination: &HostnamePort,
+    ) -> Result<bool> {
+        if !self.allowlist.is_allowed(destination) {
+            return Ok(false);
+        }
+
+        match (&self.policy_access_control, their_identifier) {
+            (None, _) => Ok(true),
+            (Some(policy_access_control), Some(their_identifier)) => {
+                policy_access_control
+                    .is_identity_authorized_with_attributes(
+                        &their_identifier.clone().into(),
+                        destination_resource_attributes(destination),
+                    )
+                    .await
+            }
+            // A policy can only be evaluated for an identity
+            (Some(_), None) => Ok(false),
+        }
+    }
+}
+
+/// Attributes of a destination requested from a dynamic outlet,
+/// which can be used in a policy expression
+pub(crate) fn destination_resource_attributes(destination: &HostnamePort) -> Env {
+    let mut env = Env::new();
+    env.put(
+        resource_attribute_name(ABAC_RESOURCE_DESTINATION_KEY),
+        str(destination.to_string()),
+    );
+    env.put(
+        resource_attribute_name(ABAC_RESOURCE_DESTINATION_HOST_KEY),
+        str(destination.hostname()),
+    );
+    env.put(
+        resource_attribute_name(ABAC_RESOURCE_DESTINATION_PORT_KEY),
+        int(destination.port()),
+    );
+    env
+}
+
+/// Return true if the policy expression uses one of the attributes of the destination
+/// requested from a dynamic outlet
+pub fn refers_to_destination(expression: &PolicyExpression) -> bool {
+    let destination_attributes = [
+        ABAC_RESOURCE_DESTINATION_KEY,
+        ABAC_RESOURCE_DESTINATION_HOST_KEY,
+        ABAC_RESOURCE_DESTINATION_PORT_KEY,
+    ]
+    .map(resource_attribute_name);
+
+    let mut exprs = vec![expression.to_expression()];
+    while let Some(expr) = exprs.pop() {
+        match expr {
+            Expr::Ident(ident) if destination_attributes.contains(&ident) => return true,
+            Expr::Seq(xs) | Expr::List(xs) => exprs.extend(xs),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Provide the credential attributes of an inlet identity, attested by the project authority,
+/// for the PROXY protocol header or the HTTP identity headers sent by an outlet.
+///
+/// Only the attributes with the configured names are returned.
+pub(crate) struct CredentialAttributesProvider {
+    identities_attributes: Arc<IdentitiesAttributes>,
+    authority: Option<Identifier>,
+    attribute_names: Vec<String>,
+}
+
+impl Debug for CredentialAttributesProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CredentialAttributesProvider")
+            .field("authority", &self.authority)
+            .field("attribute_names", &self.attribute_names)
+            .finish()
+    }
+}
+
+impl CredentialAttributesProvider {
+    fn new(
+        identities_attributes: Arc<IdentitiesAttributes>,
+        authority: Option<Identifier>,
+        attribute_names: Vec<String>,
+    ) -> Self {
+        Self {
+            identities_attributes,
+            authority,
+            attribute_names,
+        }
+    }
+}
+
+#[async_trait]
+impl IdentityAttributesProvider for CredentialAttributesProvider {
+    async fn get_attributes(
+        &self,
+        their_identifier: &LocalInfoIdentifier,
+    ) -> Result<Vec<(String, String)>> {
+        let Some(authority) = &self.authority else {
+            return Ok(vec![]);
+        };
+        if self.attribute_names.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let entry = self
+            .identities_attributes
+            .get_attributes(&their_identifier.clone().into(), authority)
+            .await?;
+        let Some(entry) = entry else {
+            return Ok(vec![]);
+        };
+
+        Ok(entry
+            .attrs()
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = String::from_utf8(name.clone()).ok()?;
+                let value = String::from_utf8(value.clone()).ok()?;
+                self.attribute_names
+                    .contains(&name)
+                    .then_some((name, value))
+            })
+            .collect())
+    }
+}
+
 #[async_trait]
 pub trait Outlets {
+    #[allow(clippy::too_many_arguments)]
     async fn create_outlet(
         &self,
         ctx: &Context,
@@ -265,11 +711,15 @@ pub trait Outlets {
         from: Option<&Address>,
         policy_expression: Option<PolicyExpression>,
         privileged: bool,
+        allowed_destinations: Option<Vec<DestinationPattern>>,
+        proxy_protocol_attributes: Option<Vec<String>>,
+        http_identity_headers: Option<HttpIdentityHeaders>,
     ) -> miette::Result<OutletStatus>;
 }
 
 #[async_trait]
 impl Outlets for BackgroundNodeClient {
+    #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all, fields(to = % to, from = ? from))]
     async fn create_outlet(
         &self,
@@ -279,13 +729,265 @@ impl Outlets for BackgroundNodeClient {
         from: Option<&Address>,
         policy_expression: Option<PolicyExpression>,
         privileged: bool,
+        allowed_destinations: Option<Vec<DestinationPattern>>,
+        proxy_protocol_attributes: Option<Vec<String>>,
+        http_identity_headers: Option<HttpIdentityHeaders>,
     ) -> miette::Result<OutletStatus> {
         let mut payload = CreateOutlet::new(to, tls, from.cloned(), true, privileged);
         if let Some(policy_expression) = policy_expression {
             payload.set_policy_expression(policy_expression);
         }
+        if let Some(allowed_destinations) = allowed_destinations {
+            payload.set_allowed_destinations(allowed_destinations);
+        }
+        if let Some(proxy_protocol_attributes) = proxy_protocol_attributes {
+            payload.set_proxy_protocol_attributes(proxy_protocol_attributes);
+        }
+        if let Some(http_identity_headers) = http_identity_headers {
+            payload.set_http_identity_headers(http_identity_headers);
+        }
         let req = Request::post("/node/outlet").body(payload);
         let result: OutletStatus = self.ask(ctx, req).await?;
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_interceptor::BAD_REQUEST_RESPONSE;
+    use crate::test_utils::{start_manager_for_tests, start_tcp_echo_server};
+    use ockam::identity::utils::now;
+    use ockam::identity::AttributesEntry;
+    use ockam_core::{route, AllowAll};
+    use ockam_multiaddr::MultiAddr;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    #[ockam_macros::test]
+    async fn destinations_must_match_the_allowlist_and_the_policy(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handle = start_manager_for_tests(context, None, None).await?;
+        let node_manager = &handle.node_manager;
+
+        let policy_access_control = node_manager
+            .policy_access_control_with_attributes(
+                None,
+                Resource::new("dynamic-outlet", ResourceType::TcpOutlet),
+                Action::Connect,
+                Some(PolicyExpression::from_str(
+                    "(= resource.destination_port 5432)",
+                )?),
+                Env::new(),
+            )
+            .await?;
+        let access_control = PolicyDestinationAccessControl::new(
+            DestinationAllowlist::new(vec!["*.internal:*".parse()?]),
+            Some(policy_access_control),
+        );
+
+        let identifier = LocalInfoIdentifier::from(node_manager.identifier());
+        let is_authorized = |host: &str, port: u16| {
+            let destination = HostnamePort::new(host, port);
+            let access_control = &access_control;
+            let identifier = identifier.clone();
+            async move {
+                access_control
+                    .is_authorized(Some(&identifier), &destination)
+                    .await
+            }
+        };
+
+        assert!(is_authorized("db.internal", 5432).await?);
+        assert!(!is_authorized("db.internal", 5433).await?);
+        assert!(!is_authorized("db.example.com", 5432).await?);
+        assert!(
+            !access_control
+                .is_authorized(None, &HostnamePort::new("db.internal", 5432))
+                .await?
+        );
+
+        Ok(())
+    }
+
+    #[ockam_macros::test]
+    async fn an_empty_allowlist_denies_every_destination(context: &mut Context) -> Result<()> {
+        let handle = start_manager_for_tests(context, None, None).await?;
+        let node_manager = &handle.node_manager;
+
+        let policy_access_control = node_manager
+            .policy_access_control_with_attributes(
+                None,
+                Resource::new("dynamic-outlet", ResourceType::TcpOutlet),
+                Action::Connect,
+                Some(PolicyExpression::from_str("true")?),
+                Env::new(),
+            )
+            .await?;
+        let identifier = LocalInfoIdentifier::from(node_manager.identifier());
+        let destination = HostnamePort::new("db.internal", 5432);
+
+        for policy_access_control in [None, Some(policy_access_control)] {
+            let access_control = PolicyDestinationAccessControl::new(
+                DestinationAllowlist::default(),
+                policy_access_control,
+            );
+            assert!(
+                !access_control
+                    .is_authorized(Some(&identifier), &destination)
+                    .await?
+            );
+            assert!(!access_control.is_authorized(None, &destination).await?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn policy_expressions_referring_to_the_destination() -> Result<()> {
+        for expression in [
+            "(= resource.destination_port 5432)",
+            "(and (= subject.role \"admin\") (= resource.destination_host \"db.internal\"))",
+            "(= resource.destination \"db.internal:5432\")",
+        ] {
+            assert!(refers_to_destination(&PolicyExpression::from_str(
+                expression
+            )?));
+        }
+        for expression in [
+            "(= subject.role \"admin\")",
+            "role",
+            "(= resource.target_port 5432)",
+        ] {
+            assert!(!refers_to_destination(&PolicyExpression::from_str(
+                expression
+            )?));
+        }
+        Ok(())
+    }
+
+    #[ockam_macros::test]
+    async fn only_the_selected_attributes_are_sent_in_the_proxy_protocol_header(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handle = start_manager_for_tests(context, None, None).await?;
+        let node_manager = &handle.node_manager;
+        let identities_attributes = node_manager
+            .secure_channels
+            .identities()
+            .identities_attributes();
+
+        let authority = node_manager.identifier();
+        let subject = handle.cli_state.create_identity_with_name("inlet").await?;
+        let attributes = BTreeMap::from([
+            (b"role".to_vec(), b"admin".to_vec()),
+            (b"team".to_vec(), b"payroll".to_vec()),
+        ]);
+        identities_attributes
+            .put_attributes(
+                &subject.identifier(),
+                AttributesEntry::new(attributes, now()?, None, Some(authority.clone())),
+            )
+            .await?;
+
+        let provider = CredentialAttributesProvider::new(
+            identities_attributes.clone(),
+            Some(authority),
+            vec!["role".into()],
+        );
+        let their_identifier = LocalInfoIdentifier::from(subject.identifier());
+        assert_eq!(
+            provider.get_attributes(&their_identifier).await?,
+            vec![("role".to_string(), "admin".to_string())]
+        );
+
+        // Without an authority, the attributes can't be trusted
+        let provider =
+            CredentialAttributesProvider::new(identities_attributes, None, vec!["role".into()]);
+        assert!(provider.get_attributes(&their_identifier).await?.is_empty());
+        Ok(())
+    }
+
+    #[ockam_macros::test]
+    async fn http_requests_get_the_identity_headers_of_the_inlet(
+        context: &mut Context,
+    ) -> Result<()> {
+        let echo_server_handle = start_tcp_echo_server().await;
+        let handle = start_manager_for_tests(context, None, None).await?;
+        let node_manager = &handle.node_manager;
+
+        node_manager
+            .create_outlet_internal(
+                context,
+                echo_server_handle.chosen_addr.clone(),
+                false,
+                Some(Address::from_string("http-outlet")),
+                true,
+                OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                false,
+                None,
+                None,
+                Some(HttpIdentityHeaders::default()),
+            )
+            .await?;
+
+        let inlet_status = node_manager
+            .create_inlet(
+                context,
+                HostnamePort::new("127.0.0.1", 0),
+                route![],
+                route![],
+                MultiAddr::from_str("/secure/api/service/http-outlet")?,
+                "alias".to_string(),
+                None,
+                None,
+                None,
+                true,
+                None,
+                false,
+                false,
+                false,
+                None,
+            )
+            .await?;
+
+        // The echo server returns the request received from the outlet
+        let request = "GET / HTTP/1.1\r\nHost: localhost\r\nX-Ockam-Identifier: Ispoofed\r\n\r\n";
+        let expected = format!(
+            "GET / HTTP/1.1\r\nHost: localhost\r\nX-Ockam-Identifier: {}\r\n\r\n",
+            node_manager.identifier()
+        );
+        let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+        socket.write_all(request.as_bytes()).await.unwrap();
+        let mut buf = vec![0u8; expected.len()];
+        socket.read_exact(&mut buf).await.unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+
+        // An ambiguous request is answered with an error and the connection is closed
+        let request =
+            "POST / HTTP/1.1\r\nContent-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n";
+        socket.write_all(request.as_bytes()).await.unwrap();
+        let mut response = vec![];
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            socket.read_to_end(&mut response),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(response, BAD_REQUEST_RESPONSE);
+
+        // The TCP outlet behind the interceptor is stopped when the outlet is deleted
+        let deleted_outlet = node_manager
+            .delete_outlet(&Address::from_string("http-outlet"))
+            .await?
+            .unwrap();
+        assert_eq!(
+            deleted_outlet.intercepted_outlet_addr,
+            Some(Address::from_string("http-outlet_outlet"))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,475 @@
+use std::time::Duration;
+
+use ockam::identity::Identifier;
+use ockam::transport::HostnamePort;
+use ockam::udp::{UdpInletOptions, UdpOutletOptions, UdpTransport};
+use ockam::{Address, Result};
+use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
+use ockam_core::api::{Error, Request, Response};
+use ockam_core::async_trait;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::cli_state::random_name;
+use crate::nodes::models::portal::{CreateUdpInlet, CreateUdpOutlet, InletStatus, OutletStatus};
+use crate::nodes::registry::{UdpInletInfo, UdpOutletInfo};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::tcp_outlets::outlet_resource_attributes;
+use crate::nodes::BackgroundNodeClient;
+use crate::session::connection_status::ConnectionStatus;
+
+use super::{NodeManager, NodeManagerWorker};
+
+/// Address of a UDP outlet created without an address, if not in use
+pub const DEFAULT_UDP_OUTLET_ADDRESS: &str = "udp_outlet";
+
+impl NodeManagerWorker {
+    #[instrument(skip_all)]
+    pub(super) async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        create_inlet: CreateUdpInlet,
+    ) -> Result<Response<InletStatus>, Response<Error>> {
+        let CreateUdpInlet {
+            listen_addr,
+            outlet_addr,
+            alias,
+            authorized,
+            policy_expression,
+            idle_timeout,
+        } = create_inlet;
+        match self
+            .node_manager
+            .create_udp_inlet(
+                ctx,
+                listen_addr,
+                outlet_addr,
+                alias,
+                authorized,
+                policy_expression,
+                idle_timeout,
+            )
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn delete_udp_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> Result<Response<InletStatus>, Response<Error>> {
+        match self.node_manager.delete_udp_inlet(ctx, alias).await {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn get_udp_inlets(
+        &self,
+    ) -> Result<Response<Vec<InletStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_udp_inlets().await))
+    }
+
+    #[instrument(skip_all)]
+    pub(super) async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        create_outlet: CreateUdpOutlet,
+    ) -> Result<Response<OutletStatus>, Response<Error>> {
+        let CreateUdpOutlet {
+            hostname_port,
+            worker_addr,
+            policy_expression,
+            idle_timeout,
+        } = create_outlet;
+        match self
+            .node_manager
+            .create_udp_outlet(
+                ctx,
+                hostname_port,
+                worker_addr,
+                policy_expression,
+                idle_timeout,
+            )
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn delete_udp_outlet(
+        &self,
+        worker_addr: &Address,
+    ) -> Result<Response<OutletStatus>, Response<Error>> {
+        match self.node_manager.delete_udp_outlet(worker_addr).await {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn get_udp_outlets(
+        &self,
+    ) -> Result<Response<Vec<OutletStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_udp_outlets().await))
+    }
+}
+
+impl NodeManager {
+    /// UDP portals use the UDP transport of the node, which only exists
+    /// if the node was started with UDP support
+    fn udp_portals_transport(&self) -> Result<&UdpTransport> {
+        self.udp_transport.as_ref().ok_or_else(|| {
+            ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "UDP portals need a node started with `ockam node create --udp`",
+            )
+        })
+    }
+
+    /// Create a UDP inlet which forwards the datagrams of each of its clients to a UDP outlet,
+    /// through a secure channel created to the outlet address.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip(self, ctx))]
+    pub async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        listen_addr: HostnamePort,
+        outlet_addr: MultiAddr,
+        alias: String,
+        authorized: Option<Identifier>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> Result<InletStatus> {
+        let udp_transport = self.udp_portals_transport()?;
+        info!("Handling request to create UDP inlet {alias} at {listen_addr}");
+
+        if self.registry.udp_inlets.contains_key(&alias).await {
+            let message = format!("A UDP inlet with alias '{alias}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        let connection = self
+            .make_connection(
+                ctx,
+                &outlet_addr,
+                self.node_identifier.clone(),
+                authorized,
+                None,
+            )
+            .await?;
+
+        let res = async {
+            let listen_addr =
+                ockam_node::compat::asynchronous::resolve_peer(listen_addr.to_string()).await?;
+            let outlet_route = connection.route()?;
+            let (incoming_ac, outgoing_ac) = self
+                .access_control(
+                    ctx,
+                    self.project_authority(),
+                    Resource::new(alias.clone(), ResourceType::UdpInlet),
+                    Action::HandleMessage,
+                    policy_expression,
+                )
+                .await?;
+            let options = UdpInletOptions::new()
+                .with_incoming_access_control(incoming_ac)
+                .with_outgoing_access_control(outgoing_ac);
+            let options = match idle_timeout {
+                Some(idle_timeout) => options.with_idle_timeout(idle_timeout),
+                None => options,
+            };
+
+            let inlet = udp_transport
+                .create_inlet(listen_addr.to_string(), outlet_route.clone(), options)
+                .await?;
+            Ok::<_, ockam_core::Error>((inlet, outlet_route))
+        }
+        .await;
+
+        let (inlet, outlet_route) = match res {
+            Ok(res) => res,
+            Err(e) => {
+                warn!(%alias, err = %e, "Failed to create UDP inlet");
+                connection.close(ctx, self).await?;
+                return Err(e);
+            }
+        };
+
+        let bind_addr = inlet.socket_address().to_string();
+        self.registry
+            .udp_inlets
+            .insert(
+                alias.clone(),
+                UdpInletInfo::new(
+                    &bind_addr,
+                    outlet_addr.clone(),
+                    inlet.processor_address().clone(),
+                    connection,
+                ),
+            )
+            .await;
+
+        Ok(InletStatus::new(
+            bind_addr,
+            inlet.processor_address().address().to_string(),
+            alias,
+            None,
+            outlet_route.to_string(),
+            ConnectionStatus::Up,
+            outlet_addr.to_string(),
+            false,
+        ))
+    }
+
+    pub async fn delete_udp_inlet(&self, ctx: &Context, alias: &str) -> Result<InletStatus> {
+        info!(%alias, "Handling request to delete UDP inlet");
+        let Some(inlet) = self.registry.udp_inlets.remove(alias).await else {
+            let message = format!("UDP inlet with alias {alias} not found");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                message,
+            ));
+        };
+
+        self.udp_portals_transport()?
+            .stop_inlet(inlet.processor_address.clone())
+            .await?;
+        inlet.connection.close(ctx, self).await?;
+        self.resources().delete_resource(&alias.into()).await?;
+
+        Ok(InletStatus::new(
+            inlet.bind_addr,
+            None,
+            alias,
+            None,
+            None,
+            ConnectionStatus::Down,
+            inlet.outlet_addr.to_string(),
+            false,
+        ))
+    }
+
+    pub async fn list_udp_inlets(&self) -> Vec<InletStatus> {
+        let mut res = vec![];
+        for (alias, info) in self.registry.udp_inlets.entries().await {
+            let outlet_route = info.connection.route().ok().map(|r| r.to_string());
+            res.push(InletStatus::new(
+                info.bind_addr,
+                info.processor_address.address().to_string(),
+                alias,
+                None,
+                outlet_route,
+                ConnectionStatus::Up,
+                info.outlet_addr.to_string(),
+                false,
+            ));
+        }
+        res
+    }
+
+    /// Create a UDP outlet which sends the datagrams of each flow opened by a UDP inlet
+    /// to the given target, from a dedicated socket
+    #[instrument(skip(self, ctx))]
+    pub async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        worker_addr: Option<Address>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> Result<OutletStatus> {
+        let udp_transport = self.udp_portals_transport()?;
+        let worker_addr = match worker_addr {
+            Some(worker_addr) => worker_addr,
+            None => {
+                // Use the default address if it's not in use
+                let default: Address = DEFAULT_UDP_OUTLET_ADDRESS.into();
+                if self.registry.udp_outlets.contains_key(&default).await {
+                    random_name().into()
+                } else {
+                    default
+                }
+            }
+        };
+        info!("Handling request to create UDP outlet to {to} with worker {worker_addr}");
+
+        if self.registry.udp_outlets.contains_key(&worker_addr).await {
+            let message = format!("A UDP outlet with address '{worker_addr}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        let (incoming_ac, outgoing_ac) = self
+            .access_control_with_attributes(
+                ctx,
+                self.project_authority(),
+                Resource::new(worker_addr.address(), ResourceType::UdpOutlet),
+                Action::HandleMessage,
+                policy_expression,
+                outlet_resource_attributes(&to),
+            )
+            .await?;
+
+        let options = UdpOutletOptions::new()
+            .with_incoming_access_control(incoming_ac)
+            .with_outgoing_access_control(outgoing_ac);
+        let options = match idle_timeout {
+            Some(idle_timeout) => options.with_idle_timeout(idle_timeout),
+            None => options,
+        };
+        let options = if self.project_authority().is_none() {
+            options.as_consumer(&self.api_transport_flow_control_id)
+        } else {
+            options
+        };
+        // Accept messages from the default secure channel listener
+        let options = match ctx
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+        {
+            Some(flow_control_id) => options.as_consumer(&flow_control_id),
+            None => options,
+        };
+
+        udp_transport
+            .create_outlet(worker_addr.clone(), to.clone(), options)
+            .await?;
+
+        self.registry
+            .udp_outlets
+            .insert(
+                worker_addr.clone(),
+                UdpOutletInfo::new(to.clone(), worker_addr.clone()),
+            )
+            .await;
+
+        Ok(OutletStatus::new(to, worker_addr, None, false))
+    }
+
+    pub async fn delete_udp_outlet(&self, worker_addr: &Address) -> Result<OutletStatus> {
+        info!(%worker_addr, "Handling request to delete UDP outlet");
+        let Some(outlet) = self.registry.udp_outlets.remove(worker_addr).await else {
+            let message = format!("UDP outlet with address {worker_addr} not found");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                message,
+            ));
+        };
+
+        self.udp_portals_transport()?
+            .stop_outlet(outlet.worker_addr.clone())
+            .await?;
+        self.resources()
+            .delete_resource(&worker_addr.address().into())
+            .await?;
+
+        Ok(OutletStatus::new(
+            outlet.to,
+            outlet.worker_addr,
+            None,
+            false,
+        ))
+    }
+
+    pub async fn list_udp_outlets(&self) -> Vec<OutletStatus> {
+        self.registry
+            .udp_outlets
+            .values()
+            .await
+            .into_iter()
+            .map(|info| OutletStatus::new(info.to, info.worker_addr, None, false))
+            .collect()
+    }
+}
+
+#[async_trait]
+pub trait UdpPortals {
+    #[allow(clippy::too_many_arguments)]
+    async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        listen_addr: &HostnamePort,
+        outlet_addr: &MultiAddr,
+        alias: &str,
+        authorized_identifier: &Option<Identifier>,
+        policy_expression: &Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> miette::Result<InletStatus>;
+
+    async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        to: &HostnamePort,
+        from: Option<&Address>,
+        policy_expression: &Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> miette::Result<OutletStatus>;
+}
+
+#[async_trait]
+impl UdpPortals for BackgroundNodeClient {
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all, fields(listen_addr = % listen_addr, outlet_addr = % outlet_addr))]
+    async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        listen_addr: &HostnamePort,
+        outlet_addr: &MultiAddr,
+        alias: &str,
+        authorized_identifier: &Option<Identifier>,
+        policy_expression: &Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> miette::Result<InletStatus> {
+        let mut payload = CreateUdpInlet::new(
+            listen_addr.clone(),
+            outlet_addr.clone(),
+            alias.into(),
+            authorized_identifier.clone(),
+        );
+        if let Some(policy_expression) = policy_expression {
+            payload.set_policy_expression(policy_expression.clone());
+        }
+        if let Some(idle_timeout) = idle_timeout {
+            payload.set_idle_timeout(idle_timeout);
+        }
+        let req = Request::post("/node/udp_inlet").body(payload);
+        let result: InletStatus = self.ask(ctx, req).await?;
+        Ok(result)
+    }
+
+    #[instrument(skip_all, fields(to = % to, from = ? from))]
+    async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        to: &HostnamePort,
+        from: Option<&Address>,
+        policy_expression: &Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> miette::Result<OutletStatus> {
+        let mut payload = CreateUdpOutlet::new(to.clone(), from.cloned());
+        if let Some(policy_expression) = policy_expression {
+            payload.set_policy_expression(policy_expression.clone());
+        }
+        if let Some(idle_timeout) = idle_timeout {
+            payload.set_idle_timeout(idle_timeout);
+        }
+        let req = Request::post("/node/udp_outlet").body(payload);
+        let result: OutletStatus = self.ask(ctx, req).await?;
+        Ok(result)
+    }
+}
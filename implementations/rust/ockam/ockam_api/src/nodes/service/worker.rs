@@ -59,6 +59,7 @@ impl NodeManagerWorker {
             // ==*== Basic node information ==*==
             (Get, ["node"]) => encode_response(req, self.get_node_status().await)?,
             (Get, ["node", "resources"]) => encode_response(req, self.get_node_resources().await)?,
+            (Post, ["node", "reload"]) => encode_response(req, self.reload_configuration().await)?,
 
             // ==*== Tcp Connection ==*==
             (Get, ["node", "tcp", "connection"]) => self.get_tcp_connections(req).await.to_vec()?,
@@ -192,6 +193,23 @@ impl NodeManagerWorker {
             }
             (Delete, ["node", "portal"]) => todo!(),
 
+            // ==*== UDP Inlets & Outlets ==*==
+            (Get, ["node", "udp_inlet"]) => encode_response(req, self.get_udp_inlets().await)?,
+            (Get, ["node", "udp_outlet"]) => encode_response(req, self.get_udp_outlets().await)?,
+            (Post, ["node", "udp_inlet"]) => {
+                encode_response(req, self.create_udp_inlet(ctx, dec.decode()?).await)?
+            }
+            (Post, ["node", "udp_outlet"]) => {
+                encode_response(req, self.create_udp_outlet(ctx, dec.decode()?).await)?
+            }
+            (Delete, ["node", "udp_inlet", alias]) => {
+                encode_response(req, self.delete_udp_inlet(ctx, alias).await)?
+            }
+            (Delete, ["node", "udp_outlet", addr]) => {
+                let addr: Address = addr.to_string().into();
+                encode_response(req, self.delete_udp_outlet(&addr).await)?
+            }
+
             // ==*== InfluxDB Inlets & Outlets  ==*==
             (Post, ["node", "influxdb_inlet"]) => encode_response(
                 req,
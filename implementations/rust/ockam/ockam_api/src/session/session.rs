@@ -1,5 +1,5 @@
 use rand::random;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Mutex as SyncMutex;
 
 use crate::nodes::service::default_address::DefaultAddress;
@@ -33,6 +33,10 @@ struct SharedState {
     is_being_replaced: Arc<AtomicBool>,
     /// Outcome of last session creation
     last_outcome: Arc<SyncMutex<Option<ReplacerOutputKind>>>,
+    /// Number of successful replacements
+    replacements: Arc<AtomicU64>,
+    /// Number of failed replacements
+    replacement_failures: Arc<AtomicU64>,
     /// Replacer impl
     replacer: Arc<AsyncMutex<dyn SessionReplacer>>,
     /// Pings that we sent. The whole list is cleared upon receiving an ack
@@ -198,6 +202,8 @@ impl Session {
             status: Default::default(),
             is_being_replaced: Arc::new(AtomicBool::new(false)),
             last_outcome: Arc::new(SyncMutex::new(None)),
+            replacements: Default::default(),
+            replacement_failures: Default::default(),
             replacer: replacer.clone(),
             sent_pings: Default::default(),
         };
@@ -277,6 +283,18 @@ impl Session {
         self.shared_state.last_outcome.lock().unwrap().clone()
     }
 
+    /// Number of times the session was successfully replaced after becoming unhealthy
+    pub fn replacement_count(&self) -> u64 {
+        self.shared_state.replacements.load(Ordering::Relaxed)
+    }
+
+    /// Number of failed attempts to replace the session
+    pub fn replacement_failure_count(&self) -> u64 {
+        self.shared_state
+            .replacement_failures
+            .load(Ordering::Relaxed)
+    }
+
     /// Start monitoring the session
     pub async fn start_monitoring(&mut self) -> Result<()> {
         let (ping_channel_sender, ping_channel_receiver) = mpsc::channel(1);
@@ -519,9 +537,13 @@ impl Session {
                                 .store(false, Ordering::Relaxed);
                             *shared_state.last_outcome.lock().unwrap() =
                                 Some(replacer_outcome.kind.clone());
+                            shared_state.replacements.fetch_add(1, Ordering::Relaxed);
                         }
                         Err(err) => {
                             warn!(key = %key, err = %err, "replacing session failed");
+                            shared_state
+                                .replacement_failures
+                                .fetch_add(1, Ordering::Relaxed);
 
                             shared_state
                                 .is_being_replaced
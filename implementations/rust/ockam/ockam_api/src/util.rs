@@ -3,13 +3,14 @@ use std::net::{SocketAddrV4, SocketAddrV6};
 use miette::miette;
 
 use ockam::tcp::{TcpConnection, TcpConnectionOptions, TcpTransport, TCP};
+use ockam::udp::UDP;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Error, Result, Route, TransportType, LOCAL};
 use ockam_multiaddr::proto::{
-    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Worker,
+    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Udp, Unix, Worker, Ws,
 };
-use ockam_multiaddr::{Code, MultiAddr, Protocol};
+use ockam_multiaddr::{Code, MultiAddr, ProtoValue, Protocol};
 
 use crate::error::ApiError;
 
@@ -209,7 +210,8 @@ pub async fn multiaddr_to_route(
 }
 
 /// Resolve all the multiaddresses which represent transport addresses
-/// For example /tcp/127.0.0.1/port/4000 is transformed to the Address (TCP, "127.0.0.1:4000")
+/// For example /ip4/127.0.0.1/tcp/4000 is transformed to the Address (TCP, "127.0.0.1:4000")
+/// and /ip4/127.0.0.1/udp/4000 to the Address (UDP, "127.0.0.1:4000")
 /// The creation of a TCP worker and the substitution of that transport address to a worker address
 /// is done later with `context.resolve_transport_route(route)`
 pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
@@ -220,29 +222,29 @@ pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
         match p.code() {
             Ip4::CODE => {
                 let ip4 = p.cast::<Ip4>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV4::new(*ip4, *port);
-                route = route.append(Address::new_with_string(TCP, socket_addr.to_string()))
+                let (transport_type, port) = transport_port(&it.next()?)?;
+                let socket_addr = SocketAddrV4::new(*ip4, port);
+                route = route.append(Address::new_with_string(
+                    transport_type,
+                    socket_addr.to_string(),
+                ))
             }
             Ip6::CODE => {
                 let ip6 = p.cast::<Ip6>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
+                let (transport_type, port) = transport_port(&it.next()?)?;
+                let socket_addr = SocketAddrV6::new(*ip6, port, 0, 0);
                 route = route.append(Address::new_with_string(
-                    TransportType::new(1),
+                    transport_type,
                     socket_addr.to_string(),
                 ))
             }
             DnsAddr::CODE => {
                 let host = p.cast::<DnsAddr>()?;
-                if let Some(p) = it.peek() {
-                    if p.code() == Tcp::CODE {
-                        let port = p.cast::<Tcp>()?;
-                        let addr = format!("{}:{}", &*host, *port);
-                        route = route.append(Address::new_with_string(TransportType::new(1), addr));
-                        let _ = it.next();
-                        continue;
-                    }
+                if let Some((transport_type, port)) = it.peek().and_then(transport_port) {
+                    let addr = format!("{}:{}", &*host, port);
+                    route = route.append(Address::new_with_string(transport_type, addr));
+                    let _ = it.next();
+                    continue;
                 }
             }
             Worker::CODE => {
@@ -266,6 +268,15 @@ pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
     Some(route.into())
 }
 
+/// Return the transport type and the port of a "/tcp" or "/udp" protocol value
+fn transport_port(p: &ProtoValue) -> Option<(TransportType, u16)> {
+    match p.code() {
+        Tcp::CODE => p.cast::<Tcp>().map(|port| (TCP, *port)),
+        Udp::CODE => p.cast::<Udp>().map(|port| (UDP, *port)),
+        _ => None,
+    }
+}
+
 /// Try to convert a multiaddr to an Ockam Address
 pub fn multiaddr_to_addr(ma: &MultiAddr) -> Option<Address> {
     let mut it = ma.iter().peekable();
@@ -357,6 +368,10 @@ pub fn is_local_node(ma: &MultiAddr) -> miette::Result<bool> {
                     .map(|ip6| ip6.is_loopback())
                     .ok_or_else(|| miette!("Invalid \"ip6\" value"))?;
             }
+            // A "/unix" socket is always on the local machine
+            Unix::CODE => {
+                at_rust_node = true;
+            }
             // A MultiAddr starting with "/service" could reference both local and remote nodes.
             _ => {
                 return Err(miette!("Invalid address, protocol not supported"));
@@ -378,9 +393,44 @@ pub fn local_worker(code: &Code) -> Result<bool> {
         | Ip4::CODE
         | Ip6::CODE
         | Tcp::CODE
+        | Udp::CODE
+        | Ws::CODE
+        | Unix::CODE
         | Secure::CODE => Ok(false),
         Worker::CODE | Service::CODE => Ok(true),
 
         _ => Err(ApiError::core(format!("unknown transport type: {code}"))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn udp_multiaddr_to_transport_route() {
+        let ma = MultiAddr::from_str("/ip4/127.0.0.1/udp/4000/service/api").unwrap();
+        let route = multiaddr_to_transport_route(&ma).unwrap();
+        assert_eq!(
+            route,
+            Route::new()
+                .append(Address::new_with_string(UDP, "127.0.0.1:4000"))
+                .append(Address::new_with_string(LOCAL, "api"))
+                .into()
+        );
+
+        let ma = MultiAddr::from_str("/dnsaddr/localhost/tcp/4000/service/api").unwrap();
+        let route = multiaddr_to_transport_route(&ma).unwrap();
+        assert_eq!(
+            route.next().unwrap(),
+            &Address::new_with_string(TCP, "localhost:4000")
+        );
+    }
+
+    #[test]
+    fn unix_multiaddr_is_local() {
+        let ma = MultiAddr::from_str("/unix/%2Ftmp%2Fnode.sock/service/api").unwrap();
+        assert!(is_local_node(&ma).unwrap());
+    }
+}
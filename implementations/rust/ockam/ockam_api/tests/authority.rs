@@ -16,6 +16,7 @@ async fn authority_starts_with_default_configuration(ctx: &mut Context) -> Resul
     assert!(!workers.contains(&Address::from(DefaultAddress::ENROLLMENT_TOKEN_ACCEPTOR)));
     assert!(!workers.contains(&Address::from(DefaultAddress::ENROLLMENT_TOKEN_ISSUER)));
     assert!(workers.contains(&Address::from(DefaultAddress::CREDENTIAL_ISSUER)));
+    assert!(workers.contains(&Address::from(DefaultAddress::REVOCATION_LIST_ISSUER)));
     assert!(workers.contains(&Address::from(DefaultAddress::SECURE_CHANNEL_LISTENER)));
     assert!(workers.contains(&Address::from(DefaultAddress::ECHO_SERVICE)));
 
@@ -34,6 +35,7 @@ async fn authority_starts_direct_authenticator(ctx: &mut Context) -> Result<()>
     assert!(!workers.contains(&Address::from(DefaultAddress::ENROLLMENT_TOKEN_ACCEPTOR)));
     assert!(!workers.contains(&Address::from(DefaultAddress::ENROLLMENT_TOKEN_ISSUER)));
     assert!(workers.contains(&Address::from(DefaultAddress::CREDENTIAL_ISSUER)));
+    assert!(workers.contains(&Address::from(DefaultAddress::REVOCATION_LIST_ISSUER)));
     assert!(workers.contains(&Address::from(DefaultAddress::SECURE_CHANNEL_LISTENER)));
     assert!(workers.contains(&Address::from(DefaultAddress::ECHO_SERVICE)));
 
@@ -52,6 +54,7 @@ async fn authority_starts_enrollment_token(ctx: &mut Context) -> Result<()> {
     assert!(workers.contains(&Address::from(DefaultAddress::ENROLLMENT_TOKEN_ACCEPTOR)));
     assert!(workers.contains(&Address::from(DefaultAddress::ENROLLMENT_TOKEN_ISSUER)));
     assert!(workers.contains(&Address::from(DefaultAddress::CREDENTIAL_ISSUER)));
+    assert!(workers.contains(&Address::from(DefaultAddress::REVOCATION_LIST_ISSUER)));
     assert!(workers.contains(&Address::from(DefaultAddress::SECURE_CHANNEL_LISTENER)));
     assert!(workers.contains(&Address::from(DefaultAddress::ECHO_SERVICE)));
 
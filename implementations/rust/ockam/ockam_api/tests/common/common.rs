@@ -40,6 +40,8 @@ pub async fn default_configuration() -> Result<Configuration> {
         no_direct_authentication: true,
         no_token_enrollment: true,
         okta: None,
+        jwt: None,
+        svid: None,
         account_authority: None,
         enforce_admin_checks: false,
         disable_trust_context_id: false,
@@ -14,7 +14,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use tokio::net::{TcpStream, UdpSocket};
 use tokio::runtime::Runtime;
 use tokio::spawn;
 use tokio::time::timeout;
@@ -80,6 +80,206 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn inlet_outlet_over_websocket(context: &mut Context) -> ockam::Result<()> {
+    TestNode::clean().await?;
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    let ws_address = node_manager
+        .websocket_transport(context)
+        .await?
+        .listen("127.0.0.1:0")
+        .await?;
+
+    node_manager
+        .create_outlet(
+            context,
+            echo_server_handle.chosen_addr.clone(),
+            false,
+            Some(Address::from_string("outlet")),
+            true,
+            OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+            false,
+        )
+        .await?;
+
+    let outlet_addr = MultiAddr::from_str(&format!(
+        "/ip4/127.0.0.1/tcp/{}/ws/secure/api/service/outlet",
+        ws_address.port()
+    ))?;
+    assert_echo_through_inlet(context, node_manager, outlet_addr).await
+}
+
+#[ockam_macros::test]
+async fn udp_inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<()> {
+    TestNode::clean().await?;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    // A UDP echo server
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server_addr = server.local_addr().unwrap();
+    spawn(async move {
+        let mut buf = [0u8; 1024];
+        while let Ok((len, peer)) = server.recv_from(&mut buf).await {
+            let _ = server.send_to(&buf[..len], peer).await;
+        }
+    });
+
+    let outlet_status = node_manager
+        .create_udp_outlet(context, HostnamePort::from(server_addr), None, None, None)
+        .await?;
+    assert_eq!(outlet_status.worker_addr.address(), "udp_outlet");
+
+    let inlet_status = node_manager
+        .create_udp_inlet(
+            context,
+            HostnamePort::new("127.0.0.1", 0),
+            MultiAddr::from_str("/secure/api/service/udp_outlet")?,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+        )
+        .await?;
+    assert_eq!(inlet_status.alias, "alias");
+    assert_ne!(inlet_status.bind_addr, "127.0.0.1:0");
+
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let mut buf = [0u8; 5];
+    let mut received = false;
+    // The first datagrams may be sent before the flow is opened
+    for _ in 0..10 {
+        client
+            .send_to(b"hello", &inlet_status.bind_addr)
+            .await
+            .unwrap();
+        if let Ok(Ok((len, _))) =
+            timeout(Duration::from_millis(500), client.recv_from(&mut buf)).await
+        {
+            assert_eq!(&buf[..len], b"hello");
+            received = true;
+            break;
+        }
+    }
+    assert!(received);
+
+    assert_eq!(node_manager.list_udp_inlets().await.len(), 1);
+    node_manager.delete_udp_inlet(context, "alias").await?;
+    node_manager
+        .delete_udp_outlet(&Address::from_string("udp_outlet"))
+        .await?;
+    assert!(node_manager.list_udp_inlets().await.is_empty());
+    assert!(node_manager.list_udp_outlets().await.is_empty());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn inlet_outlet_over_unix_domain_socket() {
+    use ockam_multiaddr::proto::{Secure, Service, Unix};
+
+    // the unix domain socket listener and the inlet are created on two different nodes
+    // since a node can't connect to its own unix domain socket listener
+    let runtime = Arc::new(Runtime::new().unwrap());
+    let handle = runtime.handle();
+    let runtime_cloned = runtime.clone();
+
+    let result: ockam::Result<()> = handle.block_on(async move {
+        let test_body = async move {
+            let echo_server_handle = start_tcp_echo_server().await;
+
+            TestNode::clean().await?;
+            let first_node = TestNode::create(runtime_cloned.clone(), None).await;
+            let second_node = TestNode::create(runtime_cloned, None).await;
+
+            let socket_path = std::env::temp_dir().join(format!(
+                "ockam-test-{}.sock",
+                ockam_core::compat::rand::random::<u32>()
+            ));
+            let socket_path = socket_path.to_str().unwrap().to_string();
+            second_node
+                .node_manager
+                .uds_transport(&second_node.context)
+                .await?
+                .listen(&socket_path)
+                .await?;
+
+            second_node
+                .node_manager
+                .create_outlet(
+                    &second_node.context,
+                    echo_server_handle.chosen_addr.clone(),
+                    false,
+                    Some(Address::from_string("outlet")),
+                    true,
+                    OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                    false,
+                )
+                .await?;
+
+            let mut outlet_addr = MultiAddr::default();
+            outlet_addr.push_back(Unix::new(socket_path.as_str()))?;
+            outlet_addr.push_back(Secure::new("api"))?;
+            outlet_addr.push_back(Service::new("outlet"))?;
+            let result = assert_echo_through_inlet(
+                &first_node.context,
+                &first_node.node_manager,
+                outlet_addr,
+            )
+            .await;
+            let _ = std::fs::remove_file(&socket_path);
+            result
+        };
+
+        timeout(Duration::from_secs(90), test_body)
+            .await
+            .unwrap_or_else(|_| Err(Error::new(Origin::Node, Kind::Timeout, "Test timed out")))
+    });
+
+    result.unwrap();
+}
+
+/// Create an inlet to the given outlet address and check that data is echoed back
+async fn assert_echo_through_inlet(
+    context: &Context,
+    node_manager: &ockam_api::nodes::InMemoryNode,
+    outlet_addr: MultiAddr,
+) -> ockam::Result<()> {
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            HostnamePort::new("127.0.0.1", 0),
+            route![],
+            route![],
+            outlet_addr,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            false,
+            false,
+            false,
+            None,
+        )
+        .await?;
+    assert_eq!(inlet_status.status, ConnectionStatus::Up);
+
+    let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+    socket.write_all(b"hello").await.unwrap();
+
+    let mut buf = [0u8; 5];
+    socket.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello");
+
+    Ok(())
+}
+
 #[test]
 fn portal_node_goes_down_reconnect() {
     // in this test we manually create three nodes with a shared runtime, then:
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::identity::models::CredentialSchemaIdentifier;
+use ockam::identity::utils::{now, AttributesBuilder};
+use ockam::identity::{
+    secure_channels, RemoteRevocationListRetriever, SecureChannelListenerOptions,
+    SecureChannelOptions, TrustIdentifierPolicy,
+};
+use ockam::route;
+use ockam::tcp::{TcpListenerOptions, TcpTransport, TCP};
+use ockam_api::authenticator::revocation_list_issuer::RevocationListIssuerWorker;
+use ockam_api::authenticator::{AuthorityRevocationsRepository, AuthorityRevocationsSqlxDatabase};
+use ockam_api::DefaultAddress;
+use ockam_core::{Address, AsyncTryClone, Result};
+use ockam_node::Context;
+
+/// A credential presented to a node is rejected once that node retrieves the revocation
+/// list published by the authority which issued it
+#[ockam_macros::test]
+async fn revoked_credentials_are_rejected_after_retrieving_the_revocation_list(
+    ctx: &mut Context,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities = secure_channels.identities();
+    let credentials = identities.credentials();
+    let authority = identities.identities_creation().create_identity().await?;
+    let member = identities.identities_creation().create_identity().await?;
+    let node = identities.identities_creation().create_identity().await?;
+
+    // Start the revocation list issuer of the authority, behind a secure channel listener
+    let tcp = TcpTransport::create(ctx).await?;
+    let tcp_listener = tcp.listen("127.0.0.1:0", TcpListenerOptions::new()).await?;
+    let options = SecureChannelListenerOptions::new().as_consumer(tcp_listener.flow_control_id());
+    ctx.flow_controls().add_consumer(
+        DefaultAddress::REVOCATION_LIST_ISSUER,
+        &options.spawner_flow_control_id(),
+    );
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &authority,
+            DefaultAddress::SECURE_CHANNEL_LISTENER,
+            options,
+        )
+        .await?;
+
+    let revocations = Arc::new(AuthorityRevocationsSqlxDatabase::create().await?);
+    ctx.start_worker(
+        DefaultAddress::REVOCATION_LIST_ISSUER,
+        RevocationListIssuerWorker::new(revocations.clone(), credentials.clone(), &authority),
+    )
+    .await?;
+
+    // The member presents its credential to the node
+    let credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            &authority,
+            &member,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("role", "member")
+                .build(),
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+    let listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &node,
+            "node_listener",
+            SecureChannelListenerOptions::new().with_authority(authority.clone()),
+        )
+        .await?;
+    secure_channels
+        .create_secure_channel(
+            ctx,
+            &member,
+            route![listener.address().clone()],
+            SecureChannelOptions::new()
+                .with_trust_policy(TrustIdentifierPolicy::new(node.clone()))
+                .with_credential(credential.clone())?,
+        )
+        .await?;
+    ctx.sleep(Duration::from_millis(200)).await;
+
+    let identities_attributes = identities.identities_attributes();
+    assert!(identities_attributes
+        .get_attributes(&member, &authority)
+        .await?
+        .is_some());
+
+    // The authority revokes the member and the node retrieves the revocation list
+    revocations.revoke(&member, &authority, now()?).await?;
+    let retriever = RemoteRevocationListRetriever::new(
+        ctx.async_try_clone().await?,
+        Arc::new(tcp.clone()),
+        secure_channels.clone(),
+        authority.clone(),
+        route![
+            Address::new_with_string(TCP, tcp_listener.socket_string()),
+            DefaultAddress::SECURE_CHANNEL_LISTENER
+        ],
+        node.clone(),
+    )
+    .with_service_address(DefaultAddress::REVOCATION_LIST_ISSUER);
+    let revocation_list = retriever.retrieve().await?;
+    assert_eq!(revocation_list.version, 1);
+    assert_eq!(revocation_list.revoked_subjects.len(), 1);
+    assert_eq!(revocation_list.revoked_subjects[0].subject, member);
+
+    // The attributes of the member are not available anymore and its credential is rejected
+    assert!(identities_attributes
+        .get_attributes(&member, &authority)
+        .await?
+        .is_none());
+    assert!(credentials
+        .credentials_verification()
+        .verify_credential(Some(&member), &[authority.clone()], &credential)
+        .await
+        .is_err());
+
+    Ok(())
+}
@@ -212,6 +212,7 @@ impl AppState {
                 false,
                 false,
                 &None,
+                false,
             )
             .await
             .map_err(|err| {
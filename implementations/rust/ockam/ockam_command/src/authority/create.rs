@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use clap::Args;
@@ -14,8 +15,11 @@ use ockam::identity::models::ChangeHistory;
 use ockam::identity::utils::now;
 use ockam::identity::{Identifier, Identity, TimestampInSeconds, Vault};
 use ockam::Context;
+use ockam_api::authenticator::jwt::JwksSource;
 use ockam_api::authenticator::{PreTrustedIdentities, PreTrustedIdentity};
-use ockam_api::authority_node::{Authority, OktaConfiguration};
+use ockam_api::authority_node::{
+    Authority, JwtConfiguration, OktaConfiguration, SvidConfiguration,
+};
 use ockam_api::cloud::project::models::ProjectModel;
 use ockam_api::colors::color_primary;
 use ockam_api::config::lookup::InternetAddress;
@@ -116,6 +120,57 @@ pub struct CreateCommand {
     #[arg(long, value_name = "ATTRIBUTE_NAMES", default_value = None)]
     attributes: Option<Vec<String>>,
 
+    /// JWT: issuer (`iss` claim) of the tokens which can be presented to enroll.
+    /// Setting this option starts a JWT authenticator for an OpenID Connect provider
+    /// like Keycloak, Dex or Azure AD
+    #[arg(long, value_name = "URL", requires = "jwt_audience")]
+    jwt_issuer: Option<String>,
+
+    /// JWT: audience (`aud` claim) of the tokens which can be presented to enroll
+    #[arg(long, value_name = "AUDIENCE", requires = "jwt_issuer")]
+    jwt_audience: Option<String>,
+
+    /// JWT: URL of the JSON Web Key Set used to verify the tokens.
+    /// Defaults to the `jwks_uri` of the issuer OpenID Connect configuration
+    #[arg(long, value_name = "URL", requires = "jwt_issuer")]
+    jwt_jwks_url: Option<String>,
+
+    /// JWT: path of a local file containing the JSON Web Key Set used to verify the tokens
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "jwt_issuer",
+        conflicts_with = "jwt_jwks_url"
+    )]
+    jwt_jwks_file: Option<PathBuf>,
+
+    /// JWT: claim which must contain the identifier of the enrolling identity
+    #[arg(
+        long,
+        value_name = "CLAIM",
+        default_value = "nonce",
+        requires = "jwt_issuer"
+    )]
+    jwt_identifier_claim: String,
+
+    /// JWT: claim to store as a member attribute, as `CLAIM` or `CLAIM=ATTRIBUTE`.
+    /// Nested claims are given as a path, for example `realm_access.roles`
+    #[arg(
+        long = "jwt-attribute",
+        value_name = "CLAIM[=ATTRIBUTE]",
+        requires = "jwt_issuer"
+    )]
+    jwt_attributes: Vec<String>,
+
+    /// SVID: SPIFFE trust domain of the workloads which can enroll with an X.509 SVID.
+    /// Setting this option starts an SVID authenticator
+    #[arg(long, value_name = "TRUST_DOMAIN", requires = "svid_trust_bundle")]
+    svid_trust_domain: Option<String>,
+
+    /// SVID: path of a PEM file containing the CA certificates of the SPIFFE trust domain
+    #[arg(long, value_name = "PATH", requires = "svid_trust_domain")]
+    svid_trust_bundle: Option<PathBuf>,
+
     /// Full, hex-encoded Identity (change history) of the account authority to trust
     /// for account and project administrator credentials.
     #[arg(long, value_name = "ACCOUNT_AUTHORITY_CHANGE_HISTORY", default_value = None, value_parser = ChangeHistory::import_from_string
@@ -219,6 +274,46 @@ impl CreateCommand {
             });
         }
 
+        if let Some(jwt_issuer) = &self.jwt_issuer {
+            args.push("--jwt-issuer".to_string());
+            args.push(jwt_issuer.clone());
+        }
+
+        if let Some(jwt_audience) = &self.jwt_audience {
+            args.push("--jwt-audience".to_string());
+            args.push(jwt_audience.clone());
+        }
+
+        if let Some(jwt_jwks_url) = &self.jwt_jwks_url {
+            args.push("--jwt-jwks-url".to_string());
+            args.push(jwt_jwks_url.clone());
+        }
+
+        if let Some(jwt_jwks_file) = &self.jwt_jwks_file {
+            args.push("--jwt-jwks-file".to_string());
+            args.push(jwt_jwks_file.to_string_lossy().to_string());
+        }
+
+        if self.jwt_issuer.is_some() {
+            args.push("--jwt-identifier-claim".to_string());
+            args.push(self.jwt_identifier_claim.clone());
+        }
+
+        self.jwt_attributes.iter().for_each(|attr| {
+            args.push("--jwt-attribute".to_string());
+            args.push(attr.clone());
+        });
+
+        if let Some(svid_trust_domain) = &self.svid_trust_domain {
+            args.push("--svid-trust-domain".to_string());
+            args.push(svid_trust_domain.clone());
+        }
+
+        if let Some(svid_trust_bundle) = &self.svid_trust_bundle {
+            args.push("--svid-trust-bundle".to_string());
+            args.push(svid_trust_bundle.to_string_lossy().to_string());
+        }
+
         if let Some(identity) = &self.identity {
             args.push("--identity".to_string());
             args.push(identity.clone());
@@ -267,6 +362,35 @@ impl CreateCommand {
             .to_pretrusted_identities(now, authority_identifier)
     }
 
+    /// Return the configuration of the JWT authenticator if an issuer is specified
+    fn jwt_configuration(&self) -> Option<JwtConfiguration> {
+        let (issuer, audience) = match (&self.jwt_issuer, &self.jwt_audience) {
+            (Some(issuer), Some(audience)) => (issuer.clone(), audience.clone()),
+            _ => return None,
+        };
+        let jwks = match (&self.jwt_jwks_url, &self.jwt_jwks_file) {
+            (_, Some(path)) => JwksSource::File(path.clone()),
+            (Some(url), None) => JwksSource::Url(url.clone()),
+            (None, None) => JwksSource::Discovery(issuer.clone()),
+        };
+        let attributes = self
+            .jwt_attributes
+            .iter()
+            .map(|attribute| match attribute.split_once('=') {
+                Some((claim, name)) => (claim.to_string(), name.to_string()),
+                None => (attribute.clone(), attribute.clone()),
+            })
+            .collect();
+        Some(JwtConfiguration {
+            address: DefaultAddress::JWT_AUTHENTICATOR.to_string(),
+            issuer,
+            audience,
+            jwks,
+            identifier_claim: self.jwt_identifier_claim.clone(),
+            attributes,
+        })
+    }
+
     pub fn logging_to_file(&self) -> bool {
         // Background nodes will spawn a foreground node in a child process.
         // In that case, the child process will log to files.
@@ -328,6 +452,17 @@ impl CreateCommand {
             _ => None,
         };
 
+        let jwt_configuration = self.jwt_configuration();
+
+        let svid_configuration = match (&self.svid_trust_domain, &self.svid_trust_bundle) {
+            (Some(trust_domain), Some(trust_bundle)) => Some(SvidConfiguration {
+                address: DefaultAddress::SVID_AUTHENTICATOR.to_string(),
+                trust_domain: trust_domain.clone(),
+                trust_bundle: trust_bundle.clone(),
+            }),
+            _ => None,
+        };
+
         let now = now().into_diagnostic()?;
         let trusted_identities = self.trusted_identities(now, &node.clone().identifier());
 
@@ -406,6 +541,8 @@ impl CreateCommand {
             no_direct_authentication: self.no_direct_authentication,
             no_token_enrollment: self.no_token_enrollment,
             okta: okta_configuration,
+            jwt: jwt_configuration,
+            svid: svid_configuration,
             account_authority,
             enforce_admin_checks: self.enforce_admin_checks,
             disable_trust_context_id: self.disable_trust_context_id,
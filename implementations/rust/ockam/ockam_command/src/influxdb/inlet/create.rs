@@ -150,6 +150,9 @@ impl Command for InfluxDBCreateCommand {
 impl InfluxDBCreateCommand {
     async fn parse_args(mut self, opts: &CommandGlobalOpts) -> miette::Result<Self> {
         self.tcp_inlet = self.tcp_inlet.parse_args(opts).await?;
+        if self.tcp_inlet.socks5 {
+            Err(miette!("An InfluxDB Inlet can't be a SOCKS5 inlet"))?
+        };
         if self
             .lease_manager_route
             .as_ref()
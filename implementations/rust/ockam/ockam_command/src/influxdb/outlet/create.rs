@@ -54,6 +54,20 @@ impl Command for InfluxDBCreateCommand {
     async fn async_run(mut self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
         initialize_default_node(ctx, &opts).await?;
 
+        if self.tcp_outlet.dynamic {
+            return Err(miette!("An InfluxDB Outlet can't be dynamic"))?;
+        }
+        if self.tcp_outlet.proxy_protocol {
+            return Err(miette!(
+                "An InfluxDB Outlet can't send a PROXY protocol header"
+            ))?;
+        }
+        if self.tcp_outlet.http_identity_headers {
+            return Err(miette!(
+                "An InfluxDB Outlet can't add HTTP identity headers"
+            ))?;
+        }
+
         let token_config = if let Some(t) = self.fixed_token {
             InfluxDBOutletConfig::OutletWithFixedToken(t)
         } else if let Some(config) = self.lease_manager_config {
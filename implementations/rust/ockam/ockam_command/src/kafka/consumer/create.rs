@@ -50,6 +50,9 @@ impl CreateCommand {
             avoid_publishing: false,
             disable_content_encryption: false,
             encrypted_fields: vec![],
+            encrypt_headers: false,
+            encrypt_keys: false,
+            record_key_secret: None,
             inlet_policy_expression: None,
             consumer_policy_expression: None,
             producer_policy_expression: None,
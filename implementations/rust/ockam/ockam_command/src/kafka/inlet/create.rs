@@ -102,6 +102,30 @@ pub struct CreateCommand {
     )]
     pub encrypted_fields: Vec<String>,
 
+    /// Also encrypt the values of the record headers. Header names are kept in clear text.
+    #[arg(long, conflicts_with = "disable-content-encryption")]
+    pub encrypt_headers: bool,
+
+    /// Also encrypt the record keys, with the `--record-key-secret`. The same key is always
+    /// encrypted to the same value, so that records with the same key still go to the same
+    /// partition and can be compacted.
+    #[arg(
+        long,
+        conflicts_with = "disable-content-encryption",
+        requires = "record_key_secret"
+    )]
+    pub encrypt_keys: bool,
+
+    /// Secret used to encrypt the record keys. All the producers and consumers of a topic
+    /// must use the same secret.
+    #[arg(
+        long,
+        value_name = "SECRET",
+        env = "OCKAM_KAFKA_RECORD_KEY_SECRET",
+        hide_env_values = true
+    )]
+    pub record_key_secret: Option<String>,
+
     /// Policy expression that will be used for access control to the Kafka Inlet.
     /// If you don't provide it, the policy set for the "tcp-inlet" resource type will be used.
     ///
@@ -187,6 +211,8 @@ impl Command for CreateCommand {
                 to.clone(),
                 !self.disable_content_encryption,
                 self.encrypted_fields,
+                self.encrypt_headers,
+                self.record_key_secret.filter(|_| self.encrypt_keys),
                 consumer_resolution,
                 consumer_publishing,
                 self.inlet_policy_expression,
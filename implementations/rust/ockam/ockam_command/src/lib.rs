@@ -72,6 +72,7 @@ mod subcommand;
 mod subscription;
 pub mod tcp;
 mod terminal;
+mod udp;
 mod upgrade;
 pub mod util;
 pub mod value_parsers;
@@ -29,6 +29,7 @@ use tracing::instrument;
 pub mod background;
 pub mod config;
 pub mod foreground;
+pub mod reconcile;
 
 const DEFAULT_NODE_NAME: &str = "_default_node_name";
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
@@ -81,7 +82,7 @@ pub struct CreateCommand {
     )]
     pub http_server: bool,
 
-    /// Disable the node's status endpoint that serves the healthcheck endpoint.
+    /// Disable the node's status endpoint that serves the healthcheck and `/metrics` endpoints.
     #[arg(
         long,
         value_name = "BOOL",
@@ -137,6 +138,8 @@ impl Default for CreateCommand {
                 enrollment_ticket: None,
                 variables: vec![],
                 started_from_configuration: false,
+                watch: false,
+                reconcile_configuration: None,
             },
             tcp_listener_address: node_manager_defaults.tcp_listener_address,
             http_server: false,
@@ -236,11 +239,15 @@ impl CreateCommand {
     /// Return true if the `name` argument is a URL, a file path, or an inline config
     fn name_arg_is_a_config(&self) -> bool {
         let is_url = is_url(&self.name).is_some();
-        let is_file = std::fs::metadata(&self.name)
-            .map(|m| m.is_file())
-            .unwrap_or(false);
         let is_inline_config = serde_yaml::from_str::<NodeConfig>(&self.name).is_ok();
-        is_url || is_file || is_inline_config
+        is_url || self.name_arg_is_a_file() || is_inline_config
+    }
+
+    /// Return true if the `name` argument is a path to a local file
+    fn name_arg_is_a_file(&self) -> bool {
+        std::fs::metadata(&self.name)
+            .map(|m| m.is_file())
+            .unwrap_or(false)
     }
 
     fn name_arg_is_a_node_name(&self) -> bool {
@@ -271,6 +278,8 @@ impl CreateCommand {
             ));
         }
 
+        self.validate_watch_arg()?;
+
         if self.http_server {
             print_warning_for_deprecated_flag_no_effect(opts, "http-server")?;
         }
@@ -278,6 +287,19 @@ impl CreateCommand {
         Ok(())
     }
 
+    /// Return an error if the configuration to watch is not a local file
+    fn validate_watch_arg(&self) -> miette::Result<()> {
+        if self.config_args.watch
+            && (self.config_args.configuration.is_some() || !self.name_arg_is_a_file())
+        {
+            return Err(miette!(
+                "The {} flag requires the configuration to be a local file",
+                color_primary("--watch")
+            ));
+        }
+        Ok(())
+    }
+
     fn status_endpoint_port(&self) -> Option<Port> {
         match (self.no_status_endpoint, self.status_endpoint_port) {
             (true, _) => None,
@@ -498,6 +520,46 @@ mod tests {
         assert!(!cmd.should_run_config());
     }
 
+    #[test]
+    fn watch_requires_a_configuration_file() {
+        let tmp_directory = tempfile::tempdir().unwrap();
+        let tmp_file = tmp_directory.path().join("config.yaml");
+        std::fs::write(&tmp_file, "name: n1").unwrap();
+
+        // Ok if the name is a file path
+        let cmd = CreateCommand {
+            name: tmp_file.to_str().unwrap().to_string(),
+            config_args: ConfigArgs {
+                watch: true,
+                ..ConfigArgs::default()
+            },
+            ..CreateCommand::default()
+        };
+        assert!(cmd.validate_watch_arg().is_ok());
+
+        // Error if the configuration is inlined
+        let cmd = CreateCommand {
+            config_args: ConfigArgs {
+                configuration: Some("name: n1".to_string()),
+                watch: true,
+                ..ConfigArgs::default()
+            },
+            ..CreateCommand::default()
+        };
+        assert!(cmd.validate_watch_arg().is_err());
+
+        // Error if the name is a URL
+        let cmd = CreateCommand {
+            name: "http://localhost:8080".to_string(),
+            config_args: ConfigArgs {
+                watch: true,
+                ..ConfigArgs::default()
+            },
+            ..CreateCommand::default()
+        };
+        assert!(cmd.validate_watch_arg().is_err());
+    }
+
     #[tokio::test]
     async fn get_default_node_name_no_previous_state() {
         let state = CliState::test().await.unwrap();
@@ -15,6 +15,7 @@ use ockam_core::{AsyncTryClone, OpenTelemetryContext};
 use ockam_node::Context;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 use tracing::{debug, instrument, Span};
 
 pub const ENROLLMENT_TICKET: &str = "ENROLLMENT_TICKET";
@@ -41,6 +42,18 @@ pub struct ConfigArgs {
     /// A flag used internally to indicate that the node was started from a configuration file.
     #[arg(hide = true, long)]
     pub started_from_configuration: bool,
+
+    /// Keep the node in sync with its configuration file.
+    /// The node watches the file and creates, updates or deletes its policies, relays,
+    /// TCP inlets and outlets and Kafka services when they change.
+    /// A reload can also be requested with the `node reload` command.
+    /// Requires the configuration to be a local file.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// The configuration file watched by the node, set internally when the `--watch` flag is used.
+    #[arg(hide = true, long, value_name = "PATH")]
+    pub reconcile_configuration: Option<PathBuf>,
 }
 
 impl CreateCommand {
@@ -56,13 +69,30 @@ impl CreateCommand {
         let identity_name = self
             .get_or_create_identity(&opts, &node_config.node.identity())
             .await?;
+        let reconcile_configuration = if self.config_args.watch {
+            Some(std::fs::canonicalize(&self.name).into_diagnostic()?)
+        } else {
+            None
+        };
         let res = if self.foreground_args.foreground {
             node_config
-                .run_foreground(ctx, &opts, &node_name, &identity_name)
+                .run_foreground(
+                    ctx,
+                    &opts,
+                    &node_name,
+                    &identity_name,
+                    reconcile_configuration,
+                )
                 .await
         } else {
             node_config
-                .run(ctx, &opts, &node_name, &identity_name)
+                .run(
+                    ctx,
+                    &opts,
+                    &node_name,
+                    &identity_name,
+                    reconcile_configuration,
+                )
                 .await
         };
         if res.is_err() {
@@ -138,7 +168,7 @@ pub struct NodeConfig {
 }
 
 impl NodeConfig {
-    fn parse(mut contents: String) -> miette::Result<Self> {
+    pub(crate) fn parse(mut contents: String) -> miette::Result<Self> {
         ConfigParser::parse(&mut contents)
     }
 
@@ -225,9 +255,15 @@ impl NodeConfig {
         opts: &CommandGlobalOpts,
         node_name: &String,
         identity_name: &String,
+        reconcile_configuration: Option<PathBuf>,
     ) -> miette::Result<()> {
         debug!("Running node config");
-        for section in self.parse_commands(node_name, identity_name)? {
+        let sections = match reconcile_configuration {
+            // The other sections are created by the node itself, which keeps them in sync with the file
+            Some(path) => self.parse_node_commands(identity_name, path)?,
+            None => self.parse_commands(node_name, identity_name)?,
+        };
+        for section in sections {
             section.run(ctx, opts).await?
         }
         Ok(())
@@ -239,6 +275,7 @@ impl NodeConfig {
         opts: &CommandGlobalOpts,
         node_name: &String,
         identity_name: &str,
+        reconcile_configuration: Option<PathBuf>,
     ) -> miette::Result<()> {
         debug!("Running node config in foreground mode");
         // First, run the `project enroll` commands to prepare the identity and project data
@@ -263,9 +300,14 @@ impl NodeConfig {
         }
 
         // Next, run the 'node create' command
-        let node_process = self
-            .node
-            .run_in_new_process(&opts.global_args, BTreeMap::default())?;
+        let mut node_args = BTreeMap::default();
+        if let Some(path) = &reconcile_configuration {
+            node_args.insert(
+                "reconcile-configuration".into(),
+                path.to_string_lossy().to_string().into(),
+            );
+        }
+        let node_process = self.node.run_in_new_process(&opts.global_args, node_args)?;
 
         // Wait for the node to be up
         let is_up = {
@@ -289,20 +331,24 @@ impl NodeConfig {
         })
         .expect("Error setting exit signal handler");
 
-        // Run the other sections
+        // Run the other sections, unless the node creates them from the watched configuration
         let node_name = Some(node_name);
-        let other_sections: Vec<ParsedCommands> = vec![
-            self.policies.into_parsed_commands()?.into(),
-            self.relays.into_parsed_commands(node_name)?.into(),
-            self.tcp_inlets.into_parsed_commands(node_name)?.into(),
-            self.tcp_outlets.into_parsed_commands(node_name)?.into(),
-            self.influxdb_inlets.into_parsed_commands(node_name)?.into(),
-            self.influxdb_outlets
-                .into_parsed_commands(node_name)?
-                .into(),
-            self.kafka_inlet.into_parsed_commands(node_name)?.into(),
-            self.kafka_outlet.into_parsed_commands(node_name)?.into(),
-        ];
+        let other_sections: Vec<ParsedCommands> = if reconcile_configuration.is_some() {
+            vec![]
+        } else {
+            vec![
+                self.policies.into_parsed_commands()?.into(),
+                self.relays.into_parsed_commands(node_name)?.into(),
+                self.tcp_inlets.into_parsed_commands(node_name)?.into(),
+                self.tcp_outlets.into_parsed_commands(node_name)?.into(),
+                self.influxdb_inlets.into_parsed_commands(node_name)?.into(),
+                self.influxdb_outlets
+                    .into_parsed_commands(node_name)?
+                    .into(),
+                self.kafka_inlet.into_parsed_commands(node_name)?.into(),
+                self.kafka_outlet.into_parsed_commands(node_name)?.into(),
+            ]
+        };
         for cmds in other_sections {
             cmds.run(ctx, opts).await?;
         }
@@ -312,6 +358,25 @@ impl NodeConfig {
         Ok(())
     }
 
+    /// Build the `project enroll` and `node create` commands only, for a node
+    /// which keeps the other resources in sync with its configuration file
+    fn parse_node_commands(
+        self,
+        identity_name: &String,
+        reconcile_configuration: PathBuf,
+    ) -> miette::Result<Vec<ParsedCommands>> {
+        let mut node = self.node.into_parsed_commands()?;
+        for cmd in node.iter_mut() {
+            cmd.config_args.reconcile_configuration = Some(reconcile_configuration.clone());
+        }
+        Ok(vec![
+            self.project_enroll
+                .into_parsed_commands(Some(identity_name))?
+                .into(),
+            node.into(),
+        ])
+    }
+
     /// Build commands and return validation errors if any
     fn parse_commands(
         self,
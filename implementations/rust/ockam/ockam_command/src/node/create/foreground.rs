@@ -4,6 +4,7 @@ use miette::{miette, IntoDiagnostic};
 use tokio::time::{sleep, Duration};
 use tracing::{debug, info, instrument};
 
+use crate::node::create::reconcile::ConfigReconciler;
 use crate::node::show::is_node_up;
 use crate::node::CreateCommand;
 use crate::secure_channel::listener::create as secure_channel_listener;
@@ -106,7 +107,8 @@ impl CreateCommand {
         .into_diagnostic()?;
         debug!("in-memory node created");
 
-        let node_manager_worker = NodeManagerWorker::new(Arc::new(node_man));
+        let node_man = Arc::new(node_man);
+        let node_manager_worker = NodeManagerWorker::new(node_man.clone());
         ctx.flow_controls()
             .add_consumer(NODEMANAGER_ADDR, tcp_listener.flow_control_id());
         ctx.start_worker(NODEMANAGER_ADDR, node_manager_worker)
@@ -128,6 +130,12 @@ impl CreateCommand {
             return Err(miette!("Failed to start services"));
         }
 
+        if let Some(path) = &self.config_args.reconcile_configuration {
+            ConfigReconciler::new(node_man, &node_name, path)
+                .start(ctx, &opts)
+                .await?;
+        }
+
         if !self.foreground_args.child_process {
             opts.terminal
                 .clone()
@@ -0,0 +1,604 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use miette::{miette, IntoDiagnostic};
+use sha2::{Digest, Sha256};
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info, warn};
+
+use crate::node::create::config::NodeConfig;
+use crate::run::parser::resource::ParsedCommand;
+use crate::CommandGlobalOpts;
+use ockam::{Address, Context};
+use ockam_abac::{ResourceName, ResourceType};
+use ockam_api::nodes::models::policies::ResourceTypeOrName;
+use ockam_api::nodes::registry::KafkaServiceKind;
+use ockam_api::nodes::InMemoryNode;
+use ockam_api::DefaultAddress;
+use ockam_core::AsyncTryClone;
+
+/// How often the configuration file is checked for changes
+const CONFIGURATION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A resource of the node which can be declared in a configuration file
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ResourceKey {
+    /// A policy for a resource name and an action
+    PolicyForResource(String, String),
+    /// A policy for a resource type and an action
+    PolicyForResourceType(String, String),
+    /// A relay, identified by its name
+    Relay(String),
+    /// A TCP inlet (or an InfluxDB inlet), identified by its alias
+    TcpInlet(String),
+    /// A TCP outlet (or an InfluxDB outlet), identified by its worker address
+    TcpOutlet(String),
+    /// A Kafka inlet, identified by its service address
+    KafkaInlet(String),
+    /// A Kafka outlet, identified by its service address
+    KafkaOutlet(String),
+}
+
+/// A resource declared in the configuration file, with the command creating it
+pub struct DesiredResource {
+    pub key: ResourceKey,
+    /// Hash of the command arguments, used to detect changes
+    pub fingerprint: String,
+    command: Box<dyn ParsedCommand>,
+}
+
+impl DesiredResource {
+    fn new<C: ParsedCommand>(key: ResourceKey, args: &[String], command: C) -> Self {
+        Self {
+            key,
+            fingerprint: Self::fingerprint(args),
+            command: Box::new(command),
+        }
+    }
+
+    /// Return the SHA-256 hash of the arguments of a command, each argument being prefixed
+    /// with its length so that different lists of arguments can't have the same representation
+    fn fingerprint(args: &[String]) -> String {
+        let mut hasher = Sha256::new();
+        for arg in args {
+            hasher.update((arg.len() as u64).to_be_bytes());
+            hasher.update(arg.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Keeps the resources of a node in sync with its configuration file.
+///
+/// The file is checked periodically, and a reconciliation can be forced with a reload request
+/// sent to the node. The policies, relays, TCP inlets and outlets and Kafka services of the
+/// configuration are compared with the ones created on the node: only the resources which were
+/// added, changed or removed are created, recreated or deleted.
+pub struct ConfigReconciler {
+    node_manager: Arc<InMemoryNode>,
+    node_name: String,
+    path: PathBuf,
+    /// Fingerprints of the resources created from the configuration file
+    applied: BTreeMap<ResourceKey, String>,
+}
+
+impl ConfigReconciler {
+    pub fn new(node_manager: Arc<InMemoryNode>, node_name: &str, path: &Path) -> Self {
+        Self {
+            node_manager,
+            node_name: node_name.to_string(),
+            path: path.to_path_buf(),
+            applied: BTreeMap::new(),
+        }
+    }
+
+    /// Start watching the configuration file in the background.
+    /// The resources of the configuration are created right away
+    pub async fn start(self, ctx: &Context, opts: &CommandGlobalOpts) -> miette::Result<()> {
+        let ctx = ctx.async_try_clone().await.into_diagnostic()?;
+        let opts = opts.clone();
+        info!(path = %self.path.display(), "watching the node configuration");
+        tokio::spawn(self.run(ctx, opts));
+        Ok(())
+    }
+
+    async fn run(mut self, ctx: Context, opts: CommandGlobalOpts) {
+        let mut reloads = self.node_manager.subscribe_to_configuration_reloads();
+        let mut poll = interval(CONFIGURATION_POLL_INTERVAL);
+        let mut last_contents: Option<String> = None;
+        loop {
+            let reload_requested = tokio::select! {
+                _ = poll.tick() => false,
+                changed = reloads.changed() => {
+                    if changed.is_err() {
+                        // The node manager was dropped, the node is stopping
+                        return;
+                    }
+                    true
+                }
+            };
+
+            let contents = match std::fs::read_to_string(&self.path) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    warn!(path = %self.path.display(), "cannot read the node configuration: {e}");
+                    continue;
+                }
+            };
+            if !reload_requested && last_contents.as_ref() == Some(&contents) {
+                continue;
+            }
+
+            match self.reconcile(&ctx, &opts, contents.clone()).await {
+                Ok(()) => {
+                    info!(path = %self.path.display(), "the node configuration was applied");
+                    last_contents = Some(contents);
+                }
+                Err(e) => {
+                    // Retry on the next tick
+                    error!(path = %self.path.display(), "cannot apply the node configuration: {e:?}");
+                    last_contents = None;
+                }
+            }
+        }
+    }
+
+    /// Create, recreate or delete the resources which differ between the configuration
+    /// and the node
+    async fn reconcile(
+        &mut self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        contents: String,
+    ) -> miette::Result<()> {
+        let desired = desired_resources(NodeConfig::parse(contents)?, &self.node_name)?;
+        let existing = self.existing_resources().await?;
+        let changes = Changes::new(&self.applied, &existing, &desired);
+
+        for key in changes.delete.iter() {
+            self.delete(ctx, key).await?;
+            self.applied.remove(key);
+        }
+        // Forget the resources which were removed from the configuration
+        self.applied
+            .retain(|key, _| desired.iter().any(|r| &r.key == key));
+
+        for resource in desired {
+            if !changes.create.contains(&resource.key) {
+                continue;
+            }
+            debug!(resource = ?resource.key, "creating a resource from the node configuration");
+            let ctx = ctx.async_try_clone().await.into_diagnostic()?;
+            resource.command.run(&ctx, opts).await?;
+            self.applied.insert(resource.key, resource.fingerprint);
+        }
+        Ok(())
+    }
+
+    /// Return the keys of the resources currently registered on the node
+    async fn existing_resources(&self) -> miette::Result<BTreeSet<ResourceKey>> {
+        let mut keys = BTreeSet::new();
+        for policy in self.node_manager.get_policies(None).await?.all() {
+            let action = policy.action().to_string();
+            keys.insert(match policy.resource() {
+                ResourceTypeOrName::Type(t) => {
+                    ResourceKey::PolicyForResourceType(t.to_string(), action)
+                }
+                ResourceTypeOrName::Name(n) => {
+                    ResourceKey::PolicyForResource(n.to_string(), action)
+                }
+            });
+        }
+        for relay in self.node_manager.get_relays().await {
+            keys.insert(ResourceKey::Relay(relay.name().to_string()));
+        }
+        for inlet in self.node_manager.list_inlets().await {
+            keys.insert(ResourceKey::TcpInlet(inlet.alias));
+        }
+        for outlet in self.node_manager.list_outlets().await {
+            keys.insert(ResourceKey::TcpOutlet(
+                outlet.worker_addr.address().to_string(),
+            ));
+        }
+        for service in self.node_manager.list_services().await {
+            match service.service_type.as_str() {
+                DefaultAddress::KAFKA_INLET => keys.insert(ResourceKey::KafkaInlet(service.addr)),
+                DefaultAddress::KAFKA_OUTLET => keys.insert(ResourceKey::KafkaOutlet(service.addr)),
+                _ => false,
+            };
+        }
+        Ok(keys)
+    }
+
+    /// Delete a resource from the node
+    async fn delete(&self, ctx: &Context, key: &ResourceKey) -> miette::Result<()> {
+        debug!(resource = ?key, "deleting a resource from the node configuration");
+        let node_manager = &self.node_manager;
+        match key {
+            ResourceKey::PolicyForResource(resource, action) => {
+                let resource = ResourceTypeOrName::Name(ResourceName::new(resource));
+                node_manager.delete_policy(resource, action).await?
+            }
+            ResourceKey::PolicyForResourceType(resource_type, action) => {
+                let resource_type = ResourceType::from_str(resource_type).into_diagnostic()?;
+                let resource = ResourceTypeOrName::Type(resource_type);
+                node_manager.delete_policy(resource, action).await?
+            }
+            ResourceKey::Relay(name) => node_manager.delete_relay_impl(name).await?,
+            ResourceKey::TcpInlet(alias) => {
+                node_manager.delete_inlet(alias).await?;
+            }
+            ResourceKey::TcpOutlet(address) => {
+                node_manager
+                    .delete_outlet(&Address::from_string(address))
+                    .await?;
+            }
+            ResourceKey::KafkaInlet(address) => {
+                node_manager
+                    .delete_kafka_service(
+                        ctx,
+                        Address::from_string(address),
+                        KafkaServiceKind::Inlet,
+                    )
+                    .await?;
+            }
+            ResourceKey::KafkaOutlet(address) => {
+                node_manager
+                    .delete_kafka_service(
+                        ctx,
+                        Address::from_string(address),
+                        KafkaServiceKind::Outlet,
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resources to delete from the node and to create from the configuration
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Changes {
+    delete: Vec<ResourceKey>,
+    create: Vec<ResourceKey>,
+}
+
+impl Changes {
+    /// Compare the resources created from the configuration (`applied`) and the resources of the
+    /// node (`existing`) with the resources of the configuration (`desired`)
+    fn new(
+        applied: &BTreeMap<ResourceKey, String>,
+        existing: &BTreeSet<ResourceKey>,
+        desired: &[DesiredResource],
+    ) -> Self {
+        let mut changes = Self::default();
+
+        // Delete the resources which were changed or removed from the configuration
+        for (key, fingerprint) in applied {
+            let unchanged = desired
+                .iter()
+                .any(|r| &r.key == key && &r.fingerprint == fingerprint);
+            if !unchanged && existing.contains(key) {
+                changes.delete.push(key.clone());
+            }
+        }
+
+        // Create the resources which are missing or were changed
+        for resource in desired {
+            let exists = existing.contains(&resource.key);
+            if exists && applied.get(&resource.key) == Some(&resource.fingerprint) {
+                continue;
+            }
+            // A resource created outside of the configuration is replaced
+            if exists && !changes.delete.contains(&resource.key) {
+                changes.delete.push(resource.key.clone());
+            }
+            changes.create.push(resource.key.clone());
+        }
+        changes
+    }
+}
+
+/// Return the resources declared in the configuration for the given node, in creation order.
+/// Resources declared for other nodes are ignored
+pub fn desired_resources(
+    config: NodeConfig,
+    node_name: &String,
+) -> miette::Result<Vec<DesiredResource>> {
+    let is_other_node = |at: &Option<String>, key: &ResourceKey| {
+        let other = at.as_ref().is_some_and(|at| at != node_name);
+        if other {
+            warn!(resource = ?key, "skipping a resource which is not declared for the node {node_name}");
+        }
+        other
+    };
+    let default_node_name = Some(node_name);
+    let mut resources = vec![];
+
+    for (args, mut cmd) in config.policies.into_parsed_commands_with_args()? {
+        let resource_type = match (&cmd.resource_type, &cmd.resource) {
+            (Some(resource_type), _) => Some(resource_type.clone()),
+            // A resource named after a resource type is handled as a resource type
+            (None, Some(resource)) => ResourceType::from_str(resource.as_str()).ok(),
+            (None, None) => None,
+        };
+        let action = cmd.action.to_string();
+        let key = match (resource_type, &cmd.resource) {
+            (Some(resource_type), _) => {
+                ResourceKey::PolicyForResourceType(resource_type.to_string(), action)
+            }
+            (None, Some(resource)) => ResourceKey::PolicyForResource(resource.to_string(), action),
+            (None, None) => {
+                return Err(miette!("A policy must have a resource or a resource type"))
+            }
+        };
+        if is_other_node(&cmd.at, &key) {
+            continue;
+        }
+        cmd.at = Some(node_name.to_string());
+        resources.push(DesiredResource::new(key, &args, cmd));
+    }
+
+    for (args, cmd) in config
+        .relays
+        .into_parsed_commands_with_args(default_node_name)?
+    {
+        let key = ResourceKey::Relay(cmd.relay_name.clone());
+        if !is_other_node(&cmd.to, &key) {
+            resources.push(DesiredResource::new(key, &args, cmd));
+        }
+    }
+
+    for (args, cmd) in config
+        .tcp_inlets
+        .into_parsed_commands_with_args(default_node_name)?
+    {
+        let key = ResourceKey::TcpInlet(inlet_alias(&cmd.alias)?);
+        if !is_other_node(&cmd.at, &key) {
+            resources.push(DesiredResource::new(key, &args, cmd));
+        }
+    }
+
+    for (args, cmd) in config
+        .tcp_outlets
+        .into_parsed_commands_with_args(default_node_name)?
+    {
+        let key = ResourceKey::TcpOutlet(outlet_address(&cmd.from));
+        if !is_other_node(&cmd.at, &key) {
+            resources.push(DesiredResource::new(key, &args, cmd));
+        }
+    }
+
+    for (args, cmd) in config
+        .influxdb_inlets
+        .into_parsed_commands_with_args(default_node_name)?
+    {
+        let key = ResourceKey::TcpInlet(inlet_alias(&cmd.tcp_inlet.alias)?);
+        if !is_other_node(&cmd.tcp_inlet.at, &key) {
+            resources.push(DesiredResource::new(key, &args, cmd));
+        }
+    }
+
+    for (args, cmd) in config
+        .influxdb_outlets
+        .into_parsed_commands_with_args(default_node_name)?
+    {
+        let key = ResourceKey::TcpOutlet(outlet_address(&cmd.tcp_outlet.from));
+        if !is_other_node(&cmd.tcp_outlet.at, &key) {
+            resources.push(DesiredResource::new(key, &args, cmd));
+        }
+    }
+
+    for (args, cmd) in config
+        .kafka_inlet
+        .into_parsed_commands_with_args(default_node_name)?
+    {
+        let key = ResourceKey::KafkaInlet(cmd.addr.clone());
+        if !is_other_node(&cmd.node_opts.at_node, &key) {
+            resources.push(DesiredResource::new(key, &args, cmd));
+        }
+    }
+
+    for (args, cmd) in config
+        .kafka_outlet
+        .into_parsed_commands_with_args(default_node_name)?
+    {
+        let key = ResourceKey::KafkaOutlet(cmd.addr.clone());
+        if !is_other_node(&cmd.node_opts.at_node, &key) {
+            resources.push(DesiredResource::new(key, &args, cmd));
+        }
+    }
+
+    let mut keys = BTreeSet::new();
+    for resource in resources.iter() {
+        if !keys.insert(resource.key.clone()) {
+            return Err(miette!(
+                "The resource {:?} is declared more than once in the configuration",
+                resource.key
+            ));
+        }
+    }
+    Ok(resources)
+}
+
+/// Inlets must be named to be compared with the inlets of the node
+fn inlet_alias(alias: &Option<String>) -> miette::Result<String> {
+    alias.clone().ok_or(miette!(
+        "The inlets of a watched configuration must be named"
+    ))
+}
+
+/// Outlets without an address use the default outlet address
+fn outlet_address(from: &Option<String>) -> String {
+    from.clone()
+        .unwrap_or(DefaultAddress::OUTLET_SERVICE.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resources(contents: &str) -> Vec<DesiredResource> {
+        let config = NodeConfig::parse(contents.to_string()).unwrap();
+        desired_resources(config, &"n1".to_string()).unwrap()
+    }
+
+    fn keys(contents: &str) -> Vec<ResourceKey> {
+        resources(contents).into_iter().map(|r| r.key).collect()
+    }
+
+    fn outlet(name: &str) -> ResourceKey {
+        ResourceKey::TcpOutlet(name.to_string())
+    }
+
+    #[test]
+    fn desired_resources_of_a_configuration() {
+        let contents = r#"
+            name: n1
+            policies:
+              - resource-type: tcp-outlet
+                expression: (= subject.component "db")
+            relays:
+              - r1
+            tcp-inlets:
+              db-inlet:
+                from: 127.0.0.1:5432
+                via: r1
+            tcp-outlets:
+              db-outlet:
+                to: 127.0.0.1:5433
+            kafka-inlet:
+              from: 127.0.0.1:9092
+              to: /project/default
+        "#;
+        assert_eq!(
+            keys(contents),
+            vec![
+                ResourceKey::PolicyForResourceType(
+                    "tcp-outlet".to_string(),
+                    "handle_message".to_string()
+                ),
+                ResourceKey::Relay("r1".to_string()),
+                ResourceKey::TcpInlet("db-inlet".to_string()),
+                ResourceKey::TcpOutlet("db-outlet".to_string()),
+                ResourceKey::KafkaInlet(DefaultAddress::KAFKA_INLET.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn resources_of_other_nodes_are_skipped() {
+        let contents = r#"
+            name: n1
+            tcp-outlets:
+              o1:
+                to: 127.0.0.1:5433
+              o2:
+                to: 127.0.0.1:5434
+                at: n2
+        "#;
+        assert_eq!(
+            keys(contents),
+            vec![ResourceKey::TcpOutlet("o1".to_string())]
+        );
+    }
+
+    #[test]
+    fn fingerprints_detect_changes() {
+        let fingerprint = |contents: &str| {
+            let config = NodeConfig::parse(contents.to_string()).unwrap();
+            desired_resources(config, &"n1".to_string())
+                .unwrap()
+                .pop()
+                .unwrap()
+                .fingerprint
+        };
+        let before = fingerprint("tcp-outlets: {o1: {to: 127.0.0.1:5433}}");
+        assert_eq!(
+            before,
+            fingerprint("tcp-outlets: {o1: {to: 127.0.0.1:5433}}")
+        );
+        assert_ne!(
+            before,
+            fingerprint("tcp-outlets: {o1: {to: 127.0.0.1:5434}}")
+        );
+    }
+
+    #[test]
+    fn reconcile_a_changed_configuration() {
+        let before = resources(
+            r#"
+            tcp-outlets:
+              unchanged: {to: 127.0.0.1:5433}
+              changed: {to: 127.0.0.1:5434}
+              removed: {to: 127.0.0.1:5435}
+            "#,
+        );
+        let after = resources(
+            r#"
+            tcp-outlets:
+              unchanged: {to: 127.0.0.1:5433}
+              changed: {to: 127.0.0.1:6434}
+              added: {to: 127.0.0.1:5436}
+            "#,
+        );
+
+        // All the resources are created the first time
+        let changes = Changes::new(&BTreeMap::new(), &BTreeSet::new(), &before);
+        assert!(changes.delete.is_empty());
+        assert_eq!(
+            changes.create,
+            vec![outlet("changed"), outlet("removed"), outlet("unchanged")]
+        );
+
+        // Then only the added, changed and removed resources are created or deleted
+        let applied: BTreeMap<ResourceKey, String> =
+            before.into_iter().map(|r| (r.key, r.fingerprint)).collect();
+        let existing: BTreeSet<ResourceKey> = applied.keys().cloned().collect();
+        let changes = Changes::new(&applied, &existing, &after);
+        assert_eq!(changes.delete, vec![outlet("changed"), outlet("removed")]);
+        assert_eq!(changes.create, vec![outlet("added"), outlet("changed")]);
+
+        // Nothing changes when the configuration is applied again
+        let applied: BTreeMap<ResourceKey, String> = after
+            .iter()
+            .map(|r| (r.key.clone(), r.fingerprint.clone()))
+            .collect();
+        let existing: BTreeSet<ResourceKey> = applied.keys().cloned().collect();
+        assert_eq!(
+            Changes::new(&applied, &existing, &after),
+            Changes::default()
+        );
+    }
+
+    #[test]
+    fn reconcile_resources_modified_outside_of_the_configuration() {
+        let desired = resources(
+            r#"
+            tcp-outlets:
+              deleted: {to: 127.0.0.1:5433}
+              replaced: {to: 127.0.0.1:5434}
+            "#,
+        );
+        let applied = BTreeMap::from([(desired[0].key.clone(), desired[0].fingerprint.clone())]);
+        let existing = BTreeSet::from([outlet("replaced"), outlet("other")]);
+
+        // A resource deleted from the node is created again, a resource created outside of the
+        // configuration is replaced, and the other resources of the node are left alone
+        let changes = Changes::new(&applied, &existing, &desired);
+        assert_eq!(changes.delete, vec![outlet("replaced")]);
+        assert_eq!(changes.create, vec![outlet("deleted"), outlet("replaced")]);
+    }
+
+    #[test]
+    fn inlets_must_be_named() {
+        let contents = r#"
+            tcp-inlets:
+              - from: 127.0.0.1:5432
+        "#;
+        let config = NodeConfig::parse(contents.to_string()).unwrap();
+        assert!(desired_resources(config, &"n1".to_string()).is_err());
+    }
+}
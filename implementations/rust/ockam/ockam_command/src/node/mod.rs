@@ -7,6 +7,7 @@ use delete::DeleteCommand;
 use list::ListCommand;
 use logs::LogCommand;
 use ockam_api::address::extract_address_value;
+use reload::ReloadCommand;
 use show::ShowCommand;
 use start::StartCommand;
 use stop::StopCommand;
@@ -18,6 +19,7 @@ mod default;
 mod delete;
 mod list;
 mod logs;
+mod reload;
 pub(crate) mod show;
 mod start;
 mod stop;
@@ -62,6 +64,8 @@ pub enum NodeSubcommand {
     #[command(display_order = 800)]
     Stop(StopCommand),
     #[command(display_order = 800)]
+    Reload(ReloadCommand),
+    #[command(display_order = 800)]
     Default(DefaultCommand),
 }
 
@@ -75,6 +79,7 @@ impl NodeSubcommand {
             NodeSubcommand::Show(c) => c.name(),
             NodeSubcommand::Start(c) => c.name(),
             NodeSubcommand::Stop(c) => c.name(),
+            NodeSubcommand::Reload(c) => c.name(),
             NodeSubcommand::Default(c) => c.name(),
         }
     }
@@ -89,6 +94,7 @@ impl NodeCommand {
             NodeSubcommand::Show(c) => c.run(opts),
             NodeSubcommand::Start(c) => c.run(opts),
             NodeSubcommand::Stop(c) => c.run(opts),
+            NodeSubcommand::Reload(c) => c.run(opts),
             NodeSubcommand::Logs(c) => c.run(opts),
             NodeSubcommand::Default(c) => c.run(opts),
         }
@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/reload/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/reload/after_long_help.txt");
+
+/// Reload the configuration file of a running node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ReloadCommand {
+    /// The name of the node to reload.
+    /// If not provided, the default node is used.
+    node_name: Option<String>,
+}
+
+#[async_trait]
+impl Command for ReloadCommand {
+    const NAME: &'static str = "node reload";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_name).await?;
+        node.tell(ctx, Request::post("/node/reload")).await?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The configuration of the node {} will be reloaded",
+                color_primary(node.node_name())
+            ))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -64,6 +64,7 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         launch_configuration,
         trust_opts,
         opentelemetry_context,
+        config_args,
         ..
     } = cmd;
     let TrustOpts {
@@ -142,6 +143,11 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         args.push("--udp".to_string());
     }
 
+    if let Some(path) = config_args.reconcile_configuration {
+        args.push("--reconcile-configuration".to_string());
+        args.push(path.to_string_lossy().to_string());
+    }
+
     args.push(name.to_owned());
 
     run_ockam(args, opts.global_args.quiet).await
@@ -16,7 +16,7 @@ use crate::docs;
 use crate::node::util::initialize_default_node;
 use crate::{Command, CommandGlobalOpts};
 
-use super::resource_type_parser;
+use super::{action_parser, resource_type_parser};
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
@@ -40,6 +40,11 @@ pub struct CreateCommand {
     #[arg(long)]
     pub resource: Option<ResourceName>,
 
+    /// The action controlled by the policy. If there is no policy for an action,
+    /// the policy for the `handle_message` action is used
+    #[arg(long, default_value = "handle_message", value_parser = action_parser)]
+    pub action: Action,
+
     #[arg(long, visible_alias = "expression", id = "POLICY_EXPRESSION")]
     pub allow: PolicyExpression,
 }
@@ -68,7 +73,7 @@ impl Command for CreateCommand {
             .into_diagnostic()?;
 
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
-        node.add_policy(ctx, &resource, &Action::HandleMessage, &self.allow)
+        node.add_policy(ctx, &resource, &self.action, &self.allow)
             .await?;
         opts.terminal
             .stdout()
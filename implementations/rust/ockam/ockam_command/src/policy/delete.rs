@@ -5,6 +5,7 @@ use colorful::Colorful;
 use console::Term;
 use miette::IntoDiagnostic;
 
+use super::action_parser;
 use crate::CommandGlobalOpts;
 use ockam::Context;
 use ockam_abac::{Action, ResourceType};
@@ -23,6 +24,10 @@ use crate::util::async_cmd;
 pub struct DeleteCommand {
     resource: Option<ResourceTypeOrName>,
 
+    /// The action controlled by the policy
+    #[arg(long, default_value = "handle_message", value_parser = action_parser)]
+    action: Action,
+
     #[arg(long, display_order = 900, id = "NODE_NAME")]
     at: Option<String>,
 
@@ -119,7 +124,7 @@ impl DeleteCommandTui for DeleteTui {
             ResourceTypeOrName::Name(resource.into())
         };
         self.node
-            .delete_policy(&self.ctx, &resource, &Action::HandleMessage)
+            .delete_policy(&self.ctx, &resource, &self.cmd.action)
             .await?;
         let resource_kind = match resource {
             ResourceTypeOrName::Type(_) => "resource type",
@@ -3,7 +3,7 @@ use std::str::FromStr;
 use clap::{Args, Subcommand};
 use miette::miette;
 
-use ockam_abac::ResourceType;
+use ockam_abac::{Action, ResourceType};
 
 pub use crate::policy::create::CreateCommand;
 use crate::policy::delete::DeleteCommand;
@@ -57,6 +57,13 @@ impl PolicyCommand {
     }
 }
 
+pub(crate) fn action_parser(input: &str) -> miette::Result<Action> {
+    Action::from_str(input).map_err(|_| {
+        let valid_values = Action::join_enum_values_as_string();
+        miette!(format!("Valid values are: {valid_values}"))
+    })
+}
+
 pub(crate) fn resource_type_parser(input: &str) -> miette::Result<ResourceType> {
     ResourceType::from_str(input).map_err(|_| {
         let valid_values = ResourceType::join_enum_values_as_string();
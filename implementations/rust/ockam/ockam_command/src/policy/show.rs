@@ -5,6 +5,7 @@ use colorful::Colorful;
 use console::Term;
 use miette::IntoDiagnostic;
 
+use super::action_parser;
 use crate::CommandGlobalOpts;
 use ockam::Context;
 use ockam_abac::{Action, ResourceType};
@@ -23,6 +24,10 @@ use crate::util::async_cmd;
 pub struct ShowCommand {
     resource: Option<ResourceTypeOrName>,
 
+    /// The action controlled by the policy
+    #[arg(long, default_value = "handle_message", value_parser = action_parser)]
+    action: Action,
+
     #[arg(long, display_order = 900, id = "NODE_NAME")]
     at: Option<String>,
 }
@@ -48,6 +53,7 @@ pub struct ShowTui {
     opts: CommandGlobalOpts,
     node: BackgroundNodeClient,
     resource: Option<ResourceTypeOrName>,
+    action: Action,
 }
 
 impl ShowTui {
@@ -62,6 +68,7 @@ impl ShowTui {
             opts,
             node,
             resource: cmd.resource,
+            action: cmd.action,
         };
         tui.show().await
     }
@@ -115,7 +122,7 @@ impl ShowCommandTui for ShowTui {
         };
         let policy = self
             .node
-            .show_policy(&self.ctx, &resource, &Action::HandleMessage)
+            .show_policy(&self.ctx, &resource, &self.action)
             .await?;
         let resource_kind = match resource {
             ResourceTypeOrName::Type(_) => "resource type",
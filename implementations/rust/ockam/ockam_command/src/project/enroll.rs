@@ -1,4 +1,5 @@
 use std::fmt::{Debug, Formatter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -16,7 +17,10 @@ use crate::util::parsers::duration_parser;
 use crate::value_parsers::parse_enrollment_ticket;
 use crate::{docs, Command, CommandGlobalOpts, Error, Result};
 use ockam::Context;
+use ockam_api::authenticator::svid::X509Svid;
 use ockam_api::cli_state::{EnrollmentTicket, NamedIdentity};
+use ockam_api::cloud::enroll::auth0::{OidcToken, TokenType};
+use ockam_api::cloud::enroll::Token;
 use ockam_api::cloud::project::models::OktaAuth0;
 use ockam_api::cloud::project::ProjectsOrchestratorApi;
 use ockam_api::cloud::AuthorityNodeClient;
@@ -32,7 +36,7 @@ use ockam_api::{fmt_log, fmt_ok};
 const LONG_ABOUT: &str = include_str!("./static/enroll/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/enroll/after_long_help.txt");
 
-/// Use an enrollment ticket, or Okta, to enroll an identity with a project
+/// Use an enrollment ticket, Okta, a JWT or an SVID to enroll an identity with a project
 #[derive(Clone, Args)]
 #[command(
 long_about = docs::about(LONG_ABOUT),
@@ -58,6 +62,36 @@ pub struct EnrollCommand {
     #[arg(display_order = 900, long = "okta", group = "authentication_method")]
     pub okta: bool,
 
+    /// Use a JWT issued by the OpenID Connect provider trusted by the project authority,
+    /// instead of an enrollment ticket
+    #[arg(
+        display_order = 900,
+        long = "jwt",
+        value_name = "JWT",
+        group = "authentication_method"
+    )]
+    pub jwt: Option<String>,
+
+    /// Path of a PEM file containing a SPIFFE X.509 SVID certificate chain, starting with
+    /// the leaf certificate, to use instead of an enrollment ticket
+    #[arg(
+        display_order = 900,
+        long = "svid",
+        value_name = "PATH",
+        group = "authentication_method",
+        requires = "svid_key"
+    )]
+    pub svid: Option<PathBuf>,
+
+    /// Path of a PEM file containing the private key of the SVID
+    #[arg(
+        display_order = 900,
+        long = "svid-key",
+        value_name = "PATH",
+        requires = "svid"
+    )]
+    pub svid_key: Option<PathBuf>,
+
     #[command(flatten)]
     pub retry_opts: RetryOpts,
 
@@ -66,13 +100,14 @@ pub struct EnrollCommand {
     pub timeout: Duration,
 }
 
-/// This custom Debug instance hides the enrollment ticket
+/// This custom Debug instance hides the enrollment ticket and the JWT
 impl Debug for EnrollCommand {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EnrollCommand")
             .field("identity_opts", &self.identity_opts)
             .field("trust_opts", &self.trust_opts)
             .field("okta", &self.okta)
+            .field("svid", &self.svid)
             .field("retry_opts", &self.retry_opts)
             .field("timeout", &self.timeout)
             .finish()
@@ -127,6 +162,12 @@ impl Command for EnrollCommand {
         if self.okta {
             self.use_okta(ctx, &opts, &authority_node_client).await?;
             node.get_project(ctx, project.project_id()).await?;
+        } else if let Some(jwt) = &self.jwt {
+            self.use_jwt(ctx, &opts, &authority_node_client, jwt)
+                .await?;
+        } else if let (Some(svid), Some(svid_key)) = (&self.svid, &self.svid_key) {
+            self.use_svid(ctx, &opts, &authority_node_client, svid, svid_key)
+                .await?;
         } else if let Some(enrollment_ticket) = enrollment_ticket {
             self.use_enrollment_ticket(ctx, &opts, &authority_node_client, enrollment_ticket)
                 .await?;
@@ -190,6 +231,66 @@ impl EnrollCommand {
                 .present_token(ctx, &enrollment_ticket.one_time_code)
                 .await?
         };
+        Self::check_enroll_status(opts, enroll_status)
+    }
+
+    async fn use_jwt(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        authority_node_client: &AuthorityNodeClient,
+        jwt: &str,
+    ) -> Result<()> {
+        let enroll_status = {
+            let pb = opts.terminal.spinner();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message("Using a JWT to enroll identity...");
+            }
+            let token = OidcToken {
+                token_type: TokenType::Bearer,
+                access_token: Token::new(jwt),
+            };
+            authority_node_client
+                .enroll_with_jwt(ctx, token)
+                .await
+                .map_err(Error::Retry)?
+        };
+        Self::check_enroll_status(opts, enroll_status)
+    }
+
+    async fn use_svid(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        authority_node_client: &AuthorityNodeClient,
+        svid: &Path,
+        svid_key: &Path,
+    ) -> Result<()> {
+        let certificate_chain = std::fs::read_to_string(svid)
+            .into_diagnostic()
+            .wrap_err(format!("Cannot read the SVID file {}", svid.display()))?;
+        let private_key = std::fs::read_to_string(svid_key)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "Cannot read the SVID key file {}",
+                svid_key.display()
+            ))?;
+        let svid = X509Svid::from_pem(&certificate_chain, &private_key)?;
+
+        let enroll_status = {
+            let pb = opts.terminal.spinner();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message("Using an SVID to enroll identity...");
+            }
+            authority_node_client
+                .enroll_with_svid(ctx, &svid)
+                .await
+                .map_err(Error::Retry)?
+        };
+        Self::check_enroll_status(opts, enroll_status)
+    }
+
+    fn check_enroll_status(opts: &CommandGlobalOpts, enroll_status: EnrollStatus) -> Result<()> {
         match enroll_status {
             EnrollStatus::EnrolledSuccessfully => {}
             EnrollStatus::AlreadyEnrolled => {
@@ -1,4 +1,3 @@
-use clap::Args as ClapArgs;
 use miette::{miette, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -24,7 +23,6 @@ pub trait ArgsToCommands: Sized {
     /// return all the commands that can be created from a section of the configuration file.
     fn into_commands<C, F>(self, get_subcommand: F) -> Result<Vec<C>>
     where
-        C: ClapArgs,
         F: Fn(&[String]) -> Result<C>,
     {
         self.into_commands_with_name_arg(get_subcommand, None)
@@ -38,7 +36,6 @@ pub trait ArgsToCommands: Sized {
         _name_arg_key: Option<&str>,
     ) -> Result<Vec<C>>
     where
-        C: ClapArgs,
         F: Fn(&[String]) -> Result<C>,
     {
         Err(miette!("The command does not support named resources"))
@@ -63,7 +60,6 @@ pub enum ResourcesContainer {
 impl ArgsToCommands for ResourcesContainer {
     fn into_commands<C, F>(self, get_subcommand: F) -> Result<Vec<C>>
     where
-        C: ClapArgs,
         F: Fn(&[String]) -> Result<C>,
     {
         match self {
@@ -111,7 +107,6 @@ impl ArgsToCommands for NamedResources {
         name_arg_key: Option<&str>,
     ) -> Result<Vec<C>>
     where
-        C: ClapArgs,
         F: Fn(&[String]) -> Result<C>,
     {
         self.items
@@ -168,7 +163,6 @@ impl ArgsToCommands for ResourceNameOrMap {
         name_arg_key: Option<&str>,
     ) -> Result<Vec<C>>
     where
-        C: ClapArgs,
         F: Fn(&[String]) -> Result<C>,
     {
         match self {
@@ -211,7 +205,6 @@ pub enum UnnamedResources {
 impl ArgsToCommands for UnnamedResources {
     fn into_commands<C, F>(self, get_subcommand: F) -> Result<Vec<C>>
     where
-        C: ClapArgs,
         F: Fn(&[String]) -> Result<C>,
     {
         let items = match self {
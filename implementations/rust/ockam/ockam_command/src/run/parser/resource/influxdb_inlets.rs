@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::run::parser::building_blocks::{ArgsToCommands, ResourceNameOrMap};
 
 use crate::influxdb::inlet::create::InfluxDBCreateCommand;
-use crate::run::parser::resource::utils::parse_cmd_from_args;
+use crate::run::parser::resource::utils::{parse_cmd_from_args, with_args};
 use crate::{influxdb::inlet, Command, OckamSubcommand};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -32,12 +32,24 @@ impl InfluxDBInlets {
         self,
         default_node_name: Option<&String>,
     ) -> Result<Vec<InfluxDBCreateCommand>> {
+        Ok(self
+            .into_parsed_commands_with_args(default_node_name)?
+            .into_iter()
+            .map(|(_, cmd)| cmd)
+            .collect())
+    }
+
+    /// Return the parsed commands, with the arguments used to create them
+    pub fn into_parsed_commands_with_args(
+        self,
+        default_node_name: Option<&String>,
+    ) -> Result<Vec<(Vec<String>, InfluxDBCreateCommand)>> {
         match self.influxdb_inlets {
             Some(c) => {
                 let mut cmds =
-                    c.into_commands_with_name_arg(Self::get_subcommand, Some("alias"))?;
+                    c.into_commands_with_name_arg(with_args(Self::get_subcommand), Some("alias"))?;
                 if let Some(node_name) = default_node_name.as_ref() {
-                    for cmd in cmds.iter_mut() {
+                    for (_, cmd) in cmds.iter_mut() {
                         if cmd.tcp_inlet.at.is_none() {
                             cmd.tcp_inlet.at = Some(node_name.to_string())
                         }
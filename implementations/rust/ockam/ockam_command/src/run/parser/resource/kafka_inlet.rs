@@ -1,7 +1,7 @@
 use crate::kafka::inlet::create::CreateCommand;
 use crate::run::parser::building_blocks::{ArgsToCommands, ResourceNameOrMap};
 
-use crate::run::parser::resource::utils::parse_cmd_from_args;
+use crate::run::parser::resource::utils::{parse_cmd_from_args, with_args};
 use crate::{kafka::inlet, Command, OckamSubcommand};
 use miette::{miette, Result};
 use ockam_api::colors::color_primary;
@@ -31,11 +31,24 @@ impl KafkaInlet {
         self,
         default_node_name: Option<&String>,
     ) -> Result<Vec<CreateCommand>> {
+        Ok(self
+            .into_parsed_commands_with_args(default_node_name)?
+            .into_iter()
+            .map(|(_, cmd)| cmd)
+            .collect())
+    }
+
+    /// Return the parsed commands, with the arguments used to create them
+    pub fn into_parsed_commands_with_args(
+        self,
+        default_node_name: Option<&String>,
+    ) -> Result<Vec<(Vec<String>, CreateCommand)>> {
         match self.kafka_inlet {
             Some(c) => {
-                let mut cmds = c.into_commands_with_name_arg(Self::get_subcommand, Some("addr"))?;
+                let mut cmds =
+                    c.into_commands_with_name_arg(with_args(Self::get_subcommand), Some("addr"))?;
                 if let Some(node_name) = default_node_name {
-                    for cmd in cmds.iter_mut() {
+                    for (_, cmd) in cmds.iter_mut() {
                         if cmd.node_opts.at_node.is_none() {
                             cmd.node_opts.at_node = Some(node_name.to_string())
                         }
@@ -67,6 +80,7 @@ mod tests {
               encrypted-fields:
                 - one
                 - two
+              encrypt-headers: true
         "#;
         let parsed: KafkaInlet = serde_yaml::from_str(unnamed).unwrap();
         let default_node_name = "n1".to_string();
@@ -94,6 +108,8 @@ mod tests {
             cmds[0].encrypted_fields,
             vec!["one".to_string(), "two".to_string()]
         );
+        assert!(cmds[0].encrypt_headers);
+        assert!(!cmds[0].encrypt_keys);
 
         let named = r#"
             kafka-inlet:
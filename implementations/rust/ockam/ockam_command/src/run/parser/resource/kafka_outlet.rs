@@ -6,7 +6,7 @@ use crate::kafka::outlet;
 use crate::kafka::outlet::create::CreateCommand;
 use crate::run::parser::building_blocks::{ArgsToCommands, ResourceNameOrMap};
 
-use crate::run::parser::resource::utils::parse_cmd_from_args;
+use crate::run::parser::resource::utils::{parse_cmd_from_args, with_args};
 use crate::{Command, OckamSubcommand};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -33,11 +33,24 @@ impl KafkaOutlet {
         self,
         default_node_name: Option<&String>,
     ) -> Result<Vec<CreateCommand>> {
+        Ok(self
+            .into_parsed_commands_with_args(default_node_name)?
+            .into_iter()
+            .map(|(_, cmd)| cmd)
+            .collect())
+    }
+
+    /// Return the parsed commands, with the arguments used to create them
+    pub fn into_parsed_commands_with_args(
+        self,
+        default_node_name: Option<&String>,
+    ) -> Result<Vec<(Vec<String>, CreateCommand)>> {
         match self.kafka_outlet {
             Some(c) => {
-                let mut cmds = c.into_commands_with_name_arg(Self::get_subcommand, Some("addr"))?;
+                let mut cmds =
+                    c.into_commands_with_name_arg(with_args(Self::get_subcommand), Some("addr"))?;
                 if let Some(node_name) = default_node_name {
-                    for cmd in cmds.iter_mut() {
+                    for (_, cmd) in cmds.iter_mut() {
                         if cmd.node_opts.at_node.is_none() {
                             cmd.node_opts.at_node = Some(node_name.to_string())
                         }
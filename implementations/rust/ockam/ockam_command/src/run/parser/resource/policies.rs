@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::policy::CreateCommand;
 use crate::run::parser::building_blocks::{ArgsToCommands, UnnamedResources};
 
-use crate::run::parser::resource::utils::parse_cmd_from_args;
+use crate::run::parser::resource::utils::{parse_cmd_from_args, with_args};
 use crate::{policy, Command, OckamSubcommand};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -28,8 +28,17 @@ impl Policies {
     }
 
     pub fn into_parsed_commands(self) -> Result<Vec<CreateCommand>> {
+        Ok(self
+            .into_parsed_commands_with_args()?
+            .into_iter()
+            .map(|(_, cmd)| cmd)
+            .collect())
+    }
+
+    /// Return the parsed commands, with the arguments used to create them
+    pub fn into_parsed_commands_with_args(self) -> Result<Vec<(Vec<String>, CreateCommand)>> {
         match self.policies {
-            Some(c) => c.into_commands(Self::get_subcommand),
+            Some(c) => c.into_commands(with_args(Self::get_subcommand)),
             None => Ok(vec![]),
         }
     }
@@ -38,6 +47,7 @@ impl Policies {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ockam_abac::Action;
 
     #[test]
     fn single_policy_config() {
@@ -91,4 +101,34 @@ mod tests {
         );
         assert_eq!(&cmds[2].allow.to_string(), "(= subject.component \"c3\")");
     }
+
+    #[test]
+    fn policy_config_with_an_action() {
+        let config = r#"
+            policies:
+              - at: n1
+                resource: r1
+                expression: (= subject.component "c1")
+              - at: n2
+                resource-type: tcp-outlet
+                action: connect
+                expression: (= resource.target_port 5432)
+        "#;
+        let parsed: Policies = serde_yaml::from_str(config).unwrap();
+        let cmds = parsed.into_parsed_commands().unwrap();
+        assert_eq!(cmds.len(), 2);
+
+        // the handle_message action is used by default
+        assert_eq!(cmds[0].at.as_ref().unwrap(), "n1");
+        assert_eq!(cmds[0].resource.as_ref().unwrap().as_str(), "r1");
+        assert_eq!(cmds[0].action, Action::HandleMessage);
+
+        assert_eq!(cmds[1].at.as_ref().unwrap(), "n2");
+        assert_eq!(
+            &cmds[1].resource_type.as_ref().unwrap().to_string(),
+            "tcp-outlet"
+        );
+        assert_eq!(cmds[1].action, Action::Connect);
+        assert_eq!(&cmds[1].allow.to_string(), "(= resource.target_port 5432)");
+    }
 }
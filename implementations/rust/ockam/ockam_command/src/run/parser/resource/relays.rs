@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::relay::CreateCommand;
 use crate::run::parser::building_blocks::{ArgsToCommands, ResourcesContainer};
 
-use crate::run::parser::resource::utils::parse_cmd_from_args;
+use crate::run::parser::resource::utils::{parse_cmd_from_args, with_args};
 use crate::{relay, Command, OckamSubcommand};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -31,11 +31,23 @@ impl Relays {
         self,
         default_node_name: Option<&String>,
     ) -> Result<Vec<CreateCommand>> {
+        Ok(self
+            .into_parsed_commands_with_args(default_node_name)?
+            .into_iter()
+            .map(|(_, cmd)| cmd)
+            .collect())
+    }
+
+    /// Return the parsed commands, with the arguments used to create them
+    pub fn into_parsed_commands_with_args(
+        self,
+        default_node_name: Option<&String>,
+    ) -> Result<Vec<(Vec<String>, CreateCommand)>> {
         match self.relays {
             Some(c) => {
-                let mut cmds = c.into_commands(Self::get_subcommand)?;
+                let mut cmds = c.into_commands(with_args(Self::get_subcommand))?;
                 if let Some(node_name) = default_node_name {
-                    for cmd in cmds.iter_mut() {
+                    for (_, cmd) in cmds.iter_mut() {
                         if cmd.to.is_none() {
                             cmd.to = Some(node_name.to_string());
                         }
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::run::parser::building_blocks::{ArgsToCommands, ResourceNameOrMap};
 
-use crate::run::parser::resource::utils::parse_cmd_from_args;
+use crate::run::parser::resource::utils::{parse_cmd_from_args, with_args};
 use crate::tcp::inlet::create::CreateCommand;
 use crate::{tcp::inlet, Command, OckamSubcommand};
 
@@ -31,12 +31,24 @@ impl TcpInlets {
         self,
         default_node_name: Option<&String>,
     ) -> Result<Vec<CreateCommand>> {
+        Ok(self
+            .into_parsed_commands_with_args(default_node_name)?
+            .into_iter()
+            .map(|(_, cmd)| cmd)
+            .collect())
+    }
+
+    /// Return the parsed commands, with the arguments used to create them
+    pub fn into_parsed_commands_with_args(
+        self,
+        default_node_name: Option<&String>,
+    ) -> Result<Vec<(Vec<String>, CreateCommand)>> {
         match self.tcp_inlets {
             Some(c) => {
                 let mut cmds =
-                    c.into_commands_with_name_arg(Self::get_subcommand, Some("alias"))?;
+                    c.into_commands_with_name_arg(with_args(Self::get_subcommand), Some("alias"))?;
                 if let Some(node_name) = default_node_name.as_ref() {
-                    for cmd in cmds.iter_mut() {
+                    for (_, cmd) in cmds.iter_mut() {
                         if cmd.at.is_none() {
                             cmd.at = Some(node_name.to_string())
                         }
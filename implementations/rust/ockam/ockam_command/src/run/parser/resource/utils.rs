@@ -28,6 +28,14 @@ pub fn parse_cmd_from_args(cmd: &str, args: &[String]) -> miette::Result<OckamSu
         .subcommand)
 }
 
+/// Wrap a function parsing a command from a list of arguments,
+/// to also return the arguments used to create the command
+pub fn with_args<C>(
+    get_subcommand: impl Fn(&[String]) -> miette::Result<C>,
+) -> impl Fn(&[String]) -> miette::Result<(Vec<String>, C)> {
+    move |args| Ok((args.to_vec(), get_subcommand(args)?))
+}
+
 pub fn subprocess_stdio(quiet: bool) -> Stdio {
     if quiet {
         // If we're running in quiet mode, we don't need to propagate
@@ -59,6 +59,8 @@ use crate::tcp::connection::TcpConnectionCommand;
 use crate::tcp::inlet::TcpInletCommand;
 use crate::tcp::listener::TcpListenerCommand;
 use crate::tcp::outlet::TcpOutletCommand;
+use crate::udp::inlet::UdpInletCommand;
+use crate::udp::outlet::UdpOutletCommand;
 use crate::util::async_cmd;
 use crate::vault::VaultCommand;
 use crate::worker::WorkerCommand;
@@ -90,6 +92,8 @@ pub enum OckamSubcommand {
     TcpConnection(TcpConnectionCommand),
     TcpOutlet(TcpOutletCommand),
     TcpInlet(TcpInletCommand),
+    UdpOutlet(UdpOutletCommand),
+    UdpInlet(UdpInletCommand),
 
     #[command(name = "influxdb-inlet")]
     InfluxDBInlet(InfluxDBInletCommand),
@@ -154,6 +158,8 @@ impl OckamSubcommand {
             OckamSubcommand::TcpConnection(c) => c.run(opts),
             OckamSubcommand::TcpOutlet(c) => c.run(opts),
             OckamSubcommand::TcpInlet(c) => c.run(opts),
+            OckamSubcommand::UdpOutlet(c) => c.run(opts),
+            OckamSubcommand::UdpInlet(c) => c.run(opts),
 
             OckamSubcommand::InfluxDBInlet(c) => c.run(opts),
             OckamSubcommand::InfluxDBOutlet(c) => c.run(opts),
@@ -304,6 +310,8 @@ impl OckamSubcommand {
             OckamSubcommand::TcpConnection(c) => c.name(),
             OckamSubcommand::TcpOutlet(c) => c.name(),
             OckamSubcommand::TcpInlet(c) => c.name(),
+            OckamSubcommand::UdpOutlet(c) => c.name(),
+            OckamSubcommand::UdpInlet(c) => c.name(),
             OckamSubcommand::InfluxDBInlet(c) => c.name(),
             OckamSubcommand::InfluxDBOutlet(c) => c.name(),
             OckamSubcommand::Rendezvous(c) => c.name(),
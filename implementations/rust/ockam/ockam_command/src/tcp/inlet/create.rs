@@ -60,6 +60,10 @@ pub struct CreateCommand {
     /// or just the name of the service as `outlet` or `/service/outlet`.
     /// If you are passing just the service name, consider using `--via` to specify the
     /// relay name (e.g. `ockam tcp-inlet create --to outlet --via myrelay`).
+    ///
+    /// A remote node can also be reached over UDP as `/ip4/10.0.0.1/udp/4000/secure/api/service/outlet`
+    /// (the node must be created with `--udp`), over WebSocket as `/dnsaddr/example.com/tcp/4000/ws/secure/api/service/outlet`,
+    /// or over a Unix domain socket as `/unix/%2Ftmp%2Fnode.sock/secure/api/service/outlet`.
     #[arg(long, display_order = 900, id = "ROUTE", default_value_t = default_to_addr())]
     pub to: String,
 
@@ -148,6 +152,11 @@ pub struct CreateCommand {
     /// Enable TLS for the TCP Inlet using the provided certificate provider.
     /// Requires `ockam-tls-certificate` credential attribute.
     pub tls_certificate_provider: Option<MultiAddr>,
+
+    /// Accept SOCKS5 connections and ask the TCP Outlet to connect to the destination
+    /// requested by each client. The TCP Outlet must be created with `--dynamic`.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["privileged", "tls", "tls_certificate_provider"])]
+    pub socks5: bool,
 }
 
 pub(crate) fn default_from_addr() -> HostnamePort {
@@ -197,6 +206,7 @@ impl Command for CreateCommand {
                         cmd.no_tcp_fallback,
                         cmd.privileged,
                         &cmd.tls_certificate_provider,
+                        cmd.socks5,
                     )
                     .await?;
 
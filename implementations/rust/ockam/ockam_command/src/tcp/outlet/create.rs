@@ -5,10 +5,11 @@ use async_trait::async_trait;
 use clap::builder::FalseyValueParser;
 use clap::Args;
 use colorful::Colorful;
-use miette::IntoDiagnostic;
+use miette::{miette, IntoDiagnostic};
 
 use crate::node::util::initialize_default_node;
 use crate::{docs, Command, CommandGlobalOpts};
+use ockam::tcp::DestinationPattern;
 use ockam::transport::HostnamePort;
 use ockam::Address;
 use ockam::Context;
@@ -18,8 +19,8 @@ use ockam_api::cli_state::journeys::{
     JourneyEvent, NODE_NAME, TCP_OUTLET_AT, TCP_OUTLET_FROM, TCP_OUTLET_TO,
 };
 use ockam_api::colors::{color_primary, color_primary_alt};
-use ockam_api::nodes::models::portal::OutletStatus;
-use ockam_api::nodes::service::tcp_outlets::Outlets;
+use ockam_api::nodes::models::portal::{HttpIdentityHeaders, OutletStatus};
+use ockam_api::nodes::service::tcp_outlets::{refers_to_destination, Outlets};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::{fmt_info, fmt_ok};
 
@@ -72,6 +73,66 @@ pub struct CreateCommand {
     /// If `OCKAM_PRIVILEGED` env variable is set to 1, this argument will be `true`.
     #[arg(long, env = "OCKAM_PRIVILEGED", value_parser = FalseyValueParser::default(), hide = true)]
     pub privileged: bool,
+
+    /// Let TCP Inlets created with `--socks5` connect to other destinations than `--to`.
+    /// Each destination, including `--to`, must match one of the `--allow-destination` patterns,
+    /// and be authorized by the policy, where `resource.destination_host` and
+    /// `resource.destination_port` can be used.
+    /// If no `--allow-destination` is given, the destinations are only checked with the
+    /// `--allow` expression, which must then use these attributes.
+    /// That expression is the `connect` policy of the outlet, see
+    /// `ockam policy show --resource <OUTLET_ADDRESS> --action connect`.
+    #[arg(long, display_order = 905, conflicts_with = "privileged")]
+    pub dynamic: bool,
+
+    /// Destinations that a dynamic TCP Outlet can connect to, as `host:ports`.
+    /// The host can be `*.domain` or `*`, and the ports can be a range like `8000-8999` or `*`.
+    /// This argument can be repeated.
+    #[arg(long, display_order = 906, id = "DESTINATION_PATTERN", requires = "dynamic", value_parser = DestinationPattern::from_str)]
+    pub allow_destination: Vec<DestinationPattern>,
+
+    /// Send a PROXY protocol v2 header to the TCP server when a connection is opened,
+    /// with the address of the TCP Inlet client and the identifier of the TCP Inlet node.
+    /// The TCP server must accept the PROXY protocol, which nginx, HAProxy or Envoy can do.
+    #[arg(long, display_order = 907, conflicts_with = "privileged")]
+    pub proxy_protocol: bool,
+
+    /// Name of a credential attribute of the TCP Inlet node, to send in the PROXY protocol header.
+    /// This argument can be repeated.
+    #[arg(
+        long,
+        display_order = 908,
+        id = "ATTRIBUTE_NAME",
+        requires = "proxy_protocol"
+    )]
+    pub proxy_protocol_attribute: Vec<String>,
+
+    /// Add headers to the HTTP/1.1 requests sent to the TCP server, with the identifier of the
+    /// TCP Inlet node in `X-Ockam-Identifier`. The `X-Ockam-*` headers set by the client are removed.
+    #[arg(long, display_order = 909, conflicts_with = "privileged")]
+    pub http_identity_headers: bool,
+
+    /// Name of a credential attribute of the TCP Inlet node, to send in an
+    /// `X-Ockam-Attribute-<name>` HTTP header. This argument can be repeated.
+    #[arg(
+        long,
+        display_order = 910,
+        id = "HTTP_ATTRIBUTE_NAME",
+        requires = "http_identity_headers"
+    )]
+    pub http_identity_attribute: Vec<String>,
+
+    /// Key used to sign the HTTP identity headers with an HMAC-SHA256, sent in `X-Ockam-Signature`
+    /// along with the signing time in `X-Ockam-Timestamp`.
+    #[arg(
+        long,
+        display_order = 911,
+        value_name = "KEY",
+        env = "OCKAM_HTTP_IDENTITY_HMAC_KEY",
+        hide_env_values = true,
+        requires = "http_identity_headers"
+    )]
+    pub http_identity_hmac_key: Option<String>,
 }
 
 #[async_trait]
@@ -98,6 +159,15 @@ impl Command for CreateCommand {
                 self.from.clone().map(Address::from).as_ref(),
                 self.allow.clone(),
                 self.privileged,
+                self.allowed_destinations()?,
+                self.proxy_protocol
+                    .then(|| self.proxy_protocol_attribute.clone()),
+                self.http_identity_headers.then(|| {
+                    HttpIdentityHeaders::new(
+                        self.http_identity_attribute.clone(),
+                        self.http_identity_hmac_key.clone(),
+                    )
+                }),
             )
             .await?
         };
@@ -132,6 +202,27 @@ impl Command for CreateCommand {
 }
 
 impl CreateCommand {
+    /// Return the destinations allowed for a dynamic outlet.
+    /// Without any `--allow-destination`, all the destinations are allowed, but only if
+    /// the `--allow` expression restricts them with the destination attributes
+    fn allowed_destinations(&self) -> miette::Result<Option<Vec<DestinationPattern>>> {
+        if !self.dynamic {
+            return Ok(None);
+        }
+        if !self.allow_destination.is_empty() {
+            return Ok(Some(self.allow_destination.clone()));
+        }
+        match &self.allow {
+            Some(expression) if refers_to_destination(expression) => {
+                Ok(Some(vec![DestinationPattern::from_str("*:*").into_diagnostic()?]))
+            }
+            _ => Err(miette!(
+                "A dynamic TCP Outlet needs at least one --allow-destination, \
+                or an --allow expression using resource.destination_host or resource.destination_port"
+            )),
+        }
+    }
+
     pub async fn add_outlet_created_journey_event(
         &self,
         opts: &CommandGlobalOpts,
@@ -156,6 +247,8 @@ impl CreateCommand {
 #[cfg(test)]
 mod tests {
     use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::tcp::outlet::TcpOutletSubCommand;
+    use crate::OckamSubcommand;
 
     use super::*;
 
@@ -167,4 +260,39 @@ mod tests {
         );
         assert!(cmd.is_ok());
     }
+
+    #[test]
+    fn a_dynamic_outlet_must_restrict_its_destinations() {
+        let parse = |args: &[&str]| {
+            let args = ["--to", "127.0.0.1:5000", "--dynamic"]
+                .iter()
+                .chain(args)
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>();
+            match parse_cmd_from_args(CreateCommand::NAME, &args).unwrap() {
+                OckamSubcommand::TcpOutlet(cmd) => match cmd.subcommand {
+                    TcpOutletSubCommand::Create(cmd) => cmd,
+                    _ => panic!("expected a tcp-outlet create command"),
+                },
+                _ => panic!("expected a tcp-outlet command"),
+            }
+        };
+
+        let cmd = parse(&["--allow-destination", "*.internal:443"]);
+        assert_eq!(
+            cmd.allowed_destinations().unwrap(),
+            Some(vec!["*.internal:443".parse().unwrap()])
+        );
+
+        let cmd = parse(&["--allow", "(= resource.destination_port 5432)"]);
+        assert_eq!(
+            cmd.allowed_destinations().unwrap(),
+            Some(vec!["*:*".parse().unwrap()])
+        );
+
+        assert!(parse(&[]).allowed_destinations().is_err());
+        assert!(parse(&["--allow", "(= subject.role \"admin\")"])
+            .allowed_destinations()
+            .is_err());
+    }
 }
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use crate::node::util::initialize_default_node;
+use crate::tcp::inlet::create::default_from_addr;
+use crate::tcp::util::alias_parser;
+use crate::util::parsers::{duration_parser, hostname_parser};
+use crate::{Command, CommandGlobalOpts};
+use ockam::identity::Identifier;
+use ockam::transport::HostnamePort;
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::address::extract_address_value;
+use ockam_api::cli_state::random_name;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::service::udp_portals::UdpPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_multiaddr::MultiAddr;
+
+/// Create a UDP Inlet
+#[derive(Clone, Debug, Args)]
+pub struct CreateCommand {
+    /// Node on which to start the UDP Inlet.
+    #[arg(long, display_order = 900, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Address on which to receive datagrams.
+    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", hide_default_value = true, default_value_t = default_from_addr(), value_parser = hostname_parser)]
+    pub from: HostnamePort,
+
+    /// Route to the UDP Outlet, for example `/node/n1/secure/api/service/udp_outlet`
+    /// or `/project/myproject/service/forward_to_myrelay/secure/api/service/udp_outlet`.
+    #[arg(long, display_order = 900, id = "ROUTE", value_parser = MultiAddr::from_str)]
+    pub to: MultiAddr,
+
+    /// Authorized identifier for secure channel connection
+    #[arg(long, name = "AUTHORIZED", display_order = 900)]
+    pub authorized: Option<Identifier>,
+
+    /// Assign a name to this UDP Inlet.
+    #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser)]
+    pub alias: Option<String>,
+
+    /// Policy expression that will be used for access control to the UDP Inlet.
+    /// If you don't provide it, the policy set for the "udp-inlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type udp-inlet`.
+    #[arg(
+        hide = true,
+        long,
+        visible_alias = "expression",
+        display_order = 900,
+        id = "POLICY_EXPRESSION"
+    )]
+    pub allow: Option<PolicyExpression>,
+
+    /// Close the flow of a client when no datagram was exchanged for this duration.
+    /// If you don't provide it, flows are closed after 60 seconds.
+    #[arg(long, display_order = 900, id = "IDLE_TIMEOUT", value_parser = duration_parser)]
+    pub idle_timeout: Option<Duration>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "udp-inlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let alias = self.alias.clone().unwrap_or_else(random_name);
+        let inlet_status = {
+            let pb = opts.terminal.spinner();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message(format!(
+                    "Creating a UDP Inlet at {}...\n",
+                    color_primary(self.from.to_string())
+                ));
+            }
+            node.create_udp_inlet(
+                ctx,
+                &self.from,
+                &self.to,
+                &alias,
+                &self.authorized,
+                &self.allow,
+                self.idle_timeout,
+            )
+            .await?
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "UDP Inlet {} on node {} is now receiving datagrams at {}, and sending them to {}\n",
+                color_primary(&alias),
+                color_primary(node.node_name()),
+                color_primary(&inlet_status.bind_addr),
+                color_primary(self.to.to_string())
+            ))
+            .machine(inlet_status.bind_addr.to_string())
+            .json(serde_json::to_string(&inlet_status).into_diagnostic()?)
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--to".to_string(),
+                "/node/n1/secure/api/service/udp_outlet".to_string(),
+                "--idle-timeout".to_string(),
+                "30s".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -8,6 +8,7 @@ use ockam_api::{fmt_info, fmt_ok};
 
 use ockam_node::Context;
 
+use crate::vault::util::secrets_encryption;
 use crate::{docs, Command, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
@@ -28,6 +29,16 @@ pub struct CreateCommand {
 
     #[arg(long, default_value = "false")]
     pub aws_kms: bool,
+
+    /// Store the signing keys on a PKCS#11 token, for example an HSM. The token is configured
+    /// with OCKAM_PKCS11_MODULE, OCKAM_PKCS11_TOKEN_LABEL and OCKAM_PKCS11_PIN
+    #[arg(long, default_value = "false", conflicts_with = "aws_kms")]
+    pub pkcs11: bool,
+
+    /// Encrypt the secrets of the vault at rest. The key is derived from the passphrase set with
+    /// OCKAM_VAULT_PASSPHRASE, or read from OCKAM_VAULT_KEY or OCKAM_VAULT_KEY_FILE
+    #[arg(long, default_value = "false")]
+    pub encrypt: bool,
 }
 
 #[async_trait]
@@ -41,10 +52,30 @@ impl Command for CreateCommand {
         ))?;
         }
 
-        let vault = opts
-            .state
-            .create_named_vault(self.name, self.path, UseAwsKms::from(self.aws_kms))
-            .await?;
+        let encryption = if self.encrypt {
+            Some(secrets_encryption()?)
+        } else {
+            None
+        };
+
+        let vault = if self.pkcs11 {
+            opts.state
+                .create_named_pkcs11_vault(self.name, self.path)
+                .await?
+        } else {
+            opts.state
+                .create_named_vault(self.name, self.path, UseAwsKms::from(self.aws_kms))
+                .await?
+        };
+
+        let vault = match encryption {
+            Some(encryption) => {
+                opts.state
+                    .encrypt_named_vault(&vault.name(), encryption)
+                    .await?
+            }
+            None => vault,
+        };
 
         opts.terminal
             .stdout()
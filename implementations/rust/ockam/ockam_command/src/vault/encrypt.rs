@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use ockam_api::fmt_ok;
+
+use ockam_node::Context;
+
+use crate::vault::util::secrets_encryption;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/encrypt/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/encrypt/after_long_help.txt");
+
+/// Encrypt the secrets of a vault at rest
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct EncryptCommand {
+    /// Name of the vault to encrypt. The default vault is used if no name is given
+    #[arg()]
+    pub name: Option<String>,
+}
+
+#[async_trait]
+impl Command for EncryptCommand {
+    const NAME: &'static str = "vault encrypt";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let encryption = secrets_encryption()?;
+        let vault = opts.state.get_named_vault_or_default(&self.name).await?;
+        let vault = opts
+            .state
+            .encrypt_named_vault(&vault.name(), encryption)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The secrets of the vault '{}' are now encrypted!",
+                vault.name()
+            ))
+            .machine(vault.name())
+            .json(serde_json::json!({ "name": &vault.name() }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(EncryptCommand::NAME, &[]);
+        assert!(cmd.is_ok());
+    }
+}
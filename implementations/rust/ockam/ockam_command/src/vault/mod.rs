@@ -2,6 +2,7 @@ use clap::{Args, Subcommand};
 
 pub use crate::vault::create::CreateCommand;
 use crate::vault::delete::DeleteCommand;
+use crate::vault::encrypt::EncryptCommand;
 use crate::vault::list::ListCommand;
 use crate::vault::move_vault::MoveCommand;
 use crate::vault::show::ShowCommand;
@@ -9,6 +10,7 @@ use crate::{docs, Command, CommandGlobalOpts};
 
 mod create;
 mod delete;
+mod encrypt;
 mod list;
 mod move_vault;
 mod show;
@@ -35,6 +37,7 @@ pub enum VaultSubcommand {
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Encrypt(EncryptCommand),
 }
 
 impl VaultCommand {
@@ -45,6 +48,7 @@ impl VaultCommand {
             VaultSubcommand::Show(cmd) => cmd.run(opts),
             VaultSubcommand::List(cmd) => cmd.run(opts),
             VaultSubcommand::Delete(cmd) => cmd.run(opts),
+            VaultSubcommand::Encrypt(cmd) => cmd.run(opts),
         }
     }
 
@@ -55,6 +59,7 @@ impl VaultCommand {
             VaultSubcommand::Show(c) => c.name(),
             VaultSubcommand::Delete(c) => c.name(),
             VaultSubcommand::List(c) => c.name(),
+            VaultSubcommand::Encrypt(c) => c.name(),
         }
     }
 }
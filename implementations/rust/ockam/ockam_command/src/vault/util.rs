@@ -1,10 +1,23 @@
 use colorful::Colorful;
 use indoc::formatdoc;
+use miette::miette;
 
 use ockam_api::cli_state::vaults::NamedVault;
-use ockam_api::cli_state::{UseAwsKms, VaultType};
+use ockam_api::cli_state::{
+    secrets_encryption_from_env, OCKAM_VAULT_KEY, OCKAM_VAULT_KEY_FILE, OCKAM_VAULT_PASSPHRASE,
+};
 use ockam_api::colors::OckamColor;
 use ockam_api::output::{indent, Output};
+use ockam_vault::storage::SecretsEncryption;
+
+/// Return the passphrase or the key used to encrypt the vault secrets at rest
+pub fn secrets_encryption() -> miette::Result<SecretsEncryption> {
+    secrets_encryption_from_env()?.ok_or_else(|| {
+        miette!(
+            "Set {OCKAM_VAULT_PASSPHRASE}, {OCKAM_VAULT_KEY} or {OCKAM_VAULT_KEY_FILE} to encrypt the vault secrets"
+        )
+    })
+}
 
 #[derive(serde::Serialize)]
 pub struct VaultOutput {
@@ -49,61 +62,27 @@ impl Output for VaultOutput {
         .to_string()
         .color(OckamColor::PrimaryResource.color());
 
-        let uses_aws_kms = if self.vault.use_aws_kms() {
-            "true"
-        } else {
-            "false"
-        }
-        .to_string()
-        .color(OckamColor::PrimaryResource.color());
-
-        Ok(match self.vault.vault_type() {
-            VaultType::DatabaseVault {
-                use_aws_kms: UseAwsKms::No,
-            } => formatdoc!(
-                r#"Name: {name}
-                   Type: {vault_type}"#,
-                name = name,
-                vault_type = vault_type
-            ),
-            VaultType::DatabaseVault {
-                use_aws_kms: UseAwsKms::Yes,
-            } => formatdoc!(
-                r#"Name: {name}
-            Type: {vault_type}
-            Uses AWS KMS: {uses_aws_kms}"#,
-                name = name,
-                uses_aws_kms = uses_aws_kms
-            ),
-            VaultType::LocalFileVault {
-                path,
-                use_aws_kms: UseAwsKms::No,
-            } => formatdoc!(
-                r#"Name: {name}
-            Type: {vault_type}
-            Path: {vault_path}"#,
-                name = name,
-                vault_type = vault_type,
-                vault_path = path
-                    .to_string_lossy()
+        let mut lines = vec![format!("Name: {name}"), format!("Type: {vault_type}")];
+        if let Some(path) = self.vault.path() {
+            lines.push(format!(
+                "Path: {}",
+                path.to_string_lossy()
                     .to_string()
                     .color(OckamColor::PrimaryResource.color())
-            ),
-            VaultType::LocalFileVault {
-                path,
-                use_aws_kms: UseAwsKms::Yes,
-            } => formatdoc!(
-                r#"Name: {name}
-            Type: External
-            Path: {vault_path}
-            Uses AWS KMS: {uses_aws_kms}"#,
-                name = name,
-                vault_path = path
-                    .to_string_lossy()
-                    .to_string()
-                    .color(OckamColor::PrimaryResource.color()),
-                uses_aws_kms = uses_aws_kms,
-            ),
-        })
+            ));
+        }
+        if self.vault.use_aws_kms() {
+            lines.push(format!(
+                "Uses AWS KMS: {}",
+                "true".color(OckamColor::PrimaryResource.color())
+            ));
+        }
+        if self.vault.use_pkcs11() {
+            lines.push(format!(
+                "Uses PKCS#11: {}",
+                "true".color(OckamColor::PrimaryResource.color())
+            ));
+        }
+        Ok(lines.join("\n"))
     }
 }
@@ -8,12 +8,14 @@ use ockam_vault::VaultForVerifyingSignatures;
 
 use crate::identities::AttributesEntry;
 use crate::models::{
-    CredentialAndPurposeKey, CredentialData, Identifier, PurposePublicKey, VersionedData,
+    CredentialAndPurposeKey, CredentialData, CredentialSignature, Identifier,
+    PurposeKeyAttestation, PurposeKeyAttestationData, PurposePublicKey,
+    RevocationListAndPurposeKey, RevocationListData, VersionedData,
 };
 use crate::utils::now;
 use crate::{
     CredentialAndPurposeKeyData, IdentityAttributesRepository, IdentityError,
-    PurposeKeyVerification, TimestampInSeconds,
+    PurposeKeyVerification, RevocationListRepository, TimestampInSeconds,
 };
 
 /// We allow Credentials to be created in the future related to this machine's time due to
@@ -25,6 +27,7 @@ pub struct CredentialsVerification {
     purpose_keys_verification: Arc<PurposeKeyVerification>,
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
 }
 
 impl CredentialsVerification {
@@ -33,11 +36,13 @@ impl CredentialsVerification {
         purpose_keys_verification: Arc<PurposeKeyVerification>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
     ) -> Self {
         Self {
             purpose_keys_verification,
             verifying_vault,
             identities_attributes_repository,
+            revocation_list_repository,
         }
     }
 }
@@ -50,14 +55,37 @@ impl CredentialsVerification {
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
     ) -> Result<CredentialAndPurposeKeyData> {
-        Self::verify_credential_static(
+        let credential_and_purpose_key_data = Self::verify_credential_static(
             self.purpose_keys_verification.clone(),
             self.verifying_vault.clone(),
             expected_subject,
             authorities,
             credential_and_purpose_key,
         )
-        .await
+        .await?;
+
+        debug!("verify revocation");
+        let credential_data = &credential_and_purpose_key_data.credential_data;
+        if let Some(subject) = &credential_data.subject {
+            if let Some(revoked_at) = self
+                .revocation_list_repository
+                .get_revoked_at(
+                    &credential_and_purpose_key_data.purpose_key_data.subject,
+                    subject,
+                )
+                .await?
+            {
+                if credential_data.created_at <= revoked_at {
+                    warn!(
+                        "the credential of {} was revoked at {}",
+                        subject, *revoked_at
+                    );
+                    return Err(IdentityError::CredentialRevoked)?;
+                }
+            }
+        }
+
+        Ok(credential_and_purpose_key_data)
     }
 
     /// Verify a [`Credential`]
@@ -85,30 +113,13 @@ impl CredentialsVerification {
             return Err(IdentityError::UnknownAuthority)?;
         }
 
-        debug!("verify purpose key type");
-        let public_key = match purpose_key_data.public_key.clone() {
-            PurposePublicKey::SecureChannelStatic(_) => {
-                return Err(IdentityError::InvalidKeyType)?;
-            }
-
-            PurposePublicKey::CredentialSigning(public_key) => public_key,
-        };
-
-        debug!("verify signature");
-        let public_key = public_key.into();
-        let versioned_data_hash = verifying_vault
-            .sha256(&credential_and_purpose_key.credential.data)
-            .await?;
-
-        let signature = credential_and_purpose_key
-            .credential
-            .signature
-            .clone()
-            .into();
-
-        if !verifying_vault
-            .verify_signature(&public_key, &versioned_data_hash.0, &signature)
-            .await?
+        if !Self::verify_signed_data(
+            verifying_vault,
+            &purpose_key_data,
+            &credential_and_purpose_key.credential.data,
+            &credential_and_purpose_key.credential.signature,
+        )
+        .await?
         {
             return Err(IdentityError::CredentialVerificationFailed)?;
         }
@@ -185,6 +196,157 @@ impl CredentialsVerification {
         })
     }
 
+    /// Verify a [`super::super::models::RevocationList`]
+    pub async fn verify_revocation_list(
+        &self,
+        authorities: &[Identifier],
+        revocation_list_and_purpose_key: &RevocationListAndPurposeKey,
+    ) -> Result<RevocationListData> {
+        let purpose_key_data = self
+            .verify_purpose_key(&revocation_list_and_purpose_key.purpose_key_attestation)
+            .await?;
+
+        debug!("verify revocation list issuer");
+        if !authorities.contains(&purpose_key_data.subject) {
+            warn!(
+                "unknown authority on a revocation list: {}. Accepted authorities: {:?}",
+                purpose_key_data.subject, authorities
+            );
+            return Err(IdentityError::UnknownAuthority)?;
+        }
+
+        let revocation_list = &revocation_list_and_purpose_key.revocation_list;
+        if !Self::verify_signed_data(
+            self.verifying_vault.clone(),
+            &purpose_key_data,
+            &revocation_list.data,
+            &revocation_list.signature,
+        )
+        .await?
+        {
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        let revocation_list_data = revocation_list.get_revocation_list_data()?;
+
+        if revocation_list_data.issuer != purpose_key_data.subject {
+            // The revocation list must be signed by its issuer
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        debug!("verify revocation list dates");
+        if revocation_list_data.created_at < purpose_key_data.created_at
+            || revocation_list_data.created_at > purpose_key_data.expires_at
+        {
+            // The revocation list must be created while the purpose key is valid
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        let now = now()?;
+        if revocation_list_data.created_at > now
+            && revocation_list_data.created_at - now > MAX_ALLOWED_TIME_DRIFT
+        {
+            // Revocation list can't be created in the future
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        Ok(revocation_list_data)
+    }
+
+    /// Receive an Authority's [`super::super::models::RevocationList`]: verify it, replace
+    /// the previous list of that Authority if it is more recent and delete the stored
+    /// attributes of the subjects revoked since the previous list
+    pub async fn receive_revocation_list(
+        &self,
+        authorities: &[Identifier],
+        revocation_list_and_purpose_key: RevocationListAndPurposeKey,
+    ) -> Result<RevocationListData> {
+        let revocation_list_data = self
+            .verify_revocation_list(authorities, &revocation_list_and_purpose_key)
+            .await?;
+
+        let mut previously_revoked = BTreeMap::new();
+        if let Some(current) = self
+            .revocation_list_repository
+            .get_revocation_list(&revocation_list_data.issuer)
+            .await?
+        {
+            let current = current.get_revocation_list_data()?;
+            if current.version > revocation_list_data.version {
+                warn!(
+                    "received revocation list version {} from {}, but version {} is already known",
+                    revocation_list_data.version, revocation_list_data.issuer, current.version
+                );
+                return Err(IdentityError::OutdatedRevocationList)?;
+            }
+            if current.version == revocation_list_data.version {
+                return Ok(revocation_list_data);
+            }
+            previously_revoked = current
+                .revoked_subjects
+                .into_iter()
+                .map(|revoked_subject| (revoked_subject.subject, revoked_subject.revoked_at))
+                .collect();
+        }
+
+        self.revocation_list_repository
+            .put_revocation_list(&revocation_list_data, revocation_list_and_purpose_key)
+            .await?;
+
+        // Attributes are stored when a credential is presented, possibly after the credential
+        // was revoked but before this list was received. They must be presented again
+        // with a credential issued after the revocation.
+        // The list is cumulative, so the subjects which were already revoked at the same time
+        // in the previous list are skipped: their current attributes come from a newer credential
+        for revoked_subject in &revocation_list_data.revoked_subjects {
+            if previously_revoked.get(&revoked_subject.subject) == Some(&revoked_subject.revoked_at)
+            {
+                continue;
+            }
+            self.identities_attributes_repository
+                .delete_attributes(&revoked_subject.subject, &revocation_list_data.issuer)
+                .await?;
+        }
+
+        Ok(revocation_list_data)
+    }
+
+    async fn verify_purpose_key(
+        &self,
+        purpose_key_attestation: &PurposeKeyAttestation,
+    ) -> Result<PurposeKeyAttestationData> {
+        debug!("verify purpose key attestation");
+        self.purpose_keys_verification
+            .verify_purpose_key_attestation(None, purpose_key_attestation)
+            .await
+    }
+
+    /// Verify that some data was signed with a credentials signing purpose key
+    async fn verify_signed_data(
+        verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+        purpose_key_data: &PurposeKeyAttestationData,
+        data: &[u8],
+        signature: &CredentialSignature,
+    ) -> Result<bool> {
+        debug!("verify purpose key type");
+        let public_key = match purpose_key_data.public_key.clone() {
+            PurposePublicKey::SecureChannelStatic(_) => {
+                return Err(IdentityError::InvalidKeyType)?;
+            }
+
+            PurposePublicKey::CredentialSigning(public_key) => public_key,
+        };
+
+        debug!("verify signature");
+        let public_key = public_key.into();
+        let versioned_data_hash = verifying_vault.sha256(data).await?;
+        let signature = signature.clone().into();
+
+        verifying_vault
+            .verify_signature(&public_key, &versioned_data_hash.0, &signature)
+            .await
+    }
+
     /// Receive someone's [`Credential`]: verify and put attributes from it to the storage
     pub async fn receive_presented_credential(
         &self,
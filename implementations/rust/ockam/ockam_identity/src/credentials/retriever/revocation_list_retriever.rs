@@ -0,0 +1,126 @@
+use tracing::{debug, info, warn};
+
+use ockam_core::api::Request;
+use ockam_core::compat::string::String;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::time::Duration;
+use ockam_core::{Result, Route};
+use ockam_node::Context;
+use ockam_transport_core::Transport;
+
+use crate::models::{RevocationListAndPurposeKey, RevocationListData};
+use crate::utils::now;
+use crate::{get_default_timeout, Identifier, SecureChannels, SecureClient};
+
+/// Default interval between two requests for the revocation list of an Authority
+pub const DEFAULT_REVOCATION_LIST_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default address of the revocation list service on the Authority node
+pub const DEFAULT_REVOCATION_LIST_SERVICE_ADDRESS: &str = "revocation_list_issuer";
+
+/// This struct periodically retrieves the revocation list of an Authority
+/// located on a different node, verifies it, and stores it so that the
+/// revoked credentials are rejected
+#[derive(Clone)]
+pub struct RemoteRevocationListRetriever {
+    ctx: Arc<Context>,
+    transport: Arc<dyn Transport>,
+    secure_channels: Arc<SecureChannels>,
+    issuer: Identifier,
+    route: Route,
+    service_address: String,
+    subject: Identifier,
+    refresh_interval: Duration,
+}
+
+impl RemoteRevocationListRetriever {
+    /// Create a new remote revocation list retriever
+    pub fn new(
+        ctx: Context,
+        transport: Arc<dyn Transport>,
+        secure_channels: Arc<SecureChannels>,
+        issuer: Identifier,
+        route: Route,
+        subject: Identifier,
+    ) -> Self {
+        Self {
+            ctx: Arc::new(ctx),
+            transport,
+            secure_channels,
+            issuer,
+            route,
+            service_address: DEFAULT_REVOCATION_LIST_SERVICE_ADDRESS.into(),
+            subject,
+            refresh_interval: DEFAULT_REVOCATION_LIST_REFRESH_INTERVAL,
+        }
+    }
+
+    /// Set a specific interval between two requests for the revocation list
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    /// Set a specific address for the revocation list service
+    pub fn with_service_address(mut self, service_address: impl Into<String>) -> Self {
+        self.service_address = service_address.into();
+        self
+    }
+
+    /// Retrieve the revocation list once, verify it and store it if it is more recent
+    /// than the one we already have
+    pub async fn retrieve(&self) -> Result<RevocationListData> {
+        let client = SecureClient::new(
+            self.secure_channels.clone(),
+            None,
+            self.transport.clone(),
+            self.route.clone(),
+            &self.issuer,
+            &self.subject,
+            get_default_timeout(),
+            get_default_timeout(),
+        );
+
+        let revocation_list: RevocationListAndPurposeKey = client
+            .ask(&self.ctx, &self.service_address, Request::get("/"))
+            .await?
+            .success()?;
+
+        let revocation_list_data = self
+            .secure_channels
+            .identities()
+            .credentials()
+            .credentials_verification()
+            .receive_revocation_list(&[self.issuer.clone()], revocation_list)
+            .await?;
+
+        debug!(
+            "Retrieved the revocation list version {} from {}",
+            revocation_list_data.version, self.issuer
+        );
+        Ok(revocation_list_data)
+    }
+
+    /// Retrieve the revocation list in the background, every `refresh_interval`
+    pub fn start(&self) {
+        let s = self.clone();
+        info!(
+            "Scheduling background revocation list refresh from {} every {} seconds",
+            s.issuer,
+            s.refresh_interval.as_secs()
+        );
+        ockam_node::spawn(async move {
+            loop {
+                if let Err(err) = s.retrieve().await {
+                    warn!(
+                        "Error retrieving the revocation list from {} in the background: {}",
+                        s.issuer, err
+                    );
+                }
+                s.ctx
+                    .sleep_long_until(*now().unwrap() + s.refresh_interval.as_secs())
+                    .await;
+            }
+        });
+    }
+}
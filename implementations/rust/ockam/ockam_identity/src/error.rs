@@ -70,6 +70,16 @@ pub enum IdentityError {
     UnknownRole,
     /// Handshake ended up in an internal invalid state
     HandshakeInternalError,
+    /// Credential was revoked by its issuer
+    CredentialRevoked,
+    /// RevocationList Verification Failed
+    RevocationListVerificationFailed,
+    /// Unknown version of the RevocationList
+    UnknownRevocationListVersion,
+    /// Invalid data_type value for RevocationList
+    InvalidRevocationListDataType,
+    /// RevocationList is older than the one we already have
+    OutdatedRevocationList,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
@@ -12,6 +12,9 @@ use crate::identities::storage::CredentialRepository;
 use crate::identities::storage::CredentialSqlxDatabase;
 #[cfg(feature = "storage")]
 use crate::identities::storage::IdentityAttributesSqlxDatabase;
+use crate::identities::storage::RevocationListRepository;
+#[cfg(feature = "storage")]
+use crate::identities::storage::RevocationListSqlxDatabase;
 use crate::identities::{ChangeHistoryRepository, IdentitiesKeys};
 use crate::models::ChangeHistory;
 use crate::purpose_keys::storage::PurposeKeysRepository;
@@ -32,6 +35,7 @@ pub struct Identities {
     identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
     purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
     cached_credentials_repository: Arc<dyn CredentialRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
 }
 
 impl Identities {
@@ -55,6 +59,11 @@ impl Identities {
         self.cached_credentials_repository.clone()
     }
 
+    /// Return the revocation lists repository
+    pub fn revocation_list_repository(&self) -> Arc<dyn RevocationListRepository> {
+        self.revocation_list_repository.clone()
+    }
+
     /// Get an [`Identity`] from the repository
     pub async fn get_identity(&self, identifier: &Identifier) -> Result<Identity> {
         self.identities_verification()
@@ -78,6 +87,7 @@ impl Identities {
     pub fn identities_attributes(&self) -> Arc<IdentitiesAttributes> {
         Arc::new(IdentitiesAttributes::new(
             self.identity_attributes_repository.clone(),
+            self.revocation_list_repository.clone(),
         ))
     }
 
@@ -124,6 +134,7 @@ impl Identities {
             self.purpose_keys(),
             self.identities_creation().clone(),
             self.identity_attributes_repository.clone(),
+            self.revocation_list_repository.clone(),
         ))
     }
 }
@@ -136,6 +147,7 @@ impl Identities {
         identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
         purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
         cached_credentials_repository: Arc<dyn CredentialRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
     ) -> Identities {
         Identities {
             vault,
@@ -143,6 +155,7 @@ impl Identities {
             identity_attributes_repository,
             purpose_keys_repository,
             cached_credentials_repository,
+            revocation_list_repository,
         }
     }
 
@@ -172,6 +185,10 @@ impl Identities {
             )),
             purpose_keys_repository: Arc::new(PurposeKeysSqlxDatabase::new(database.clone())),
             cached_credentials_repository: Arc::new(CredentialSqlxDatabase::new(
+                database.clone(),
+                node_name,
+            )),
+            revocation_list_repository: Arc::new(RevocationListSqlxDatabase::new(
                 database, node_name,
             )),
         }
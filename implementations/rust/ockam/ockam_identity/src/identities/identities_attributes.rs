@@ -1,7 +1,8 @@
 use crate::utils::now;
-use crate::{AttributesEntry, Identifier, IdentityAttributesRepository};
+use crate::{AttributesEntry, Identifier, IdentityAttributesRepository, RevocationListRepository};
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
+use tracing::debug;
 use tracing_attributes::instrument;
 
 /// This struct provides access to the identities attributes stored on a node.
@@ -11,21 +12,31 @@ use tracing_attributes::instrument;
 /// - Setting the time at which a given attribute is persisted
 /// - Deleting expired attributes from storage. This deletion is performed every time the
 ///   repository is accessed to retrieve attributes
+/// - Ignoring attributes which were stored before the attesting authority revoked them.
+///   Attributes stored after that point are deleted when the revocation list is received
 ///
 #[derive(Clone)]
 pub struct IdentitiesAttributes {
     repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
 }
 
 impl IdentitiesAttributes {
     /// Return a new IdentitiesAttributes struct
-    pub fn new(repository: Arc<dyn IdentityAttributesRepository>) -> IdentitiesAttributes {
-        IdentitiesAttributes { repository }
+    pub fn new(
+        repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+    ) -> IdentitiesAttributes {
+        IdentitiesAttributes {
+            repository,
+            revocation_list_repository,
+        }
     }
 
     /// Return the attributes for a given pair subject/attesting authority
     /// If there are expired attributes for any subject, they are deleted before retrieving the attributes for the
     /// current subject.
+    /// No attributes are returned if the attesting authority revoked them after they were stored.
     #[instrument(skip_all, fields(subject = %subject, attested_by = %attested_by))]
     pub async fn get_attributes(
         &self,
@@ -33,7 +44,26 @@ impl IdentitiesAttributes {
         attested_by: &Identifier,
     ) -> Result<Option<AttributesEntry>> {
         self.repository.delete_expired_attributes(now()?).await?;
-        self.repository.get_attributes(subject, attested_by).await
+        let entry = match self.repository.get_attributes(subject, attested_by).await? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        if let Some(revoked_at) = self
+            .revocation_list_repository
+            .get_revoked_at(attested_by, subject)
+            .await?
+        {
+            if entry.added_at() <= revoked_at {
+                debug!(
+                    "the attributes of {} were revoked by {} at {}",
+                    subject, attested_by, *revoked_at
+                );
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(entry))
     }
 
     /// Set the attributes associated with the given identity identifier.
@@ -51,8 +81,11 @@ mod tests {
     use std::time::Duration;
 
     use super::*;
+    use crate::models::RevokedSubject;
     use crate::utils::now;
-    use crate::{identities, IdentityAttributesSqlxDatabase, TimestampInSeconds};
+    use crate::{
+        identities, IdentityAttributesSqlxDatabase, RevocationListSqlxDatabase, TimestampInSeconds,
+    };
 
     #[tokio::test]
     async fn test_identities_attributes_expiration() -> Result<()> {
@@ -93,6 +126,59 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_identities_attributes_revocation() -> Result<()> {
+        let identities = identities().await?;
+        let identities_attributes = identities.identities_attributes();
+        let authority = identities.identities_creation().create_identity().await?;
+        let subject = identities.identities_creation().create_identity().await?;
+
+        let attributes = create_attributes_entry(&authority, now()?, 100.into()).await?;
+        identities_attributes
+            .put_attributes(&subject, attributes.clone())
+            .await?;
+        let result = identities_attributes
+            .get_attributes(&subject, &authority)
+            .await?;
+        assert_eq!(result, Some(attributes.clone()));
+
+        // the attributes are not returned anymore once the authority revokes them
+        let revocation_list = identities
+            .credentials()
+            .credentials_creation()
+            .issue_revocation_list(
+                &authority,
+                1,
+                vec![RevokedSubject {
+                    subject: subject.clone(),
+                    revoked_at: now()?,
+                }],
+            )
+            .await?;
+        identities
+            .credentials()
+            .credentials_verification()
+            .receive_revocation_list(&[authority.clone()], revocation_list)
+            .await?;
+
+        let result = identities_attributes
+            .get_attributes(&subject, &authority)
+            .await?;
+        assert_eq!(result, None);
+
+        // attributes stored after the revocation are returned again
+        let attributes = create_attributes_entry(&authority, now()? + 1, 100.into()).await?;
+        identities_attributes
+            .put_attributes(&subject, attributes.clone())
+            .await?;
+        let result = identities_attributes
+            .get_attributes(&subject, &authority)
+            .await?;
+        assert_eq!(result, Some(attributes));
+
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_attributes_entry(
         identifier: &Identifier,
@@ -116,8 +202,9 @@ mod tests {
     }
 
     async fn create_identities_attributes() -> Result<IdentitiesAttributes> {
-        Ok(IdentitiesAttributes::new(Arc::new(
-            IdentityAttributesSqlxDatabase::create().await?,
-        )))
+        Ok(IdentitiesAttributes::new(
+            Arc::new(IdentityAttributesSqlxDatabase::create().await?),
+            Arc::new(RevocationListSqlxDatabase::create().await?),
+        ))
     }
 }
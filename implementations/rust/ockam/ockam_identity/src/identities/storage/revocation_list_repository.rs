@@ -0,0 +1,29 @@
+use crate::models::{RevocationListAndPurposeKey, RevocationListData};
+use crate::{Identifier, TimestampInSeconds};
+use async_trait::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+
+/// This trait supports the persistence of the revocation lists received from authorities
+#[async_trait]
+pub trait RevocationListRepository: Send + Sync + 'static {
+    /// Get the latest revocation list received from an issuer
+    async fn get_revocation_list(
+        &self,
+        issuer: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>>;
+
+    /// Put a verified revocation list (overwriting the previous list of the same issuer)
+    async fn put_revocation_list(
+        &self,
+        revocation_list_data: &RevocationListData,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<()>;
+
+    /// Return the time at which the credentials of a subject were revoked by an issuer, if they were
+    async fn get_revoked_at(
+        &self,
+        issuer: &Identifier,
+        subject: &Identifier,
+    ) -> Result<Option<TimestampInSeconds>>;
+}
@@ -0,0 +1,240 @@
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::any::AnyArgumentBuffer;
+use sqlx::*;
+use tracing::debug;
+
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+use crate::models::{Identifier, RevocationListAndPurposeKey, RevocationListData};
+use crate::{RevocationListRepository, TimestampInSeconds};
+
+/// Implementation of `RevocationListRepository` trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct RevocationListSqlxDatabase {
+    database: SqlxDatabase,
+    node_name: String,
+}
+
+impl RevocationListSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase, node_name: &str) -> Self {
+        debug!("create a repository for revocation lists");
+        Self {
+            database,
+            node_name: node_name.to_string(),
+        }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("revocation list").await?,
+            "default",
+        ))
+    }
+}
+
+#[async_trait]
+impl RevocationListRepository for RevocationListSqlxDatabase {
+    async fn get_revocation_list(
+        &self,
+        issuer: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>> {
+        let query = query_as(
+            "SELECT revocation_list FROM revocation_list WHERE issuer_identifier = $1 AND node_name = $2",
+        )
+        .bind(issuer)
+        .bind(self.node_name.clone());
+        let row: Option<RevocationListRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.revocation_list()).transpose()
+    }
+
+    async fn put_revocation_list(
+        &self,
+        revocation_list_data: &RevocationListData,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<()> {
+        let issuer = &revocation_list_data.issuer;
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query1 = query(
+            r#"INSERT INTO revocation_list (issuer_identifier, version, revocation_list, node_name)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (issuer_identifier, node_name)
+            DO UPDATE SET version = $2, revocation_list = $3"#,
+        )
+        .bind(issuer)
+        .bind(revocation_list_data.version as i64)
+        .bind(revocation_list)
+        .bind(self.node_name.clone());
+        query1.execute(&mut *transaction).await.void()?;
+
+        let query2 =
+            query("DELETE FROM revoked_subject WHERE issuer_identifier = $1 AND node_name = $2")
+                .bind(issuer)
+                .bind(self.node_name.clone());
+        query2.execute(&mut *transaction).await.void()?;
+
+        for revoked_subject in revocation_list_data.revoked_subjects.iter() {
+            let query3 = query(
+                r#"INSERT INTO revoked_subject (issuer_identifier, subject_identifier, revoked_at, node_name)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (issuer_identifier, subject_identifier, node_name)
+                DO UPDATE SET revoked_at = $3"#,
+            )
+            .bind(issuer)
+            .bind(&revoked_subject.subject)
+            .bind(revoked_subject.revoked_at)
+            .bind(self.node_name.clone());
+            query3.execute(&mut *transaction).await.void()?;
+        }
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_revoked_at(
+        &self,
+        issuer: &Identifier,
+        subject: &Identifier,
+    ) -> Result<Option<TimestampInSeconds>> {
+        let query = query_as(
+            "SELECT revoked_at FROM revoked_subject WHERE issuer_identifier = $1 AND subject_identifier = $2 AND node_name = $3",
+        )
+        .bind(issuer)
+        .bind(subject)
+        .bind(self.node_name.clone());
+        let row: Option<RevokedSubjectRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(row.map(|r| TimestampInSeconds(r.revoked_at as u64)))
+    }
+}
+
+// Database serialization / deserialization
+
+impl Type<Any> for RevocationListAndPurposeKey {
+    fn type_info() -> <Any as Database>::TypeInfo {
+        <Vec<u8> as Type<Any>>::type_info()
+    }
+}
+
+impl Encode<'_, Any> for RevocationListAndPurposeKey {
+    fn encode_by_ref(&self, buf: &mut AnyArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <Vec<u8> as Encode<'_, Any>>::encode_by_ref(&self.encode_as_cbor_bytes().unwrap(), buf)
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct RevocationListRow {
+    revocation_list: Vec<u8>,
+}
+
+impl RevocationListRow {
+    fn revocation_list(&self) -> Result<RevocationListAndPurposeKey> {
+        RevocationListAndPurposeKey::decode_from_cbor_bytes(&self.revocation_list)
+    }
+}
+
+#[derive(FromRow)]
+struct RevokedSubjectRow {
+    revoked_at: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use ockam_core::compat::sync::Arc;
+    use ockam_node::database::with_dbs;
+
+    use super::*;
+    use crate::identities;
+    use crate::models::RevokedSubject;
+    use crate::utils::now;
+
+    #[tokio::test]
+    async fn test_revocation_list_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn RevocationListRepository> =
+                Arc::new(RevocationListSqlxDatabase::new(db, "node"));
+
+            let identities = identities().await?;
+            let issuer = identities.identities_creation().create_identity().await?;
+            let subject1 = identities.identities_creation().create_identity().await?;
+            let subject2 = identities.identities_creation().create_identity().await?;
+
+            assert_eq!(repository.get_revocation_list(&issuer).await?, None);
+            assert_eq!(repository.get_revoked_at(&issuer, &subject1).await?, None);
+
+            let revoked_at = now()?;
+            let revocation_list1 = identities
+                .credentials()
+                .credentials_creation()
+                .issue_revocation_list(
+                    &issuer,
+                    1,
+                    vec![RevokedSubject {
+                        subject: subject1.clone(),
+                        revoked_at,
+                    }],
+                )
+                .await?;
+            repository
+                .put_revocation_list(
+                    &revocation_list1.get_revocation_list_data()?,
+                    revocation_list1.clone(),
+                )
+                .await?;
+
+            assert_eq!(
+                repository.get_revocation_list(&issuer).await?,
+                Some(revocation_list1)
+            );
+            assert_eq!(
+                repository.get_revoked_at(&issuer, &subject1).await?,
+                Some(revoked_at)
+            );
+            assert_eq!(repository.get_revoked_at(&issuer, &subject2).await?, None);
+
+            // a new list replaces all the revoked subjects of the previous one
+            let revocation_list2 = identities
+                .credentials()
+                .credentials_creation()
+                .issue_revocation_list(
+                    &issuer,
+                    2,
+                    vec![RevokedSubject {
+                        subject: subject2.clone(),
+                        revoked_at,
+                    }],
+                )
+                .await?;
+            repository
+                .put_revocation_list(
+                    &revocation_list2.get_revocation_list_data()?,
+                    revocation_list2.clone(),
+                )
+                .await?;
+
+            assert_eq!(
+                repository.get_revocation_list(&issuer).await?,
+                Some(revocation_list2)
+            );
+            assert_eq!(repository.get_revoked_at(&issuer, &subject1).await?, None);
+            assert_eq!(
+                repository.get_revoked_at(&issuer, &subject2).await?,
+                Some(revoked_at)
+            );
+
+            Ok(())
+        })
+        .await
+    }
+}
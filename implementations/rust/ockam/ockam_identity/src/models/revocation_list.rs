@@ -0,0 +1,57 @@
+use crate::models::{CredentialSignature, Identifier, PurposeKeyAttestation, TimestampInSeconds};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::vec::Vec;
+
+/// `data_type` value in [`VersionedData`] struct when used with [`RevocationList`]
+pub const REVOCATION_LIST_DATA_TYPE: u8 = 4;
+
+/// List of subjects whose [`super::Credential`]s were revoked by an Authority (issuer)
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationList {
+    /// CBOR serialized [`super::VersionedData`]
+    /// where VersionedData::data is CBOR serialized [`RevocationListData`]
+    /// and VersionedData::data_type is [`REVOCATION_LIST_DATA_TYPE`]
+    #[cbor(with = "minicbor::bytes")]
+    #[n(0)] pub data: Vec<u8>,
+    /// Signature over data field using corresponding Credentials [`super::PurposeKeyAttestation`]
+    #[n(1)] pub signature: CredentialSignature,
+}
+
+/// Data inside a [`RevocationList`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationListData {
+    /// Authority (issuer) that revoked the Credentials
+    #[n(0)] pub issuer: Identifier,
+    /// Monotonically increasing version of the list. A list with a lower version
+    /// must never replace a list with a higher version
+    #[n(1)] pub version: u64,
+    /// Creation [`TimestampInSeconds`] (UTC)
+    #[n(2)] pub created_at: TimestampInSeconds,
+    /// Revoked subjects
+    #[n(3)] pub revoked_subjects: Vec<RevokedSubject>,
+}
+
+/// A subject whose [`super::Credential`]s were revoked
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevokedSubject {
+    /// Subject of the revoked Credentials
+    #[n(0)] pub subject: Identifier,
+    /// Every Credential issued to that subject at or before that [`TimestampInSeconds`] (UTC)
+    /// is revoked
+    #[n(1)] pub revoked_at: TimestampInSeconds,
+}
+
+/// [`RevocationList`] and the corresponding [`PurposeKeyAttestation`] that was used to sign that
+/// [`RevocationList`] and will be used to verify it
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationListAndPurposeKey {
+    /// [`RevocationList`]
+    #[n(0)] pub revocation_list: RevocationList,
+    /// Corresponding [`PurposeKeyAttestation`] that was used to sign that
+    /// [`RevocationList`] and will be used to verify it
+    #[n(1)] pub purpose_key_attestation: PurposeKeyAttestation,
+}
@@ -0,0 +1,64 @@
+use crate::models::{
+    RevocationList, RevocationListAndPurposeKey, RevocationListData, TimestampInSeconds,
+    VersionedData, REVOCATION_LIST_DATA_TYPE,
+};
+use crate::{Identifier, IdentityError};
+
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+impl RevocationList {
+    /// Create [`VersionedData`] with corresponding version and data_type
+    pub fn create_versioned_data(data: Vec<u8>) -> VersionedData {
+        VersionedData {
+            version: 1,
+            data_type: REVOCATION_LIST_DATA_TYPE,
+            data,
+        }
+    }
+
+    /// Extract [`RevocationListData`]
+    pub fn get_revocation_list_data(&self) -> Result<RevocationListData> {
+        RevocationListData::get_data(&minicbor::decode(&self.data)?)
+    }
+}
+
+impl RevocationListData {
+    /// Extract [`RevocationListData`] from [`VersionedData`]
+    pub fn get_data(versioned_data: &VersionedData) -> Result<Self> {
+        if versioned_data.version != 1 {
+            return Err(IdentityError::UnknownRevocationListVersion)?;
+        }
+
+        if versioned_data.data_type != REVOCATION_LIST_DATA_TYPE {
+            return Err(IdentityError::InvalidRevocationListDataType)?;
+        }
+
+        Ok(minicbor::decode(&versioned_data.data)?)
+    }
+
+    /// Return the time at which the credentials of a given subject were revoked, if they were
+    pub fn revoked_at(&self, subject: &Identifier) -> Option<TimestampInSeconds> {
+        self.revoked_subjects
+            .iter()
+            .find(|r| &r.subject == subject)
+            .map(|r| r.revoked_at)
+    }
+}
+
+impl RevocationListAndPurposeKey {
+    /// Encode the revocation list as a CBOR bytes
+    pub fn encode_as_cbor_bytes(&self) -> Result<Vec<u8>> {
+        ockam_core::cbor_encode_preallocate(self)
+    }
+
+    /// Decode the revocation list from bytes
+    pub fn decode_from_cbor_bytes(bytes: &[u8]) -> Result<RevocationListAndPurposeKey> {
+        Ok(minicbor::decode(bytes)?)
+    }
+
+    /// Return the encoded revocation list data
+    pub fn get_revocation_list_data(&self) -> Result<RevocationListData> {
+        self.revocation_list.get_revocation_list_data()
+    }
+}
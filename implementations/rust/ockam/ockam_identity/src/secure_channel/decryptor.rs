@@ -9,11 +9,11 @@ use crate::secure_channel::encryptor::{Encryptor, KEY_RENEWAL_INTERVAL};
 use crate::secure_channel::handshake::handshake_state_machine::CommonStateMachine;
 use crate::secure_channel::key_tracker::KeyTracker;
 use crate::secure_channel::nonce_tracker::NonceTracker;
-use crate::secure_channel::{Addresses, Role};
+use crate::secure_channel::{Addresses, PendingResumption, ResumptionTicket, Role};
 use crate::{
     DecryptionRequest, DecryptionResponse, Identities, IdentityError, Nonce,
-    PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelMessage,
-    SecureChannelPaddedMessage, NOISE_NONCE_LEN,
+    PlaintextPayloadMessage, RefreshCredentialsMessage, ResumptionTicketMessage,
+    SecureChannelMessage, SecureChannelPaddedMessage, NOISE_NONCE_LEN,
 };
 
 use crate::secure_channel::encryptor_worker::SecureChannelSharedState;
@@ -31,6 +31,8 @@ pub(crate) struct DecryptorHandler {
     identities: Arc<Identities>,
     authority: Option<Identifier>,
     shared_state: SecureChannelSharedState,
+    // Set on the initiator side until the responder sends a resumption ticket
+    resumption: Option<PendingResumption>,
 }
 
 impl DecryptorHandler {
@@ -45,6 +47,7 @@ impl DecryptorHandler {
         vault: Arc<dyn VaultForSecureChannels>,
         their_identity_id: Identifier,
         shared_state: SecureChannelSharedState,
+        resumption: Option<PendingResumption>,
     ) -> Self {
         let decryptor = if key_exchange_only {
             Decryptor::new_naive(key, vault)
@@ -60,6 +63,7 @@ impl DecryptorHandler {
             identities,
             authority,
             shared_state,
+            resumption,
         }
     }
 
@@ -185,6 +189,32 @@ impl DecryptorHandler {
         Ok(())
     }
 
+    async fn handle_resumption_ticket(&mut self, msg: ResumptionTicketMessage) -> Result<()> {
+        let Some(resumption) = self.resumption.take() else {
+            warn!(
+                "Ignoring an unexpected resumption ticket for {}",
+                self.addresses.decryptor_remote
+            );
+            return Ok(());
+        };
+
+        debug!(
+            "Storing a resumption ticket for {}",
+            self.addresses.decryptor_remote
+        );
+        resumption
+            .cache
+            .put(
+                resumption.name,
+                ResumptionTicket {
+                    ticket: msg.ticket,
+                    expires_at: msg.expires_at,
+                    state: resumption.state,
+                },
+            )
+            .await
+    }
+
     #[instrument(skip_all)]
     pub(crate) async fn handle_decrypt(
         &mut self,
@@ -216,13 +246,22 @@ impl DecryptorHandler {
                 self.handle_refresh_credentials(ctx, decrypted_msg).await?
             }
             SecureChannelMessage::Close => self.handle_close(ctx).await?,
+            SecureChannelMessage::ResumptionTicket(decrypted_msg) => {
+                self.handle_resumption_ticket(decrypted_msg).await?
+            }
         };
 
         Ok(())
     }
 
-    /// Remove the channel keys on shutdown
-    pub(crate) async fn shutdown(&self) -> Result<()> {
+    /// Remove the channel keys on shutdown, and the resumption secret if no ticket was received
+    pub(crate) async fn shutdown(&mut self) -> Result<()> {
+        if let Some(resumption) = self.resumption.take() {
+            self.decryptor
+                .vault
+                .delete_secret_buffer(resumption.state.secret)
+                .await?;
+        }
         self.decryptor.shutdown().await
     }
 }
@@ -20,8 +20,8 @@ use crate::secure_channel::encryptor::Encryptor;
 use crate::secure_channel::handshake::handshake::AES_GCM_TAGSIZE;
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, Identifier, IdentityError, Nonce,
-    PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelMessage,
-    SecureChannelPaddedMessage, NOISE_NONCE_LEN,
+    PlaintextPayloadMessage, RefreshCredentialsMessage, ResumptionTicketMessage,
+    SecureChannelMessage, SecureChannelPaddedMessage, NOISE_NONCE_LEN,
 };
 
 /// Wrap last received (during successful decryption) nonce and current route to the remote in a
@@ -65,6 +65,8 @@ pub(crate) struct EncryptorWorker {
     credential_retriever: Option<Arc<dyn CredentialRetriever>>,
     last_presented_credential: Option<CredentialAndPurposeKey>,
     shared_state: SecureChannelSharedState,
+    // Ticket sent to the initiator once the secure channel is established
+    resumption_ticket: Option<ResumptionTicketMessage>,
 }
 
 impl EncryptorWorker {
@@ -79,6 +81,7 @@ impl EncryptorWorker {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         last_presented_credential: Option<CredentialAndPurposeKey>,
         shared_state: SecureChannelSharedState,
+        resumption_ticket: Option<ResumptionTicketMessage>,
     ) -> Self {
         Self {
             role,
@@ -90,6 +93,7 @@ impl EncryptorWorker {
             credential_retriever,
             last_presented_credential,
             shared_state,
+            resumption_ticket,
         }
     }
 
@@ -288,6 +292,29 @@ impl EncryptorWorker {
         Ok(())
     }
 
+    async fn send_resumption_ticket(
+        &mut self,
+        ctx: &Context,
+        ticket: ResumptionTicketMessage,
+    ) -> Result<()> {
+        let msg = SecureChannelMessage::ResumptionTicket(ticket);
+        let msg = Self::add_padding(msg);
+
+        // Encrypt the message
+        let msg = self.encrypt(ctx, msg).await?;
+
+        let remote_route = self.shared_state.remote_route.read().unwrap().route.clone();
+        // Send the message to the decryptor on the other side
+        ctx.send_from_address(
+            remote_route,
+            NeutralMessage::from(msg),
+            self.addresses.encryptor.clone(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
     async fn send_close_channel(&mut self, ctx: &Context) -> Result<()> {
         let msg = SecureChannelMessage::Close;
         let msg = Self::add_padding(msg);
@@ -326,11 +353,20 @@ impl Worker for EncryptorWorker {
     type Message = Any;
     type Context = Context;
 
-    async fn initialize(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         if let Some(credential_retriever) = &self.credential_retriever {
             credential_retriever.subscribe(&self.addresses.encryptor_internal)?;
         }
 
+        if let Some(ticket) = self.resumption_ticket.take() {
+            if let Err(err) = self.send_resumption_ticket(ctx, ticket).await {
+                warn!(
+                    "Error while sending a resumption ticket for {}: {err}",
+                    self.addresses.encryptor
+                );
+            }
+        }
+
         Ok(())
     }
 
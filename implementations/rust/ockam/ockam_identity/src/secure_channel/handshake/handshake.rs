@@ -9,8 +9,9 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{
-    AeadSecretKeyHandle, HKDFNumberOfOutputs, SecretBufferHandle, VaultForSecureChannels,
-    X25519PublicKey, X25519SecretKeyHandle, X25519_PUBLIC_KEY_LENGTH,
+    AeadSecretKeyHandle, HKDFNumberOfOutputs, MlKemCiphertext, MlKemPublicKey,
+    MlKemSecretKeyHandle, SecretBufferHandle, VaultForSecureChannels, X25519PublicKey,
+    X25519SecretKeyHandle, ML_KEM_768_CIPHERTEXT_LENGTH, X25519_PUBLIC_KEY_LENGTH,
 };
 use sha2::{Digest, Sha256};
 use Status::*;
@@ -68,6 +69,21 @@ impl Handshake {
         Ok(message1)
     }
 
+    /// Generate an ephemeral ML-KEM key for a hybrid key exchange and return its public key.
+    /// The public key is sent in the message 1 payload
+    pub(super) async fn generate_ml_kem_key(&mut self) -> Result<MlKemPublicKey> {
+        let key = self.vault.generate_ephemeral_ml_kem_secret_key().await?;
+        let public_key = self.vault.get_ml_kem_public_key(&key).await?;
+        self.state.kem = Some(key);
+        Ok(public_key)
+    }
+
+    /// Set the secret shared with the responder during a previous handshake, in order to resume
+    /// the secure channel with a resumption ticket sent in the message 1 payload
+    pub(super) fn set_resumption_secret(&mut self, resumption_secret: SecretBufferHandle) {
+        self.state.resumption_secret = Some(resumption_secret);
+    }
+
     /// Decode the first message to get the ephemeral public key sent by the initiator
     pub(super) async fn decode_message1(&mut self, message1: &[u8]) -> Result<Vec<u8>> {
         if message1.len() > NOISE_MAX_MESSAGE_SIZE {
@@ -92,16 +108,17 @@ impl Handshake {
     /// Encode the second message from the responder to the initiator
     /// That message contains: the responder ephemeral public key + a Diffie-Hellman key +
     ///   an encrypted payload containing the responder identity / signature / credentials
-    pub(super) async fn encode_message2(&mut self, payload: &[u8]) -> Result<Vec<u8>> {
+    /// For a hybrid key exchange, the message also contains an encrypted ML-KEM ciphertext,
+    /// encapsulated with the initiator ML-KEM public key, after the responder ephemeral public key
+    pub(super) async fn encode_message2(
+        &mut self,
+        payload: &[u8],
+        ml_kem_public_key: Option<&MlKemPublicKey>,
+    ) -> Result<Vec<u8>> {
         let mut state = self.state.clone();
-        // output e.pubKey
-        let e_pub_key = self.get_public_key(state.e()?).await?;
-        state.mix_hash(&e_pub_key.0);
-        let mut message2 = e_pub_key.0.to_vec();
-
-        // ck, k = HKDF(ck, DH(e, re), 2)
-        let dh = self.dh(state.e()?, state.re()?).await?;
-        self.hkdf(&mut state, dh).await?;
+        let mut message2 = self
+            .encode_message2_key_exchange(&mut state, ml_kem_public_key)
+            .await?;
 
         // encrypt and output s.pubKey
         let s_pub_key = self.get_public_key(state.s()?).await?;
@@ -124,6 +141,33 @@ impl Handshake {
         Ok(message2)
     }
 
+    /// Encode the second message of a resumed handshake, when the initiator sent a valid
+    /// resumption ticket in message 1.
+    /// That message contains: the responder ephemeral public key + an encrypted empty payload.
+    /// The identities are not exchanged again, since the resumption secret, shared by both parties
+    /// at the end of a previous handshake, is mixed in the chaining key
+    pub(super) async fn encode_resumed_message2(
+        &mut self,
+        resumption_secret: SecretBufferHandle,
+        ml_kem_public_key: Option<&MlKemPublicKey>,
+    ) -> Result<Vec<u8>> {
+        let mut state = self.state.clone();
+        let mut message2 = self
+            .encode_message2_key_exchange(&mut state, ml_kem_public_key)
+            .await?;
+
+        // ck, k = HKDF(ck, resumption secret, 2)
+        self.hkdf(&mut state, resumption_secret).await?;
+
+        // encrypt and output an empty payload
+        let c = self.encrypt_and_hash(&mut state, &[]).await?;
+        message2.extend(c);
+        state.resumed = true;
+
+        self.state = state;
+        Ok(message2)
+    }
+
     /// Decode the second message sent by the responder
     pub(super) async fn decode_message2(&mut self, message2: &[u8]) -> Result<Vec<u8>> {
         if message2.len() > NOISE_MAX_MESSAGE_SIZE {
@@ -140,6 +184,27 @@ impl Handshake {
         let dh = self.dh(state.e()?, state.re()?).await?;
         self.hkdf(&mut state, dh).await?;
 
+        // decrypt the ML-KEM ciphertext if the responder supports the hybrid key exchange
+        // ck, k = HKDF(ck, ML-KEM shared secret, 2)
+        let mut message2 = Self::read_message2_after_ephemeral_key(message2)?;
+        if let Some(ciphertext) = self.decrypt_ml_kem_ciphertext(&mut state, message2).await {
+            let shared_secret = self
+                .vault
+                .ml_kem_decapsulate(state.kem()?, &ciphertext)
+                .await?;
+            self.hkdf(&mut state, shared_secret).await?;
+            state.hybrid = true;
+            message2 = Self::read_message2_after_ml_kem_ciphertext(message2)?;
+        }
+
+        // decrypt the payload of a resumed handshake if a resumption ticket was sent in message 1
+        // ck, k = HKDF(ck, resumption secret, 2)
+        if let Some(payload) = self.decrypt_resumed_message2(&mut state, message2).await? {
+            state.resumed = true;
+            self.state = state;
+            return Ok(payload);
+        }
+
         // decrypt rs.pubKey
         let rs_pub_key = Self::read_message2_encrypted_key(message2)?;
         let rs_pub_key = self.hash_and_decrypt(&mut state, rs_pub_key).await?;
@@ -218,10 +283,18 @@ impl Handshake {
 
     /// Set the final state of the state machine by creating the encryption / decryption keys
     /// and return the other party identity
-    pub(super) async fn set_final_state(&mut self, role: Role) -> Result<()> {
+    /// A resumption secret can also be derived, in order to resume the secure channel later
+    pub(super) async fn set_final_state(
+        &mut self,
+        role: Role,
+        derive_resumption_secret: bool,
+    ) -> Result<()> {
         // k1, k2 = HKDF(ck, zerolen, 2)
+        // or k1, k2, resumption secret = HKDF(ck, zerolen, 3)
         let mut state = self.state.clone();
-        let (k1, k2) = self.compute_final_keys(&mut state).await?;
+        let (k1, k2, resumption_secret) = self
+            .compute_final_keys(&mut state, derive_resumption_secret)
+            .await?;
         let (encryption_key, decryption_key) = if role.is_initiator() {
             (k2, k1)
         } else {
@@ -230,6 +303,7 @@ impl Handshake {
         state.status = Ready(HandshakeKeys {
             encryption_key,
             decryption_key,
+            resumption_secret,
         });
         // now remove the ephemeral keys which are not useful anymore
         self.state = state;
@@ -285,22 +359,47 @@ impl Handshake {
 
     /// Compute two derived ck, and k keys based on existing ck and k keys + a Diffie-Hellman key
     async fn hkdf(&self, state: &mut HandshakeState, dh: SecretBufferHandle) -> Result<()> {
-        let hkdf_output = self
-            .vault
-            .hkdf(state.ck()?, Some(&dh), HKDFNumberOfOutputs::Two)
-            .await?;
+        let (new_ck, new_k) = self.hkdf_outputs(state, &dh).await?;
 
         // The Diffie-Hellman secret is not useful anymore
         // we can delete it from memory
         self.vault.delete_secret_buffer(dh).await?;
 
+        self.replace_ck_and_k(state, new_ck, new_k).await
+    }
+
+    /// Derive new ck and k keys from the current ck key and some input key material,
+    /// without modifying the state
+    async fn hkdf_outputs(
+        &self,
+        state: &HandshakeState,
+        input_key_material: &SecretBufferHandle,
+    ) -> Result<(SecretBufferHandle, AeadSecretKeyHandle)> {
+        let hkdf_output = self
+            .vault
+            .hkdf(
+                state.ck()?,
+                Some(input_key_material),
+                HKDFNumberOfOutputs::Two,
+            )
+            .await?;
+
         let [new_ck, new_k]: [SecretBufferHandle; 2] = hkdf_output
             .0
              .0
             .try_into()
             .map_err(|_| XXError::InternalVaultError)?;
         let new_k = self.vault.convert_secret_buffer_to_aead_key(new_k).await?;
+        Ok((new_ck, new_k))
+    }
 
+    /// Replace the ck and k keys of the state and delete the previous ones
+    async fn replace_ck_and_k(
+        &self,
+        state: &mut HandshakeState,
+        new_ck: SecretBufferHandle,
+        new_k: AeadSecretKeyHandle,
+    ) -> Result<()> {
         let old_ck = state.take_ck()?;
         state.ck = Some(new_ck);
         self.vault.delete_secret_buffer(old_ck).await?;
@@ -314,21 +413,33 @@ impl Handshake {
         Ok(())
     }
 
-    /// Compute the final encryption and decryption keys
+    /// Compute the final encryption and decryption keys, and optionally a resumption secret
     async fn compute_final_keys(
         &self,
         state: &mut HandshakeState,
-    ) -> Result<(AeadSecretKeyHandle, AeadSecretKeyHandle)> {
-        let hkdf_output = self
+        derive_resumption_secret: bool,
+    ) -> Result<(
+        AeadSecretKeyHandle,
+        AeadSecretKeyHandle,
+        Option<SecretBufferHandle>,
+    )> {
+        let number_of_outputs = if derive_resumption_secret {
+            HKDFNumberOfOutputs::Three
+        } else {
+            HKDFNumberOfOutputs::Two
+        };
+        let mut hkdf_output = self
             .vault
-            .hkdf(state.ck()?, None, HKDFNumberOfOutputs::Two)
-            .await?;
-
-        let [k1, k2]: [SecretBufferHandle; 2] = hkdf_output
+            .hkdf(state.ck()?, None, number_of_outputs)
+            .await?
             .0
              .0
-            .try_into()
-            .map_err(|_| XXError::InternalVaultError)?;
+            .into_iter();
+
+        let (Some(k1), Some(k2)) = (hkdf_output.next(), hkdf_output.next()) else {
+            return Err(XXError::InternalVaultError)?;
+        };
+        let resumption_secret = hkdf_output.next();
 
         let k1 = self.vault.convert_secret_buffer_to_aead_key(k1).await?;
         let k2 = self.vault.convert_secret_buffer_to_aead_key(k2).await?;
@@ -336,7 +447,7 @@ impl Handshake {
         self.vault.delete_secret_buffer(state.take_ck()?).await?;
         self.vault.delete_aead_secret_key(state.take_k()?).await?;
 
-        Ok((k1, k2))
+        Ok((k1, k2, resumption_secret))
     }
 
     /// Decrypt a ciphertext 'c' using the key 'k' and the additional data 'h'
@@ -375,12 +486,104 @@ impl Handshake {
         Ok(destination)
     }
 
+    /// Output the beginning of message 2, common to full and resumed handshakes:
+    /// the responder ephemeral public key and the optional ML-KEM ciphertext
+    async fn encode_message2_key_exchange(
+        &self,
+        state: &mut HandshakeState,
+        ml_kem_public_key: Option<&MlKemPublicKey>,
+    ) -> Result<Vec<u8>> {
+        // output e.pubKey
+        let e_pub_key = self.get_public_key(state.e()?).await?;
+        state.mix_hash(&e_pub_key.0);
+        let mut message2 = e_pub_key.0.to_vec();
+
+        // ck, k = HKDF(ck, DH(e, re), 2)
+        let dh = self.dh(state.e()?, state.re()?).await?;
+        self.hkdf(state, dh).await?;
+
+        // encrypt and output the ML-KEM ciphertext
+        // ck, k = HKDF(ck, ML-KEM shared secret, 2)
+        if let Some(ml_kem_public_key) = ml_kem_public_key {
+            let (ciphertext, shared_secret) =
+                self.vault.ml_kem_encapsulate(ml_kem_public_key).await?;
+            let c = self.encrypt_and_hash(state, &ciphertext.0).await?;
+            message2.extend(c);
+            self.hkdf(state, shared_secret).await?;
+            state.hybrid = true;
+        }
+
+        Ok(message2)
+    }
+
+    /// Decrypt the ML-KEM ciphertext at the beginning of the message if an ML-KEM key was sent
+    /// in message 1. Return None if the responder answered with a classic key exchange,
+    /// in which case the state is not modified.
+    async fn decrypt_ml_kem_ciphertext(
+        &self,
+        state: &mut HandshakeState,
+        message: &[u8],
+    ) -> Option<MlKemCiphertext> {
+        state.kem.as_ref()?;
+        let c = Self::read_ml_kem_ciphertext(message).ok()?;
+
+        let mut hybrid_state = state.clone();
+        let ciphertext = self.hash_and_decrypt(&mut hybrid_state, c).await.ok()?;
+        *state = hybrid_state;
+        Some(MlKemCiphertext(ciphertext))
+    }
+
+    /// Decrypt the payload of a resumed handshake, if a resumption secret was set for the
+    /// initiator. Return None if the responder answered with a full handshake,
+    /// in which case the state is not modified.
+    async fn decrypt_resumed_message2(
+        &self,
+        state: &mut HandshakeState,
+        message: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let Some(resumption_secret) = state.resumption_secret.clone() else {
+            return Ok(None);
+        };
+
+        let (new_ck, new_k) = self.hkdf_outputs(state, &resumption_secret).await?;
+        let mut resumed_state = state.clone();
+        resumed_state.ck = Some(new_ck.clone());
+        resumed_state.k = Some(new_k.clone());
+        resumed_state.n = 0;
+
+        match self.hash_and_decrypt(&mut resumed_state, message).await {
+            Ok(payload) => {
+                self.replace_ck_and_k(state, new_ck, new_k).await?;
+                state.n = resumed_state.n;
+                state.h = resumed_state.h;
+                if let Some(resumption_secret) = state.resumption_secret.take() {
+                    self.vault.delete_secret_buffer(resumption_secret).await?;
+                }
+                Ok(Some(payload))
+            }
+            Err(_) => {
+                self.vault.delete_secret_buffer(new_ck).await?;
+                self.vault.delete_aead_secret_key(new_k).await?;
+                Ok(None)
+            }
+        }
+    }
+
     async fn delete_ephemeral_keys(&mut self) -> Result<()> {
         _ = self
             .vault
             .delete_ephemeral_x25519_secret_key(self.state.take_e()?)
             .await?;
 
+        if let Some(kem) = self.state.kem.take() {
+            _ = self.vault.delete_ephemeral_ml_kem_secret_key(kem).await?;
+        }
+
+        // the resumption secret is not used if the responder answered with a full handshake
+        if let Some(resumption_secret) = self.state.resumption_secret.take() {
+            _ = self.vault.delete_secret_buffer(resumption_secret).await?;
+        }
+
         Ok(())
     }
 }
@@ -414,15 +617,33 @@ impl Handshake {
         Self::read_end::<X25519_PUBLIC_KEY_LENGTH>(message)
     }
 
-    /// Read the message 2 encrypted key, which is present after the public key
+    /// Read the message 2 bytes present after the public key
+    fn read_message2_after_ephemeral_key(message: &[u8]) -> Result<&[u8]> {
+        Self::read_end::<X25519_PUBLIC_KEY_LENGTH>(message)
+    }
+
+    /// Read the encrypted ML-KEM ciphertext at the beginning of the message 2 bytes
+    /// present after the public key
+    fn read_ml_kem_ciphertext(message: &[u8]) -> Result<&[u8]> {
+        const L: usize = ML_KEM_768_CIPHERTEXT_LENGTH + AES_GCM_TAGSIZE;
+        Ok(Self::read_start::<L>(message)?)
+    }
+
+    /// Read the message 2 bytes present after the encrypted ML-KEM ciphertext
+    fn read_message2_after_ml_kem_ciphertext(message: &[u8]) -> Result<&[u8]> {
+        const L: usize = ML_KEM_768_CIPHERTEXT_LENGTH + AES_GCM_TAGSIZE;
+        Self::read_end::<L>(message)
+    }
+
+    /// Read the message 2 encrypted key, which is present after the key exchange data
     fn read_message2_encrypted_key(message: &[u8]) -> Result<&[u8]> {
         const L: usize = X25519_PUBLIC_KEY_LENGTH + AES_GCM_TAGSIZE;
-        Self::read_middle::<X25519_PUBLIC_KEY_LENGTH, L>(message)
+        Ok(Self::read_start::<L>(message)?)
     }
 
     /// Read the message 2 encrypted payload, which is present after the encrypted key
     fn read_message2_payload(message: &[u8]) -> Result<&[u8]> {
-        const L: usize = 2 * X25519_PUBLIC_KEY_LENGTH + AES_GCM_TAGSIZE;
+        const L: usize = X25519_PUBLIC_KEY_LENGTH + AES_GCM_TAGSIZE;
         Self::read_end::<L>(message)
     }
 
@@ -456,15 +677,6 @@ impl Handshake {
         Ok(message[N..].try_into().unwrap())
     }
 
-    /// Read 'length' bytes of the message after the first 'drop_length' bytes
-    fn read_middle<const N: usize, const L: usize>(message: &[u8]) -> Result<&[u8]> {
-        if message.len() < N + L {
-            return Err(XXError::MessageLenMismatch)?;
-        }
-
-        Ok(message[N..(N + L)].try_into().unwrap())
-    }
-
     /// Read the bytes of a key at the beginning of a message
     fn read_key(message: &[u8]) -> Result<&[u8; X25519_PUBLIC_KEY_LENGTH]> {
         Self::read_start::<X25519_PUBLIC_KEY_LENGTH>(message)
@@ -479,6 +691,14 @@ pub(super) struct HandshakeState {
     k: Option<AeadSecretKeyHandle>,
     re: Option<X25519PublicKey>,
     pub(super) rs: Option<X25519PublicKey>,
+    // ephemeral ML-KEM key of the initiator, for a hybrid key exchange
+    kem: Option<MlKemSecretKeyHandle>,
+    // true if an ML-KEM shared secret was mixed in the chaining key
+    pub(super) hybrid: bool,
+    // secret shared with the responder during a previous handshake, for a resumed handshake
+    resumption_secret: Option<SecretBufferHandle>,
+    // true if the resumption secret was mixed in the chaining key
+    pub(super) resumed: bool,
     n: u64,
     h: [u8; SHA256_SIZE],
     ck: Option<SecretBufferHandle>,
@@ -497,6 +717,10 @@ impl HandshakeState {
             k: None,
             re: None,
             rs: None,
+            kem: None,
+            hybrid: false,
+            resumption_secret: None,
+            resumed: false,
             n: 0,
             h: [0u8; SHA256_SIZE],
             ck: None,
@@ -587,6 +811,16 @@ impl HandshakeState {
         })
     }
 
+    pub(super) fn kem(&self) -> Result<&MlKemSecretKeyHandle> {
+        self.kem.as_ref().ok_or_else(|| {
+            Error::new(
+                Origin::KeyExchange,
+                Kind::Invalid,
+                "key id kem should have been set",
+            )
+        })
+    }
+
     pub(super) fn re(&self) -> Result<&X25519PublicKey> {
         self.re.as_ref().ok_or_else(|| {
             Error::new(
@@ -683,10 +917,176 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_hybrid_handshake() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let (mut initiator, mut responder) = create_handshakes(vault.clone()).await?;
+
+        let ml_kem_public_key = initiator.generate_ml_kem_key().await?;
+        let message1 = initiator.encode_message1(&[]).await?;
+        responder.decode_message1(&message1).await?;
+        let message2 = responder
+            .encode_message2(b"responder", Some(&ml_kem_public_key))
+            .await?;
+        assert_eq!(initiator.decode_message2(&message2).await?, b"responder");
+        let message3 = initiator.encode_message3(b"initiator").await?;
+        assert_eq!(responder.decode_message3(&message3).await?, b"initiator");
+
+        assert!(initiator.state.hybrid);
+        assert!(responder.state.hybrid);
+        check_final_keys(vault.clone(), initiator, responder).await?;
+        assert_eq!(vault.number_of_ephemeral_ml_kem_secrets(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_handshake_with_a_classic_responder() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let (mut initiator, mut responder) = create_handshakes(vault.clone()).await?;
+
+        // the responder ignores the ML-KEM public key
+        initiator.generate_ml_kem_key().await?;
+        let message1 = initiator.encode_message1(&[]).await?;
+        responder.decode_message1(&message1).await?;
+        let message2 = responder.encode_message2(b"responder", None).await?;
+        assert_eq!(initiator.decode_message2(&message2).await?, b"responder");
+        let message3 = initiator.encode_message3(b"initiator").await?;
+        assert_eq!(responder.decode_message3(&message3).await?, b"initiator");
+
+        assert!(!initiator.state.hybrid);
+        assert!(!responder.state.hybrid);
+        check_final_keys(vault.clone(), initiator, responder).await?;
+        assert_eq!(vault.number_of_ephemeral_ml_kem_secrets(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resumed_handshake() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let (initiator_secret, responder_secret) = full_handshake(vault.clone()).await?;
+        let (mut initiator, mut responder) = create_handshakes(vault.clone()).await?;
+
+        initiator.set_resumption_secret(initiator_secret);
+        let message1 = initiator.encode_message1(b"ticket").await?;
+        assert_eq!(responder.decode_message1(&message1).await?, b"ticket");
+        let message2 = responder
+            .encode_resumed_message2(responder_secret, None)
+            .await?;
+        assert!(initiator.decode_message2(&message2).await?.is_empty());
+
+        assert!(initiator.state.resumed);
+        assert!(responder.state.resumed);
+        check_final_keys(vault.clone(), initiator, responder).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resumed_handshake_with_a_different_secret() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let (initiator_secret, _) = full_handshake(vault.clone()).await?;
+        let (_, responder_secret) = full_handshake(vault.clone()).await?;
+        let (mut initiator, mut responder) = create_handshakes(vault.clone()).await?;
+
+        initiator.set_resumption_secret(initiator_secret);
+        let message1 = initiator.encode_message1(b"ticket").await?;
+        responder.decode_message1(&message1).await?;
+        let message2 = responder
+            .encode_resumed_message2(responder_secret, None)
+            .await?;
+        assert!(initiator.decode_message2(&message2).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resumption_declined_by_the_responder() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let (initiator_secret, _) = full_handshake(vault.clone()).await?;
+        let (mut initiator, mut responder) = create_handshakes(vault.clone()).await?;
+
+        // the responder ignores the ticket and runs a full handshake
+        initiator.set_resumption_secret(initiator_secret);
+        let message1 = initiator.encode_message1(b"ticket").await?;
+        responder.decode_message1(&message1).await?;
+        let message2 = responder.encode_message2(b"responder", None).await?;
+        assert_eq!(initiator.decode_message2(&message2).await?, b"responder");
+        let message3 = initiator.encode_message3(b"initiator").await?;
+        assert_eq!(responder.decode_message3(&message3).await?, b"initiator");
+
+        assert!(!initiator.state.resumed);
+        assert!(!responder.state.resumed);
+        check_final_keys(vault.clone(), initiator, responder).await?;
+        Ok(())
+    }
+
     // --------------------
     // TESTS IMPLEMENTATION
     // --------------------
 
+    /// Run a full handshake and return the resumption secrets of the initiator and the responder
+    async fn full_handshake(
+        vault: Arc<SoftwareVaultForSecureChannels>,
+    ) -> Result<(SecretBufferHandle, SecretBufferHandle)> {
+        let (mut initiator, mut responder) = create_handshakes(vault.clone()).await?;
+        let message1 = initiator.encode_message1(&[]).await?;
+        responder.decode_message1(&message1).await?;
+        let message2 = responder.encode_message2(&[], None).await?;
+        initiator.decode_message2(&message2).await?;
+        let message3 = initiator.encode_message3(&[]).await?;
+        responder.decode_message3(&message3).await?;
+        check_final_keys(vault, initiator, responder).await
+    }
+
+    async fn create_handshakes(
+        vault: Arc<SoftwareVaultForSecureChannels>,
+    ) -> Result<(Handshake, Handshake)> {
+        let mut initiator = Handshake::new(
+            vault.clone(),
+            vault.generate_static_x25519_secret_key().await?,
+        )
+        .await?;
+        let mut responder = Handshake::new(
+            vault.clone(),
+            vault.generate_static_x25519_secret_key().await?,
+        )
+        .await?;
+        initiator.initialize().await?;
+        responder.initialize().await?;
+        Ok((initiator, responder))
+    }
+
+    /// Check that a message encrypted by the initiator can be decrypted by the responder,
+    /// and return the resumption secrets of the initiator and the responder
+    async fn check_final_keys(
+        vault: Arc<SoftwareVaultForSecureChannels>,
+        mut initiator: Handshake,
+        mut responder: Handshake,
+    ) -> Result<(SecretBufferHandle, SecretBufferHandle)> {
+        initiator.set_final_state(Role::Initiator, true).await?;
+        responder.set_final_state(Role::Responder, true).await?;
+        let initiator_keys = initiator.get_handshake_keys().unwrap();
+        let responder_keys = responder.get_handshake_keys().unwrap();
+
+        let nonce = Nonce::new(0).to_aes_gcm_nonce();
+        let mut message = b"hello".to_vec();
+        message.extend_from_slice(&[0u8; AES_GCM_TAGSIZE]);
+        vault
+            .aead_encrypt(&initiator_keys.encryption_key, &mut message, &nonce, &[])
+            .await?;
+        let decrypted = vault
+            .aead_decrypt(&responder_keys.decryption_key, &mut message, &nonce, &[])
+            .await?;
+        assert_eq!(decrypted, b"hello");
+
+        let initiator_secret = initiator_keys.resumption_secret.unwrap();
+        let responder_secret = responder_keys.resumption_secret.unwrap();
+        assert!(vault.get_secret_buffer(&initiator_secret).is_some());
+        assert_eq!(
+            vault.get_secret_buffer(&initiator_secret),
+            vault.get_secret_buffer(&responder_secret)
+        );
+        Ok((initiator_secret, responder_secret))
+    }
+
     struct HandshakeMessages {
         initiator_static_key: X25519SecretKey,
         initiator_ephemeral_key: X25519SecretKey,
@@ -740,7 +1140,7 @@ mod tests {
         assert_eq!(decoded, messages.message1_payload);
 
         let result = responder
-            .encode_message2(&messages.message2_payload)
+            .encode_message2(&messages.message2_payload, None)
             .await?;
         assert_eq!(result, messages.message2_ciphertext);
 
@@ -755,10 +1155,10 @@ mod tests {
         let decoded = responder.decode_message3(&result).await?;
         assert_eq!(decoded, messages.message3_payload);
 
-        let result = initiator.set_final_state(Role::Responder).await;
+        let result = initiator.set_final_state(Role::Responder, false).await;
         assert!(result.is_ok());
 
-        let result = responder.set_final_state(Role::Initiator).await;
+        let result = responder.set_final_state(Role::Initiator, false).await;
         assert!(result.is_ok());
 
         Ok(())
@@ -6,13 +6,16 @@ use ockam_core::compat::string::ToString;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::{async_trait, Result};
-use ockam_vault::{AeadSecretKeyHandle, X25519PublicKey};
+use ockam_vault::{AeadSecretKeyHandle, SecretBufferHandle, X25519PublicKey};
 
 use crate::models::{
     ChangeHistory, CredentialAndPurposeKey, PurposeKeyAttestation, PurposePublicKey,
+    TimestampInSeconds,
 };
+use crate::secure_channel::ResumptionState;
 use crate::{
-    CredentialRetriever, Identifier, Identities, IdentityError, SecureChannelTrustInfo, TrustPolicy,
+    CredentialRetriever, Identifier, Identities, IdentityError, ResumptionTicketMessage,
+    SecureChannelTrustInfo, TrustPolicy,
 };
 
 /// Interface for a state machine in a key exchange protocol
@@ -22,12 +25,14 @@ pub(crate) trait StateMachine: Send + Sync + 'static {
     fn get_handshake_results(&self) -> Option<HandshakeResults>;
 }
 
-/// Events received by the state machine, either initializing the state machine
-/// or receiving a message from the other party
+/// Events received by the state machine, either initializing the state machine,
+/// receiving a message from the other party, or receiving a cookie from a secure channel
+/// listener asking to send the first message again
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum Event {
     Initialize,
     ReceivedMessage(Vec<u8>),
+    ReceivedCookie(Vec<u8>),
 }
 
 /// Outcome of processing an event: either no action or a message to send to the other party
@@ -47,11 +52,13 @@ pub(crate) enum Status {
     Ready(HandshakeKeys),
 }
 
-/// At the end of a successful handshake a pair of encryption/decryption keys is available
+/// At the end of a successful handshake a pair of encryption/decryption keys is available,
+/// and optionally a secret used to resume the secure channel later
 #[derive(Debug, Clone)]
 pub(crate) struct HandshakeKeys {
     pub(super) encryption_key: AeadSecretKeyHandle,
     pub(super) decryption_key: AeadSecretKeyHandle,
+    pub(super) resumption_secret: Option<SecretBufferHandle>,
 }
 
 /// The end result of a handshake with identity/credentials exchange is
@@ -61,6 +68,12 @@ pub(crate) struct HandshakeResults {
     pub(super) handshake_keys: HandshakeKeys,
     pub(super) their_identifier: Identifier,
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
+    /// True if the secure channel was resumed with a resumption ticket
+    pub(super) resumed: bool,
+    /// Resumption ticket to send to the initiator, on the responder side
+    pub(super) resumption_ticket: Option<ResumptionTicketMessage>,
+    /// Resumption state waiting for a ticket sent by the responder, on the initiator side
+    pub(super) resumption_state: Option<ResumptionState>,
 }
 
 /// This struct implements functions common to both initiator and the responder state machines
@@ -72,7 +85,12 @@ pub(crate) struct CommonStateMachine {
     pub(super) trust_policy: Arc<dyn TrustPolicy>,
     pub(super) authority: Option<Identifier>, // TODO: Replace with ABAC
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
-    their_identifier: Option<Identifier>,
+    pub(super) their_identifier: Option<Identifier>,
+    // Earliest expiration of the credentials presented by both parties
+    pub(super) credentials_expiration: Option<TimestampInSeconds>,
+    pub(super) resumed: bool,
+    pub(super) resumption_ticket: Option<ResumptionTicketMessage>,
+    pub(super) resumption_state: Option<ResumptionState>,
 }
 
 impl CommonStateMachine {
@@ -93,6 +111,10 @@ impl CommonStateMachine {
             authority,
             presented_credential: None,
             their_identifier: None,
+            credentials_expiration: None,
+            resumed: false,
+            resumption_ticket: None,
+            resumption_state: None,
         }
     }
 
@@ -112,6 +134,9 @@ impl CommonStateMachine {
         };
 
         self.presented_credential.clone_from(&credential);
+        if let Some(credential) = &credential {
+            self.add_credentials_expiration(credential.get_expires_at()?);
+        }
         let credentials = credential.map(|c| vec![c]).unwrap_or(vec![]);
 
         let payload = IdentityAndCredentials {
@@ -130,6 +155,12 @@ impl CommonStateMachine {
         peer: IdentityAndCredentials,
         peer_public_key: X25519PublicKey,
     ) -> Result<()> {
+        for credential in &peer.credentials {
+            if let Ok(expires_at) = credential.get_expires_at() {
+                self.add_credentials_expiration(expires_at);
+            }
+        }
+
         let identifier = Self::process_identity_payload_static(
             self.identities.clone(),
             Some(self.trust_policy.clone()),
@@ -158,10 +189,80 @@ impl CommonStateMachine {
                 their_identifier,
                 handshake_keys,
                 presented_credential: self.presented_credential.clone(),
+                resumed: self.resumed,
+                resumption_ticket: self.resumption_ticket.clone(),
+                resumption_state: self.resumption_state.clone(),
             }),
             _ => None,
         }
     }
+
+    /// Keep track of the earliest expiration of the presented credentials
+    fn add_credentials_expiration(&mut self, expires_at: TimestampInSeconds) {
+        self.credentials_expiration = Some(match self.credentials_expiration {
+            Some(credentials_expiration) => credentials_expiration.min(expires_at),
+            None => expires_at,
+        });
+    }
+
+    /// Create a resumption state bound to the current identities of both parties,
+    /// at the end of a handshake
+    pub(super) async fn make_resumption_state(
+        &self,
+        secret: SecretBufferHandle,
+    ) -> Result<ResumptionState> {
+        let their_identifier = self
+            .their_identifier
+            .clone()
+            .ok_or(IdentityError::HandshakeInternalError)?;
+        Ok(ResumptionState {
+            secret,
+            my_identifier: self.identifier.clone(),
+            my_change_history: self.identities.get_change_history(&self.identifier).await?,
+            their_change_history: self
+                .identities
+                .get_change_history(&their_identifier)
+                .await?,
+            their_identifier,
+            credentials_expiration: self.credentials_expiration,
+        })
+    }
+
+    /// Check that a secure channel can be resumed with a given resumption state:
+    /// none of the identities must have changed since the handshake which created that state,
+    /// and the other party must still be trusted
+    pub(super) async fn can_resume(&self, state: &ResumptionState) -> Result<bool> {
+        if state.my_identifier != self.identifier {
+            return Ok(false);
+        }
+
+        let my_change_history = self.identities.get_change_history(&self.identifier).await?;
+        let their_change_history = self
+            .identities
+            .get_change_history(&state.their_identifier)
+            .await
+            .ok();
+        if my_change_history != state.my_change_history
+            || their_change_history.as_ref() != Some(&state.their_change_history)
+        {
+            debug!(
+                "Cannot resume the secure channel with {}: an identity has changed",
+                state.their_identifier
+            );
+            return Ok(false);
+        }
+
+        let trust_info = SecureChannelTrustInfo::new(state.their_identifier.clone());
+        self.trust_policy.check(&trust_info).await
+    }
+
+    /// Resume a secure channel: the identity of the other party and the credentials expiration
+    /// are taken from the resumption state
+    pub(super) fn resume(&mut self, state: &ResumptionState) {
+        self.their_identifier = Some(state.their_identifier.clone());
+        self.credentials_expiration = state.credentials_expiration;
+        self.resumed = true;
+    }
 }
 
 impl CommonStateMachine {
@@ -306,3 +407,43 @@ pub(super) struct IdentityAndCredentials {
     /// to verify those Credentials
     #[n(2)] pub(super) credentials: Vec<CredentialAndPurposeKey>,
 }
+
+/// Optional payload of the first message sent by the initiator.
+/// This payload is ignored by responders which don't support it, and it is empty when none
+/// of its fields are set, in order to stay compatible with those responders
+#[derive(Debug, Clone, Default, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub(crate) struct Message1Payload {
+    /// Cookie sent back by a secure channel listener requiring the initiator to prove that it can
+    /// receive messages before starting the handshake
+    #[cbor(n(0), with = "minicbor::bytes")] pub(crate) cookie: Option<Vec<u8>>,
+    /// ML-KEM public key of the initiator, requesting a hybrid key exchange
+    #[cbor(n(1), with = "minicbor::bytes")] pub(crate) ml_kem_public_key: Option<Vec<u8>>,
+    /// Resumption ticket received at the end of a previous handshake, to resume a secure channel
+    #[cbor(n(2), with = "minicbor::bytes")] pub(crate) resumption_ticket: Option<Vec<u8>>,
+    /// Request a resumption ticket at the end of the handshake
+    #[n(3)] pub(crate) request_resumption_ticket: Option<bool>,
+}
+
+impl Message1Payload {
+    /// Encode the payload, as an empty vector if no field is set
+    pub(crate) fn encode(&self) -> Result<Vec<u8>> {
+        if self.cookie.is_none()
+            && self.ml_kem_public_key.is_none()
+            && self.resumption_ticket.is_none()
+            && self.request_resumption_ticket.is_none()
+        {
+            Ok(Vec::new())
+        } else {
+            ockam_core::cbor_encode_preallocate(self)
+        }
+    }
+
+    /// Decode the payload. An empty or invalid payload is decoded as a payload without any field
+    pub(crate) fn decode(payload: &[u8]) -> Self {
+        if payload.is_empty() {
+            return Self::default();
+        }
+        minicbor::decode(payload).unwrap_or_default()
+    }
+}
@@ -1,11 +1,11 @@
-use core::sync::atomic::AtomicBool;
+use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
-    AllowAll, Any, DenyAll, Error, Mailbox, Mailboxes, NeutralMessage, OutgoingAccessControl,
-    Route, Routed, SecureChannelMetadata,
+    Address, AllowAll, Any, DenyAll, Error, Mailbox, Mailboxes, NeutralMessage,
+    OutgoingAccessControl, Route, Routed, SecureChannelMetadata,
 };
 use ockam_core::{Result, Worker};
 use ockam_node::callback::CallbackSender;
@@ -22,14 +22,16 @@ use crate::secure_channel::encryptor_worker::{
 };
 use crate::secure_channel::handshake::handshake_state_machine::Action::SendMessage;
 use crate::secure_channel::handshake::handshake_state_machine::Event::{
-    Initialize, ReceivedMessage,
+    Initialize, ReceivedCookie, ReceivedMessage,
 };
 use crate::secure_channel::handshake::handshake_state_machine::{
     Action, HandshakeResults, StateMachine,
 };
 use crate::secure_channel::handshake::initiator_state_machine::InitiatorStateMachine;
 use crate::secure_channel::handshake::responder_state_machine::ResponderStateMachine;
-use crate::secure_channel::{Addresses, Role};
+use crate::secure_channel::{
+    Addresses, HandshakePermit, HandshakeResumption, PendingResumption, ResumptionTicketCache, Role,
+};
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, IdentityError, PersistedSecureChannel,
     SecureChannelPurposeKey, SecureChannelRegistryEntry, SecureChannelRepository, SecureChannels,
@@ -48,6 +50,12 @@ pub(crate) struct HandshakeWorker {
     key_exchange_only: bool,
     remote_route: Option<Route>,
     decryptor_handler: Option<DecryptorHandler>,
+    handshake_failed: bool,
+    // Slot reserved by a secure channel listener for this handshake, released once the
+    // handshake is completed or when the worker stops
+    handshake_permit: Option<HandshakePermit>,
+    // Set once the handshake is completed, to cancel the responder handshake timeout
+    handshake_completed: Arc<AtomicBool>,
 
     authority: Option<Identifier>,
     change_history_repository: Arc<dyn ChangeHistoryRepository>,
@@ -57,6 +65,9 @@ pub(crate) struct HandshakeWorker {
     secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
 
     shared_state: SecureChannelSharedState,
+
+    // Cache and name used by an initiator to store the resumption ticket sent by the responder
+    resumption_cache: Option<(Arc<ResumptionTicketCache>, String)>,
 }
 
 #[ockam_core::worker]
@@ -111,17 +122,33 @@ impl Worker for HandshakeWorker {
         if self.decryptor_handler.is_some() {
             self.handle_decrypt(context, message).await
         } else {
-            self.handle_handshake(context, message).await
+            let result = self.handle_handshake(context, message).await;
+            // A failed handshake on the initiator side is recorded when waiting for its result
+            if result.is_err() && !self.role.is_initiator() && !self.handshake_failed {
+                self.handshake_failed = true;
+                self.secure_channels
+                    .secure_channel_registry
+                    .record_handshake_failure();
+            }
+            result
         }
     }
 
     async fn shutdown(&mut self, context: &mut Self::Context) -> Result<()> {
+        // A responder stopped before the end of its handshake, for example after a timeout
+        if self.decryptor_handler.is_none() && !self.role.is_initiator() && !self.handshake_failed {
+            self.handshake_failed = true;
+            self.secure_channels
+                .secure_channel_registry
+                .record_handshake_failure();
+        }
+
         let _ = context.stop_worker(self.addresses.encryptor.clone()).await;
         self.secure_channels
             .secure_channel_registry
             .unregister_channel(&self.addresses.encryptor);
 
-        if let Some(handler) = &self.decryptor_handler {
+        if let Some(handler) = &mut self.decryptor_handler {
             handler.shutdown().await?
         }
 
@@ -146,12 +173,25 @@ impl HandshakeWorker {
         timeout: Option<Duration>,
         role: Role,
         key_exchange_only: bool,
+        hybrid_key_exchange: bool,
+        resumption: Option<HandshakeResumption>,
         secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
         encryptor_remote_route: Arc<RwLock<RemoteRoute>>,
+        handshake_permit: Option<HandshakePermit>,
     ) -> Result<Option<Identifier>> {
         let vault = secure_channels.identities.vault().secure_channel_vault;
         let identities = secure_channels.identities();
 
+        let (resumption_cache, resumption_ticket, resumption_tickets) = match resumption {
+            Some(HandshakeResumption::Initiator {
+                cache,
+                name,
+                ticket,
+            }) => (Some((cache, name)), ticket.map(|ticket| *ticket), None),
+            Some(HandshakeResumption::Responder(tickets)) => (None, None, Some(tickets)),
+            None => (None, None, None),
+        };
+
         let state_machine: Box<dyn StateMachine> = if role.is_initiator() {
             Box::new(
                 InitiatorStateMachine::new(
@@ -162,6 +202,9 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    hybrid_key_exchange,
+                    resumption_cache.is_some(),
+                    resumption_ticket,
                 )
                 .await?,
             )
@@ -175,6 +218,8 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    hybrid_key_exchange,
+                    resumption_tickets,
                 )
                 .await?,
             )
@@ -187,10 +232,12 @@ impl HandshakeWorker {
             (None, None)
         };
 
+        let secure_channels_registry = secure_channels.secure_channel_registry.clone();
         let shared_state = SecureChannelSharedState {
             should_send_close: Arc::new(AtomicBool::new(true)),
             remote_route: encryptor_remote_route,
         };
+        let handshake_completed = Arc::new(AtomicBool::new(false));
         let worker = Self {
             secure_channels,
             callback_sender,
@@ -201,11 +248,15 @@ impl HandshakeWorker {
             remote_route: remote_route.clone(),
             addresses: addresses.clone(),
             decryptor_handler: None,
+            handshake_failed: false,
+            handshake_permit,
+            handshake_completed: handshake_completed.clone(),
             credential_retriever,
             authority,
             change_history_repository: identities.change_history_repository(),
             secure_channel_repository,
             shared_state,
+            resumption_cache,
         };
 
         WorkerBuilder::new(worker)
@@ -221,6 +272,20 @@ impl HandshakeWorker {
             role, addresses.decryptor_remote, addresses.encryptor
         );
 
+        // a responder does not wait for the end of the handshake, it is stopped
+        // if the handshake is not completed in time
+        if !role.is_initiator() {
+            if let Some(timeout) = timeout {
+                Self::stop_after_timeout(
+                    context,
+                    &addresses.decryptor_remote,
+                    timeout,
+                    handshake_completed,
+                )
+                .await?;
+            }
+        }
+
         // before sending messages make sure that the handshake is finished and
         // the encryptor worker is ready
         let their_identifier = if role.is_initiator() {
@@ -232,6 +297,7 @@ impl HandshakeWorker {
                     match res {
                         Ok(their_identifier) => Some(their_identifier),
                         Err(err) => {
+                            secure_channels_registry.record_handshake_failure();
                             error!(
                             "Timeout {:?} or error reached when creating secure channel for: {}. Encryptor: {}. Error: {err:?}",
                             timeout, my_identifier, addresses.encryptor
@@ -268,19 +334,33 @@ impl HandshakeWorker {
         let return_route = message.return_route;
         let payload = message.payload;
 
-        if let SendMessage(send_message) = self
+        // A message coming back from the secure channel listener itself, instead of
+        // the spawned responder, contains a cookie
+        let received_cookie = self.role.is_initiator()
+            && self.remote_route.as_ref().map(|r| r.recipient().ok())
+                == Some(return_route.recipient().ok());
+        let event = if received_cookie {
+            ReceivedCookie(payload)
+        } else {
+            ReceivedMessage(payload)
+        };
+
+        let action = self
             .state_machine
             .as_mut()
             .ok_or(IdentityError::HandshakeInternalError)?
-            .on_event(ReceivedMessage(payload))
-            .await?
-        {
-            // set the remote route by taking the most up to date message return route
-            // In the case of the initiator the first return route mentions the secure channel listener
-            // address so we need to wait for the return route corresponding to the remote handshake worker
-            // when it has been spawned
+            .on_event(event)
+            .await?;
+
+        // set the remote route by taking the most up to date message return route
+        // In the case of the initiator the first return route mentions the secure channel listener
+        // address so we need to wait for the return route corresponding to the remote handshake worker
+        // when it has been spawned
+        if !received_cookie {
             self.remote_route = Some(return_route);
+        }
 
+        if let SendMessage(send_message) = action {
             context
                 .send_from_address(
                     self.remote_route()?,
@@ -300,6 +380,8 @@ impl HandshakeWorker {
             // start the encryptor worker and return the decryptor
             let their_identifier = final_state.their_identifier.clone();
             self.decryptor_handler = Some(self.finalize(context, final_state).await?);
+            self.handshake_completed.store(true, Ordering::Relaxed);
+            self.handshake_permit.take();
             if let Some(callback_sender) = self.callback_sender.take() {
                 callback_sender.send(their_identifier)?;
             }
@@ -333,6 +415,34 @@ impl HandshakeWorker {
         }
     }
 
+    /// Stop the handshake worker if the handshake is not completed after the given timeout
+    async fn stop_after_timeout(
+        context: &Context,
+        decryptor_remote: &Address,
+        timeout: Duration,
+        handshake_completed: Arc<AtomicBool>,
+    ) -> Result<()> {
+        let ctx = context
+            .new_detached(
+                Address::random_tagged("HandshakeWorker.timeout"),
+                DenyAll,
+                DenyAll,
+            )
+            .await?;
+        let decryptor_remote = decryptor_remote.clone();
+        ockam_node::spawn(async move {
+            ctx.sleep(timeout).await;
+            if !handshake_completed.load(Ordering::Relaxed) {
+                warn!(
+                    "Handshake timeout {:?} reached for the secure channel responder {}",
+                    timeout, decryptor_remote
+                );
+                let _ = ctx.stop_worker(decryptor_remote).await;
+            }
+        });
+        Ok(())
+    }
+
     /// Return the route for the other party's handshake worker
     fn remote_route(&self) -> Result<Route> {
         self.remote_route.clone().ok_or_else(|| {
@@ -381,6 +491,21 @@ impl HandshakeWorker {
         handshake_results: HandshakeResults,
     ) -> Result<DecryptorHandler> {
         let their_identifier = handshake_results.their_identifier.clone();
+        if handshake_results.resumed {
+            self.secure_channels
+                .secure_channel_registry
+                .record_handshake_resumed();
+        }
+
+        // an initiator waits for a resumption ticket sent over the secure channel
+        let resumption = match (&self.resumption_cache, handshake_results.resumption_state) {
+            (Some((cache, name)), Some(state)) => Some(PendingResumption {
+                cache: cache.clone(),
+                name: name.clone(),
+                state,
+            }),
+            _ => None,
+        };
 
         // create a decryptor to delegate the processing of all messages after the handshake
         let decryptor = DecryptorHandler::new(
@@ -393,6 +518,7 @@ impl HandshakeWorker {
             self.secure_channels.identities.vault().secure_channel_vault,
             handshake_results.their_identifier.clone(),
             self.shared_state.clone(),
+            resumption,
         );
 
         // create a separate encryptor worker which will be started independently
@@ -420,6 +546,7 @@ impl HandshakeWorker {
                 credential_retriever,
                 handshake_results.presented_credential,
                 self.shared_state.clone(),
+                handshake_results.resumption_ticket,
             );
 
             let main_mailbox = Mailbox::new(
@@ -567,11 +694,15 @@ impl HandshakeWorker {
             key_exchange_only,
             remote_route,
             decryptor_handler,
+            handshake_failed: false,
+            handshake_permit: None,
+            handshake_completed: Arc::new(AtomicBool::new(true)),
             authority,
             change_history_repository,
             credential_retriever,
             secure_channel_repository,
             shared_state,
+            resumption_cache: None,
         }
     }
 }
@@ -4,7 +4,8 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
-use ockam_vault::{VaultForSecureChannels, X25519PublicKey};
+use ockam_vault::{MlKemPublicKey, VaultForSecureChannels, X25519PublicKey};
+use tracing::{debug, warn};
 use Action::*;
 use Event::*;
 use Role::*;
@@ -12,11 +13,12 @@ use Status::*;
 
 use crate::models::Identifier;
 use crate::secure_channel::handshake::error::XXError;
-use crate::secure_channel::handshake::handshake::Handshake;
+use crate::secure_channel::handshake::handshake::{Handshake, HandshakeState};
 use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
-    StateMachine, Status,
+    Message1Payload, StateMachine, Status,
 };
+use crate::secure_channel::ResumptionTicket;
 use crate::{CredentialRetriever, Identities, Role, SecureChannelPurposeKey, TrustPolicy};
 
 /// Implementation of a state machine for the key exchange on the initiator side
@@ -28,15 +30,55 @@ impl StateMachine for InitiatorStateMachine {
             // Initialize the handshake and send message 1
             (Initial, Initialize) => {
                 self.initialize_handshake().await?;
-                let message1 = self.encode_message1(&[]).await?;
+                if self.hybrid_key_exchange {
+                    self.ml_kem_public_key = Some(self.generate_ml_kem_key().await?);
+                }
+                self.use_resumption_ticket().await?;
+                self.state_before_message1 = Some(self.handshake.state.clone());
+                let message1 = self.encode_message1(&self.message1_payload(None)?).await?;
 
                 // Send message 1 and wait for message 2
                 self.handshake.state.status = WaitingForMessage2;
                 Ok(SendMessage(message1))
             }
+            // The listener requires a cookie before starting the handshake:
+            // send message 1 again, only once, with the cookie as its payload
+            (WaitingForMessage2, ReceivedCookie(cookie)) => {
+                let state_before_message1 = self
+                    .state_before_message1
+                    .take()
+                    .ok_or(XXError::InvalidInternalState)?;
+                self.handshake.state = state_before_message1;
+                let message1 = self
+                    .encode_message1(&self.message1_payload(Some(cookie))?)
+                    .await?;
+
+                self.handshake.state.status = WaitingForMessage2;
+                Ok(SendMessage(message1))
+            }
             // Process message 2 and send message 3
             (WaitingForMessage2, ReceivedMessage(message)) => {
                 let message2_payload = self.decode_message2(&message).await?;
+                if self.hybrid_key_exchange && !self.handshake.state.hybrid {
+                    warn!("The responder does not support the hybrid key exchange, falling back to a classic key exchange");
+                }
+
+                // The responder accepted the resumption ticket: the handshake is completed
+                if self.handshake.state.resumed {
+                    let ticket = self
+                        .resumption_ticket
+                        .take()
+                        .ok_or(XXError::InvalidInternalState)?;
+                    self.common.resume(&ticket.state);
+                    self.set_final_state(Initiator, self.request_resumption_ticket)
+                        .await?;
+                    self.prepare_resumption().await?;
+                    return Ok(NoAction);
+                }
+                if self.resumption_ticket.take().is_some() {
+                    debug!("The resumption ticket was not accepted, running a full handshake");
+                }
+
                 let their_identity_payload: IdentityAndCredentials =
                     minicbor::decode(&message2_payload)?;
                 self.process_identity_payload(
@@ -50,7 +92,9 @@ impl StateMachine for InitiatorStateMachine {
                     .await
                     .map_err(|_e| XXError::InvalidInternalState)?;
                 let message3 = self.encode_message3(&identity_payload).await?;
-                self.set_final_state(Initiator).await?;
+                self.set_final_state(Initiator, self.request_resumption_ticket)
+                    .await?;
+                self.prepare_resumption().await?;
                 Ok(SendMessage(message3))
             }
             // incorrect state / event
@@ -74,6 +118,15 @@ impl StateMachine for InitiatorStateMachine {
 pub(super) struct InitiatorStateMachine {
     pub(super) common: CommonStateMachine,
     pub(super) handshake: Handshake,
+    // State used to send message 1 again when the responder replies with a cookie
+    state_before_message1: Option<HandshakeState>,
+    // Request a hybrid key exchange by sending an ML-KEM public key in message 1
+    hybrid_key_exchange: bool,
+    ml_kem_public_key: Option<MlKemPublicKey>,
+    // Request a resumption ticket at the end of the handshake
+    request_resumption_ticket: bool,
+    // Ticket received at the end of a previous handshake, used to resume the secure channel
+    resumption_ticket: Option<ResumptionTicket>,
 }
 
 impl InitiatorStateMachine {
@@ -87,16 +140,61 @@ impl InitiatorStateMachine {
         to self.handshake {
             #[call(initialize)]
             async fn initialize_handshake(&mut self) -> Result<()>;
+            async fn generate_ml_kem_key(&mut self) -> Result<MlKemPublicKey>;
             async fn encode_message1(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
             async fn decode_message2(&mut self, message: &[u8]) -> Result<Vec<u8>>;
             async fn encode_message3(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
-            async fn set_final_state(&mut self, role: Role) -> Result<()>;
+            async fn set_final_state(&mut self, role: Role, derive_resumption_secret: bool) -> Result<()>;
             fn get_handshake_keys(&self) -> Option<HandshakeKeys>;
         }
     }
+
+    /// Payload of message 1, with an optional cookie sent by the responder
+    fn message1_payload(&self, cookie: Option<Vec<u8>>) -> Result<Vec<u8>> {
+        Message1Payload {
+            cookie,
+            ml_kem_public_key: self.ml_kem_public_key.as_ref().map(|k| k.0.clone()),
+            resumption_ticket: self.resumption_ticket.as_ref().map(|t| t.ticket.clone()),
+            request_resumption_ticket: self.request_resumption_ticket.then_some(true),
+        }
+        .encode()
+    }
+
+    /// Send the resumption ticket in message 1, if the secure channel can still be resumed
+    async fn use_resumption_ticket(&mut self) -> Result<()> {
+        let Some(ticket) = self.resumption_ticket.take() else {
+            return Ok(());
+        };
+
+        if self.common.can_resume(&ticket.state).await? {
+            self.handshake
+                .set_resumption_secret(ticket.state.secret.clone());
+            self.resumption_ticket = Some(ticket);
+        } else {
+            self.common
+                .identities
+                .vault()
+                .secure_channel_vault
+                .delete_secret_buffer(ticket.state.secret)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Keep the resumption secret until the responder sends a resumption ticket
+    async fn prepare_resumption(&mut self) -> Result<()> {
+        if let Some(secret) = self
+            .get_handshake_keys()
+            .and_then(|keys| keys.resumption_secret)
+        {
+            self.common.resumption_state = Some(self.common.make_resumption_state(secret).await?);
+        }
+        Ok(())
+    }
 }
 
 impl InitiatorStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -105,6 +203,9 @@ impl InitiatorStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        hybrid_key_exchange: bool,
+        request_resumption_ticket: bool,
+        resumption_ticket: Option<ResumptionTicket>,
     ) -> Result<InitiatorStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -118,6 +219,11 @@ impl InitiatorStateMachine {
         Ok(InitiatorStateMachine {
             common,
             handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            state_before_message1: None,
+            hybrid_key_exchange,
+            ml_kem_public_key: None,
+            request_resumption_ticket,
+            resumption_ticket,
         })
     }
 }
@@ -4,7 +4,8 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
-use ockam_vault::{VaultForSecureChannels, X25519PublicKey};
+use ockam_vault::{MlKemPublicKey, SecretBufferHandle, VaultForSecureChannels, X25519PublicKey};
+use tracing::debug;
 use Action::*;
 use Event::*;
 use Role::*;
@@ -15,9 +16,14 @@ use crate::secure_channel::handshake::error::XXError;
 use crate::secure_channel::handshake::handshake::Handshake;
 use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
-    StateMachine, Status,
+    Message1Payload, StateMachine, Status,
+};
+use crate::secure_channel::{ResumptionState, ResumptionTickets};
+use crate::utils::now;
+use crate::{
+    CredentialRetriever, Identities, ResumptionTicketMessage, Role, SecureChannelPurposeKey,
+    TrustPolicy,
 };
-use crate::{CredentialRetriever, Identities, Role, SecureChannelPurposeKey, TrustPolicy};
 
 /// Implementation of a state machine for the key exchange on the responder side
 #[async_trait]
@@ -33,13 +39,42 @@ impl StateMachine for ResponderStateMachine {
             }
             // Process message 1 and send message 2
             (WaitingForMessage1, ReceivedMessage(message)) => {
-                self.decode_message1(&message).await?;
+                let message1_payload =
+                    Message1Payload::decode(&self.decode_message1(&message).await?);
+                let ml_kem_public_key = if self.hybrid_key_exchange {
+                    message1_payload.ml_kem_public_key.map(MlKemPublicKey)
+                } else {
+                    None
+                };
+                self.issue_resumption_ticket = self.resumption_tickets.is_some()
+                    && message1_payload.request_resumption_ticket == Some(true);
+
+                // Resume the secure channel without exchanging identities
+                if let Some(resumption_state) = self
+                    .redeem_resumption_ticket(message1_payload.resumption_ticket)
+                    .await?
+                {
+                    let message2 = self
+                        .encode_resumed_message2(
+                            resumption_state.secret.clone(),
+                            ml_kem_public_key.as_ref(),
+                        )
+                        .await?;
+                    self.common.resume(&resumption_state);
+                    self.set_final_state(Responder, self.issue_resumption_ticket)
+                        .await?;
+                    self.make_resumption_ticket().await?;
+                    return Ok(SendMessage(message2));
+                }
+
                 let identity_payload = self
                     .common
                     .make_identity_payload()
                     .await
                     .map_err(|_e| XXError::InvalidInternalState)?;
-                let message2 = self.encode_message2(&identity_payload).await?;
+                let message2 = self
+                    .encode_message2(&identity_payload, ml_kem_public_key.as_ref())
+                    .await?;
 
                 self.handshake.state.status = WaitingForMessage3;
                 Ok(SendMessage(message2))
@@ -54,7 +89,9 @@ impl StateMachine for ResponderStateMachine {
                     self.handshake.state.rs()?.clone(),
                 )
                 .await?;
-                self.set_final_state(Responder).await?;
+                self.set_final_state(Responder, self.issue_resumption_ticket)
+                    .await?;
+                self.make_resumption_ticket().await?;
                 Ok(NoAction)
             }
             // incorrect state / event
@@ -77,6 +114,12 @@ impl StateMachine for ResponderStateMachine {
 pub struct ResponderStateMachine {
     common: CommonStateMachine,
     handshake: Handshake,
+    // Accept a hybrid key exchange when the initiator sends an ML-KEM public key in message 1
+    hybrid_key_exchange: bool,
+    // Tickets issued by the secure channel listener, to resume secure channels
+    resumption_tickets: Option<Arc<ResumptionTickets>>,
+    // Issue a ticket at the end of the handshake, if the initiator requested it
+    issue_resumption_ticket: bool,
 }
 
 impl ResponderStateMachine {
@@ -91,15 +134,66 @@ impl ResponderStateMachine {
             #[call(initialize)]
             async fn initialize_handshake(&mut self) -> Result<()>;
             async fn decode_message1(&mut self, message: &[u8]) -> Result<Vec<u8>>;
-            async fn encode_message2(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
+            async fn encode_message2(&mut self, payload: &[u8], ml_kem_public_key: Option<&MlKemPublicKey>) -> Result<Vec<u8>>;
+            async fn encode_resumed_message2(&mut self, resumption_secret: SecretBufferHandle, ml_kem_public_key: Option<&MlKemPublicKey>) -> Result<Vec<u8>>;
             async fn decode_message3(&mut self, message: &[u8]) -> Result<Vec<u8>>;
-            async fn set_final_state(&mut self, role: Role) -> Result<()>;
+            async fn set_final_state(&mut self, role: Role, derive_resumption_secret: bool) -> Result<()>;
             fn get_handshake_keys(&self) -> Option<HandshakeKeys>;
         }
     }
+
+    /// Redeem the resumption ticket sent by the initiator, if the listener issued it and
+    /// if the secure channel can still be resumed
+    async fn redeem_resumption_ticket(
+        &self,
+        ticket: Option<Vec<u8>>,
+    ) -> Result<Option<ResumptionState>> {
+        let (Some(resumption_tickets), Some(ticket)) = (&self.resumption_tickets, ticket) else {
+            return Ok(None);
+        };
+        let Some(ticket) = resumption_tickets.redeem(&ticket, now()?).await? else {
+            debug!("Unknown or expired resumption ticket, running a full handshake");
+            return Ok(None);
+        };
+
+        if self.common.can_resume(&ticket.state).await? {
+            Ok(Some(ticket.state))
+        } else {
+            self.common
+                .identities
+                .vault()
+                .secure_channel_vault
+                .delete_secret_buffer(ticket.state.secret)
+                .await?;
+            Ok(None)
+        }
+    }
+
+    /// Issue a resumption ticket at the end of the handshake, if the initiator requested it
+    async fn make_resumption_ticket(&mut self) -> Result<()> {
+        let Some(resumption_tickets) = self.resumption_tickets.clone() else {
+            return Ok(());
+        };
+        let Some(secret) = self
+            .get_handshake_keys()
+            .and_then(|keys| keys.resumption_secret)
+        else {
+            return Ok(());
+        };
+
+        let state = self.common.make_resumption_state(secret).await?;
+        if let Some(ticket) = resumption_tickets.issue(state, now()?).await? {
+            self.common.resumption_ticket = Some(ResumptionTicketMessage {
+                ticket: ticket.ticket,
+                expires_at: ticket.expires_at,
+            });
+        }
+        Ok(())
+    }
 }
 
 impl ResponderStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -108,6 +202,8 @@ impl ResponderStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        hybrid_key_exchange: bool,
+        resumption_tickets: Option<Arc<ResumptionTickets>>,
     ) -> Result<ResponderStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -121,6 +217,9 @@ impl ResponderStateMachine {
         Ok(ResponderStateMachine {
             common,
             handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            hybrid_key_exchange,
+            resumption_tickets,
+            issue_resumption_ticket: false,
         })
     }
 }
@@ -0,0 +1,111 @@
+use hmac::{Hmac, Mac};
+use ockam_core::compat::rand::random;
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Route;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Duration, in seconds, during which a cookie is accepted. A cookie created at the end of
+/// a period is still accepted during the next period.
+const COOKIE_PERIOD_SECONDS: u64 = 30;
+
+/// Stateless cookies sent by a secure channel listener to an initiator before allocating
+/// any state for its handshake.
+///
+/// A cookie is bound to the return route and to the ephemeral key of the first handshake
+/// message. The initiator proves it can receive messages on that return route
+/// by sending the first handshake message again, with the cookie as its payload.
+pub(crate) struct HandshakeCookies {
+    secret: [u8; 32],
+}
+
+impl HandshakeCookies {
+    /// Create cookies with a fresh random secret
+    pub(crate) fn new() -> Self {
+        Self { secret: random() }
+    }
+
+    /// Create a cookie for the given return route and ephemeral key, at a given time in seconds
+    pub(crate) fn create(&self, return_route: &Route, ephemeral_key: &[u8], now: u64) -> Vec<u8> {
+        self.mac(now / COOKIE_PERIOD_SECONDS, return_route, ephemeral_key)
+            .finalize()
+            .into_bytes()
+            .to_vec()
+    }
+
+    /// Check that a cookie was created for the given return route and ephemeral key,
+    /// during the current or the previous period
+    pub(crate) fn verify(
+        &self,
+        cookie: &[u8],
+        return_route: &Route,
+        ephemeral_key: &[u8],
+        now: u64,
+    ) -> bool {
+        let period = now / COOKIE_PERIOD_SECONDS;
+        [Some(period), period.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .any(|period| {
+                self.mac(period, return_route, ephemeral_key)
+                    .verify_slice(cookie)
+                    .is_ok()
+            })
+    }
+
+    /// Return the HMAC-SHA256 of the period, the ephemeral key and the return route
+    fn mac(&self, period: u64, return_route: &Route, ephemeral_key: &[u8]) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(&period.to_be_bytes());
+        mac.update(&(ephemeral_key.len() as u32).to_be_bytes());
+        mac.update(ephemeral_key);
+        for address in return_route.iter() {
+            let address = address.to_string();
+            mac.update(&(address.len() as u32).to_be_bytes());
+            mac.update(address.as_bytes());
+        }
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    #[test]
+    fn cookie_is_bound_to_the_route_and_the_key() {
+        let cookies = HandshakeCookies::new();
+        let return_route = route!["tcp_sender", "initiator"];
+        let cookie = cookies.create(&return_route, &[1; 32], 100);
+
+        assert!(cookies.verify(&cookie, &return_route, &[1; 32], 100));
+        assert!(!cookies.verify(&cookie[..16], &return_route, &[1; 32], 100));
+        assert!(!cookies.verify(&cookie, &route!["other", "initiator"], &[1; 32], 100));
+        assert!(!cookies.verify(&cookie, &return_route, &[2; 32], 100));
+        assert!(!HandshakeCookies::new().verify(&cookie, &return_route, &[1; 32], 100));
+    }
+
+    #[test]
+    fn cookie_expires() {
+        let cookies = HandshakeCookies::new();
+        let return_route = route!["initiator"];
+        let cookie = cookies.create(&return_route, &[1; 32], 100);
+
+        assert!(cookies.verify(
+            &cookie,
+            &return_route,
+            &[1; 32],
+            100 + COOKIE_PERIOD_SECONDS
+        ));
+        assert!(!cookies.verify(
+            &cookie,
+            &return_route,
+            &[1; 32],
+            100 + 2 * COOKIE_PERIOD_SECONDS
+        ));
+    }
+}
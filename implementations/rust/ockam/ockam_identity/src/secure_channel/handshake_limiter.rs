@@ -0,0 +1,171 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::Address;
+
+/// Limits on the handshakes accepted by a secure channel listener.
+///
+/// The source of a handshake is the first hop of the return route of its first message,
+/// which is usually the transport connection used by the initiator.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HandshakeLimits {
+    pub(crate) max_concurrent: Option<usize>,
+    pub(crate) max_concurrent_per_source: Option<usize>,
+    pub(crate) max_per_second: Option<u32>,
+    pub(crate) max_per_second_per_source: Option<u32>,
+}
+
+impl HandshakeLimits {
+    /// Return true if no limit is set
+    pub(crate) fn is_unlimited(&self) -> bool {
+        self.max_concurrent.is_none()
+            && self.max_concurrent_per_source.is_none()
+            && self.max_per_second.is_none()
+            && self.max_per_second_per_source.is_none()
+    }
+
+    /// Return true if the number of concurrent handshakes is limited
+    pub(crate) fn limits_concurrency(&self) -> bool {
+        self.max_concurrent.is_some() || self.max_concurrent_per_source.is_some()
+    }
+}
+
+/// Keep track of the handshakes in progress, and of the handshakes started during the current
+/// second, in order to reject the handshakes exceeding the [`HandshakeLimits`]
+pub(crate) struct HandshakeLimiter {
+    limits: HandshakeLimits,
+    state: Arc<Mutex<HandshakeLimiterState>>,
+}
+
+#[derive(Default)]
+struct HandshakeLimiterState {
+    concurrent: usize,
+    concurrent_per_source: BTreeMap<Address, usize>,
+    current_second: u64,
+    started: u32,
+    started_per_source: BTreeMap<Address, u32>,
+}
+
+impl HandshakeLimiter {
+    /// Create a new limiter
+    pub(crate) fn new(limits: HandshakeLimits) -> Self {
+        Self {
+            limits,
+            state: Default::default(),
+        }
+    }
+
+    /// Reserve a slot for a new handshake coming from a given source, at a given time in seconds.
+    /// Return `None` if the handshake exceeds one of the limits.
+    pub(crate) fn try_acquire(&self, source: &Address, now: u64) -> Option<HandshakePermit> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.current_second != now {
+            state.current_second = now;
+            state.started = 0;
+            state.started_per_source.clear();
+        }
+
+        let concurrent_for_source = state
+            .concurrent_per_source
+            .get(source)
+            .copied()
+            .unwrap_or_default();
+        let started_for_source = state
+            .started_per_source
+            .get(source)
+            .copied()
+            .unwrap_or_default();
+
+        if is_exceeded(self.limits.max_concurrent, state.concurrent)
+            || is_exceeded(self.limits.max_concurrent_per_source, concurrent_for_source)
+            || is_exceeded(self.limits.max_per_second, state.started)
+            || is_exceeded(self.limits.max_per_second_per_source, started_for_source)
+        {
+            return None;
+        }
+
+        state.concurrent += 1;
+        *state
+            .concurrent_per_source
+            .entry(source.clone())
+            .or_default() += 1;
+        state.started += 1;
+        *state.started_per_source.entry(source.clone()).or_default() += 1;
+
+        Some(HandshakePermit {
+            state: self.state.clone(),
+            source: source.clone(),
+        })
+    }
+}
+
+fn is_exceeded<T: PartialOrd>(limit: Option<T>, current: T) -> bool {
+    limit.map(|limit| current >= limit).unwrap_or(false)
+}
+
+/// A slot reserved for a handshake in progress, released when dropped
+pub(crate) struct HandshakePermit {
+    state: Arc<Mutex<HandshakeLimiterState>>,
+    source: Address,
+}
+
+impl Drop for HandshakePermit {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.concurrent = state.concurrent.saturating_sub(1);
+        if let Some(concurrent) = state.concurrent_per_source.get_mut(&self.source) {
+            *concurrent = concurrent.saturating_sub(1);
+            if *concurrent == 0 {
+                state.concurrent_per_source.remove(&self.source);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_handshakes_are_limited() {
+        let limiter = HandshakeLimiter::new(HandshakeLimits {
+            max_concurrent: Some(2),
+            max_concurrent_per_source: Some(1),
+            ..Default::default()
+        });
+        let source1 = Address::from_string("source1");
+        let source2 = Address::from_string("source2");
+        let source3 = Address::from_string("source3");
+
+        let permit1 = limiter.try_acquire(&source1, 0).unwrap();
+        assert!(limiter.try_acquire(&source1, 0).is_none());
+
+        let _permit2 = limiter.try_acquire(&source2, 0).unwrap();
+        assert!(limiter.try_acquire(&source3, 0).is_none());
+
+        // a completed handshake releases its slot
+        drop(permit1);
+        assert!(limiter.try_acquire(&source1, 0).is_some());
+    }
+
+    #[test]
+    fn handshakes_per_second_are_limited() {
+        let limiter = HandshakeLimiter::new(HandshakeLimits {
+            max_per_second: Some(3),
+            max_per_second_per_source: Some(2),
+            ..Default::default()
+        });
+        let source1 = Address::from_string("source1");
+        let source2 = Address::from_string("source2");
+
+        assert!(limiter.try_acquire(&source1, 10).is_some());
+        assert!(limiter.try_acquire(&source1, 10).is_some());
+        assert!(limiter.try_acquire(&source1, 10).is_none());
+        assert!(limiter.try_acquire(&source2, 10).is_some());
+        assert!(limiter.try_acquire(&source2, 10).is_none());
+
+        // the counts are reset every second
+        assert!(limiter.try_acquire(&source1, 11).is_some());
+        assert!(limiter.try_acquire(&source2, 11).is_some());
+    }
+}
@@ -1,15 +1,22 @@
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
-use ockam_core::{Address, Any, Result, Routed, Worker};
+use ockam_core::{Address, Any, NeutralMessage, Result, Routed, Worker};
 use ockam_node::Context;
+use ockam_vault::X25519_PUBLIC_KEY_LENGTH;
+use tracing::debug;
 
 use crate::models::Identifier;
 use crate::secure_channel::addresses::Addresses;
 use crate::secure_channel::encryptor_worker::RemoteRoute;
+use crate::secure_channel::handshake::handshake_state_machine::Message1Payload;
+use crate::secure_channel::handshake_cookie::HandshakeCookies;
+use crate::secure_channel::handshake_limiter::{HandshakeLimiter, HandshakePermit};
 use crate::secure_channel::handshake_worker::HandshakeWorker;
 use crate::secure_channel::options::SecureChannelListenerOptions;
 use crate::secure_channel::role::Role;
+use crate::secure_channel::{HandshakeResumption, ResumptionTickets};
 use crate::secure_channels::secure_channels::SecureChannels;
+use crate::utils::now;
 use crate::SecureChannelRepository;
 
 pub(crate) struct SecureChannelListenerWorker {
@@ -17,6 +24,9 @@ pub(crate) struct SecureChannelListenerWorker {
     identifier: Identifier,
     options: SecureChannelListenerOptions,
     secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
+    handshake_limiter: Option<HandshakeLimiter>,
+    handshake_cookies: Option<HandshakeCookies>,
+    resumption_tickets: Option<Arc<ResumptionTickets>>,
 }
 
 impl SecureChannelListenerWorker {
@@ -31,11 +41,34 @@ impl SecureChannelListenerWorker {
             None
         };
 
+        let handshake_limiter = if options.handshake_limits.is_unlimited() {
+            None
+        } else {
+            Some(HandshakeLimiter::new(options.handshake_limits.clone()))
+        };
+
+        let handshake_cookies = if options.handshake_cookies {
+            Some(HandshakeCookies::new())
+        } else {
+            None
+        };
+
+        let resumption_tickets = match options.resumption_ticket_lifetime {
+            Some(lifetime) if !options.key_exchange_only => Some(Arc::new(ResumptionTickets::new(
+                secure_channels.identities.vault().secure_channel_vault,
+                lifetime,
+            ))),
+            _ => None,
+        };
+
         Self {
             secure_channels,
             identifier,
             options,
             secure_channel_repository,
+            handshake_limiter,
+            handshake_cookies,
+            resumption_tickets,
         }
     }
 
@@ -55,6 +88,57 @@ impl SecureChannelListenerWorker {
 
         Ok(())
     }
+
+    /// Return true if the first handshake message contains a valid cookie.
+    /// Otherwise send a new cookie to the initiator so that it can send its message again.
+    async fn check_cookie(
+        &self,
+        ctx: &Context,
+        cookies: &HandshakeCookies,
+        message: &Routed<Any>,
+        now: u64,
+    ) -> Result<bool> {
+        let payload = message.payload();
+        let (ephemeral_key, message1_payload) =
+            payload.split_at(payload.len().min(X25519_PUBLIC_KEY_LENGTH));
+        let return_route = message.return_route();
+
+        if let Some(cookie) = Message1Payload::decode(message1_payload).cookie {
+            if cookies.verify(&cookie, return_route, ephemeral_key, now) {
+                return Ok(true);
+            }
+        }
+
+        debug!("Sending a handshake cookie to {return_route}");
+        self.secure_channels
+            .secure_channel_registry
+            .record_handshake_retry();
+        let cookie = cookies.create(return_route, ephemeral_key, now);
+        ctx.send(return_route.clone(), NeutralMessage::from(cookie))
+            .await?;
+        Ok(false)
+    }
+
+    /// Reserve a slot for a new handshake, if the listener limits allow it.
+    /// The source of the handshake is the first hop of the message return route.
+    fn acquire_handshake_permit(
+        &self,
+        handshake_limiter: &HandshakeLimiter,
+        message: &Routed<Any>,
+        now: u64,
+    ) -> Result<Option<HandshakePermit>> {
+        let source = message.return_route().next()?;
+        match handshake_limiter.try_acquire(source, now) {
+            Some(permit) => Ok(Some(permit)),
+            None => {
+                debug!("Rejecting a secure channel handshake from {source}: too many handshakes");
+                self.secure_channels
+                    .secure_channel_registry
+                    .record_handshake_rejected();
+                Ok(None)
+            }
+        }
+    }
 }
 
 #[ockam_core::worker]
@@ -67,6 +151,27 @@ impl Worker for SecureChannelListenerWorker {
         ctx: &mut Self::Context,
         message: Routed<Self::Message>,
     ) -> Result<()> {
+        let now = *now()?;
+
+        if let Some(cookies) = &self.handshake_cookies {
+            if !self.check_cookie(ctx, cookies, &message, now).await? {
+                return Ok(());
+            }
+        }
+
+        let handshake_permit = match &self.handshake_limiter {
+            Some(handshake_limiter) => {
+                match self.acquire_handshake_permit(handshake_limiter, &message, now)? {
+                    Some(handshake_permit) => Some(handshake_permit),
+                    None => return Ok(()),
+                }
+            }
+            None => None,
+        };
+        self.secure_channels
+            .secure_channel_registry
+            .record_handshake_started();
+
         let addresses = Addresses::generate(Role::Responder);
         let flow_control_id = self.options.setup_flow_control_for_channel(
             ctx.flow_controls(),
@@ -108,11 +213,16 @@ impl Worker for SecureChannelListenerWorker {
             credential_retriever,
             self.options.authority.clone(),
             None,
-            None,
+            self.options.handshake_timeout(),
             Role::Responder,
             self.options.key_exchange_only,
+            self.options.hybrid_key_exchange,
+            self.resumption_tickets
+                .clone()
+                .map(HandshakeResumption::Responder),
             self.secure_channel_repository.clone(),
             RemoteRoute::create(),
+            handshake_permit,
         )
         .await?;
 
@@ -1,4 +1,4 @@
-use crate::models::{ChangeHistory, CredentialAndPurposeKey};
+use crate::models::{ChangeHistory, CredentialAndPurposeKey, TimestampInSeconds};
 use minicbor::{CborLen, Decode, Encode};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{CowBytes, Route};
@@ -23,6 +23,8 @@ pub enum SecureChannelMessage<'a> {
     #[n(1)] RefreshCredentials(#[n(0)] RefreshCredentialsMessage),
     /// Close the channel.
     #[n(2)] Close,
+    /// Ticket to resume the channel later. Only sent to initiators requesting it.
+    #[n(3)] ResumptionTicket(#[n(0)] ResumptionTicketMessage),
 }
 
 /// Secure Channel Message format.
@@ -47,3 +49,13 @@ pub struct RefreshCredentialsMessage {
     /// to verify those Credentials
     #[n(1)] pub credentials: Vec<CredentialAndPurposeKey>,
 }
+
+/// Resumption ticket sent by a secure channel listener to the initiator.
+#[derive(Debug, Encode, Decode, CborLen, Clone)]
+#[rustfmt::skip]
+pub struct ResumptionTicketMessage {
+    /// Opaque ticket to send in the first handshake message to resume the channel.
+    #[cbor(n(0), with = "minicbor::bytes")] pub ticket: Vec<u8>,
+    /// Expiration of the ticket.
+    #[n(1)] pub expires_at: TimestampInSeconds,
+}
@@ -6,6 +6,8 @@ mod decryptor;
 mod encryptor;
 mod encryptor_worker;
 pub(crate) mod handshake;
+mod handshake_cookie;
+mod handshake_limiter;
 mod key_tracker;
 mod listener;
 mod message;
@@ -13,6 +15,7 @@ mod nonce;
 mod nonce_tracker;
 mod options;
 mod registry;
+mod resumption;
 mod role;
 
 /// List of trust policies to setup ABAC controls
@@ -24,11 +27,13 @@ pub use api::*;
 pub(crate) use decryptor::*;
 pub(crate) use encryptor_worker::*;
 pub(crate) use handshake::*;
+pub(crate) use handshake_limiter::*;
 pub(crate) use listener::*;
 pub use message::*;
 pub use nonce::*;
 pub use options::*;
 pub use registry::*;
+pub(crate) use resumption::*;
 pub(crate) use role::*;
 pub use trust_policy::*;
 
@@ -1,11 +1,13 @@
 use cfg_if::cfg_if;
 
+use ockam_core::compat::string::String;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
 use ockam_core::{Address, OutgoingAccessControl, Result};
 
 use crate::models::CredentialAndPurposeKey;
+use crate::secure_channel::handshake_limiter::HandshakeLimits;
 use crate::secure_channel::Addresses;
 use crate::{
     CredentialRetrieverCreator, Identifier, IdentityError, MemoryCredentialRetrieverCreator,
@@ -37,6 +39,10 @@ pub struct SecureChannelOptions {
     pub(crate) key_exchange_only: bool,
     // Secure Channel will be persisted (currently only supported for key_exchange_only = true)
     pub(crate) is_persistent: bool,
+    // Request a hybrid X25519 + ML-KEM-768 key exchange
+    pub(crate) hybrid_key_exchange: bool,
+    // Name under which the resumption ticket sent by the listener is stored
+    pub(crate) resumption_name: Option<String>,
 }
 
 impl fmt::Debug for SecureChannelOptions {
@@ -57,6 +63,8 @@ impl SecureChannelOptions {
             timeout: DEFAULT_TIMEOUT,
             key_exchange_only: false,
             is_persistent: false,
+            hybrid_key_exchange: false,
+            resumption_name: None,
         }
     }
 
@@ -119,6 +127,25 @@ impl SecureChannelOptions {
         self.is_persistent = true;
         Ok(self)
     }
+
+    /// Request a hybrid X25519 + ML-KEM-768 key exchange, in order to protect the secure channel
+    /// against an attacker recording the traffic until a quantum computer can break X25519.
+    /// The key exchange falls back to X25519 only if the listener doesn't support it.
+    /// NOTE: ML-KEM is only supported by the software vault with the `ml-kem` feature
+    pub fn with_hybrid_key_exchange(mut self) -> Self {
+        self.hybrid_key_exchange = true;
+        self
+    }
+
+    /// Request a resumption ticket from the listener and store it under the given name.
+    /// The next secure channel created with the same name and the same identity uses that
+    /// ticket to skip the exchange of identities and credentials, if the listener still accepts it.
+    /// Otherwise a full handshake is performed.
+    /// NOTE: This option is ignored for key exchange only secure channels
+    pub fn with_session_resumption(mut self, name: impl Into<String>) -> Self {
+        self.resumption_name = Some(name.into());
+        self
+    }
 }
 
 impl SecureChannelOptions {
@@ -185,6 +212,16 @@ pub struct SecureChannelListenerOptions {
     pub(crate) key_exchange_only: bool,
     // Secure Channel will be persisted (currently only supported for key_exchange_only = true)
     pub(crate) is_persistent: bool,
+    // Limits on the handshakes accepted by the listener
+    pub(crate) handshake_limits: HandshakeLimits,
+    // Timeout for the handshakes started by the listener
+    pub(crate) handshake_timeout: Option<Duration>,
+    // Require initiators to send back a cookie before starting a handshake
+    pub(crate) handshake_cookies: bool,
+    // Accept a hybrid X25519 + ML-KEM-768 key exchange
+    pub(crate) hybrid_key_exchange: bool,
+    // Lifetime of the resumption tickets issued by the listener, if enabled
+    pub(crate) resumption_ticket_lifetime: Option<Duration>,
 }
 
 impl fmt::Debug for SecureChannelListenerOptions {
@@ -207,6 +244,11 @@ impl SecureChannelListenerOptions {
             credential_retriever_creator: None,
             key_exchange_only: false,
             is_persistent: false,
+            handshake_limits: HandshakeLimits::default(),
+            handshake_timeout: None,
+            handshake_cookies: false,
+            hybrid_key_exchange: false,
+            resumption_ticket_lifetime: None,
         }
     }
 
@@ -272,9 +314,78 @@ impl SecureChannelListenerOptions {
         self.is_persistent = true;
         Ok(self)
     }
+
+    /// Accept a hybrid X25519 + ML-KEM-768 key exchange from the initiators requesting it.
+    /// Other initiators still use an X25519 only key exchange.
+    /// NOTE: ML-KEM is only supported by the software vault with the `ml-kem` feature
+    pub fn with_hybrid_key_exchange(mut self) -> Self {
+        self.hybrid_key_exchange = true;
+        self
+    }
+
+    /// Limit the number of handshakes in progress at the same time.
+    /// Additional handshakes are dropped until a slot is released.
+    pub fn with_max_concurrent_handshakes(mut self, max: usize) -> Self {
+        self.handshake_limits.max_concurrent = Some(max);
+        self
+    }
+
+    /// Limit the number of handshakes in progress at the same time for a given source,
+    /// i.e. for the first hop of the route used by the initiator
+    pub fn with_max_concurrent_handshakes_per_source(mut self, max: usize) -> Self {
+        self.handshake_limits.max_concurrent_per_source = Some(max);
+        self
+    }
+
+    /// Limit the number of handshakes started every second
+    pub fn with_max_handshakes_per_second(mut self, max: u32) -> Self {
+        self.handshake_limits.max_per_second = Some(max);
+        self
+    }
+
+    /// Limit the number of handshakes started every second for a given source,
+    /// i.e. for the first hop of the route used by the initiator
+    pub fn with_max_handshakes_per_second_per_source(mut self, max: u32) -> Self {
+        self.handshake_limits.max_per_second_per_source = Some(max);
+        self
+    }
+
+    /// Stop the handshakes which are not completed after a given duration.
+    /// When the number of concurrent handshakes is limited the default is [`DEFAULT_TIMEOUT`],
+    /// otherwise handshakes don't time out.
+    pub fn with_handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = Some(timeout);
+        self
+    }
+
+    /// Answer the first handshake message with a stateless cookie, and start the handshake
+    /// only when the initiator sends that message again with the cookie.
+    /// This makes sure that the initiator can receive messages before allocating any state.
+    pub fn with_handshake_cookies(mut self) -> Self {
+        self.handshake_cookies = true;
+        self
+    }
+
+    /// Issue single-use resumption tickets to the initiators requesting them.
+    /// A ticket expires after the given lifetime, or earlier if the credentials presented
+    /// during the handshake expire. It is rejected if any identity has rotated its keys since.
+    /// NOTE: This option is ignored for key exchange only secure channels
+    pub fn with_resumption_tickets(mut self, lifetime: Duration) -> Self {
+        self.resumption_ticket_lifetime = Some(lifetime);
+        self
+    }
 }
 
 impl SecureChannelListenerOptions {
+    /// Timeout for the handshakes started by the listener
+    pub(crate) fn handshake_timeout(&self) -> Option<Duration> {
+        self.handshake_timeout.or_else(|| {
+            self.handshake_limits
+                .limits_concurrency()
+                .then_some(DEFAULT_TIMEOUT)
+        })
+    }
+
     pub(crate) fn setup_flow_control_for_listener(
         &self,
         flow_controls: &FlowControls,
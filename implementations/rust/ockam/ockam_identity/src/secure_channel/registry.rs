@@ -1,3 +1,4 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::vec::Vec;
@@ -90,6 +91,16 @@ impl SecureChannelRegistryEntry {
 pub struct SecureChannelRegistry {
     // Encryptor address is used as a key
     registry: Arc<RwLock<BTreeMap<Address, SecureChannelRegistryEntry>>>,
+    // Number of handshakes which did not complete, either as an initiator or as a responder
+    handshake_failures: Arc<AtomicUsize>,
+    // Number of handshakes started by secure channel listeners
+    handshakes_started: Arc<AtomicUsize>,
+    // Number of handshakes refused by secure channel listeners because of their limits
+    handshakes_rejected: Arc<AtomicUsize>,
+    // Number of cookies sent by secure channel listeners before starting a handshake
+    handshake_retries: Arc<AtomicUsize>,
+    // Number of handshakes completed with a resumption ticket
+    handshakes_resumed: Arc<AtomicUsize>,
 }
 
 impl SecureChannelRegistry {
@@ -97,10 +108,63 @@ impl SecureChannelRegistry {
     pub fn new() -> Self {
         Self {
             registry: Default::default(),
+            handshake_failures: Default::default(),
+            handshakes_started: Default::default(),
+            handshakes_rejected: Default::default(),
+            handshake_retries: Default::default(),
+            handshakes_resumed: Default::default(),
         }
     }
 }
 
+impl SecureChannelRegistry {
+    /// Number of secure channel handshakes which failed since the registry was created
+    pub fn handshake_failures(&self) -> usize {
+        self.handshake_failures.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_handshake_failure(&self) {
+        self.handshake_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of handshakes started by secure channel listeners since the registry was created
+    pub fn handshakes_started(&self) -> usize {
+        self.handshakes_started.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_handshake_started(&self) {
+        self.handshakes_started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of handshakes rejected by secure channel listeners because of their limits
+    pub fn handshakes_rejected(&self) -> usize {
+        self.handshakes_rejected.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_handshake_rejected(&self) {
+        self.handshakes_rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of times secure channel listeners asked an initiator to retry its handshake
+    /// with a cookie
+    pub fn handshake_retries(&self) -> usize {
+        self.handshake_retries.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_handshake_retry(&self) {
+        self.handshake_retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of secure channels resumed with a resumption ticket instead of a full handshake
+    pub fn handshakes_resumed(&self) -> usize {
+        self.handshakes_resumed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_handshake_resumed(&self) {
+        self.handshakes_resumed.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
 impl SecureChannelRegistry {
     /// Register new SecureChannel in that registry
     pub fn register_channel(&self, info: SecureChannelRegistryEntry) -> Result<()> {
@@ -0,0 +1,312 @@
+use core::time::Duration;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::rand::random;
+use ockam_core::compat::string::String;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use ockam_vault::{SecretBufferHandle, VaultForSecureChannels};
+
+use crate::models::{ChangeHistory, Identifier, TimestampInSeconds};
+
+/// Maximum number of resumption tickets kept by a secure channel listener
+const MAX_RESUMPTION_TICKETS: usize = 10_000;
+
+/// Secret and identities bound to a resumption ticket.
+///
+/// The secret is derived by both parties at the end of a handshake and is used to resume
+/// the secure channel without exchanging identities and credentials again.
+/// The change histories are used to reject a ticket once one of the identities has rotated its keys.
+#[derive(Debug, Clone)]
+pub(crate) struct ResumptionState {
+    pub(crate) secret: SecretBufferHandle,
+    pub(crate) my_identifier: Identifier,
+    pub(crate) my_change_history: ChangeHistory,
+    pub(crate) their_identifier: Identifier,
+    pub(crate) their_change_history: ChangeHistory,
+    /// Expiration of the credentials presented during the handshake, if any.
+    /// A resumed secure channel can not outlive those credentials
+    pub(crate) credentials_expiration: Option<TimestampInSeconds>,
+}
+
+/// An opaque resumption ticket, with its expiration and its resumption state
+#[derive(Debug, Clone)]
+pub(crate) struct ResumptionTicket {
+    pub(crate) ticket: Vec<u8>,
+    pub(crate) expires_at: TimestampInSeconds,
+    pub(crate) state: ResumptionState,
+}
+
+/// Resumption tickets issued by a secure channel listener.
+///
+/// A ticket is a random identifier sent to the initiator over the secure channel, once the
+/// handshake is completed. A ticket can only be redeemed once, before its expiration.
+pub(crate) struct ResumptionTickets {
+    vault: Arc<dyn VaultForSecureChannels>,
+    lifetime: Duration,
+    tickets: Mutex<BTreeMap<Vec<u8>, ResumptionTicket>>,
+}
+
+impl ResumptionTickets {
+    /// Create an empty set of tickets, issued with a given lifetime
+    pub(crate) fn new(vault: Arc<dyn VaultForSecureChannels>, lifetime: Duration) -> Self {
+        Self {
+            vault,
+            lifetime,
+            tickets: Default::default(),
+        }
+    }
+
+    /// Issue a new ticket for a resumption state, at a given time.
+    /// The ticket expires after the tickets lifetime, or when the credentials expire.
+    /// Return `None` if too many tickets are currently valid.
+    pub(crate) async fn issue(
+        &self,
+        state: ResumptionState,
+        now: TimestampInSeconds,
+    ) -> Result<Option<ResumptionTicket>> {
+        let expired = {
+            let mut tickets = self.tickets.lock().unwrap();
+            let expired_tickets: Vec<Vec<u8>> = tickets
+                .iter()
+                .filter(|(_, ticket)| ticket.expires_at <= now)
+                .map(|(ticket, _)| ticket.clone())
+                .collect();
+            expired_tickets
+                .iter()
+                .filter_map(|ticket| tickets.remove(ticket))
+                .collect::<Vec<_>>()
+        };
+        for ticket in expired {
+            self.vault.delete_secret_buffer(ticket.state.secret).await?;
+        }
+
+        let mut expires_at = TimestampInSeconds(now.0 + self.lifetime.as_secs());
+        if let Some(credentials_expiration) = state.credentials_expiration {
+            expires_at = expires_at.min(credentials_expiration);
+        }
+        if expires_at <= now {
+            self.vault.delete_secret_buffer(state.secret).await?;
+            return Ok(None);
+        }
+
+        let ticket = {
+            let mut tickets = self.tickets.lock().unwrap();
+            if tickets.len() >= MAX_RESUMPTION_TICKETS {
+                None
+            } else {
+                let ticket = ResumptionTicket {
+                    ticket: random::<[u8; 16]>().to_vec(),
+                    expires_at,
+                    state: state.clone(),
+                };
+                tickets.insert(ticket.ticket.clone(), ticket.clone());
+                Some(ticket)
+            }
+        };
+
+        if ticket.is_none() {
+            self.vault.delete_secret_buffer(state.secret).await?;
+        }
+        Ok(ticket)
+    }
+
+    /// Remove a ticket and return it if it has not expired
+    pub(crate) async fn redeem(
+        &self,
+        ticket: &[u8],
+        now: TimestampInSeconds,
+    ) -> Result<Option<ResumptionTicket>> {
+        let ticket = self.tickets.lock().unwrap().remove(ticket);
+        match ticket {
+            Some(ticket) if ticket.expires_at > now => Ok(Some(ticket)),
+            Some(ticket) => {
+                self.vault.delete_secret_buffer(ticket.state.secret).await?;
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Number of tickets which have not been redeemed yet
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.tickets.lock().unwrap().len()
+    }
+}
+
+/// Resumption tickets received by initiators.
+///
+/// A ticket is stored under the identifier of the initiator and a name chosen when creating
+/// the secure channel, in order to resume a secure channel with the same listener, even if
+/// the route to that listener has changed.
+pub(crate) struct ResumptionTicketCache {
+    vault: Arc<dyn VaultForSecureChannels>,
+    tickets: Mutex<BTreeMap<(Identifier, String), ResumptionTicket>>,
+}
+
+impl ResumptionTicketCache {
+    /// Create an empty cache
+    pub(crate) fn new(vault: Arc<dyn VaultForSecureChannels>) -> Self {
+        Self {
+            vault,
+            tickets: Default::default(),
+        }
+    }
+
+    /// Store a ticket, replacing any previous ticket stored under the same name
+    pub(crate) async fn put(&self, name: String, ticket: ResumptionTicket) -> Result<()> {
+        let key = (ticket.state.my_identifier.clone(), name);
+        let previous = self.tickets.lock().unwrap().insert(key, ticket);
+        if let Some(previous) = previous {
+            self.vault
+                .delete_secret_buffer(previous.state.secret)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Remove the ticket stored under a name and return it if it has not expired
+    pub(crate) async fn take(
+        &self,
+        my_identifier: &Identifier,
+        name: &str,
+        now: TimestampInSeconds,
+    ) -> Result<Option<ResumptionTicket>> {
+        let ticket = self
+            .tickets
+            .lock()
+            .unwrap()
+            .remove(&(my_identifier.clone(), name.into()));
+        match ticket {
+            Some(ticket) if ticket.expires_at > now => Ok(Some(ticket)),
+            Some(ticket) => {
+                self.vault.delete_secret_buffer(ticket.state.secret).await?;
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Resumption parameters of a handshake
+pub(crate) enum HandshakeResumption {
+    /// An initiator requests a resumption ticket, stored under a name once received,
+    /// and uses the ticket previously stored under that name, if any
+    Initiator {
+        cache: Arc<ResumptionTicketCache>,
+        name: String,
+        ticket: Option<Box<ResumptionTicket>>,
+    },
+    /// A responder resumes the secure channels with the tickets it issued, and issues new tickets
+    Responder(Arc<ResumptionTickets>),
+}
+
+/// Resumption state of an initiator, waiting for a ticket sent by the responder
+/// over the secure channel
+pub(crate) struct PendingResumption {
+    pub(crate) cache: Arc<ResumptionTicketCache>,
+    pub(crate) name: String,
+    pub(crate) state: ResumptionState,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identities::identities;
+    use ockam_vault::SoftwareVaultForSecureChannels;
+
+    #[tokio::test]
+    async fn tickets_are_redeemed_once() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let tickets = ResumptionTickets::new(vault.clone(), Duration::from_secs(10));
+        let state = create_state(vault.clone(), None).await?;
+
+        let ticket = tickets
+            .issue(state, TimestampInSeconds(100))
+            .await?
+            .unwrap();
+        assert_eq!(ticket.expires_at, TimestampInSeconds(110));
+
+        assert!(tickets
+            .redeem(&ticket.ticket, TimestampInSeconds(101))
+            .await?
+            .is_some());
+        assert!(tickets
+            .redeem(&ticket.ticket, TimestampInSeconds(101))
+            .await?
+            .is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tickets_expire() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let tickets = ResumptionTickets::new(vault.clone(), Duration::from_secs(10));
+
+        // the ticket expires with the credentials
+        let state = create_state(vault.clone(), Some(TimestampInSeconds(105))).await?;
+        let ticket = tickets
+            .issue(state, TimestampInSeconds(100))
+            .await?
+            .unwrap();
+        assert_eq!(ticket.expires_at, TimestampInSeconds(105));
+        assert!(tickets
+            .redeem(&ticket.ticket, TimestampInSeconds(105))
+            .await?
+            .is_none());
+
+        // expired tickets are removed when a new ticket is issued
+        let state = create_state(vault.clone(), None).await?;
+        tickets
+            .issue(state, TimestampInSeconds(100))
+            .await?
+            .unwrap();
+        let state = create_state(vault.clone(), None).await?;
+        tickets
+            .issue(state, TimestampInSeconds(200))
+            .await?
+            .unwrap();
+        assert_eq!(tickets.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cached_tickets_are_taken_once() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let cache = ResumptionTicketCache::new(vault.clone());
+        let state = create_state(vault.clone(), None).await?;
+        let my_identifier = state.my_identifier.clone();
+        let ticket = ResumptionTicket {
+            ticket: vec![1, 2, 3],
+            expires_at: TimestampInSeconds(110),
+            state,
+        };
+        cache.put("listener".into(), ticket).await?;
+
+        let now = TimestampInSeconds(100);
+        assert!(cache.take(&my_identifier, "other", now).await?.is_none());
+        let ticket = cache.take(&my_identifier, "listener", now).await?.unwrap();
+        assert_eq!(ticket.ticket, vec![1, 2, 3]);
+        assert!(cache.take(&my_identifier, "listener", now).await?.is_none());
+        Ok(())
+    }
+
+    async fn create_state(
+        vault: Arc<SoftwareVaultForSecureChannels>,
+        credentials_expiration: Option<TimestampInSeconds>,
+    ) -> Result<ResumptionState> {
+        let identities = identities().await?;
+        let me = identities.identities_creation().create_identity().await?;
+        let them = identities.identities_creation().create_identity().await?;
+        Ok(ResumptionState {
+            secret: vault.import_secret_buffer(vec![1; 32]).await?,
+            my_change_history: identities.get_change_history(&me).await?,
+            my_identifier: me,
+            their_change_history: identities.get_change_history(&them).await?,
+            their_identifier: them,
+            credentials_expiration,
+        })
+    }
+}
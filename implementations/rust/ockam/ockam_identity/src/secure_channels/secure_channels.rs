@@ -1,4 +1,5 @@
 use core::sync::atomic::AtomicBool;
+use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControls;
 use ockam_core::Result;
@@ -10,10 +11,11 @@ use crate::identities::Identities;
 use crate::models::Identifier;
 use crate::secure_channel::handshake_worker::HandshakeWorker;
 use crate::secure_channel::{
-    Addresses, DecryptorHandler, RemoteRoute, Role, SecureChannelListenerOptions,
-    SecureChannelListenerWorker, SecureChannelOptions, SecureChannelRegistry,
-    SecureChannelSharedState,
+    Addresses, DecryptorHandler, HandshakeResumption, RemoteRoute, ResumptionTicketCache, Role,
+    SecureChannelListenerOptions, SecureChannelListenerWorker, SecureChannelOptions,
+    SecureChannelRegistry, SecureChannelSharedState,
 };
+use crate::utils::now;
 #[cfg(feature = "storage")]
 use crate::SecureChannelsBuilder;
 use crate::{
@@ -27,6 +29,8 @@ pub struct SecureChannels {
     pub(crate) identities: Arc<Identities>,
     pub(crate) secure_channel_registry: SecureChannelRegistry,
     pub(crate) secure_channel_repository: Arc<dyn SecureChannelRepository>,
+    // Resumption tickets received as an initiator
+    pub(crate) resumption_tickets: Arc<ResumptionTicketCache>,
 }
 
 impl SecureChannels {
@@ -36,10 +40,14 @@ impl SecureChannels {
         secure_channel_registry: SecureChannelRegistry,
         secure_channel_repository: Arc<dyn SecureChannelRepository>,
     ) -> Self {
+        let resumption_tickets = Arc::new(ResumptionTicketCache::new(
+            identities.vault().secure_channel_vault,
+        ));
         Self {
             identities,
             secure_channel_registry,
             secure_channel_repository,
+            resumption_tickets,
         }
     }
 
@@ -157,6 +165,19 @@ impl SecureChannels {
             None
         };
 
+        let resumption = match &options.resumption_name {
+            Some(name) if !options.key_exchange_only => Some(HandshakeResumption::Initiator {
+                cache: self.resumption_tickets.clone(),
+                name: name.clone(),
+                ticket: self
+                    .resumption_tickets
+                    .take(identifier, name, now()?)
+                    .await?
+                    .map(Box::new),
+            }),
+            _ => None,
+        };
+
         let encryptor_remote_route = RemoteRoute::create();
         let Some(their_identifier) = HandshakeWorker::create(
             ctx,
@@ -172,8 +193,11 @@ impl SecureChannels {
             Some(options.timeout),
             Role::Initiator,
             options.key_exchange_only,
+            options.hybrid_key_exchange,
+            resumption,
             secure_channel_repository,
             encryptor_remote_route.clone(),
+            None,
         )
         .await?
         else {
@@ -241,6 +265,7 @@ impl SecureChannels {
             self.vault().secure_channel_vault.clone(),
             their_identifier.clone(),
             shared_state.clone(),
+            None,
         );
 
         let decryptor_worker = HandshakeWorker::new(
@@ -3,16 +3,16 @@ use std::sync::atomic::{AtomicU8, Ordering};
 
 use ockam_core::compat::sync::Arc;
 use ockam_core::{
-    route, Address, AllowAll, Any, DenyAll, Mailboxes, Result, Routed, SecureChannelLocalInfo,
-    Worker, SECURE_CHANNEL_IDENTIFIER,
+    route, Address, AllowAll, Any, DenyAll, Mailboxes, NeutralMessage, Result, Routed,
+    SecureChannelLocalInfo, Worker, SECURE_CHANNEL_IDENTIFIER,
 };
 use ockam_identity::models::{CredentialSchemaIdentifier, Identifier};
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
     DecryptionResponse, EncryptionRequest, EncryptionResponse, IdentityAccessControlBuilder,
-    SecureChannelListenerOptions, SecureChannelOptions, SecureChannels, TrustEveryonePolicy,
-    TrustIdentifierPolicy, Vault,
+    SecureChannel, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
+    TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
 };
 use ockam_node::{Context, MessageReceiveOptions, WorkerBuilder};
 use ockam_vault::{
@@ -271,6 +271,49 @@ async fn test_channel_rejected_trust_policy(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_rejected_trust_policy_is_a_handshake_failure(
+    ctx: &mut Context,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let alice_broken_trust_policy = TrustIdentifierPolicy::new(
+        Identifier::try_from("Iabababababababababababababababababababababababababababababababab")
+            .unwrap(),
+    );
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new().with_trust_policy(alice_broken_trust_policy),
+        )
+        .await?;
+
+    secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_timeout(Duration::from_millis(500)),
+        )
+        .await?;
+
+    // wait for the listener to process the last handshake message
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    let registry = secure_channels.secure_channel_registry();
+    assert_eq!(registry.handshakes_started(), 1);
+    assert_eq!(registry.handshake_failures(), 1);
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_channel_send_multiple_messages_both_directions(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -1133,3 +1176,285 @@ async fn address_metadata__encryptor__should_be_terminal(ctx: &mut Context) -> R
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn test_channel_with_handshake_cookies(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new().with_handshake_cookies(),
+        )
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    child_ctx
+        .send(
+            route![alice_channel, child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    assert_eq!("Hello, Bob!", msg.into_body()?);
+
+    let registry = secure_channels.secure_channel_registry();
+    assert_eq!(registry.handshake_retries(), 1);
+    assert_eq!(registry.handshakes_started(), 1);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_hybrid_key_exchange(ctx: &mut Context) -> Result<()> {
+    check_hybrid_key_exchange(
+        ctx,
+        SecureChannelListenerOptions::new()
+            .with_hybrid_key_exchange()
+            .with_handshake_cookies(),
+    )
+    .await
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_hybrid_key_exchange_and_a_classic_listener(
+    ctx: &mut Context,
+) -> Result<()> {
+    check_hybrid_key_exchange(ctx, SecureChannelListenerOptions::new()).await
+}
+
+/// Check that an initiator requesting a hybrid key exchange can exchange messages
+/// with a listener created with the given options
+async fn check_hybrid_key_exchange(
+    ctx: &mut Context,
+    listener_options: SecureChannelListenerOptions,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(ctx, &bob, "bob_listener", listener_options)
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_hybrid_key_exchange(),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    child_ctx
+        .send(
+            route![alice_channel, child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    assert_eq!("Hello, Bob!", msg.into_body()?);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_resumption(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let registry = secure_channels.secure_channel_registry();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new()
+                .with_resumption_tickets(Duration::from_secs(60))
+                .with_hybrid_key_exchange(),
+        )
+        .await?;
+    let mut child_ctx = create_child_context(ctx).await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    // the first secure channel runs a full handshake, the next ones are resumed
+    for resumed in 0..3 {
+        let alice_channel = secure_channels
+            .create_secure_channel(
+                ctx,
+                &alice,
+                route!["bob_listener"],
+                SecureChannelOptions::new()
+                    .with_session_resumption("bob")
+                    .with_hybrid_key_exchange(),
+            )
+            .await?;
+        assert_eq!(registry.handshakes_resumed(), 2 * resumed);
+        assert_eq!(alice_channel.their_identifier(), &bob);
+        check_channel_messages(&mut child_ctx, &alice_channel).await?;
+
+        // wait for the resumption ticket
+        ctx.sleep(Duration::from_millis(250)).await;
+        secure_channels
+            .stop_secure_channel(ctx, alice_channel.encryptor_address())
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_resumption_after_key_rotation(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let registry = secure_channels.secure_channel_registry();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new().with_resumption_tickets(Duration::from_secs(60)),
+        )
+        .await?;
+    let mut child_ctx = create_child_context(ctx).await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    let options = || SecureChannelOptions::new().with_session_resumption("bob");
+    secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], options())
+        .await?;
+    ctx.sleep(Duration::from_millis(250)).await;
+
+    // the resumption ticket is not used once an identity has rotated its keys
+    identities_creation.rotate_identity(&alice).await?;
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], options())
+        .await?;
+    assert_eq!(registry.handshakes_resumed(), 0);
+    check_channel_messages(&mut child_ctx, &alice_channel).await?;
+
+    Ok(())
+}
+
+/// Create a "child" context receiving the messages sent over the secure channels
+async fn create_child_context(ctx: &Context) -> Result<Context> {
+    ctx.new_detached_with_mailboxes(Mailboxes::main(
+        "child",
+        Arc::new(AllowAll),
+        Arc::new(AllowAll),
+    ))
+    .await
+}
+
+/// Check that a message can be sent to the child context over a secure channel
+async fn check_channel_messages(child_ctx: &mut Context, channel: &SecureChannel) -> Result<()> {
+    child_ctx
+        .send(
+            route![channel.clone(), child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    assert_eq!("Hello, Bob!", msg.into_body()?);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_listener_limits_concurrent_handshakes(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new()
+                .with_max_concurrent_handshakes(1)
+                .with_handshake_timeout(Duration::from_secs(1)),
+        )
+        .await?;
+
+    // start a handshake which is never completed
+    ctx.send(route!["bob_listener"], NeutralMessage::from(vec![1u8; 32]))
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_timeout(Duration::from_millis(500)),
+        )
+        .await;
+    assert!(result.is_err());
+
+    let registry = secure_channels.secure_channel_registry();
+    assert_eq!(registry.handshakes_started(), 1);
+    assert_eq!(registry.handshakes_rejected(), 1);
+
+    // the incomplete handshake is stopped after the handshake timeout
+    ctx.sleep(Duration::from_millis(1000)).await;
+
+    secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    assert_eq!(registry.handshakes_started(), 2);
+
+    Ok(())
+}
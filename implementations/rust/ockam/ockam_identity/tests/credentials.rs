@@ -4,9 +4,9 @@ use std::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, Any, DenyAll};
 use ockam_core::{route, Result, Routed, Worker};
-use ockam_identity::models::CredentialSchemaIdentifier;
+use ockam_identity::models::{CredentialSchemaIdentifier, RevokedSubject};
 use ockam_identity::secure_channels::secure_channels;
-use ockam_identity::utils::AttributesBuilder;
+use ockam_identity::utils::{now, AttributesBuilder};
 use ockam_identity::{
     CredentialAccessControl, SecureChannelListenerOptions, SecureChannelOptions,
     TrustIdentifierPolicy,
@@ -402,3 +402,197 @@ impl Worker for CountingWorker {
         Ok(())
     }
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn credential_revoked_after_presentation__attributes_are_rejected(
+    ctx: &mut Context,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let identities_attributes = identities.identities_attributes();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let server = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    let credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            &authority,
+            &client,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("is_superuser", "true")
+                .build(),
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+
+    // The credential is revoked right away, but the revocation list is only received
+    // after the credential has been presented
+    let revoked_at = now()?;
+    ctx.sleep(Duration::from_millis(1100)).await;
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &server,
+            "listener",
+            SecureChannelListenerOptions::new().with_authority(authority.clone()),
+        )
+        .await?;
+
+    secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route!["listener"],
+            SecureChannelOptions::new()
+                .with_trust_policy(TrustIdentifierPolicy::new(server.clone()))
+                .with_credential(credential.clone())?,
+        )
+        .await?;
+
+    ctx.sleep(Duration::from_millis(200)).await;
+
+    let attrs = identities_attributes
+        .get_attributes(&client, &authority)
+        .await?;
+    assert!(attrs.is_some());
+
+    let revocation_list = credentials
+        .credentials_creation()
+        .issue_revocation_list(
+            &authority,
+            1,
+            vec![RevokedSubject {
+                subject: client.clone(),
+                revoked_at,
+            }],
+        )
+        .await?;
+    credentials
+        .credentials_verification()
+        .receive_revocation_list(&[authority.clone()], revocation_list)
+        .await?;
+
+    let attrs = identities_attributes
+        .get_attributes(&client, &authority)
+        .await?;
+    assert!(attrs.is_none());
+
+    let result = credentials
+        .credentials_verification()
+        .verify_credential(Some(&client), &[authority.clone()], &credential)
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn subject_reenrolled_after_revocation__attributes_are_kept_by_later_lists(
+    ctx: &mut Context,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let identities_attributes = identities.identities_attributes();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let server = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+    let other = identities_creation.create_identity().await?;
+
+    // The client is revoked, then enrolled again with a new credential
+    let client_revoked_at = now()?;
+    let revocation_list = credentials
+        .credentials_creation()
+        .issue_revocation_list(
+            &authority,
+            1,
+            vec![RevokedSubject {
+                subject: client.clone(),
+                revoked_at: client_revoked_at,
+            }],
+        )
+        .await?;
+    credentials
+        .credentials_verification()
+        .receive_revocation_list(&[authority.clone()], revocation_list)
+        .await?;
+    ctx.sleep(Duration::from_millis(1100)).await;
+
+    let credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            &authority,
+            &client,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("is_superuser", "true")
+                .build(),
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &server,
+            "listener",
+            SecureChannelListenerOptions::new().with_authority(authority.clone()),
+        )
+        .await?;
+
+    secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route!["listener"],
+            SecureChannelOptions::new()
+                .with_trust_policy(TrustIdentifierPolicy::new(server.clone()))
+                .with_credential(credential)?,
+        )
+        .await?;
+
+    ctx.sleep(Duration::from_millis(200)).await;
+
+    let attrs = identities_attributes
+        .get_attributes(&client, &authority)
+        .await?;
+    assert!(attrs.is_some());
+
+    // Another subject is revoked, the new list still contains the previous revocation
+    let revocation_list = credentials
+        .credentials_creation()
+        .issue_revocation_list(
+            &authority,
+            2,
+            vec![
+                RevokedSubject {
+                    subject: client.clone(),
+                    revoked_at: client_revoked_at,
+                },
+                RevokedSubject {
+                    subject: other,
+                    revoked_at: now()?,
+                },
+            ],
+        )
+        .await?;
+    credentials
+        .credentials_verification()
+        .receive_revocation_list(&[authority.clone()], revocation_list)
+        .await?;
+
+    let attrs = identities_attributes
+        .get_attributes(&client, &authority)
+        .await?;
+    assert!(attrs.is_some());
+
+    Ok(())
+}
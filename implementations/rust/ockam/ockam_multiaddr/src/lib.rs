@@ -27,7 +27,7 @@ use once_cell::race::OnceBox;
 use std::net::{SocketAddrV4, SocketAddrV6};
 use tinyvec::{Array, ArrayVec, TinyVec};
 
-use crate::proto::{DnsAddr, Ip4, Ip6, Tcp};
+use crate::proto::{DnsAddr, Ip4, Ip6, Tcp, Udp};
 pub use error::Error;
 use ockam_core::env::FromString;
 pub use registry::{Registry, RegistryBuilder};
@@ -595,26 +595,33 @@ impl MultiAddr {
 
     /// If the input MultiAddr is "/dnsaddr/localhost/tcp/4000/service/api",
     /// then this will return string format of the SocketAddr: "127.0.0.1:4000".
+    ///
+    /// The port can be either a TCP or a UDP port.
     pub fn to_socket_addr(&self) -> Result<String, Error> {
+        fn port(p: Option<ProtoValue>) -> Result<u16, Error> {
+            let p = p.ok_or_else(|| Error::message("No port found"))?;
+            p.cast::<Tcp>()
+                .map(|port| *port)
+                .or_else(|| p.cast::<Udp>().map(|port| *port))
+                .ok_or_else(|| Error::invalid_proto(p.code()))
+        }
+
         let mut it = self.iter().peekable();
         while let Some(p) = it.next() {
             match p.code() {
                 Ip4::CODE => {
                     let ip4 = p.cast::<Ip4>().unwrap();
-                    let port = it.next().unwrap().cast::<Tcp>().unwrap();
-                    return Ok(SocketAddrV4::new(*ip4, *port).to_string());
+                    return Ok(SocketAddrV4::new(*ip4, port(it.next())?).to_string());
                 }
                 Ip6::CODE => {
                     let ip6 = p.cast::<Ip6>().unwrap();
-                    let port = it.next().unwrap().cast::<Tcp>().unwrap();
-                    return Ok(SocketAddrV6::new(*ip6, *port, 0, 0).to_string());
+                    return Ok(SocketAddrV6::new(*ip6, port(it.next())?, 0, 0).to_string());
                 }
                 DnsAddr::CODE => {
                     let host = p.cast::<DnsAddr>().unwrap();
                     if let Some(p) = it.peek() {
-                        if p.code() == Tcp::CODE {
-                            let port = p.cast::<Tcp>().unwrap();
-                            return Ok(format!("{}:{}", &*host, *port));
+                        if p.code() == Tcp::CODE || p.code() == Udp::CODE {
+                            return Ok(format!("{}:{}", &*host, port(it.next())?));
                         }
                     }
                 }
@@ -805,6 +812,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{MultiAddr, Protocol, Registry};
+    use core::str::FromStr;
     use tinyvec::TinyVec;
 
     #[test]
@@ -823,6 +831,16 @@ mod tests {
         assert_eq!(v, t);
     }
 
+    #[test]
+    fn to_socket_addr() {
+        let ma = MultiAddr::from_str("/dnsaddr/localhost/tcp/4000/ws/service/api").unwrap();
+        assert_eq!(ma.to_socket_addr().unwrap(), "localhost:4000");
+        let ma = MultiAddr::from_str("/ip4/127.0.0.1/udp/5000/service/api").unwrap();
+        assert_eq!(ma.to_socket_addr().unwrap(), "127.0.0.1:5000");
+        let ma = MultiAddr::from_str("/ip4/127.0.0.1/service/api").unwrap();
+        assert!(ma.to_socket_addr().is_err());
+    }
+
     #[test]
     fn self_multiaddr() {
         let multiaddr = MultiAddr::try_from_str("self", Registry::default()).unwrap();
@@ -1,5 +1,7 @@
 use core::fmt;
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
+use ockam_multiaddr::proto::{
+    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Udp, Unix, Ws,
+};
 use ockam_multiaddr::{Code, Match, MultiAddr, Protocol};
 use quickcheck::{quickcheck, Arbitrary, Gen};
 use rand::distributions::{Alphanumeric, DistString};
@@ -132,6 +134,18 @@ quickcheck! {
                         addr.push_back(Space::new("space")).unwrap();
                         prot.push_back(Space::CODE);
                     }
+                    Udp::CODE => {
+                        addr.push_back(Udp::new(0)).unwrap();
+                        prot.push_back(Udp::CODE);
+                    }
+                    Ws::CODE => {
+                        addr.push_back(Ws).unwrap();
+                        prot.push_back(Ws::CODE);
+                    }
+                    Unix::CODE => {
+                        addr.push_back(Unix::new("/tmp/ockam.sock")).unwrap();
+                        prot.push_back(Unix::CODE);
+                    }
                     _ => unreachable!()
                 }
             }
@@ -153,6 +167,9 @@ const PROTOS: &[Code] = &[
     Node::CODE,
     Project::CODE,
     Space::CODE,
+    Udp::CODE,
+    Ws::CODE,
+    Unix::CODE,
 ];
 
 impl Arbitrary for Addr {
@@ -169,6 +186,9 @@ impl Arbitrary for Addr {
                 Project::CODE => a.push_back(Project::new(gen_string())).unwrap(),
                 Space::CODE => a.push_back(Space::new(gen_string())).unwrap(),
                 Node::CODE => a.push_back(Node::new(gen_string())).unwrap(),
+                Udp::CODE => a.push_back(Udp::new(u16::arbitrary(g))).unwrap(),
+                Ws::CODE => a.push_back(Ws).unwrap(),
+                Unix::CODE => a.push_back(Unix::new(gen_path())).unwrap(),
                 _ => unreachable!(),
             }
         }
@@ -176,6 +196,35 @@ impl Arbitrary for Addr {
     }
 }
 
+#[test]
+fn ws_has_no_value() {
+    let a = MultiAddr::from_str("/dnsaddr/localhost/tcp/4000/ws/service/api").unwrap();
+    let codes = a.iter().map(|p| p.code()).collect::<Vec<_>>();
+    assert_eq!(codes, [DnsAddr::CODE, Tcp::CODE, Ws::CODE, Service::CODE]);
+    assert_eq!(a.to_string(), "/dnsaddr/localhost/tcp/4000/ws/service/api");
+    assert!(MultiAddr::from_str("/ws").is_ok());
+}
+
+#[test]
+fn unix_path_is_percent_encoded() {
+    let mut a = MultiAddr::default();
+    a.push_back(Unix::new("/tmp/50%/node.sock")).unwrap();
+    a.push_back(Service::new("api")).unwrap();
+    assert_eq!(
+        a.to_string(),
+        "/unix/%2Ftmp%2F50%25%2Fnode.sock/service/api"
+    );
+
+    let b = MultiAddr::from_str("/unix/%2Ftmp%2F50%25%2Fnode.sock/service/api").unwrap();
+    assert_eq!(a, b);
+    let proto = b.first().unwrap();
+    let unix = proto.cast::<Unix>().unwrap();
+    assert_eq!(&*unix, "/tmp/50%/node.sock");
+
+    assert!(MultiAddr::from_str("/unix/%2Ftmp%2").is_err());
+    assert!(MultiAddr::from_str("/unix/%41").is_err());
+}
+
 /// An operation to perform on a MultiAddr.
 #[derive(Debug, Copy, Clone)]
 enum Op {
@@ -220,3 +269,7 @@ fn gen_string() -> String {
     s.retain(|c| c != '/');
     s
 }
+
+fn gen_path() -> String {
+    format!("/tmp/{}%/{}.sock", gen_string(), gen_string())
+}
@@ -96,6 +96,12 @@ impl Context {
         &self.flow_controls
     }
 
+    /// Number of messages currently waiting in the router mailbox
+    #[cfg(feature = "std")]
+    pub fn router_mailbox_depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
     /// Return the tracing context
     #[cfg(feature = "std")]
     pub fn tracing_context(&self) -> OpenTelemetryContext {
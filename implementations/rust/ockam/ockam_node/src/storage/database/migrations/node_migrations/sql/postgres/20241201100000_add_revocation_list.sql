@@ -0,0 +1,30 @@
+-- This table stores the latest revocation list received from each authority
+CREATE TABLE revocation_list
+(
+    issuer_identifier TEXT    NOT NULL,
+    version           INTEGER NOT NULL,
+    revocation_list   BYTEA   NOT NULL,
+    node_name         TEXT    NOT NULL -- node name to isolate the revocation lists that each node has
+);
+
+CREATE UNIQUE INDEX revocation_list_issuer_node_name_index ON revocation_list (issuer_identifier, node_name);
+
+-- This table stores the subjects of the latest revocation list received from each authority
+CREATE TABLE revoked_subject
+(
+    issuer_identifier  TEXT    NOT NULL,
+    subject_identifier TEXT    NOT NULL,
+    revoked_at         INTEGER NOT NULL,
+    node_name          TEXT    NOT NULL -- node name to isolate the revocation lists that each node has
+);
+
+CREATE UNIQUE INDEX revoked_subject_issuer_subject_node_name_index ON revoked_subject (issuer_identifier, subject_identifier, node_name);
+
+-- This table stores the members revoked by an authority
+CREATE TABLE authority_revocation
+(
+    identifier TEXT    NOT NULL UNIQUE,
+    revoked_by TEXT    NOT NULL,
+    revoked_at INTEGER NOT NULL,
+    version    INTEGER NOT NULL -- version of the revocation list which includes that revocation
+);
@@ -0,0 +1,7 @@
+-- This table stores the parameters used to encrypt the vault secrets at rest.
+-- It contains at most one row, and no row if the secrets are stored in clear.
+CREATE TABLE secrets_encryption
+(
+    salt      BYTEA NOT NULL, -- salt used to derive the encryption key from a passphrase
+    key_check BYTEA NOT NULL  -- constant value encrypted with the key, to check that the right key is used
+);
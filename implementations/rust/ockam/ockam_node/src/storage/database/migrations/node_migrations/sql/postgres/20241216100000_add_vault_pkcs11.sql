@@ -0,0 +1,2 @@
+-- This column indicates if the signing keys of a vault are stored on a PKCS#11 token
+ALTER TABLE vault ADD COLUMN is_pkcs11 BOOLEAN NOT NULL DEFAULT FALSE;
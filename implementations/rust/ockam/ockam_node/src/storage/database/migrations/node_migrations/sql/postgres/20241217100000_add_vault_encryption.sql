@@ -0,0 +1,2 @@
+-- This column indicates if the secrets of a vault are encrypted at rest
+ALTER TABLE vault ADD COLUMN is_encrypted BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- This column indicates if the signing keys of a vault are stored on a PKCS#11 token (1 means true)
+ALTER TABLE vault ADD COLUMN is_pkcs11 INTEGER NOT NULL DEFAULT 0;
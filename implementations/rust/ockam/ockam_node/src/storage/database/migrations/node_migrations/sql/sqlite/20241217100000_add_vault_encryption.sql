@@ -0,0 +1,2 @@
+-- This column indicates if the secrets of a vault are encrypted at rest (1 means true)
+ALTER TABLE vault ADD COLUMN is_encrypted INTEGER NOT NULL DEFAULT 0;
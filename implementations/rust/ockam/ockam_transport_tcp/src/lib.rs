@@ -27,11 +27,15 @@ mod workers;
 
 pub(crate) use workers::*;
 
-pub use options::{TcpConnectionOptions, TcpListenerOptions};
+pub use options::{
+    HttpProxy, TcpConnectionOptions, TcpListenerOptions, TcpTlsClientOptions, TcpTlsServerOptions,
+};
 pub use portal::{
-    new_certificate_provider_cache, Direction, PortalInletInterceptor, PortalInterceptor,
-    PortalInterceptorFactory, PortalInterceptorWorker, PortalInternalMessage, PortalMessage,
-    PortalOutletInterceptor, TlsCertificate, TlsCertificateProvider, MAX_PAYLOAD_SIZE,
+    new_certificate_provider_cache, DestinationAccessControl, DestinationAllowlist,
+    DestinationPattern, Direction, IdentityAttributesProvider, PortalInletInterceptor,
+    PortalInterceptor, PortalInterceptorFactory, PortalInterceptorWorker, PortalInternalMessage,
+    PortalMessage, PortalOutletInterceptor, TcpPortalMetrics, TlsCertificate,
+    TlsCertificateProvider, MAX_PAYLOAD_SIZE, PP2_TYPE_OCKAM_ATTRIBUTE, PP2_TYPE_OCKAM_IDENTIFIER,
 };
 pub use protocol_version::*;
 pub use registry::*;
@@ -0,0 +1,210 @@
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::RangeInclusive;
+use core::str::FromStr;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, compat::boxed::Box, Error, LocalInfoIdentifier, Result};
+use ockam_transport_core::HostnamePort;
+
+/// Access control for the destinations requested by SOCKS5 inlets.
+///
+/// A dynamic outlet only connects to a destination requested by an inlet if this access control
+/// authorizes it, for the identity on the other side of the secure channel, if any.
+#[async_trait]
+pub trait DestinationAccessControl: Debug + Send + Sync + 'static {
+    /// Return true if the inlet can connect to the destination
+    async fn is_authorized(
+        &self,
+        their_identifier: Option<&LocalInfoIdentifier>,
+        destination: &HostnamePort,
+    ) -> Result<bool>;
+}
+
+/// List of the destinations which a dynamic outlet can connect to
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DestinationAllowlist {
+    patterns: Vec<DestinationPattern>,
+}
+
+impl DestinationAllowlist {
+    /// Create an allowlist from a list of destination patterns
+    pub fn new(patterns: Vec<DestinationPattern>) -> Self {
+        Self { patterns }
+    }
+
+    /// Return true if the destination matches one of the patterns
+    pub fn is_allowed(&self, destination: &HostnamePort) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches(destination))
+    }
+
+    /// Return true if the allowlist does not contain any pattern
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+#[async_trait]
+impl DestinationAccessControl for DestinationAllowlist {
+    async fn is_authorized(
+        &self,
+        _their_identifier: Option<&LocalInfoIdentifier>,
+        destination: &HostnamePort,
+    ) -> Result<bool> {
+        Ok(self.is_allowed(destination))
+    }
+}
+
+/// Pattern of allowed destinations, written as `host:ports` where:
+///
+///  - `host` is a hostname or an IP address (IPv6 addresses are written between brackets),
+///    `*.domain` for all the subdomains of a domain, or `*` for all hosts.
+///  - `ports` is a port number, a range of ports like `8000-8999`, or `*` for all ports.
+///
+/// Hostnames are compared without case sensitivity, and are not resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DestinationPattern {
+    host: HostPattern,
+    ports: RangeInclusive<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum HostPattern {
+    Any,
+    Subdomains(String),
+    Exact(String),
+}
+
+impl DestinationPattern {
+    /// Return true if the destination matches this pattern
+    pub fn matches(&self, destination: &HostnamePort) -> bool {
+        if !self.ports.contains(&destination.port()) {
+            return false;
+        }
+        let hostname = destination.hostname().to_lowercase();
+        match &self.host {
+            HostPattern::Any => true,
+            HostPattern::Subdomains(domain) => hostname
+                .strip_suffix(domain.as_str())
+                .is_some_and(|subdomain| subdomain.ends_with('.') && subdomain.len() > 1),
+            HostPattern::Exact(host) => &hostname == host,
+        }
+    }
+}
+
+impl FromStr for DestinationPattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!("Invalid destination pattern '{s}': {reason}"),
+            )
+        };
+
+        let (host, ports) = s
+            .rsplit_once(':')
+            .ok_or_else(|| invalid("expected host:ports"))?;
+
+        let host = host.to_lowercase();
+        let host = if host == "*" {
+            HostPattern::Any
+        } else if let Some(domain) = host.strip_prefix("*.") {
+            if domain.is_empty() || domain.contains('*') {
+                return Err(invalid("expected a domain after '*.'"));
+            }
+            HostPattern::Subdomains(domain.to_string())
+        } else if host.is_empty() || host.contains('*') {
+            return Err(invalid("expected a hostname, '*.domain' or '*'"));
+        } else {
+            HostPattern::Exact(host)
+        };
+
+        let parse_port = |port: &str| {
+            port.parse::<u16>()
+                .map_err(|_| invalid("expected a port, a range of ports or '*'"))
+        };
+        let ports = if ports == "*" {
+            0..=u16::MAX
+        } else if let Some((start, end)) = ports.split_once('-') {
+            let (start, end) = (parse_port(start)?, parse_port(end)?);
+            if start > end {
+                return Err(invalid("the range of ports is empty"));
+            }
+            start..=end
+        } else {
+            let port = parse_port(ports)?;
+            port..=port
+        };
+
+        Ok(Self { host, ports })
+    }
+}
+
+impl Display for DestinationPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match &self.host {
+            HostPattern::Any => write!(f, "*")?,
+            HostPattern::Subdomains(domain) => write!(f, "*.{domain}")?,
+            HostPattern::Exact(host) => write!(f, "{host}")?,
+        }
+        match (*self.ports.start(), *self.ports.end()) {
+            (0, u16::MAX) => write!(f, ":*"),
+            (start, end) if start == end => write!(f, ":{start}"),
+            (start, end) => write!(f, ":{start}-{end}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_destinations() -> Result<()> {
+        let allowlist = DestinationAllowlist::new(vec![
+            "db.internal:5432".parse()?,
+            "*.web.internal:8000-8999".parse()?,
+            "[::1]:*".parse()?,
+            "*:443".parse()?,
+        ]);
+
+        assert!(allowlist.is_allowed(&HostnamePort::new("DB.internal", 5432)));
+        assert!(!allowlist.is_allowed(&HostnamePort::new("db.internal", 5433)));
+        assert!(allowlist.is_allowed(&HostnamePort::new("app.web.internal", 8080)));
+        assert!(!allowlist.is_allowed(&HostnamePort::new("web.internal", 8080)));
+        assert!(!allowlist.is_allowed(&HostnamePort::new("appweb.internal", 8080)));
+        assert!(!allowlist.is_allowed(&HostnamePort::new("app.web.internal", 9000)));
+        assert!(allowlist.is_allowed(&HostnamePort::new("[::1]", 22)));
+        assert!(allowlist.is_allowed(&HostnamePort::new("example.com", 443)));
+        assert!(!allowlist.is_allowed(&HostnamePort::new("example.com", 80)));
+        assert!(!DestinationAllowlist::default().is_allowed(&HostnamePort::new("localhost", 80)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_destination_patterns() -> Result<()> {
+        for pattern in [
+            "db.internal:5432",
+            "*.internal:8000-8999",
+            "[::1]:*",
+            "*:443",
+        ] {
+            assert_eq!(pattern.parse::<DestinationPattern>()?.to_string(), pattern);
+        }
+
+        for pattern in [
+            "db.internal",
+            "db.*:80",
+            "*.:80",
+            "host:",
+            "host:90-80",
+            ":80",
+        ] {
+            assert!(pattern.parse::<DestinationPattern>().is_err(), "{pattern}");
+        }
+        Ok(())
+    }
+}
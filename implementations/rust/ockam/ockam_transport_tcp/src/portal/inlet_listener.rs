@@ -1,6 +1,6 @@
 use crate::portal::addresses::{Addresses, PortalType};
 use crate::portal::tls_certificate::TlsCertificateProvider;
-use crate::portal::{InletSharedState, ReadHalfMaybeTls, WriteHalfMaybeTls};
+use crate::portal::{InletSharedState, PortalPeer, ReadHalfMaybeTls, WriteHalfMaybeTls};
 use crate::{portal::TcpPortalWorker, TcpInlet, TcpInletOptions, TcpRegistry};
 use log::warn;
 use ockam_core::compat::net::SocketAddr;
@@ -10,9 +10,7 @@ use ockam_core::{async_trait, compat::boxed::Box, Result};
 use ockam_core::{Address, Processor, Route};
 use ockam_node::compat::asynchronous::RwLock;
 use ockam_node::Context;
-use ockam_transport_core::{HostnamePort, TransportError};
-use rustls::pki_types::CertificateDer;
-use std::io::BufReader;
+use ockam_transport_core::TransportError;
 use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::time::Instant;
@@ -55,6 +53,14 @@ impl TcpInletListenProcessor {
         addr: SocketAddr,
         options: TcpInletOptions,
     ) -> Result<TcpInlet> {
+        if options.is_socks5 && options.tls_certificate_provider.is_some() {
+            return Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "A SOCKS5 inlet cannot be used with TLS",
+            ));
+        }
+
         let processor_address = Address::random_tagged("TcpInletListenProcessor");
 
         debug!("Binding TcpPortalListenerWorker to {}", addr);
@@ -112,37 +118,8 @@ impl TcpInletListenProcessor {
                 }
             };
 
-            let chain = {
-                let mut reader = BufReader::new(certificate.full_chain_pem.as_slice());
-                let chain = rustls_pemfile::certs(&mut reader);
-                let chain: std::io::Result<Vec<CertificateDer<'static>>> = chain.collect();
-                chain.unwrap()
-            };
-
-            let private_key = {
-                let mut reader = BufReader::new(certificate.private_key_pem.as_slice());
-                let mut private_keys = rustls_pemfile::pkcs8_private_keys(&mut reader);
-
-                match private_keys.next() {
-                    Some(Ok(private_key)) => private_key.into(),
-
-                    Some(Err(error)) => {
-                        return Err(ockam_core::Error::new(
-                            Origin::Transport,
-                            Kind::Parse,
-                            error,
-                        ));
-                    }
-
-                    None => {
-                        return Err(ockam_core::Error::new(
-                            Origin::Transport,
-                            Kind::Parse,
-                            "No private key found in the provided certificate",
-                        ));
-                    }
-                }
-            };
+            let chain = certificate.certificate_chain()?;
+            let private_key = certificate.private_key()?;
 
             let config = rustls::ServerConfig::builder()
                 .with_no_client_auth()
@@ -220,12 +197,15 @@ impl Processor for TcpInletListenProcessor {
             ctx,
             self.registry.clone(),
             streams,
-            HostnamePort::from(socket_addr),
+            PortalPeer::Tcp(socket_addr.into()),
+            Some(socket_addr),
+            self.options.is_socks5,
             inlet_shared_state.route().clone(),
             inlet_shared_state.their_identifier(),
             addresses,
             self.options.incoming_access_control.clone(),
             self.options.outgoing_access_control.clone(),
+            self.options.metrics.clone(),
         )
         .await?;
 
@@ -3,12 +3,13 @@ use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl,
 use ockam_core::{
     async_trait, route, Address, AllowOnwardAddress, AllowSourceAddress, Any,
     AnyIncomingAccessControl, AnyOutgoingAccessControl, Encodable, IncomingAccessControl,
-    LocalInfo, LocalMessage, NeutralMessage, OutgoingAccessControl, Route, Routed, Worker,
+    LocalInfo, LocalInfoIdentifier, LocalMessage, NeutralMessage, OutgoingAccessControl, Route,
+    Routed, SecureChannelLocalInfo, Worker,
 };
 use ockam_node::{Context, WorkerBuilder};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 /// Direction of the data being intercepted
 #[derive(Clone, Copy, Debug)]
@@ -32,12 +33,30 @@ pub trait PortalInterceptor: 'static + Send + Sync {
         direction: Direction,
         buffer: &[u8],
     ) -> ockam_core::Result<Option<Vec<u8>>>;
+
+    /// This method is called when [`PortalInterceptor::intercept`] fails.
+    /// When a buffer is returned, the connection is closed on both sides, after sending that
+    /// buffer, if it is not empty, back to the sender of the data which could not be intercepted.
+    /// Otherwise, which is the default, that data is dropped and the connection stays open.
+    fn error_response(&self, _direction: Direction) -> Option<Vec<u8>> {
+        None
+    }
 }
 
 /// Portal Interceptor Factory
 pub trait PortalInterceptorFactory: 'static + Send + Sync {
     /// Create a new instance of a portal interceptor
     fn create(&self) -> Arc<dyn PortalInterceptor>;
+
+    /// Create a new instance of a portal interceptor on the outlet side, for a connection
+    /// requested by the identity on the other side of the secure channel, if any.
+    /// By default, the identity is ignored
+    fn create_for_identifier(
+        &self,
+        _their_identifier: Option<LocalInfoIdentifier>,
+    ) -> Arc<dyn PortalInterceptor> {
+        self.create()
+    }
 }
 
 /// Portal interceptor for the outlet side
@@ -46,6 +65,7 @@ pub struct PortalOutletInterceptor {
     outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     incoming_access_control: Arc<dyn IncomingAccessControl>,
     spawner_flow_control_id: Option<FlowControlId>,
+    outlet_address: Option<Address>,
 }
 
 impl PortalOutletInterceptor {
@@ -66,12 +86,58 @@ impl PortalOutletInterceptor {
         interceptor_factory: Arc<dyn PortalInterceptorFactory>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> ockam_core::Result<()> {
+        Self::start(
+            context,
+            listener_address,
+            None,
+            spawner_flow_control_id,
+            interceptor_factory,
+            outgoing_access_control,
+            incoming_access_control,
+        )
+        .await
+    }
+
+    /// Starts a listener that will intercept data in a portal on the outlet side,
+    /// like [`PortalOutletInterceptor::create`], and always forward it to the given outlet.
+    /// The inlets can then use the listener address as if it was the outlet address.
+    pub async fn create_for_outlet(
+        context: &Context,
+        listener_address: Address,
+        outlet_address: Address,
+        spawner_flow_control_id: Option<FlowControlId>,
+        interceptor_factory: Arc<dyn PortalInterceptorFactory>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> ockam_core::Result<()> {
+        Self::start(
+            context,
+            listener_address,
+            Some(outlet_address),
+            spawner_flow_control_id,
+            interceptor_factory,
+            outgoing_access_control,
+            incoming_access_control,
+        )
+        .await
+    }
+
+    async fn start(
+        context: &Context,
+        listener_address: Address,
+        outlet_address: Option<Address>,
+        spawner_flow_control_id: Option<FlowControlId>,
+        interceptor_factory: Arc<dyn PortalInterceptorFactory>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
     ) -> ockam_core::Result<()> {
         let worker = Self {
             spawner_flow_control_id,
             interceptor_factory,
             outgoing_access_control,
             incoming_access_control: incoming_access_control.clone(),
+            outlet_address,
         };
 
         WorkerBuilder::new(worker)
@@ -94,10 +160,16 @@ impl Worker for PortalOutletInterceptor {
         message: Routed<Self::Message>,
     ) -> ockam_core::Result<()> {
         let source_address = message.src_addr();
+        let their_identifier = SecureChannelLocalInfo::find_info(message.local_message())
+            .map(|l| l.their_identifier())
+            .ok();
         let mut message = message.into_local_message();
 
         // Remove our address
         message = message.pop_front_onward_route()?;
+        if let Some(outlet_address) = &self.outlet_address {
+            message = message.push_front_onward_route(outlet_address);
+        }
 
         // unique flow control id for each interceptor instance
         let flow_control_id = FlowControls::generate_flow_control_id();
@@ -109,7 +181,8 @@ impl Worker for PortalOutletInterceptor {
             self.spawner_flow_control_id.clone(),
             self.incoming_access_control.clone(),
             self.outgoing_access_control.clone(),
-            self.interceptor_factory.create(),
+            self.interceptor_factory
+                .create_for_identifier(their_identifier),
         )
         .await?;
 
@@ -238,6 +311,11 @@ pub struct PortalInterceptorWorker {
     disconnect_received: Arc<AtomicBool>,
     interceptor: Arc<dyn PortalInterceptor>,
     direction: Direction,
+    // Local info of the last message received from the source of the data,
+    // attached to the messages sent on behalf of the other worker
+    source_local_info: Vec<LocalInfo>,
+    // Set when the interceptor failed, the connection is being closed
+    rejected: bool,
 }
 
 #[async_trait]
@@ -257,15 +335,46 @@ impl Worker for PortalInterceptorWorker {
     ) -> ockam_core::Result<()> {
         let onward_route = routed_message.onward_route();
         let return_route = routed_message.return_route();
-        let local_info = routed_message.local_message().local_info();
+        // Messages from the other worker are sent when it closes the connection
+        let from_other_worker = routed_message.src_addr() == self.other_worker_address;
+        let local_info = if from_other_worker {
+            self.source_local_info.clone()
+        } else {
+            let local_info = routed_message.local_message().local_info();
+            self.source_local_info = local_info.clone();
+            local_info
+        };
         let portal_message = PortalMessage::decode(routed_message.payload())?;
 
         match portal_message {
+            PortalMessage::Payload(message, _) if from_other_worker => {
+                // The error response of the other worker is not intercepted
+                self.split_and_send(
+                    context,
+                    onward_route.clone(),
+                    return_route.clone(),
+                    message,
+                    &local_info,
+                )
+                .await?;
+            }
+            PortalMessage::Payload(_, _) if self.rejected => {
+                trace!("dropping data received after the interceptor failed");
+            }
             PortalMessage::Payload(message, _) => {
-                let buffer: Option<Vec<u8>> = self
+                let buffer = match self
                     .interceptor
                     .intercept(context, self.direction, message)
-                    .await?;
+                    .await
+                {
+                    Ok(buffer) => buffer,
+                    Err(error) => match self.interceptor.error_response(self.direction) {
+                        Some(response) => {
+                            return self.reject(context, routed_message, error, response).await
+                        }
+                        None => return Err(error),
+                    },
+                };
                 match buffer {
                     Some(buffer) => {
                         trace!(
@@ -288,7 +397,10 @@ impl Worker for PortalInterceptorWorker {
             }
             PortalMessage::Disconnect => {
                 let return_route = return_route.clone();
-                self.forward(context, routed_message).await?;
+                let local_message = routed_message
+                    .into_local_message()
+                    .with_local_info(local_info);
+                self.forward_local(context, local_message).await?;
 
                 // the first one to receive disconnect and to swap the atomic will stop both workers
                 let disconnect_received = self.disconnect_received.swap(true, Ordering::SeqCst);
@@ -304,7 +416,9 @@ impl Worker for PortalInterceptorWorker {
                     context.stop_worker(context.address()).await?;
                 }
             }
-            PortalMessage::Ping => self.forward(context, routed_message).await?,
+            PortalMessage::Ping(_) | PortalMessage::Connect(..) => {
+                self.forward(context, routed_message).await?
+            }
 
             PortalMessage::Pong => {
                 match self.direction {
@@ -386,25 +500,36 @@ impl PortalInterceptorWorker {
             disconnect_received: disconnect_received.clone(),
             fixed_onward_route: Some(inlet_instance),
             interceptor: interceptor.clone(),
+            source_local_info: vec![],
+            rejected: false,
         };
 
+        // allow receiving the messages sent by the other worker when it closes the connection
         WorkerBuilder::new(from_outlet_worker)
             .with_address(from_outlet_worker_address.clone())
-            .with_incoming_access_control_arc(incoming_access_control)
+            .with_incoming_access_control_arc(Arc::new(AnyIncomingAccessControl::new(vec![
+                Arc::new(AllowSourceAddress(from_inlet_worker_address.clone())),
+                incoming_access_control,
+            ])))
             .start(context)
             .await?;
 
         let from_inlet_worker = Self {
-            other_worker_address: from_outlet_worker_address,
+            other_worker_address: from_outlet_worker_address.clone(),
             direction: Direction::FromInletToOutlet,
             disconnect_received: disconnect_received.clone(),
             fixed_onward_route: None,
             interceptor: interceptor.clone(),
+            source_local_info: vec![],
+            rejected: false,
         };
 
         WorkerBuilder::new(from_inlet_worker)
             .with_address(from_inlet_worker_address.clone())
-            .with_outgoing_access_control_arc(outgoing_access_control)
+            .with_outgoing_access_control_arc(Arc::new(AnyOutgoingAccessControl::new(vec![
+                Arc::new(AllowOnwardAddress::new(from_outlet_worker_address)),
+                outgoing_access_control,
+            ])))
             .start(context)
             .await?;
 
@@ -451,6 +576,8 @@ impl PortalInterceptorWorker {
             disconnect_received: disconnect_received.clone(),
             fixed_onward_route: Some(outlet_route),
             interceptor: interceptor.clone(),
+            source_local_info: vec![],
+            rejected: false,
         };
         let from_outlet_worker = Self {
             other_worker_address: from_inlet_worker_address.clone(),
@@ -458,6 +585,8 @@ impl PortalInterceptorWorker {
             disconnect_received: disconnect_received.clone(),
             fixed_onward_route: None,
             interceptor: interceptor.clone(),
+            source_local_info: vec![],
+            rejected: false,
         };
 
         let flow_controls = context.flow_controls();
@@ -469,18 +598,22 @@ impl PortalInterceptorWorker {
             vec![],
         );
 
-        // allow the other worker to forward the `pong` message
+        // allow the other worker to forward the `pong` message,
+        // and to send the messages closing the connection
         WorkerBuilder::new(from_inlet_worker)
             .with_address(from_inlet_worker_address.clone())
             .with_incoming_access_control_arc(Arc::new(AnyIncomingAccessControl::new(vec![
                 Arc::new(AllowSourceAddress(from_outlet_worker_address.clone())),
                 incoming_access_control,
             ])))
-            .with_outgoing_access_control_arc(Arc::new(FlowControlOutgoingAccessControl::new(
-                flow_controls,
-                flow_control_id.clone(),
-                spawner_flow_control_id.clone(),
-            )))
+            .with_outgoing_access_control_arc(Arc::new(AnyOutgoingAccessControl::new(vec![
+                Arc::new(AllowOnwardAddress::new(from_outlet_worker_address.clone())),
+                Arc::new(FlowControlOutgoingAccessControl::new(
+                    flow_controls,
+                    flow_control_id.clone(),
+                    spawner_flow_control_id.clone(),
+                )),
+            ])))
             .start(context)
             .await?;
 
@@ -501,12 +634,61 @@ impl PortalInterceptorWorker {
         Ok(from_inlet_worker_address)
     }
 
+    /// Close the connection when the interceptor fails and returns an error response:
+    ///  - send a disconnect message to the destination of the data,
+    ///  - send the error response and a disconnect message to the source of the data,
+    ///    via the other worker, which then stops both workers.
+    async fn reject(
+        &mut self,
+        context: &mut Context,
+        routed_message: Routed<NeutralMessage>,
+        error: ockam_core::Error,
+        response: Vec<u8>,
+    ) -> ockam_core::Result<()> {
+        warn!(
+            "closing the portal connection, the data could not be intercepted: {}",
+            error
+        );
+        self.rejected = true;
+
+        let local_message = routed_message.into_local_message();
+        let disconnect = PortalMessage::Disconnect.encode()?;
+        let source_route: Route = local_message
+            .return_route()
+            .clone()
+            .modify()
+            .prepend(self.other_worker_address.clone())
+            .into();
+
+        self.forward_local(context, local_message.set_payload(disconnect.clone()))
+            .await?;
+
+        for chunk in response.chunks(MAX_PAYLOAD_SIZE) {
+            let message = LocalMessage::new()
+                .with_onward_route(source_route.clone())
+                .with_payload(PortalMessage::Payload(chunk, None).encode()?);
+            context.forward(message).await?;
+        }
+        let message = LocalMessage::new()
+            .with_onward_route(source_route)
+            .with_payload(disconnect);
+        context.forward(message).await
+    }
+
     async fn forward(
         &self,
         context: &mut Context,
         routed_message: Routed<NeutralMessage>,
     ) -> ockam_core::Result<()> {
-        let mut local_message = routed_message.into_local_message();
+        self.forward_local(context, routed_message.into_local_message())
+            .await
+    }
+
+    async fn forward_local(
+        &self,
+        context: &mut Context,
+        mut local_message: LocalMessage,
+    ) -> ockam_core::Result<()> {
         tracing::trace!(
             "before: onwards={:?}; return={:?};",
             local_message.onward_route(),
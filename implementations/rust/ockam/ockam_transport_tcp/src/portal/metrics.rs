@@ -0,0 +1,70 @@
+use core::fmt;
+use core::fmt::Formatter;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters collected by the connections of a TCP Inlet or a TCP Outlet.
+///
+/// The counters can be shared with the portal via
+/// [`TcpInletOptions::with_metrics`](crate::TcpInletOptions::with_metrics) or
+/// [`TcpOutletOptions::with_metrics`](crate::TcpOutletOptions::with_metrics) in order to
+/// monitor the portal.
+#[derive(Debug, Default)]
+pub struct TcpPortalMetrics {
+    connections: AtomicU64,
+    active_connections: AtomicU64,
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+}
+
+impl TcpPortalMetrics {
+    /// Number of connections handled by the portal since it was created
+    pub fn connections(&self) -> u64 {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// Number of connections currently open
+    pub fn active_connections(&self) -> u64 {
+        self.active_connections.load(Ordering::Relaxed)
+    }
+
+    /// Number of bytes read from the TCP connections of the portal
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Number of bytes written to the TCP connections of the portal
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn connection_opened(&self) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        self.active_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn connection_closed(&self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_bytes_received(&self, bytes: usize) {
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_bytes_sent(&self, bytes: usize) {
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Display for TcpPortalMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Connections: {}, Active connections: {}, Bytes received: {}, Bytes sent: {}",
+            self.connections(),
+            self.active_connections(),
+            self.bytes_received(),
+            self.bytes_sent()
+        )
+    }
+}
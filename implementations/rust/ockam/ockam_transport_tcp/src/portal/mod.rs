@@ -1,22 +1,34 @@
 pub mod addresses;
+mod destination;
 mod inlet_listener;
 mod inlet_shared_state;
 mod interceptor;
+mod metrics;
 pub mod options;
 mod outlet_listener;
 mod portal_message;
 mod portal_receiver;
 mod portal_worker;
+mod proxy_protocol;
+mod socks5;
 mod tls_certificate;
+#[cfg(unix)]
+mod unix_inlet_listener;
 
+pub use destination::*;
 pub(crate) use inlet_listener::*;
 pub(crate) use inlet_shared_state::*;
 pub use interceptor::{
     Direction, PortalInletInterceptor, PortalInterceptor, PortalInterceptorFactory,
     PortalInterceptorWorker, PortalOutletInterceptor,
 };
+pub use metrics::TcpPortalMetrics;
 pub(crate) use outlet_listener::*;
 pub use portal_message::*;
 pub(crate) use portal_receiver::*;
 pub(crate) use portal_worker::*;
+pub use proxy_protocol::*;
+pub(crate) use socks5::*;
 pub use tls_certificate::*;
+#[cfg(unix)]
+pub(crate) use unix_inlet_listener::*;
@@ -1,5 +1,7 @@
 use crate::portal::addresses::Addresses;
-use crate::TlsCertificateProvider;
+use crate::{
+    DestinationAccessControl, IdentityAttributesProvider, TcpPortalMetrics, TlsCertificateProvider,
+};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
@@ -11,6 +13,9 @@ pub struct TcpInletOptions {
     pub(crate) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     pub(crate) is_paused: bool,
     pub(crate) tls_certificate_provider: Option<Arc<dyn TlsCertificateProvider>>,
+    pub(crate) metrics: Arc<TcpPortalMetrics>,
+    pub(crate) is_socks5: bool,
+    pub(crate) unix_socket_permissions: Option<u32>,
 }
 
 impl TcpInletOptions {
@@ -21,6 +26,9 @@ impl TcpInletOptions {
             outgoing_access_control: Arc::new(AllowAll),
             is_paused: false,
             tls_certificate_provider: None,
+            metrics: Default::default(),
+            is_socks5: false,
+            unix_socket_permissions: None,
         }
     }
 
@@ -30,6 +38,22 @@ impl TcpInletOptions {
         self
     }
 
+    /// Accept SOCKS5 connections. The destination requested by each client is sent to the outlet,
+    /// which must accept dynamic destinations, see [`TcpOutletOptions::with_destination_access_control`].
+    /// NOTE: SOCKS5 inlets can't use TLS
+    pub fn socks5(mut self) -> Self {
+        self.is_socks5 = true;
+        self
+    }
+
+    /// Set the permissions of the socket file created by an inlet listening on a
+    /// Unix domain socket, for example `0o660` to only let the owner and the group connect.
+    /// When omitted the permissions are set by the process umask
+    pub fn with_unix_socket_permissions(mut self, mode: u32) -> Self {
+        self.unix_socket_permissions = Some(mode);
+        self
+    }
+
     /// Set TLS certificate provider.
     /// Whe omitted the inlet will be clear-text
     pub fn with_tls_certificate_provider(
@@ -40,6 +64,12 @@ impl TcpInletOptions {
         self
     }
 
+    /// Set the counters updated by the connections of the inlet
+    pub fn with_metrics(mut self, metrics: Arc<TcpPortalMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
     /// Set Incoming Access Control
     pub fn with_incoming_access_control_impl(
         mut self,
@@ -112,6 +142,10 @@ pub struct TcpOutletOptions {
     pub(crate) incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub(crate) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     pub(crate) tls: bool,
+    pub(crate) metrics: Arc<TcpPortalMetrics>,
+    pub(crate) destination_access_control: Option<Arc<dyn DestinationAccessControl>>,
+    pub(crate) proxy_protocol: bool,
+    pub(crate) identity_attributes_provider: Option<Arc<dyn IdentityAttributesProvider>>,
 }
 
 impl TcpOutletOptions {
@@ -122,6 +156,10 @@ impl TcpOutletOptions {
             incoming_access_control: Arc::new(AllowAll),
             outgoing_access_control: Arc::new(AllowAll),
             tls: false,
+            metrics: Default::default(),
+            destination_access_control: None,
+            proxy_protocol: false,
+            identity_attributes_provider: None,
         }
     }
 
@@ -149,6 +187,43 @@ impl TcpOutletOptions {
         self
     }
 
+    /// Set the counters updated by the connections of the outlet
+    pub fn with_metrics(mut self, metrics: Arc<TcpPortalMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Make the outlet dynamic: SOCKS5 inlets can request a connection to any destination
+    /// authorized by the given access control. Other inlets are connected to the outlet peer,
+    /// if the access control authorizes it as well
+    pub fn with_destination_access_control(
+        mut self,
+        access_control: Arc<dyn DestinationAccessControl>,
+    ) -> Self {
+        self.destination_access_control = Some(access_control);
+        self
+    }
+
+    /// Send a PROXY protocol v2 header to the destination of each connection.
+    /// The header contains the address of the inlet client and the identifier
+    /// of the inlet, see [`PP2_TYPE_OCKAM_IDENTIFIER`](crate::PP2_TYPE_OCKAM_IDENTIFIER)
+    pub fn with_proxy_protocol(mut self) -> Self {
+        self.proxy_protocol = true;
+        self
+    }
+
+    /// Send a PROXY protocol v2 header to the destination of each connection, including
+    /// the credential attributes of the inlet returned by the provider,
+    /// see [`PP2_TYPE_OCKAM_ATTRIBUTE`](crate::PP2_TYPE_OCKAM_ATTRIBUTE)
+    pub fn with_proxy_protocol_attributes(
+        mut self,
+        provider: Arc<dyn IdentityAttributesProvider>,
+    ) -> Self {
+        self.proxy_protocol = true;
+        self.identity_attributes_provider = Some(provider);
+        self
+    }
+
     /// Set Outgoing Access Control
     pub fn with_outgoing_access_control_impl(
         mut self,
@@ -1,11 +1,15 @@
 use crate::portal::addresses::{Addresses, PortalType};
+use crate::portal::{PortalPeer, ProxyProtocolHeader};
 use crate::{portal::TcpPortalWorker, PortalMessage, TcpOutletOptions, TcpRegistry};
+use core::net::SocketAddr;
+use ockam_core::compat::sync::Arc;
 use ockam_core::{
-    async_trait, Address, DenyAll, NeutralMessage, Result, Routed, SecureChannelLocalInfo, Worker,
+    async_trait, Address, DenyAll, LocalInfoIdentifier, NeutralMessage, OutgoingAccessControl,
+    Result, Route, Routed, SecureChannelLocalInfo, Worker,
 };
 use ockam_node::{Context, WorkerBuilder};
-use ockam_transport_core::{HostnamePort, TransportError};
-use tracing::{debug, instrument};
+use ockam_transport_core::TransportError;
+use tracing::{debug, instrument, warn};
 
 /// A TCP Portal Outlet listen worker
 ///
@@ -14,16 +18,16 @@ use tracing::{debug, instrument};
 /// [`TcpTransport::create_outlet`](crate::TcpTransport::create_outlet).
 pub(crate) struct TcpOutletListenWorker {
     registry: TcpRegistry,
-    hostname_port: HostnamePort,
+    peer: PortalPeer,
     options: TcpOutletOptions,
 }
 
 impl TcpOutletListenWorker {
     /// Create a new `TcpOutletListenWorker`
-    fn new(registry: TcpRegistry, hostname_port: HostnamePort, options: TcpOutletOptions) -> Self {
+    fn new(registry: TcpRegistry, peer: PortalPeer, options: TcpOutletOptions) -> Self {
         Self {
             registry,
-            hostname_port,
+            peer,
             options,
         }
     }
@@ -33,18 +37,25 @@ impl TcpOutletListenWorker {
         ctx: &Context,
         registry: TcpRegistry,
         address: Address,
-        hostname_port: HostnamePort,
+        peer: PortalPeer,
         options: TcpOutletOptions,
     ) -> Result<()> {
         let access_control = options.incoming_access_control.clone();
+        // A dynamic Outlet replies to the Inlets requesting a destination which is not allowed
+        let outgoing_access_control: Arc<dyn OutgoingAccessControl> =
+            if options.destination_access_control.is_some() {
+                options.outgoing_access_control.clone()
+            } else {
+                Arc::new(DenyAll)
+            };
 
         options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
 
-        let worker = Self::new(registry, hostname_port, options);
+        let worker = Self::new(registry, peer, options);
         WorkerBuilder::new(worker)
             .with_address(address)
             .with_incoming_access_control_arc(access_control)
-            .with_outgoing_access_control(DenyAll)
+            .with_outgoing_access_control_arc(outgoing_access_control)
             .start(ctx)
             .await?;
 
@@ -52,6 +63,37 @@ impl TcpOutletListenWorker {
     }
 }
 
+impl TcpOutletListenWorker {
+    /// Let the Inlet know that its requested destination is not allowed
+    async fn refuse_connection(&self, ctx: &Context, return_route: Route) -> Result<()> {
+        ctx.send(
+            return_route,
+            PortalMessage::Disconnect.to_neutral_message()?,
+        )
+        .await
+    }
+
+    /// Create the PROXY protocol header sent to the destination, with the credential
+    /// attributes of the Inlet if a provider is configured
+    async fn create_proxy_protocol_header(
+        &self,
+        source_addr: Option<SocketAddr>,
+        their_identifier: Option<&LocalInfoIdentifier>,
+    ) -> Result<ProxyProtocolHeader> {
+        let attributes = match (&self.options.identity_attributes_provider, their_identifier) {
+            (Some(provider), Some(their_identifier)) => {
+                provider.get_attributes(their_identifier).await?
+            }
+            _ => vec![],
+        };
+        Ok(ProxyProtocolHeader::new(
+            source_addr,
+            their_identifier,
+            attributes,
+        ))
+    }
+}
+
 #[async_trait]
 impl Worker for TcpOutletListenWorker {
     type Context = Context;
@@ -86,10 +128,48 @@ impl Worker for TcpOutletListenWorker {
         let body = msg.payload;
         let msg = PortalMessage::decode(&body)?;
 
-        if !matches!(msg, PortalMessage::Ping) {
-            return Err(TransportError::Protocol)?;
+        let destination_access_control = &self.options.destination_access_control;
+        let (peer, source_addr) = match msg {
+            PortalMessage::Ping(source_addr) => (self.peer.clone(), source_addr),
+            PortalMessage::Connect(destination, source_addr)
+                if destination_access_control.is_some() =>
+            {
+                (PortalPeer::Tcp(destination.parse()?), source_addr)
+            }
+            _ => return Err(TransportError::Protocol)?,
+        };
+
+        // A dynamic Outlet checks all the destinations, including its default one
+        if let (Some(destination_access_control), PortalPeer::Tcp(hostname_port)) =
+            (destination_access_control, &peer)
+        {
+            if !destination_access_control
+                .is_authorized(their_identifier.as_ref(), hostname_port)
+                .await?
+            {
+                warn!(
+                    "Connection to {hostname_port} refused for {:?}",
+                    their_identifier.as_ref().map(|i| i.to_string())
+                );
+                return self.refuse_connection(ctx, return_route).await;
+            }
         }
 
+        let proxy_protocol_header = if self.options.proxy_protocol {
+            match self
+                .create_proxy_protocol_header(source_addr, their_identifier.as_ref())
+                .await
+            {
+                Ok(header) => Some(header),
+                Err(err) => {
+                    warn!("Cannot create the PROXY protocol header for {peer}: {err}");
+                    return self.refuse_connection(ctx, return_route).await;
+                }
+            }
+        } else {
+            None
+        };
+
         let addresses = Addresses::generate(PortalType::Outlet);
 
         TcpOutletOptions::setup_flow_control_for_outlet(ctx.flow_controls(), &addresses, &src_addr);
@@ -97,13 +177,15 @@ impl Worker for TcpOutletListenWorker {
         TcpPortalWorker::start_new_outlet(
             ctx,
             self.registry.clone(),
-            self.hostname_port.clone(),
+            peer,
             self.options.tls,
+            proxy_protocol_header,
             return_route.clone(),
             their_identifier,
             addresses.clone(),
             self.options.incoming_access_control.clone(),
             self.options.outgoing_access_control.clone(),
+            self.options.metrics.clone(),
         )
         .await?;
 
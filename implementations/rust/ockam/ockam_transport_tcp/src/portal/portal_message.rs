@@ -1,3 +1,4 @@
+use core::net::SocketAddr;
 use ockam_core::bare::{read_slice, write_slice};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Encodable, Encoded, Message, NeutralMessage};
@@ -6,8 +7,9 @@ use serde::{Deserialize, Serialize};
 /// A command message type for a Portal
 #[derive(Debug, PartialEq, Eq)]
 pub enum PortalMessage<'de> {
-    /// First message that Inlet sends to the Outlet
-    Ping,
+    /// First message that Inlet sends to the Outlet,
+    /// with the address of the Inlet client, if known
+    Ping(Option<SocketAddr>),
     /// First message that Outlet sends to the Inlet
     Pong,
     /// Message to indicate that connection from Outlet to the target,
@@ -18,6 +20,9 @@ pub enum PortalMessage<'de> {
     //  require reliable channel anyways. And if PortalMessage is sent over a channel that
     //  guarantees ordering, we don't need route_index
     Payload(&'de [u8], Option<u16>),
+    /// First message that a SOCKS5 Inlet sends to a dynamic Outlet,
+    /// with the `host:port` destination requested by the client and the address of the client
+    Connect(&'de str, Option<SocketAddr>),
 }
 
 impl<'de> PortalMessage<'de> {
@@ -33,7 +38,7 @@ impl<'de> PortalMessage<'de> {
         let enum_variant = slice.get(0)?;
         let mut index = 1;
         match enum_variant {
-            0 => Some(PortalMessage::Ping),
+            0 => Some(PortalMessage::Ping(Self::read_source(slice, &mut index)?)),
             1 => Some(PortalMessage::Pong),
             2 => Some(PortalMessage::Disconnect),
             3 => {
@@ -56,10 +61,27 @@ impl<'de> PortalMessage<'de> {
                     None
                 }
             }
+            4 => {
+                let destination = read_slice(slice, &mut index)?;
+                Some(PortalMessage::Connect(
+                    core::str::from_utf8(destination).ok()?,
+                    Self::read_source(slice, &mut index)?,
+                ))
+            }
             _ => None,
         }
     }
 
+    /// Read the optional address of the Inlet client, which is absent in older messages
+    fn read_source(slice: &'de [u8], index: &mut usize) -> Option<Option<SocketAddr>> {
+        if slice.get(*index) != Some(&1) {
+            return Some(None);
+        }
+        *index += 1;
+        let source = read_slice(slice, index)?;
+        Some(Some(core::str::from_utf8(source).ok()?.parse().ok()?))
+    }
+
     /// Shortcut to encode a PortalMessage into a NeutralMessage
     pub fn to_neutral_message(self) -> ockam_core::Result<NeutralMessage> {
         Ok(NeutralMessage::from(self.encode()?))
@@ -76,7 +98,11 @@ impl Encodable for PortalMessage<'_> {
 impl PortalMessage<'_> {
     fn internal_encode(self) -> std::io::Result<Encoded> {
         match self {
-            PortalMessage::Ping => Ok(vec![0]),
+            PortalMessage::Ping(source) => {
+                let mut vec = vec![0];
+                Self::write_source(&mut vec, source);
+                Ok(vec)
+            }
             PortalMessage::Pong => Ok(vec![1]),
             PortalMessage::Disconnect => Ok(vec![2]),
             PortalMessage::Payload(payload, counter) => {
@@ -96,6 +122,20 @@ impl PortalMessage<'_> {
                 // }
                 Ok(vec)
             }
+            PortalMessage::Connect(destination, source) => {
+                let mut vec = vec![4];
+                write_slice(&mut vec, destination.as_bytes());
+                Self::write_source(&mut vec, source);
+                Ok(vec)
+            }
+        }
+    }
+
+    /// The address is only appended when known, so that older Outlets can decode the message
+    fn write_source(vec: &mut Vec<u8>, source: Option<SocketAddr>) {
+        if let Some(source) = source {
+            vec.push(1); // has source
+            write_slice(vec, source.to_string().as_bytes());
         }
     }
 }
@@ -131,7 +171,7 @@ mod test {
 
         let encoded = PortalMessageV1::encode(PortalMessageV1::Ping).unwrap();
         let decoded = PortalMessage::decode(&encoded).unwrap();
-        assert!(matches!(decoded, PortalMessage::Ping));
+        assert!(matches!(decoded, PortalMessage::Ping(None)));
 
         let encoded = PortalMessageV1::encode(PortalMessageV1::Pong).unwrap();
         let decoded = PortalMessage::decode(&encoded).unwrap();
@@ -154,7 +194,12 @@ mod test {
     fn newer_message_can_be_decoded() {
         let payload = "hello".as_bytes().to_vec();
 
-        let encoded = PortalMessage::encode(PortalMessage::Ping).unwrap();
+        let encoded = PortalMessage::encode(PortalMessage::Ping(None)).unwrap();
+        let decoded = PortalMessageV1::decode(&encoded).unwrap();
+        assert!(matches!(decoded, PortalMessageV1::Ping));
+
+        let source = "10.0.0.1:50000".parse().unwrap();
+        let encoded = PortalMessage::encode(PortalMessage::Ping(Some(source))).unwrap();
         let decoded = PortalMessageV1::decode(&encoded).unwrap();
         assert!(matches!(decoded, PortalMessageV1::Ping));
 
@@ -180,9 +225,9 @@ mod test {
     fn newer_message_can_be_encoded() {
         let payload = "hello".as_bytes().to_vec();
 
-        let encoded = PortalMessage::encode(PortalMessage::Ping).unwrap();
+        let encoded = PortalMessage::encode(PortalMessage::Ping(None)).unwrap();
         let decoded = PortalMessage::decode(&encoded).unwrap();
-        assert!(matches!(decoded, PortalMessage::Ping));
+        assert!(matches!(decoded, PortalMessage::Ping(None)));
 
         let encoded = PortalMessage::encode(PortalMessage::Pong).unwrap();
         let decoded = PortalMessage::decode(&encoded).unwrap();
@@ -210,4 +255,26 @@ mod test {
             panic!("Decoded message is not a Payload");
         }
     }
+
+    #[test]
+    fn connect_message_can_be_encoded() {
+        let encoded =
+            PortalMessage::encode(PortalMessage::Connect("db.internal:5432", None)).unwrap();
+        let decoded = PortalMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded, PortalMessage::Connect("db.internal:5432", None));
+    }
+
+    #[test]
+    fn source_address_can_be_encoded() {
+        let source = Some("[::1]:50000".parse().unwrap());
+
+        let encoded = PortalMessage::encode(PortalMessage::Ping(source)).unwrap();
+        let decoded = PortalMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded, PortalMessage::Ping(source));
+
+        let encoded =
+            PortalMessage::encode(PortalMessage::Connect("db.internal:5432", source)).unwrap();
+        let decoded = PortalMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded, PortalMessage::Connect("db.internal:5432", source));
+    }
 }
@@ -1,20 +1,37 @@
 use crate::portal::addresses::{Addresses, PortalType};
+#[cfg(unix)]
+use crate::portal::portal_worker::ReadHalfMaybeTls::ReadHalfUnix;
 use crate::portal::portal_worker::ReadHalfMaybeTls::{ReadHalfNoTls, ReadHalfWithTls};
+#[cfg(unix)]
+use crate::portal::portal_worker::WriteHalfMaybeTls::WriteHalfUnix;
 use crate::portal::portal_worker::WriteHalfMaybeTls::{WriteHalfNoTls, WriteHalfWithTls};
-use crate::transport::{connect, connect_tls};
-use crate::{portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::transport::{create_tcp_stream, start_tls};
+use crate::{
+    portal::{
+        read_socks5_request, write_socks5_reply, ProxyProtocolHeader, Socks5Reply,
+        TcpPortalRecvProcessor,
+    },
+    PortalInternalMessage, PortalMessage, TcpPortalMetrics, TcpRegistry,
+};
+use core::fmt::{Display, Formatter};
+use core::net::SocketAddr;
 use ockam_core::compat::{boxed::Box, sync::Arc};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
     async_trait, AllowOnwardAddress, AllowSourceAddress, Decodable, DenyAll, IncomingAccessControl,
     LocalInfoIdentifier, Mailbox, Mailboxes, OutgoingAccessControl, SecureChannelLocalInfo,
 };
-use ockam_core::{Any, Result, Route, Routed, Worker};
+use ockam_core::{Any, Error, Result, Route, Routed, Worker};
 use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::{HostnamePort, TransportError};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWriteExt, ReadHalf, WriteHalf};
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::{unix, UnixStream};
 use tokio_rustls::TlsStream;
 use tracing::{debug, info, instrument, trace, warn};
 
@@ -44,7 +61,7 @@ pub(crate) struct TcpPortalWorker {
     their_identifier: Option<LocalInfoIdentifier>,
     write_half: Option<WriteHalfMaybeTls>,
     read_half: Option<ReadHalfMaybeTls>,
-    hostname_port: HostnamePort,
+    peer: PortalPeer,
     addresses: Addresses,
     remote_route: Option<Route>,
     is_disconnecting: bool,
@@ -52,16 +69,50 @@ pub(crate) struct TcpPortalWorker {
     last_received_packet_counter: u16,
     outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     is_tls: bool,
+    // Set for inlets which read their destination from a SOCKS5 request
+    is_socks5: bool,
+    // Address of the client connected to an inlet
+    source_addr: Option<SocketAddr>,
+    // Set for outlets which send a PROXY protocol header to their destination
+    proxy_protocol_header: Option<ProxyProtocolHeader>,
+    metrics: Arc<TcpPortalMetrics>,
+    // Set once the connection has been counted as opened in the metrics
+    is_connection_counted: bool,
 }
 
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum ReadHalfMaybeTls {
     ReadHalfNoTls(OwnedReadHalf),
     ReadHalfWithTls(ReadHalf<TlsStream<TcpStream>>),
+    #[cfg(unix)]
+    ReadHalfUnix(unix::OwnedReadHalf),
 }
 
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum WriteHalfMaybeTls {
     WriteHalfNoTls(OwnedWriteHalf),
     WriteHalfWithTls(WriteHalf<TlsStream<TcpStream>>),
+    #[cfg(unix)]
+    WriteHalfUnix(unix::OwnedWriteHalf),
+}
+
+/// Other end of the stream of a portal worker: the destination of an Outlet,
+/// or the client connected to an Inlet
+#[derive(Clone, Debug)]
+pub(crate) enum PortalPeer {
+    Tcp(HostnamePort),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl Display for PortalPeer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PortalPeer::Tcp(hostname_port) => write!(f, "{hostname_port}"),
+            #[cfg(unix)]
+            PortalPeer::Unix(path) => write!(f, "{}", path.display()),
+        }
+    }
 }
 
 impl TcpPortalWorker {
@@ -72,24 +123,31 @@ impl TcpPortalWorker {
         ctx: &Context,
         registry: TcpRegistry,
         streams: (ReadHalfMaybeTls, WriteHalfMaybeTls),
-        hostname_port: HostnamePort,
+        peer: PortalPeer,
+        source_addr: Option<SocketAddr>,
+        is_socks5: bool,
         ping_route: Route,
         their_identifier: Option<LocalInfoIdentifier>,
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>, // To propagate to the receiver
+        metrics: Arc<TcpPortalMetrics>,
     ) -> Result<()> {
         Self::start(
             ctx,
             registry,
-            hostname_port,
+            peer,
             false,
+            is_socks5,
+            source_addr,
+            None,
             State::SendPing { ping_route },
             their_identifier,
             Some(streams),
             addresses,
             incoming_access_control,
             outgoing_access_control,
+            metrics,
         )
         .await
     }
@@ -100,25 +158,31 @@ impl TcpPortalWorker {
     pub(super) async fn start_new_outlet(
         ctx: &Context,
         registry: TcpRegistry,
-        hostname_port: HostnamePort,
+        peer: PortalPeer,
         tls: bool,
+        proxy_protocol_header: Option<ProxyProtocolHeader>,
         pong_route: Route,
         their_identifier: Option<LocalInfoIdentifier>,
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        metrics: Arc<TcpPortalMetrics>,
     ) -> Result<()> {
         Self::start(
             ctx,
             registry,
-            hostname_port,
+            peer,
             tls,
+            false,
+            None,
+            proxy_protocol_header,
             State::SendPong { pong_route },
             their_identifier,
             None,
             addresses,
             incoming_access_control,
             outgoing_access_control,
+            metrics,
         )
         .await
     }
@@ -129,14 +193,18 @@ impl TcpPortalWorker {
     async fn start(
         ctx: &Context,
         registry: TcpRegistry,
-        hostname_port: HostnamePort,
+        peer: PortalPeer,
         is_tls: bool,
+        is_socks5: bool,
+        source_addr: Option<SocketAddr>,
+        proxy_protocol_header: Option<ProxyProtocolHeader>,
         state: State,
         their_identifier: Option<LocalInfoIdentifier>,
         streams: Option<(ReadHalfMaybeTls, WriteHalfMaybeTls)>,
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        metrics: Arc<TcpPortalMetrics>,
     ) -> Result<()> {
         let portal_type = if streams.is_some() {
             PortalType::Inlet
@@ -152,7 +220,7 @@ impl TcpPortalWorker {
         let (rx, tx) = match streams {
             // A TcpStream is provided in case of an inlet
             Some((rx, tx)) => {
-                debug!("Connected to {}", &hostname_port);
+                debug!("Connected to {}", &peer);
                 (Some(rx), Some(tx))
             }
             None => (None, None),
@@ -165,14 +233,19 @@ impl TcpPortalWorker {
             their_identifier,
             write_half: tx,
             read_half: rx,
-            hostname_port,
+            peer,
             addresses: addresses.clone(),
             remote_route: None,
             is_disconnecting: false,
             portal_type,
             last_received_packet_counter: u16::MAX,
             is_tls,
+            is_socks5,
+            source_addr,
+            proxy_protocol_header,
             outgoing_access_control: outgoing_access_control.clone(),
+            metrics,
+            is_connection_counted: false,
         };
 
         let internal_mailbox = Mailbox::new(
@@ -197,6 +270,9 @@ impl TcpPortalWorker {
     }
 }
 
+/// Maximum duration for a SOCKS5 client to send its request
+const SOCKS5_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
 enum DisconnectionReason {
     FailedTx,
     FailedRx,
@@ -216,6 +292,8 @@ impl TcpPortalWorker {
             match rx {
                 ReadHalfNoTls(rx) => self.start_receive_processor(ctx, onward_route, rx).await,
                 ReadHalfWithTls(rx) => self.start_receive_processor(ctx, onward_route, rx).await,
+                #[cfg(unix)]
+                ReadHalfUnix(rx) => self.start_receive_processor(ctx, onward_route, rx).await,
             }
         } else {
             Err(TransportError::PortalInvalidState)?
@@ -234,6 +312,7 @@ impl TcpPortalWorker {
             rx,
             self.addresses.clone(),
             onward_route,
+            self.metrics.clone(),
         );
 
         let remote = Mailbox::new(
@@ -356,38 +435,117 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// Read the destination requested by the SOCKS5 client
     #[instrument(skip_all)]
-    async fn handle_send_ping(&self, ctx: &Context, ping_route: Route) -> Result<State> {
+    async fn read_socks5_destination(&mut self) -> Result<HostnamePort> {
+        let (Some(ReadHalfNoTls(rx)), Some(WriteHalfNoTls(tx))) =
+            (&mut self.read_half, &mut self.write_half)
+        else {
+            return Err(TransportError::PortalInvalidState)?;
+        };
+
+        match tokio::time::timeout(SOCKS5_HANDSHAKE_TIMEOUT, read_socks5_request(rx, tx)).await {
+            Ok(destination) => destination,
+            Err(_) => Err(Error::new(
+                Origin::Transport,
+                Kind::Timeout,
+                "The SOCKS5 client did not send its request in time",
+            )),
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn handle_send_ping(&mut self, ctx: &Context, ping_route: Route) -> Result<State> {
         // Force creation of Outlet on the other side
-        ctx.send_from_address(
-            ping_route,
-            PortalMessage::Ping.to_neutral_message()?,
-            self.addresses.sender_remote.clone(),
-        )
-        .await?;
+        let message = if self.is_socks5 {
+            let destination = self.read_socks5_destination().await?;
+            PortalMessage::Connect(&destination.to_string(), self.source_addr)
+                .to_neutral_message()?
+        } else {
+            PortalMessage::Ping(self.source_addr).to_neutral_message()?
+        };
+        ctx.send_from_address(ping_route, message, self.addresses.sender_remote.clone())
+            .await?;
 
         debug!("Inlet at: {} sent ping", self.addresses.sender_internal);
 
         Ok(State::ReceivePong)
     }
 
-    #[instrument(skip_all)]
-    async fn handle_send_pong(&mut self, ctx: &Context, pong_route: Route) -> Result<State> {
-        if self.write_half.is_some() {
-            // Should not happen
-            return Err(TransportError::PortalInvalidState)?;
+    /// Connect the Outlet to its destination
+    async fn connect(&mut self) -> Result<()> {
+        debug!("Connect to {}", self.peer);
+        match self.peer.clone() {
+            PortalPeer::Tcp(hostname_port) => self.connect_tcp(&hostname_port).await,
+            #[cfg(unix)]
+            PortalPeer::Unix(path) => self.connect_unix(&path).await,
+        }
+    }
+
+    /// Connect the Outlet to a TCP destination
+    async fn connect_tcp(&mut self, hostname_port: &HostnamePort) -> Result<()> {
+        let mut stream = create_tcp_stream(hostname_port).await?;
+
+        // The PROXY protocol header is sent before the TLS handshake
+        if let Some(header) = &self.proxy_protocol_header {
+            let header = header.encode(stream.peer_addr().ok())?;
+            stream
+                .write_all(&header)
+                .await
+                .map_err(TransportError::from)?;
         }
+
         if self.is_tls {
-            debug!("Connect to {} via TLS", &self.hostname_port);
-            let (rx, tx) = connect_tls(&self.hostname_port).await?;
+            debug!("Start TLS with {}", hostname_port);
+            let (rx, tx) = start_tls(stream, hostname_port).await?;
             self.write_half = Some(WriteHalfWithTls(tx));
             self.read_half = Some(ReadHalfWithTls(rx));
         } else {
-            debug!("Connect to {}", self.hostname_port);
-            let (rx, tx) = connect(&self.hostname_port).await?;
+            let (rx, tx) = stream.into_split();
             self.write_half = Some(WriteHalfNoTls(tx));
             self.read_half = Some(ReadHalfNoTls(rx));
         }
+        Ok(())
+    }
+
+    /// Connect the Outlet to a Unix domain socket
+    #[cfg(unix)]
+    async fn connect_unix(&mut self, path: &Path) -> Result<()> {
+        let mut stream = UnixStream::connect(path)
+            .await
+            .map_err(TransportError::from)?;
+
+        // There is no TCP destination address, only the PROXY protocol TLVs are meaningful
+        if let Some(header) = &self.proxy_protocol_header {
+            let header = header.encode(None)?;
+            stream
+                .write_all(&header)
+                .await
+                .map_err(TransportError::from)?;
+        }
+
+        let (rx, tx) = stream.into_split();
+        self.write_half = Some(WriteHalfUnix(tx));
+        self.read_half = Some(ReadHalfUnix(rx));
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    async fn handle_send_pong(&mut self, ctx: &Context, pong_route: Route) -> Result<State> {
+        if self.write_half.is_some() {
+            // Should not happen
+            return Err(TransportError::PortalInvalidState)?;
+        }
+        if let Err(err) = self.connect().await {
+            // Let the Inlet know that it won't receive a pong
+            ctx.send_from_address(
+                pong_route,
+                PortalMessage::Disconnect.to_neutral_message()?,
+                self.addresses.sender_remote.clone(),
+            )
+            .await?;
+            return Err(err);
+        }
 
         // Respond to Inlet before starting the processor but
         // after the connection has been established
@@ -436,6 +594,8 @@ impl Worker for TcpPortalWorker {
 
         self.registry
             .add_portal_worker(&self.addresses.sender_remote);
+        self.metrics.connection_opened();
+        self.is_connection_counted = true;
 
         Ok(())
     }
@@ -444,6 +604,9 @@ impl Worker for TcpPortalWorker {
     async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
         self.registry
             .remove_portal_worker(&self.addresses.sender_remote);
+        if self.is_connection_counted {
+            self.metrics.connection_closed();
+        }
 
         Ok(())
     }
@@ -491,10 +654,11 @@ impl Worker for TcpPortalWorker {
                 if !remote_packet {
                     return Err(TransportError::PortalInvalidState)?;
                 };
-                if PortalMessage::decode(&payload)? != PortalMessage::Pong {
-                    return Err(TransportError::Protocol)?;
-                };
-                self.handle_receive_pong(ctx, return_route).await
+                match PortalMessage::decode(&payload)? {
+                    PortalMessage::Pong => self.handle_receive_pong(ctx, return_route).await,
+                    PortalMessage::Disconnect => self.handle_connection_refused(ctx).await,
+                    _ => Err(TransportError::Protocol)?,
+                }
             }
             State::Initialized => {
                 trace!(
@@ -515,7 +679,9 @@ impl Worker for TcpPortalWorker {
                             self.start_disconnection(ctx, DisconnectionReason::Remote)
                                 .await
                         }
-                        PortalMessage::Ping | PortalMessage::Pong => {
+                        PortalMessage::Ping(_)
+                        | PortalMessage::Pong
+                        | PortalMessage::Connect(..) => {
                             return Err(TransportError::Protocol)?;
                         }
                     }
@@ -537,6 +703,11 @@ impl Worker for TcpPortalWorker {
 impl TcpPortalWorker {
     #[instrument(skip_all)]
     async fn handle_receive_pong(&mut self, ctx: &Context, return_route: Route) -> Result<()> {
+        if self.is_socks5 {
+            if let Some(WriteHalfNoTls(tx)) = &mut self.write_half {
+                write_socks5_reply(tx, Socks5Reply::Succeeded).await?;
+            }
+        }
         self.start_receiver(ctx, return_route.clone()).await?;
         debug!("Inlet at: {} received pong", self.addresses.sender_internal);
         self.remote_route = Some(return_route);
@@ -544,6 +715,23 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// The Outlet could not connect to its destination, or refused the requested destination
+    #[instrument(skip_all)]
+    async fn handle_connection_refused(&mut self, ctx: &Context) -> Result<()> {
+        info!(
+            "Outlet refused the connection for Inlet at: {}",
+            self.addresses.sender_internal
+        );
+        if self.is_socks5 {
+            if let Some(WriteHalfNoTls(tx)) = &mut self.write_half {
+                // The client is disconnected right after, so a failed reply is not an issue
+                let _ = write_socks5_reply(tx, Socks5Reply::GeneralFailure).await;
+            }
+        }
+        self.is_disconnecting = true;
+        self.stop_sender(ctx).await
+    }
+
     #[instrument(skip_all)]
     async fn handle_disconnect(&mut self, ctx: &Context) -> Result<()> {
         info!(
@@ -573,14 +761,19 @@ impl TcpPortalWorker {
         let result = match tx {
             WriteHalfNoTls(tx) => tx.write_all(payload).await,
             WriteHalfWithTls(tx) => tx.write_all(payload).await,
+            #[cfg(unix)]
+            WriteHalfUnix(tx) => tx.write_all(payload).await,
         };
-        if let Err(err) = result {
-            warn!(
-                "Failed to send message to peer {} with error: {}",
-                self.hostname_port, err
-            );
-            self.start_disconnection(ctx, DisconnectionReason::FailedTx)
-                .await?;
+        match result {
+            Ok(()) => self.metrics.add_bytes_sent(payload.len()),
+            Err(err) => {
+                warn!(
+                    "Failed to send message to peer {} with error: {}",
+                    self.peer, err
+                );
+                self.start_disconnection(ctx, DisconnectionReason::FailedTx)
+                    .await?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,176 @@
+use core::fmt::Debug;
+use core::net::{IpAddr, SocketAddr};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, compat::boxed::Box, Error, LocalInfoIdentifier, Result};
+
+/// Signature starting every PROXY protocol v2 header
+const SIGNATURE: [u8; 12] = [
+    0x0D, 0x0A, 0x0D, 0x0A, 0x00, 0x0D, 0x0A, 0x51, 0x55, 0x49, 0x54, 0x0A,
+];
+/// Version 2, PROXY command
+const VERSION_2_PROXY: u8 = 0x21;
+const AF_UNSPEC: u8 = 0x00;
+const TCP_OVER_IPV4: u8 = 0x11;
+const TCP_OVER_IPV6: u8 = 0x21;
+
+/// Type of the TLV carrying the Ockam identifier of the inlet, as a string.
+/// The types between `0xE0` and `0xEF` are reserved for custom TLVs by the specification
+pub const PP2_TYPE_OCKAM_IDENTIFIER: u8 = 0xE0;
+
+/// Type of the TLVs carrying the credential attributes of the inlet, as `name=value` strings.
+/// There is one TLV per attribute
+pub const PP2_TYPE_OCKAM_ATTRIBUTE: u8 = 0xE1;
+
+/// Provide the credential attributes of an identity, so that they can be sent by an outlet
+/// to its target in the PROXY protocol header.
+#[async_trait]
+pub trait IdentityAttributesProvider: Debug + Send + Sync + 'static {
+    /// Return the attributes, as `(name, value)` pairs, of the identity on the other side
+    /// of the secure channel
+    async fn get_attributes(
+        &self,
+        their_identifier: &LocalInfoIdentifier,
+    ) -> Result<Vec<(String, String)>>;
+}
+
+/// PROXY protocol v2 header sent by an outlet before any data, see
+/// <https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt>
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ProxyProtocolHeader {
+    source: Option<SocketAddr>,
+    tlvs: Vec<(u8, Vec<u8>)>,
+}
+
+impl ProxyProtocolHeader {
+    /// Create a header for the connection of an inlet client
+    pub(crate) fn new(
+        source: Option<SocketAddr>,
+        their_identifier: Option<&LocalInfoIdentifier>,
+        attributes: Vec<(String, String)>,
+    ) -> Self {
+        let mut tlvs = vec![];
+        if let Some(their_identifier) = their_identifier {
+            tlvs.push((
+                PP2_TYPE_OCKAM_IDENTIFIER,
+                their_identifier.to_string().into_bytes(),
+            ));
+        }
+        for (name, value) in attributes {
+            tlvs.push((
+                PP2_TYPE_OCKAM_ATTRIBUTE,
+                format!("{name}={value}").into_bytes(),
+            ));
+        }
+        Self { source, tlvs }
+    }
+
+    /// Encode the header, given the address of the target.
+    /// The addresses are only sent if both of them are known, IPv4 addresses are mapped
+    /// to IPv6 addresses when the other address is an IPv6 address
+    pub(crate) fn encode(&self, destination: Option<SocketAddr>) -> Result<Vec<u8>> {
+        let (family, addresses) = match (self.source, destination) {
+            (Some(source), Some(destination)) => encode_addresses(source, destination),
+            _ => (AF_UNSPEC, vec![]),
+        };
+
+        let mut tlvs = vec![];
+        for (tlv_type, value) in &self.tlvs {
+            let length = u16::try_from(value.len()).map_err(|_| header_too_large())?;
+            tlvs.push(*tlv_type);
+            tlvs.extend_from_slice(&length.to_be_bytes());
+            tlvs.extend_from_slice(value);
+        }
+
+        let length = u16::try_from(addresses.len() + tlvs.len()).map_err(|_| header_too_large())?;
+        let mut header = Vec::with_capacity(16 + length as usize);
+        header.extend_from_slice(&SIGNATURE);
+        header.push(VERSION_2_PROXY);
+        header.push(family);
+        header.extend_from_slice(&length.to_be_bytes());
+        header.extend_from_slice(&addresses);
+        header.extend_from_slice(&tlvs);
+        Ok(header)
+    }
+}
+
+fn encode_addresses(source: SocketAddr, destination: SocketAddr) -> (u8, Vec<u8>) {
+    let mut addresses = vec![];
+    match (source.ip(), destination.ip()) {
+        (IpAddr::V4(source_ip), IpAddr::V4(destination_ip)) => {
+            addresses.extend_from_slice(&source_ip.octets());
+            addresses.extend_from_slice(&destination_ip.octets());
+            addresses.extend_from_slice(&source.port().to_be_bytes());
+            addresses.extend_from_slice(&destination.port().to_be_bytes());
+            (TCP_OVER_IPV4, addresses)
+        }
+        (source_ip, destination_ip) => {
+            addresses.extend_from_slice(&to_ipv6(source_ip).octets());
+            addresses.extend_from_slice(&to_ipv6(destination_ip).octets());
+            addresses.extend_from_slice(&source.port().to_be_bytes());
+            addresses.extend_from_slice(&destination.port().to_be_bytes());
+            (TCP_OVER_IPV6, addresses)
+        }
+    }
+}
+
+fn to_ipv6(ip: IpAddr) -> core::net::Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+fn header_too_large() -> Error {
+    Error::new(
+        Origin::Transport,
+        Kind::Invalid,
+        "The PROXY protocol header is too large",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::Result;
+
+    #[test]
+    fn encode_a_header_with_ipv4_addresses_and_tlvs() -> Result<()> {
+        let header = ProxyProtocolHeader::new(
+            Some("10.0.0.1:50000".parse().unwrap()),
+            None,
+            vec![("role".into(), "admin".into())],
+        );
+        let encoded = header.encode(Some("127.0.0.1:5432".parse().unwrap()))?;
+
+        let mut expected = SIGNATURE.to_vec();
+        expected.extend_from_slice(&[VERSION_2_PROXY, TCP_OVER_IPV4, 0, 12 + 3 + 10]);
+        expected.extend_from_slice(&[10, 0, 0, 1, 127, 0, 0, 1]);
+        expected.extend_from_slice(&50000u16.to_be_bytes());
+        expected.extend_from_slice(&5432u16.to_be_bytes());
+        expected.extend_from_slice(&[PP2_TYPE_OCKAM_ATTRIBUTE, 0, 10]);
+        expected.extend_from_slice(b"role=admin");
+        assert_eq!(encoded, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn encode_a_header_with_mixed_or_unknown_addresses() -> Result<()> {
+        let header =
+            ProxyProtocolHeader::new(Some("10.0.0.1:50000".parse().unwrap()), None, vec![]);
+        let encoded = header.encode(Some("[::1]:5432".parse().unwrap()))?;
+        assert_eq!(encoded[13], TCP_OVER_IPV6);
+        assert_eq!(encoded[14..16], [0, 36]);
+        assert_eq!(
+            encoded[16..32],
+            "10.0.0.1"
+                .parse::<core::net::Ipv4Addr>()
+                .unwrap()
+                .to_ipv6_mapped()
+                .octets()
+        );
+
+        let encoded = header.encode(None)?;
+        assert_eq!(encoded[12..], [VERSION_2_PROXY, AF_UNSPEC, 0, 0]);
+        Ok(())
+    }
+}
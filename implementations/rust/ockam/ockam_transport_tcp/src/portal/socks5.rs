@@ -0,0 +1,238 @@
+use core::net::{Ipv4Addr, Ipv6Addr};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_transport_core::{HostnamePort, TransportError};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::{debug, instrument};
+
+const SOCKS5_VERSION: u8 = 0x05;
+const NO_AUTHENTICATION: u8 = 0x00;
+const NO_ACCEPTABLE_METHODS: u8 = 0xff;
+const CONNECT_COMMAND: u8 = 0x01;
+const IPV4_ADDRESS: u8 = 0x01;
+const DOMAIN_NAME: u8 = 0x03;
+const IPV6_ADDRESS: u8 = 0x04;
+
+/// Reply codes sent to a SOCKS5 client, see RFC 1928, section 6
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Socks5Reply {
+    /// The connection to the destination is established
+    Succeeded = 0x00,
+    /// The outlet refused or failed to connect to the destination
+    GeneralFailure = 0x01,
+    /// Only the CONNECT command is supported
+    CommandNotSupported = 0x07,
+    /// The address type of the destination is unknown
+    AddressTypeNotSupported = 0x08,
+}
+
+/// Run the server side of a SOCKS5 handshake, without authentication,
+/// and return the destination of the CONNECT request sent by the client.
+///
+/// The client must then be sent a reply with [`write_socks5_reply`].
+#[instrument(skip_all)]
+pub(crate) async fn read_socks5_request<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    rx: &mut R,
+    tx: &mut W,
+) -> Result<HostnamePort> {
+    // version, number of methods, methods
+    let version = read_u8(rx).await?;
+    if version != SOCKS5_VERSION {
+        return Err(socks5_error(format!(
+            "Unsupported SOCKS version: {version}"
+        )));
+    }
+    let methods_count = read_u8(rx).await?;
+    let mut methods = vec![0; methods_count as usize];
+    rx.read_exact(&mut methods)
+        .await
+        .map_err(TransportError::from)?;
+
+    if !methods.contains(&NO_AUTHENTICATION) {
+        write_all(tx, &[SOCKS5_VERSION, NO_ACCEPTABLE_METHODS]).await?;
+        return Err(socks5_error(
+            "The SOCKS5 client does not support connections without authentication",
+        ));
+    }
+    write_all(tx, &[SOCKS5_VERSION, NO_AUTHENTICATION]).await?;
+
+    // version, command, reserved, address type
+    let mut header = [0; 4];
+    rx.read_exact(&mut header)
+        .await
+        .map_err(TransportError::from)?;
+    if header[0] != SOCKS5_VERSION {
+        return Err(socks5_error(format!(
+            "Unsupported SOCKS version: {}",
+            header[0]
+        )));
+    }
+    if header[1] != CONNECT_COMMAND {
+        write_socks5_reply(tx, Socks5Reply::CommandNotSupported).await?;
+        return Err(socks5_error(format!(
+            "Unsupported SOCKS5 command: {}",
+            header[1]
+        )));
+    }
+
+    let hostname = match header[3] {
+        IPV4_ADDRESS => {
+            let mut ip = [0; 4];
+            rx.read_exact(&mut ip).await.map_err(TransportError::from)?;
+            Ipv4Addr::from(ip).to_string()
+        }
+        IPV6_ADDRESS => {
+            let mut ip = [0; 16];
+            rx.read_exact(&mut ip).await.map_err(TransportError::from)?;
+            format!("[{}]", Ipv6Addr::from(ip))
+        }
+        DOMAIN_NAME => {
+            let length = read_u8(rx).await?;
+            let mut domain = vec![0; length as usize];
+            rx.read_exact(&mut domain)
+                .await
+                .map_err(TransportError::from)?;
+            String::from_utf8(domain)
+                .map_err(|_| socks5_error("The SOCKS5 destination is not a valid domain name"))?
+        }
+        address_type => {
+            write_socks5_reply(tx, Socks5Reply::AddressTypeNotSupported).await?;
+            return Err(socks5_error(format!(
+                "Unsupported SOCKS5 address type: {address_type}"
+            )));
+        }
+    };
+    let port = rx.read_u16().await.map_err(TransportError::from)?;
+
+    let destination = HostnamePort::new(hostname, port);
+    debug!(%destination, "Received a SOCKS5 CONNECT request");
+    Ok(destination)
+}
+
+/// Send the reply to a SOCKS5 CONNECT request.
+/// The bound address is not known on the inlet side, so it is always sent as `0.0.0.0:0`
+pub(crate) async fn write_socks5_reply<W: AsyncWrite + Unpin>(
+    stream: &mut W,
+    reply: Socks5Reply,
+) -> Result<()> {
+    let mut message = vec![SOCKS5_VERSION, reply as u8, 0x00, IPV4_ADDRESS];
+    message.extend_from_slice(&Ipv4Addr::UNSPECIFIED.octets());
+    message.extend_from_slice(&0u16.to_be_bytes());
+    write_all(stream, &message).await
+}
+
+async fn read_u8<R: AsyncRead + Unpin>(stream: &mut R) -> Result<u8> {
+    Ok(stream.read_u8().await.map_err(TransportError::from)?)
+}
+
+async fn write_all<W: AsyncWrite + Unpin>(stream: &mut W, bytes: &[u8]) -> Result<()> {
+    Ok(stream
+        .write_all(bytes)
+        .await
+        .map_err(TransportError::from)?)
+}
+
+fn socks5_error(message: impl Into<String>) -> Error {
+    Error::new(Origin::Transport, Kind::Protocol, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{duplex, split};
+
+    #[tokio::test]
+    async fn read_a_domain_name_request() -> Result<()> {
+        let (mut client, server) = duplex(1024);
+        let (mut server_rx, mut server_tx) = split(server);
+        let mut request = vec![SOCKS5_VERSION, 1, NO_AUTHENTICATION];
+        request.extend_from_slice(&[SOCKS5_VERSION, CONNECT_COMMAND, 0, DOMAIN_NAME, 11]);
+        request.extend_from_slice(b"db.internal");
+        request.extend_from_slice(&5432u16.to_be_bytes());
+        client.write_all(&request).await.unwrap();
+
+        let destination = read_socks5_request(&mut server_rx, &mut server_tx).await?;
+        assert_eq!(destination, HostnamePort::new("db.internal", 5432));
+
+        write_socks5_reply(&mut server_tx, Socks5Reply::Succeeded).await?;
+        let mut response = [0; 12];
+        client.read_exact(&mut response).await.unwrap();
+        assert_eq!(
+            response,
+            [
+                SOCKS5_VERSION,
+                NO_AUTHENTICATION,
+                SOCKS5_VERSION,
+                0,
+                0,
+                IPV4_ADDRESS,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_ip_address_requests() -> Result<()> {
+        let (mut client, server) = duplex(1024);
+        let (mut server_rx, mut server_tx) = split(server);
+        let mut request = vec![SOCKS5_VERSION, 1, NO_AUTHENTICATION];
+        request.extend_from_slice(&[SOCKS5_VERSION, CONNECT_COMMAND, 0, IPV4_ADDRESS]);
+        request.extend_from_slice(&[10, 0, 0, 1, 0, 80]);
+        client.write_all(&request).await.unwrap();
+        let destination = read_socks5_request(&mut server_rx, &mut server_tx).await?;
+        assert_eq!(destination, HostnamePort::new("10.0.0.1", 80));
+
+        let (mut client, server) = duplex(1024);
+        let (mut server_rx, mut server_tx) = split(server);
+        let mut request = vec![SOCKS5_VERSION, 1, NO_AUTHENTICATION];
+        request.extend_from_slice(&[SOCKS5_VERSION, CONNECT_COMMAND, 0, IPV6_ADDRESS]);
+        request.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        request.extend_from_slice(&22u16.to_be_bytes());
+        client.write_all(&request).await.unwrap();
+        let destination = read_socks5_request(&mut server_rx, &mut server_tx).await?;
+        assert_eq!(destination, HostnamePort::new("[::1]", 22));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reject_unsupported_requests() -> Result<()> {
+        // username/password authentication only
+        let (mut client, server) = duplex(1024);
+        let (mut server_rx, mut server_tx) = split(server);
+        client.write_all(&[SOCKS5_VERSION, 1, 0x02]).await.unwrap();
+        assert!(read_socks5_request(&mut server_rx, &mut server_tx)
+            .await
+            .is_err());
+        let mut response = [0; 2];
+        client.read_exact(&mut response).await.unwrap();
+        assert_eq!(response, [SOCKS5_VERSION, NO_ACCEPTABLE_METHODS]);
+
+        // BIND command
+        let (mut client, server) = duplex(1024);
+        let (mut server_rx, mut server_tx) = split(server);
+        let mut request = vec![SOCKS5_VERSION, 1, NO_AUTHENTICATION];
+        request.extend_from_slice(&[SOCKS5_VERSION, 0x02, 0, IPV4_ADDRESS]);
+        client.write_all(&request).await.unwrap();
+        assert!(read_socks5_request(&mut server_rx, &mut server_tx)
+            .await
+            .is_err());
+        let mut response = [0; 12];
+        client.read_exact(&mut response).await.unwrap();
+        assert_eq!(
+            response[..4],
+            [
+                SOCKS5_VERSION,
+                NO_AUTHENTICATION,
+                SOCKS5_VERSION,
+                Socks5Reply::CommandNotSupported as u8
+            ]
+        );
+        Ok(())
+    }
+}
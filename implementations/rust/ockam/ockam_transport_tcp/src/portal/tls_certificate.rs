@@ -2,8 +2,11 @@ use core::fmt::{Debug, Display, Formatter};
 use log::warn;
 use minicbor::{Decode, Encode};
 use ockam_core::async_trait;
+use ockam_core::errcode::{Kind, Origin};
 use ockam_node::Context;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use serde::{Deserialize, Serialize};
+use std::io::BufReader;
 use std::ops::Sub;
 use std::sync::Arc;
 use std::time::Duration;
@@ -30,6 +33,40 @@ pub struct TlsCertificate {
     #[n(2)] pub private_key_pem: Vec<u8>,
 }
 
+impl TlsCertificate {
+    /// Parse the certificate chain
+    pub(crate) fn certificate_chain(&self) -> ockam_core::Result<Vec<CertificateDer<'static>>> {
+        parse_certificates(&self.full_chain_pem)
+    }
+
+    /// Parse the first PKCS#8 private key
+    pub(crate) fn private_key(&self) -> ockam_core::Result<PrivateKeyDer<'static>> {
+        let mut reader = BufReader::new(self.private_key_pem.as_slice());
+        let private_key = rustls_pemfile::pkcs8_private_keys(&mut reader).next();
+        match private_key {
+            Some(Ok(private_key)) => Ok(private_key.into()),
+            Some(Err(error)) => Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Parse,
+                error,
+            )),
+            None => Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Parse,
+                "No private key found in the provided certificate",
+            )),
+        }
+    }
+}
+
+/// Parse a list of PEM encoded certificates
+pub(crate) fn parse_certificates(pem: &[u8]) -> ockam_core::Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(pem);
+    rustls_pemfile::certs(&mut reader)
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|error| ockam_core::Error::new(Origin::Transport, Kind::Parse, error))
+}
+
 #[async_trait]
 /// TLS certificate provider abstraction, to keep the implementation opaque
 /// to the TCP transport.
@@ -0,0 +1,184 @@
+use crate::portal::addresses::{Addresses, PortalType};
+use crate::portal::{InletSharedState, PortalPeer, ReadHalfMaybeTls, WriteHalfMaybeTls};
+use crate::{portal::TcpPortalWorker, TcpInletOptions, TcpRegistry, UnixInlet};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, compat::boxed::Box, Result};
+use ockam_core::{Address, Processor, Route};
+use ockam_node::compat::asynchronous::RwLock;
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use std::fs::Permissions;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use tokio::net::UnixListener;
+use tracing::{debug, error, instrument, warn};
+
+/// A Unix domain socket Portal Inlet listen processor
+///
+/// Unix domain socket Portal Inlet listen processors are created by `TcpTransport`
+/// after a call is made to
+/// [`TcpTransport::create_unix_inlet`](crate::TcpTransport::create_unix_inlet).
+/// The socket file is removed when the processor is stopped.
+pub(crate) struct UnixInletListenProcessor {
+    registry: TcpRegistry,
+    inner: UnixListener,
+    socket_path: PathBuf,
+    inlet_shared_state: Arc<RwLock<InletSharedState>>,
+    options: TcpInletOptions,
+}
+
+impl UnixInletListenProcessor {
+    /// Start a new `UnixInletListenProcessor`
+    #[instrument(skip_all, name = "UnixInletListenProcessor::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        registry: TcpRegistry,
+        outlet_listener_route: Route,
+        socket_path: PathBuf,
+        options: TcpInletOptions,
+    ) -> Result<UnixInlet> {
+        if options.is_socks5 || options.tls_certificate_provider.is_some() {
+            return Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "A Unix domain socket inlet cannot use SOCKS5 or TLS",
+            ));
+        }
+
+        let processor_address = Address::random_tagged("UnixInletListenProcessor");
+
+        remove_stale_socket_file(&socket_path)?;
+
+        debug!(
+            "Binding UnixInletListenProcessor to {}",
+            socket_path.display()
+        );
+        let inner = match UnixListener::bind(&socket_path) {
+            Ok(inner) => inner,
+            Err(err) => {
+                error!(path = %socket_path.display(), %err, "could not bind to socket file");
+                return Err(TransportError::from(err))?;
+            }
+        };
+
+        if let Some(mode) = options.unix_socket_permissions {
+            if let Err(err) = std::fs::set_permissions(&socket_path, Permissions::from_mode(mode)) {
+                let _ = std::fs::remove_file(&socket_path);
+                return Err(TransportError::from(err))?;
+            }
+        }
+
+        let inlet_shared_state =
+            InletSharedState::create(ctx, outlet_listener_route, options.is_paused).await?;
+        let inlet_shared_state = Arc::new(RwLock::new(inlet_shared_state));
+        let processor = Self {
+            registry,
+            inner,
+            socket_path: socket_path.clone(),
+            inlet_shared_state: inlet_shared_state.clone(),
+            options,
+        };
+
+        ctx.start_processor(processor_address.clone(), processor)
+            .await?;
+
+        Ok(UnixInlet::new(
+            socket_path,
+            processor_address,
+            inlet_shared_state,
+        ))
+    }
+}
+
+/// Remove a socket file left by a process which didn't clean it up.
+/// Fail if the file is not a socket, or if another process is still listening on it.
+fn remove_stale_socket_file(socket_path: &Path) -> Result<()> {
+    let Ok(metadata) = std::fs::symlink_metadata(socket_path) else {
+        return Ok(());
+    };
+
+    if !metadata.file_type().is_socket()
+        || std::os::unix::net::UnixStream::connect(socket_path).is_ok()
+    {
+        return Err(ockam_core::Error::new(
+            Origin::Transport,
+            Kind::AlreadyExists,
+            format!("The file {} is already in use", socket_path.display()),
+        ));
+    }
+
+    debug!("Removing the stale socket file {}", socket_path.display());
+    std::fs::remove_file(socket_path).map_err(TransportError::from)?;
+    Ok(())
+}
+
+#[async_trait]
+impl Processor for UnixInletListenProcessor {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "UnixInletListenProcessor::initialize")]
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry.add_inlet_listener_processor(&ctx.address());
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UnixInletListenProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry
+            .remove_inlet_listener_processor(&ctx.address());
+
+        if let Err(err) = std::fs::remove_file(&self.socket_path) {
+            warn!(
+                "Cannot remove the socket file {}: {err}",
+                self.socket_path.display()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UnixInletListenProcessor::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let (stream, _) = self.inner.accept().await.map_err(TransportError::from)?;
+
+        let addresses = Addresses::generate(PortalType::Inlet);
+
+        let inlet_shared_state = self.inlet_shared_state.read().await.clone();
+
+        if inlet_shared_state.is_paused() {
+            // Just drop the stream
+            return Ok(true);
+        }
+
+        TcpInletOptions::setup_flow_control(
+            ctx.flow_controls(),
+            &addresses,
+            inlet_shared_state.route().next()?,
+        );
+
+        let (rx, tx) = stream.into_split();
+
+        TcpPortalWorker::start_new_inlet(
+            ctx,
+            self.registry.clone(),
+            (
+                ReadHalfMaybeTls::ReadHalfUnix(rx),
+                WriteHalfMaybeTls::WriteHalfUnix(tx),
+            ),
+            PortalPeer::Unix(self.socket_path.clone()),
+            None,
+            false,
+            inlet_shared_state.route().clone(),
+            inlet_shared_state.their_identifier(),
+            addresses,
+            self.options.incoming_access_control.clone(),
+            self.options.outgoing_access_control.clone(),
+            self.options.metrics.clone(),
+        )
+        .await?;
+
+        Ok(true)
+    }
+}
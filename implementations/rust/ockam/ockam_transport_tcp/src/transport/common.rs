@@ -1,3 +1,5 @@
+use crate::HttpProxy;
+use base64::prelude::{Engine, BASE64_STANDARD};
 use cfg_if::cfg_if;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
@@ -5,9 +7,10 @@ use ockam_transport_core::{HostnamePort, TransportError};
 use socket2::{SockRef, TcpKeepalive};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::io::{ReadHalf, WriteHalf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
+use tokio::time::timeout;
 use tokio_rustls::rustls::pki_types::ServerName;
 use tokio_rustls::rustls::{ClientConfig, RootCertStore};
 use tokio_rustls::{TlsConnector, TlsStream};
@@ -51,6 +54,72 @@ pub(crate) async fn create_tcp_stream(to: &HostnamePort) -> Result<TcpStream> {
     Ok(connection)
 }
 
+/// Maximum size of the response headers sent by an HTTP proxy
+const MAX_HTTP_PROXY_RESPONSE_SIZE: usize = 8 * 1024;
+
+/// Ask an HTTP proxy, reached via the given stream, to open a tunnel to a socket address
+#[instrument(skip_all)]
+pub(crate) async fn http_proxy_connect(
+    stream: &mut TcpStream,
+    proxy: &HttpProxy,
+    to: &HostnamePort,
+) -> Result<()> {
+    debug!(to = %to, "Opening a tunnel through the HTTP proxy");
+    let mut request = format!("CONNECT {to} HTTP/1.1\r\nHost: {to}\r\n");
+    if let Some((user, password)) = &proxy.credentials {
+        let credentials = BASE64_STANDARD.encode(format!("{user}:{password}"));
+        request.push_str(&format!("Proxy-Authorization: Basic {credentials}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(TransportError::from)?;
+
+    let response = match timeout(proxy.response_timeout, read_http_proxy_response(stream)).await {
+        Ok(response) => response?,
+        Err(_) => {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Timeout,
+                format!("The HTTP proxy did not answer the request to connect to {to}"),
+            ))
+        }
+    };
+
+    // The status line looks like `HTTP/1.1 200 Connection established`
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') && status.len() == 3 => {
+            debug!(to = %to, "Tunnel opened through the HTTP proxy");
+            Ok(())
+        }
+        _ => Err(Error::new(
+            Origin::Transport,
+            Kind::Protocol,
+            format!("The HTTP proxy refused to connect to {to}: {status_line}"),
+        )),
+    }
+}
+
+/// Read the status line and the headers of the response of an HTTP proxy,
+/// up to [`MAX_HTTP_PROXY_RESPONSE_SIZE`] bytes
+async fn read_http_proxy_response(stream: &mut TcpStream) -> Result<String> {
+    // Read the response one byte at a time to not consume any tunneled data
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_HTTP_PROXY_RESPONSE_SIZE {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Protocol,
+                "The HTTP proxy response is too large",
+            ));
+        }
+        response.push(stream.read_u8().await.map_err(TransportError::from)?);
+    }
+    Ok(String::from_utf8_lossy(&response).to_string())
+}
+
 /// Connect to a socket address via a TlsStream
 #[allow(clippy::type_complexity)]
 #[instrument(skip_all)]
@@ -64,7 +133,19 @@ pub(crate) async fn connect_tls(
 
     // create a tcp stream
     let connection = create_tcp_stream(to).await?;
+    start_tls(connection, to).await
+}
 
+/// Run a TLS handshake over an established TCP stream
+#[allow(clippy::type_complexity)]
+#[instrument(skip_all)]
+pub(crate) async fn start_tls(
+    connection: TcpStream,
+    to: &HostnamePort,
+) -> Result<(
+    ReadHalf<TlsStream<TcpStream>>,
+    WriteHalf<TlsStream<TcpStream>>,
+)> {
     // create a TLS connector
     let tls_connector = create_tls_connector().await?;
 
@@ -95,6 +176,15 @@ pub(crate) async fn connect_tls(
 
 /// Create a TLS connector using the system certificates
 pub(crate) async fn create_tls_connector() -> Result<TlsConnector> {
+    let config = ClientConfig::builder()
+        .with_root_certificates(native_root_cert_store()?)
+        .with_no_client_auth();
+
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// Create a root certificate store containing the system certificates
+pub(crate) fn native_root_cert_store() -> Result<RootCertStore> {
     let certificates = rustls_native_certs::load_native_certs();
 
     if let Some(e) = certificates.errors.first() {
@@ -112,9 +202,5 @@ pub(crate) async fn create_tls_connector() -> Result<TlsConnector> {
     let mut root_cert_store = RootCertStore::empty();
     root_cert_store.add_parsable_certificates(certificates);
 
-    let config = ClientConfig::builder()
-        .with_root_certificates(root_cert_store)
-        .with_no_client_auth();
-
-    Ok(TlsConnector::from(Arc::new(config)))
+    Ok(root_cert_store)
 }
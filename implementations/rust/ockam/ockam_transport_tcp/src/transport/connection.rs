@@ -1,5 +1,5 @@
-use crate::transport::connect;
-use crate::workers::{Addresses, TcpRecvProcessor, TcpSendWorker};
+use crate::transport::{create_tcp_stream, http_proxy_connect, tls_connect};
+use crate::workers::{Addresses, TcpSendWorker};
 use crate::{TcpConnectionMode, TcpConnectionOptions, TcpTransport};
 use core::fmt;
 use core::fmt::Formatter;
@@ -70,7 +70,7 @@ impl TcpConnection {
     pub fn receiver_address(&self) -> &Address {
         &self.receiver_address
     }
-    /// Corresponding [`SocketAddr`]
+    /// Corresponding [`SocketAddr`], which is the proxy address when using an HTTP proxy
     pub fn socket_address(&self) -> &SocketAddr {
         &self.socket_address
     }
@@ -87,6 +87,9 @@ impl TcpConnection {
 impl TcpTransport {
     /// Establish an outgoing TCP connection.
     ///
+    /// The connection can be wrapped in TLS with [`TcpConnectionOptions::with_tls`], and go
+    /// through an HTTP proxy with [`TcpConnectionOptions::with_http_proxy`].
+    ///
     /// ```rust
     /// use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
     /// # use ockam_node::Context;
@@ -100,46 +103,61 @@ impl TcpTransport {
     pub async fn connect(
         &self,
         peer: impl Into<String>,
-        options: TcpConnectionOptions,
+        mut options: TcpConnectionOptions,
     ) -> Result<TcpConnection> {
         let peer = HostnamePort::from_str(&peer.into())?;
         debug!("Connecting to {}", peer.clone());
 
-        let (read_half, write_half) = connect(&peer).await?;
-        let socket = read_half
+        let stream = match &options.http_proxy {
+            Some(proxy) => {
+                let mut stream = create_tcp_stream(&proxy.address).await?;
+                http_proxy_connect(&mut stream, proxy, &peer).await?;
+                stream
+            }
+            None => create_tcp_stream(&peer).await?,
+        };
+        let socket = stream
             .peer_addr()
             .map_err(|e| ockam_core::Error::new(Origin::Transport, Kind::Internal, e))?;
 
         let mode = TcpConnectionMode::Outgoing;
         let addresses = Addresses::generate(mode);
 
+        let tls = options.tls.take();
         options.setup_flow_control(self.ctx.flow_controls(), &addresses);
         let flow_control_id = options.flow_control_id.clone();
         let receiver_outgoing_access_control =
             options.create_receiver_outgoing_access_control(self.ctx.flow_controls());
 
-        TcpSendWorker::start(
-            &self.ctx,
-            self.registry.clone(),
-            write_half,
-            &addresses,
-            socket,
-            mode,
-            &flow_control_id,
-        )
-        .await?;
-
-        TcpRecvProcessor::start(
-            &self.ctx,
-            self.registry.clone(),
-            read_half,
-            &addresses,
-            socket,
-            mode,
-            &flow_control_id,
-            receiver_outgoing_access_control,
-        )
-        .await?;
+        match tls {
+            Some(tls) => {
+                let stream = tls_connect(stream, &peer, &tls).await?;
+                TcpSendWorker::start_pair(
+                    &self.ctx,
+                    self.registry.clone(),
+                    tokio::io::split(stream),
+                    &addresses,
+                    socket,
+                    mode,
+                    &flow_control_id,
+                    receiver_outgoing_access_control,
+                )
+                .await?;
+            }
+            None => {
+                TcpSendWorker::start_pair(
+                    &self.ctx,
+                    self.registry.clone(),
+                    stream.into_split(),
+                    &addresses,
+                    socket,
+                    mode,
+                    &flow_control_id,
+                    receiver_outgoing_access_control,
+                )
+                .await?;
+            }
+        }
 
         Ok(TcpConnection::new(
             addresses.sender_address().clone(),
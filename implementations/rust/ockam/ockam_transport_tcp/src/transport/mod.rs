@@ -3,8 +3,10 @@ mod connection;
 mod lifecycle;
 mod listener;
 mod portals;
+mod tls;
 
 pub(crate) use common::*;
+pub(crate) use tls::*;
 
 pub use crate::portal::options::*;
 pub use connection::*;
@@ -1,14 +1,20 @@
-use crate::portal::{InletSharedState, TcpInletListenProcessor};
+#[cfg(unix)]
+use crate::portal::UnixInletListenProcessor;
+use crate::portal::{InletSharedState, PortalPeer, TcpInletListenProcessor};
 use crate::{portal::TcpOutletListenWorker, TcpInletOptions, TcpOutletOptions, TcpTransport};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::Arc;
+#[cfg(unix)]
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControls;
 use ockam_core::{route, Address, Result, Route};
 use ockam_node::compat::asynchronous::RwLock;
 use ockam_node::Context;
 use ockam_transport_core::{parse_socket_addr, HostnamePort};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
 use tracing::instrument;
 
 impl TcpTransport {
@@ -98,7 +104,89 @@ impl TcpTransport {
             &self.ctx,
             self.registry.clone(),
             address.into(),
-            peer,
+            PortalPeer::Tcp(peer),
+            options,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Create an Inlet that listens on a Unix domain socket created at `socket_path`.
+    /// It works as an Inlet created with [`TcpTransport::create_inlet`] and can be used with
+    /// any Outlet. A stale socket file left at that path is replaced, and the socket file
+    /// is removed when the Inlet is stopped.
+    /// The permissions of the socket file are set with
+    /// [`TcpInletOptions::with_unix_socket_permissions`].
+    /// NOTE: Unix domain socket Inlets can't use SOCKS5 or TLS
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::{TcpInletOptions, TcpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{Result, route};
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let route_path = route!["outlet"];
+    ///
+    /// let tcp = TcpTransport::create(&ctx).await?;
+    /// let options = TcpInletOptions::new().with_unix_socket_permissions(0o600);
+    /// let inlet = tcp.create_unix_inlet("/tmp/inlet.sock", route_path, options).await?;
+    /// # tcp.stop_inlet(inlet.processor_address().clone()).await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(unix)]
+    #[instrument(skip(self, socket_path), fields(path = % socket_path.as_ref().display(), outlet_route = ? outlet_route.clone()))]
+    pub async fn create_unix_inlet(
+        &self,
+        socket_path: impl AsRef<Path>,
+        outlet_route: impl Into<Route> + Clone + Debug,
+        options: TcpInletOptions,
+    ) -> Result<UnixInlet> {
+        UnixInletListenProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            outlet_route.into(),
+            socket_path.as_ref().to_path_buf(),
+            options,
+        )
+        .await
+    }
+
+    /// Create an Outlet Listener at address, that connects to the Unix domain socket at
+    /// `socket_path` for each Inlet connection, for example to reach a service which
+    /// only listens on a Unix domain socket. The Outlet can be used with any Inlet.
+    /// NOTE: Unix domain socket Outlets can't use TLS or accept dynamic destinations
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::{TcpOutletOptions, TcpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let tcp = TcpTransport::create(&ctx).await?;
+    /// tcp.create_unix_outlet("outlet", "/var/run/docker.sock", TcpOutletOptions::new()).await?;
+    /// # tcp.stop_outlet("outlet").await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(unix)]
+    #[instrument(skip(self, socket_path), fields(address = ? address.clone().into(), path = % socket_path.as_ref().display()))]
+    pub async fn create_unix_outlet(
+        &self,
+        address: impl Into<Address> + Clone + Debug,
+        socket_path: impl AsRef<Path>,
+        options: TcpOutletOptions,
+    ) -> Result<()> {
+        if options.tls || options.destination_access_control.is_some() {
+            return Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "A Unix domain socket outlet cannot use TLS or dynamic destinations",
+            ));
+        }
+
+        TcpOutletListenWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            address.into(),
+            PortalPeer::Unix(socket_path.as_ref().to_path_buf()),
             options,
         )
         .await?;
@@ -286,3 +374,83 @@ impl TcpInlet {
         Ok(())
     }
 }
+
+/// Result of [`TcpTransport::create_unix_inlet`] call.
+#[cfg(unix)]
+#[derive(Clone, Debug)]
+pub struct UnixInlet {
+    socket_path: PathBuf,
+    processor_address: Address,
+    inlet_shared_state: Arc<RwLock<InletSharedState>>,
+}
+
+#[cfg(unix)]
+impl fmt::Display for UnixInlet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket file: {}. Processor address: {}",
+            self.socket_path.display(),
+            self.processor_address
+        )
+    }
+}
+
+#[cfg(unix)]
+impl UnixInlet {
+    /// Constructor
+    pub fn new(
+        socket_path: PathBuf,
+        processor_address: Address,
+        inlet_shared_state: Arc<RwLock<InletSharedState>>,
+    ) -> Self {
+        Self {
+            socket_path,
+            processor_address,
+            inlet_shared_state,
+        }
+    }
+
+    /// Path of the socket file
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Processor address
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+
+    /// Update the route to the outlet node.
+    /// Existing connections will still use the old route,
+    /// only newly accepted connections will use the new route.
+    pub async fn update_outlet_node_route(&self, ctx: &Context, new_route: Route) -> Result<()> {
+        let mut inlet_shared_state = self.inlet_shared_state.write().await;
+
+        let new_route = TcpInlet::build_new_full_route(new_route, inlet_shared_state.route())?;
+        inlet_shared_state.update_route(ctx, new_route).await
+    }
+
+    /// Pause the Inlet, all incoming connections will be dropped.
+    pub async fn pause(&self) {
+        let mut inlet_shared_state = self.inlet_shared_state.write().await;
+
+        inlet_shared_state.set_is_paused(true);
+    }
+
+    /// Unpause the Inlet and update the outlet route.
+    pub async fn unpause(&self, ctx: &Context, new_route: Route) -> Result<()> {
+        let mut inlet_shared_state = self.inlet_shared_state.write().await;
+
+        let new_route = TcpInlet::build_new_full_route(new_route, inlet_shared_state.route())?;
+        inlet_shared_state.update_route(ctx, new_route).await?;
+        inlet_shared_state.set_is_paused(false);
+
+        Ok(())
+    }
+
+    /// Stop the Inlet and remove its socket file
+    pub async fn stop(&self, ctx: &Context) -> Result<()> {
+        ctx.stop_processor(self.processor_address.clone()).await
+    }
+}
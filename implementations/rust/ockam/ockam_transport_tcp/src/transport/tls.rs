@@ -0,0 +1,101 @@
+use crate::portal::parse_certificates;
+use crate::transport::native_root_cert_store;
+use crate::{TcpTlsClientOptions, TcpTlsServerOptions};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_transport_core::HostnamePort;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore, ServerConfig};
+use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
+use tracing::{debug, instrument};
+
+/// Establish a TLS session over an existing TCP stream
+#[instrument(skip_all)]
+pub(crate) async fn tls_connect(
+    stream: TcpStream,
+    to: &HostnamePort,
+    options: &TcpTlsClientOptions,
+) -> Result<TlsStream<TcpStream>> {
+    let root_cert_store = match &options.root_certificates_pem {
+        Some(root_certificates_pem) => root_cert_store(root_certificates_pem)?,
+        None => native_root_cert_store()?,
+    };
+
+    let builder = ClientConfig::builder().with_root_certificates(root_cert_store);
+    let config = match &options.client_certificate {
+        Some(certificate) => builder
+            .with_client_auth_cert(certificate.certificate_chain()?, certificate.private_key()?)
+            .map_err(|e| Error::new(Origin::Transport, Kind::Parse, e))?,
+        None => builder.with_no_client_auth(),
+    };
+
+    let server_name = options.server_name.clone().unwrap_or_else(|| to.hostname());
+    let server_name = ServerName::try_from(server_name).map_err(|e| {
+        Error::new(
+            Origin::Transport,
+            Kind::Io,
+            format!("Cannot create a ServerName for {to}: {e:?}"),
+        )
+    })?;
+
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await
+        .map_err(|e| {
+            Error::new(
+                Origin::Transport,
+                Kind::Io,
+                format!("Cannot connect using TLS to {to}: {e:?}"),
+            )
+        })?;
+    debug!("Connected using TLS to {to}");
+
+    Ok(TlsStream::from(stream))
+}
+
+/// Create a TLS acceptor for a TCP listener
+pub(crate) fn create_tls_acceptor(options: &TcpTlsServerOptions) -> Result<TlsAcceptor> {
+    let builder = ServerConfig::builder();
+    let builder = match &options.client_certificates_pem {
+        Some(client_certificates_pem) => {
+            let verifier =
+                WebPkiClientVerifier::builder(Arc::new(root_cert_store(client_certificates_pem)?))
+                    .build()
+                    .map_err(|e| Error::new(Origin::Transport, Kind::Parse, e))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let config = builder
+        .with_single_cert(
+            options.certificate.certificate_chain()?,
+            options.certificate.private_key()?,
+        )
+        .map_err(|e| Error::new(Origin::Transport, Kind::Parse, e))?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Create a root certificate store containing the given PEM encoded certificates
+fn root_cert_store(certificates_pem: &[u8]) -> Result<RootCertStore> {
+    let mut root_cert_store = RootCertStore::empty();
+    for certificate in parse_certificates(certificates_pem)? {
+        root_cert_store
+            .add(certificate)
+            .map_err(|e| Error::new(Origin::Transport, Kind::Parse, e))?;
+    }
+
+    if root_cert_store.is_empty() {
+        return Err(Error::new(
+            Origin::Transport,
+            Kind::Parse,
+            "No certificate found in the provided root certificates",
+        ));
+    }
+
+    Ok(root_cert_store)
+}
@@ -1,11 +1,19 @@
-use crate::workers::{Addresses, TcpRecvProcessor};
+use crate::transport::create_tls_acceptor;
+use crate::workers::Addresses;
 use crate::{TcpConnectionMode, TcpListenerInfo, TcpListenerOptions, TcpRegistry, TcpSendWorker};
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, compat::net::SocketAddr};
-use ockam_core::{Address, Processor, Result};
+use ockam_core::{Address, AsyncTryClone, Processor, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpListener;
-use tracing::{debug, instrument};
+use tokio_rustls::{TlsAcceptor, TlsStream};
+use tracing::{debug, instrument, warn};
+
+/// Maximum duration of the TLS handshake of an incoming connection
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// A TCP Listen processor
 ///
@@ -16,7 +24,11 @@ pub(crate) struct TcpListenProcessor {
     registry: TcpRegistry,
     inner: TcpListener,
     socket_address: SocketAddr,
-    options: TcpListenerOptions,
+    options: Arc<TcpListenerOptions>,
+    tls_acceptor: Option<TlsAcceptor>,
+    /// Context used to start the workers of the TLS connections,
+    /// once their handshake is done in a separate task
+    connections_ctx: Arc<Context>,
 }
 
 impl TcpListenProcessor {
@@ -27,6 +39,8 @@ impl TcpListenProcessor {
         addr: SocketAddr,
         options: TcpListenerOptions,
     ) -> Result<(SocketAddr, Address)> {
+        let tls_acceptor = options.tls.as_ref().map(create_tls_acceptor).transpose()?;
+
         debug!("Binding TcpListener to {}", addr);
         let inner = TcpListener::bind(addr)
             .await
@@ -40,13 +54,52 @@ impl TcpListenProcessor {
             registry,
             inner,
             socket_address: saddr,
-            options,
+            options: Arc::new(options),
+            tls_acceptor,
+            connections_ctx: Arc::new(ctx.async_try_clone().await?),
         };
 
         ctx.start_processor(address.clone(), processor).await?;
 
         Ok((saddr, address))
     }
+
+    /// Start the workers handling an accepted connection
+    async fn start_connection<R, W>(
+        ctx: &Context,
+        registry: TcpRegistry,
+        options: &TcpListenerOptions,
+        streams: (R, W),
+        peer: SocketAddr,
+    ) -> Result<()>
+    where
+        R: AsyncRead + Unpin + Send + Sync + 'static,
+        W: AsyncWrite + Unpin + Send + Sync + 'static,
+    {
+        let mode = TcpConnectionMode::Incoming;
+        let addresses = Addresses::generate(mode);
+
+        let receiver_flow_control_id =
+            options.setup_flow_control_for_connection(ctx.flow_controls(), &addresses);
+        let receiver_outgoing_access_control = options.create_receiver_outgoing_access_control(
+            ctx.flow_controls(),
+            receiver_flow_control_id.clone(),
+        );
+
+        // Worker to receive messages from the Node and send them over the wire,
+        // and processor to receive messages over the wire and forward them to the node
+        TcpSendWorker::start_pair(
+            ctx,
+            registry,
+            streams,
+            &addresses,
+            peer,
+            mode,
+            &receiver_flow_control_id,
+            receiver_outgoing_access_control,
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -81,44 +134,53 @@ impl Processor for TcpListenProcessor {
         let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
         debug!("TCP connection accepted");
 
-        let mode = TcpConnectionMode::Incoming;
-        let addresses = Addresses::generate(mode);
-
-        let receiver_flow_control_id = self
-            .options
-            .setup_flow_control_for_connection(ctx.flow_controls(), &addresses);
-        let receiver_outgoing_access_control =
-            self.options.create_receiver_outgoing_access_control(
-                ctx.flow_controls(),
-                receiver_flow_control_id.clone(),
-            );
-
-        let (read_half, write_half) = stream.into_split();
-
-        // Worker to receive messages from the Node and send them over the wire
-        TcpSendWorker::start(
-            ctx,
-            self.registry.clone(),
-            write_half,
-            &addresses,
-            peer,
-            mode,
-            &receiver_flow_control_id,
-        )
-        .await?;
-
-        // Processor to receive messages over the wire and forward them to the node
-        TcpRecvProcessor::start(
-            ctx,
-            self.registry.clone(),
-            read_half,
-            &addresses,
-            peer,
-            mode,
-            &receiver_flow_control_id,
-            receiver_outgoing_access_control,
-        )
-        .await?;
+        match &self.tls_acceptor {
+            Some(tls_acceptor) => {
+                // The TLS handshake is done in a separate task,
+                // so that a slow or silent peer doesn't block the other connections
+                let tls_acceptor = tls_acceptor.clone();
+                let connections_ctx = self.connections_ctx.clone();
+                let registry = self.registry.clone();
+                let options = self.options.clone();
+                ctx.runtime().spawn(async move {
+                    let handshake =
+                        tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, tls_acceptor.accept(stream))
+                            .await;
+                    let tls_stream = match handshake {
+                        Ok(Ok(tls_stream)) => TlsStream::from(tls_stream),
+                        Ok(Err(err)) => {
+                            warn!(%peer, %err, "TLS handshake failed");
+                            return;
+                        }
+                        Err(_) => {
+                            warn!(%peer, "TLS handshake timed out");
+                            return;
+                        }
+                    };
+                    if let Err(err) = Self::start_connection(
+                        &connections_ctx,
+                        registry,
+                        &options,
+                        tokio::io::split(tls_stream),
+                        peer,
+                    )
+                    .await
+                    {
+                        warn!(%peer, %err, "Cannot start the TLS connection workers");
+                    }
+                });
+            }
+            None => {
+                Self::start_connection(
+                    ctx,
+                    self.registry.clone(),
+                    &self.options,
+                    stream.into_split(),
+                    peer,
+                )
+                .await?
+            }
+        }
 
         Ok(true)
     }
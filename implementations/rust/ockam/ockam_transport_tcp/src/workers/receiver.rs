@@ -15,7 +15,7 @@ use ockam_core::{
 use ockam_core::{Processor, Result};
 use ockam_node::{Context, ProcessorBuilder};
 use ockam_transport_core::TransportError;
-use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tracing::{info, instrument, trace};
 
 /// A TCP receiving message processor
@@ -26,21 +26,21 @@ use tracing::{info, instrument, trace};
 /// This half of the worker is created when spawning a new connection
 /// worker pair, and listens for incoming TCP packets, to relay into
 /// the node message system.
-pub(crate) struct TcpRecvProcessor {
+pub(crate) struct TcpRecvProcessor<R> {
     registry: TcpRegistry,
     incoming_buffer: Vec<u8>,
-    read_half: OwnedReadHalf,
+    read_half: R,
     socket_address: SocketAddr,
     addresses: Addresses,
     mode: TcpConnectionMode,
     flow_control_id: FlowControlId,
 }
 
-impl TcpRecvProcessor {
+impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpRecvProcessor<R> {
     /// Create a new `TcpRecvProcessor`
     fn new(
         registry: TcpRegistry,
-        read_half: OwnedReadHalf,
+        read_half: R,
         socket_address: SocketAddr,
         addresses: Addresses,
         mode: TcpConnectionMode,
@@ -62,14 +62,14 @@ impl TcpRecvProcessor {
     pub async fn start(
         ctx: &Context,
         registry: TcpRegistry,
-        read_half: OwnedReadHalf,
+        read_half: R,
         addresses: &Addresses,
         socket_address: SocketAddr,
         mode: TcpConnectionMode,
         flow_control_id: &FlowControlId,
         receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     ) -> Result<()> {
-        let receiver = TcpRecvProcessor::new(
+        let receiver = Self::new(
             registry,
             read_half,
             socket_address,
@@ -114,7 +114,7 @@ impl TcpRecvProcessor {
 }
 
 #[async_trait]
-impl Processor for TcpRecvProcessor {
+impl<R: AsyncRead + Unpin + Send + Sync + 'static> Processor for TcpRecvProcessor<R> {
     type Context = Context;
 
     #[instrument(skip_all, name = "TcpRecvProcessor::initialize")]
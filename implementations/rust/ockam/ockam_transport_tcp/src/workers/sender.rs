@@ -1,10 +1,10 @@
-use crate::workers::Addresses;
+use crate::workers::{Addresses, TcpRecvProcessor};
 use crate::{TcpConnectionMode, TcpProtocolVersion, TcpRegistry, TcpSenderInfo, MAX_MESSAGE_SIZE};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
     async_trait,
     compat::{net::SocketAddr, sync::Arc},
-    AllowAll, AllowSourceAddress, DenyAll, LocalMessage,
+    AllowAll, AllowSourceAddress, DenyAll, LocalMessage, OutgoingAccessControl,
 };
 use ockam_core::{Any, Decodable, Mailbox, Mailboxes, Message, Result, Routed, Worker};
 use ockam_node::{Context, WorkerBuilder};
@@ -12,8 +12,7 @@ use ockam_node::{Context, WorkerBuilder};
 use crate::transport_message::TcpTransportMessage;
 use ockam_transport_core::TransportError;
 use serde::{Deserialize, Serialize};
-use tokio::io::AsyncWriteExt;
-use tokio::net::tcp::OwnedWriteHalf;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use tracing::{info, instrument, trace, warn};
 
 #[derive(Serialize, Deserialize, Message, Clone)]
@@ -29,10 +28,10 @@ pub(crate) enum TcpSendWorkerMsg {
 /// This half of the worker is created when spawning a new connection
 /// worker pair, and listens for messages from the node message system
 /// to dispatch to a remote peer.
-pub(crate) struct TcpSendWorker {
+pub(crate) struct TcpSendWorker<W> {
     buffer: Vec<u8>,
     registry: TcpRegistry,
-    write_half: OwnedWriteHalf,
+    write_half: W,
     socket_address: SocketAddr,
     addresses: Addresses,
     mode: TcpConnectionMode,
@@ -40,11 +39,11 @@ pub(crate) struct TcpSendWorker {
     rx_should_be_stopped: bool,
 }
 
-impl TcpSendWorker {
+impl<W: AsyncWrite + Unpin + Send + Sync + 'static> TcpSendWorker<W> {
     /// Create a new `TcpSendWorker`
     fn new(
         registry: TcpRegistry,
-        write_half: OwnedWriteHalf,
+        write_half: W,
         socket_address: SocketAddr,
         addresses: Addresses,
         mode: TcpConnectionMode,
@@ -63,15 +62,51 @@ impl TcpSendWorker {
     }
 }
 
-impl TcpSendWorker {
-    /// Create a `(TcpSendWorker, TcpRecvProcessor)` pair that opens and
-    /// manages the connection with the given peer
+impl<W: AsyncWrite + Unpin + Send + Sync + 'static> TcpSendWorker<W> {
+    /// Create a `(TcpSendWorker, TcpRecvProcessor)` pair that manages
+    /// the connection with the given peer
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn start_pair<R: AsyncRead + Unpin + Send + Sync + 'static>(
+        ctx: &Context,
+        registry: TcpRegistry,
+        (read_half, write_half): (R, W),
+        addresses: &Addresses,
+        socket_address: SocketAddr,
+        mode: TcpConnectionMode,
+        receiver_flow_control_id: &FlowControlId,
+        receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<()> {
+        Self::start(
+            ctx,
+            registry.clone(),
+            write_half,
+            addresses,
+            socket_address,
+            mode,
+            receiver_flow_control_id,
+        )
+        .await?;
+
+        TcpRecvProcessor::start(
+            ctx,
+            registry,
+            read_half,
+            addresses,
+            socket_address,
+            mode,
+            receiver_flow_control_id,
+            receiver_outgoing_access_control,
+        )
+        .await
+    }
+
+    /// Start a `TcpSendWorker` sending messages to the given peer
     #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all, name = "TcpSendWorker::start")]
     pub(crate) async fn start(
         ctx: &Context,
         registry: TcpRegistry,
-        write_half: OwnedWriteHalf,
+        write_half: W,
         addresses: &Addresses,
         socket_address: SocketAddr,
         mode: TcpConnectionMode,
@@ -160,7 +195,7 @@ impl TcpSendWorker {
 }
 
 #[async_trait]
-impl Worker for TcpSendWorker {
+impl<W: AsyncWrite + Unpin + Send + Sync + 'static> Worker for TcpSendWorker<W> {
     type Context = Context;
     type Message = Any;
 
@@ -182,6 +217,7 @@ impl Worker for TcpSendWorker {
             .write_u8(TcpProtocolVersion::V1.into())
             .await
             .is_err()
+            || self.write_half.flush().await.is_err()
         {
             warn!(
                 "Failed to send protocol version to peer {}",
@@ -248,7 +284,9 @@ impl Worker for TcpSendWorker {
                 return Err(err);
             };
 
-            if self.write_half.write_all(&self.buffer).await.is_err() {
+            if self.write_half.write_all(&self.buffer).await.is_err()
+                || self.write_half.flush().await.is_err()
+            {
                 warn!("Failed to send message to peer {}", self.socket_address);
                 self.stop(ctx).await?;
 
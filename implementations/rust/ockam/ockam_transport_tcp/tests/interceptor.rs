@@ -1,3 +1,4 @@
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, route, AllowAll};
 use ockam_node::Context;
 use ockam_transport_tcp::{
@@ -33,8 +34,37 @@ impl PortalInterceptor for MockPortalInterceptor {
     }
 }
 
+/// An interceptor which fails on any data sent by the client, and closes the connection
+struct RejectingPortalInterceptor;
+
+#[async_trait]
+impl PortalInterceptor for RejectingPortalInterceptor {
+    async fn intercept(
+        &self,
+        _context: &mut Context,
+        direction: Direction,
+        buffer: &[u8],
+    ) -> ockam_core::Result<Option<Vec<u8>>> {
+        match direction {
+            Direction::FromInletToOutlet => Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "rejected",
+            )),
+            Direction::FromOutletToInlet => Ok(Some(buffer.to_vec())),
+        }
+    }
+
+    fn error_response(&self, direction: Direction) -> Option<Vec<u8>> {
+        match direction {
+            Direction::FromInletToOutlet => Some(b"rejected".to_vec()),
+            Direction::FromOutletToInlet => None,
+        }
+    }
+}
+
 struct MockPortalInterceptorFactory {
-    interceptor: Arc<MockPortalInterceptor>,
+    interceptor: Arc<dyn PortalInterceptor>,
 }
 
 impl PortalInterceptorFactory for MockPortalInterceptorFactory {
@@ -46,6 +76,15 @@ impl PortalInterceptorFactory for MockPortalInterceptorFactory {
 async fn setup(
     context: &mut Context,
 ) -> ockam_core::Result<(String, TcpListener, Arc<MockPortalInterceptor>)> {
+    let mock_portal_interceptor = Arc::new(MockPortalInterceptor::default());
+    let (inlet_address, listener) = setup_with(context, mock_portal_interceptor.clone()).await?;
+    Ok((inlet_address, listener, mock_portal_interceptor))
+}
+
+async fn setup_with(
+    context: &mut Context,
+    interceptor: Arc<dyn PortalInterceptor>,
+) -> ockam_core::Result<(String, TcpListener)> {
     let tcp = TcpTransport::create(context).await?;
 
     let listener = {
@@ -60,14 +99,10 @@ async fn setup(
         listener
     };
 
-    let mock_portal_interceptor = Arc::new(MockPortalInterceptor::default());
-
     PortalInletInterceptor::create(
         context,
         "interceptor_listener".into(),
-        Arc::new(MockPortalInterceptorFactory {
-            interceptor: mock_portal_interceptor.clone(),
-        }),
+        Arc::new(MockPortalInterceptorFactory { interceptor }),
         Arc::new(AllowAll),
         Arc::new(AllowAll),
     )
@@ -82,11 +117,7 @@ async fn setup(
         )
         .await?;
 
-    Ok((
-        inlet.socket_address().to_string(),
-        listener,
-        mock_portal_interceptor,
-    ))
+    Ok((inlet.socket_address().to_string(), listener))
 }
 
 const LENGTH: usize = 32;
@@ -147,3 +178,35 @@ async fn interceptor__simple_payload__received(context: &mut Context) -> ockam_c
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5_000)]
+async fn interceptor__rejected_payload__connection_closed(
+    context: &mut Context,
+) -> ockam_core::Result<()> {
+    let (inlet_addr, listener) = setup_with(context, Arc::new(RejectingPortalInterceptor)).await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = vec![];
+        stream.read_to_end(&mut received).await.unwrap();
+        received
+    });
+
+    // Wait till the listener is up
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    write_binary(&mut stream, generate_binary()).await;
+
+    // the client receives the error response, then the connection is closed
+    let mut response = vec![];
+    stream.read_to_end(&mut response).await.unwrap();
+    assert_eq!(response, b"rejected");
+
+    // the target receives no data, then the connection is closed
+    let received = handle.await.unwrap();
+    assert!(received.is_empty());
+
+    Ok(())
+}
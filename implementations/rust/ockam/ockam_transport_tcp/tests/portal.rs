@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -7,7 +8,8 @@ use ockam_core::compat::rand::random;
 use ockam_core::{route, Result};
 use ockam_node::Context;
 use ockam_transport_tcp::{
-    TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
+    DestinationAllowlist, TcpConnectionOptions, TcpInletOptions, TcpListenerOptions,
+    TcpOutletOptions, TcpPortalMetrics, TcpTransport,
 };
 
 const LENGTH: usize = 32;
@@ -87,6 +89,63 @@ async fn portal__standard_flow__should_succeed(ctx: &mut Context) -> Result<()>
     Ok(())
 }
 
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__metrics__should_count_connections_and_bytes(ctx: &mut Context) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let inlet_metrics = Arc::new(TcpPortalMetrics::default());
+    let outlet_metrics = Arc::new(TcpPortalMetrics::default());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    tcp.create_outlet(
+        "outlet",
+        listener
+            .local_addr()
+            .unwrap()
+            .to_string()
+            .try_into()
+            .unwrap(),
+        TcpOutletOptions::new().with_metrics(outlet_metrics.clone()),
+    )
+    .await?;
+    let inlet = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_metrics(inlet_metrics.clone()),
+        )
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+        stream
+    });
+
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    assert_eq!(inlet_metrics.connections(), 1);
+    assert_eq!(inlet_metrics.active_connections(), 1);
+    assert_eq!(inlet_metrics.bytes_received(), LENGTH as u64);
+    assert_eq!(inlet_metrics.bytes_sent(), LENGTH as u64);
+
+    assert_eq!(outlet_metrics.connections(), 1);
+    assert_eq!(outlet_metrics.bytes_received(), LENGTH as u64);
+    assert_eq!(outlet_metrics.bytes_sent(), LENGTH as u64);
+
+    Ok(())
+}
+
 #[allow(non_snake_case)]
 #[ockam_macros::test(timeout = 5000)]
 async fn portal__reverse_flow__should_succeed(ctx: &mut Context) -> Result<()> {
@@ -313,3 +372,265 @@ async fn portal__update_route__should_succeed(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+/// Run the client side of a SOCKS5 handshake and return the reply code
+async fn socks5_connect(stream: &mut TcpStream, port: u16) -> u8 {
+    let mut request = vec![5, 1, 0, 5, 1, 0, 1, 127, 0, 0, 1];
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await.unwrap();
+
+    let mut response = [0u8; 12];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(response[..2], [5, 0]);
+    response[3]
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__socks5_inlet_to_dynamic_outlet__should_only_connect_to_allowed_destinations(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let allowed_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let allowed_port = allowed_listener.local_addr().unwrap().port();
+    let denied_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let denied_port = denied_listener.local_addr().unwrap().port();
+
+    let allowlist = DestinationAllowlist::new(vec![format!("127.0.0.1:{allowed_port}").parse()?]);
+    tcp.create_outlet(
+        "outlet",
+        denied_listener
+            .local_addr()
+            .unwrap()
+            .to_string()
+            .try_into()?,
+        TcpOutletOptions::new().with_destination_access_control(Arc::new(allowlist)),
+    )
+    .await?;
+
+    let inlet = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().socks5(),
+        )
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = allowed_listener.accept().await.unwrap();
+
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+        stream
+    });
+
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    assert_eq!(socks5_connect(&mut stream, allowed_port).await, 0);
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    // The reply is a general failure, and the outlet never connects to the destination
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    assert_eq!(socks5_connect(&mut stream, denied_port).await, 1);
+    let accepted = tokio::time::timeout(Duration::from_millis(250), denied_listener.accept()).await;
+    assert!(accepted.is_err());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__proxy_protocol__should_send_the_inlet_client_address(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let listener_addr = listener.local_addr().unwrap();
+    tcp.create_outlet(
+        "outlet",
+        listener_addr.to_string().try_into()?,
+        TcpOutletOptions::new().with_proxy_protocol(),
+    )
+    .await?;
+
+    let inlet = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    let client_addr = stream.local_addr().unwrap();
+    write_binary(&mut stream, payload).await;
+
+    let (mut stream, _) = listener.accept().await.unwrap();
+
+    // signature, version and command, TCP over IPv4, length
+    let mut header = [0u8; 16];
+    stream.read_exact(&mut header).await.unwrap();
+    assert_eq!(header[..12], *b"\r\n\r\n\0\r\nQUIT\n");
+    assert_eq!(header[12..], [0x21, 0x11, 0, 12]);
+
+    // no TLVs are sent without a secure channel
+    let mut addresses = [0u8; 12];
+    stream.read_exact(&mut addresses).await.unwrap();
+    assert_eq!(addresses[..4], [127, 0, 0, 1]);
+    assert_eq!(addresses[4..8], [127, 0, 0, 1]);
+    assert_eq!(addresses[8..10], client_addr.port().to_be_bytes());
+    assert_eq!(addresses[10..], listener_addr.port().to_be_bytes());
+
+    read_assert_binary(&mut stream, payload).await;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn unix_socket_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("ockam-portal-{:x}.sock", random::<u64>()))
+}
+
+#[cfg(unix)]
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__unix_inlet_to_tcp_outlet__should_succeed(ctx: &mut Context) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixStream;
+
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address.try_into().unwrap(),
+        TcpOutletOptions::new(),
+    )
+    .await?;
+
+    let socket_path = unix_socket_path();
+    let inlet = tcp
+        .create_unix_inlet(
+            &socket_path,
+            route!["outlet"],
+            TcpInletOptions::new().with_unix_socket_permissions(0o600),
+        )
+        .await?;
+    let mode = std::fs::metadata(&socket_path)
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let mut payload = [0u8; LENGTH];
+        stream.read_exact(&mut payload).await.unwrap();
+        assert_eq!(payload, payload1);
+        write_binary(&mut stream, payload2).await;
+        stream
+    });
+
+    let mut stream = UnixStream::connect(&socket_path).await.unwrap();
+    stream.write_all(&payload1).await.unwrap();
+    let mut payload = [0u8; LENGTH];
+    stream.read_exact(&mut payload).await.unwrap();
+    assert_eq!(payload, payload2);
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    // The socket file is removed with the inlet
+    inlet.stop(ctx).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!socket_path.exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__tcp_inlet_to_unix_outlet__should_succeed(ctx: &mut Context) -> Result<()> {
+    use tokio::net::UnixListener;
+
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let socket_path = unix_socket_path();
+    let listener = UnixListener::bind(&socket_path).unwrap();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    tcp.create_unix_outlet("outlet", &socket_path, TcpOutletOptions::new())
+        .await?;
+    let inlet = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let mut payload = [0u8; LENGTH];
+        stream.read_exact(&mut payload).await.unwrap();
+        assert_eq!(payload, payload1);
+        stream.write_all(&payload2).await.unwrap();
+        stream
+    });
+
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    std::fs::remove_file(&socket_path).unwrap();
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__unix_inlet__should_only_replace_stale_socket_files(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    // A regular file is never replaced
+    let file_path = unix_socket_path();
+    std::fs::write(&file_path, b"data").unwrap();
+    let res = tcp
+        .create_unix_inlet(&file_path, route!["outlet"], TcpInletOptions::new())
+        .await;
+    assert!(res.is_err());
+    std::fs::remove_file(&file_path).unwrap();
+
+    // A socket which is still listened on is not replaced
+    let socket_path = unix_socket_path();
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+    let res = tcp
+        .create_unix_inlet(&socket_path, route!["outlet"], TcpInletOptions::new())
+        .await;
+    assert!(res.is_err());
+
+    // A socket left by a stopped process is replaced
+    drop(listener);
+    let inlet = tcp
+        .create_unix_inlet(&socket_path, route!["outlet"], TcpInletOptions::new())
+        .await?;
+    assert_eq!(inlet.socket_path(), socket_path.as_path());
+    inlet.stop(ctx).await?;
+
+    Ok(())
+}
@@ -19,13 +19,19 @@ extern crate alloc;
 
 mod messages;
 mod options;
+mod portal;
 mod puncture;
 mod transport;
 mod workers;
 
 pub use options::UdpBindOptions;
+pub use portal::{
+    UdpInletOptions, UdpOutletOptions, UdpPortalMessage, DEFAULT_UDP_PORTAL_IDLE_TIMEOUT,
+    DEFAULT_UDP_PORTAL_MAX_FLOWS,
+};
 pub use puncture::*;
-pub use transport::{UdpBind, UdpBindArguments, UdpTransport, UdpTransportExtension};
+pub use transport::{UdpBind, UdpBindArguments, UdpInlet, UdpTransport, UdpTransportExtension};
+pub use workers::UdpReceiverMetrics;
 
 pub(crate) const CLUSTER_NAME: &str = "_internals.transport.udp";
 
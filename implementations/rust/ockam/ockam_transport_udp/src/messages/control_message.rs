@@ -0,0 +1,22 @@
+use crate::messages::{UdpDeliveryAck, UdpPathMtuAck, UdpPathMtuProbe};
+use minicbor::{CborLen, Decode, Encode};
+
+/// Datagrams exchanged between UDP transports in addition to
+/// [`UdpTransportMessage`](crate::messages::UdpTransportMessage)s.
+///
+///  - [`UdpControlMessage::PathMtuProbe`]s of different sizes are sent to discover the largest
+///    datagram that can be delivered to a peer, the peer answers with a
+///    [`UdpControlMessage::PathMtuAck`] for each probe it received.
+///  - [`UdpControlMessage::DeliveryAck`] acknowledges routing messages sent with reliable
+///    delivery.
+///
+/// These messages never have the same encoding as a
+/// [`UdpTransportMessage`](crate::messages::UdpTransportMessage), which allows the receiver
+/// to tell them apart.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum UdpControlMessage<'a> {
+    #[n(0)] PathMtuProbe(#[b(0)] UdpPathMtuProbe<'a>),
+    #[n(1)] PathMtuAck(#[n(0)] UdpPathMtuAck),
+    #[n(2)] DeliveryAck(#[n(0)] UdpDeliveryAck),
+}
@@ -0,0 +1,91 @@
+use crate::messages::{Version, CURRENT_VERSION};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::vec::Vec;
+
+/// Maximum number of selectively acknowledged sequence numbers in a [`UdpDeliveryAck`],
+/// so that an acknowledgement always fits in a single datagram.
+pub const MAX_SELECTIVE_ACKS: usize = 32;
+
+/// The receiver only buffers routing messages whose sequence number is less than that far
+/// ahead of the first routing message it hasn't received yet.
+pub const MAX_DELIVERY_WINDOW: u64 = 1024;
+
+/// Added to a [`UdpRoutingMessage`](crate::messages::UdpRoutingMessage) sent with reliable
+/// delivery. The receiver delivers routing messages of the same stream in the order of their
+/// sequence numbers, and acknowledges them with a [`UdpDeliveryAck`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct UdpDeliveryHeader {
+    /// Random identifier of the stream, chosen by the sender for each peer
+    #[n(0)] pub stream_id: u32,
+    /// Sequence number of the routing message within the stream, starting from 0
+    #[n(1)] pub sequence_number: u64,
+}
+
+/// Acknowledgement of routing messages sent with reliable delivery
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct UdpDeliveryAck {
+    #[n(0)] pub version: Version,
+    #[n(1)] pub stream_id: u32,
+    /// All routing messages with a lower sequence number were received
+    #[n(2)] pub cumulative: u64,
+    /// Routing messages received out of order, at most [`MAX_SELECTIVE_ACKS`]
+    #[n(3)] pub selective: Vec<u64>,
+}
+
+impl UdpDeliveryAck {
+    /// Constructor
+    pub fn new(stream_id: u32, cumulative: u64, selective: Vec<u64>) -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            stream_id,
+            cumulative,
+            selective,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::messages::{
+        UdpControlMessage, UdpDeliveryAck, UdpDeliveryHeader, UdpRoutingMessage,
+        UdpTransportMessage, MAX_ON_THE_WIRE_SIZE, MAX_SELECTIVE_ACKS,
+    };
+    use ockam_core::{route, CowBytes};
+
+    #[test]
+    fn test_max_ack_size() {
+        let ack = UdpControlMessage::DeliveryAck(UdpDeliveryAck::new(
+            u32::MAX,
+            u64::MAX,
+            vec![u64::MAX; MAX_SELECTIVE_ACKS],
+        ));
+
+        let ack = minicbor::to_vec(ack).unwrap();
+        assert!(ack.len() <= MAX_ON_THE_WIRE_SIZE);
+        assert!(minicbor::decode::<UdpTransportMessage>(&ack).is_err());
+    }
+
+    #[test]
+    fn test_routing_message_delivery_header() {
+        let routing_message = UdpRoutingMessage::new(
+            route!["onward"],
+            route!["return"],
+            CowBytes::from(vec![1, 2, 3]),
+            None,
+        );
+        let encoded = minicbor::to_vec(&routing_message).unwrap();
+
+        let decoded: UdpRoutingMessage = minicbor::decode(&encoded).unwrap();
+        assert_eq!(decoded.delivery, None);
+
+        let routing_message = routing_message.with_delivery(UdpDeliveryHeader {
+            stream_id: 1,
+            sequence_number: 2,
+        });
+        let encoded = minicbor::to_vec(&routing_message).unwrap();
+        let decoded: UdpRoutingMessage = minicbor::decode(&encoded).unwrap();
+        assert_eq!(decoded, routing_message);
+    }
+}
@@ -1,7 +1,13 @@
+mod control_message;
+mod delivery_message;
+mod path_mtu_message;
 mod routing_message;
 mod routing_number;
 mod transport_message;
 
+pub use control_message::*;
+pub use delivery_message::*;
+pub use path_mtu_message::*;
 pub use routing_message::*;
 pub use routing_number::*;
 pub use transport_message::*;
@@ -0,0 +1,129 @@
+use crate::messages::{
+    UdpControlMessage, Version, CURRENT_VERSION, MAX_ON_THE_WIRE_SIZE, MAX_PAYLOAD_SIZE,
+};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::CowBytes;
+
+/// Largest datagram we ever send, it corresponds to a 9000 bytes (jumbo frame) IPv4 MTU
+/// minus the IP and UDP headers.
+pub const MAX_DATAGRAM_SIZE: usize = 8972;
+
+/// Datagram sizes we try during Path MTU discovery, from the largest to the smallest.
+/// They correspond to jumbo frames, Ethernet, typical VPN tunnels and the IPv6 minimum MTU
+/// (minus the IP and UDP headers).
+pub const PATH_MTU_PROBE_SIZES: [usize; 4] = [MAX_DATAGRAM_SIZE, 1472, 1372, 1232];
+
+/// Maximum payload size of a [`UdpTransportMessage`](crate::messages::UdpTransportMessage)
+/// that fits into a datagram of the given size after encoding.
+pub fn max_payload_size(datagram_size: usize) -> usize {
+    datagram_size.max(MAX_ON_THE_WIRE_SIZE) - (MAX_ON_THE_WIRE_SIZE - MAX_PAYLOAD_SIZE)
+}
+
+/// Probe of a given size. The padding is used to reach the desired size on the wire.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct UdpPathMtuProbe<'a> {
+    #[n(0)] pub version: Version,
+    #[n(1)] pub size: u16,
+    #[b(2)] pub padding: CowBytes<'a>,
+}
+
+/// Acknowledgement of a received [`UdpPathMtuProbe`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct UdpPathMtuAck {
+    #[n(0)] pub version: Version,
+    #[n(1)] pub size: u16,
+}
+
+impl UdpPathMtuProbe<'static> {
+    /// Create a probe which is exactly `size` bytes long when encoded as a
+    /// [`UdpControlMessage`]
+    pub fn new(size: u16) -> Self {
+        let mut probe = Self {
+            version: CURRENT_VERSION,
+            size,
+            padding: CowBytes::from(vec![]),
+        };
+
+        let empty_len = minicbor::len(UdpControlMessage::PathMtuProbe(probe.clone()));
+        // The length of the padding is encoded in 1, 2 or 3 bytes depending on its value,
+        // so we pick the padding length consistent with its own header length
+        let padding_len = (0..=2)
+            .filter_map(|extra_header_len| {
+                (size as usize).checked_sub(empty_len + extra_header_len)
+            })
+            .find(|padding_len| {
+                let extra_header_len = match padding_len {
+                    0..=23 => 0,
+                    24..=255 => 1,
+                    _ => 2,
+                };
+                empty_len + extra_header_len + padding_len == size as usize
+            })
+            .unwrap_or(0);
+
+        probe.padding = CowBytes::from(vec![0u8; padding_len]);
+        probe
+    }
+}
+
+impl UdpPathMtuAck {
+    /// Constructor
+    pub fn new(size: u16) -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::messages::{
+        max_payload_size, RoutingNumber, UdpControlMessage, UdpPathMtuAck, UdpPathMtuProbe,
+        UdpTransportMessage, Version, MAX_ON_THE_WIRE_SIZE, MAX_PAYLOAD_SIZE, PATH_MTU_PROBE_SIZES,
+    };
+
+    #[test]
+    fn test_probe_size() {
+        for size in PATH_MTU_PROBE_SIZES
+            .into_iter()
+            .chain([MAX_ON_THE_WIRE_SIZE, 40, 300])
+        {
+            let probe = UdpControlMessage::PathMtuProbe(UdpPathMtuProbe::new(size as u16));
+            let len = minicbor::to_vec(probe).unwrap().len();
+            assert_eq!(len, size);
+        }
+    }
+
+    #[test]
+    fn test_max_payload_size() {
+        assert_eq!(max_payload_size(MAX_ON_THE_WIRE_SIZE), MAX_PAYLOAD_SIZE);
+        assert_eq!(max_payload_size(0), MAX_PAYLOAD_SIZE);
+
+        for size in PATH_MTU_PROBE_SIZES {
+            let msg = UdpTransportMessage::new(
+                Version(u8::MAX),
+                RoutingNumber(u16::MAX),
+                u32::MAX,
+                true,
+                vec![0u8; max_payload_size(size)],
+            );
+
+            let len = minicbor::to_vec(msg).unwrap().len();
+            assert!(len <= size);
+        }
+    }
+
+    #[test]
+    fn test_not_a_transport_message() {
+        let probe = UdpControlMessage::PathMtuProbe(UdpPathMtuProbe::new(1472));
+        let probe = minicbor::to_vec(probe).unwrap();
+        assert!(minicbor::decode::<UdpTransportMessage>(&probe).is_err());
+
+        let ack = UdpControlMessage::PathMtuAck(UdpPathMtuAck::new(1472));
+        let ack = minicbor::to_vec(ack).unwrap();
+        assert!(minicbor::decode::<UdpTransportMessage>(&ack).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::messages::UdpDeliveryHeader;
 use cfg_if::cfg_if;
 use minicbor::{CborLen, Decode, Encode};
 use ockam_core::compat::string::String;
@@ -15,6 +16,7 @@ pub struct UdpRoutingMessage<'a> {
     #[n(1)] pub return_route: Route,
     #[b(2)] pub payload: CowBytes<'a>,
     #[n(3)] pub tracing_context: Option<String>,
+    #[n(4)] pub delivery: Option<UdpDeliveryHeader>,
 }
 
 impl<'a> UdpRoutingMessage<'a> {
@@ -30,6 +32,15 @@ impl<'a> UdpRoutingMessage<'a> {
             return_route,
             payload,
             tracing_context,
+            delivery: None,
+        }
+    }
+
+    /// Specify the reliable delivery header
+    pub fn with_delivery(self, delivery: UdpDeliveryHeader) -> Self {
+        Self {
+            delivery: Some(delivery),
+            ..self
         }
     }
 
@@ -39,6 +50,7 @@ impl<'a> UdpRoutingMessage<'a> {
             return_route: self.return_route,
             payload: self.payload.into_owned().into(),
             tracing_context: self.tracing_context,
+            delivery: self.delivery,
         }
     }
 
@@ -6,7 +6,7 @@ use std::cmp::Ordering;
 /// Number of the [`UdpRoutingMessage`]. Each [`UdpRoutingMessage`] is assigned a value, which
 /// helps the receiver to assemble the message. Start with a random value and uses overflowing
 /// arithmetics for increment.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Encode, Decode, CborLen)]
 #[cbor(transparent)]
 pub struct RoutingNumber(#[n(0)] pub u16);
 
@@ -42,6 +42,12 @@ impl UdpBindOptions {
             vec![addresses.sender_address().clone()],
         );
 
+        // Allows the receiver to notify the sender about delivery acknowledgements
+        flow_controls.add_consumer(
+            addresses.delivery_ack_address().clone(),
+            &self.flow_control_id,
+        );
+
         for id in &self.consumer {
             flow_controls.add_consumer(addresses.sender_address().clone(), id);
         }
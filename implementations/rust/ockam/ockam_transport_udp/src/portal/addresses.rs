@@ -0,0 +1,37 @@
+use ockam_core::Address;
+
+/// Addresses of a UDP portal flow worker
+#[derive(Clone, Debug)]
+pub(crate) struct UdpPortalFlowAddresses {
+    /// Used to exchange messages with the other side of the portal
+    remote: Address,
+    /// Used to receive the datagrams read from the UDP socket
+    internal: Address,
+    /// Used to check if the flow is idle
+    idle_timer: Address,
+    /// Address of the processor reading datagrams for an outlet flow
+    receiver: Address,
+}
+
+impl UdpPortalFlowAddresses {
+    pub(crate) fn generate() -> Self {
+        Self {
+            remote: Address::random_tagged("UdpPortalFlow.remote"),
+            internal: Address::random_tagged("UdpPortalFlow.internal"),
+            idle_timer: Address::random_tagged("UdpPortalFlow.idle_timer"),
+            receiver: Address::random_tagged("UdpPortalFlow.receiver"),
+        }
+    }
+    pub fn remote(&self) -> &Address {
+        &self.remote
+    }
+    pub fn internal(&self) -> &Address {
+        &self.internal
+    }
+    pub fn idle_timer(&self) -> &Address {
+        &self.idle_timer
+    }
+    pub fn receiver(&self) -> &Address {
+        &self.receiver
+    }
+}
@@ -0,0 +1,69 @@
+use crate::portal::UdpPortalFlowAddresses;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{async_trait, AllowOnwardAddress, DenyAll, NeutralMessage, Processor, Result};
+use ockam_node::{Context, ProcessorBuilder};
+use tokio::net::UdpSocket;
+use tracing::{debug, instrument};
+
+/// Size of the buffer receiving datagrams, which fits the largest UDP payload
+pub(crate) const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// A UDP Portal flow receiver processor
+///
+/// Reads the datagrams sent by the target to the socket of an outlet flow,
+/// and forwards them to that flow.
+pub(crate) struct UdpPortalFlowReceiver {
+    socket: Arc<UdpSocket>,
+    addresses: UdpPortalFlowAddresses,
+    buffer: Vec<u8>,
+}
+
+impl UdpPortalFlowReceiver {
+    #[instrument(skip_all, name = "UdpPortalFlowReceiver::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        socket: Arc<UdpSocket>,
+        addresses: UdpPortalFlowAddresses,
+    ) -> Result<()> {
+        let receiver = Self {
+            socket,
+            addresses: addresses.clone(),
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+        };
+
+        ProcessorBuilder::new(receiver)
+            .with_address(addresses.receiver().clone())
+            .with_incoming_access_control(DenyAll)
+            .with_outgoing_access_control(AllowOnwardAddress(addresses.internal().clone()))
+            .start(ctx)
+            .await
+    }
+}
+
+#[async_trait]
+impl Processor for UdpPortalFlowReceiver {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "UdpPortalFlowReceiver::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let len = match self.socket.recv(&mut self.buffer).await {
+            Ok(len) => len,
+            Err(err) => {
+                // e.g. the target is not listening yet, which is not a reason to close the flow
+                debug!(
+                    "Failed to receive a datagram for {}: {err}",
+                    self.addresses.remote()
+                );
+                return Ok(true);
+            }
+        };
+
+        ctx.send(
+            self.addresses.internal().clone(),
+            NeutralMessage::from(self.buffer[..len].to_vec()),
+        )
+        .await?;
+
+        Ok(true)
+    }
+}
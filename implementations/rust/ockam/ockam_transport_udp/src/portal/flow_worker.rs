@@ -0,0 +1,379 @@
+use crate::portal::flow_receiver::UdpPortalFlowReceiver;
+use crate::portal::{UdpPortalFlowAddresses, UdpPortalMessage};
+use core::time::Duration;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{
+    async_trait, Address, AllowSourceAddress, DenyAll, IncomingAccessControl, LocalInfoIdentifier,
+    Mailbox, Mailboxes, NeutralMessage, OutgoingAccessControl, Result, Route, Routed,
+    SecureChannelLocalInfo, Worker,
+};
+use ockam_node::{Context, DelayedEvent, WorkerBuilder};
+use std::net::SocketAddr;
+use std::time::Instant;
+use tokio::net::UdpSocket;
+use tokio::sync::OwnedSemaphorePermit;
+use tracing::{debug, instrument, trace, warn};
+
+/// Maximum number of datagrams buffered by an inlet flow until the outlet flow is opened
+const MAX_PENDING_DATAGRAMS: usize = 32;
+
+/// Flows of an inlet, indexed by the address of their client
+pub(crate) type UdpInletFlows = Arc<Mutex<HashMap<SocketAddr, UdpPortalFlowAddresses>>>;
+
+/// Side of the portal a flow belongs to
+pub(crate) enum UdpPortalFlowType {
+    /// The flow sends its datagrams to this client, using the socket of the inlet
+    Inlet {
+        client: SocketAddr,
+        flows: UdpInletFlows,
+    },
+    /// The flow uses a socket connected to the outlet target
+    Outlet,
+}
+
+/// A UDP Portal flow worker
+///
+/// Each flow corresponds to one client of an inlet, i.e. to one source address,
+/// and exchanges the datagrams of that client with the flow created by the outlet for it.
+/// A flow is closed on both sides when no datagram was exchanged for the idle timeout.
+pub(crate) struct UdpPortalFlowWorker {
+    flow_type: UdpPortalFlowType,
+    socket: Arc<UdpSocket>,
+    addresses: UdpPortalFlowAddresses,
+    remote_route: Option<Route>,
+    /// Route used by an inlet flow to open the outlet flow
+    outlet_listener_route: Option<Route>,
+    their_identifier: Option<LocalInfoIdentifier>,
+    pending_datagrams: Vec<Vec<u8>>,
+    idle_timer: DelayedEvent<()>,
+    idle_timeout: Duration,
+    last_activity: Instant,
+    /// Permit of an outlet flow, counting it as one of the flows of the outlet until it is dropped
+    _flow_permit: Option<OwnedSemaphorePermit>,
+}
+
+impl UdpPortalFlowWorker {
+    /// Start a new flow for a client of an inlet. The flow opens the outlet flow
+    /// and buffers the first datagrams until it is opened
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all, name = "UdpPortalFlowWorker::start_new_inlet")]
+    pub(crate) async fn start_new_inlet(
+        ctx: &Context,
+        socket: Arc<UdpSocket>,
+        client: SocketAddr,
+        flows: UdpInletFlows,
+        outlet_listener_route: Route,
+        addresses: UdpPortalFlowAddresses,
+        listener_address: Address,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        idle_timeout: Duration,
+    ) -> Result<()> {
+        Self::start(
+            ctx,
+            UdpPortalFlowType::Inlet { client, flows },
+            socket,
+            outlet_listener_route,
+            None,
+            addresses,
+            None,
+            listener_address,
+            incoming_access_control,
+            outgoing_access_control,
+            idle_timeout,
+        )
+        .await
+    }
+
+    /// Start a new flow for an outlet, sending the datagrams of the inlet flow
+    /// through a socket connected to the target
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all, name = "UdpPortalFlowWorker::start_new_outlet")]
+    pub(crate) async fn start_new_outlet(
+        ctx: &Context,
+        socket: Arc<UdpSocket>,
+        remote_route: Route,
+        their_identifier: Option<LocalInfoIdentifier>,
+        addresses: UdpPortalFlowAddresses,
+        flow_permit: OwnedSemaphorePermit,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        idle_timeout: Duration,
+    ) -> Result<()> {
+        let receiver_address = addresses.receiver().clone();
+        Self::start(
+            ctx,
+            UdpPortalFlowType::Outlet,
+            socket.clone(),
+            remote_route,
+            their_identifier,
+            addresses.clone(),
+            Some(flow_permit),
+            receiver_address,
+            incoming_access_control,
+            outgoing_access_control,
+            idle_timeout,
+        )
+        .await?;
+
+        UdpPortalFlowReceiver::start(ctx, socket, addresses).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn start(
+        ctx: &Context,
+        flow_type: UdpPortalFlowType,
+        socket: Arc<UdpSocket>,
+        remote_route: Route,
+        their_identifier: Option<LocalInfoIdentifier>,
+        addresses: UdpPortalFlowAddresses,
+        flow_permit: Option<OwnedSemaphorePermit>,
+        datagrams_source_address: Address,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        idle_timeout: Duration,
+    ) -> Result<()> {
+        let idle_timer = DelayedEvent::create(ctx, addresses.idle_timer().clone(), ()).await?;
+
+        let remote_mailbox = Mailbox::new(
+            addresses.remote().clone(),
+            incoming_access_control,
+            outgoing_access_control,
+        );
+        let internal_mailbox = Mailbox::new(
+            addresses.internal().clone(),
+            Arc::new(AllowSourceAddress(datagrams_source_address)),
+            Arc::new(DenyAll),
+        );
+        let idle_timer_mailbox = Mailbox::new(
+            addresses.idle_timer().clone(),
+            Arc::new(AllowSourceAddress(idle_timer.address())),
+            Arc::new(DenyAll),
+        );
+
+        // The route of an inlet flow is the route to the outlet listener until the
+        // outlet flow is opened
+        let (remote_route, outlet_listener_route) = match flow_type {
+            UdpPortalFlowType::Inlet { .. } => (None, Some(remote_route)),
+            UdpPortalFlowType::Outlet => (Some(remote_route), None),
+        };
+
+        let worker = Self {
+            flow_type,
+            socket,
+            addresses: addresses.clone(),
+            remote_route,
+            outlet_listener_route,
+            their_identifier,
+            pending_datagrams: vec![],
+            idle_timer,
+            idle_timeout,
+            last_activity: Instant::now(),
+            _flow_permit: flow_permit,
+        };
+
+        WorkerBuilder::new(worker)
+            .with_mailboxes(Mailboxes::new(
+                remote_mailbox,
+                vec![internal_mailbox, idle_timer_mailbox],
+            ))
+            .start(ctx)
+            .await
+    }
+}
+
+impl UdpPortalFlowWorker {
+    /// Send a message to the other side of the portal
+    async fn send_to_remote(&self, ctx: &Context, message: UdpPortalMessage<'_>) -> Result<()> {
+        if let Some(remote_route) = &self.remote_route {
+            ctx.send_from_address(
+                remote_route.clone(),
+                message.to_neutral_message()?,
+                self.addresses.remote().clone(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Send a datagram received from the other side of the portal to the client or the target
+    async fn send_to_socket(&self, datagram: &[u8]) {
+        let res = match &self.flow_type {
+            UdpPortalFlowType::Inlet { client, .. } => self.socket.send_to(datagram, client).await,
+            UdpPortalFlowType::Outlet => self.socket.send(datagram).await,
+        };
+
+        // Datagrams may be lost anyway, so errors don't close the flow
+        if let Err(err) = res {
+            debug!(
+                "Failed to send a datagram from {}: {err}",
+                self.addresses.remote()
+            );
+        }
+    }
+
+    async fn handle_datagram(&mut self, ctx: &Context, datagram: Vec<u8>) -> Result<()> {
+        self.last_activity = Instant::now();
+
+        if self.remote_route.is_some() {
+            return self
+                .send_to_remote(ctx, UdpPortalMessage::Datagram(datagram.into()))
+                .await;
+        }
+
+        if self.pending_datagrams.len() < MAX_PENDING_DATAGRAMS {
+            self.pending_datagrams.push(datagram);
+        } else {
+            trace!(
+                "Dropping a datagram for {} until the flow is opened",
+                self.addresses.remote()
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn handle_remote_message(
+        &mut self,
+        ctx: &mut Context,
+        msg: Routed<NeutralMessage>,
+    ) -> Result<()> {
+        let their_identifier = SecureChannelLocalInfo::find_info(msg.local_message())
+            .map(|l| l.their_identifier())
+            .ok();
+        let return_route = msg.return_route().clone();
+        let payload = msg.into_payload();
+        let msg = UdpPortalMessage::decode(&payload)?;
+
+        if let UdpPortalMessage::Opened = msg {
+            if self.remote_route.is_some() {
+                warn!("The flow {} is already opened", self.addresses.remote());
+                return Ok(());
+            }
+
+            debug!("Opened the flow {}", self.addresses.remote());
+            self.remote_route = Some(return_route);
+            self.their_identifier = their_identifier;
+            self.last_activity = Instant::now();
+            for datagram in core::mem::take(&mut self.pending_datagrams) {
+                self.send_to_remote(ctx, UdpPortalMessage::Datagram(datagram.into()))
+                    .await?;
+            }
+            return Ok(());
+        }
+
+        if self.remote_route.is_none() {
+            warn!(
+                "Dropping a message received before the flow {} is opened",
+                self.addresses.remote()
+            );
+            return Ok(());
+        }
+
+        if their_identifier != self.their_identifier {
+            warn!(
+                "Identity changed for the flow {} from {:?} to {:?}",
+                self.addresses.remote(),
+                self.their_identifier.as_ref().map(|i| i.to_string()),
+                their_identifier.as_ref().map(|i| i.to_string()),
+            );
+            return Ok(());
+        }
+
+        match msg {
+            UdpPortalMessage::Datagram(datagram) => {
+                self.last_activity = Instant::now();
+                self.send_to_socket(&datagram).await;
+            }
+            UdpPortalMessage::Close => {
+                debug!("The flow {} was closed remotely", self.addresses.remote());
+                self.remote_route = None;
+                ctx.stop_worker(self.addresses.remote().clone()).await?;
+            }
+            UdpPortalMessage::Open | UdpPortalMessage::Opened => {
+                warn!(
+                    "Unexpected message received by the flow {}",
+                    self.addresses.remote()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Close the flow if it has been idle for long enough
+    async fn handle_idle_timer(&mut self, ctx: &mut Context) -> Result<()> {
+        let elapsed = self.last_activity.elapsed();
+        if elapsed < self.idle_timeout {
+            return self.idle_timer.schedule(self.idle_timeout - elapsed).await;
+        }
+
+        debug!("Closing the idle flow {}", self.addresses.remote());
+        self.send_to_remote(ctx, UdpPortalMessage::Close).await?;
+        self.remote_route = None;
+        ctx.stop_worker(self.addresses.remote().clone()).await
+    }
+}
+
+#[async_trait]
+impl Worker for UdpPortalFlowWorker {
+    type Context = Context;
+    type Message = NeutralMessage;
+
+    #[instrument(skip_all, name = "UdpPortalFlowWorker::initialize")]
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        match &self.outlet_listener_route {
+            Some(outlet_listener_route) => {
+                ctx.send_from_address(
+                    outlet_listener_route.clone(),
+                    UdpPortalMessage::Open.to_neutral_message()?,
+                    self.addresses.remote().clone(),
+                )
+                .await?
+            }
+            // Let the inlet flow know that the outlet flow is ready to receive datagrams
+            None => self.send_to_remote(ctx, UdpPortalMessage::Opened).await?,
+        }
+        self.idle_timer.schedule(self.idle_timeout).await
+    }
+
+    #[instrument(skip_all, name = "UdpPortalFlowWorker::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.idle_timer.cancel();
+
+        match &self.flow_type {
+            UdpPortalFlowType::Inlet { client, flows } => {
+                let mut flows = flows.lock().unwrap();
+                // The client may already use a new flow
+                if flows.get(client).map(|a| a.remote()) == Some(self.addresses.remote()) {
+                    flows.remove(client);
+                }
+            }
+            UdpPortalFlowType::Outlet => {
+                let _ = ctx.stop_processor(self.addresses.receiver().clone()).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UdpPortalFlowWorker::handle_message")]
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let msg_addr = msg.msg_addr();
+        if &msg_addr == self.addresses.remote() {
+            self.handle_remote_message(ctx, msg).await
+        } else if &msg_addr == self.addresses.internal() {
+            self.handle_datagram(ctx, msg.into_payload()).await
+        } else if &msg_addr == self.addresses.idle_timer() {
+            self.handle_idle_timer(ctx).await
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::portal::flow_receiver::MAX_DATAGRAM_SIZE;
+use crate::portal::{UdpInletFlows, UdpPortalFlowAddresses, UdpPortalFlowWorker};
+use crate::UdpInletOptions;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{
+    async_trait, Address, AllowAll, DenyAll, NeutralMessage, Processor, Result, Route,
+};
+use ockam_node::{Context, ProcessorBuilder};
+use ockam_transport_core::TransportError;
+use std::net::SocketAddr;
+use tokio::net::UdpSocket;
+use tracing::{debug, error, instrument, warn};
+
+/// A UDP Portal Inlet listen processor
+///
+/// UDP Portal Inlet listen processors are created by `UdpTransport`
+/// after a call is made to
+/// [`UdpTransport::create_inlet`](crate::UdpTransport::create_inlet).
+/// A flow is started for each new source address of the received datagrams.
+pub(crate) struct UdpInletListenProcessor {
+    socket: Arc<UdpSocket>,
+    outlet_listener_route: Route,
+    options: UdpInletOptions,
+    flows: UdpInletFlows,
+    buffer: Vec<u8>,
+}
+
+impl UdpInletListenProcessor {
+    /// Start a new `UdpInletListenProcessor`, return the bound socket address
+    #[instrument(skip_all, name = "UdpInletListenProcessor::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        processor_address: Address,
+        outlet_listener_route: Route,
+        addr: SocketAddr,
+        options: UdpInletOptions,
+    ) -> Result<SocketAddr> {
+        debug!("Binding UdpInletListenProcessor to {}", addr);
+        let socket = match UdpSocket::bind(addr).await {
+            Ok(socket) => socket,
+            Err(err) => {
+                error!(%addr, %err, "could not bind to address");
+                return Err(TransportError::BindFailed)?;
+            }
+        };
+        let socket_addr = socket
+            .local_addr()
+            .map_err(|_| TransportError::BindFailed)?;
+
+        let processor = Self {
+            socket: Arc::new(socket),
+            outlet_listener_route,
+            options,
+            flows: Default::default(),
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+        };
+
+        ProcessorBuilder::new(processor)
+            .with_address(processor_address)
+            .with_incoming_access_control(DenyAll)
+            // Only the internal mailboxes of the flows accept the datagrams
+            .with_outgoing_access_control(AllowAll)
+            .start(ctx)
+            .await?;
+
+        Ok(socket_addr)
+    }
+
+    /// Start a flow for a new client
+    async fn start_flow(
+        &self,
+        ctx: &Context,
+        client: SocketAddr,
+    ) -> Result<UdpPortalFlowAddresses> {
+        let addresses = UdpPortalFlowAddresses::generate();
+
+        let next = self.outlet_listener_route.next()?;
+        UdpInletOptions::setup_flow_control(ctx.flow_controls(), &addresses, next);
+
+        self.flows.lock().unwrap().insert(client, addresses.clone());
+
+        UdpPortalFlowWorker::start_new_inlet(
+            ctx,
+            self.socket.clone(),
+            client,
+            self.flows.clone(),
+            self.outlet_listener_route.clone(),
+            addresses.clone(),
+            ctx.address(),
+            self.options.incoming_access_control.clone(),
+            self.options.outgoing_access_control.clone(),
+            self.options.idle_timeout,
+        )
+        .await?;
+
+        debug!(
+            "Started the UDP Inlet flow {} for {client}",
+            addresses.remote()
+        );
+
+        Ok(addresses)
+    }
+}
+
+#[async_trait]
+impl Processor for UdpInletListenProcessor {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "UdpInletListenProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        let flows: Vec<Address> = self
+            .flows
+            .lock()
+            .unwrap()
+            .values()
+            .map(|addresses| addresses.remote().clone())
+            .collect();
+        for flow in flows {
+            let _ = ctx.stop_worker(flow).await;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UdpInletListenProcessor::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let (len, client) = match self.socket.recv_from(&mut self.buffer).await {
+            Ok(res) => res,
+            Err(err) => {
+                warn!("Failed to receive a datagram: {err}");
+                return Ok(true);
+            }
+        };
+
+        let flow = self.flows.lock().unwrap().get(&client).cloned();
+        let addresses = match flow {
+            Some(addresses) => addresses,
+            None => {
+                if self.flows.lock().unwrap().len() >= self.options.max_flows {
+                    debug!(
+                        "Dropping a datagram from {client}, the maximum number of flows is reached"
+                    );
+                    return Ok(true);
+                }
+                self.start_flow(ctx, client).await?
+            }
+        };
+
+        // The flow may have just expired, in which case the datagram is lost
+        if let Err(err) = ctx
+            .send(
+                addresses.internal().clone(),
+                NeutralMessage::from(self.buffer[..len].to_vec()),
+            )
+            .await
+        {
+            debug!("Failed to forward a datagram from {client}: {err}");
+        }
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,56 @@
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{CowBytes, Error, NeutralMessage, Result};
+
+/// Messages exchanged between the two sides of a UDP portal flow.
+///
+///  - An inlet flow sends [`UdpPortalMessage::Open`] to the outlet, which starts an outlet flow
+///    and answers with [`UdpPortalMessage::Opened`] from the address of that flow.
+///  - The datagrams are then exchanged as [`UdpPortalMessage::Datagram`]s.
+///  - The side where the flow expires first sends [`UdpPortalMessage::Close`].
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum UdpPortalMessage<'a> {
+    /// Open a new flow
+    #[n(0)] Open,
+    /// The flow was opened by the outlet
+    #[n(1)] Opened,
+    /// A datagram sent by a client or by the target
+    #[n(2)] Datagram(#[b(0)] CowBytes<'a>),
+    /// The flow is closed
+    #[n(3)] Close,
+}
+
+impl<'a> UdpPortalMessage<'a> {
+    /// Decode a message without copying the datagram
+    pub fn decode(slice: &'a [u8]) -> Result<Self> {
+        minicbor::decode(slice)
+            .map_err(|e| Error::new(Origin::Transport, Kind::Protocol, e.to_string()))
+    }
+
+    /// Encode the message into a NeutralMessage
+    pub fn to_neutral_message(&self) -> Result<NeutralMessage> {
+        Ok(NeutralMessage::from(ockam_core::cbor_encode_preallocate(
+            self,
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_can_be_encoded() -> Result<()> {
+        for message in [
+            UdpPortalMessage::Open,
+            UdpPortalMessage::Opened,
+            UdpPortalMessage::Datagram(b"hello".as_slice().into()),
+            UdpPortalMessage::Close,
+        ] {
+            let encoded: Vec<u8> = message.to_neutral_message()?.into();
+            assert_eq!(UdpPortalMessage::decode(&encoded)?, message);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+mod addresses;
+mod flow_receiver;
+mod flow_worker;
+mod inlet_listener;
+mod messages;
+mod options;
+mod outlet_listener;
+
+pub(crate) use addresses::*;
+pub(crate) use flow_worker::*;
+pub(crate) use inlet_listener::*;
+pub use messages::*;
+pub use options::*;
+pub(crate) use outlet_listener::*;
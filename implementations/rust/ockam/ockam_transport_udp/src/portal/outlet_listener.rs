@@ -0,0 +1,130 @@
+use crate::portal::{UdpPortalFlowAddresses, UdpPortalFlowWorker, UdpPortalMessage};
+use crate::UdpOutletOptions;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{
+    async_trait, Address, DenyAll, NeutralMessage, Result, Routed, SecureChannelLocalInfo, Worker,
+};
+use ockam_node::compat::asynchronous::resolve_peer;
+use ockam_node::{Context, WorkerBuilder};
+use ockam_transport_core::{HostnamePort, TransportError};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::UdpSocket;
+use tokio::sync::Semaphore;
+use tracing::{debug, instrument, warn};
+
+/// A UDP Portal Outlet listen worker
+///
+/// UDP Portal Outlet listen workers are created by `UdpTransport`
+/// after a call is made to
+/// [`UdpTransport::create_outlet`](crate::UdpTransport::create_outlet).
+/// A flow is started for each flow opened by an inlet.
+pub(crate) struct UdpOutletListenWorker {
+    hostname_port: HostnamePort,
+    options: UdpOutletOptions,
+    /// One permit is held by each flow, until it is stopped
+    flows: Arc<Semaphore>,
+}
+
+impl UdpOutletListenWorker {
+    #[instrument(skip_all, name = "UdpOutletListenWorker::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        address: Address,
+        hostname_port: HostnamePort,
+        options: UdpOutletOptions,
+    ) -> Result<()> {
+        let access_control = options.incoming_access_control.clone();
+
+        options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
+
+        let flows = Arc::new(Semaphore::new(options.max_flows));
+        let worker = Self {
+            hostname_port,
+            options,
+            flows,
+        };
+        WorkerBuilder::new(worker)
+            .with_address(address)
+            .with_incoming_access_control_arc(access_control)
+            .with_outgoing_access_control(DenyAll)
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Create a socket connected to the target, so that only its datagrams are received
+    async fn connect(&self) -> Result<UdpSocket> {
+        let peer = resolve_peer(self.hostname_port.to_string()).await?;
+        let bind_addr = match peer.ip() {
+            IpAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+            IpAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+        };
+        let socket = UdpSocket::bind(bind_addr)
+            .await
+            .map_err(|_| TransportError::BindFailed)?;
+        socket.connect(peer).await.map_err(TransportError::from)?;
+        Ok(socket)
+    }
+}
+
+#[async_trait]
+impl Worker for UdpOutletListenWorker {
+    type Context = Context;
+    type Message = NeutralMessage;
+
+    #[instrument(skip_all, name = "UdpOutletListenWorker::handle_message")]
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let their_identifier = SecureChannelLocalInfo::find_info(msg.local_message())
+            .map(|l| l.their_identifier())
+            .ok();
+        let src_addr = msg.src_addr();
+        let return_route = msg.return_route().clone();
+        let UdpPortalMessage::Open = UdpPortalMessage::decode(msg.payload())? else {
+            return Err(TransportError::Protocol)?;
+        };
+
+        let Ok(flow_permit) = self.flows.clone().try_acquire_owned() else {
+            warn!(
+                "Refusing to open a flow to {}, the maximum number of flows is reached",
+                self.hostname_port
+            );
+            return Ok(());
+        };
+
+        let socket = self.connect().await?;
+
+        let addresses = UdpPortalFlowAddresses::generate();
+
+        UdpOutletOptions::setup_flow_control_for_outlet_flow(
+            ctx.flow_controls(),
+            &addresses,
+            &src_addr,
+        );
+
+        UdpPortalFlowWorker::start_new_outlet(
+            ctx,
+            Arc::new(socket),
+            return_route,
+            their_identifier,
+            addresses.clone(),
+            flow_permit,
+            self.options.incoming_access_control.clone(),
+            self.options.outgoing_access_control.clone(),
+            self.options.idle_timeout,
+        )
+        .await?;
+
+        debug!(
+            "Started the UDP Outlet flow {} to {}",
+            addresses.remote(),
+            self.hostname_port
+        );
+
+        Ok(())
+    }
+}
@@ -57,16 +57,27 @@ impl UdpPunctureNegotiationListener {
         msg: UdpPunctureNegotiationMessageInitiate,
         return_route: Route,
     ) -> Result<()> {
+        // Reliable delivery is only used if the initiator supports it
+        let reliable_delivery = msg.reliable_delivery == Some(true);
+
         // We create a new bind for each puncture. Ownership will be transferred to the
         // UdpPunctureReceiverWorker which is responsible for stopping it eventually
         // TODO: Consider limiting incoming access control for that bind
         let udp_bind = udp
             .bind(
-                UdpBindArguments::new().with_bind_address("0.0.0.0:0")?,
+                UdpBindArguments::new()
+                    .with_bind_address("0.0.0.0:0")?
+                    .with_path_mtu_discovery(true)
+                    .with_reliable_delivery(reliable_delivery),
                 UdpBindOptions::new(),
             )
             .await?;
 
+        // The Rendezvous service may not support reliable delivery
+        if reliable_delivery {
+            udp_bind.set_reliable_delivery(false)?;
+        }
+
         let client = RendezvousClient::new(&udp_bind, rendezvous_route);
         let my_udp_public_address = match client.get_my_address(&ctx).await {
             Ok(my_udp_public_address) => my_udp_public_address,
@@ -80,6 +91,10 @@ impl UdpPunctureNegotiationListener {
             }
         };
 
+        if reliable_delivery {
+            udp_bind.set_reliable_delivery(true)?;
+        }
+
         let initiator_remote_address = Address::from(msg.initiator_remote_address);
 
         let options = UdpPunctureOptions::new_with_spawner(flow_control_id);
@@ -112,6 +127,7 @@ impl UdpPunctureNegotiationListener {
             UdpPunctureNegotiationMessageAcknowledge {
                 responder_udp_public_address: my_udp_public_address,
                 responder_remote_address: my_remote_address.to_vec(),
+                reliable_delivery: Some(reliable_delivery),
             },
         )
         .await?;
@@ -8,6 +8,8 @@ use ockam_core::{Decodable, Encodable, Message, Result};
 pub struct UdpPunctureNegotiationMessageInitiate {
     #[n(0)] pub initiator_udp_public_address: String,
     #[n(1)] pub initiator_remote_address: Vec<u8>,
+    /// Whether the initiator supports reliable delivery. Not set by older initiators
+    #[n(2)] pub reliable_delivery: Option<bool>,
 }
 
 /// UDP Puncture negotiation starts with initiator sending this message
@@ -18,6 +20,8 @@ pub struct UdpPunctureNegotiationMessageInitiate {
 pub struct UdpPunctureNegotiationMessageAcknowledge {
     #[n(0)] pub responder_udp_public_address: String,
     #[n(1)] pub responder_remote_address: Vec<u8>,
+    /// Whether both sides use reliable delivery. Not set by older responders
+    #[n(2)] pub reliable_delivery: Option<bool>,
 }
 
 impl Encodable for UdpPunctureNegotiationMessageInitiate {
@@ -47,3 +51,43 @@ impl Decodable for UdpPunctureNegotiationMessageAcknowledge {
 }
 
 impl Message for UdpPunctureNegotiationMessageAcknowledge {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Message sent by nodes which don't support reliable delivery
+    #[derive(Encode, Decode, CborLen, Debug, Clone)]
+    #[rustfmt::skip]
+    struct PreviousUdpPunctureNegotiationMessageInitiate {
+        #[n(0)] initiator_udp_public_address: String,
+        #[n(1)] initiator_remote_address: Vec<u8>,
+    }
+
+    #[test]
+    fn reliable_delivery_is_not_set_by_older_nodes() {
+        let previous = PreviousUdpPunctureNegotiationMessageInitiate {
+            initiator_udp_public_address: "1.2.3.4:5000".to_string(),
+            initiator_remote_address: vec![1, 2, 3],
+        };
+        let encoded = minicbor::to_vec(previous).unwrap();
+
+        let decoded: UdpPunctureNegotiationMessageInitiate = minicbor::decode(&encoded).unwrap();
+        assert_eq!(decoded.initiator_udp_public_address, "1.2.3.4:5000");
+        assert_eq!(decoded.reliable_delivery, None);
+    }
+
+    #[test]
+    fn reliable_delivery_is_ignored_by_older_nodes() {
+        let acknowledge = UdpPunctureNegotiationMessageAcknowledge {
+            responder_udp_public_address: "1.2.3.4:5000".to_string(),
+            responder_remote_address: vec![1, 2, 3],
+            reliable_delivery: Some(true),
+        };
+        let encoded = minicbor::to_vec(acknowledge).unwrap();
+
+        let decoded: PreviousUdpPunctureNegotiationMessageInitiate =
+            minicbor::decode(&encoded).unwrap();
+        assert_eq!(decoded.initiator_remote_address, vec![1, 2, 3]);
+    }
+}
@@ -42,11 +42,18 @@ impl UdpPunctureNegotiation {
         // TODO: Consider limiting incoming access control for that bind
         let udp_bind = udp
             .bind(
-                UdpBindArguments::new().with_bind_address("0.0.0.0:0")?,
+                UdpBindArguments::new()
+                    .with_bind_address("0.0.0.0:0")?
+                    .with_path_mtu_discovery(true)
+                    .with_reliable_delivery(true),
                 UdpBindOptions::new(),
             )
             .await?;
 
+        // Reliable delivery is only used once the responder confirms it supports it.
+        // The Rendezvous service may not support it either
+        udp_bind.set_reliable_delivery(false)?;
+
         debug!(
             "Initializing UdpPunctureNegotiation Initiator at {}",
             child_ctx.address_ref()
@@ -80,6 +87,7 @@ impl UdpPunctureNegotiation {
                 UdpPunctureNegotiationMessageInitiate {
                     initiator_udp_public_address: my_udp_public_address,
                     initiator_remote_address: my_remote_address.to_vec(),
+                    reliable_delivery: Some(true),
                 },
             )
             .await?;
@@ -117,6 +125,15 @@ impl UdpPunctureNegotiation {
             }
         };
 
+        // Older responders don't acknowledge routing messages sent with reliable delivery
+        let reliable_delivery = response.reliable_delivery == Some(true);
+        debug!(
+            "Reliable delivery for the UDP puncture at {}: {}",
+            child_ctx.address_ref(),
+            reliable_delivery
+        );
+        udp_bind.set_reliable_delivery(reliable_delivery)?;
+
         let options = UdpPunctureOptions::new();
 
         // Start puncture
@@ -1,16 +1,24 @@
-use crate::workers::{split_socket, Addresses, UdpReceiverProcessor, UdpSenderWorker};
+use crate::messages::MAX_ON_THE_WIRE_SIZE;
+use crate::workers::{
+    set_dont_fragment, split_socket, Addresses, PathMtuStorage, ReliableDelivery,
+    UdpReceiverMetrics, UdpReceiverProcessor, UdpSenderWorker,
+};
 use crate::{UdpBindOptions, UdpTransport};
 use core::fmt;
 use core::fmt::Formatter;
+use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
-use ockam_core::{Address, AllowAll, DenyAll, Error, Result};
+use ockam_core::{
+    Address, AllowAll, AllowSourceAddress, DenyAll, Error, Mailbox, Mailboxes, Result,
+};
 use ockam_node::compat::asynchronous::resolve_peer;
-use ockam_node::{ProcessorBuilder, WorkerBuilder};
+use ockam_node::{DelayedEvent, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::{parse_socket_addr, TransportError};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::net::UdpSocket;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// UDP bind arguments
 pub struct UdpBindArguments {
@@ -18,6 +26,10 @@ pub struct UdpBindArguments {
     peer_address: Option<SocketAddr>,
     /// Local bind address
     bind_address: SocketAddr,
+    /// Whether we probe the Path MTU of each peer
+    path_mtu_discovery: bool,
+    /// Whether routing messages are acknowledged, retransmitted and delivered in order
+    reliable_delivery: bool,
 }
 
 impl Default for UdpBindArguments {
@@ -25,6 +37,8 @@ impl Default for UdpBindArguments {
         Self {
             peer_address: None,
             bind_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0),
+            path_mtu_discovery: false,
+            reliable_delivery: false,
         }
     }
 }
@@ -64,6 +78,31 @@ impl UdpBindArguments {
 
         self
     }
+
+    /// Enable Path MTU discovery. Each peer is probed for the largest datagram size that
+    /// reaches it, and messages are split into datagrams of that size.
+    /// Datagrams are sent with the Don't Fragment bit set, Path MTU discovery is disabled on
+    /// platforms where it can't be set.
+    /// Datagrams of 508 bytes are used until probes are acknowledged,
+    /// or if the peer doesn't support Path MTU discovery.
+    pub fn with_path_mtu_discovery(mut self, path_mtu_discovery: bool) -> Self {
+        self.path_mtu_discovery = path_mtu_discovery;
+
+        self
+    }
+
+    /// Enable reliable delivery. Routing messages sent to each peer are acknowledged by the
+    /// peer, retransmitted if lost, and delivered in the order they were sent, while the
+    /// sending rate is limited by a congestion window. This allows running protocols that
+    /// expect a reliable stream, e.g. TCP portals, on top of this transport.
+    /// The peer acknowledges routing messages even if it doesn't have reliable delivery enabled.
+    /// Older peers don't, in which case reliable delivery can be disabled with
+    /// [`UdpBind::set_reliable_delivery`].
+    pub fn with_reliable_delivery(mut self, reliable_delivery: bool) -> Self {
+        self.reliable_delivery = reliable_delivery;
+
+        self
+    }
 }
 
 impl UdpTransport {
@@ -98,6 +137,19 @@ impl UdpTransport {
             .local_addr()
             .map_err(|_| Error::new(Origin::Transport, Kind::Io, "invalid local address"))?;
 
+        // Path MTU probes are only meaningful if they are not fragmented
+        let path_mtu = if arguments.path_mtu_discovery {
+            match set_dont_fragment(&socket) {
+                Ok(()) => Some(PathMtuStorage::default()),
+                Err(err) => {
+                    warn!(%local_addr, "Path MTU discovery is disabled, the Don't Fragment bit can't be set: {}", err);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         // Split socket into sink and stream
         let (socket_read, socket_write) = split_socket(socket);
 
@@ -114,16 +166,64 @@ impl UdpTransport {
         let receiver_outgoing_access_control =
             options.create_receiver_outgoing_access_control(self.ctx.flow_controls());
 
-        let sender = UdpSenderWorker::new(addresses.clone(), socket_write, arguments.peer_address);
+        let main_mailbox = Mailbox::new(
+            addresses.sender_address().clone(),
+            Arc::new(AllowAll),
+            Arc::new(DenyAll),
+        );
+
+        let reliable_delivery_enabled = arguments
+            .reliable_delivery
+            .then(|| Arc::new(AtomicBool::new(true)));
+        let (reliable_delivery, mailboxes) = if let Some(enabled) = &reliable_delivery_enabled {
+            let retransmission_timer =
+                DelayedEvent::create(&self.ctx, addresses.retransmission_address().clone(), ())
+                    .await?;
+
+            let delivery_ack_mailbox = Mailbox::new(
+                addresses.delivery_ack_address().clone(),
+                Arc::new(AllowSourceAddress(addresses.receiver_address().clone())),
+                Arc::new(DenyAll),
+            );
+            let retransmission_mailbox = Mailbox::new(
+                addresses.retransmission_address().clone(),
+                Arc::new(AllowSourceAddress(retransmission_timer.address())),
+                Arc::new(DenyAll),
+            );
+
+            (
+                Some(ReliableDelivery::new(retransmission_timer, enabled.clone())),
+                Mailboxes::new(
+                    main_mailbox,
+                    vec![delivery_ack_mailbox, retransmission_mailbox],
+                ),
+            )
+        } else {
+            (None, Mailboxes::new(main_mailbox, vec![]))
+        };
+
+        let sender = UdpSenderWorker::new(
+            addresses.clone(),
+            socket_write.clone(),
+            arguments.peer_address,
+            path_mtu.clone(),
+            reliable_delivery,
+        );
         WorkerBuilder::new(sender)
-            .with_address(addresses.sender_address().clone())
-            .with_incoming_access_control(AllowAll)
-            .with_outgoing_access_control(DenyAll)
+            .with_mailboxes(mailboxes)
             .start(&self.ctx)
             .await?;
 
-        let receiver =
-            UdpReceiverProcessor::new(addresses.clone(), socket_read, arguments.peer_address);
+        let receiver_metrics = Arc::new(UdpReceiverMetrics::default());
+        let receiver = UdpReceiverProcessor::new(
+            addresses.clone(),
+            socket_read,
+            socket_write,
+            arguments.peer_address,
+            receiver_metrics.clone(),
+            path_mtu.clone(),
+            arguments.reliable_delivery,
+        );
         ProcessorBuilder::new(receiver)
             .with_address(addresses.receiver_address().clone())
             .with_incoming_access_control(DenyAll)
@@ -136,6 +236,9 @@ impl UdpTransport {
             arguments.peer_address,
             local_addr,
             flow_control_id,
+            receiver_metrics,
+            path_mtu,
+            reliable_delivery_enabled,
         );
 
         Ok(bind)
@@ -154,6 +257,9 @@ pub struct UdpBind {
     peer: Option<SocketAddr>,
     bind_address: SocketAddr,
     flow_control_id: FlowControlId,
+    receiver_metrics: Arc<UdpReceiverMetrics>,
+    path_mtu: Option<PathMtuStorage>,
+    reliable_delivery: Option<Arc<AtomicBool>>,
 }
 
 impl fmt::Display for UdpBind {
@@ -177,12 +283,18 @@ impl UdpBind {
         peer: Option<SocketAddr>,
         bind_address: SocketAddr,
         flow_control_id: FlowControlId,
+        receiver_metrics: Arc<UdpReceiverMetrics>,
+        path_mtu: Option<PathMtuStorage>,
+        reliable_delivery: Option<Arc<AtomicBool>>,
     ) -> Self {
         Self {
             addresses,
             peer,
             bind_address,
             flow_control_id,
+            receiver_metrics,
+            path_mtu,
+            reliable_delivery,
         }
     }
 
@@ -210,6 +322,43 @@ impl UdpBind {
     pub fn flow_control_id(&self) -> &FlowControlId {
         &self.flow_control_id
     }
+
+    /// Metrics collected while assembling received routing messages
+    pub fn receiver_metrics(&self) -> &UdpReceiverMetrics {
+        &self.receiver_metrics
+    }
+
+    /// Size of the datagrams currently sent to the given peer.
+    /// Always 508 bytes if Path MTU discovery is disabled.
+    pub fn path_mtu(&self, peer: &SocketAddr) -> usize {
+        self.path_mtu
+            .as_ref()
+            .and_then(|path_mtu| path_mtu.path_mtu(peer))
+            .unwrap_or(MAX_ON_THE_WIRE_SIZE)
+    }
+
+    /// Whether routing messages are currently sent with reliable delivery
+    pub fn reliable_delivery(&self) -> bool {
+        self.reliable_delivery
+            .as_ref()
+            .is_some_and(|enabled| enabled.load(Ordering::Relaxed))
+    }
+
+    /// Send the next routing messages with or without reliable delivery, for instance
+    /// once it is known whether the peer supports it.
+    /// Return an error if the bind was not created with reliable delivery.
+    pub fn set_reliable_delivery(&self, reliable_delivery: bool) -> Result<()> {
+        let Some(enabled) = &self.reliable_delivery else {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "reliable delivery was not enabled for this bind",
+            ));
+        };
+
+        enabled.store(reliable_delivery, Ordering::Relaxed);
+        Ok(())
+    }
 }
 
 impl From<UdpBind> for Address {
@@ -0,0 +1,120 @@
+use crate::portal::{UdpInletListenProcessor, UdpOutletListenWorker};
+use crate::{UdpInletOptions, UdpOutletOptions, UdpTransport};
+use core::fmt;
+use core::fmt::Formatter;
+use ockam_core::{Address, Result, Route};
+use ockam_transport_core::{parse_socket_addr, HostnamePort};
+use std::net::SocketAddr;
+use tracing::instrument;
+
+impl UdpTransport {
+    /// Create a UDP Inlet that listens on a local socket address and forwards the datagrams
+    /// of each of its clients to a UDP Outlet, through a flow which is closed when idle.
+    ///
+    /// ```rust
+    /// use ockam_transport_udp::{UdpInletOptions, UdpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{AllowAll, Result, route};
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let route_path = route!["outlet"];
+    ///
+    /// let udp = UdpTransport::create(&ctx).await?;
+    /// udp.create_inlet("localhost:5353", route_path, UdpInletOptions::new()).await?;
+    /// # udp.stop_inlet("inlet").await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self), fields(address = ? bind_addr.clone().into(), outlet_route = ? outlet_route.clone()))]
+    pub async fn create_inlet(
+        &self,
+        bind_addr: impl Into<String> + Clone + fmt::Debug,
+        outlet_route: impl Into<Route> + Clone + fmt::Debug,
+        options: UdpInletOptions,
+    ) -> Result<UdpInlet> {
+        let socket_address = parse_socket_addr(&bind_addr.into())?;
+        let processor_address = Address::random_tagged("UdpInletListenProcessor");
+        let socket_address = UdpInletListenProcessor::start(
+            &self.ctx,
+            processor_address.clone(),
+            outlet_route.into(),
+            socket_address,
+            options,
+        )
+        .await?;
+
+        Ok(UdpInlet::new(socket_address, processor_address))
+    }
+
+    /// Stop a UDP Inlet, and all its flows, given its processor address
+    #[instrument(skip(self), fields(address = ? addr.clone().into()))]
+    pub async fn stop_inlet(&self, addr: impl Into<Address> + Clone + fmt::Debug) -> Result<()> {
+        self.ctx.stop_processor(addr).await
+    }
+
+    /// Create a UDP Outlet worker at the given address, which sends the datagrams of the
+    /// flows opened by UDP Inlets to the given target
+    ///
+    /// ```rust
+    /// use ockam_transport_udp::{UdpOutletOptions, UdpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{AllowAll, Result};
+    /// # use ockam_transport_core::HostnamePort;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    ///
+    /// let udp = UdpTransport::create(&ctx).await?;
+    /// udp.create_outlet("outlet", HostnamePort::new("localhost", 53), UdpOutletOptions::new()).await?;
+    /// # udp.stop_outlet("outlet").await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self), fields(address = ? address.clone().into(), peer=peer.clone().to_string()))]
+    pub async fn create_outlet(
+        &self,
+        address: impl Into<Address> + Clone + fmt::Debug,
+        peer: HostnamePort,
+        options: UdpOutletOptions,
+    ) -> Result<()> {
+        UdpOutletListenWorker::start(&self.ctx, address.into(), peer, options).await
+    }
+
+    /// Stop a UDP Outlet given its worker address. Its flows expire when they are idle.
+    #[instrument(skip(self), fields(address = ? addr.clone().into()))]
+    pub async fn stop_outlet(&self, addr: impl Into<Address> + Clone + fmt::Debug) -> Result<()> {
+        self.ctx.stop_worker(addr).await
+    }
+}
+
+/// Result of [`UdpTransport::create_inlet`] call.
+#[derive(Clone, Debug)]
+pub struct UdpInlet {
+    socket_address: SocketAddr,
+    processor_address: Address,
+}
+
+impl fmt::Display for UdpInlet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}, Processor: {}",
+            self.socket_address, self.processor_address
+        )
+    }
+}
+
+impl UdpInlet {
+    /// Constructor
+    pub(crate) fn new(socket_address: SocketAddr, processor_address: Address) -> Self {
+        Self {
+            socket_address,
+            processor_address,
+        }
+    }
+
+    /// Socket Address the inlet is bound to
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+
+    /// Inlet processor Address
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+}
@@ -4,16 +4,24 @@ use ockam_core::Address;
 pub(crate) struct Addresses {
     sender_address: Address,
     receiver_address: Address,
+    /// Used by the receiver to notify the sender about delivery acknowledgements
+    delivery_ack_address: Address,
+    /// Used to trigger retransmissions
+    retransmission_address: Address,
 }
 
 impl Addresses {
     pub(crate) fn generate() -> Self {
         let sender_address = Address::random_tagged("UdpSender");
         let receiver_address = Address::random_tagged("UdpReceiver");
+        let delivery_ack_address = Address::random_tagged("UdpSender.delivery_ack");
+        let retransmission_address = Address::random_tagged("UdpSender.retransmission");
 
         Self {
             sender_address,
             receiver_address,
+            delivery_ack_address,
+            retransmission_address,
         }
     }
     pub fn sender_address(&self) -> &Address {
@@ -22,4 +30,14 @@ impl Addresses {
     pub fn receiver_address(&self) -> &Address {
         &self.receiver_address
     }
+    pub fn delivery_ack_address(&self) -> &Address {
+        &self.delivery_ack_address
+    }
+    pub fn retransmission_address(&self) -> &Address {
+        &self.retransmission_address
+    }
+    /// Addresses that can only be reached from inside the node
+    pub fn is_internal(&self, address: &Address) -> bool {
+        address == &self.delivery_ack_address || address == &self.retransmission_address
+    }
 }
@@ -1,9 +1,19 @@
 mod addresses;
+mod path_mtu;
+mod pending_messages;
 mod receiver;
+mod receiver_metrics;
+mod reliable_receiver;
+mod reliable_sender;
 mod sender;
 mod socket_split;
 
 pub(crate) use addresses::*;
+pub(crate) use path_mtu::*;
+pub(crate) use pending_messages::*;
 pub(crate) use receiver::*;
+pub use receiver_metrics::*;
+pub(crate) use reliable_receiver::*;
+pub(crate) use reliable_sender::*;
 pub(crate) use sender::*;
 pub(crate) use socket_split::*;
@@ -0,0 +1,287 @@
+use crate::messages::{MAX_DATAGRAM_SIZE, MAX_ON_THE_WIRE_SIZE};
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// How often we probe the Path MTU of a peer again, since the path may change over time
+pub(crate) const PATH_MTU_PROBE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How long we wait for probes to be acknowledged before settling on the largest acknowledged
+/// size. Until then the largest size acknowledged so far is used.
+pub(crate) const PATH_MTU_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Path MTU state of a single peer
+#[derive(Debug)]
+struct PeerPathMtu {
+    /// Datagram size currently used for that peer
+    datagram_size: usize,
+    /// When the last probing round started
+    probed_at: Instant,
+    /// Largest probe acknowledged during the last probing round
+    largest_acknowledged: Option<usize>,
+    /// Smallest size which couldn't be sent during the last probing round because
+    /// it exceeds the Path MTU known by the local host
+    too_big: Option<usize>,
+    /// Whether the result of the last probing round has been applied
+    round_completed: bool,
+    /// Whether a new probing round must be started right away
+    probe_again: bool,
+}
+
+impl PeerPathMtu {
+    fn new(now: Instant) -> Self {
+        Self {
+            datagram_size: MAX_ON_THE_WIRE_SIZE,
+            probed_at: now,
+            largest_acknowledged: None,
+            too_big: None,
+            round_completed: false,
+            probe_again: false,
+        }
+    }
+}
+
+/// Path MTU discovered for each peer. Shared between the sender, which sends probes and
+/// fragments messages according to the discovered size, and the receiver, which receives
+/// acknowledgements for the probes.
+///
+/// Sizes are expressed as UDP payload sizes, i.e. the size of a datagram we send, and are never
+/// lower than [`MAX_ON_THE_WIRE_SIZE`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PathMtuStorage(Arc<Mutex<HashMap<SocketAddr, PeerPathMtu>>>);
+
+impl PathMtuStorage {
+    /// Return the datagram size to use for a peer, and whether a new probing round
+    /// should be started for it
+    pub(crate) fn datagram_size(&self, peer: SocketAddr, now: Instant) -> (usize, bool) {
+        let mut peers = self.0.lock().unwrap();
+
+        let state = match peers.get_mut(&peer) {
+            Some(state) => state,
+            None => {
+                peers.insert(peer, PeerPathMtu::new(now));
+                return (MAX_ON_THE_WIRE_SIZE, true);
+            }
+        };
+
+        let elapsed = now.saturating_duration_since(state.probed_at);
+
+        if !state.round_completed && elapsed >= PATH_MTU_PROBE_TIMEOUT {
+            // Larger sizes may have stopped working since the previous round
+            let datagram_size = state.largest_acknowledged.unwrap_or(MAX_ON_THE_WIRE_SIZE);
+            if datagram_size != state.datagram_size {
+                debug!(
+                    "Path MTU for {} changed from {} to {}",
+                    peer, state.datagram_size, datagram_size
+                );
+            }
+            state.datagram_size = datagram_size;
+            state.round_completed = true;
+        }
+
+        if elapsed >= PATH_MTU_PROBE_INTERVAL || state.probe_again {
+            state.probed_at = now;
+            state.probe_again = false;
+            state.largest_acknowledged = None;
+            state.too_big = None;
+            state.round_completed = false;
+            return (state.datagram_size, true);
+        }
+
+        (state.datagram_size, false)
+    }
+
+    /// Record that a probe of the given size was received by the peer
+    pub(crate) fn acknowledge(&self, peer: SocketAddr, size: usize) {
+        if !(MAX_ON_THE_WIRE_SIZE..=MAX_DATAGRAM_SIZE).contains(&size) {
+            return;
+        }
+
+        let mut peers = self.0.lock().unwrap();
+
+        // Only accept acknowledgements for peers that we actually probed
+        let Some(state) = peers.get_mut(&peer) else {
+            return;
+        };
+
+        // A probe which couldn't be sent can't have been received
+        if state.too_big.is_some_and(|too_big| size >= too_big) {
+            return;
+        }
+
+        state.largest_acknowledged = Some(state.largest_acknowledged.unwrap_or(0).max(size));
+
+        // Larger sizes are used right away, smaller ones when the probing round completes
+        if size > state.datagram_size {
+            debug!(
+                "Path MTU for {} changed from {} to {}",
+                peer, state.datagram_size, size
+            );
+            state.datagram_size = size;
+        }
+    }
+
+    /// Record that a datagram of the given size couldn't be sent to the peer because it exceeds
+    /// the Path MTU known by the local host. If that size is currently used, the smallest
+    /// datagram size is used until the peer is probed again.
+    pub(crate) fn too_big(&self, peer: SocketAddr, size: usize) {
+        let mut peers = self.0.lock().unwrap();
+
+        let Some(state) = peers.get_mut(&peer) else {
+            return;
+        };
+
+        state.too_big = Some(state.too_big.map_or(size, |too_big| too_big.min(size)));
+        state.largest_acknowledged = state.largest_acknowledged.filter(|s| *s < size);
+
+        if state.datagram_size >= size {
+            let datagram_size = state.largest_acknowledged.unwrap_or(MAX_ON_THE_WIRE_SIZE);
+            debug!(
+                "Path MTU for {} changed from {} to {}",
+                peer, state.datagram_size, datagram_size
+            );
+            state.datagram_size = datagram_size;
+            state.probe_again = true;
+        }
+    }
+
+    /// Currently used datagram size for a peer, if it was probed
+    pub(crate) fn path_mtu(&self, peer: &SocketAddr) -> Option<usize> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(peer)
+            .map(|state| state.datagram_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer() -> SocketAddr {
+        "127.0.0.1:5000".parse().unwrap()
+    }
+
+    #[test]
+    fn probe_new_peer() {
+        let storage = PathMtuStorage::default();
+        let now = Instant::now();
+
+        assert_eq!(storage.path_mtu(&peer()), None);
+        assert_eq!(
+            storage.datagram_size(peer(), now),
+            (MAX_ON_THE_WIRE_SIZE, true)
+        );
+        assert_eq!(
+            storage.datagram_size(peer(), now),
+            (MAX_ON_THE_WIRE_SIZE, false)
+        );
+
+        storage.acknowledge(peer(), 1232);
+        storage.acknowledge(peer(), 1472);
+        assert_eq!(storage.datagram_size(peer(), now), (1472, false));
+        assert_eq!(storage.path_mtu(&peer()), Some(1472));
+    }
+
+    #[test]
+    fn ignore_unexpected_acknowledgements() {
+        let storage = PathMtuStorage::default();
+        let now = Instant::now();
+
+        storage.acknowledge(peer(), 1472);
+        assert_eq!(storage.path_mtu(&peer()), None);
+
+        let _ = storage.datagram_size(peer(), now);
+        storage.acknowledge(peer(), MAX_DATAGRAM_SIZE + 1);
+        storage.acknowledge(peer(), 100);
+        assert_eq!(storage.path_mtu(&peer()), Some(MAX_ON_THE_WIRE_SIZE));
+    }
+
+    #[test]
+    fn decrease_after_new_probing_round() {
+        let storage = PathMtuStorage::default();
+        let now = Instant::now();
+
+        let _ = storage.datagram_size(peer(), now);
+        storage.acknowledge(peer(), MAX_DATAGRAM_SIZE);
+
+        let later = now + PATH_MTU_PROBE_INTERVAL;
+        assert_eq!(
+            storage.datagram_size(peer(), later),
+            (MAX_DATAGRAM_SIZE, true)
+        );
+        storage.acknowledge(peer(), 1372);
+
+        // The previous size is kept until the probing round completes
+        assert_eq!(
+            storage.datagram_size(peer(), later),
+            (MAX_DATAGRAM_SIZE, false)
+        );
+        assert_eq!(
+            storage.datagram_size(peer(), later + PATH_MTU_PROBE_TIMEOUT),
+            (1372, false)
+        );
+    }
+
+    #[test]
+    fn oversized_probes_are_not_acknowledged() {
+        let storage = PathMtuStorage::default();
+        let now = Instant::now();
+
+        let _ = storage.datagram_size(peer(), now);
+        storage.too_big(peer(), MAX_DATAGRAM_SIZE);
+        storage.acknowledge(peer(), MAX_DATAGRAM_SIZE);
+        storage.acknowledge(peer(), 1472);
+        assert_eq!(storage.path_mtu(&peer()), Some(1472));
+        assert_eq!(
+            storage.datagram_size(peer(), now + PATH_MTU_PROBE_TIMEOUT),
+            (1472, false)
+        );
+    }
+
+    #[test]
+    fn decrease_when_datagrams_are_too_big() {
+        let storage = PathMtuStorage::default();
+        let now = Instant::now();
+
+        let _ = storage.datagram_size(peer(), now);
+        storage.acknowledge(peer(), 1372);
+        storage.acknowledge(peer(), MAX_DATAGRAM_SIZE);
+        assert_eq!(storage.path_mtu(&peer()), Some(MAX_DATAGRAM_SIZE));
+
+        // The local route MTU decreased after the probes were acknowledged
+        storage.too_big(peer(), MAX_DATAGRAM_SIZE);
+        assert_eq!(storage.path_mtu(&peer()), Some(MAX_ON_THE_WIRE_SIZE));
+
+        // The peer is probed again right away
+        assert_eq!(
+            storage.datagram_size(peer(), now),
+            (MAX_ON_THE_WIRE_SIZE, true)
+        );
+        storage.too_big(peer(), MAX_DATAGRAM_SIZE);
+        storage.acknowledge(peer(), 1372);
+        assert_eq!(
+            storage.datagram_size(peer(), now + PATH_MTU_PROBE_TIMEOUT),
+            (1372, false)
+        );
+    }
+
+    #[test]
+    fn fallback_when_nothing_is_acknowledged() {
+        let storage = PathMtuStorage::default();
+        let now = Instant::now();
+
+        let _ = storage.datagram_size(peer(), now);
+        storage.acknowledge(peer(), 1472);
+
+        let later = now + PATH_MTU_PROBE_INTERVAL;
+        let _ = storage.datagram_size(peer(), later);
+        assert_eq!(
+            storage.datagram_size(peer(), later + PATH_MTU_PROBE_TIMEOUT),
+            (MAX_ON_THE_WIRE_SIZE, false)
+        );
+    }
+}
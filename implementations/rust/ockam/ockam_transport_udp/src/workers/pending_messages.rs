@@ -0,0 +1,556 @@
+use super::UdpReceiverMetrics;
+use crate::messages::{RoutingNumber, UdpRoutingMessage, UdpTransportMessage};
+use crate::MAX_MESSAGE_SIZE;
+use ockam_core::compat::collections::{BTreeMap, HashMap, VecDeque};
+use ockam_core::compat::sync::Arc;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tracing::{debug, trace, warn};
+
+/// Maximum number of routing messages that can be assembled simultaneously for one peer.
+/// When a new routing message arrives and the limit is reached, the oldest one is dropped.
+pub(crate) const MAX_PENDING_ROUTING_MESSAGES_PER_PEER: usize = 16;
+
+/// Maximum amount of bytes buffered for partially received routing messages of all peers.
+/// When the limit is reached, the oldest partially received routing messages are dropped.
+pub(crate) const MAX_PENDING_BYTES: usize = 4 * MAX_MESSAGE_SIZE;
+
+/// A partially received routing message is dropped if it wasn't fully received within that time
+pub(crate) const REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Routing messages whose routing number is that far behind the newest routing number received
+/// from the same peer are considered late and are dropped
+const MAX_ROUTING_NUMBER_DISTANCE: u16 = 256;
+
+/// Number of recently assembled or dropped routing numbers remembered for each peer, so that
+/// duplicated or late datagrams don't start assembling the same routing message again
+const FINISHED_ROUTING_NUMBERS_WINDOW: usize = 64;
+
+/// State for a peer without pending routing messages is cleared after that period of inactivity
+const PEER_INACTIVITY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How often we check for expired routing messages and inactive peers
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Result of adding a fragment to a [`PendingRoutingMessage`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum FragmentStatus {
+    /// The fragment was added
+    Added,
+    /// The fragment with the same offset has already been received
+    Duplicate,
+    /// The fragment is inconsistent with previously received fragments
+    Invalid,
+}
+
+/// A routing message which has been partially received
+struct PendingRoutingMessage {
+    /// Received payloads, indexed by their offset
+    fragments: BTreeMap<u32, Vec<u8>>,
+    /// Total amount of bytes received so far
+    received_bytes: usize,
+    /// Total size of the routing message, known once the last fragment has arrived
+    total_size: Option<usize>,
+    /// When the first fragment has arrived
+    started_at: Instant,
+}
+
+impl PendingRoutingMessage {
+    fn new(started_at: Instant) -> Self {
+        Self {
+            fragments: Default::default(),
+            received_bytes: 0,
+            total_size: None,
+            started_at,
+        }
+    }
+
+    fn add_fragment(&mut self, offset: u32, is_last: bool, payload: &[u8]) -> FragmentStatus {
+        let end = offset as usize + payload.len();
+
+        if end > MAX_MESSAGE_SIZE {
+            return FragmentStatus::Invalid;
+        }
+
+        if let Some(total_size) = self.total_size {
+            if end > total_size || (is_last && end != total_size) {
+                return FragmentStatus::Invalid;
+            }
+        }
+
+        if self.fragments.contains_key(&offset) {
+            return FragmentStatus::Duplicate;
+        }
+
+        if is_last {
+            // Fragments received so far must not go beyond the end of the message
+            if let Some((last_offset, last_payload)) = self.fragments.last_key_value() {
+                if *last_offset as usize + last_payload.len() > end {
+                    return FragmentStatus::Invalid;
+                }
+            }
+            self.total_size = Some(end);
+        }
+
+        self.received_bytes += payload.len();
+        self.fragments.insert(offset, payload.to_vec());
+
+        FragmentStatus::Added
+    }
+
+    fn is_complete(&self) -> bool {
+        self.total_size == Some(self.received_bytes)
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started_at) >= REASSEMBLY_TIMEOUT
+    }
+
+    /// Concatenate all the fragments, returns None if they are not contiguous
+    fn assemble(self) -> Option<Vec<u8>> {
+        let mut binary = Vec::with_capacity(self.received_bytes);
+        for (offset, payload) in self.fragments {
+            if offset as usize != binary.len() {
+                return None;
+            }
+            binary.extend_from_slice(&payload);
+        }
+
+        Some(binary)
+    }
+}
+
+/// Pending routing messages for a certain peer.
+/// Fragments of several routing messages can be received in any order, each routing message
+/// is assembled as soon as all of its fragments have arrived.
+struct PeerPendingRoutingMessageStorage {
+    /// The newest routing number received from that peer
+    newest_routing_number: RoutingNumber,
+    /// Routing messages which are being assembled
+    pending: HashMap<RoutingNumber, PendingRoutingMessage>,
+    /// Routing numbers that were recently assembled or dropped
+    finished: VecDeque<RoutingNumber>,
+    /// Last time a datagram was received from that peer
+    last_activity: Instant,
+}
+
+impl PeerPendingRoutingMessageStorage {
+    // Create given a first received message
+    fn new(routing_number: RoutingNumber, now: Instant) -> Self {
+        Self {
+            newest_routing_number: routing_number,
+            pending: Default::default(),
+            finished: Default::default(),
+            last_activity: now,
+        }
+    }
+
+    fn is_late(&self, routing_number: RoutingNumber) -> bool {
+        if self.finished.contains(&routing_number) {
+            return true;
+        }
+
+        routing_number < self.newest_routing_number
+            && self.newest_routing_number.0.wrapping_sub(routing_number.0)
+                > MAX_ROUTING_NUMBER_DISTANCE
+            && !self.pending.contains_key(&routing_number)
+    }
+
+    fn mark_finished(&mut self, routing_number: RoutingNumber) {
+        if self.finished.len() == FINISHED_ROUTING_NUMBERS_WINDOW {
+            self.finished.pop_front();
+        }
+        self.finished.push_back(routing_number);
+    }
+
+    /// Remove a pending routing message and return the amount of bytes it was holding
+    fn remove(&mut self, routing_number: RoutingNumber) -> usize {
+        self.mark_finished(routing_number);
+        self.pending
+            .remove(&routing_number)
+            .map(|m| m.received_bytes)
+            .unwrap_or(0)
+    }
+
+    /// The oldest pending routing message
+    fn oldest(&self) -> Option<RoutingNumber> {
+        self.pending.keys().min().copied()
+    }
+}
+
+/// Pending routing messages that we haven't yet assembled for all peers
+pub(crate) struct PendingRoutingMessageStorage {
+    peers: HashMap<SocketAddr, PeerPendingRoutingMessageStorage>,
+    /// Amount of bytes held by all pending routing messages
+    pending_bytes: usize,
+    /// Last time we checked for expired routing messages
+    last_cleanup: Instant,
+    metrics: Arc<UdpReceiverMetrics>,
+}
+
+impl PendingRoutingMessageStorage {
+    pub(crate) fn new(metrics: Arc<UdpReceiverMetrics>) -> Self {
+        Self {
+            peers: Default::default(),
+            pending_bytes: 0,
+            last_cleanup: Instant::now(),
+            metrics,
+        }
+    }
+
+    /// Add a newly received datagram and return a routing message if it could be fully assembled.
+    ///
+    /// Expired routing messages are removed when a datagram arrives, so no background task is
+    /// needed to enforce the reassembly timeout.
+    pub(crate) fn add_transport_message_and_try_assemble(
+        &mut self,
+        peer: SocketAddr,
+        transport_message: UdpTransportMessage<'_>,
+        now: Instant,
+    ) -> Option<UdpRoutingMessage<'static>> {
+        trace!(
+            "Received routing message {}, offset {}",
+            transport_message.routing_number,
+            transport_message.offset
+        );
+
+        if now.saturating_duration_since(self.last_cleanup) >= CLEANUP_INTERVAL {
+            self.remove_expired(now);
+        }
+
+        let routing_number = transport_message.routing_number;
+        let payload_len = transport_message.payload.len();
+
+        {
+            let peer_pending_messages = self
+                .peers
+                .entry(peer)
+                .or_insert_with(|| PeerPendingRoutingMessageStorage::new(routing_number, now));
+            peer_pending_messages.last_activity = now;
+
+            if peer_pending_messages.is_late(routing_number) {
+                debug!(
+                    "Dropping routing message: {} because it arrived late or was already processed. Offset {}",
+                    routing_number, transport_message.offset
+                );
+                self.metrics.increment_dropped_fragments();
+                return None;
+            }
+
+            if routing_number > peer_pending_messages.newest_routing_number {
+                peer_pending_messages.newest_routing_number = routing_number;
+            }
+
+            if !peer_pending_messages.pending.contains_key(&routing_number)
+                && peer_pending_messages.pending.len() >= MAX_PENDING_ROUTING_MESSAGES_PER_PEER
+            {
+                if let Some(oldest) = peer_pending_messages.oldest() {
+                    warn!(
+                        "Dropping routing message {} because too many routing messages are being assembled",
+                        oldest
+                    );
+                    self.pending_bytes -= peer_pending_messages.remove(oldest);
+                    self.metrics.increment_dropped_messages();
+                }
+            }
+        }
+
+        // Free some memory if needed, without dropping the message we're currently assembling
+        while self.pending_bytes + payload_len > MAX_PENDING_BYTES {
+            if !self.evict_oldest((peer, routing_number)) {
+                warn!(
+                    "Dropping routing message: {} because the memory limit was reached. Offset {}",
+                    routing_number, transport_message.offset
+                );
+                self.metrics.increment_dropped_fragments();
+                return None;
+            }
+        }
+
+        let peer_pending_messages = self.peers.get_mut(&peer)?;
+        let pending_message = peer_pending_messages
+            .pending
+            .entry(routing_number)
+            .or_insert_with(|| PendingRoutingMessage::new(now));
+
+        match pending_message.add_fragment(
+            transport_message.offset,
+            transport_message.is_last,
+            &transport_message.payload,
+        ) {
+            FragmentStatus::Added => {
+                self.pending_bytes += payload_len;
+            }
+            FragmentStatus::Duplicate => {
+                debug!(
+                    "Dropping duplicate part of routing message: {}. Offset {}",
+                    routing_number, transport_message.offset
+                );
+                self.metrics.increment_dropped_fragments();
+                return None;
+            }
+            FragmentStatus::Invalid => {
+                warn!(
+                    "Dropping routing message: {} because of an inconsistent part. Offset {}",
+                    routing_number, transport_message.offset
+                );
+                self.pending_bytes -= peer_pending_messages.remove(routing_number);
+                self.metrics.increment_dropped_messages();
+                return None;
+            }
+        }
+
+        if !pending_message.is_complete() {
+            return None;
+        }
+
+        let pending_message = peer_pending_messages.pending.remove(&routing_number)?;
+        peer_pending_messages.mark_finished(routing_number);
+        self.pending_bytes -= pending_message.received_bytes;
+
+        let binary = match pending_message.assemble() {
+            Some(binary) => binary,
+            None => {
+                warn!(
+                    "Dropping routing message: {} because its parts are overlapping",
+                    routing_number
+                );
+                self.metrics.increment_dropped_messages();
+                return None;
+            }
+        };
+
+        match minicbor::decode::<UdpRoutingMessage>(&binary) {
+            Ok(routing_message) => {
+                self.metrics.increment_assembled_messages();
+                Some(routing_message.into_owned())
+            }
+            Err(err) => {
+                warn!("Error while decoding UDP message {}", err);
+                self.metrics.increment_dropped_messages();
+                None
+            }
+        }
+    }
+
+    /// Drop the routing message that started being assembled first, skipping the `except` one.
+    /// Return false if there was nothing to drop.
+    fn evict_oldest(&mut self, except: (SocketAddr, RoutingNumber)) -> bool {
+        let oldest = self
+            .peers
+            .iter()
+            .flat_map(|(peer, storage)| {
+                storage
+                    .pending
+                    .iter()
+                    .map(move |(routing_number, message)| {
+                        (*peer, *routing_number, message.started_at)
+                    })
+            })
+            .filter(|(peer, routing_number, _)| (*peer, *routing_number) != except)
+            .min_by_key(|(_, _, started_at)| *started_at);
+
+        let Some((peer, routing_number, _)) = oldest else {
+            return false;
+        };
+
+        warn!(
+            "Dropping routing message {} from {} because the memory limit was reached",
+            routing_number, peer
+        );
+
+        if let Some(storage) = self.peers.get_mut(&peer) {
+            self.pending_bytes -= storage.remove(routing_number);
+        }
+        self.metrics.increment_dropped_messages();
+
+        true
+    }
+
+    /// Drop routing messages which were not assembled within [`REASSEMBLY_TIMEOUT`] and forget
+    /// peers that have been inactive for a long time
+    fn remove_expired(&mut self, now: Instant) {
+        for (peer, storage) in self.peers.iter_mut() {
+            let expired: Vec<RoutingNumber> = storage
+                .pending
+                .iter()
+                .filter(|(_, message)| message.is_expired(now))
+                .map(|(routing_number, _)| *routing_number)
+                .collect();
+
+            for routing_number in expired {
+                debug!(
+                    "Dropping routing message {} from {} because it wasn't received in time",
+                    routing_number, peer
+                );
+                self.pending_bytes -= storage.remove(routing_number);
+                self.metrics.increment_expired_messages();
+            }
+        }
+
+        self.peers.retain(|_, storage| {
+            !storage.pending.is_empty()
+                || now.saturating_duration_since(storage.last_activity) < PEER_INACTIVITY_TIMEOUT
+        });
+
+        self.last_cleanup = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::MAX_PAYLOAD_SIZE;
+    use crate::workers::TransportMessagesIterator;
+    use ockam_core::{route, LocalMessage};
+
+    fn peer() -> SocketAddr {
+        "127.0.0.1:5000".parse().unwrap()
+    }
+
+    fn create_storage() -> (PendingRoutingMessageStorage, Arc<UdpReceiverMetrics>) {
+        let metrics = Arc::new(UdpReceiverMetrics::default());
+        (PendingRoutingMessageStorage::new(metrics.clone()), metrics)
+    }
+
+    fn split(routing_number: RoutingNumber, payload: Vec<u8>) -> Vec<Vec<u8>> {
+        let local_message = LocalMessage::new()
+            .with_onward_route(route!["onward"])
+            .with_return_route(route!["return"])
+            .with_payload(payload);
+
+        let data = minicbor::to_vec(UdpRoutingMessage::from(local_message)).unwrap();
+
+        TransportMessagesIterator::new(routing_number, data, MAX_PAYLOAD_SIZE)
+            .map(|m| m.unwrap())
+            .collect()
+    }
+
+    fn add(
+        storage: &mut PendingRoutingMessageStorage,
+        datagram: &[u8],
+        now: Instant,
+    ) -> Option<UdpRoutingMessage<'static>> {
+        let transport_message: UdpTransportMessage = minicbor::decode(datagram).unwrap();
+        storage.add_transport_message_and_try_assemble(peer(), transport_message, now)
+    }
+
+    #[test]
+    fn assemble_in_order() {
+        let (mut storage, metrics) = create_storage();
+        let payload = vec![1u8; 2000];
+        let datagrams = split(RoutingNumber(1), payload.clone());
+        assert!(datagrams.len() > 1);
+
+        let now = Instant::now();
+        let (last, first) = datagrams.split_last().unwrap();
+        for datagram in first {
+            assert!(add(&mut storage, datagram, now).is_none());
+        }
+        let message = add(&mut storage, last, now).unwrap();
+
+        assert_eq!(message.payload.to_vec(), payload);
+        assert_eq!(metrics.assembled_messages(), 1);
+        assert_eq!(storage.pending_bytes, 0);
+    }
+
+    #[test]
+    fn assemble_out_of_order() {
+        let (mut storage, metrics) = create_storage();
+        let payload: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+        let mut datagrams = split(RoutingNumber(u16::MAX), payload.clone());
+        datagrams.reverse();
+        datagrams.swap(1, 2);
+
+        let now = Instant::now();
+        let (last, first) = datagrams.split_last().unwrap();
+        for datagram in first {
+            assert!(add(&mut storage, datagram, now).is_none());
+        }
+        let message = add(&mut storage, last, now).unwrap();
+
+        assert_eq!(message.payload.to_vec(), payload);
+        assert_eq!(metrics.assembled_messages(), 1);
+        assert_eq!(metrics.dropped_messages(), 0);
+    }
+
+    #[test]
+    fn assemble_interleaved_routing_messages() {
+        let (mut storage, metrics) = create_storage();
+        let payload1 = vec![1u8; 1500];
+        let payload2 = vec![2u8; 1500];
+        let datagrams1 = split(RoutingNumber(10), payload1.clone());
+        let datagrams2 = split(RoutingNumber(11), payload2.clone());
+
+        let now = Instant::now();
+        let mut assembled = vec![];
+        for (d2, d1) in datagrams2.iter().zip(datagrams1.iter()) {
+            assembled.extend(add(&mut storage, d2, now));
+            assembled.extend(add(&mut storage, d1, now));
+        }
+
+        assert_eq!(assembled.len(), 2);
+        assert_eq!(assembled[0].payload.to_vec(), payload2);
+        assert_eq!(assembled[1].payload.to_vec(), payload1);
+        assert_eq!(metrics.assembled_messages(), 2);
+    }
+
+    #[test]
+    fn drop_duplicates() {
+        let (mut storage, metrics) = create_storage();
+        let datagrams = split(RoutingNumber(1), vec![1u8; 1000]);
+
+        let now = Instant::now();
+        assert!(add(&mut storage, &datagrams[0], now).is_none());
+        assert!(add(&mut storage, &datagrams[0], now).is_none());
+        for datagram in &datagrams[1..] {
+            let _ = add(&mut storage, datagram, now);
+        }
+        // The message was already assembled
+        assert!(add(&mut storage, &datagrams[0], now).is_none());
+
+        assert_eq!(metrics.assembled_messages(), 1);
+        assert_eq!(metrics.dropped_fragments(), 2);
+    }
+
+    #[test]
+    fn expire_incomplete_routing_messages() {
+        let (mut storage, metrics) = create_storage();
+        let datagrams1 = split(RoutingNumber(1), vec![1u8; 1000]);
+        let datagrams2 = split(RoutingNumber(2), vec![2u8; 10]);
+
+        let now = Instant::now();
+        assert!(add(&mut storage, &datagrams1[0], now).is_none());
+
+        let later = now + REASSEMBLY_TIMEOUT;
+        assert!(add(&mut storage, &datagrams2[0], later).is_some());
+        assert_eq!(metrics.expired_messages(), 1);
+        assert_eq!(storage.pending_bytes, 0);
+
+        // The remaining parts of the expired message are dropped
+        for datagram in &datagrams1[1..] {
+            assert!(add(&mut storage, datagram, later).is_none());
+        }
+        assert_eq!(metrics.assembled_messages(), 1);
+    }
+
+    #[test]
+    fn drop_oldest_when_too_many_routing_messages() {
+        let (mut storage, metrics) = create_storage();
+
+        let now = Instant::now();
+        for i in 0..=MAX_PENDING_ROUTING_MESSAGES_PER_PEER as u16 {
+            let datagrams = split(RoutingNumber(i), vec![1u8; 1000]);
+            assert!(add(&mut storage, &datagrams[0], now).is_none());
+        }
+
+        assert_eq!(metrics.dropped_messages(), 1);
+        assert_eq!(
+            storage.peers[&peer()].pending.len(),
+            MAX_PENDING_ROUTING_MESSAGES_PER_PEER
+        );
+        assert!(!storage.peers[&peer()]
+            .pending
+            .contains_key(&RoutingNumber(0)));
+    }
+}
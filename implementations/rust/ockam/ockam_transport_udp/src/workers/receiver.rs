@@ -1,116 +1,19 @@
-use super::{Addresses, UdpSocketRead};
+use super::{
+    Addresses, DeliveryAckNotification, PathMtuStorage, PendingRoutingMessageStorage,
+    ReliableReceiveStreams, UdpReceiverMetrics, UdpSocketRead, UdpSocketWrite,
+};
 use crate::messages::{
-    RoutingNumber, UdpRoutingMessage, UdpTransportMessage, MAX_ON_THE_WIRE_SIZE,
+    UdpControlMessage, UdpPathMtuAck, UdpRoutingMessage, UdpTransportMessage, MAX_DATAGRAM_SIZE,
 };
 use crate::UDP;
-use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Address, Error, LocalMessage, Processor, Result, RouteBuilder};
 use ockam_node::Context;
 use std::net::SocketAddr;
+use std::time::Instant;
 use tracing::{trace, warn};
 
-/// Pending routing messages that we haven't yet assembled for all peers
-/// TODO: Clearing everything for a socket after long inactivity would be nice
-#[derive(Default)]
-struct PendingRoutingMessageStorage(HashMap<SocketAddr, PeerPendingRoutingMessageStorage>);
-
-/// Pending routing messages for a certain peer
-/// Currently, we only support messages with the right order, which means:
-///  1. If a newer routing message is received - the old one is dropped if it wasn't fully assembled
-///  2. If a part of a routing message has arrived out of order - the message is fully dropped
-struct PeerPendingRoutingMessageStorage {
-    expected_routing_number: RoutingNumber,
-    routing_message_binary: Vec<u8>,
-}
-
-impl PeerPendingRoutingMessageStorage {
-    // Create given a first received message
-    fn new(routing_number: RoutingNumber) -> Self {
-        Self {
-            expected_routing_number: routing_number,
-            routing_message_binary: vec![],
-        }
-    }
-
-    fn add_transport_message_and_try_assemble(
-        &mut self,
-        transport_message: UdpTransportMessage<'_>,
-    ) -> Option<UdpRoutingMessage<'static>> {
-        trace!(
-            "Received routing message {}, offset {}",
-            transport_message.routing_number,
-            transport_message.offset
-        );
-
-        if transport_message.routing_number < self.expected_routing_number {
-            warn!(
-                "Dropping routing message: {} because it arrived late. Offset {}",
-                transport_message.routing_number, transport_message.offset
-            );
-            return None;
-        }
-
-        if transport_message.routing_number > self.expected_routing_number {
-            warn!(
-                "Dropping routing message {} because a new routing message has arrived: {}",
-                self.expected_routing_number, transport_message.routing_number
-            );
-            self.routing_message_binary.clear();
-            self.expected_routing_number = transport_message.routing_number;
-        }
-
-        if self.routing_message_binary.len() != transport_message.offset as usize {
-            warn!(
-                "Dropping routing message: {} because expected offset is {}, while received offset is {}",
-                transport_message.routing_number,
-                self.routing_message_binary.len(),
-                transport_message.offset
-            );
-            self.routing_message_binary.clear();
-            self.expected_routing_number.increment();
-            return None;
-        }
-
-        self.routing_message_binary
-            .extend_from_slice(&transport_message.payload);
-
-        if !transport_message.is_last {
-            return None;
-        }
-
-        let res = match minicbor::decode::<UdpRoutingMessage>(&self.routing_message_binary) {
-            Ok(routing_message) => Some(routing_message.into_owned()),
-            Err(err) => {
-                warn!("Error while decoding UDP message {}", err);
-                None
-            }
-        };
-
-        self.expected_routing_number.increment();
-        self.routing_message_binary.clear();
-
-        res
-    }
-}
-
-impl PendingRoutingMessageStorage {
-    fn add_transport_message_and_try_assemble(
-        &mut self,
-        peer: SocketAddr,
-        transport_message: UdpTransportMessage<'_>,
-    ) -> Option<UdpRoutingMessage<'static>> {
-        let routing_number = transport_message.routing_number;
-
-        let peer_pending_messages = self
-            .0
-            .entry(peer)
-            .or_insert_with(|| PeerPendingRoutingMessageStorage::new(routing_number));
-
-        peer_pending_messages.add_transport_message_and_try_assemble(transport_message)
-    }
-}
-
 /// A listener for the UDP transport
 ///
 /// This processor handles the reception of messages on a
@@ -123,21 +26,163 @@ pub(crate) struct UdpReceiverProcessor {
     addresses: Addresses,
     /// The read half of the underlying UDP socket.
     socket_read: UdpSocketRead,
+    /// The write half of the underlying UDP socket, used to send control messages.
+    socket_write: UdpSocketWrite,
     /// Will be Some if we communicate with one specific peer.
     peer: Option<SocketAddr>,
     /// Pending routing messages that we haven't yet assembled fully
     pending_routing_messages: PendingRoutingMessageStorage,
+    /// Will be Some if Path MTU discovery is enabled
+    path_mtu: Option<PathMtuStorage>,
+    /// Routing messages received with reliable delivery
+    reliable_streams: ReliableReceiveStreams,
+    /// Whether the sender uses reliable delivery and needs to be notified about acknowledgements
+    reliable_delivery: bool,
 }
 
 impl UdpReceiverProcessor {
-    pub fn new(addresses: Addresses, socket_read: UdpSocketRead, peer: Option<SocketAddr>) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        addresses: Addresses,
+        socket_read: UdpSocketRead,
+        socket_write: UdpSocketWrite,
+        peer: Option<SocketAddr>,
+        metrics: Arc<UdpReceiverMetrics>,
+        path_mtu: Option<PathMtuStorage>,
+        reliable_delivery: bool,
+    ) -> Self {
         Self {
             addresses,
             socket_read,
+            socket_write,
             peer,
-            pending_routing_messages: Default::default(),
+            pending_routing_messages: PendingRoutingMessageStorage::new(metrics),
+            path_mtu,
+            reliable_streams: ReliableReceiveStreams::new(Instant::now()),
+            reliable_delivery,
         }
     }
+
+    async fn send_control_message(
+        &self,
+        addr: SocketAddr,
+        message: UdpControlMessage<'_>,
+    ) -> Result<()> {
+        let message = ockam_core::cbor_encode_preallocate(message)?;
+        self.socket_write
+            .send_to(&message, addr)
+            .await
+            .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))?;
+
+        Ok(())
+    }
+
+    /// Handle a datagram which is not a [`UdpTransportMessage`]
+    async fn handle_control_message(
+        &self,
+        ctx: &Context,
+        addr: SocketAddr,
+        datagram: &[u8],
+        error: minicbor::decode::Error,
+    ) -> Result<()> {
+        let message: UdpControlMessage = match minicbor::decode(datagram) {
+            Ok(message) => message,
+            // Neither a transport message nor a control message
+            Err(_) => return Err(error)?,
+        };
+
+        match message {
+            UdpControlMessage::PathMtuProbe(probe) => {
+                // We always answer, the peer may have Path MTU discovery enabled even if we don't
+                if probe.size as usize != datagram.len() {
+                    warn!(
+                        "Dropping a Path MTU probe from {} with size {}, while received {} bytes",
+                        addr,
+                        probe.size,
+                        datagram.len()
+                    );
+                    return Ok(());
+                }
+
+                trace!(
+                    "Acknowledging Path MTU probe of size {} from {}",
+                    probe.size,
+                    addr
+                );
+                let ack = UdpControlMessage::PathMtuAck(UdpPathMtuAck::new(probe.size));
+                self.send_control_message(addr, ack).await?;
+            }
+            UdpControlMessage::PathMtuAck(ack) => {
+                trace!(
+                    "Received Path MTU acknowledgement of size {} from {}",
+                    ack.size,
+                    addr
+                );
+                if let Some(path_mtu) = &self.path_mtu {
+                    path_mtu.acknowledge(addr, ack.size as usize);
+                }
+            }
+            UdpControlMessage::DeliveryAck(ack) => {
+                trace!(
+                    "Received delivery acknowledgement {} for stream {} from {}",
+                    ack.cumulative,
+                    ack.stream_id,
+                    addr
+                );
+                if self.reliable_delivery {
+                    ctx.send(
+                        self.addresses.delivery_ack_address().clone(),
+                        DeliveryAckNotification::new(addr, ack),
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forward a received routing message to its destination inside our node
+    async fn forward(
+        &self,
+        ctx: &Context,
+        addr: SocketAddr,
+        routing_message: UdpRoutingMessage<'static>,
+    ) -> Result<()> {
+        let Some(next) = routing_message.onward_route.iter().next() else {
+            return Ok(());
+        };
+
+        if self.addresses.is_internal(next) {
+            warn!(
+                "Dropping a message from {} sent to an internal address {}",
+                addr, next
+            );
+            return Ok(());
+        }
+
+        let return_route = RouteBuilder::default().append(self.addresses.sender_address().clone());
+
+        let return_route = if self.peer.is_some() {
+            // If the peer address is defined, we don't need to specify it in the return route
+            return_route
+        } else {
+            // Add the peer address so that sender knows where to send the message
+            return_route.append(Address::new_with_string(UDP, addr.to_string()))
+        };
+
+        let mut local_message = LocalMessage::from(routing_message);
+
+        let return_route = return_route.append_route(local_message.return_route.clone());
+
+        local_message = local_message.set_return_route(return_route.into());
+
+        trace!(onward_route = %local_message.onward_route(),
+            return_route = %local_message.return_route(),
+            "Forwarding UDP message");
+
+        ctx.forward(local_message).await
+    }
 }
 
 #[async_trait]
@@ -151,7 +196,7 @@ impl Processor for UdpReceiverProcessor {
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         trace!("Waiting for incoming UDP datagram...");
 
-        let mut buf = [0u8; MAX_ON_THE_WIRE_SIZE];
+        let mut buf = [0u8; MAX_DATAGRAM_SIZE];
         let (len, addr) = self
             .socket_read
             .recv_from(&mut buf)
@@ -169,12 +214,21 @@ impl Processor for UdpReceiverProcessor {
             }
         }
 
-        let transport_message: UdpTransportMessage = minicbor::decode(&buf[..len])?;
+        let transport_message: UdpTransportMessage = match minicbor::decode(&buf[..len]) {
+            Ok(transport_message) => transport_message,
+            Err(err) => {
+                self.handle_control_message(ctx, addr, &buf[..len], err)
+                    .await?;
+                return Ok(true);
+            }
+        };
+
+        let now = Instant::now();
 
         // Let's save newly received message and see if we can assemble a Routing Message
         let routing_message = match self
             .pending_routing_messages
-            .add_transport_message_and_try_assemble(addr, transport_message)
+            .add_transport_message_and_try_assemble(addr, transport_message, now)
         {
             Some(routing_message) => routing_message,
             None => {
@@ -183,31 +237,22 @@ impl Processor for UdpReceiverProcessor {
             }
         };
 
-        if routing_message.onward_route.is_empty() {
-            return Ok(true);
-        }
-
-        let return_route = RouteBuilder::default().append(self.addresses.sender_address().clone());
-
-        let return_route = if self.peer.is_some() {
-            // If the peer address is defined, we don't need to specify it in the return route
-            return_route
-        } else {
-            // Add the peer address so that sender knows where to send the message
-            return_route.append(Address::new_with_string(UDP, addr.to_string()))
+        let routing_messages = match routing_message.delivery {
+            None => vec![routing_message],
+            Some(header) => {
+                // We always acknowledge, the peer may use reliable delivery even if we don't
+                let (routing_messages, ack) =
+                    self.reliable_streams
+                        .receive(addr, header, routing_message, now);
+                self.send_control_message(addr, UdpControlMessage::DeliveryAck(ack))
+                    .await?;
+                routing_messages
+            }
         };
 
-        let mut local_message = LocalMessage::from(routing_message);
-
-        let return_route = return_route.append_route(local_message.return_route.clone());
-
-        local_message = local_message.set_return_route(return_route.into());
-
-        trace!(onward_route = %local_message.onward_route(),
-            return_route = %local_message.return_route(),
-            "Forwarding UDP message");
-
-        ctx.forward(local_message).await?;
+        for routing_message in routing_messages {
+            self.forward(ctx, addr, routing_message).await?;
+        }
 
         Ok(true)
     }
@@ -0,0 +1,70 @@
+use core::fmt;
+use core::fmt::Formatter;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters collected by the UDP receiver while assembling routing messages
+/// out of UDP datagrams.
+///
+/// The counters are shared between the receiver processor and the [`UdpBind`](crate::UdpBind),
+/// which exposes them via [`UdpBind::receiver_metrics`](crate::UdpBind::receiver_metrics).
+#[derive(Debug, Default)]
+pub struct UdpReceiverMetrics {
+    assembled_messages: AtomicU64,
+    dropped_fragments: AtomicU64,
+    dropped_messages: AtomicU64,
+    expired_messages: AtomicU64,
+}
+
+impl UdpReceiverMetrics {
+    /// Number of routing messages that were successfully assembled
+    pub fn assembled_messages(&self) -> u64 {
+        self.assembled_messages.load(Ordering::Relaxed)
+    }
+
+    /// Number of datagrams that were dropped without being added to a pending routing message,
+    /// e.g. duplicates, late datagrams or datagrams exceeding the memory limits
+    pub fn dropped_fragments(&self) -> u64 {
+        self.dropped_fragments.load(Ordering::Relaxed)
+    }
+
+    /// Number of partially received routing messages that were dropped, because they were
+    /// evicted to respect the memory limits or couldn't be decoded
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped_messages.load(Ordering::Relaxed)
+    }
+
+    /// Number of partially received routing messages that were dropped, because they were not
+    /// fully received within the reassembly timeout
+    pub fn expired_messages(&self) -> u64 {
+        self.expired_messages.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn increment_assembled_messages(&self) {
+        self.assembled_messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn increment_dropped_fragments(&self) {
+        self.dropped_fragments.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn increment_dropped_messages(&self) {
+        self.dropped_messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn increment_expired_messages(&self) {
+        self.expired_messages.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Display for UdpReceiverMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Assembled: {}, Dropped fragments: {}, Dropped messages: {}, Expired messages: {}",
+            self.assembled_messages(),
+            self.dropped_fragments(),
+            self.dropped_messages(),
+            self.expired_messages()
+        )
+    }
+}
@@ -0,0 +1,327 @@
+use crate::messages::{
+    UdpDeliveryAck, UdpDeliveryHeader, UdpRoutingMessage, MAX_DELIVERY_WINDOW, MAX_SELECTIVE_ACKS,
+};
+use crate::workers::MAX_PENDING_BYTES;
+use ockam_core::compat::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tracing::{debug, trace};
+
+/// State for a stream is cleared after that period of inactivity. It must be longer than
+/// [`STREAM_IDLE_TIMEOUT`](crate::workers::STREAM_IDLE_TIMEOUT), after which the sender
+/// starts a new stream.
+const STREAM_INACTIVITY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How often we check for inactive streams
+pub(crate) const CLEANUP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum number of streams we keep track of. When a new stream starts, the least recently
+/// active stream is dropped.
+pub(crate) const MAX_RECEIVE_STREAMS: usize = 256;
+
+/// Receiving side of a reliable stream from a peer
+struct ReliableReceiveStream {
+    /// Sequence number of the first routing message we haven't received yet
+    next_expected: u64,
+    /// Routing messages received ahead of `next_expected`
+    out_of_order: BTreeMap<u64, UdpRoutingMessage<'static>>,
+    /// Amount of payload bytes held in `out_of_order`
+    out_of_order_bytes: usize,
+    last_activity: Instant,
+}
+
+impl ReliableReceiveStream {
+    fn new(now: Instant) -> Self {
+        Self {
+            next_expected: 0,
+            out_of_order: Default::default(),
+            out_of_order_bytes: 0,
+            last_activity: now,
+        }
+    }
+
+    /// Add a received routing message, and return routing messages that can now be delivered
+    /// in order. At most `available_bytes` more payload bytes can be buffered.
+    fn receive(
+        &mut self,
+        sequence_number: u64,
+        routing_message: UdpRoutingMessage<'static>,
+        available_bytes: usize,
+    ) -> Vec<UdpRoutingMessage<'static>> {
+        if sequence_number < self.next_expected || self.out_of_order.contains_key(&sequence_number)
+        {
+            trace!("Dropping duplicate routing message {}", sequence_number);
+            return vec![];
+        }
+
+        if sequence_number - self.next_expected >= MAX_DELIVERY_WINDOW {
+            debug!(
+                "Dropping routing message {} since it is too far ahead of {}",
+                sequence_number, self.next_expected
+            );
+            return vec![];
+        }
+
+        if sequence_number != self.next_expected && routing_message.payload.len() > available_bytes
+        {
+            debug!(
+                "Dropping routing message {} since too many routing messages are buffered",
+                sequence_number
+            );
+            return vec![];
+        }
+
+        self.out_of_order_bytes += routing_message.payload.len();
+        self.out_of_order.insert(sequence_number, routing_message);
+
+        let mut deliverable = vec![];
+        while let Some(routing_message) = self.out_of_order.remove(&self.next_expected) {
+            self.out_of_order_bytes -= routing_message.payload.len();
+            deliverable.push(routing_message);
+            self.next_expected += 1;
+        }
+
+        deliverable
+    }
+
+    fn ack(&self, stream_id: u32) -> UdpDeliveryAck {
+        UdpDeliveryAck::new(
+            stream_id,
+            self.next_expected,
+            self.out_of_order
+                .keys()
+                .take(MAX_SELECTIVE_ACKS)
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+/// Reliable streams received from all peers. A peer may have several streams,
+/// for instance after it was restarted.
+///
+/// At most [`MAX_RECEIVE_STREAMS`] streams are kept, and at most [`MAX_PENDING_BYTES`] are
+/// buffered for all the streams.
+pub(crate) struct ReliableReceiveStreams {
+    streams: HashMap<(SocketAddr, u32), ReliableReceiveStream>,
+    /// Amount of payload bytes buffered for all the streams
+    out_of_order_bytes: usize,
+    /// Last time we checked for inactive streams
+    last_cleanup: Instant,
+}
+
+impl ReliableReceiveStreams {
+    pub(crate) fn new(now: Instant) -> Self {
+        Self {
+            streams: Default::default(),
+            out_of_order_bytes: 0,
+            last_cleanup: now,
+        }
+    }
+
+    /// Add a routing message sent with reliable delivery. Return routing messages that can be
+    /// delivered in order, and the acknowledgement that should be sent back to the peer.
+    pub(crate) fn receive(
+        &mut self,
+        peer: SocketAddr,
+        header: UdpDeliveryHeader,
+        routing_message: UdpRoutingMessage<'static>,
+        now: Instant,
+    ) -> (Vec<UdpRoutingMessage<'static>>, UdpDeliveryAck) {
+        if now.saturating_duration_since(self.last_cleanup) >= CLEANUP_INTERVAL {
+            self.remove_streams(|stream| {
+                now.saturating_duration_since(stream.last_activity) >= STREAM_INACTIVITY_TIMEOUT
+            });
+            self.last_cleanup = now;
+        }
+
+        let key = (peer, header.stream_id);
+        if !self.streams.contains_key(&key) && self.streams.len() >= MAX_RECEIVE_STREAMS {
+            self.remove_least_recently_active();
+        }
+
+        let available_bytes = MAX_PENDING_BYTES.saturating_sub(self.out_of_order_bytes);
+        let stream = self
+            .streams
+            .entry(key)
+            .or_insert_with(|| ReliableReceiveStream::new(now));
+        stream.last_activity = now;
+
+        let buffered_bytes = stream.out_of_order_bytes;
+        let deliverable = stream.receive(header.sequence_number, routing_message, available_bytes);
+        self.out_of_order_bytes =
+            self.out_of_order_bytes - buffered_bytes + stream.out_of_order_bytes;
+
+        (deliverable, stream.ack(header.stream_id))
+    }
+
+    fn remove_least_recently_active(&mut self) {
+        let Some(key) = self
+            .streams
+            .iter()
+            .min_by_key(|(_, stream)| stream.last_activity)
+            .map(|(key, _)| *key)
+        else {
+            return;
+        };
+
+        debug!(
+            "Dropping the stream {} from {} since too many streams are received",
+            key.1, key.0
+        );
+        if let Some(stream) = self.streams.remove(&key) {
+            self.out_of_order_bytes -= stream.out_of_order_bytes;
+        }
+    }
+
+    fn remove_streams(&mut self, remove: impl Fn(&ReliableReceiveStream) -> bool) {
+        let mut removed_bytes = 0;
+        self.streams.retain(|_, stream| {
+            if remove(stream) {
+                removed_bytes += stream.out_of_order_bytes;
+                false
+            } else {
+                true
+            }
+        });
+        self.out_of_order_bytes -= removed_bytes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::{route, CowBytes};
+
+    fn peer() -> SocketAddr {
+        "127.0.0.1:5000".parse().unwrap()
+    }
+
+    fn receive(
+        streams: &mut ReliableReceiveStreams,
+        stream_id: u32,
+        sequence_number: u64,
+    ) -> (Vec<u8>, UdpDeliveryAck) {
+        let routing_message = UdpRoutingMessage::new(
+            route!["onward"],
+            route!["return"],
+            CowBytes::from(vec![sequence_number as u8]),
+            None,
+        );
+        let header = UdpDeliveryHeader {
+            stream_id,
+            sequence_number,
+        };
+
+        let (deliverable, ack) = streams.receive(peer(), header, routing_message, Instant::now());
+
+        (deliverable.into_iter().map(|m| m.payload[0]).collect(), ack)
+    }
+
+    #[test]
+    fn deliver_in_order() {
+        let mut streams = ReliableReceiveStreams::new(Instant::now());
+
+        let (delivered, ack) = receive(&mut streams, 1, 1);
+        assert!(delivered.is_empty());
+        assert_eq!(ack.cumulative, 0);
+        assert_eq!(ack.selective, vec![1]);
+
+        let (delivered, ack) = receive(&mut streams, 1, 2);
+        assert!(delivered.is_empty());
+        assert_eq!(ack.selective, vec![1, 2]);
+
+        let (delivered, ack) = receive(&mut streams, 1, 0);
+        assert_eq!(delivered, vec![0, 1, 2]);
+        assert_eq!(ack.cumulative, 3);
+        assert!(ack.selective.is_empty());
+    }
+
+    #[test]
+    fn drop_duplicates() {
+        let mut streams = ReliableReceiveStreams::new(Instant::now());
+
+        let (delivered, _) = receive(&mut streams, 1, 0);
+        assert_eq!(delivered, vec![0]);
+
+        let (delivered, ack) = receive(&mut streams, 1, 0);
+        assert!(delivered.is_empty());
+        assert_eq!(ack.cumulative, 1);
+    }
+
+    #[test]
+    fn drop_too_far_ahead() {
+        let mut streams = ReliableReceiveStreams::new(Instant::now());
+
+        let (delivered, ack) = receive(&mut streams, 1, MAX_DELIVERY_WINDOW);
+        assert!(delivered.is_empty());
+        assert!(ack.selective.is_empty());
+    }
+
+    #[test]
+    fn limit_the_number_of_streams() {
+        let mut streams = ReliableReceiveStreams::new(Instant::now());
+        let now = Instant::now();
+
+        for stream_id in 0..=MAX_RECEIVE_STREAMS as u32 {
+            let routing_message =
+                UdpRoutingMessage::new(route![], route![], CowBytes::from(vec![1]), None);
+            let header = UdpDeliveryHeader {
+                stream_id,
+                sequence_number: 1,
+            };
+            let now = now + Duration::from_millis(stream_id as u64);
+            let _ = streams.receive(peer(), header, routing_message, now);
+        }
+        assert_eq!(streams.streams.len(), MAX_RECEIVE_STREAMS);
+        assert_eq!(streams.out_of_order_bytes, MAX_RECEIVE_STREAMS);
+
+        // The least recently active stream was dropped along with its buffered routing message
+        assert!(!streams.streams.contains_key(&(peer(), 0)));
+        assert!(streams.streams.contains_key(&(peer(), 1)));
+    }
+
+    #[test]
+    fn limit_the_buffered_bytes_of_all_streams() {
+        let mut streams = ReliableReceiveStreams::new(Instant::now());
+
+        for stream_id in 0..2 {
+            let routing_message = UdpRoutingMessage::new(
+                route![],
+                route![],
+                CowBytes::from(vec![0; MAX_PENDING_BYTES / 2 + 1]),
+                None,
+            );
+            let header = UdpDeliveryHeader {
+                stream_id,
+                sequence_number: 1,
+            };
+            let (_, ack) = streams.receive(peer(), header, routing_message, Instant::now());
+
+            if stream_id == 0 {
+                assert_eq!(ack.selective, vec![1]);
+            } else {
+                assert!(ack.selective.is_empty());
+            }
+        }
+        assert_eq!(streams.out_of_order_bytes, MAX_PENDING_BYTES / 2 + 1);
+
+        // Routing messages which can be delivered right away are not buffered
+        let (delivered, _) = receive(&mut streams, 1, 0);
+        assert_eq!(delivered, vec![0]);
+    }
+
+    #[test]
+    fn independent_streams() {
+        let mut streams = ReliableReceiveStreams::new(Instant::now());
+
+        let (delivered, _) = receive(&mut streams, 1, 0);
+        assert_eq!(delivered, vec![0]);
+
+        // A new stream from the same peer starts from 0 again
+        let (delivered, ack) = receive(&mut streams, 2, 0);
+        assert_eq!(delivered, vec![0]);
+        assert_eq!(ack.stream_id, 2);
+        assert_eq!(ack.cumulative, 1);
+    }
+}
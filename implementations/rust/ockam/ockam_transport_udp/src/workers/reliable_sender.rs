@@ -0,0 +1,571 @@
+use crate::messages::{UdpDeliveryAck, UdpDeliveryHeader, UdpRoutingMessage, MAX_DELIVERY_WINDOW};
+use crate::MAX_MESSAGE_SIZE;
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::collections::{BTreeMap, VecDeque};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::{Decodable, Encodable, Message, Result};
+use ockam_transport_core::TransportError;
+use rand::random;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tracing::{debug, trace};
+
+/// Number of routing messages that can be sent before receiving an acknowledgement when a
+/// stream starts
+pub(crate) const INITIAL_CONGESTION_WINDOW: usize = 4;
+
+/// Upper bound for the congestion window
+pub(crate) const MAX_CONGESTION_WINDOW: usize = 256;
+
+/// The congestion window grows exponentially until that threshold, and linearly after
+const INITIAL_SLOW_START_THRESHOLD: usize = 64;
+
+/// Maximum number of routing messages waiting for the congestion window to open.
+/// Sending fails when that limit is reached.
+pub(crate) const MAX_QUEUED_MESSAGES: usize = 4096;
+
+/// Retransmission timeout used before the round trip time is measured
+const INITIAL_RETRANSMISSION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Lower bound for the retransmission timeout
+const MIN_RETRANSMISSION_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Upper bound for the retransmission timeout. It's kept low, since punctured paths
+/// are usually lossy while they are being opened
+const MAX_RETRANSMISSION_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// A stream is reset, and all its pending routing messages dropped, if a routing message
+/// couldn't be delivered after that many retransmissions
+pub(crate) const MAX_RETRANSMISSIONS: u32 = 15;
+
+/// Number of acknowledgements without progress after which the first unacknowledged routing
+/// message is considered lost
+const DUPLICATE_ACK_THRESHOLD: usize = 3;
+
+/// An idle stream is replaced by a new one after that period of time, so that the receiver
+/// can forget about streams that are not used anymore
+pub(crate) const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Routing message which was sent, but not yet acknowledged
+struct InFlightMessage {
+    data: Vec<u8>,
+    sent_at: Instant,
+    retransmissions: u32,
+    selectively_acknowledged: bool,
+}
+
+/// Sending side of a reliable stream to a peer.
+///
+/// Routing messages are assigned a sequence number, sent as long as the congestion window
+/// allows it and kept until they are acknowledged. Unacknowledged routing messages are
+/// retransmitted after a timeout, or earlier if the peer acknowledges newer routing messages.
+///
+/// This type only keeps track of the state, the returned encoded routing messages are sent by
+/// the [`UdpSenderWorker`](crate::workers::UdpSenderWorker).
+pub(crate) struct ReliableSendStream {
+    stream_id: u32,
+    next_sequence_number: u64,
+    /// Encoded routing messages waiting for the congestion window to open
+    queue: VecDeque<(u64, Vec<u8>)>,
+    /// Encoded routing messages waiting for an acknowledgement
+    in_flight: BTreeMap<u64, InFlightMessage>,
+    congestion_window: usize,
+    slow_start_threshold: usize,
+    /// Acknowledgements received since the last increase during congestion avoidance
+    congestion_avoidance_acks: usize,
+    /// Acknowledgements received without progress
+    duplicate_acks: usize,
+    /// While recovering from a loss, the highest sequence number sent when the loss was detected
+    recovery_point: Option<u64>,
+    smoothed_rtt: Option<Duration>,
+    rtt_variance: Duration,
+    retransmission_timeout: Duration,
+    last_activity: Instant,
+}
+
+impl ReliableSendStream {
+    pub(crate) fn new(now: Instant) -> Self {
+        Self {
+            stream_id: random(),
+            next_sequence_number: 0,
+            queue: Default::default(),
+            in_flight: Default::default(),
+            congestion_window: INITIAL_CONGESTION_WINDOW,
+            slow_start_threshold: INITIAL_SLOW_START_THRESHOLD,
+            congestion_avoidance_acks: 0,
+            duplicate_acks: 0,
+            recovery_point: None,
+            smoothed_rtt: None,
+            rtt_variance: Duration::ZERO,
+            retransmission_timeout: INITIAL_RETRANSMISSION_TIMEOUT,
+            last_activity: now,
+        }
+    }
+
+    /// Drop all pending routing messages and start a new stream.
+    /// Return the number of dropped routing messages.
+    pub(crate) fn reset(&mut self, now: Instant) -> usize {
+        let dropped = self.queue.len() + self.in_flight.len();
+        *self = Self::new(now);
+        dropped
+    }
+
+    fn is_idle(&self) -> bool {
+        self.queue.is_empty() && self.in_flight.is_empty()
+    }
+
+    /// Whether the stream has been idle long enough to be replaced by a new one
+    pub(crate) fn is_expired(&self, now: Instant) -> bool {
+        self.is_idle() && now.saturating_duration_since(self.last_activity) >= STREAM_IDLE_TIMEOUT
+    }
+
+    /// Number of routing messages that were sent and are not known to be received
+    fn pipe(&self) -> usize {
+        self.in_flight
+            .values()
+            .filter(|m| !m.selectively_acknowledged)
+            .count()
+    }
+
+    /// Assign a sequence number to a routing message and queue it
+    pub(crate) fn enqueue(
+        &mut self,
+        routing_message: UdpRoutingMessage<'_>,
+        now: Instant,
+    ) -> Result<()> {
+        if self.is_expired(now) {
+            self.reset(now);
+        }
+
+        if self.queue.len() >= MAX_QUEUED_MESSAGES {
+            return Err(TransportError::Capacity)?;
+        }
+
+        let header = UdpDeliveryHeader {
+            stream_id: self.stream_id,
+            sequence_number: self.next_sequence_number,
+        };
+        let data = ockam_core::cbor_encode_preallocate(routing_message.with_delivery(header))?;
+
+        if data.len() > MAX_MESSAGE_SIZE {
+            return Err(TransportError::MessageLengthExceeded)?;
+        }
+
+        self.queue.push_back((self.next_sequence_number, data));
+        self.next_sequence_number += 1;
+        self.last_activity = now;
+
+        Ok(())
+    }
+
+    /// Return queued routing messages that can be sent given the congestion window
+    pub(crate) fn poll_transmit(&mut self, now: Instant) -> Vec<Vec<u8>> {
+        let mut datagrams = vec![];
+        let mut pipe = self.pipe();
+
+        while pipe < self.congestion_window {
+            // The receiver doesn't buffer routing messages too far ahead
+            if let (Some((first, _)), Some((next, _))) =
+                (self.in_flight.first_key_value(), self.queue.front())
+            {
+                if next - first >= MAX_DELIVERY_WINDOW {
+                    break;
+                }
+            }
+
+            let Some((sequence_number, data)) = self.queue.pop_front() else {
+                break;
+            };
+
+            self.in_flight.insert(
+                sequence_number,
+                InFlightMessage {
+                    data: data.clone(),
+                    sent_at: now,
+                    retransmissions: 0,
+                    selectively_acknowledged: false,
+                },
+            );
+            datagrams.push(data);
+            pipe += 1;
+        }
+
+        datagrams
+    }
+
+    /// Handle an acknowledgement, and return routing messages that should be retransmitted
+    pub(crate) fn on_ack(&mut self, ack: &UdpDeliveryAck, now: Instant) -> Vec<Vec<u8>> {
+        if ack.stream_id != self.stream_id {
+            trace!(
+                "Ignoring acknowledgement for stream {}, current stream is {}",
+                ack.stream_id,
+                self.stream_id
+            );
+            return vec![];
+        }
+
+        self.last_activity = now;
+
+        let mut newly_acknowledged = 0;
+        let mut rtt_samples = vec![];
+        let mut progress = false;
+
+        while let Some(entry) = self.in_flight.first_entry() {
+            if *entry.key() >= ack.cumulative {
+                break;
+            }
+            let message = entry.remove();
+            progress = true;
+            if !message.selectively_acknowledged {
+                newly_acknowledged += 1;
+                if message.retransmissions == 0 {
+                    rtt_samples.push(now.saturating_duration_since(message.sent_at));
+                }
+            }
+        }
+
+        for sequence_number in &ack.selective {
+            if let Some(message) = self.in_flight.get_mut(sequence_number) {
+                if !message.selectively_acknowledged {
+                    message.selectively_acknowledged = true;
+                    newly_acknowledged += 1;
+                    if message.retransmissions == 0 {
+                        rtt_samples.push(now.saturating_duration_since(message.sent_at));
+                    }
+                }
+            }
+        }
+
+        for rtt in rtt_samples {
+            self.update_rtt(rtt);
+        }
+
+        let mut retransmissions = vec![];
+
+        if progress {
+            self.duplicate_acks = 0;
+
+            match self.recovery_point {
+                Some(recovery_point) if ack.cumulative > recovery_point => {
+                    self.recovery_point = None;
+                }
+                Some(_) => {
+                    // Partial acknowledgement, the next routing message was probably lost too
+                    retransmissions.extend(self.retransmit_first(now));
+                }
+                None => {}
+            }
+        } else if !ack.selective.is_empty() && !self.in_flight.is_empty() {
+            self.duplicate_acks += 1;
+
+            if self.duplicate_acks == DUPLICATE_ACK_THRESHOLD && self.recovery_point.is_none() {
+                debug!(
+                    "Fast retransmission for stream {}, congestion window {}",
+                    self.stream_id, self.congestion_window
+                );
+                self.slow_start_threshold = (self.pipe() / 2).max(2);
+                self.congestion_window = self.slow_start_threshold;
+                self.congestion_avoidance_acks = 0;
+                self.recovery_point = self.in_flight.last_key_value().map(|(k, _)| *k);
+                retransmissions.extend(self.retransmit_first(now));
+            }
+        }
+
+        if self.recovery_point.is_none() {
+            for _ in 0..newly_acknowledged {
+                self.increase_congestion_window();
+            }
+        }
+
+        retransmissions
+    }
+
+    /// Handle the retransmission timer, and return routing messages that should be retransmitted.
+    /// Fail if a routing message couldn't be delivered after [`MAX_RETRANSMISSIONS`].
+    pub(crate) fn on_timer(&mut self, now: Instant) -> Result<Vec<Vec<u8>>> {
+        let expired = self
+            .in_flight
+            .iter()
+            .find(|(_, m)| {
+                !m.selectively_acknowledged
+                    && now.saturating_duration_since(m.sent_at) >= self.retransmission_timeout
+            })
+            .map(|(sequence_number, m)| (*sequence_number, m.retransmissions));
+
+        let Some((sequence_number, retransmissions)) = expired else {
+            return Ok(vec![]);
+        };
+
+        if retransmissions >= MAX_RETRANSMISSIONS {
+            return Err(TransportError::ConnectionDrop)?;
+        }
+
+        debug!(
+            "Retransmission timeout for routing message {} of stream {}",
+            sequence_number, self.stream_id
+        );
+
+        self.slow_start_threshold = (self.pipe() / 2).max(2);
+        self.congestion_window = 1;
+        self.congestion_avoidance_acks = 0;
+        self.duplicate_acks = 0;
+        self.recovery_point = self.in_flight.last_key_value().map(|(k, _)| *k);
+        self.retransmission_timeout =
+            (self.retransmission_timeout * 2).min(MAX_RETRANSMISSION_TIMEOUT);
+
+        Ok(self.retransmit(sequence_number, now).into_iter().collect())
+    }
+
+    /// When the retransmission timer should fire next
+    pub(crate) fn next_timeout(&self) -> Option<Instant> {
+        self.in_flight
+            .values()
+            .filter(|m| !m.selectively_acknowledged)
+            .map(|m| m.sent_at + self.retransmission_timeout)
+            .min()
+    }
+
+    fn retransmit_first(&mut self, now: Instant) -> Option<Vec<u8>> {
+        let sequence_number = self
+            .in_flight
+            .iter()
+            .find(|(_, m)| !m.selectively_acknowledged)
+            .map(|(sequence_number, _)| *sequence_number)?;
+
+        self.retransmit(sequence_number, now)
+    }
+
+    fn retransmit(&mut self, sequence_number: u64, now: Instant) -> Option<Vec<u8>> {
+        let message = self.in_flight.get_mut(&sequence_number)?;
+        message.sent_at = now;
+        message.retransmissions += 1;
+
+        trace!(
+            "Retransmitting routing message {} of stream {}, attempt {}",
+            sequence_number,
+            self.stream_id,
+            message.retransmissions
+        );
+
+        Some(message.data.clone())
+    }
+
+    fn increase_congestion_window(&mut self) {
+        if self.congestion_window < self.slow_start_threshold {
+            self.congestion_window += 1;
+        } else {
+            self.congestion_avoidance_acks += 1;
+            if self.congestion_avoidance_acks >= self.congestion_window {
+                self.congestion_avoidance_acks = 0;
+                self.congestion_window += 1;
+            }
+        }
+
+        self.congestion_window = self.congestion_window.min(MAX_CONGESTION_WINDOW);
+    }
+
+    /// Update the round trip time estimation as described in RFC 6298
+    fn update_rtt(&mut self, rtt: Duration) {
+        match self.smoothed_rtt {
+            None => {
+                self.smoothed_rtt = Some(rtt);
+                self.rtt_variance = rtt / 2;
+            }
+            Some(smoothed_rtt) => {
+                let difference = if smoothed_rtt > rtt {
+                    smoothed_rtt - rtt
+                } else {
+                    rtt - smoothed_rtt
+                };
+                self.rtt_variance = (self.rtt_variance * 3 + difference) / 4;
+                self.smoothed_rtt = Some((smoothed_rtt * 7 + rtt) / 8);
+            }
+        }
+
+        let smoothed_rtt = self.smoothed_rtt.unwrap_or(rtt);
+        self.retransmission_timeout = (smoothed_rtt
+            + (self.rtt_variance * 4).max(Duration::from_millis(10)))
+        .clamp(MIN_RETRANSMISSION_TIMEOUT, MAX_RETRANSMISSION_TIMEOUT);
+    }
+}
+
+/// Sent by the [`UdpReceiverProcessor`](crate::workers::UdpReceiverProcessor) to the
+/// [`UdpSenderWorker`](crate::workers::UdpSenderWorker) when an acknowledgement is received
+#[derive(Debug, Clone, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub(crate) struct DeliveryAckNotification {
+    #[n(0)] pub peer: String,
+    #[n(1)] pub ack: UdpDeliveryAck,
+}
+
+impl DeliveryAckNotification {
+    pub(crate) fn new(peer: SocketAddr, ack: UdpDeliveryAck) -> Self {
+        Self {
+            peer: peer.to_string(),
+            ack,
+        }
+    }
+}
+
+impl Encodable for DeliveryAckNotification {
+    fn encode(self) -> Result<Vec<u8>> {
+        ockam_core::cbor_encode_preallocate(self)
+    }
+}
+
+impl Decodable for DeliveryAckNotification {
+    fn decode(data: &[u8]) -> Result<Self> {
+        Ok(minicbor::decode(data)?)
+    }
+}
+
+impl Message for DeliveryAckNotification {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::{route, CowBytes};
+
+    fn routing_message(i: u8) -> UdpRoutingMessage<'static> {
+        UdpRoutingMessage::new(
+            route!["onward"],
+            route!["return"],
+            CowBytes::from(vec![i]),
+            None,
+        )
+    }
+
+    fn sequence_number(data: &[u8]) -> u64 {
+        let routing_message: UdpRoutingMessage = minicbor::decode(data).unwrap();
+        routing_message.delivery.unwrap().sequence_number
+    }
+
+    fn ack(stream: &ReliableSendStream, cumulative: u64, selective: Vec<u64>) -> UdpDeliveryAck {
+        UdpDeliveryAck::new(stream.stream_id, cumulative, selective)
+    }
+
+    #[test]
+    fn congestion_window_limits_sending() {
+        let now = Instant::now();
+        let mut stream = ReliableSendStream::new(now);
+
+        for i in 0..10 {
+            stream.enqueue(routing_message(i), now).unwrap();
+        }
+
+        let sent = stream.poll_transmit(now);
+        assert_eq!(sent.len(), INITIAL_CONGESTION_WINDOW);
+        assert_eq!(sequence_number(&sent[0]), 0);
+        assert!(stream.poll_transmit(now).is_empty());
+
+        // Slow start: each acknowledged routing message increases the window
+        assert!(stream.on_ack(&ack(&stream, 2, vec![]), now).is_empty());
+        let sent = stream.poll_transmit(now);
+        assert_eq!(sent.len(), 4);
+        assert_eq!(sequence_number(&sent[0]), 4);
+    }
+
+    #[test]
+    fn retransmit_on_timeout() {
+        let now = Instant::now();
+        let mut stream = ReliableSendStream::new(now);
+
+        stream.enqueue(routing_message(0), now).unwrap();
+        stream.enqueue(routing_message(1), now).unwrap();
+        assert_eq!(stream.poll_transmit(now).len(), 2);
+
+        assert!(stream.on_timer(now).unwrap().is_empty());
+        assert_eq!(
+            stream.next_timeout(),
+            Some(now + INITIAL_RETRANSMISSION_TIMEOUT)
+        );
+
+        let later = now + INITIAL_RETRANSMISSION_TIMEOUT;
+        let retransmitted = stream.on_timer(later).unwrap();
+        assert_eq!(retransmitted.len(), 1);
+        assert_eq!(sequence_number(&retransmitted[0]), 0);
+        assert_eq!(stream.congestion_window, 1);
+
+        // Partial acknowledgement during recovery retransmits the next message
+        let retransmitted = stream.on_ack(&ack(&stream, 1, vec![]), later);
+        assert_eq!(retransmitted.len(), 1);
+        assert_eq!(sequence_number(&retransmitted[0]), 1);
+
+        stream.on_ack(&ack(&stream, 2, vec![]), later);
+        assert!(stream.is_idle());
+        assert_eq!(stream.next_timeout(), None);
+    }
+
+    #[test]
+    fn fast_retransmit_on_duplicate_acks() {
+        let now = Instant::now();
+        let mut stream = ReliableSendStream::new(now);
+
+        for i in 0..4 {
+            stream.enqueue(routing_message(i), now).unwrap();
+        }
+        assert_eq!(stream.poll_transmit(now).len(), 4);
+
+        // Message 0 is lost
+        assert!(stream.on_ack(&ack(&stream, 0, vec![1]), now).is_empty());
+        assert!(stream.on_ack(&ack(&stream, 0, vec![1, 2]), now).is_empty());
+        let retransmitted = stream.on_ack(&ack(&stream, 0, vec![1, 2, 3]), now);
+        assert_eq!(retransmitted.len(), 1);
+        assert_eq!(sequence_number(&retransmitted[0]), 0);
+
+        stream.on_ack(&ack(&stream, 4, vec![]), now);
+        assert!(stream.is_idle());
+    }
+
+    #[test]
+    fn give_up_after_max_retransmissions() {
+        let mut now = Instant::now();
+        let mut stream = ReliableSendStream::new(now);
+
+        stream.enqueue(routing_message(0), now).unwrap();
+        assert_eq!(stream.poll_transmit(now).len(), 1);
+
+        for _ in 0..MAX_RETRANSMISSIONS {
+            now += MAX_RETRANSMISSION_TIMEOUT;
+            assert_eq!(stream.on_timer(now).unwrap().len(), 1);
+        }
+
+        now += MAX_RETRANSMISSION_TIMEOUT;
+        assert!(stream.on_timer(now).is_err());
+
+        let stream_id = stream.stream_id;
+        assert_eq!(stream.reset(now), 1);
+        assert_ne!(stream.stream_id, stream_id);
+    }
+
+    #[test]
+    fn ignore_acks_for_other_streams() {
+        let now = Instant::now();
+        let mut stream = ReliableSendStream::new(now);
+
+        stream.enqueue(routing_message(0), now).unwrap();
+        assert_eq!(stream.poll_transmit(now).len(), 1);
+
+        let other = UdpDeliveryAck::new(stream.stream_id.wrapping_add(1), 1, vec![]);
+        stream.on_ack(&other, now);
+        assert!(!stream.is_idle());
+    }
+
+    #[test]
+    fn expire_when_idle() {
+        let now = Instant::now();
+        let mut stream = ReliableSendStream::new(now);
+
+        stream.enqueue(routing_message(0), now).unwrap();
+        assert_eq!(stream.poll_transmit(now).len(), 1);
+
+        // Streams with unacknowledged routing messages never expire
+        let later = now + STREAM_IDLE_TIMEOUT;
+        assert!(!stream.is_expired(later));
+
+        stream.on_ack(&ack(&stream, 1, vec![]), now);
+        assert!(stream.is_idle());
+        assert!(!stream.is_expired(now));
+        assert!(stream.is_expired(later));
+    }
+}
@@ -1,15 +1,60 @@
-use super::{Addresses, UdpSocketWrite};
+use super::{
+    is_message_too_big, Addresses, DeliveryAckNotification, PathMtuStorage, ReliableSendStream,
+    UdpSocketWrite, CLEANUP_INTERVAL,
+};
 use crate::messages::{
-    RoutingNumber, UdpRoutingMessage, UdpTransportMessage, CURRENT_VERSION, MAX_PAYLOAD_SIZE,
+    max_payload_size, RoutingNumber, UdpControlMessage, UdpPathMtuProbe, UdpRoutingMessage,
+    UdpTransportMessage, CURRENT_VERSION, MAX_ON_THE_WIRE_SIZE, PATH_MTU_PROBE_SIZES,
 };
 use crate::{MAX_MESSAGE_SIZE, UDP};
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{async_trait, Any, Error, LocalMessage, Result, Routed, Worker};
+use ockam_core::{async_trait, Any, Decodable, Error, Result, Routed, Worker};
 use ockam_node::compat::asynchronous::resolve_peer;
-use ockam_node::Context;
+use ockam_node::{Context, DelayedEvent};
 use ockam_transport_core::TransportError;
 use std::net::SocketAddr;
-use tracing::{error, trace, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tracing::{debug, error, trace, warn};
+
+/// State of the reliable delivery to all peers
+pub(crate) struct ReliableDelivery {
+    streams: HashMap<SocketAddr, ReliableSendStream>,
+    /// For generating internal retransmission events
+    retransmission_timer: DelayedEvent<()>,
+    /// Routing messages are sent without reliable delivery while this is false,
+    /// for instance when the peer doesn't support it
+    enabled: Arc<AtomicBool>,
+    /// Last time we checked for expired streams
+    last_cleanup: Instant,
+}
+
+impl ReliableDelivery {
+    pub(crate) fn new(retransmission_timer: DelayedEvent<()>, enabled: Arc<AtomicBool>) -> Self {
+        Self {
+            streams: Default::default(),
+            retransmission_timer,
+            enabled,
+            last_cleanup: Instant::now(),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Forget about streams which have been idle for a while
+    fn remove_expired_streams(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.last_cleanup) < CLEANUP_INTERVAL {
+            return;
+        }
+
+        self.streams.retain(|_, stream| !stream.is_expired(now));
+        self.last_cleanup = now;
+    }
+}
 
 /// A sender for the UDP transport
 ///
@@ -23,6 +68,10 @@ pub(crate) struct UdpSenderWorker {
     peer: Option<SocketAddr>,
     /// Current number of the packet
     current_routing_number: RoutingNumber,
+    /// Will be Some if Path MTU discovery is enabled
+    path_mtu: Option<PathMtuStorage>,
+    /// Will be Some if reliable delivery is enabled
+    reliable_delivery: Option<ReliableDelivery>,
 }
 
 impl UdpSenderWorker {
@@ -31,13 +80,190 @@ impl UdpSenderWorker {
         addresses: Addresses,
         socket_write: UdpSocketWrite,
         peer: Option<SocketAddr>,
+        path_mtu: Option<PathMtuStorage>,
+        reliable_delivery: Option<ReliableDelivery>,
     ) -> Self {
         Self {
             addresses,
             socket_write,
             peer,
             current_routing_number: RoutingNumber::new(),
+            path_mtu,
+            reliable_delivery,
+        }
+    }
+
+    /// Datagram size to use for the given peer. Starts probing the peer Path MTU if needed.
+    async fn datagram_size(&self, peer: SocketAddr) -> usize {
+        let Some(path_mtu) = &self.path_mtu else {
+            return MAX_ON_THE_WIRE_SIZE;
+        };
+
+        let (datagram_size, should_probe) = path_mtu.datagram_size(peer, Instant::now());
+
+        if should_probe {
+            self.send_path_mtu_probes(peer).await;
+        }
+
+        datagram_size
+    }
+
+    /// Send probes of all candidate sizes. The peer acknowledges the ones it receives, and the
+    /// acknowledgements are handled by the receiver processor.
+    async fn send_path_mtu_probes(&self, peer: SocketAddr) {
+        for size in PATH_MTU_PROBE_SIZES {
+            let probe = UdpControlMessage::PathMtuProbe(UdpPathMtuProbe::new(size as u16));
+            let probe = match ockam_core::cbor_encode_preallocate(probe) {
+                Ok(probe) => probe,
+                Err(err) => {
+                    warn!("Failed to encode Path MTU probe: {}", err);
+                    continue;
+                }
+            };
+
+            // Probes are sent with the Don't Fragment bit set. Sending fails if the probe exceeds
+            // the Path MTU known by the local host, which means that size is too big
+            match self.socket_write.send_to(&probe, peer).await {
+                Ok(_) => trace!("Sent Path MTU probe of size {} to {}", size, peer),
+                Err(err) => {
+                    debug!(
+                        "Failed to send Path MTU probe of size {} to {}: {:?}",
+                        size, peer, err
+                    );
+                    if is_message_too_big(&err) {
+                        if let Some(path_mtu) = &self.path_mtu {
+                            path_mtu.too_big(peer, size);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Split an encoded [`UdpRoutingMessage`] into datagrams and send them
+    async fn send_routing_message(&mut self, peer: SocketAddr, data: Vec<u8>) -> Result<()> {
+        let datagram_size = self.datagram_size(peer).await;
+
+        // Split the routing message into a vector of smaller messages suitable for 1 UDP datagram
+        let messages = TransportMessagesIterator::new(
+            self.current_routing_number,
+            data,
+            max_payload_size(datagram_size),
+        );
+
+        self.current_routing_number.increment();
+
+        for message in messages {
+            let message = message?;
+            match self.socket_write.send_to(&message, peer).await {
+                Ok(_) => {
+                    trace!("Successful send to {}", peer);
+                }
+                Err(e) => {
+                    error!("Failed send to {}: {:?}", peer, e);
+                    // The Path MTU decreased since it was probed, use smaller datagrams from now on
+                    if is_message_too_big(&e) {
+                        if let Some(path_mtu) = &self.path_mtu {
+                            path_mtu.too_big(peer, datagram_size);
+                        }
+                    }
+                    return Err(Error::new(Origin::Transport, Kind::Io, e))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send encoded routing messages to their peers, then schedule the next retransmission
+    async fn send_reliable(&mut self, datagrams: Vec<(SocketAddr, Vec<u8>)>) -> Result<()> {
+        let mut res = Ok(());
+        for (peer, data) in datagrams {
+            // Lost routing messages will be retransmitted anyway
+            if let Err(err) = self.send_routing_message(peer, data).await {
+                res = Err(err);
+            }
+        }
+
+        self.schedule_retransmission().await?;
+
+        res
+    }
+
+    async fn schedule_retransmission(&mut self) -> Result<()> {
+        let Some(reliable_delivery) = &mut self.reliable_delivery else {
+            return Ok(());
+        };
+
+        let next_timeout = reliable_delivery
+            .streams
+            .values()
+            .filter_map(|stream| stream.next_timeout())
+            .min();
+
+        match next_timeout {
+            Some(next_timeout) => {
+                reliable_delivery
+                    .retransmission_timer
+                    .schedule(next_timeout.saturating_duration_since(Instant::now()))
+                    .await?
+            }
+            None => reliable_delivery.retransmission_timer.cancel(),
+        }
+
+        Ok(())
+    }
+
+    /// Handle an acknowledgement forwarded by the receiver processor
+    async fn handle_delivery_ack(&mut self, msg: Routed<Any>) -> Result<()> {
+        let Some(reliable_delivery) = &mut self.reliable_delivery else {
+            return Ok(());
+        };
+
+        let notification = DeliveryAckNotification::decode(msg.payload())?;
+        let peer: SocketAddr = notification
+            .peer
+            .parse()
+            .map_err(|_| TransportError::InvalidAddress(notification.peer.clone()))?;
+
+        let Some(stream) = reliable_delivery.streams.get_mut(&peer) else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let mut datagrams = stream.on_ack(&notification.ack, now);
+        datagrams.extend(stream.poll_transmit(now));
+
+        self.send_reliable(datagrams.into_iter().map(|d| (peer, d)).collect())
+            .await
+    }
+
+    /// Retransmit routing messages that weren't acknowledged in time
+    async fn handle_retransmission(&mut self) -> Result<()> {
+        let Some(reliable_delivery) = &mut self.reliable_delivery else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        reliable_delivery.remove_expired_streams(now);
+        let mut datagrams = vec![];
+        for (peer, stream) in reliable_delivery.streams.iter_mut() {
+            match stream.on_timer(now) {
+                Ok(retransmissions) => {
+                    datagrams.extend(retransmissions.into_iter().map(|d| (*peer, d)));
+                    datagrams.extend(stream.poll_transmit(now).into_iter().map(|d| (*peer, d)));
+                }
+                Err(_) => {
+                    let dropped = stream.reset(now);
+                    warn!(
+                        "Peer {} didn't acknowledge routing messages, dropping {} routing messages",
+                        peer, dropped
+                    );
+                }
+            }
         }
+
+        self.send_reliable(datagrams).await
     }
 }
 
@@ -47,6 +273,10 @@ impl Worker for UdpSenderWorker {
     type Context = Context;
 
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        if let Some(reliable_delivery) = &mut self.reliable_delivery {
+            reliable_delivery.retransmission_timer.cancel();
+        }
+
         let _ = ctx
             .stop_processor(self.addresses.receiver_address().clone())
             .await;
@@ -59,6 +289,13 @@ impl Worker for UdpSenderWorker {
         _ctx: &mut Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
+        let msg_addr = msg.msg_addr();
+        if &msg_addr == self.addresses.delivery_ack_address() {
+            return self.handle_delivery_ack(msg).await;
+        } else if &msg_addr == self.addresses.retransmission_address() {
+            return self.handle_retransmission().await;
+        }
+
         // Parse message and remove our address from its routing
         let mut msg = msg.into_local_message();
         msg = msg.pop_front_onward_route()?;
@@ -87,49 +324,57 @@ impl Worker for UdpSenderWorker {
             return Err(TransportError::InvalidAddress(peer.to_string()))?;
         }
 
-        // Serialize a [`LocalMessage`] into a vector of smaller messages suitable for 1 UDP datagram
-        let messages = TransportMessagesIterator::new(self.current_routing_number, msg)?;
+        let routing_message = UdpRoutingMessage::from(msg);
 
-        self.current_routing_number.increment();
+        if let Some(reliable_delivery) = self
+            .reliable_delivery
+            .as_mut()
+            .filter(|reliable_delivery| reliable_delivery.is_enabled())
+        {
+            let now = Instant::now();
+            reliable_delivery.remove_expired_streams(now);
+            let stream = reliable_delivery
+                .streams
+                .entry(peer)
+                .or_insert_with(|| ReliableSendStream::new(now));
+            stream.enqueue(routing_message, now)?;
+            let datagrams = stream.poll_transmit(now);
 
-        for message in messages {
-            let message = message?;
-            match self.socket_write.send_to(&message, peer).await {
-                Ok(_) => {
-                    trace!("Successful send to {}", peer);
-                }
-                Err(e) => {
-                    error!("Failed send to {}: {:?}", peer, e);
-                    return Err(Error::new(Origin::Transport, Kind::Io, e))?;
-                }
-            }
+            return self
+                .send_reliable(datagrams.into_iter().map(|d| (peer, d)).collect())
+                .await;
         }
 
-        Ok(())
+        let data = ockam_core::cbor_encode_preallocate(routing_message)?;
+
+        if data.len() > MAX_MESSAGE_SIZE {
+            return Err(TransportError::MessageLengthExceeded)?;
+        }
+
+        self.send_routing_message(peer, data).await
     }
 }
 
-struct TransportMessagesIterator {
+pub(crate) struct TransportMessagesIterator {
     current_routing_number: RoutingNumber,
     offset: u32,
     data: Vec<u8>,
+    max_payload_size: usize,
 }
 
 impl TransportMessagesIterator {
-    fn new(current_routing_number: RoutingNumber, local_message: LocalMessage) -> Result<Self> {
-        let routing_message = UdpRoutingMessage::from(local_message);
-
-        let routing_message = ockam_core::cbor_encode_preallocate(routing_message)?;
-
-        if routing_message.len() > MAX_MESSAGE_SIZE {
-            return Err(TransportError::MessageLengthExceeded)?;
-        }
-
-        Ok(Self {
+    /// Split an encoded [`UdpRoutingMessage`]
+    pub(crate) fn new(
+        current_routing_number: RoutingNumber,
+        data: Vec<u8>,
+        max_payload_size: usize,
+    ) -> Self {
+        Self {
             current_routing_number,
             offset: 0,
-            data: routing_message,
-        })
+            data,
+            max_payload_size,
+        }
     }
 }
 
@@ -144,9 +389,9 @@ impl Iterator for TransportMessagesIterator {
 
         let mut is_last = true;
         let mut length = self.data.len() - offset;
-        if length > MAX_PAYLOAD_SIZE {
+        if length > self.max_payload_size {
             is_last = false;
-            length = MAX_PAYLOAD_SIZE;
+            length = self.max_payload_size;
         }
 
         let part = UdpTransportMessage::new(
@@ -26,3 +26,110 @@ impl UdpSocketWrite {
         self.0.send_to(buf, target).await
     }
 }
+
+/// Set the Don't Fragment bit on all the datagrams sent by the socket, so that datagrams larger
+/// than the Path MTU are dropped instead of being fragmented. This is required for Path MTU
+/// probes to be meaningful. This transport only supports IPv4.
+pub fn set_dont_fragment(socket: &UdpSocket) -> io::Result<()> {
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_os = "linux", target_os = "android"))] {
+            set_ip_option(socket, libc::IP_MTU_DISCOVER, libc::IP_PMTUDISC_DO)
+        } else if #[cfg(any(target_os = "macos", target_os = "ios"))] {
+            set_ip_option(socket, libc::IP_DONTFRAG, 1)
+        } else {
+            let _ = socket;
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the Don't Fragment bit can't be set on this platform",
+            ))
+        }
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+))]
+#[allow(unsafe_code)]
+fn set_ip_option(socket: &UdpSocket, option: libc::c_int, value: libc::c_int) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: the file descriptor is valid for the lifetime of the socket, and the option
+    // value is a c_int whose size is passed along with its pointer
+    let res = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IP,
+            option,
+            core::ptr::addr_of!(value).cast(),
+            size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Return true if sending failed because the datagram is larger than the Path MTU known
+/// by the local host, which can only happen when the Don't Fragment bit is set
+pub fn is_message_too_big(err: &io::Error) -> bool {
+    cfg_if::cfg_if! {
+        if #[cfg(unix)] {
+            err.raw_os_error() == Some(libc::EMSGSIZE)
+        } else if #[cfg(windows)] {
+            // WSAEMSGSIZE
+            err.raw_os_error() == Some(10040)
+        } else {
+            let _ = err;
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[allow(unsafe_code)]
+    #[tokio::test]
+    async fn dont_fragment_is_set() {
+        use std::os::fd::AsRawFd;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        set_dont_fragment(&socket).unwrap();
+
+        let mut value: libc::c_int = 0;
+        let mut len = size_of::<libc::c_int>() as libc::socklen_t;
+        let res = unsafe {
+            libc::getsockopt(
+                socket.as_raw_fd(),
+                libc::IPPROTO_IP,
+                libc::IP_MTU_DISCOVER,
+                core::ptr::addr_of_mut!(value).cast(),
+                &mut len,
+            )
+        };
+        assert_eq!(res, 0);
+        assert_eq!(value, libc::IP_PMTUDISC_DO);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn too_big_errors_are_detected() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        // Larger than the maximum size of an IPv4 packet
+        let err = socket
+            .send_to(&[0; 70_000], peer.local_addr().unwrap())
+            .await
+            .unwrap_err();
+        assert!(is_message_too_big(&err));
+    }
+}
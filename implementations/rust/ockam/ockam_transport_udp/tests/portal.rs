@@ -0,0 +1,207 @@
+use ockam_core::{route, Result};
+use ockam_node::Context;
+use ockam_transport_core::HostnamePort;
+use ockam_transport_udp::{UdpInletOptions, UdpOutletOptions, UdpTransport};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Start a target answering each datagram with the source address it was received from
+async fn start_target() -> Result<SocketAddr> {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buffer = vec![0; 1024];
+        while let Ok((len, peer)) = socket.recv_from(&mut buffer).await {
+            let answer = format!("{}:{}", String::from_utf8_lossy(&buffer[..len]), peer);
+            let _ = socket.send_to(answer.as_bytes(), peer).await;
+        }
+    });
+
+    Ok(address)
+}
+
+async fn send_and_receive(client: &UdpSocket, inlet: SocketAddr, message: &str) -> String {
+    match try_send_and_receive(client, inlet, message, 10).await {
+        Some(answer) => answer,
+        None => panic!("no answer received for {message}"),
+    }
+}
+
+/// Send a message until an answer is received, at most `attempts` times
+async fn try_send_and_receive(
+    client: &UdpSocket,
+    inlet: SocketAddr,
+    message: &str,
+    attempts: usize,
+) -> Option<String> {
+    let mut buffer = vec![0; 1024];
+    // The first datagrams may be sent before the inlet flow is opened
+    for _ in 0..attempts {
+        client.send_to(message.as_bytes(), inlet).await.unwrap();
+        if let Ok(Ok((len, _))) =
+            timeout(Duration::from_millis(500), client.recv_from(&mut buffer)).await
+        {
+            return Some(String::from_utf8_lossy(&buffer[..len]).to_string());
+        }
+    }
+    None
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__datagrams_of_each_client__should_be_exchanged_with_the_target(
+    ctx: &mut Context,
+) -> Result<()> {
+    let target = start_target().await?;
+    let udp = UdpTransport::create(ctx).await?;
+
+    udp.create_outlet(
+        "outlet",
+        HostnamePort::from(target),
+        UdpOutletOptions::new(),
+    )
+    .await?;
+    let inlet = udp
+        .create_inlet("127.0.0.1:0", route!["outlet"], UdpInletOptions::new())
+        .await?;
+
+    let client1 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let client2 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+    let answer1 = send_and_receive(&client1, inlet.socket_address(), "hello").await;
+    let answer2 = send_and_receive(&client2, inlet.socket_address(), "hello").await;
+
+    // Each client uses its own flow, hence its own source address for the target
+    assert!(answer1.starts_with("hello:"));
+    assert!(answer2.starts_with("hello:"));
+    assert_ne!(answer1, answer2);
+
+    let again = timeout(
+        TIMEOUT,
+        send_and_receive(&client1, inlet.socket_address(), "hello"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(again, answer1);
+
+    udp.stop_inlet(inlet.processor_address().clone()).await?;
+    udp.stop_outlet("outlet").await?;
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__idle_flows__should_be_closed(ctx: &mut Context) -> Result<()> {
+    let target = start_target().await?;
+    let udp = UdpTransport::create(ctx).await?;
+
+    let idle_timeout = Duration::from_millis(300);
+    udp.create_outlet(
+        "outlet",
+        HostnamePort::from(target),
+        UdpOutletOptions::new().with_idle_timeout(Duration::from_secs(60)),
+    )
+    .await?;
+    let inlet = udp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            UdpInletOptions::new().with_idle_timeout(idle_timeout),
+        )
+        .await?;
+
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let answer = send_and_receive(&client, inlet.socket_address(), "hello").await;
+
+    tokio::time::sleep(idle_timeout * 4).await;
+
+    // The inlet flow closed the outlet flow, the next datagram opens a new one
+    let new_answer = send_and_receive(&client, inlet.socket_address(), "hello").await;
+    assert_ne!(answer, new_answer);
+
+    udp.stop_inlet(inlet.processor_address().clone()).await?;
+    udp.stop_outlet("outlet").await?;
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__inlet_flows_over_the_limit__should_be_refused(ctx: &mut Context) -> Result<()> {
+    let target = start_target().await?;
+    let udp = UdpTransport::create(ctx).await?;
+
+    udp.create_outlet(
+        "outlet",
+        HostnamePort::from(target),
+        UdpOutletOptions::new(),
+    )
+    .await?;
+    let inlet = udp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            UdpInletOptions::new().with_max_flows(1),
+        )
+        .await?;
+
+    let client1 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let client2 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+    send_and_receive(&client1, inlet.socket_address(), "hello").await;
+    // The datagrams of a new client are dropped by the inlet
+    let answer = try_send_and_receive(&client2, inlet.socket_address(), "hello", 2).await;
+    assert_eq!(answer, None);
+
+    udp.stop_inlet(inlet.processor_address().clone()).await?;
+    udp.stop_outlet("outlet").await?;
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__outlet_flows_over_the_limit__should_be_refused(ctx: &mut Context) -> Result<()> {
+    let target = start_target().await?;
+    let udp = UdpTransport::create(ctx).await?;
+
+    let idle_timeout = Duration::from_millis(300);
+    udp.create_outlet(
+        "outlet",
+        HostnamePort::from(target),
+        UdpOutletOptions::new()
+            .with_max_flows(1)
+            .with_idle_timeout(idle_timeout),
+    )
+    .await?;
+    let inlet = udp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            UdpInletOptions::new().with_idle_timeout(idle_timeout),
+        )
+        .await?;
+
+    let client1 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let client2 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+    send_and_receive(&client1, inlet.socket_address(), "hello").await;
+    // The outlet doesn't open a flow for a new client
+    let answer = try_send_and_receive(&client2, inlet.socket_address(), "hello", 1).await;
+    assert_eq!(answer, None);
+
+    // A flow can be opened again once the other flows are closed
+    tokio::time::sleep(idle_timeout * 4).await;
+    send_and_receive(&client2, inlet.socket_address(), "hello").await;
+
+    udp.stop_inlet(inlet.processor_address().clone()).await?;
+    udp.stop_outlet("outlet").await?;
+
+    Ok(())
+}
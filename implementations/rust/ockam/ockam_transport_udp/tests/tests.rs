@@ -1,6 +1,6 @@
 use ockam_core::compat::rand::{self, Rng};
-use ockam_core::{route, Result, Routed, Worker};
-use ockam_node::{Context, MessageSendReceiveOptions};
+use ockam_core::{route, AllowAll, Result, Routed, Worker};
+use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions};
 use ockam_transport_core::MAXIMUM_MESSAGE_LENGTH;
 use ockam_transport_udp::{UdpBindArguments, UdpBindOptions, UdpTransport, UDP};
 use std::net::SocketAddr;
@@ -425,6 +425,137 @@ async fn send_receive_large_message(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn send_receive_large_message_with_path_mtu_discovery(ctx: &mut Context) -> Result<()> {
+    // Find available ports
+    let bind_addrs = utils::available_local_ports(2).await?;
+    debug!("bind_addrs = {:?}", bind_addrs);
+
+    // Transport
+    let transport = UdpTransport::create(ctx).await?;
+
+    ctx.start_worker("echoer", Echoer::new(false)).await?;
+    let bind1 = transport
+        .bind(
+            UdpBindArguments::new()
+                .with_bind_address(bind_addrs[0].to_string())?
+                .with_peer_address(bind_addrs[1].to_string())
+                .await?
+                .with_path_mtu_discovery(true),
+            UdpBindOptions::new(),
+        )
+        .await?;
+    let bind2 = transport
+        .bind(
+            UdpBindArguments::new()
+                .with_bind_address(bind_addrs[1].to_string())?
+                .with_peer_address(bind_addrs[0].to_string())
+                .await?
+                .with_path_mtu_discovery(true),
+            UdpBindOptions::new(),
+        )
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("echoer", bind1.flow_control_id());
+
+    for _ in 0..3 {
+        let msg: String = rand::thread_rng()
+            .sample_iter(&rand::distributions::Alphanumeric)
+            .take(MAXIMUM_MESSAGE_LENGTH)
+            .map(char::from)
+            .collect();
+
+        let r = route![bind2.sender_address().clone(), "echoer"];
+        let reply = ctx
+            .send_and_receive_extended::<String>(
+                r,
+                msg.clone(),
+                MessageSendReceiveOptions::new().with_timeout(TIMEOUT),
+            )
+            .await?
+            .into_body()?;
+
+        assert_eq!(reply, msg, "Should receive the same message");
+    }
+
+    // Loopback supports the largest probed datagrams
+    assert!(bind2.path_mtu(&bind_addrs[0]) > 508);
+    assert!(bind1.path_mtu(&bind_addrs[1]) > 508);
+    assert_eq!(bind1.receiver_metrics().dropped_messages(), 0);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn send_receive_in_order_with_reliable_delivery(ctx: &mut Context) -> Result<()> {
+    // Find available ports
+    let bind_addrs = utils::available_local_ports(2).await?;
+    debug!("bind_addrs = {:?}", bind_addrs);
+
+    // Transport
+    let transport = UdpTransport::create(ctx).await?;
+
+    ctx.start_worker("echoer", Echoer::new(false)).await?;
+    let bind1 = transport
+        .bind(
+            UdpBindArguments::new()
+                .with_bind_address(bind_addrs[0].to_string())?
+                .with_peer_address(bind_addrs[1].to_string())
+                .await?
+                .with_reliable_delivery(true),
+            UdpBindOptions::new(),
+        )
+        .await?;
+    let bind2 = transport
+        .bind(
+            UdpBindArguments::new()
+                .with_bind_address(bind_addrs[1].to_string())?
+                .with_peer_address(bind_addrs[0].to_string())
+                .await?
+                .with_reliable_delivery(true),
+            UdpBindOptions::new(),
+        )
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("echoer", bind1.flow_control_id());
+
+    let mut child_ctx = ctx
+        .new_detached("reliable_test", AllowAll, AllowAll)
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("reliable_test", bind2.flow_control_id());
+
+    const MESSAGES: usize = 100;
+
+    // Send all messages at once, without waiting for the replies
+    for i in 0..MESSAGES {
+        let msg = format!("{}:{}", i, "a".repeat(i * 100));
+        child_ctx
+            .send(route![bind2.sender_address().clone(), "echoer"], msg)
+            .await?;
+    }
+
+    for i in 0..MESSAGES {
+        let reply = child_ctx
+            .receive_extended::<String>(MessageReceiveOptions::new().with_timeout(TIMEOUT))
+            .await?
+            .into_body()?;
+
+        assert_eq!(
+            reply,
+            format!("{}:{}", i, "a".repeat(i * 100)),
+            "Should receive messages in order"
+        );
+    }
+
+    assert_eq!(bind1.receiver_metrics().dropped_messages(), 0);
+    assert_eq!(bind2.receiver_metrics().dropped_messages(), 0);
+
+    Ok(())
+}
+
 pub struct Echoer {
     check_sender_is_the_same: bool,
     prev_src_addr: Option<String>,
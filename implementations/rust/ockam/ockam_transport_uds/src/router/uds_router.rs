@@ -195,8 +195,8 @@ impl UdsRouter {
         // Modify the transport message route
         let msg = msg.replace_front_onward_route(&next)?;
 
-        // Send the local message to the connection worker
-        ctx.send(next.clone(), msg).await?;
+        // Forward the local message to the connection worker
+        ctx.forward(msg).await?;
 
         Ok(())
     }
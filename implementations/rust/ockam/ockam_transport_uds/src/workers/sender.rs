@@ -1,8 +1,8 @@
 use std::os::unix::net::SocketAddr;
 
 use ockam_core::{
-    async_trait, compat::sync::Arc, Address, AllowAll, Any, Decodable, DenyAll, LocalMessage,
-    Mailbox, Mailboxes, Message, Result, Routed, Worker,
+    async_trait, compat::sync::Arc, Address, AllowAll, Any, Decodable, DenyAll, Mailbox, Mailboxes,
+    Message, Result, Routed, Worker,
 };
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::{encode_transport_message, TransportError};
@@ -133,14 +133,12 @@ impl UdsSendWorker {
         peer: SocketAddr,
         hostnames: Vec<String>,
     ) -> Result<WorkerPair> {
-        let udsrouter_main_addr = router_handle.main_addr().clone();
-
         trace!("Creating new UDS worker pair");
         let (worker, pair) = Self::new_pair(router_handle, stream, peer, hostnames).await?;
 
         let tx_mailbox = Mailbox::new(
             pair.tx_addr(),
-            Arc::new(ockam_core::AllowSourceAddress(udsrouter_main_addr)),
+            Arc::new(ockam_core::AllowAll),
             Arc::new(ockam_core::DenyAll),
         );
 
@@ -268,7 +266,7 @@ impl Worker for UdsSendWorker {
                 }
             }
         } else {
-            let mut local_message = LocalMessage::decode(msg.payload())?;
+            let mut local_message = msg.into_local_message();
             // Remove our own address from the route so the other end
             // knows what to do with the incoming message
             local_message = local_message.pop_front_onward_route()?;
@@ -56,9 +56,10 @@ impl WebSocketRouterHandle {
             )
             .await?;
 
-        let WebSocketRouterResponse::Register(res) = response;
-
-        res
+        match response {
+            WebSocketRouterResponse::Register(res) => res,
+            _ => Err(TransportError::InvalidRouterResponseType)?,
+        }
     }
 
     /// Bind an incoming connection listener for this router.
@@ -96,16 +97,24 @@ impl WebSocketRouterHandle {
         Ok((peer_addr, hostnames))
     }
 
-    /// Establish an outgoing WS connection on an existing transport.
-    pub(crate) async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<()> {
-        // Get peer address and connect to it.
-        let (peer_addr, hostnames) = Self::resolve_peer(peer.as_ref())?;
-
-        // Create a new `WorkerPair` for the given peer, initializing a new pair
-        // of sender worker and receiver processor.
-        let pair = WorkerPair::from_client(&self.ctx, peer_addr, hostnames).await?;
+    /// Establish an outgoing WS connection on an existing transport,
+    /// or reuse the existing connection to the same peer.
+    ///
+    /// Return the address of the worker sending messages to the peer.
+    pub(crate) async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<Address> {
+        let response = self
+            .ctx
+            .send_and_receive(
+                self.api_addr.clone(),
+                WebSocketRouterRequest::Connect {
+                    peer: peer.as_ref().to_string(),
+                },
+            )
+            .await?;
 
-        // Handle node's register request.
-        self.register(&pair).await
+        match response {
+            WebSocketRouterResponse::Connect(res) => res,
+            _ => Err(TransportError::InvalidRouterResponseType)?,
+        }
     }
 }
@@ -26,11 +26,17 @@ pub enum WebSocketRouterRequest {
         /// The clients own worker bus address.
         self_addr: Address,
     },
+    /// Connect to a peer, or return the existing connection to this peer.
+    Connect {
+        /// The peer address, as `host:port`.
+        peer: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Message)]
 pub enum WebSocketRouterResponse {
     Register(Result<()>),
+    Connect(Result<Address>),
 }
 
 /// A WebSocket address router and connection listener.
@@ -141,6 +147,17 @@ impl Worker for WebSocketRouter {
                     )
                     .await?;
                 }
+                WebSocketRouterRequest::Connect { peer } => {
+                    trace!("handle_message connect: {:?}", peer);
+                    let res = self.handle_connect(peer).await;
+
+                    ctx.send_from_address(
+                        return_route,
+                        WebSocketRouterResponse::Connect(res),
+                        self.api_addr.clone(),
+                    )
+                    .await?;
+                }
             };
         } else {
             return Err(TransportError::InvalidAddress(msg_addr.to_string()))?;
@@ -179,8 +196,8 @@ impl WebSocketRouter {
 
         let msg = msg.replace_front_onward_route(&next)?;
 
-        // Send the transport message to the connection worker
-        ctx.send(next.clone(), msg).await?;
+        // Forward the local message to the connection worker
+        ctx.forward(msg).await?;
 
         Ok(())
     }
@@ -239,4 +256,15 @@ impl WebSocketRouter {
 
         Ok(self_addr)
     }
+
+    /// Reuse the connection worker already registered for the peer, if any,
+    /// otherwise connect to it.
+    async fn handle_connect(&mut self, peer: String) -> Result<Address> {
+        let (peer_addr, _) = WebSocketRouterHandle::resolve_peer(peer.clone())?;
+        let peer_addr: Address = WebSocketAddress::from(peer_addr).into();
+        if let Some(self_addr) = self.map.get(&peer_addr) {
+            return Ok(self_addr.clone());
+        }
+        self.connect(peer).await
+    }
 }
@@ -67,6 +67,9 @@ impl WebSocketTransport {
 
     /// Establish an outgoing WebSocket connection on an existing transport.
     ///
+    /// If a connection to this peer already exists it is reused.
+    /// Return the address of the worker sending messages to the peer.
+    ///
     /// ```rust
     /// use ockam_transport_websocket::WebSocketTransport;
     /// # use ockam_node::Context;
@@ -77,7 +80,7 @@ impl WebSocketTransport {
     /// ws.connect("127.0.0.1:5000").await?; // and connect to port 5000
     /// # Ok(()) }
     /// ```
-    pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<()> {
+    pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<Address> {
         self.router_handle.connect(peer).await
     }
 
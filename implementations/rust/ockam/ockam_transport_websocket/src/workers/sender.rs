@@ -8,8 +8,8 @@ use tokio_tungstenite::tungstenite::protocol::Message as WebSocketMessage;
 
 use crate::error::WebSocketError;
 use ockam_core::{
-    async_trait, route, Address, AllowAll, Any, Decodable, Encodable, LocalMessage, Mailbox,
-    Mailboxes, Result, Routed, TransportMessage, Worker,
+    async_trait, route, Address, AllowAll, Any, Encodable, Mailbox, Mailboxes, Result, Routed,
+    TransportMessage, Worker,
 };
 use ockam_node::{Context, DelayedEvent, WorkerBuilder};
 use ockam_transport_core::TransportError;
@@ -205,7 +205,7 @@ where
             }
             debug!("Sent heartbeat to peer {}", self.peer);
         } else {
-            let mut msg = LocalMessage::decode(msg.payload())?;
+            let mut msg = msg.into_local_message();
 
             // Remove our own address from the route so the other end
             // knows what to do with the incoming message
@@ -25,3 +25,20 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     };
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn connect_reuses_existing_connection(ctx: &mut Context) -> Result<()> {
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener_address = transport.listen("127.0.0.1:0").await?;
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let sender = transport.connect(listener_address.to_string()).await?;
+    let reply = ctx
+        .send_and_receive::<String>(route![sender.clone(), "echoer"], "Hello".to_string())
+        .await?;
+    assert_eq!(reply, "Hello");
+
+    let again = transport.connect(listener_address.to_string()).await?;
+    assert_eq!(sender, again, "Should reuse the existing connection");
+    Ok(())
+}
@@ -39,6 +39,20 @@ pub enum VaultError {
     InsufficientEncryptBuffer,
     /// Buffer is too short during decryption
     InsufficientDecryptBuffer,
+    /// The key used to encrypt secrets at rest could not be derived
+    SecretsEncryptionKeyDerivation,
+    /// The key used to encrypt secrets at rest is not the one used previously
+    InvalidSecretsEncryptionKey,
+    /// Secrets are encrypted at rest but no key was provided
+    MissingSecretsEncryptionKey,
+    /// Secrets are expected to be encrypted at rest but they are stored in clear
+    SecretsNotEncrypted,
+    /// Secrets are already encrypted at rest
+    SecretsAlreadyEncrypted,
+    /// ML-KEM is not supported by this vault implementation
+    MlKemNotSupported,
+    /// ML-KEM key generation, encapsulation or decapsulation failed
+    MlKemError,
 }
 
 impl ockam_core::compat::error::Error for VaultError {}
@@ -63,6 +77,20 @@ impl core::fmt::Display for VaultError {
             Self::AeadSecretNotFound => write!(f, "aead secret was not found in the storage"),
             Self::InsufficientEncryptBuffer => write!(f, "insufficient encrypt buffer"),
             Self::InsufficientDecryptBuffer => write!(f, "insufficient decrypt buffer"),
+            Self::SecretsEncryptionKeyDerivation => {
+                write!(f, "the secrets encryption key could not be derived")
+            }
+            Self::InvalidSecretsEncryptionKey => {
+                write!(f, "the secrets encryption key or passphrase is incorrect")
+            }
+            Self::MissingSecretsEncryptionKey => write!(
+                f,
+                "the secrets are encrypted, a passphrase or a key is required to access them"
+            ),
+            Self::SecretsNotEncrypted => write!(f, "the secrets are not encrypted"),
+            Self::SecretsAlreadyEncrypted => write!(f, "the secrets are already encrypted"),
+            Self::MlKemNotSupported => write!(f, "ML-KEM is not supported by this vault"),
+            Self::MlKemError => write!(f, "ML-KEM operation failed"),
         }
     }
 }
@@ -72,8 +100,15 @@ impl From<VaultError> for Error {
     fn from(err: VaultError) -> Self {
         use VaultError::*;
         let kind = match err {
-            InvalidPublicKey | InvalidKeyType | InvalidHkdfOutputType => Kind::Misuse,
+            InvalidPublicKey
+            | InvalidKeyType
+            | InvalidHkdfOutputType
+            | InvalidSecretsEncryptionKey
+            | MissingSecretsEncryptionKey
+            | SecretsNotEncrypted
+            | SecretsAlreadyEncrypted => Kind::Misuse,
             UnknownEcdhKeyType => Kind::NotFound,
+            MlKemNotSupported => Kind::Unsupported,
             _ => Kind::Invalid,
         };
 
@@ -46,7 +46,7 @@ cfg_if! {
         pub struct AesGen(AeadSecret);
 
         /// Depending on the secret type make the right type of encrypting / decrypting algorithm
-        pub(super) fn make_aes(secret: &AeadSecret) -> AesGen {
+        pub(crate) fn make_aes(secret: &AeadSecret) -> AesGen {
             AesGen(secret.clone())
         }
     } else if #[cfg(feature = "OCKAM_XX_25519_AES128_GCM_SHA256")] {
@@ -58,7 +58,7 @@ cfg_if! {
         pub struct AesGen(AeadSecret);
 
         /// Depending on the secret type make the right type of encrypting / decrypting algorithm
-        pub(super) fn make_aes(secret: &AeadSecret) -> AesGen {
+        pub(crate) fn make_aes(secret: &AeadSecret) -> AesGen {
             AesGen(secret.clone())
         }
     }
@@ -70,7 +70,7 @@ cfg_if! {
         pub struct AesGen(AesGcm<AesType, U12>);
 
         /// Depending on the secret type make the right type of encrypting / decrypting algorithm
-        pub(super) fn make_aes(secret: &AeadSecret) -> AesGen {
+        pub(crate) fn make_aes(secret: &AeadSecret) -> AesGen {
             AesGen(Aes256Gcm::new((&secret.0).into()))
         }
     } else if #[cfg(feature = "OCKAM_XX_25519_AES128_GCM_SHA256")] {
@@ -83,7 +83,7 @@ cfg_if! {
         pub struct AesGen(AesGcm<AesType, U12>);
 
         /// Depending on the secret type make the right type of encrypting / decrypting algorithm
-        pub(super) fn make_aes(secret: &AeadSecret) -> AesGen {
+        pub(crate) fn make_aes(secret: &AeadSecret) -> AesGen {
             AesGen(Aes128Gcm::new((&secret.0).into()))
         }
     }
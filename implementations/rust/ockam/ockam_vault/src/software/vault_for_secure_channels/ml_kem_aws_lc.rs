@@ -0,0 +1,53 @@
+use aws_lc_rs::kem::{Ciphertext, DecapsulationKey, EncapsulationKey};
+use aws_lc_rs::unstable::kem::ML_KEM_768;
+
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+use crate::{BufferSecret, MlKemCiphertext, MlKemPublicKey, VaultError};
+
+/// ML-KEM-768 Secret Key (decapsulation key).
+pub(crate) struct MlKemSecretKey(DecapsulationKey);
+
+impl MlKemSecretKey {
+    /// Generate a new random key
+    pub(crate) fn generate() -> Result<Self> {
+        let key = DecapsulationKey::generate(&ML_KEM_768).map_err(|_| VaultError::MlKemError)?;
+        Ok(Self(key))
+    }
+
+    /// Return the corresponding public key (encapsulation key)
+    pub(crate) fn public_key(&self) -> Result<MlKemPublicKey> {
+        let public_key = self
+            .0
+            .encapsulation_key()
+            .and_then(|key| key.key_bytes())
+            .map_err(|_| VaultError::MlKemError)?;
+        Ok(MlKemPublicKey(public_key.as_ref().to_vec()))
+    }
+
+    /// Decapsulate a ciphertext to get the shared secret
+    pub(crate) fn decapsulate(&self, ciphertext: &MlKemCiphertext) -> Result<BufferSecret> {
+        let shared_secret = self
+            .0
+            .decapsulate(Ciphertext::from(ciphertext.0.as_slice()))
+            .map_err(|_| VaultError::MlKemError)?;
+        Ok(BufferSecret::new(shared_secret.as_ref().to_vec()))
+    }
+}
+
+/// Encapsulate a shared secret to a peer public key
+pub(crate) fn ml_kem_encapsulate(
+    peer_public_key: &MlKemPublicKey,
+) -> Result<(MlKemCiphertext, BufferSecret)> {
+    let peer_public_key = EncapsulationKey::new(&ML_KEM_768, &peer_public_key.0)
+        .map_err(|_| VaultError::InvalidPublicKey)?;
+    let (ciphertext, shared_secret) = peer_public_key
+        .encapsulate()
+        .map_err(|_| VaultError::MlKemError)?;
+
+    Ok((
+        MlKemCiphertext(ciphertext.as_ref().to_vec()),
+        BufferSecret::new(Vec::from(shared_secret.as_ref())),
+    ))
+}
@@ -0,0 +1,26 @@
+use ockam_core::Result;
+
+use crate::{BufferSecret, MlKemCiphertext, MlKemPublicKey, VaultError};
+
+/// ML-KEM-768 Secret Key, which can't be created without the "ml-kem" feature
+pub(crate) enum MlKemSecretKey {}
+
+impl MlKemSecretKey {
+    pub(crate) fn generate() -> Result<Self> {
+        Err(VaultError::MlKemNotSupported)?
+    }
+
+    pub(crate) fn public_key(&self) -> Result<MlKemPublicKey> {
+        match *self {}
+    }
+
+    pub(crate) fn decapsulate(&self, _ciphertext: &MlKemCiphertext) -> Result<BufferSecret> {
+        match *self {}
+    }
+}
+
+pub(crate) fn ml_kem_encapsulate(
+    _peer_public_key: &MlKemPublicKey,
+) -> Result<(MlKemCiphertext, BufferSecret)> {
+    Err(VaultError::MlKemNotSupported)?
+}
@@ -11,10 +11,20 @@ compile_error! {"One feature must be enabled: \"aws-lc\" or \"rust-crypto\""}
 cfg_if! {
     if #[cfg(feature = "aws-lc")] {
         mod aes_aws_lc;
-        use aes_aws_lc::make_aes;
+        pub(crate) use aes_aws_lc::make_aes;
     } else {
         mod aes_rs;
-        use aes_rs::make_aes;
+        pub(crate) use aes_rs::make_aes;
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "ml-kem")] {
+        mod ml_kem_aws_lc;
+        pub(crate) use ml_kem_aws_lc::*;
+    } else {
+        mod ml_kem_unsupported;
+        pub(crate) use ml_kem_unsupported::*;
     }
 }
 
@@ -15,18 +15,19 @@ use crate::storage::SecretsSqlxDatabase;
 
 use crate::{
     AeadSecret, AeadSecretKeyHandle, BufferSecret, HKDFNumberOfOutputs, HandleToSecret, HashOutput,
-    HkdfOutput, SecretBufferHandle, SoftwareVaultForVerifyingSignatures, VaultError,
-    VaultForSecureChannels, X25519PublicKey, X25519SecretKey, X25519SecretKeyHandle,
-    AEAD_SECRET_LENGTH,
+    HkdfOutput, MlKemCiphertext, MlKemPublicKey, MlKemSecretKeyHandle, SecretBufferHandle,
+    SoftwareVaultForVerifyingSignatures, VaultError, VaultForSecureChannels, X25519PublicKey,
+    X25519SecretKey, X25519SecretKeyHandle, AEAD_SECRET_LENGTH,
 };
 
-use super::make_aes;
+use super::{make_aes, ml_kem_encapsulate, MlKemSecretKey};
 
 /// [`SecureChannelVault`] implementation using software
 pub struct SoftwareVaultForSecureChannels {
     ephemeral_buffer_secrets: Arc<RwLock<BTreeMap<SecretBufferHandle, BufferSecret>>>,
     ephemeral_aead_secrets: Arc<RwLock<BTreeMap<AeadSecretKeyHandle, AeadSecret>>>,
     ephemeral_x25519_secrets: Arc<RwLock<BTreeMap<X25519SecretKeyHandle, X25519SecretKey>>>,
+    ephemeral_ml_kem_secrets: Arc<RwLock<BTreeMap<MlKemSecretKeyHandle, MlKemSecretKey>>>,
     secrets_repository: Arc<dyn SecretsRepository>,
 }
 
@@ -37,6 +38,7 @@ impl SoftwareVaultForSecureChannels {
             ephemeral_buffer_secrets: Default::default(),
             ephemeral_aead_secrets: Default::default(),
             ephemeral_x25519_secrets: Default::default(),
+            ephemeral_ml_kem_secrets: Default::default(),
             secrets_repository,
         }
     }
@@ -102,6 +104,11 @@ impl SoftwareVaultForSecureChannels {
         self.ephemeral_x25519_secrets.read().unwrap().len()
     }
 
+    /// Return the total number of ephemeral ML-KEM secrets present in the Vault
+    pub fn number_of_ephemeral_ml_kem_secrets(&self) -> usize {
+        self.ephemeral_ml_kem_secrets.read().unwrap().len()
+    }
+
     /// Return the total number of ephemeral buffer secrets present in the Vault
     pub fn number_of_ephemeral_buffer_secrets(&self) -> usize {
         self.ephemeral_buffer_secrets.read().unwrap().len()
@@ -382,6 +389,72 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
         Ok(Self::compute_handle_for_public_key(public_key))
     }
 
+    async fn generate_ephemeral_ml_kem_secret_key(&self) -> Result<MlKemSecretKeyHandle> {
+        let secret = MlKemSecretKey::generate()?;
+        let handle = MlKemSecretKeyHandle(Self::generate_random_handle());
+
+        self.ephemeral_ml_kem_secrets
+            .write()
+            .unwrap()
+            .insert(handle.clone(), secret);
+
+        Ok(handle)
+    }
+
+    async fn delete_ephemeral_ml_kem_secret_key(
+        &self,
+        secret_key_handle: MlKemSecretKeyHandle,
+    ) -> Result<bool> {
+        Ok(self
+            .ephemeral_ml_kem_secrets
+            .write()
+            .unwrap()
+            .remove(&secret_key_handle)
+            .is_some())
+    }
+
+    async fn get_ml_kem_public_key(
+        &self,
+        secret_key_handle: &MlKemSecretKeyHandle,
+    ) -> Result<MlKemPublicKey> {
+        match self
+            .ephemeral_ml_kem_secrets
+            .read()
+            .unwrap()
+            .get(secret_key_handle)
+        {
+            Some(secret) => secret.public_key(),
+            None => Err(VaultError::KeyNotFound)?,
+        }
+    }
+
+    async fn ml_kem_encapsulate(
+        &self,
+        peer_public_key: &MlKemPublicKey,
+    ) -> Result<(MlKemCiphertext, SecretBufferHandle)> {
+        let (ciphertext, shared_secret) = ml_kem_encapsulate(peer_public_key)?;
+
+        Ok((ciphertext, self.import_buffer_secret_impl(shared_secret)))
+    }
+
+    async fn ml_kem_decapsulate(
+        &self,
+        secret_key_handle: &MlKemSecretKeyHandle,
+        ciphertext: &MlKemCiphertext,
+    ) -> Result<SecretBufferHandle> {
+        let shared_secret = match self
+            .ephemeral_ml_kem_secrets
+            .read()
+            .unwrap()
+            .get(secret_key_handle)
+        {
+            Some(secret) => secret.decapsulate(ciphertext)?,
+            None => return Err(VaultError::KeyNotFound)?,
+        };
+
+        Ok(self.import_buffer_secret_impl(shared_secret))
+    }
+
     async fn import_secret_buffer(&self, buffer: Vec<u8>) -> Result<SecretBufferHandle> {
         Ok(self.import_buffer_secret_impl(BufferSecret::new(buffer)))
     }
@@ -0,0 +1,153 @@
+use argon2::Argon2;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use ockam_core::compat::rand::{thread_rng, RngCore};
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+use crate::{make_aes, AeadSecret, VaultError, AEAD_SECRET_LENGTH, AES_NONCE_LENGTH};
+
+/// Version of the format of encrypted secrets: `version || nonce || ciphertext || tag`
+const ENCRYPTED_SECRET_VERSION: u8 = 1;
+
+/// Length of the AES-GCM authentication tag
+const TAG_LENGTH: usize = 16;
+
+/// Length of the random salt used to derive a key from a passphrase
+pub(crate) const SALT_LENGTH: usize = 16;
+
+/// Value encrypted with the key when encryption is enabled, in order to check that the same
+/// key is used when the secrets are accessed again
+const KEY_CHECK_VALUE: &[u8] = b"ockam vault secrets";
+
+/// Additional data used when encrypting the key check value
+const KEY_CHECK_AAD: &[u8] = b"secrets_encryption";
+
+/// Source of the key used to encrypt the secrets of a vault at rest
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub enum SecretsEncryption {
+    /// The key is derived from a passphrase with Argon2id, using a random salt stored
+    /// with the secrets
+    Passphrase(String),
+    /// The key is provided directly, for example from a key file or an OS keyring
+    Key(AeadSecret),
+}
+
+/// Key used to encrypt secrets before they are stored
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub(crate) struct SecretsEncryptionKey(AeadSecret);
+
+impl SecretsEncryptionKey {
+    /// Create the encryption key, the salt is only used when the key is derived from a passphrase
+    pub(crate) fn derive(encryption: &SecretsEncryption, salt: &[u8]) -> Result<Self> {
+        match encryption {
+            SecretsEncryption::Key(key) => Ok(Self(key.clone())),
+            SecretsEncryption::Passphrase(passphrase) => {
+                let mut key = AeadSecret([0u8; AEAD_SECRET_LENGTH]);
+                Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key.0)
+                    .map_err(|_| VaultError::SecretsEncryptionKeyDerivation)?;
+                Ok(Self(key))
+            }
+        }
+    }
+
+    /// Return a new random salt
+    pub(crate) fn random_salt() -> Vec<u8> {
+        let mut salt = vec![0u8; SALT_LENGTH];
+        thread_rng().fill_bytes(&mut salt);
+        salt
+    }
+
+    /// Return a value which can be used to check that a key is the same as this one
+    pub(crate) fn key_check(&self) -> Result<Vec<u8>> {
+        self.encrypt(KEY_CHECK_AAD, KEY_CHECK_VALUE)
+    }
+
+    /// Check that a value returned by `key_check` was created with this key
+    pub(crate) fn verify_key_check(&self, key_check: &[u8]) -> Result<()> {
+        match self.decrypt(KEY_CHECK_AAD, key_check) {
+            Ok(value) if value.as_slice() == KEY_CHECK_VALUE => Ok(()),
+            _ => Err(VaultError::InvalidSecretsEncryptionKey)?,
+        }
+    }
+
+    /// Encrypt a secret. The handle of the secret is authenticated as well so that
+    /// an encrypted secret can not be moved to another handle.
+    pub(crate) fn encrypt(&self, handle: &[u8], secret: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; AES_NONCE_LENGTH];
+        thread_rng().fill_bytes(&mut nonce);
+
+        // the buffer contains the secret in clear until it is encrypted
+        let mut encrypted = Zeroizing::new(Vec::with_capacity(
+            1 + AES_NONCE_LENGTH + secret.len() + TAG_LENGTH,
+        ));
+        encrypted.push(ENCRYPTED_SECRET_VERSION);
+        encrypted.extend_from_slice(&nonce);
+        encrypted.extend_from_slice(secret);
+        encrypted.extend_from_slice(&[0u8; TAG_LENGTH]);
+
+        make_aes(&self.0).encrypt_message(
+            &mut encrypted[1 + AES_NONCE_LENGTH..],
+            &nonce,
+            handle,
+        )?;
+        Ok(core::mem::take(&mut *encrypted))
+    }
+
+    /// Decrypt a secret encrypted with `encrypt`. The decrypted secret is erased from memory
+    /// when it is dropped
+    pub(crate) fn decrypt(&self, handle: &[u8], encrypted: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        if encrypted.len() < 1 + AES_NONCE_LENGTH + TAG_LENGTH
+            || encrypted[0] != ENCRYPTED_SECRET_VERSION
+        {
+            return Err(VaultError::AeadAesGcmDecrypt)?;
+        }
+
+        let (nonce, ciphertext) = encrypted[1..].split_at(AES_NONCE_LENGTH);
+        let mut secret = Zeroizing::new(ciphertext.to_vec());
+        let length = make_aes(&self.0)
+            .decrypt_message(&mut secret, nonce, handle)?
+            .len();
+        secret.truncate(length);
+        Ok(secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() -> Result<()> {
+        let key = SecretsEncryptionKey::derive(&SecretsEncryption::Key(AeadSecret([1; 32])), &[])?;
+
+        let encrypted = key.encrypt(&[1, 2, 3], &[4; 32])?;
+        assert_ne!(encrypted[1 + AES_NONCE_LENGTH..], [4; 32]);
+        assert_eq!(*key.decrypt(&[1, 2, 3], &encrypted)?, vec![4; 32]);
+
+        // the secret can't be decrypted for another handle or with another key
+        assert!(key.decrypt(&[1, 2], &encrypted).is_err());
+        let other =
+            SecretsEncryptionKey::derive(&SecretsEncryption::Key(AeadSecret([2; 32])), &[])?;
+        assert!(other.decrypt(&[1, 2, 3], &encrypted).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_passphrase_key_check() -> Result<()> {
+        let salt = SecretsEncryptionKey::random_salt();
+        let passphrase = SecretsEncryption::Passphrase("passphrase".into());
+        let key = SecretsEncryptionKey::derive(&passphrase, &salt)?;
+        let key_check = key.key_check()?;
+
+        SecretsEncryptionKey::derive(&passphrase, &salt)?.verify_key_check(&key_check)?;
+
+        let wrong = SecretsEncryption::Passphrase("wrong".into());
+        assert!(SecretsEncryptionKey::derive(&wrong, &salt)?
+            .verify_key_check(&key_check)
+            .is_err());
+        Ok(())
+    }
+}
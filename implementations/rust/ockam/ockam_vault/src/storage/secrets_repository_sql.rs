@@ -11,31 +11,144 @@ use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Result;
 use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
 
+use crate::storage::secrets_encryption::SecretsEncryptionKey;
 use crate::storage::secrets_repository::SecretsRepository;
+use crate::storage::SecretsEncryption;
 
 use crate::{
     AeadSecret, AeadSecretKeyHandle, ECDSASHA256CurveP256SecretKey, EdDSACurve25519SecretKey,
-    HandleToSecret, SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
-    AEAD_TYPE,
+    HandleToSecret, SigningSecret, SigningSecretKeyHandle, VaultError, X25519SecretKey,
+    X25519SecretKeyHandle, AEAD_TYPE,
 };
 
 /// Implementation of a secrets repository using a SQL database
+///
+/// Secrets can optionally be encrypted at rest with a key provided by the user,
+/// see [`SecretsSqlxDatabase::new_encrypted`].
 #[derive(Clone)]
 pub struct SecretsSqlxDatabase {
     database: SqlxDatabase,
+    encryption_key: Option<SecretsEncryptionKey>,
 }
 
 impl SecretsSqlxDatabase {
     /// Create a new database for secrets
     pub fn new(database: SqlxDatabase) -> Self {
         debug!("create a repository for secrets");
-        Self { database }
+        Self {
+            database,
+            encryption_key: None,
+        }
+    }
+
+    /// Create a new database for secrets, where secrets are encrypted with a key
+    /// provided directly or derived from a passphrase.
+    ///
+    /// The secrets must have been encrypted with [`SecretsSqlxDatabase::encrypt_secrets`]
+    /// first, using the same passphrase or key.
+    pub async fn new_encrypted(
+        database: SqlxDatabase,
+        encryption: SecretsEncryption,
+    ) -> Result<Self> {
+        debug!("create a repository for encrypted secrets");
+        let query = query_as("SELECT salt, key_check FROM secrets_encryption");
+        let row: Option<SecretsEncryptionRow> =
+            query.fetch_optional(&*database.pool).await.into_core()?;
+
+        let Some(row) = row else {
+            return Err(VaultError::SecretsNotEncrypted)?;
+        };
+        let encryption_key = SecretsEncryptionKey::derive(&encryption, &row.salt)?;
+        encryption_key.verify_key_check(&row.key_check)?;
+
+        Ok(Self {
+            database,
+            encryption_key: Some(encryption_key),
+        })
+    }
+
+    /// Encrypt the secrets of a database which are stored in clear, with a key provided
+    /// directly or derived from a passphrase, and return a repository for the encrypted secrets.
+    ///
+    /// This migration is done in one transaction, and all the secrets stored afterwards are
+    /// encrypted with the same key.
+    pub async fn encrypt_secrets(
+        database: SqlxDatabase,
+        encryption: SecretsEncryption,
+    ) -> Result<Self> {
+        debug!("encrypt the secrets stored in clear");
+        let mut transaction = database.begin().await.into_core()?;
+
+        let select = query_as("SELECT salt, key_check FROM secrets_encryption");
+        let row: Option<SecretsEncryptionRow> =
+            select.fetch_optional(&mut *transaction).await.into_core()?;
+        if row.is_some() {
+            return Err(VaultError::SecretsAlreadyEncrypted)?;
+        }
+
+        let salt = SecretsEncryptionKey::random_salt();
+        let encryption_key = SecretsEncryptionKey::derive(&encryption, &salt)?;
+        let insert = query("INSERT INTO secrets_encryption (salt, key_check) VALUES ($1, $2)")
+            .bind(salt)
+            .bind(encryption_key.key_check()?);
+        insert.execute(&mut *transaction).await.void()?;
+
+        for table in ["signing_secret", "x25519_secret", "aead_secret"] {
+            let select = format!("SELECT handle, secret FROM {table}");
+            let rows: Vec<StoredSecretRow> = query_as(&select)
+                .fetch_all(&mut *transaction)
+                .await
+                .into_core()?;
+
+            let update = format!("UPDATE {table} SET secret = $1 WHERE handle = $2");
+            for row in rows.iter() {
+                let query = query(&update)
+                    .bind(encryption_key.encrypt(&row.handle, &row.secret)?)
+                    .bind(row.handle.clone());
+                query.execute(&mut *transaction).await.void()?;
+            }
+        }
+        transaction.commit().await.void()?;
+
+        Ok(Self {
+            database,
+            encryption_key: Some(encryption_key),
+        })
     }
 
     /// Create a new in-memory database for secrets
     pub async fn create() -> Result<Self> {
         Ok(Self::new(SqlxDatabase::in_memory("secrets").await?))
     }
+
+    /// Return true if the secrets stored in this database are encrypted
+    pub async fn is_encrypted(&self) -> Result<bool> {
+        let query = query_as("SELECT salt, key_check FROM secrets_encryption");
+        let row: Option<SecretsEncryptionRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(row.is_some())
+    }
+
+    /// Return the value to store for a secret
+    fn seal_secret(&self, handle: &HandleToSecret, secret: &[u8]) -> Result<Vec<u8>> {
+        match &self.encryption_key {
+            Some(encryption_key) => encryption_key.encrypt(handle.value(), secret),
+            None => Ok(secret.to_vec()),
+        }
+    }
+
+    /// Replace a stored secret with its decrypted value if the secrets are encrypted.
+    /// All the secrets of a database are encrypted once the encryption is enabled.
+    fn open_secret(&self, handle: &[u8], secret: &mut Vec<u8>) -> Result<()> {
+        if let Some(encryption_key) = &self.encryption_key {
+            let decrypted = encryption_key.decrypt(handle, secret)?;
+            secret.clear();
+            secret.extend_from_slice(&decrypted);
+        }
+        Ok(())
+    }
 }
 
 const ED_DSA_CURVE_25519: &str = "EdDSACurve25519";
@@ -62,7 +175,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
         )
         .bind(handle)
         .bind(secret_type)
-        .bind(secret);
+        .bind(self.seal_secret(handle.handle(), secret.key())?);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -84,7 +197,13 @@ impl SecretsRepository for SecretsSqlxDatabase {
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.signing_secret()).transpose()?)
+        match row {
+            Some(mut row) => {
+                self.open_secret(&row.handle, &mut row.secret)?;
+                Ok(Some(row.signing_secret()?))
+            }
+            None => Ok(None),
+        }
     }
 
     async fn get_signing_secret_handles(&self) -> Result<Vec<SigningSecretKeyHandle>> {
@@ -110,7 +229,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
         DO UPDATE SET secret = $2"#,
         )
         .bind(handle)
-        .bind(secret);
+        .bind(self.seal_secret(&handle.0, secret.key())?);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -131,7 +250,13 @@ impl SecretsRepository for SecretsSqlxDatabase {
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.x25519_secret()).transpose()?)
+        match row {
+            Some(mut row) => {
+                self.open_secret(&row.handle, &mut row.secret)?;
+                Ok(Some(row.x25519_secret()?))
+            }
+            None => Ok(None),
+        }
     }
 
     async fn get_x25519_secret_handles(&self) -> Result<Vec<X25519SecretKeyHandle>> {
@@ -157,7 +282,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
         )
         .bind(handle)
         .bind(AEAD_TYPE)
-        .bind(secret);
+        .bind(self.seal_secret(&handle.0 .0, &secret.0)?);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -176,7 +301,13 @@ impl SecretsRepository for SecretsSqlxDatabase {
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.aead_secret()).transpose()?)
+        match row {
+            Some(mut row) => {
+                self.open_secret(handle.0 .0.value(), &mut row.secret)?;
+                Ok(Some(row.aead_secret()?))
+            }
+            None => Ok(None),
+        }
     }
 
     async fn delete_all(&self) -> Result<()> {
@@ -190,6 +321,9 @@ impl SecretsRepository for SecretsSqlxDatabase {
         let query3 = query("DELETE FROM aead_secret");
         query3.execute(&mut *transaction).await.void()?;
 
+        let query4 = query("DELETE FROM secrets_encryption");
+        query4.execute(&mut *transaction).await.void()?;
+
         transaction.commit().await.void()
     }
 }
@@ -287,7 +421,7 @@ struct SigningSecretRow {
 
 impl SigningSecretRow {
     fn signing_secret(&self) -> Result<SigningSecret> {
-        let secret = self.secret.clone().try_into().map_err(|_| {
+        let secret = self.secret.as_slice().try_into().map_err(|_| {
             ockam_core::Error::new(
                 Origin::Api,
                 Kind::Serialization,
@@ -370,6 +504,18 @@ impl AeadSecretRow {
     }
 }
 
+#[derive(FromRow, Zeroize, ZeroizeOnDrop)]
+struct StoredSecretRow {
+    handle: Vec<u8>,
+    secret: Vec<u8>,
+}
+
+#[derive(FromRow)]
+struct SecretsEncryptionRow {
+    salt: Vec<u8>,
+    key_check: Vec<u8>,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -471,6 +617,67 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_encrypted_secrets_repository() -> Result<()> {
+        let database = SqlxDatabase::in_memory("secrets").await?;
+        let handle = SigningSecretKeyHandle::EdDSACurve25519(HandleToSecret::new(vec![1, 2, 3]));
+        let secret = SigningSecret::EdDSACurve25519(EdDSACurve25519SecretKey::new([1; 32]));
+        let aead_handle = AeadSecretKeyHandle::new(HandleToSecret::new(vec![4, 5, 6]));
+        let aead_secret = AeadSecret([2; 32]);
+        let key = SecretsEncryption::Key(AeadSecret([3; 32]));
+
+        // store some secrets in clear
+        let repository = SecretsSqlxDatabase::new(database.clone());
+        repository
+            .store_signing_secret(&handle, secret.clone())
+            .await?;
+        assert!(!repository.is_encrypted().await?);
+
+        // the secrets are not encrypted just by using a key
+        assert!(
+            SecretsSqlxDatabase::new_encrypted(database.clone(), key.clone())
+                .await
+                .is_err()
+        );
+        assert!(!repository.is_encrypted().await?);
+
+        // the secrets are encrypted by an explicit migration, only once
+        let repository =
+            SecretsSqlxDatabase::encrypt_secrets(database.clone(), key.clone()).await?;
+        assert!(repository.is_encrypted().await?);
+        assert!(
+            SecretsSqlxDatabase::encrypt_secrets(database.clone(), key.clone())
+                .await
+                .is_err()
+        );
+        repository
+            .store_aead_secret(&aead_handle, aead_secret.clone())
+            .await?;
+
+        // the existing secret has been encrypted
+        let stored: Vec<u8> = query_scalar("SELECT secret FROM signing_secret")
+            .fetch_one(&*database.pool)
+            .await
+            .into_core()?;
+        assert_ne!(stored, secret.key().to_vec());
+        assert!(repository.get_signing_secret(&handle).await? == Some(secret.clone()));
+        assert!(repository.get_aead_secret(&aead_handle).await? == Some(aead_secret));
+
+        // the secrets can't be read with another key
+        let other_key = SecretsEncryption::Key(AeadSecret([4; 32]));
+        assert!(
+            SecretsSqlxDatabase::new_encrypted(database.clone(), other_key)
+                .await
+                .is_err()
+        );
+
+        // the secrets can be read again with the same key
+        let repository = SecretsSqlxDatabase::new_encrypted(database, key).await?;
+        assert!(repository.get_signing_secret(&handle).await? == Some(secret));
+
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_repository() -> Result<Arc<dyn SecretsRepository>> {
         Ok(Arc::new(SecretsSqlxDatabase::create().await?))
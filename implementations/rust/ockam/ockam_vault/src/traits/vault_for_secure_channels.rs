@@ -1,6 +1,6 @@
 use crate::{
-    AeadSecretKeyHandle, HashOutput, HkdfOutput, SecretBufferHandle, X25519PublicKey,
-    X25519SecretKeyHandle,
+    AeadSecretKeyHandle, HashOutput, HkdfOutput, MlKemCiphertext, MlKemPublicKey,
+    MlKemSecretKeyHandle, SecretBufferHandle, VaultError, X25519PublicKey, X25519SecretKeyHandle,
 };
 
 use ockam_core::compat::vec::Vec;
@@ -94,6 +94,48 @@ pub trait VaultForSecureChannels: Send + Sync + 'static {
         public_key: &X25519PublicKey,
     ) -> Result<X25519SecretKeyHandle>;
 
+    /// Generate a fresh ephemeral (not persisted) ML-KEM-768 Key.
+    /// ML-KEM is optional, by default it is reported as unsupported.
+    async fn generate_ephemeral_ml_kem_secret_key(&self) -> Result<MlKemSecretKeyHandle> {
+        Err(VaultError::MlKemNotSupported)?
+    }
+
+    /// Delete ephemeral ML-KEM-768 Key.
+    async fn delete_ephemeral_ml_kem_secret_key(
+        &self,
+        _secret_key_handle: MlKemSecretKeyHandle,
+    ) -> Result<bool> {
+        Err(VaultError::MlKemNotSupported)?
+    }
+
+    /// Get [`MlKemPublicKey`] of the corresponding ML-KEM-768 Secret Key given its Handle.
+    async fn get_ml_kem_public_key(
+        &self,
+        _secret_key_handle: &MlKemSecretKeyHandle,
+    ) -> Result<MlKemPublicKey> {
+        Err(VaultError::MlKemNotSupported)?
+    }
+
+    /// Perform ML-KEM-768 encapsulation to the peer public key.
+    /// Return the ciphertext to send to the peer and the shared secret.
+    /// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+    async fn ml_kem_encapsulate(
+        &self,
+        _peer_public_key: &MlKemPublicKey,
+    ) -> Result<(MlKemCiphertext, SecretBufferHandle)> {
+        Err(VaultError::MlKemNotSupported)?
+    }
+
+    /// Perform ML-KEM-768 decapsulation of a ciphertext sent by the peer.
+    /// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+    async fn ml_kem_decapsulate(
+        &self,
+        _secret_key_handle: &MlKemSecretKeyHandle,
+        _ciphertext: &MlKemCiphertext,
+    ) -> Result<SecretBufferHandle> {
+        Err(VaultError::MlKemNotSupported)?
+    }
+
     /// Import a Secret Buffer.
     async fn import_secret_buffer(&self, buffer: Vec<u8>) -> Result<SecretBufferHandle>;
 
@@ -1,4 +1,5 @@
 use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::vec::Vec;
 
 /// X25519 public key length.
 pub const X25519_PUBLIC_KEY_LENGTH: usize = 32;
@@ -9,6 +10,12 @@ pub const EDDSA_CURVE25519_PUBLIC_KEY_LENGTH: usize = 32;
 /// NIST P256 public key length.
 pub const ECDSA_SHA256_CURVEP256_PUBLIC_KEY_LENGTH: usize = 65;
 
+/// ML-KEM-768 encapsulation key length.
+pub const ML_KEM_768_PUBLIC_KEY_LENGTH: usize = 1184;
+
+/// ML-KEM-768 ciphertext length.
+pub const ML_KEM_768_CIPHERTEXT_LENGTH: usize = 1088;
+
 /// A public key for verifying signatures.
 #[derive(Encode, Decode, CborLen, Debug, Clone, PartialEq, Eq)]
 #[rustfmt::skip]
@@ -65,3 +72,15 @@ pub struct ECDSASHA256CurveP256PublicKey(
 pub struct X25519PublicKey(
     #[cbor(n(0), with = "minicbor::bytes")] pub [u8; X25519_PUBLIC_KEY_LENGTH],
 );
+
+/// ML-KEM-768 Public Key (encapsulation key) is used for a post-quantum key encapsulation.
+///
+/// - ML-KEM as defined [here][1].
+///
+/// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MlKemPublicKey(pub Vec<u8>);
+
+/// ML-KEM-768 Ciphertext produced by an encapsulation to a [`MlKemPublicKey`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MlKemCiphertext(pub Vec<u8>);
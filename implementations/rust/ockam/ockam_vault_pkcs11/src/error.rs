@@ -0,0 +1,42 @@
+use ockam_core::errcode::{Kind, Origin};
+use thiserror::Error;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("pkcs11 error loading the module {module}: {error}")]
+    Module { module: String, error: String },
+    #[error("no token with label {0} was found")]
+    TokenNotFound(String),
+    #[error("pkcs11 error opening a session: {0}")]
+    Session(String),
+    #[error("pkcs11 error creating new key: {0}")]
+    Create(String),
+    #[error("pkcs11 error signing message with key {keyid}: {error}")]
+    Sign { keyid: String, error: String },
+    #[error("pkcs11 error exporting public key {keyid}: {error}")]
+    Export { keyid: String, error: String },
+    #[error("pkcs11 error deleting key {keyid}: {error}")]
+    Delete { keyid: String, error: String },
+    #[error("pkcs11 error listing the existing keys: {0}")]
+    List(String),
+    #[error("public key ec point is incorrect")]
+    InvalidPublicKey,
+    #[error("signature is incorrect")]
+    InvalidSignature,
+    #[error("key was not found")]
+    KeyNotFound,
+    #[error("invalid handle")]
+    InvalidHandle,
+    #[error("the {0} environment variable must be set")]
+    MissingConfiguration(&'static str),
+    #[error("pkcs11 call could not complete: {0}")]
+    Task(String),
+}
+
+impl From<Error> for ockam_core::Error {
+    #[track_caller]
+    fn from(e: Error) -> Self {
+        ockam_core::Error::new(Origin::Other, Kind::Io, e)
+    }
+}
@@ -0,0 +1,439 @@
+use crate::error::Error;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::error::RvError;
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::rand::{thread_rng, RngCore};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::env::get_env;
+use ockam_core::{async_trait, Result};
+use ockam_vault::{
+    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, HandleToSecret, Signature,
+    SigningSecretKeyHandle, VerifyingPublicKey,
+};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing as log;
+
+/// Path to the PKCS#11 module (shared library) provided by the HSM vendor
+pub const OCKAM_PKCS11_MODULE: &str = "OCKAM_PKCS11_MODULE";
+
+/// Label of the token where keys are stored
+pub const OCKAM_PKCS11_TOKEN_LABEL: &str = "OCKAM_PKCS11_TOKEN_LABEL";
+
+/// PIN of the token user
+pub const OCKAM_PKCS11_PIN: &str = "OCKAM_PKCS11_PIN";
+
+/// DER encoding of the OID of the NIST P-256 curve (prime256v1)
+const P256_EC_PARAMS: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+/// Label set on the keys created by Ockam
+const KEY_LABEL: &[u8] = b"ockam";
+
+/// Length of the random identifiers (CKA_ID) of the keys created by Ockam
+const KEY_ID_LENGTH: usize = 16;
+
+/// A PKCS#11 module must only be initialized once per application, and it is finalized
+/// when its context is dropped. Therefore contexts are shared by all the clients.
+static CONTEXTS: OnceLock<Mutex<HashMap<PathBuf, Pkcs11>>> = OnceLock::new();
+
+/// PKCS#11 configuration.
+#[derive(Clone)]
+pub struct Pkcs11Config {
+    module: PathBuf,
+    token_label: String,
+    pin: AuthPin,
+}
+
+impl core::fmt::Debug for Pkcs11Config {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pkcs11Config")
+            .field("module", &self.module)
+            .field("token_label", &self.token_label)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Pkcs11Config {
+    /// Create a new configuration from the environment variables
+    /// [`OCKAM_PKCS11_MODULE`], [`OCKAM_PKCS11_TOKEN_LABEL`] and [`OCKAM_PKCS11_PIN`]
+    pub fn from_env() -> Result<Pkcs11Config> {
+        let module: String = get_env(OCKAM_PKCS11_MODULE)?
+            .ok_or(Error::MissingConfiguration(OCKAM_PKCS11_MODULE))?;
+        let token_label: String = get_env(OCKAM_PKCS11_TOKEN_LABEL)?
+            .ok_or(Error::MissingConfiguration(OCKAM_PKCS11_TOKEN_LABEL))?;
+        let pin: String =
+            get_env(OCKAM_PKCS11_PIN)?.ok_or(Error::MissingConfiguration(OCKAM_PKCS11_PIN))?;
+        Ok(Self::new(module, token_label, pin))
+    }
+
+    /// Create a new configuration for a PKCS#11 token
+    pub fn new(
+        module: impl Into<PathBuf>,
+        token_label: impl Into<String>,
+        pin: impl Into<String>,
+    ) -> Pkcs11Config {
+        Pkcs11Config {
+            module: module.into(),
+            token_label: token_label.into(),
+            pin: AuthPin::new(pin.into()),
+        }
+    }
+}
+
+/// PKCS#11 client, using a logged-in session on a token.
+///
+/// The methods of this client block the current thread until the token answers. The
+/// [`Pkcs11Token`] implementation runs them on a thread dedicated to blocking operations.
+#[derive(Clone)]
+pub struct Pkcs11Client {
+    // PKCS#11 sessions can only be used by one thread at a time
+    session: Arc<Mutex<Session>>,
+}
+
+impl Pkcs11Client {
+    /// Create a client without blocking the async runtime, see [`Pkcs11Client::new`]
+    pub async fn create(config: Pkcs11Config) -> Result<Pkcs11Client> {
+        tokio::task::spawn_blocking(move || Self::new(config))
+            .await
+            .map_err(|err| Error::Task(err.to_string()))?
+    }
+
+    /// Load the PKCS#11 module, then open a session on the configured token and log in
+    pub fn new(config: Pkcs11Config) -> Result<Pkcs11Client> {
+        let pkcs11 = Self::context(&config.module)?;
+
+        let slots = pkcs11
+            .get_slots_with_token()
+            .map_err(|err| Error::Session(err.to_string()))?;
+        let slot = slots
+            .into_iter()
+            .find(|slot| {
+                pkcs11
+                    .get_token_info(*slot)
+                    .map(|info| info.label().trim() == config.token_label)
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| Error::TokenNotFound(config.token_label.clone()))?;
+
+        let session = pkcs11
+            .open_rw_session(slot)
+            .map_err(|err| Error::Session(err.to_string()))?;
+        // The login state is shared by all the sessions of an application
+        match session.login(UserType::User, Some(&config.pin)) {
+            Ok(()) | Err(cryptoki::error::Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => (),
+            Err(err) => return Err(Error::Session(err.to_string()))?,
+        }
+
+        Ok(Self {
+            session: Arc::new(Mutex::new(session)),
+        })
+    }
+
+    /// Run a call to the token on a thread dedicated to blocking operations, so that the
+    /// async runtime is not blocked while the token processes the call
+    async fn run_blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Pkcs11Client) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let client = self.clone();
+        tokio::task::spawn_blocking(move || f(&client))
+            .await
+            .map_err(|err| Error::Task(err.to_string()))?
+    }
+
+    /// Return the initialized context for a PKCS#11 module
+    fn context(module: &Path) -> Result<Pkcs11> {
+        let mut contexts = CONTEXTS.get_or_init(Default::default).lock().unwrap();
+        if let Some(pkcs11) = contexts.get(module) {
+            return Ok(pkcs11.clone());
+        }
+
+        let error = |err: cryptoki::error::Error| Error::Module {
+            module: module.to_string_lossy().to_string(),
+            error: err.to_string(),
+        };
+        let pkcs11 = Pkcs11::new(module).map_err(error)?;
+        pkcs11
+            .initialize(CInitializeArgs::OsThreads)
+            .map_err(error)?;
+
+        contexts.insert(module.to_path_buf(), pkcs11.clone());
+        Ok(pkcs11)
+    }
+
+    fn cast_handle_to_key_id(handle: &SigningSecretKeyHandle) -> Result<Vec<u8>> {
+        match handle {
+            SigningSecretKeyHandle::EdDSACurve25519(_) => Err(Error::InvalidHandle)?,
+            SigningSecretKeyHandle::ECDSASHA256CurveP256(handle) => Ok(handle.value().clone()),
+        }
+    }
+
+    fn key_id_to_handle(key_id: Vec<u8>) -> SigningSecretKeyHandle {
+        SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(key_id))
+    }
+
+    /// Find the object of a given class for a key
+    fn find_key_object(
+        session: &Session,
+        class: ObjectClass,
+        key_id: &[u8],
+    ) -> cryptoki::error::Result<Option<ObjectHandle>> {
+        let template = [
+            Attribute::Class(class),
+            Attribute::KeyType(KeyType::EC),
+            Attribute::Id(key_id.to_vec()),
+        ];
+        Ok(session.find_objects(&template)?.into_iter().next())
+    }
+
+    /// Create a new NIST P-256 key-pair on the token and return its ID.
+    pub fn create_key(&self) -> Result<SigningSecretKeyHandle> {
+        log::trace!("create new key");
+        let mut key_id = vec![0u8; KEY_ID_LENGTH];
+        thread_rng().fill_bytes(&mut key_id);
+
+        let public_key_template = [
+            Attribute::Token(true),
+            Attribute::Verify(true),
+            Attribute::EcParams(P256_EC_PARAMS.to_vec()),
+            Attribute::Id(key_id.clone()),
+            Attribute::Label(KEY_LABEL.to_vec()),
+        ];
+        let private_key_template = [
+            Attribute::Token(true),
+            Attribute::Private(true),
+            Attribute::Sensitive(true),
+            Attribute::Extractable(false),
+            Attribute::Sign(true),
+            Attribute::Id(key_id.clone()),
+            Attribute::Label(KEY_LABEL.to_vec()),
+        ];
+
+        let session = self.session.lock().unwrap();
+        session
+            .generate_key_pair(
+                &Mechanism::EccKeyPairGen,
+                &public_key_template,
+                &private_key_template,
+            )
+            .map_err(|err| {
+                log::error!(%err, "failed to create new key");
+                Error::Create(err.to_string())
+            })?;
+
+        log::debug!(key = %hex::encode(&key_id), "created new key");
+        Ok(Self::key_id_to_handle(key_id))
+    }
+
+    /// Delete a key-pair from the token.
+    pub fn delete_key(&self, key: &SigningSecretKeyHandle) -> Result<bool> {
+        let key_id = Self::cast_handle_to_key_id(key)?;
+        log::trace!(key = %hex::encode(&key_id), "delete key");
+        let error = |err: cryptoki::error::Error| Error::Delete {
+            keyid: hex::encode(&key_id),
+            error: err.to_string(),
+        };
+
+        let session = self.session.lock().unwrap();
+        let mut deleted = false;
+        for class in [ObjectClass::PRIVATE_KEY, ObjectClass::PUBLIC_KEY] {
+            if let Some(object) = Self::find_key_object(&session, class, &key_id).map_err(error)? {
+                session.destroy_object(object).map_err(error)?;
+                deleted = true;
+            }
+        }
+
+        if !deleted {
+            log::debug!(key = %hex::encode(&key_id), "key does not exist");
+        }
+        Ok(deleted)
+    }
+
+    /// Get the public key part of a key-pair stored on the token.
+    pub fn public_key(&self, key: &SigningSecretKeyHandle) -> Result<VerifyingPublicKey> {
+        let key_id = Self::cast_handle_to_key_id(key)?;
+        log::trace!(key = %hex::encode(&key_id), "get public key");
+        let error = |err: cryptoki::error::Error| Error::Export {
+            keyid: hex::encode(&key_id),
+            error: err.to_string(),
+        };
+
+        let session = self.session.lock().unwrap();
+        let object = Self::find_key_object(&session, ObjectClass::PUBLIC_KEY, &key_id)
+            .map_err(error)?
+            .ok_or(Error::KeyNotFound)?;
+        let attributes = session
+            .get_attributes(object, &[AttributeType::EcParams, AttributeType::EcPoint])
+            .map_err(error)?;
+
+        let mut ec_point = None;
+        for attribute in attributes {
+            match attribute {
+                Attribute::EcParams(params) if params != P256_EC_PARAMS => {
+                    log::error!(key = %hex::encode(&key_id), "curve not supported to get a public key");
+                    return Err(Error::InvalidPublicKey)?;
+                }
+                Attribute::EcPoint(point) => ec_point = Some(point),
+                _ => (),
+            }
+        }
+
+        let public_key = decode_ec_point(&ec_point.ok_or(Error::InvalidPublicKey)?)?;
+        log::debug!(key = %hex::encode(&key_id), "received public key");
+        Ok(VerifyingPublicKey::ECDSASHA256CurveP256(public_key))
+    }
+
+    /// Return the identifiers of all the EC private keys which can be used to sign
+    pub fn list_keys(&self) -> Result<Vec<SigningSecretKeyHandle>> {
+        let session = self.session.lock().unwrap();
+        let template = [
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::KeyType(KeyType::EC),
+            Attribute::Sign(true),
+        ];
+        let objects = session.find_objects(&template).map_err(|err| {
+            log::error!(%err, "failed to list all keys");
+            Error::List(err.to_string())
+        })?;
+
+        let mut result = vec![];
+        for object in objects {
+            let attributes = session
+                .get_attributes(object, &[AttributeType::Id])
+                .map_err(|err| Error::List(err.to_string()))?;
+            for attribute in attributes {
+                if let Attribute::Id(key_id) = attribute {
+                    result.push(Self::key_id_to_handle(key_id))
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Sign a message with a private key stored on the token.
+    pub fn sign(&self, key: &SigningSecretKeyHandle, message: &[u8]) -> Result<Signature> {
+        let key_id = Self::cast_handle_to_key_id(key)?;
+        log::trace!(key = %hex::encode(&key_id), "sign message");
+        let error = |err: cryptoki::error::Error| {
+            log::error!(key = %hex::encode(&key_id), %err, "failed to sign message");
+            Error::Sign {
+                keyid: hex::encode(&key_id),
+                error: err.to_string(),
+            }
+        };
+
+        let session = self.session.lock().unwrap();
+        let object = Self::find_key_object(&session, ObjectClass::PRIVATE_KEY, &key_id)
+            .map_err(error)?
+            .ok_or(Error::KeyNotFound)?;
+
+        // The digest is computed here since CKM_ECDSA_SHA256 is not supported by all tokens
+        let signature = session
+            .sign(&Mechanism::Ecdsa, object, &Sha256::digest(message))
+            .map_err(error)?;
+
+        log::debug!(key = %hex::encode(&key_id), "signed message");
+        let signature = ECDSASHA256CurveP256Signature(
+            signature.try_into().map_err(|_| Error::InvalidSignature)?,
+        );
+        Ok(Signature::ECDSASHA256CurveP256(signature))
+    }
+}
+
+/// This trait is introduced to help with the testing of the Pkcs11SigningVault
+#[async_trait]
+pub trait Pkcs11Token {
+    /// Create a key
+    async fn create_key(&self) -> Result<SigningSecretKeyHandle>;
+
+    /// Delete a key
+    async fn delete_key(&self, key: &SigningSecretKeyHandle) -> Result<bool>;
+
+    /// Get PublicKey
+    async fn public_key(&self, key: &SigningSecretKeyHandle) -> Result<VerifyingPublicKey>;
+
+    /// List All Keys
+    async fn list_keys(&self) -> Result<Vec<SigningSecretKeyHandle>>;
+
+    /// Sign a message
+    async fn sign(&self, key: &SigningSecretKeyHandle, message: &[u8]) -> Result<Signature>;
+}
+
+#[async_trait]
+impl Pkcs11Token for Pkcs11Client {
+    async fn create_key(&self) -> Result<SigningSecretKeyHandle> {
+        self.run_blocking(|client| client.create_key()).await
+    }
+
+    async fn delete_key(&self, key: &SigningSecretKeyHandle) -> Result<bool> {
+        let key = key.clone();
+        self.run_blocking(move |client| client.delete_key(&key))
+            .await
+    }
+
+    async fn public_key(&self, key: &SigningSecretKeyHandle) -> Result<VerifyingPublicKey> {
+        let key = key.clone();
+        self.run_blocking(move |client| client.public_key(&key))
+            .await
+    }
+
+    async fn list_keys(&self) -> Result<Vec<SigningSecretKeyHandle>> {
+        self.run_blocking(|client| client.list_keys()).await
+    }
+
+    async fn sign(&self, key: &SigningSecretKeyHandle, message: &[u8]) -> Result<Signature> {
+        let key = key.clone();
+        let message = message.to_vec();
+        self.run_blocking(move |client| client.sign(&key, &message))
+            .await
+    }
+}
+
+/// Decode a CKA_EC_POINT value. It is a DER-encoded OCTET STRING containing an
+/// uncompressed point, but some tokens return the raw point.
+fn decode_ec_point(ec_point: &[u8]) -> Result<ECDSASHA256CurveP256PublicKey> {
+    let point = match ec_point {
+        [0x04, 0x41, point @ ..] if point.len() == 0x41 => point,
+        point => point,
+    };
+    let public_key =
+        p256::PublicKey::from_sec1_bytes(point).map_err(|_| Error::InvalidPublicKey)?;
+    let public_key =
+        p256::elliptic_curve::sec1::ToEncodedPoint::to_encoded_point(&public_key, false);
+    Ok(ECDSASHA256CurveP256PublicKey(
+        public_key
+            .as_bytes()
+            .try_into()
+            .map_err(|_| Error::InvalidPublicKey)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ec_point() {
+        let secret = p256::SecretKey::from_slice(&[1; 32]).unwrap();
+        let point = p256::elliptic_curve::sec1::ToEncodedPoint::to_encoded_point(
+            &secret.public_key(),
+            false,
+        );
+        let point = point.as_bytes();
+
+        // DER-encoded octet string
+        let mut der = vec![0x04, point.len() as u8];
+        der.extend_from_slice(point);
+        assert_eq!(decode_ec_point(&der).unwrap().0.as_slice(), point);
+
+        // raw point
+        assert_eq!(decode_ec_point(point).unwrap().0.as_slice(), point);
+
+        assert!(decode_ec_point(&[0x04, 0x02, 0x01, 0x02]).is_err());
+    }
+}
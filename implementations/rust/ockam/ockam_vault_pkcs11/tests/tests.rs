@@ -0,0 +1,79 @@
+use ockam_core::Result;
+use ockam_vault::{
+    SigningKeyType, SoftwareVaultForVerifyingSignatures, VaultForSigning,
+    VaultForVerifyingSignatures,
+};
+use ockam_vault_pkcs11::Pkcs11SigningVault;
+
+/// These tests need to be executed with the following environment variables
+/// OCKAM_PKCS11_MODULE
+/// OCKAM_PKCS11_TOKEN_LABEL
+/// OCKAM_PKCS11_PIN
+///
+/// They can be run locally against SoftHSM:
+///
+/// softhsm2-util --init-token --free --label ockam --so-pin 1234 --pin 1234
+/// OCKAM_PKCS11_MODULE=/usr/lib/softhsm/libsofthsm2.so \
+/// OCKAM_PKCS11_TOKEN_LABEL=ockam \
+/// OCKAM_PKCS11_PIN=1234 \
+/// cargo test -p ockam_vault_pkcs11 -- --ignored
+
+#[tokio::test]
+#[ignore]
+async fn test_sign_verify() -> Result<()> {
+    let signing_vault = Pkcs11SigningVault::create().await?;
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+    let message = b"hello world";
+    let signature = signing_vault.sign(&handle, message.as_slice()).await?;
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+    let verifier = SoftwareVaultForVerifyingSignatures::new();
+    assert!(
+        verifier
+            .verify_signature(&public_key, message, &signature)
+            .await?
+    );
+
+    signing_vault.delete_signing_secret_key(handle).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_keys_management() -> Result<()> {
+    let signing_vault = Pkcs11SigningVault::create().await?;
+
+    let number_of_keys1 = signing_vault.number_of_keys().await?;
+
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+
+    let number_of_keys2 = signing_vault.number_of_keys().await?;
+    assert_eq!(number_of_keys1 + 1, number_of_keys2);
+
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+    assert_eq!(
+        signing_vault.get_secret_key_handle(&public_key).await?,
+        handle
+    );
+
+    // the keys are found again when the token is opened again
+    let other_vault = Pkcs11SigningVault::create().await?;
+    assert!(other_vault.keys().contains(&handle));
+
+    assert!(
+        signing_vault
+            .delete_signing_secret_key(handle.clone())
+            .await?
+    );
+    assert!(!signing_vault.delete_signing_secret_key(handle).await?);
+
+    let number_of_keys3 = signing_vault.number_of_keys().await?;
+    assert_eq!(number_of_keys1, number_of_keys3);
+
+    Ok(())
+}